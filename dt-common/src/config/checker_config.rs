@@ -13,9 +13,15 @@ pub struct CheckerConfig {
     pub revise_match_full_row: bool,
     pub retry_interval_secs: u64,
     pub max_retries: u32,
+    // mysql / pg only, read rows in REPEATABLE READ transactions and re-read the source on retries
+    pub snapshot_isolation: bool,
+    // review tasks only, re-check the miss / diff logs of the previous round until consistent
+    pub review_rounds: u32,
+    pub review_interval_secs: u64,
     pub check_log_dir: String,
     pub check_log_file_size: String,
     pub check_log_max_rows: usize,
+    pub check_log_compress: bool,
     pub db_type: DbType,
     pub url: String,
     pub connection_auth: ConnectionAuthConfig,
@@ -37,9 +43,13 @@ impl Default for CheckerConfig {
             revise_match_full_row: false,
             retry_interval_secs: 0,
             max_retries: 0,
+            snapshot_isolation: false,
+            review_rounds: 1,
+            review_interval_secs: 30,
             check_log_dir: String::new(),
             check_log_file_size: "100mb".to_string(),
             check_log_max_rows: 1000,
+            check_log_compress: false,
             db_type: DbType::default(),
             url: String::new(),
             connection_auth: ConnectionAuthConfig::default(),
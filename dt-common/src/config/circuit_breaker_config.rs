@@ -0,0 +1,14 @@
+/// Pauses sinking while the target is unhealthy, the failed rows are kept by mysql / pg sinkers
+/// and retried once the target recovers, extraction pauses when the buffer is full.
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBreakerConfig {
+    // mysql error numbers, pg sqlstates or message substrings, empty for any sink error,
+    // which requires probe_sql
+    pub trip_errors: Vec<String>,
+    // the target is healthy if it returns any row, run before batches when closed and
+    // before retries when open, empty to retry the batch directly
+    pub probe_sql: String,
+    pub probe_interval_secs: u64,
+    // 3600 by default, 0 to wait until the target recovers
+    pub max_open_secs: u64,
+}
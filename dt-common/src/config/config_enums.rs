@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString, IntoStaticStr};
 
@@ -34,6 +36,13 @@ pub enum DbType {
     Doris,
     #[strum(serialize = "tidb")]
     Tidb,
+    #[strum(serialize = "http")]
+    Http,
+    #[strum(serialize = "opensearch")]
+    OpenSearch,
+    // only as [resumer] storage
+    #[strum(serialize = "etcd")]
+    Etcd,
 }
 
 #[derive(Display, EnumString, IntoStaticStr, Debug, Clone, Hash, PartialEq, Eq)]
@@ -73,6 +82,10 @@ pub enum SinkType {
     Push,
     #[strum(serialize = "merge")]
     Merge,
+    #[strum(serialize = "file")]
+    File,
+    #[strum(serialize = "validate")]
+    Validate,
 }
 
 #[derive(EnumString, IntoStaticStr, Clone, Display)]
@@ -108,6 +121,50 @@ pub enum ConflictPolicyEnum {
     Interrupt,
 }
 
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum FileFormat {
+    #[default]
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "jsonl")]
+    Jsonl,
+    // zstd compressed and checksummed chunks for offline migration
+    #[strum(serialize = "chunk")]
+    Chunk,
+    #[strum(serialize = "orc")]
+    Orc,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum OrcCompression {
+    #[strum(serialize = "none")]
+    None,
+    #[default]
+    #[strum(serialize = "zlib")]
+    Zlib,
+    #[strum(serialize = "zstd")]
+    Zstd,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum TimePartitionUnit {
+    #[strum(serialize = "day")]
+    Day,
+    #[default]
+    #[strum(serialize = "month")]
+    Month,
+    #[strum(serialize = "year")]
+    Year,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq)]
+pub enum DistributedRole {
+    #[strum(serialize = "coordinator")]
+    Coordinator,
+    #[strum(serialize = "worker")]
+    Worker,
+}
+
 #[derive(Display, EnumString, IntoStaticStr, PartialEq)]
 pub enum MetaCenterType {
     #[strum(serialize = "basic")]
@@ -158,6 +215,15 @@ impl TaskType {
     }
 }
 
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum LogFormat {
+    #[default]
+    #[strum(serialize = "text")]
+    Text,
+    #[strum(serialize = "json")]
+    Json,
+}
+
 #[derive(Display, EnumString, IntoStaticStr, PartialEq, Default)]
 pub enum ResumeType {
     #[strum(serialize = "from_log")]
@@ -194,3 +260,163 @@ pub enum RdbParallelType {
     #[strum(serialize = "chunk")]
     Chunk,
 }
+
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum SnapshotTableOrder {
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "size_desc")]
+    SizeDesc,
+    #[strum(serialize = "size_asc")]
+    SizeAsc,
+}
+
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum UnknownModulePolicy {
+    #[default]
+    #[strum(serialize = "fail")]
+    Fail,
+    #[strum(serialize = "skip")]
+    Skip,
+}
+
+// read concern of Mongo snapshot extraction
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum MongoReadConcern {
+    #[default]
+    #[strum(serialize = "local")]
+    Local,
+    #[strum(serialize = "majority")]
+    Majority,
+    // all collections are read at the same cluster time
+    #[strum(serialize = "snapshot")]
+    Snapshot,
+}
+
+// read preference of Mongo snapshot extraction, to offload the primary
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum MongoReadPreference {
+    #[default]
+    #[strum(serialize = "primary")]
+    Primary,
+    #[strum(serialize = "primary_preferred")]
+    PrimaryPreferred,
+    #[strum(serialize = "secondary")]
+    Secondary,
+    #[strum(serialize = "secondary_preferred")]
+    SecondaryPreferred,
+    #[strum(serialize = "nearest")]
+    Nearest,
+}
+
+// for Postgres cdc, how to handle tables whose update/delete events have no before images,
+// i.e. tables without primary key and not in REPLICA IDENTITY FULL
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum ReplicaIdentityPolicy {
+    #[default]
+    #[strum(serialize = "fail")]
+    Fail,
+    #[strum(serialize = "skip")]
+    Skip,
+}
+
+// for mysql / pg targets, how to handle ddls which do not fit the current target structure,
+// e.g. adding a column which already exists or narrowing the type of a column
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum DdlGuardPolicy {
+    // ddls are applied without checks
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    // apply with a warning
+    #[strum(serialize = "warn")]
+    Warn,
+    // skip with a warning
+    #[strum(serialize = "skip")]
+    Skip,
+    // fail the task before the ddl is applied
+    #[strum(serialize = "halt")]
+    Halt,
+}
+
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug)]
+pub enum KafkaCompression {
+    #[default]
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "gzip")]
+    Gzip,
+    #[strum(serialize = "snappy")]
+    Snappy,
+    #[strum(serialize = "lz4")]
+    Lz4,
+    #[strum(serialize = "zstd")]
+    Zstd,
+}
+
+// case of target database / table names, e.g. for lower_case_table_names of mysql targets
+// differing from the source
+#[derive(
+    Display,
+    EnumString,
+    IntoStaticStr,
+    PartialEq,
+    Default,
+    Clone,
+    Debug,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub enum IdentifierCase {
+    #[default]
+    #[strum(serialize = "preserve")]
+    Preserve,
+    #[strum(serialize = "lower")]
+    Lower,
+    #[strum(serialize = "upper")]
+    Upper,
+}
+
+impl IdentifierCase {
+    pub fn apply<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Lower if name.chars().any(char::is_uppercase) => Cow::Owned(name.to_lowercase()),
+            Self::Upper if name.chars().any(char::is_lowercase) => Cow::Owned(name.to_uppercase()),
+            _ => Cow::Borrowed(name),
+        }
+    }
+}
+
+// when a [sinker].sql_hooks statement runs on the target
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Eq, Clone, Debug, Hash, Deserialize)]
+pub enum SqlHookStage {
+    #[strum(serialize = "before_task")]
+    #[serde(rename = "before_task")]
+    BeforeTask,
+    #[strum(serialize = "after_task")]
+    #[serde(rename = "after_task")]
+    AfterTask,
+    // before the first rows of a table in snapshot
+    #[strum(serialize = "before_tb")]
+    #[serde(rename = "before_tb")]
+    BeforeTb,
+    // after the snapshot of a table is finished
+    #[strum(serialize = "after_tb")]
+    #[serde(rename = "after_tb")]
+    AfterTb,
+}
+
+#[derive(Display, EnumString, IntoStaticStr, PartialEq, Default, Clone, Debug, Deserialize)]
+pub enum SqlHookErrorPolicy {
+    // fail the task
+    #[default]
+    #[strum(serialize = "fail")]
+    #[serde(rename = "fail")]
+    Fail,
+    // log a warning and go on
+    #[strum(serialize = "warn")]
+    #[serde(rename = "warn")]
+    Warn,
+}
@@ -0,0 +1,17 @@
+#[derive(Clone, Debug)]
+pub enum DistributedConfig {
+    // assigns tables to workers, tracks their heartbeats and reassigns tables of dead workers
+    Coordinator {
+        http_host: String,
+        http_port: u64,
+        heartbeat_timeout_secs: u64,
+        // max tables handed out to a worker in one assignment
+        assign_batch_size: usize,
+    },
+    // fetches table assignments from the coordinator and migrates them
+    Worker {
+        coordinator_url: String,
+        worker_id: String,
+        heartbeat_interval_secs: u64,
+    },
+}
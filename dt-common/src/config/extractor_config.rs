@@ -1,11 +1,21 @@
 use std::collections::HashMap;
 
+use anyhow::Context;
+use serde::Deserialize;
+
 use crate::{
     config::{
-        config_enums::RdbParallelType, connection_auth_config::ConnectionAuthConfig,
+        config_enums::{
+            MongoReadConcern, MongoReadPreference, RdbParallelType, ReplicaIdentityPolicy,
+            SnapshotTableOrder, UnknownModulePolicy,
+        },
+        config_token_parser::{ConfigTokenParser, TokenEscapePair},
+        connection_auth_config::ConnectionAuthConfig,
         limiter_config::RateLimiterConfig,
+        timeout_config::TimeoutConfig,
     },
     meta::mongo::mongo_cdc_source::MongoCdcSource,
+    utils::{sql_util::SqlUtil, time_util::TimeUtil},
 };
 
 use super::config_enums::{DbType, ExtractType};
@@ -40,6 +50,10 @@ pub enum ExtractorConfig {
         parallel_type: RdbParallelType,
         batch_size: usize,
         partition_cols: String,
+        snapshot_watermark: bool,
+        table_order: SnapshotTableOrder,
+        // emit enum / set values as numeric indexes instead of labels
+        enum_set_as_index: bool,
     },
 
     MysqlCdc {
@@ -54,10 +68,18 @@ pub enum ExtractorConfig {
         binlog_timeout_secs: u64,
         heartbeat_interval_secs: u64,
         heartbeat_tb: String,
+        heartbeat_tb_cleanup: bool,
         start_time_utc: String,
         end_time_utc: String,
         keepalive_idle_secs: u64,
         keepalive_interval_secs: u64,
+        // attach the statement of ROWS_QUERY events to rows, requires binlog_rows_query_log_events=ON
+        capture_rows_query: bool,
+        enum_set_as_index: bool,
+        // other hosts of the same replication topology, switched to by gtid_set if the stream breaks
+        failover_urls: Vec<String>,
+        // more source instances extracted into the same pipeline, empty if not multi-source
+        sources: Vec<MysqlCdcSourceConfig>,
     },
 
     MysqlCheck {
@@ -65,6 +87,8 @@ pub enum ExtractorConfig {
         connection_auth: ConnectionAuthConfig,
         check_log_dir: String,
         batch_size: usize,
+        // revise only, skip rows changed in target since the check
+        conflict_check: bool,
     },
 
     PgSnapshot {
@@ -78,6 +102,10 @@ pub enum ExtractorConfig {
         parallel_type: RdbParallelType,
         batch_size: usize,
         partition_cols: String,
+        snapshot_watermark: bool,
+        table_order: SnapshotTableOrder,
+        // read all ranges from one exported snapshot
+        consistent_snapshot: bool,
     },
 
     PgCdc {
@@ -85,14 +113,18 @@ pub enum ExtractorConfig {
         connection_auth: ConnectionAuthConfig,
         slot_name: String,
         pub_name: String,
+        // publication for tables matched by filter instead of all tables
+        pub_for_filtered_tables: bool,
         start_lsn: String,
         recreate_slot_if_exists: bool,
         keepalive_interval_secs: u64,
         heartbeat_interval_secs: u64,
         heartbeat_tb: String,
+        heartbeat_tb_cleanup: bool,
         ddl_meta_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        replica_identity_policy: ReplicaIdentityPolicy,
     },
 
     PgCheck {
@@ -100,6 +132,13 @@ pub enum ExtractorConfig {
         connection_auth: ConnectionAuthConfig,
         check_log_dir: String,
         batch_size: usize,
+        // revise only, skip rows changed in target since the check
+        conflict_check: bool,
+    },
+
+    // offline import of chunk files exported by the file sinker
+    ChunkFile {
+        file_dir: String,
     },
 
     MongoSnapshot {
@@ -113,6 +152,8 @@ pub enum ExtractorConfig {
         parallel_size: usize,
         parallel_type: RdbParallelType,
         batch_size: u32,
+        read_concern: MongoReadConcern,
+        read_preference: MongoReadPreference,
     },
 
     MongoCdc {
@@ -126,6 +167,7 @@ pub enum ExtractorConfig {
         source: MongoCdcSource,
         heartbeat_interval_secs: u64,
         heartbeat_tb: String,
+        heartbeat_tb_cleanup: bool,
     },
 
     MongoCheck {
@@ -152,6 +194,7 @@ pub enum ExtractorConfig {
         connection_auth: ConnectionAuthConfig,
         repl_port: u64,
         is_cluster: Option<bool>,
+        module_config: RedisModuleConfig,
     },
 
     RedisCdc {
@@ -176,10 +219,12 @@ pub enum ExtractorConfig {
         heartbeat_interval_secs: u64,
         heartbeat_key: String,
         is_cluster: Option<bool>,
+        module_config: RedisModuleConfig,
     },
 
     RedisSnapshotFile {
         file_path: String,
+        module_config: RedisModuleConfig,
     },
 
     RedisScan {
@@ -201,9 +246,129 @@ pub enum ExtractorConfig {
         partition: i32,
         offset: i64,
         ack_interval_secs: u64,
+        // recent rows kept to drop redelivered ones, 0 to disable
+        dedup_window_size: usize,
+        // stop once rows after it are met, None to consume forever
+        replay_until: Option<KafkaReplayUntil>,
     },
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeartbeatTb {
+    pub schema: String,
+    pub tb: String,
+    // drop the table / collection when the task exits
+    pub cleanup: bool,
+}
+
+impl ExtractorConfig {
+    /// Heartbeat table of cdc tasks, None if heartbeat is disabled or heartbeat_tb is not like schema.tb
+    pub fn heartbeat_tb(&self, db_type: &DbType) -> Option<HeartbeatTb> {
+        let (heartbeat_interval_secs, heartbeat_tb, cleanup) = match self {
+            Self::MysqlCdc {
+                heartbeat_interval_secs,
+                heartbeat_tb,
+                heartbeat_tb_cleanup,
+                ..
+            }
+            | Self::PgCdc {
+                heartbeat_interval_secs,
+                heartbeat_tb,
+                heartbeat_tb_cleanup,
+                ..
+            }
+            | Self::MongoCdc {
+                heartbeat_interval_secs,
+                heartbeat_tb,
+                heartbeat_tb_cleanup,
+                ..
+            } => (
+                *heartbeat_interval_secs,
+                heartbeat_tb,
+                *heartbeat_tb_cleanup,
+            ),
+            _ => return None,
+        };
+        if heartbeat_interval_secs == 0 || heartbeat_tb.is_empty() {
+            return None;
+        }
+
+        let tokens = ConfigTokenParser::parse(
+            heartbeat_tb,
+            &['.'],
+            &TokenEscapePair::from_char_pairs(SqlUtil::get_escape_pairs(db_type)),
+        );
+        if tokens.len() != 2 {
+            return None;
+        }
+        Some(HeartbeatTb {
+            schema: SqlUtil::unescape_by_db_type(&tokens[0], db_type),
+            tb: SqlUtil::unescape_by_db_type(&tokens[1], db_type),
+            cleanup,
+        })
+    }
+}
+
+/// Another source instance of a multi-source mysql cdc task, the one in [extractor].url is named
+/// "default". Settings not listed are the same as [extractor].
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct MysqlCdcSourceConfig {
+    pub id: String,
+    pub url: String,
+    // server_id of [extractor] if not set
+    #[serde(default)]
+    pub server_id: Option<u64>,
+    #[serde(default)]
+    pub binlog_filename: String,
+    #[serde(default)]
+    pub binlog_position: u32,
+    #[serde(default)]
+    pub gtid_set: String,
+    // added to the target dbs of rows from this source
+    #[serde(default)]
+    pub db_prefix: String,
+}
+
+/// Where a kafka replay stops, parsed from [extractor].replay_until:
+/// offset:1000 for an offset (inclusive), or a utc time like 2024-01-01 10:00:00.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KafkaReplayUntil {
+    Offset(i64),
+    // unix millis
+    Time(i64),
+}
+
+impl KafkaReplayUntil {
+    pub fn from_str(str: &str) -> anyhow::Result<Self> {
+        if let Some(offset) = str.strip_prefix("offset:") {
+            let offset = offset
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid replay offset: [{}]", str))?;
+            return Ok(Self::Offset(offset));
+        }
+        let datetime = TimeUtil::datetime_from_utc_str(str.trim())?;
+        Ok(Self::Time(datetime.timestamp_millis()))
+    }
+
+    /// whether a row at the offset committed at the time (unix millis) comes after the stop point
+    pub fn is_passed(&self, offset: i64, commit_millis: Option<i64>) -> bool {
+        match self {
+            Self::Offset(until) => offset > *until,
+            Self::Time(until) => commit_millis.is_some_and(|millis| millis > *until),
+        }
+    }
+}
+
+/// how module keys in redis rdb are handled
+#[derive(Clone, Debug, Default)]
+pub struct RedisModuleConfig {
+    // module type names to sync, e.g. ReJSON-RL, empty for all
+    pub module_types: Vec<String>,
+    // for module keys of other types
+    pub unknown_module_policy: UnknownModulePolicy,
+}
+
 #[derive(Clone, Debug, Hash)]
 pub struct BasicExtractorConfig {
     pub db_type: DbType,
@@ -214,4 +379,5 @@ pub struct BasicExtractorConfig {
     pub rate_limiter: RateLimiterConfig,
     pub app_name: Option<String>,
     pub is_direct_connection: Option<bool>,
+    pub timeout: TimeoutConfig,
 }
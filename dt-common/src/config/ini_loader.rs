@@ -5,6 +5,7 @@ use configparser::ini::Ini;
 
 use crate::error::Error;
 
+#[derive(Clone)]
 pub struct IniLoader {
     pub ini: Ini,
 }
@@ -16,6 +17,10 @@ impl IniLoader {
             .expect("failed to open ini file")
             .read_to_string(&mut config_str)
             .expect("failed to read ini content");
+        Self::from_content(config_str)
+    }
+
+    pub fn from_content(config_str: String) -> Self {
         let mut ini = Ini::new();
         // allow using comment symbols(; and #) in value
         // E.g. do_dbs=`a;`,`bcd`
@@ -0,0 +1,14 @@
+/// Retention of local artifacts written by the task, e.g. rotated logs and check logs,
+/// enforced periodically while the task is running.
+#[derive(Clone, Debug, Default)]
+pub struct JanitorConfig {
+    // [runtime].log_dir, [checker].check_log_dir, [sinker].statistic_log_dir and extra dirs
+    pub dirs: Vec<String>,
+    pub interval_secs: u64,
+    // 0 to keep artifacts regardless of age
+    pub max_age_secs: u64,
+    // the oldest artifacts are removed once all artifacts exceed it, 0 for no limit
+    pub max_size: u64,
+    // warn when the volume of any dir is used over it, 0 to disable
+    pub disk_usage_warn_percent: u64,
+}
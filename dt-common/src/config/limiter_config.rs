@@ -8,4 +8,9 @@ pub struct RateLimiterConfig {
 pub struct CapacityLimiterConfig {
     pub buffer_size: usize,
     pub buffer_memory_mb: usize,
+    // limits of control items, e.g. ddl, begin / commit and heartbeat, apart from data items
+    pub control_buffer_size: usize,
+    pub control_buffer_memory_mb: usize,
+    // bytes of buffered items plus items being sunk, 0 for no limit
+    pub memory_budget_mb: usize,
 }
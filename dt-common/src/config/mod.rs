@@ -1,25 +1,32 @@
 pub mod checker_config;
+pub mod circuit_breaker_config;
 pub mod config_enums;
 pub mod config_token_parser;
 pub mod connection_auth_config;
 pub mod data_marker_config;
+pub mod distributed_config;
 pub mod extractor_config;
 pub mod filter_config;
 pub mod global_config;
 pub mod ini_loader;
+pub mod janitor_config;
 pub mod limiter_config;
 pub mod meta_center_config;
 pub mod monitor_config;
 pub mod parallelizer_config;
 pub mod pipeline_config;
 pub mod processor_config;
+pub mod proxy_config;
 pub mod resumer_config;
 pub mod router_config;
 pub mod runtime_config;
 pub mod s3_config;
 pub mod sinker_config;
 pub mod ssl_config;
+pub mod struct_sync_config;
+pub mod subset_config;
 pub mod task_config;
+pub mod timeout_config;
 
 #[cfg(feature = "metrics")]
 pub mod metrics_config;
@@ -7,10 +7,14 @@ pub enum ParallelizerConfig {
     Basic {
         parallel_type: ParallelType,
         parallel_size: usize,
+        // rdb_merge / mongo only, in catch-up, sink tables with the deepest backlog first
+        lag_priority: bool,
+        adaptive_batch: Option<AdaptiveBatchConfig>,
     },
     Snapshot {
         parallel_size: usize,
         chunk_partitioner_rebalance: ChunkPartitionerRebalanceConfig,
+        adaptive_batch: Option<AdaptiveBatchConfig>,
     },
 }
 
@@ -30,6 +34,21 @@ impl ParallelizerConfig {
         }
     }
 
+    pub fn lag_priority(&self) -> bool {
+        match self {
+            Self::Basic { lag_priority, .. } => *lag_priority,
+            Self::Snapshot { .. } => false,
+        }
+    }
+
+    pub fn adaptive_batch(&self) -> Option<&AdaptiveBatchConfig> {
+        match self {
+            Self::Basic { adaptive_batch, .. } | Self::Snapshot { adaptive_batch, .. } => {
+                adaptive_batch.as_ref()
+            }
+        }
+    }
+
     pub fn chunk_partitioner_rebalance(&self) -> Option<&ChunkPartitionerRebalanceConfig> {
         match self {
             Self::Basic { .. } => None,
@@ -41,6 +60,17 @@ impl ParallelizerConfig {
     }
 }
 
+/// Bounds of the batch size tuned by measured sink latency, None if adaptive batching is off.
+#[derive(Clone, Debug)]
+pub struct AdaptiveBatchConfig {
+    pub min_batch_size: usize,
+    /// Defaults to sinker.batch_size, which is also the initial batch size.
+    pub max_batch_size: usize,
+    /// Expected latency of sinking one batch, the batch size grows when far below it and
+    /// shrinks when above it.
+    pub target_latency_ms: u64,
+}
+
 #[derive(Clone, Debug)]
 pub struct ChunkPartitionerRebalanceConfig {
     /// Rebalance behavior for snapshot DML partitions.
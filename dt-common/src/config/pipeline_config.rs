@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
 use crate::config::limiter_config::CapacityLimiterConfig;
 
 use super::config_enums::PipelineType;
@@ -10,4 +14,99 @@ pub struct PipelineConfig {
     pub batch_sink_interval_secs: u64,
     pub counter_time_window_secs: u64,
     pub counter_max_sub_count: u64,
+    pub table_overrides: TableOverrides,
+    // control file listing tables to remove from the running task, empty to disable
+    pub table_removal_file: String,
+    // control file listing tables to backfill into the running cdc task, empty to disable
+    pub table_backfill_file: String,
+    // memory limit of rows held during backfills, pulling data pauses once reached, 0 for no limit
+    pub table_backfill_max_held_mb: u64,
+    // apply each source transaction in one target transaction, cdc to mysql / pg only
+    pub transactional: bool,
+    // transactional only, rows of an open transaction beyond this size are spilled to files
+    pub trx_max_held_mb: u64,
+    // transactional only, dir of the spill files, {runtime.log_dir}/trx_spill if empty
+    pub trx_spill_dir: String,
+    // progress of tables in snapshot tasks, empty to disable
+    pub snapshot_manifest_file: String,
+    // seconds without sunk rows before the source is considered idle, 0 to disable
+    pub idle_threshold_secs: u64,
+    // checkpoint_interval_secs while the source is idle
+    pub idle_checkpoint_interval_secs: u64,
+    // fraction of data items traced from extraction to sink, 0 to disable
+    pub trace_sample_rate: f64,
+    // seconds between reports of traced latencies
+    pub trace_report_interval_secs: u64,
+}
+
+/// Per-table overrides of [sinker].batch_size, [parallelizer].parallel_size and the key
+/// columns of tables, tables not listed inherit the global settings.
+#[derive(Clone, Debug, Default)]
+pub struct TableOverrides {
+    overrides: HashMap<(String, String), TableOverride>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct TableOverride {
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    // max sinkers the rows of the table are spread to
+    #[serde(default)]
+    pub parallel_size: Option<usize>,
+    // replace the primary / unique key found in table meta in merging, partitioning and
+    // conflict clauses of sinkers, e.g. a unique business column instead of a uuid pk
+    #[serde(default)]
+    pub key_cols: Option<Vec<String>>,
+}
+
+impl TableOverrides {
+    pub fn new(overrides: HashMap<(String, String), TableOverride>) -> Self {
+        Self { overrides }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&(String, String), &TableOverride)> {
+        self.overrides.iter()
+    }
+
+    pub fn get(&self, schema: &str, tb: &str) -> Option<&TableOverride> {
+        if self.overrides.is_empty() {
+            return None;
+        }
+        self.overrides.get(&(schema.to_string(), tb.to_string()))
+    }
+
+    pub fn batch_size(&self, schema: &str, tb: &str, default: usize) -> usize {
+        self.get(schema, tb)
+            .and_then(|o| o.batch_size)
+            .unwrap_or(default)
+    }
+
+    pub fn parallel_size(&self, schema: &str, tb: &str, default: usize) -> usize {
+        self.get(schema, tb)
+            .and_then(|o| o.parallel_size)
+            // a hint never raises the global parallelism
+            .map(|size| size.min(default))
+            .unwrap_or(default)
+    }
+
+    pub fn key_cols(&self, schema: &str, tb: &str) -> Option<&Vec<String>> {
+        self.get(schema, tb).and_then(|o| o.key_cols.as_ref())
+    }
+
+    /// Rekey the overrides by the names the data will carry after routing.
+    pub fn map_tables<F>(&self, f: F) -> Self
+    where
+        F: Fn(&str, &str) -> (String, String),
+    {
+        let overrides = self
+            .overrides
+            .iter()
+            .map(|((schema, tb), o)| (f(schema, tb), o.clone()))
+            .collect();
+        Self { overrides }
+    }
 }
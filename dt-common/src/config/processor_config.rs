@@ -1,5 +1,50 @@
+use serde::Deserialize;
+
+use crate::meta::mongo::mongo_projection::MongoProjection;
+
 #[derive(Clone)]
 pub struct ProcessorConfig {
     pub lua_code_file: String,
     pub lua_code: String,
+    pub text_cleanup: bool,
+    pub mongo_projections: Vec<MongoProjection>,
+    pub soft_deletes: Vec<SoftDeleteConfig>,
+    pub redis_mappings: Vec<RedisMappingConfig>,
+}
+
+/// Deletes of the table are sunk as updates setting flag_col to flag_value and / or
+/// time_col to the current utc time, db and tb are the names after [router].
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SoftDeleteConfig {
+    pub db: String,
+    pub tb: String,
+    #[serde(default)]
+    pub flag_col: String,
+    #[serde(default = "default_flag_value")]
+    pub flag_value: String,
+    #[serde(default)]
+    pub time_col: String,
+}
+
+fn default_flag_value() -> String {
+    "1".to_string()
+}
+
+/// Keys matching key_pattern are sunk as rows of db.tb, e.g. user:{id} extracts the id column
+/// from the key, * matches anything. Hash fields are mapped to columns of the same names,
+/// string values to value_col.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RedisMappingConfig {
+    pub key_pattern: String,
+    pub db: String,
+    pub tb: String,
+    #[serde(default = "default_value_col")]
+    pub value_col: String,
+    // redis db of the keys, all dbs if not set
+    #[serde(default)]
+    pub db_id: Option<i64>,
+}
+
+fn default_value_col() -> String {
+    "value".to_string()
 }
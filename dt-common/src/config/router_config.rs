@@ -1,9 +1,38 @@
-#[derive(Clone, Hash)]
+use std::hash::{Hash, Hasher};
+
+use crate::config::config_enums::IdentifierCase;
+
+#[derive(Clone)]
 pub enum RouterConfig {
     Rdb {
         schema_map: String,
         tb_map: String,
         col_map: String,
         topic_map: String,
+        // applied to target database / table names, including those of db_map and tb_map
+        identifier_case: IdentifierCase,
     },
 }
+
+// task ids generated from configs are kept for routers without identifier_case
+impl Hash for RouterConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Rdb {
+                schema_map,
+                tb_map,
+                col_map,
+                topic_map,
+                identifier_case,
+            } => {
+                schema_map.hash(state);
+                tb_map.hash(state);
+                col_map.hash(state);
+                topic_map.hash(state);
+                if *identifier_case != IdentifierCase::Preserve {
+                    identifier_case.hash(state);
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,12 @@
+use super::config_enums::LogFormat;
+
 #[derive(Clone)]
 pub struct RuntimeConfig {
     pub log_level: String,
+    // {log_dir}/{task_id} if log_dir_per_task is set
     pub log_dir: String,
     pub log4rs_file: String,
     pub check_result_stdout_only: bool,
+    pub log_dir_per_task: bool,
+    pub log_format: LogFormat,
 }
@@ -1,9 +1,18 @@
-use super::config_enums::{ConflictPolicyEnum, DbType};
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::config_enums::{
+    ConflictPolicyEnum, DbType, DdlGuardPolicy, FileFormat, KafkaCompression, OrcCompression,
+    SqlHookErrorPolicy, SqlHookStage, TimePartitionUnit,
+};
 use crate::config::{
     config_enums::{RdbTransactionIsolation, SinkType},
     connection_auth_config::ConnectionAuthConfig,
     limiter_config::RateLimiterConfig,
+    s3_config::S3Config,
     task_config::APE_DTS,
+    timeout_config::TimeoutConfig,
 };
 
 #[derive(Clone, Debug)]
@@ -19,6 +28,21 @@ pub enum SinkerConfig {
         // Specifies the transaction isolation level used for writes. The database default is used if not specified.
         // If ReadCommitted or ReadUncommitted is set, the target database must have BINLOG_FORMAT set to at least MIXED (ROW is recommended). Otherwise, write operations will fail.
         transaction_isolation: RdbTransactionIsolation,
+        // create missing target tables from source structures on first DML
+        auto_create_table: bool,
+        // retries of rows failed by deadlocks / lock wait timeouts, 0 to disable
+        max_retries: u32,
+        retry_interval_ms: u64,
+        // more target instances for a scale-out target, None if not sharded
+        shard: Option<ShardConfig>,
+        // what to do with ddls which do not fit the current target structure
+        ddl_guard_policy: DdlGuardPolicy,
+        // whether to skip a failed ddl statement or fail the task
+        ddl_conflict_policy: ConflictPolicyEnum,
+        // mysql -> mysql snapshot only, dir shared with the target to load batches by LOAD DATA INFILE
+        load_data_dir: String,
+        // statements run on the target before / after the task and each table's snapshot
+        sql_hooks: Vec<SqlHookConfig>,
     },
 
     Pg {
@@ -27,6 +51,22 @@ pub enum SinkerConfig {
         batch_size: usize,
         replace: bool,
         disable_foreign_key_checks: bool,
+        auto_create_table: bool,
+        // max prepared statements kept by each connection
+        statement_cache_capacity: usize,
+        // max statements sent before waiting for results in serial sink, 1 to disable pipelining
+        pipeline_size: usize,
+        // retries of rows failed by deadlocks / lock wait timeouts, 0 to disable
+        max_retries: u32,
+        retry_interval_ms: u64,
+        shard: Option<ShardConfig>,
+        ddl_guard_policy: DdlGuardPolicy,
+        ddl_conflict_policy: ConflictPolicyEnum,
+        // apply consecutive transactional ddls of a batch in one transaction
+        ddl_transaction: bool,
+        // pg -> pg only, copy large objects referenced by oid / lo columns, keeping their oids
+        copy_large_objects: bool,
+        sql_hooks: Vec<SqlHookConfig>,
     },
 
     Mongo {
@@ -50,12 +90,17 @@ pub enum SinkerConfig {
         url: String,
         connection_auth: ConnectionAuthConfig,
         conflict_policy: ConflictPolicyEnum,
+        // for views, triggers and routines, same as conflict_policy if not set
+        object_conflict_policy: ConflictPolicyEnum,
+        // pg -> mysql only, key: schema.tb.col / pg type, value: mysql column type
+        type_mappings: HashMap<String, String>,
     },
 
     PgStruct {
         url: String,
         connection_auth: ConnectionAuthConfig,
         conflict_policy: ConflictPolicyEnum,
+        object_conflict_policy: ConflictPolicyEnum,
     },
 
     Kafka {
@@ -64,6 +109,55 @@ pub enum SinkerConfig {
         ack_timeout_secs: u64,
         required_acks: String,
         with_field_defs: bool,
+        // send a stable key per row in message headers for downstream dedup
+        idempotency_key: bool,
+        compression: KafkaCompression,
+        // wait up to linger_ms to batch messages before sending, 0 to send each batch immediately
+        linger_ms: u64,
+        // attach source metadata of rows, e.g. binlog file + position, lsn, commit time
+        with_provenance: bool,
+        // send decimals / dates / timestamps as avro logical types instead of strings
+        with_logical_types: bool,
+        // send all ddls to this topic, keyed by tables
+        ddl_topic: String,
+        // send ddls to the topics of their tables with this suffix, keyed by tables
+        ddl_topic_suffix: String,
+        // produce each batch in a kafka transaction, sinkers use transactional ids with this prefix
+        transactional_id: String,
+        // columns compressed with zstd before sending, e.g. LONGBLOB / LONGTEXT
+        compress_cols: Vec<KafkaCompressColsConfig>,
+        // values shorter than this are sent as they are
+        compress_min_bytes: usize,
+        // split messages larger than this into chunks, 0 to disable
+        max_message_bytes: usize,
+    },
+
+    Http {
+        url: String,
+        batch_size: usize,
+        // extra request headers, e.g. Authorization
+        headers: HashMap<String, String>,
+        timeout_secs: u64,
+        // retries of a failed batch, with backoff starting at retry_interval_ms and doubled each time
+        max_retries: u32,
+        retry_interval_ms: u64,
+        with_provenance: bool,
+        // send a stable key per row in the body and per batch in the request header for downstream dedup
+        idempotency_key: bool,
+    },
+
+    OpenSearch {
+        url: String,
+        connection_auth: ConnectionAuthConfig,
+        batch_size: usize,
+        // keyed by (schema, tb), tables not listed are written to {schema}_{tb} in lower case
+        indices: HashMap<(String, String), String>,
+        // create missing indices with mappings generated from source table structures
+        create_index: bool,
+        timeout_secs: u64,
+        // retries of a failed bulk request / items rejected with 429, with backoff doubled each time
+        max_retries: u32,
+        retry_interval_ms: u64,
     },
 
     Redis {
@@ -71,7 +165,14 @@ pub enum SinkerConfig {
         connection_auth: ConnectionAuthConfig,
         batch_size: usize,
         method: String,
+        restore_fallback: bool,
         is_cluster: Option<bool>,
+        // rows from relational sources, keyed by (schema, tb)
+        table_keys: HashMap<(String, String), RedisTableKey>,
+    },
+
+    RedisAof {
+        file_path: String,
     },
 
     RedisStatistic {
@@ -93,6 +194,11 @@ pub enum SinkerConfig {
         url: String,
         connection_auth: ConnectionAuthConfig,
         conflict_policy: ConflictPolicyEnum,
+        // estimated source data size per bucket, 0 to leave bucket count to the target
+        bucket_size_mb: u64,
+        // time columns to pre-create range partitions on, same format as [extractor] partition_cols
+        partition_cols: String,
+        partition_unit: TimePartitionUnit,
     },
 
     Doris {
@@ -106,11 +212,18 @@ pub enum SinkerConfig {
         url: String,
         connection_auth: ConnectionAuthConfig,
         conflict_policy: ConflictPolicyEnum,
+        // estimated source data size per bucket, 0 to leave bucket count to the target
+        bucket_size_mb: u64,
+        // time columns to pre-create range partitions on, same format as [extractor] partition_cols
+        partition_cols: String,
+        partition_unit: TimePartitionUnit,
     },
 
     ClickHouse {
         url: String,
         batch_size: usize,
+        // set insert_deduplication_token for Replicated* tables
+        insert_dedup_token: bool,
     },
 
     ClickhouseStruct {
@@ -122,6 +235,81 @@ pub enum SinkerConfig {
     Sql {
         reverse: bool,
     },
+
+    File {
+        file_dir: String,
+        file_format: FileFormat,
+        // rotate to a new file once the current one exceeds this size, in bytes
+        max_file_size: u64,
+        gzip: bool,
+        // orc only, compression of streams inside the file
+        orc_compression: OrcCompression,
+        // finished files are uploaded to s3 and removed locally if set
+        s3_config: Option<S3Config>,
+        s3_key_prefix: String,
+    },
+
+    // check rows against the target without writing, for mysql / pg targets
+    Validate {
+        db_type: DbType,
+        url: String,
+        connection_auth: ConnectionAuthConfig,
+        // rows validated for each table
+        sample_rows: usize,
+    },
+}
+
+/// Key template and ttl of rows from a relational table written into redis
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct RedisTableKey {
+    // e.g. user:{id}, empty for the default key: {schema}.{tb}.{primary key value}
+    #[serde(default)]
+    pub key: String,
+    // 0 for no expiration
+    #[serde(default)]
+    pub ttl_secs: u64,
+}
+
+/// Targets of a sharded task, the one in [sinker].url is the default target named "default".
+#[derive(Clone, Debug, Default)]
+pub struct ShardConfig {
+    pub targets: Vec<ShardTargetConfig>,
+    // rows of these tables are spread to all targets by hash of key values, same format as do_tbs
+    pub hash_tbs: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ShardTargetConfig {
+    pub name: String,
+    pub url: String,
+    // tables sunk to this target, same format as do_tbs
+    #[serde(default)]
+    pub tbs: String,
+}
+
+/// A statement run on the target at the given stage, {schema} and {tb} in sql are replaced by
+/// the quoted target names of the table for before_tb / after_tb hooks.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SqlHookConfig {
+    pub stage: SqlHookStage,
+    // source tables the hook applies to, same format as do_tbs, ignored by task hooks
+    #[serde(default = "default_hook_tbs")]
+    pub tbs: String,
+    pub sql: String,
+    #[serde(default)]
+    pub on_error: SqlHookErrorPolicy,
+}
+
+fn default_hook_tbs() -> String {
+    "*.*".to_string()
+}
+
+/// Columns of the tables compressed by the kafka sinker, decompressed by the kafka extractor.
+#[derive(Clone, Debug, Deserialize)]
+pub struct KafkaCompressColsConfig {
+    // source tables, same format as do_tbs
+    pub tbs: String,
+    pub cols: Vec<String>,
 }
 
 #[derive(Clone, Debug, Hash)]
@@ -138,6 +326,31 @@ pub struct BasicSinkerConfig {
     pub is_direct_connection: Option<bool>,
     // redis special attrs
     pub is_cluster: Option<bool>,
+    // seconds to pause and reconnect when the target is unreachable, 0 to fail at once
+    pub conn_grace_period_secs: u64,
+    // seconds idle before the connection is checked prior to the next batch, 0 to disable
+    pub health_check_interval_secs: u64,
+    pub timeout: TimeoutConfig,
+}
+
+impl SinkerConfig {
+    /// Whether rows should carry their source positions to the sinker.
+    pub fn needs_row_position(&self) -> bool {
+        matches!(
+            self,
+            Self::Kafka {
+                idempotency_key: true,
+                ..
+            } | Self::Kafka {
+                with_provenance: true,
+                ..
+            } | Self::Http { .. }
+                | Self::ClickHouse {
+                    insert_dedup_token: true,
+                    ..
+                }
+        )
+    }
 }
 
 impl Default for BasicSinkerConfig {
@@ -153,6 +366,9 @@ impl Default for BasicSinkerConfig {
             app_name: Some(APE_DTS.to_string()),
             is_direct_connection: None,
             is_cluster: None,
+            conn_grace_period_secs: 0,
+            health_check_interval_secs: 30,
+            timeout: TimeoutConfig::default(),
         }
     }
 }
@@ -0,0 +1,6 @@
+/// Periodic reconciliation of target structures with the source while the task is running,
+/// additive changes are applied and destructive ones are only logged.
+#[derive(Clone, Debug, Default)]
+pub struct StructSyncConfig {
+    pub interval_secs: u64,
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+/// A referentially consistent slice of the source: rows of seed tables matching their
+/// conditions, plus rows they reference by foreign keys, recursively.
+#[derive(Clone, Debug, Default)]
+pub struct SubsetConfig {
+    pub seeds: Vec<SubsetSeed>,
+    // also take rows referencing the seed rows, e.g. items of seed orders, recursively
+    pub include_children: bool,
+    // max foreign key hops from seed tables
+    pub max_depth: usize,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SubsetSeed {
+    pub db: String,
+    pub tb: String,
+    pub condition: String,
+}
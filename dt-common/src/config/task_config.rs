@@ -1,47 +1,68 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{
+    cmp,
     fs::{self, File},
     io::Read,
 };
 
 use anyhow::{bail, Ok};
+use serde::Deserialize;
 
 #[cfg(feature = "metrics")]
 use crate::config::metrics_config::MetricsConfig;
 use crate::{
     config::{
-        config_enums::{RdbParallelType, ResumeType},
+        config_enums::{
+            LogFormat, OrcCompression, RdbParallelType, ResumeType, UnknownModulePolicy,
+        },
         connection_auth_config::ConnectionAuthConfig,
         global_config::GlobalConfig,
         limiter_config::{CapacityLimiterConfig, RateLimiterConfig},
     },
     error::Error,
-    meta::mongo::mongo_cdc_source::MongoCdcSource,
-    utils::task_util::TaskUtil,
+    log_filter::parse_size_limit,
+    meta::mongo::{
+        mongo_cdc_source::MongoCdcSource, mongo_constant::MongoConstants,
+        mongo_projection::MongoProjection,
+    },
+    utils::{proxy_tunnel::ProxyTunnel, task_util::TaskUtil},
 };
 
 use super::{
     checker_config::CheckerConfig,
+    circuit_breaker_config::CircuitBreakerConfig,
     config_enums::{
-        CheckMode, ConflictPolicyEnum, DbType, ExtractType, MetaCenterType, ParallelType,
-        PipelineType, SinkType, TaskKind, TaskType,
+        CheckMode, ConflictPolicyEnum, DbType, DistributedRole, ExtractType, FileFormat,
+        MetaCenterType, ParallelType, PipelineType, SinkType, TaskKind, TaskType,
     },
     data_marker_config::DataMarkerConfig,
-    extractor_config::{BasicExtractorConfig, ExtractorConfig},
+    distributed_config::DistributedConfig,
+    extractor_config::{
+        BasicExtractorConfig, ExtractorConfig, KafkaReplayUntil, MysqlCdcSourceConfig,
+        RedisModuleConfig,
+    },
     filter_config::FilterConfig,
     ini_loader::IniLoader,
+    janitor_config::JanitorConfig,
     meta_center_config::MetaCenterConfig,
     parallelizer_config::{
-        ChunkPartitionerRebalanceConfig, ChunkPartitionerRebalanceCost,
+        AdaptiveBatchConfig, ChunkPartitionerRebalanceConfig, ChunkPartitionerRebalanceCost,
         ChunkPartitionerRebalanceStrategy, ParallelizerConfig,
     },
-    pipeline_config::PipelineConfig,
-    processor_config::ProcessorConfig,
+    pipeline_config::{PipelineConfig, TableOverride, TableOverrides},
+    processor_config::{ProcessorConfig, RedisMappingConfig, SoftDeleteConfig},
+    proxy_config::ProxyConfig,
     resumer_config::ResumerConfig,
     router_config::RouterConfig,
     runtime_config::RuntimeConfig,
     s3_config::S3Config,
-    sinker_config::{BasicSinkerConfig, SinkerConfig},
+    sinker_config::{
+        BasicSinkerConfig, KafkaCompressColsConfig, RedisTableKey, ShardConfig, ShardTargetConfig,
+        SinkerConfig, SqlHookConfig,
+    },
+    struct_sync_config::StructSyncConfig,
+    subset_config::{SubsetConfig, SubsetSeed},
+    timeout_config::TimeoutConfig,
 };
 
 #[derive(Clone)]
@@ -61,12 +82,19 @@ pub struct TaskConfig {
     pub meta_center: Option<MetaCenterConfig>,
     pub data_marker: Option<DataMarkerConfig>,
     pub processor: Option<ProcessorConfig>,
+    pub distributed: Option<DistributedConfig>,
+    pub janitor: Option<JanitorConfig>,
+    pub subset: Option<SubsetConfig>,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    pub struct_sync: Option<StructSyncConfig>,
+    pub proxy: ProxyConfig,
     #[cfg(feature = "metrics")]
     pub metrics: MetricsConfig,
 }
 
 pub const DEFAULT_DB_BATCH_SIZE: usize = 100;
 pub const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+pub const DEFAULT_PG_STATEMENT_CACHE_CAPACITY: usize = 100;
 pub const DEFAULT_CHECK_LOG_FILE_SIZE: &str = "100mb";
 
 // sections
@@ -83,15 +111,24 @@ const DATA_MARKER: &str = "data_marker";
 const PROCESSOR: &str = "processor";
 const CHECKER: &str = "checker";
 const META_CENTER: &str = "metacenter";
+const DISTRIBUTED: &str = "distributed";
+const JANITOR: &str = "janitor";
+const SUBSET: &str = "subset";
+const CIRCUIT_BREAKER: &str = "circuit_breaker";
+const STRUCT_SYNC: &str = "struct_sync";
+const PROXY: &str = "proxy";
 // keys
 const CHECK_LOG_DIR: &str = "check_log_dir";
 const CHECK_LOG_FILE_SIZE: &str = "check_log_file_size";
 const CHECK_LOG_MAX_ROWS: &str = "check_log_max_rows";
+const CHECK_LOG_COMPRESS: &str = "check_log_compress";
 const OUTPUT_FULL_ROW: &str = "output_full_row";
 const OUTPUT_REVISE_SQL: &str = "output_revise_sql";
 const REVISE_MATCH_FULL_ROW: &str = "revise_match_full_row";
 const RETRY_INTERVAL_SECS: &str = "retry_interval_secs";
 const MAX_RETRIES: &str = "max_retries";
+const REVIEW_ROUNDS: &str = "review_rounds";
+const CONFLICT_CHECK: &str = "conflict_check";
 const ENABLE: &str = "enable";
 const DB_TYPE: &str = "db_type";
 const URL: &str = "url";
@@ -112,18 +149,33 @@ const REBALANCE_COST: &str = "rebalance_cost";
 const REBALANCE_MAX_PARTITIONS_PER_SINKER: &str = "rebalance_max_partitions_per_sinker";
 const REBALANCE_MIN_PARTITION_ROWS: &str = "rebalance_min_partition_rows";
 const REBALANCE_SPLIT_SKEW_RATIO: &str = "rebalance_split_skew_ratio";
+const ADAPTIVE_BATCH_MIN_SIZE: &str = "adaptive_batch_min_size";
+const ADAPTIVE_BATCH_MAX_SIZE: &str = "adaptive_batch_max_size";
+const ADAPTIVE_BATCH_TARGET_LATENCY_MS: &str = "adaptive_batch_target_latency_ms";
 const LEGACY_TB_PARALLEL_SIZE: &str = "tb_parallel_size";
 const DDL_CONFLICT_POLICY: &str = "ddl_conflict_policy";
 const REPLACE: &str = "replace";
 const DISABLE_FOREIGN_KEY_CHECKS: &str = "disable_foreign_key_checks";
+const STATEMENT_CACHE_CAPACITY: &str = "statement_cache_capacity";
+const PIPELINE_SIZE: &str = "pipeline_size";
 const RESUME_TYPE: &str = "resume_type";
 const CHECKER_QUEUE_SIZE: &str = "queue_size";
 const CHECK_LOG_S3: &str = "check_log_s3";
 const S3_KEY_PREFIX: &str = "s3_key_prefix";
 const CDC_CHECK_LOG_INTERVAL_SECS: &str = "cdc_check_log_interval_secs";
 const SAMPLE_RATE: &str = "sample_rate";
+const SAMPLE_ROWS: &str = "sample_rows";
+const ENUM_SET_AS_INDEX: &str = "enum_set_as_index";
+const WITH_PROVENANCE: &str = "with_provenance";
+const IDEMPOTENCY_KEY: &str = "idempotency_key";
 const IS_DIRECT_CONNECTION: &str = "is_direct_connection";
 const MONGO_REQUIRE_SHARD_KEY_FILTER: &str = "mongo_require_shard_key_filter";
+const TABLE_OVERRIDES: &str = "table_overrides";
+const TABLE_KEYS: &str = "table_keys";
+const INDICES: &str = "indices";
+const JSON_PREFIX: &str = "json:";
+// chunks of a message leave room for its key and headers
+const MIN_KAFKA_MESSAGE_BYTES: usize = 4096;
 
 // default values
 pub const APE_DTS: &str = "APE_DTS";
@@ -132,15 +184,22 @@ const RESUMER_CONNECTION_LIMIT_DEFAULT: usize = 5;
 
 impl TaskConfig {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
-        let loader = IniLoader::new(task_config_file);
+        Self::from_loader(IniLoader::new(task_config_file))
+    }
 
-        let pipeline = Self::load_pipeline_config(&loader);
-        let runtime = Self::load_runtime_config(&loader)?;
+    /// configs loaded from an ini changed in memory, e.g. by lifecycle commands of dt-main
+    pub fn from_loader(loader: IniLoader) -> anyhow::Result<Self> {
+        let mut pipeline = Self::load_pipeline_config(&loader);
+        let mut runtime = Self::load_runtime_config(&loader)?;
         let (sinker_basic, sinker) = Self::load_sinker_config(&loader)?;
         let (extractor_basic, extractor) = Self::load_extractor_config(&loader, &pipeline)?;
         let filter = Self::load_filter_config(&loader)?;
         let router = Self::load_router_config(&loader)?;
         let parallelizer = Self::load_parallelizer_config(&loader, &sinker_basic, &pipeline)?;
+        pipeline.table_overrides = Self::load_table_overrides(&loader, &parallelizer)?;
+        if pipeline.transactional {
+            Self::check_transactional(&extractor_basic, &sinker, &parallelizer)?;
+        }
         let checker = Self::load_checker_config(&loader)?;
         if let Some(checker_cfg) = checker.as_ref() {
             if matches!(extractor_basic.extract_type, ExtractType::Cdc)
@@ -166,11 +225,21 @@ impl TaskConfig {
                 ));
             }
 
+            if checker_cfg.review_rounds == 0
+                || (checker_cfg.review_rounds > 1
+                    && !matches!(extractor_basic.extract_type, ExtractType::CheckLog))
+            {
+                bail!(Error::ConfigError(format!(
+                    "config [checker].{} must be greater than 0, and can only be greater than 1 with [extractor] extract_type=check_log",
+                    REVIEW_ROUNDS
+                )));
+            }
+
             let task_type = if matches!(extractor_basic.extract_type, ExtractType::CheckLog)
                 && matches!(sinker_basic.sink_type, SinkType::Dummy)
                 && matches!(
                     checker_cfg.db_type,
-                    DbType::Mysql | DbType::Pg | DbType::Mongo
+                    DbType::Mysql | DbType::Pg | DbType::Mongo | DbType::ClickHouse
                 ) {
                 None
             } else {
@@ -194,6 +263,20 @@ impl TaskConfig {
                 )
             };
 
+            let checker_db_type = Self::checker_target_db_type(
+                &extractor_basic.extract_type,
+                &sinker_basic,
+                checker_cfg,
+            );
+            if matches!(checker_db_type, DbType::ClickHouse)
+                && !matches!(extractor_basic.db_type, DbType::Mysql | DbType::Pg)
+            {
+                bail!(Error::ConfigError(format!(
+                    "config [checker] with ClickHouse target only supports [extractor] db_type=mysql or pg, got: {}",
+                    extractor_basic.db_type
+                )));
+            }
+
             let check_log_s3_supported = task_type.is_some_and(|task_type| {
                 task_type.is_cdc_inline_check() || task_type.is_standalone_snapshot_check()
             });
@@ -232,17 +315,27 @@ impl TaskConfig {
                 task_type.is_some_and(|task_type| task_type.is_inline_check()),
             )?;
         }
+        let global = Self::load_global_config(
+            &loader,
+            &extractor_basic,
+            &sinker_basic,
+            checker.as_ref(),
+            &filter,
+            &router,
+        )?;
+        if runtime.log_dir_per_task {
+            // all dirs defaulting to log_dir follow, e.g. [resumer] log_dir, {log_dir}/check
+            runtime.log_dir = format!("{}/{}", runtime.log_dir, global.task_id);
+        }
         let resumer =
             Self::load_resumer_config(&loader, &runtime, &sinker_basic, checker.as_ref())?;
+        let subset = Self::load_subset_config(&loader, &extractor_basic, &filter)?;
+        let distributed = Self::load_distributed_config(&loader, &extractor_basic)?;
+        let janitor = Self::load_janitor_config(&loader, &runtime, &sinker, checker.as_ref())?;
+        let circuit_breaker = Self::load_circuit_breaker_config(&loader, &sinker)?;
+        let struct_sync = Self::load_struct_sync_config(&loader, &extractor_basic, &sinker_basic)?;
         Ok(Self {
-            global: Self::load_global_config(
-                &loader,
-                &extractor_basic,
-                &sinker_basic,
-                checker.as_ref(),
-                &filter,
-                &router,
-            )?,
+            global,
             extractor_basic,
             extractor,
             parallelizer,
@@ -257,6 +350,12 @@ impl TaskConfig {
             data_marker: Self::load_data_marker_config(&loader)?,
             processor: Self::load_processor_config(&loader)?,
             meta_center: Self::load_meta_center_config(&loader)?,
+            distributed,
+            janitor,
+            subset,
+            circuit_breaker,
+            struct_sync,
+            proxy: Self::load_proxy_config(&loader)?,
             #[cfg(feature = "metrics")]
             metrics: Self::load_metrics_config(&loader)?,
         })
@@ -357,7 +456,10 @@ impl TaskConfig {
     }
 
     fn write_sink_supports_inline_checker(target_db_type: &DbType) -> bool {
-        matches!(target_db_type, DbType::Mysql | DbType::Pg | DbType::Mongo)
+        matches!(
+            target_db_type,
+            DbType::Mysql | DbType::Pg | DbType::Mongo | DbType::ClickHouse
+        )
     }
 
     fn task_kind_from_extract_type(extract_type: &ExtractType) -> Option<TaskKind> {
@@ -391,7 +493,7 @@ impl TaskConfig {
                 (
                     TaskKind::Snapshot,
                     SinkType::Dummy,
-                    DbType::Mysql | DbType::Pg | DbType::Mongo,
+                    DbType::Mysql | DbType::Pg | DbType::Mongo | DbType::ClickHouse,
                 ) => Some(CheckMode::Standalone),
                 (TaskKind::Snapshot, SinkType::Write, db_type)
                     if Self::write_sink_supports_inline_checker(db_type) =>
@@ -444,6 +546,7 @@ impl TaskConfig {
         let keepalive_interval_secs: u64 =
             loader.get_with_default(EXTRACTOR, KEEPALIVE_INTERVAL_SECS, 10);
         let heartbeat_tb = loader.get_optional(EXTRACTOR, HEARTBEAT_TB);
+        let heartbeat_tb_cleanup = loader.get_optional(EXTRACTOR, "heartbeat_tb_cleanup");
         let batch_size = loader.get_with_default(
             EXTRACTOR,
             BATCH_SIZE,
@@ -473,6 +576,7 @@ impl TaskConfig {
             rate_limiter,
             app_name: Some(app_name.to_owned()),
             is_direct_connection,
+            timeout: TimeoutConfig::from(loader, EXTRACTOR),
         };
 
         let not_supported_err =
@@ -495,6 +599,9 @@ impl TaskConfig {
                     ),
                     batch_size,
                     partition_cols: loader.get_optional(EXTRACTOR, PARTITION_COLS),
+                    snapshot_watermark: loader.get_optional(EXTRACTOR, "snapshot_watermark"),
+                    table_order: loader.get_optional(EXTRACTOR, "table_order"),
+                    enum_set_as_index: loader.get_optional(EXTRACTOR, ENUM_SET_AS_INDEX),
                 },
 
                 ExtractType::Cdc => ExtractorConfig::MysqlCdc {
@@ -517,6 +624,7 @@ impl TaskConfig {
                     ),
                     heartbeat_interval_secs,
                     heartbeat_tb,
+                    heartbeat_tb_cleanup,
                     keepalive_idle_secs: loader.get_with_default(
                         EXTRACTOR,
                         "keepalive_idle_secs",
@@ -529,6 +637,15 @@ impl TaskConfig {
                     ),
                     start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
                     end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+                    capture_rows_query: loader.get_optional(EXTRACTOR, "capture_rows_query"),
+                    enum_set_as_index: loader.get_optional(EXTRACTOR, ENUM_SET_AS_INDEX),
+                    failover_urls: loader
+                        .get_optional::<String>(EXTRACTOR, "failover_urls")
+                        .split(',')
+                        .map(|i| i.trim().to_string())
+                        .filter(|i| !i.is_empty())
+                        .collect(),
+                    sources: Self::load_mysql_cdc_sources(loader)?,
                 },
 
                 ExtractType::CheckLog => ExtractorConfig::MysqlCheck {
@@ -536,6 +653,11 @@ impl TaskConfig {
                     connection_auth,
                     check_log_dir: loader.get_required(EXTRACTOR, CHECK_LOG_DIR),
                     batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
+                    conflict_check: loader.get_optional(EXTRACTOR, CONFLICT_CHECK),
+                },
+
+                ExtractType::SnapshotFile => ExtractorConfig::ChunkFile {
+                    file_dir: loader.get_required(EXTRACTOR, "file_dir"),
                 },
 
                 ExtractType::Struct => ExtractorConfig::MysqlStruct {
@@ -568,6 +690,9 @@ impl TaskConfig {
                     ),
                     batch_size,
                     partition_cols: loader.get_optional(EXTRACTOR, PARTITION_COLS),
+                    snapshot_watermark: loader.get_optional(EXTRACTOR, "snapshot_watermark"),
+                    table_order: loader.get_optional(EXTRACTOR, "table_order"),
+                    consistent_snapshot: loader.get_optional(EXTRACTOR, "consistent_snapshot"),
                 },
 
                 ExtractType::Cdc => ExtractorConfig::PgCdc {
@@ -575,15 +700,20 @@ impl TaskConfig {
                     connection_auth,
                     slot_name: loader.get_required(EXTRACTOR, "slot_name"),
                     pub_name: loader.get_optional(EXTRACTOR, "pub_name"),
+                    pub_for_filtered_tables: loader
+                        .get_optional(EXTRACTOR, "pub_for_filtered_tables"),
                     start_lsn: loader.get_optional(EXTRACTOR, "start_lsn"),
                     recreate_slot_if_exists: loader
                         .get_optional(EXTRACTOR, "recreate_slot_if_exists"),
                     keepalive_interval_secs,
                     heartbeat_interval_secs,
                     heartbeat_tb,
+                    heartbeat_tb_cleanup,
                     ddl_meta_tb: loader.get_optional(EXTRACTOR, "ddl_meta_tb"),
                     start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
                     end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+                    replica_identity_policy: loader
+                        .get_optional(EXTRACTOR, "replica_identity_policy"),
                 },
 
                 ExtractType::CheckLog => ExtractorConfig::PgCheck {
@@ -591,6 +721,11 @@ impl TaskConfig {
                     connection_auth,
                     check_log_dir: loader.get_required(EXTRACTOR, CHECK_LOG_DIR),
                     batch_size: loader.get_with_default(EXTRACTOR, BATCH_SIZE, 200),
+                    conflict_check: loader.get_optional(EXTRACTOR, CONFLICT_CHECK),
+                },
+
+                ExtractType::SnapshotFile => ExtractorConfig::ChunkFile {
+                    file_dir: loader.get_required(EXTRACTOR, "file_dir"),
                 },
 
                 ExtractType::Struct => ExtractorConfig::PgStruct {
@@ -634,6 +769,8 @@ impl TaskConfig {
                             RdbParallelType::Table,
                         ),
                         batch_size,
+                        read_concern: loader.get_optional(EXTRACTOR, "read_concern"),
+                        read_preference: loader.get_optional(EXTRACTOR, "read_preference"),
                     }
                 }
 
@@ -650,6 +787,7 @@ impl TaskConfig {
                         source: MongoCdcSource::parse(&source)?,
                         heartbeat_interval_secs,
                         heartbeat_tb,
+                        heartbeat_tb_cleanup,
                     }
                 }
 
@@ -687,11 +825,13 @@ impl TaskConfig {
                         connection_auth,
                         repl_port,
                         is_cluster: Self::get_is_cluster_config(loader, EXTRACTOR),
+                        module_config: Self::load_redis_module_config(loader),
                     }
                 }
 
                 ExtractType::SnapshotFile => ExtractorConfig::RedisSnapshotFile {
                     file_path: loader.get_required(EXTRACTOR, "file_path"),
+                    module_config: Self::load_redis_module_config(loader),
                 },
 
                 ExtractType::Scan => ExtractorConfig::RedisScan {
@@ -728,6 +868,7 @@ impl TaskConfig {
                         heartbeat_interval_secs,
                         heartbeat_key: loader.get_optional(EXTRACTOR, "heartbeat_key"),
                         is_cluster: Self::get_is_cluster_config(loader, EXTRACTOR),
+                        module_config: Self::load_redis_module_config(loader),
                     }
                 }
 
@@ -746,6 +887,8 @@ impl TaskConfig {
                 partition: loader.get_optional(EXTRACTOR, "partition"),
                 offset: loader.get_optional(EXTRACTOR, "offset"),
                 ack_interval_secs: loader.get_optional(EXTRACTOR, "ack_interval_secs"),
+                dedup_window_size: loader.get_optional(EXTRACTOR, "dedup_window_size"),
+                replay_until: Self::load_kafka_replay_until(loader)?,
             },
 
             db_type => {
@@ -819,6 +962,7 @@ impl TaskConfig {
             max_mbps: loader.get_optional(SINKER, "max_mbps"),
         };
         let is_cluster = Self::get_is_cluster_config(loader, SINKER);
+        let default_basic = BasicSinkerConfig::default();
 
         let basic = BasicSinkerConfig {
             sink_type: sink_type.clone(),
@@ -831,6 +975,17 @@ impl TaskConfig {
             app_name: Some(app_name.to_owned()),
             is_direct_connection,
             is_cluster,
+            conn_grace_period_secs: loader.get_with_default(
+                SINKER,
+                "conn_grace_period_secs",
+                default_basic.conn_grace_period_secs,
+            ),
+            health_check_interval_secs: loader.get_with_default(
+                SINKER,
+                "health_check_interval_secs",
+                default_basic.health_check_interval_secs,
+            ),
+            timeout: TimeoutConfig::from(loader, SINKER),
         };
 
         let conflict_policy: ConflictPolicyEnum =
@@ -852,18 +1007,45 @@ impl TaskConfig {
                         true,
                     ),
                     transaction_isolation: loader.get_optional(SINKER, "transaction_isolation"),
+                    auto_create_table: Self::load_auto_create_table(loader, &DbType::Mysql)?,
+                    max_retries: loader.get_with_default(SINKER, MAX_RETRIES, 3),
+                    retry_interval_ms: loader.get_with_default(SINKER, "retry_interval_ms", 200),
+                    shard: Self::load_shard_config(loader)?,
+                    ddl_guard_policy: loader.get_optional(SINKER, "ddl_guard_policy"),
+                    ddl_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        DDL_CONFLICT_POLICY,
+                        ConflictPolicyEnum::Interrupt,
+                    ),
+                    load_data_dir: Self::load_load_data_dir(loader)?,
+                    sql_hooks: Self::load_sql_hooks(loader)?,
                 },
 
                 SinkType::Struct => SinkerConfig::MysqlStruct {
                     url,
                     connection_auth,
+                    object_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        "object_conflict_policy",
+                        conflict_policy.clone(),
+                    ),
                     conflict_policy,
+                    type_mappings: Self::load_type_mappings(loader)?,
                 },
 
                 SinkType::Sql => SinkerConfig::Sql {
                     reverse: loader.get_optional(SINKER, REVERSE),
                 },
 
+                SinkType::File => Self::load_file_sinker_config(loader)?,
+
+                SinkType::Validate => Self::load_validate_sinker_config(
+                    loader,
+                    db_type.clone(),
+                    url,
+                    connection_auth,
+                )?,
+
                 _ => bail! { not_supported_err },
             },
 
@@ -878,11 +1060,35 @@ impl TaskConfig {
                         DISABLE_FOREIGN_KEY_CHECKS,
                         true,
                     ),
+                    auto_create_table: Self::load_auto_create_table(loader, &DbType::Pg)?,
+                    statement_cache_capacity: loader.get_with_default(
+                        SINKER,
+                        STATEMENT_CACHE_CAPACITY,
+                        DEFAULT_PG_STATEMENT_CACHE_CAPACITY,
+                    ),
+                    pipeline_size: cmp::max(loader.get_with_default(SINKER, PIPELINE_SIZE, 1), 1),
+                    max_retries: loader.get_with_default(SINKER, MAX_RETRIES, 3),
+                    retry_interval_ms: loader.get_with_default(SINKER, "retry_interval_ms", 200),
+                    shard: Self::load_shard_config(loader)?,
+                    ddl_guard_policy: loader.get_optional(SINKER, "ddl_guard_policy"),
+                    ddl_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        DDL_CONFLICT_POLICY,
+                        ConflictPolicyEnum::Interrupt,
+                    ),
+                    ddl_transaction: loader.get_optional(SINKER, "ddl_transaction"),
+                    copy_large_objects: loader.get_optional(SINKER, "copy_large_objects"),
+                    sql_hooks: Self::load_sql_hooks(loader)?,
                 },
 
                 SinkType::Struct => SinkerConfig::PgStruct {
                     url,
                     connection_auth,
+                    object_conflict_policy: loader.get_with_default(
+                        SINKER,
+                        "object_conflict_policy",
+                        conflict_policy.clone(),
+                    ),
                     conflict_policy,
                 },
 
@@ -890,6 +1096,12 @@ impl TaskConfig {
                     reverse: loader.get_optional(SINKER, REVERSE),
                 },
 
+                SinkType::File => Self::load_file_sinker_config(loader)?,
+
+                SinkType::Validate => {
+                    Self::load_validate_sinker_config(loader, DbType::Pg, url, connection_auth)?
+                }
+
                 _ => bail! { not_supported_err },
             },
 
@@ -915,15 +1127,99 @@ impl TaskConfig {
                     conflict_policy,
                 },
 
+                SinkType::File => Self::load_file_sinker_config(loader)?,
+
                 _ => bail! { not_supported_err },
             },
 
-            DbType::Kafka => SinkerConfig::Kafka {
-                url,
-                batch_size,
-                ack_timeout_secs: loader.get_with_default(SINKER, "ack_timeout_secs", 5),
-                required_acks: loader.get_with_default(SINKER, "required_acks", "one".to_string()),
-                with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+            DbType::Kafka => {
+                let ddl_topic: String = loader.get_optional(SINKER, "ddl_topic");
+                let ddl_topic_suffix: String = loader.get_optional(SINKER, "ddl_topic_suffix");
+                if !ddl_topic.is_empty() && !ddl_topic_suffix.is_empty() {
+                    bail!(Error::ConfigError(
+                        "config [sinker].ddl_topic and ddl_topic_suffix can not be set together"
+                            .into()
+                    ));
+                }
+                let max_message_bytes: usize = loader.get_optional(SINKER, "max_message_bytes");
+                if max_message_bytes > 0 && max_message_bytes < MIN_KAFKA_MESSAGE_BYTES {
+                    bail!(Error::ConfigError(format!(
+                        "config [sinker].max_message_bytes should be 0 or at least {}",
+                        MIN_KAFKA_MESSAGE_BYTES
+                    )));
+                }
+                SinkerConfig::Kafka {
+                    url,
+                    batch_size,
+                    ack_timeout_secs: loader.get_with_default(SINKER, "ack_timeout_secs", 5),
+                    required_acks: loader.get_with_default(
+                        SINKER,
+                        "required_acks",
+                        "one".to_string(),
+                    ),
+                    with_field_defs: loader.get_with_default(SINKER, "with_field_defs", true),
+                    idempotency_key: loader.get_optional(SINKER, IDEMPOTENCY_KEY),
+                    compression: loader.get_optional(SINKER, "compression"),
+                    linger_ms: loader.get_optional(SINKER, "linger_ms"),
+                    with_provenance: loader.get_optional(SINKER, WITH_PROVENANCE),
+                    with_logical_types: loader.get_optional(SINKER, "with_logical_types"),
+                    ddl_topic,
+                    ddl_topic_suffix,
+                    transactional_id: loader.get_optional(SINKER, "transactional_id"),
+                    compress_cols: Self::load_kafka_compress_cols(loader)?,
+                    compress_min_bytes: loader.get_with_default(SINKER, "compress_min_bytes", 1024),
+                    max_message_bytes,
+                }
+            }
+
+            DbType::Http => match sink_type {
+                SinkType::Write => {
+                    if url.is_empty() {
+                        bail!(Error::ConfigError(
+                            "config [sinker].url is required for db_type=http".into()
+                        ));
+                    }
+                    SinkerConfig::Http {
+                        url,
+                        batch_size,
+                        headers: Self::load_http_headers(loader)?,
+                        timeout_secs: loader.get_with_default(SINKER, "timeout_secs", 30),
+                        max_retries: loader.get_with_default(SINKER, MAX_RETRIES, 5),
+                        retry_interval_ms: loader.get_with_default(
+                            SINKER,
+                            "retry_interval_ms",
+                            1000,
+                        ),
+                        with_provenance: loader.get_optional(SINKER, WITH_PROVENANCE),
+                        idempotency_key: loader.get_optional(SINKER, IDEMPOTENCY_KEY),
+                    }
+                }
+                _ => bail! { not_supported_err },
+            },
+
+            DbType::OpenSearch => match sink_type {
+                SinkType::Write => {
+                    if url.is_empty() {
+                        bail!(Error::ConfigError(
+                            "config [sinker].url is required for db_type=opensearch".into()
+                        ));
+                    }
+                    SinkerConfig::OpenSearch {
+                        url,
+                        connection_auth,
+                        batch_size,
+                        indices: Self::load_opensearch_indices(loader)?,
+                        create_index: loader.get_with_default(SINKER, "create_index", true),
+                        timeout_secs: loader.get_with_default(SINKER, "timeout_secs", 30),
+                        max_retries: loader.get_with_default(SINKER, MAX_RETRIES, 5),
+                        retry_interval_ms: loader.get_with_default(
+                            SINKER,
+                            "retry_interval_ms",
+                            1000,
+                        ),
+                    }
+                }
+                _ => bail! { not_supported_err },
             },
 
             DbType::Redis => match sink_type {
@@ -932,7 +1228,13 @@ impl TaskConfig {
                     connection_auth,
                     batch_size,
                     method: loader.get_optional(SINKER, "method"),
+                    restore_fallback: loader.get_with_default(SINKER, "restore_fallback", true),
                     is_cluster,
+                    table_keys: Self::load_redis_table_keys(loader)?,
+                },
+
+                SinkType::File => SinkerConfig::RedisAof {
+                    file_path: loader.get_required(SINKER, "file_path"),
                 },
 
                 SinkType::Statistic => SinkerConfig::RedisStatistic {
@@ -958,6 +1260,9 @@ impl TaskConfig {
                     url,
                     connection_auth,
                     conflict_policy,
+                    bucket_size_mb: loader.get_optional(SINKER, "bucket_size_mb"),
+                    partition_cols: loader.get_optional(SINKER, PARTITION_COLS),
+                    partition_unit: loader.get_optional(SINKER, "partition_unit"),
                 },
 
                 _ => bail! { not_supported_err },
@@ -975,13 +1280,20 @@ impl TaskConfig {
                     url,
                     connection_auth,
                     conflict_policy,
+                    bucket_size_mb: loader.get_optional(SINKER, "bucket_size_mb"),
+                    partition_cols: loader.get_optional(SINKER, PARTITION_COLS),
+                    partition_unit: loader.get_optional(SINKER, "partition_unit"),
                 },
 
                 _ => bail! { not_supported_err },
             },
 
             DbType::ClickHouse => match sink_type {
-                SinkType::Write => SinkerConfig::ClickHouse { url, batch_size },
+                SinkType::Write => SinkerConfig::ClickHouse {
+                    url,
+                    batch_size,
+                    insert_dedup_token: loader.get_with_default(SINKER, "insert_dedup_token", true),
+                },
 
                 SinkType::Struct => SinkerConfig::ClickhouseStruct {
                     url,
@@ -999,6 +1311,273 @@ impl TaskConfig {
         Ok((basic, sinker))
     }
 
+    fn load_auto_create_table(loader: &IniLoader, sinker_db_type: &DbType) -> anyhow::Result<bool> {
+        let auto_create_table: bool = loader.get_optional(SINKER, "auto_create_table");
+        if !auto_create_table {
+            return Ok(false);
+        }
+        // target tables are built by the struct statement builders of the source db
+        let extractor_db_type: DbType = loader.get_required(EXTRACTOR, DB_TYPE);
+        if &extractor_db_type != sinker_db_type {
+            bail!(Error::ConfigError(format!(
+                "config [sinker].auto_create_table is only supported for {} -> {}, but source is {}",
+                sinker_db_type, sinker_db_type, extractor_db_type
+            )));
+        }
+        Ok(true)
+    }
+
+    fn load_load_data_dir(loader: &IniLoader) -> anyhow::Result<String> {
+        let load_data_dir: String = loader.get_optional(SINKER, "load_data_dir");
+        if load_data_dir.is_empty() {
+            return Ok(load_data_dir);
+        }
+        // values are written as text of mysql types and loaded by the target as is
+        let extractor_db_type: DbType = loader.get_required(EXTRACTOR, DB_TYPE);
+        let extract_type: ExtractType = loader.get_required(EXTRACTOR, "extract_type");
+        if extractor_db_type != DbType::Mysql || extract_type != ExtractType::Snapshot {
+            bail!(Error::ConfigError(format!(
+                "config [sinker].load_data_dir is only supported for mysql -> mysql snapshot tasks, but source is {} {}",
+                extractor_db_type, extract_type
+            )));
+        }
+        Ok(load_data_dir)
+    }
+
+    fn load_mysql_cdc_sources(loader: &IniLoader) -> anyhow::Result<Vec<MysqlCdcSourceConfig>> {
+        let config_str: String = loader.get_optional(EXTRACTOR, "sources");
+        if config_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // sources=json:[{"id":"src_2","url":"mysql://127.0.0.1:3308","db_prefix":"src_2_"}]
+        let sources: Vec<MysqlCdcSourceConfig> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [extractor].sources is not valid json: {}",
+                e
+            ))
+        })?;
+        let mut ids = HashSet::from(["default".to_string()]);
+        for source in sources.iter() {
+            if source.url.is_empty() {
+                bail!(Error::ConfigError(format!(
+                    "config [extractor].sources: url of source [{}] is required",
+                    source.id
+                )));
+            }
+            if source.id.is_empty() || !ids.insert(source.id.clone()) {
+                bail!(Error::ConfigError(format!(
+                    "config [extractor].sources: source id [{}] is empty or duplicated, default is reserved",
+                    source.id
+                )));
+            }
+        }
+        Ok(sources)
+    }
+
+    fn load_shard_config(loader: &IniLoader) -> anyhow::Result<Option<ShardConfig>> {
+        let config_str: String = loader.get_optional(SINKER, "shards");
+        let hash_tbs: String = loader.get_optional(SINKER, "shard_hash_tbs");
+        if config_str.trim().is_empty() {
+            if !hash_tbs.is_empty() {
+                bail!(Error::ConfigError(
+                    "config [sinker].shard_hash_tbs requires [sinker].shards".into()
+                ));
+            }
+            return Ok(None);
+        }
+
+        // shards=json:[{"name":"shard_1","url":"mysql://127.0.0.1:3308","tbs":"db_1.*"}]
+        let targets: Vec<ShardTargetConfig> =
+            serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)).map_err(
+                |e| Error::ConfigError(format!("config [sinker].shards is not valid json: {}", e)),
+            )?;
+        let mut names = HashSet::from(["default".to_string()]);
+        for target in targets.iter() {
+            if target.url.is_empty() {
+                bail!(Error::ConfigError(format!(
+                    "config [sinker].shards: url of shard [{}] is required",
+                    target.name
+                )));
+            }
+            if target.name.is_empty() || !names.insert(target.name.clone()) {
+                bail!(Error::ConfigError(format!(
+                    "config [sinker].shards: shard name [{}] is empty or duplicated, default is reserved",
+                    target.name
+                )));
+            }
+        }
+        if targets.is_empty() {
+            bail!(Error::ConfigError(
+                "config [sinker].shards should contain at least one shard".into()
+            ));
+        }
+        Ok(Some(ShardConfig { targets, hash_tbs }))
+    }
+
+    fn load_kafka_replay_until(loader: &IniLoader) -> anyhow::Result<Option<KafkaReplayUntil>> {
+        let config_str: String = loader.get_optional(EXTRACTOR, "replay_until");
+        if config_str.trim().is_empty() {
+            return Ok(None);
+        }
+        match KafkaReplayUntil::from_str(&config_str) {
+            Ok(replay_until) => Ok(Some(replay_until)),
+            Err(_) => bail!(Error::ConfigError(format!(
+                "config [extractor].replay_until: [{}] is neither offset:<offset> nor a utc time like 2024-01-01 10:00:00",
+                config_str
+            ))),
+        }
+    }
+
+    fn load_kafka_compress_cols(
+        loader: &IniLoader,
+    ) -> anyhow::Result<Vec<KafkaCompressColsConfig>> {
+        let config_str: String = loader.get_optional(SINKER, "compress_cols");
+        if config_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // compress_cols=json:[{"tbs":"db_1.tb_1","cols":["content","attachment"]}]
+        let configs: Vec<KafkaCompressColsConfig> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [sinker].compress_cols is not valid json: {}",
+                e
+            ))
+        })?;
+        Ok(configs)
+    }
+
+    fn load_sql_hooks(loader: &IniLoader) -> anyhow::Result<Vec<SqlHookConfig>> {
+        let config_str: String = loader.get_optional(SINKER, "sql_hooks");
+        if config_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // sql_hooks=json:[{"stage":"after_tb","tbs":"db_1.*","sql":"ANALYZE TABLE {schema}.{tb}"}]
+        let hooks: Vec<SqlHookConfig> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [sinker].sql_hooks is not valid json: {}",
+                e
+            ))
+        })?;
+        if let Some(hook) = hooks.iter().find(|hook| hook.sql.trim().is_empty()) {
+            bail!(Error::ConfigError(format!(
+                "config [sinker].sql_hooks: sql of {} hook is empty",
+                hook.stage
+            )));
+        }
+        Ok(hooks)
+    }
+
+    fn load_validate_sinker_config(
+        loader: &IniLoader,
+        db_type: DbType,
+        url: String,
+        connection_auth: ConnectionAuthConfig,
+    ) -> anyhow::Result<SinkerConfig> {
+        let sample_rows: usize = loader.get_with_default(SINKER, SAMPLE_ROWS, 1000);
+        if sample_rows == 0 {
+            bail!(Error::ConfigError(
+                "config [sinker].sample_rows must be greater than 0".into()
+            ));
+        }
+        Ok(SinkerConfig::Validate {
+            db_type,
+            url,
+            connection_auth,
+            sample_rows,
+        })
+    }
+
+    fn load_file_sinker_config(loader: &IniLoader) -> anyhow::Result<SinkerConfig> {
+        let max_file_size: String =
+            loader.get_with_default(SINKER, "max_file_size", "100mb".to_string());
+        let max_file_size = parse_size_limit(&max_file_size).map_err(|e| {
+            Error::ConfigError(format!(
+                "invalid config [sinker].max_file_size: {}, error: {}",
+                max_file_size, e
+            ))
+        })?;
+        let file_format = loader.get_with_default(SINKER, "file_format", FileFormat::Csv);
+        let s3_config = Self::load_s3_config(loader, SINKER);
+        // chunks are imported from the local file_dir
+        if s3_config.is_some() && file_format == FileFormat::Chunk {
+            bail! {Error::ConfigError(
+                "config [sinker].s3_bucket is not supported by file_format=chunk".into()
+            )}
+        }
+        Ok(SinkerConfig::File {
+            file_dir: loader.get_required(SINKER, "file_dir"),
+            file_format,
+            max_file_size,
+            gzip: loader.get_optional(SINKER, "gzip"),
+            orc_compression: loader.get_with_default(
+                SINKER,
+                "orc_compression",
+                OrcCompression::Zlib,
+            ),
+            s3_config,
+            s3_key_prefix: loader.get_optional(SINKER, S3_KEY_PREFIX),
+        })
+    }
+
+    /// Source transactions are applied by target transactions kept open across batches, which
+    /// only the serial mysql / pg sinker supports.
+    fn check_transactional(
+        extractor_basic: &BasicExtractorConfig,
+        sinker: &SinkerConfig,
+        parallelizer: &ParallelizerConfig,
+    ) -> anyhow::Result<()> {
+        if !matches!(extractor_basic.extract_type, ExtractType::Cdc)
+            || !matches!(extractor_basic.db_type, DbType::Mysql | DbType::Pg)
+        {
+            bail!(Error::ConfigError(
+                "config [pipeline].transactional=true requires [extractor] db_type=mysql / pg and extract_type=cdc".into()
+            ));
+        }
+        if !matches!(
+            sinker,
+            SinkerConfig::Mysql { shard: None, .. } | SinkerConfig::Pg { shard: None, .. }
+        ) {
+            bail!(Error::ConfigError(
+                "config [pipeline].transactional=true requires [sinker] db_type=mysql / pg and sink_type=write without shards".into()
+            ));
+        }
+        if !matches!(parallelizer.parallel_type(), ParallelType::Serial)
+            || parallelizer.parallel_size() != 1
+        {
+            bail!(Error::ConfigError(
+                "config [pipeline].transactional=true requires [parallelizer] parallel_type=serial and parallel_size=1".into()
+            ));
+        }
+        Ok(())
+    }
+
+    fn load_s3_config(loader: &IniLoader, section: &str) -> Option<S3Config> {
+        let bucket: String = loader.get_optional(section, "s3_bucket");
+        if bucket.is_empty() {
+            return None;
+        }
+        Some(S3Config {
+            bucket,
+            access_key: loader.get_optional(section, "s3_access_key_id"),
+            secret_key: loader.get_optional(section, "s3_secret_access_key"),
+            region: loader.get_optional(section, "s3_region"),
+            endpoint: loader.get_optional(section, "s3_endpoint"),
+            root_dir: loader.get_optional(section, "s3_root_dir"),
+            root_url: loader.get_optional(section, "s3_root_url"),
+        })
+    }
+
     fn load_parallelizer_config(
         loader: &IniLoader,
         sinker_basic: &BasicSinkerConfig,
@@ -1007,10 +1586,25 @@ impl TaskConfig {
         let parallel_size = loader.get_with_default(PARALLELIZER, PARALLEL_SIZE, 1);
         let parallel_type =
             loader.get_with_default(PARALLELIZER, "parallel_type", ParallelType::Serial);
+        let adaptive_batch = Self::load_adaptive_batch_config(loader, sinker_basic)?;
+        let lag_priority: bool = loader.get_optional(PARALLELIZER, "lag_priority");
+        if lag_priority
+            && !matches!(
+                parallel_type,
+                ParallelType::RdbMerge | ParallelType::RdbPartition | ParallelType::Mongo
+            )
+        {
+            bail!(Error::ConfigError(format!(
+                "config [parallelizer].lag_priority is only supported by parallel_type=rdb_merge / rdb_partition / mongo, got: {}",
+                parallel_type
+            )));
+        }
         if !matches!(parallel_type, ParallelType::Snapshot) {
             return Ok(ParallelizerConfig::Basic {
                 parallel_size,
                 parallel_type,
+                lag_priority,
+                adaptive_batch,
             });
         }
 
@@ -1073,13 +1667,58 @@ impl TaskConfig {
                 min_partition_rows,
                 split_skew_ratio,
             },
+            adaptive_batch,
         })
     }
 
+    fn load_adaptive_batch_config(
+        loader: &IniLoader,
+        sinker_basic: &BasicSinkerConfig,
+    ) -> anyhow::Result<Option<AdaptiveBatchConfig>> {
+        if !loader.get_optional::<bool>(PARALLELIZER, "adaptive_batch") {
+            return Ok(None);
+        }
+
+        let max_batch_size = loader.get_with_default(
+            PARALLELIZER,
+            ADAPTIVE_BATCH_MAX_SIZE,
+            sinker_basic.batch_size,
+        );
+        let min_batch_size = loader.get_with_default(
+            PARALLELIZER,
+            ADAPTIVE_BATCH_MIN_SIZE,
+            cmp::max(max_batch_size / 10, 1),
+        );
+        if min_batch_size == 0 || min_batch_size > max_batch_size {
+            bail!(Error::ConfigError(format!(
+                "config [parallelizer].{} must be greater than 0 and not greater than {}",
+                ADAPTIVE_BATCH_MIN_SIZE, ADAPTIVE_BATCH_MAX_SIZE
+            )));
+        }
+
+        let target_latency_ms =
+            loader.get_with_default(PARALLELIZER, ADAPTIVE_BATCH_TARGET_LATENCY_MS, 1000);
+        if target_latency_ms == 0 {
+            bail!(Error::ConfigError(format!(
+                "config [parallelizer].{} must be greater than 0",
+                ADAPTIVE_BATCH_TARGET_LATENCY_MS
+            )));
+        }
+
+        Ok(Some(AdaptiveBatchConfig {
+            min_batch_size,
+            max_batch_size,
+            target_latency_ms,
+        }))
+    }
+
     fn load_pipeline_config(loader: &IniLoader) -> PipelineConfig {
         let capacity_limiter = CapacityLimiterConfig {
             buffer_size: loader.get_with_default(PIPELINE, "buffer_size", 16000),
             buffer_memory_mb: loader.get_optional(PIPELINE, "buffer_memory_mb"),
+            control_buffer_size: loader.get_with_default(PIPELINE, "control_buffer_size", 1000),
+            control_buffer_memory_mb: loader.get_optional(PIPELINE, "control_buffer_memory_mb"),
+            memory_budget_mb: loader.get_optional(PIPELINE, "memory_budget_mb"),
         };
         let mut config = PipelineConfig {
             capacity_limiter,
@@ -1092,6 +1731,30 @@ impl TaskConfig {
             counter_time_window_secs: loader.get_optional(PIPELINE, "counter_time_window_secs"),
             counter_max_sub_count: loader.get_with_default(PIPELINE, "counter_max_sub_count", 1000),
             pipeline_type: loader.get_with_default(PIPELINE, "pipeline_type", PipelineType::Basic),
+            table_overrides: TableOverrides::default(),
+            table_removal_file: loader.get_optional(PIPELINE, "table_removal_file"),
+            table_backfill_file: loader.get_optional(PIPELINE, "table_backfill_file"),
+            table_backfill_max_held_mb: loader.get_with_default(
+                PIPELINE,
+                "table_backfill_max_held_mb",
+                1024,
+            ),
+            transactional: loader.get_optional(PIPELINE, "transactional"),
+            trx_max_held_mb: loader.get_with_default(PIPELINE, "trx_max_held_mb", 64),
+            trx_spill_dir: loader.get_optional(PIPELINE, "trx_spill_dir"),
+            snapshot_manifest_file: loader.get_optional(PIPELINE, "snapshot_manifest_file"),
+            idle_threshold_secs: loader.get_optional(PIPELINE, "idle_threshold_secs"),
+            idle_checkpoint_interval_secs: loader.get_with_default(
+                PIPELINE,
+                "idle_checkpoint_interval_secs",
+                60,
+            ),
+            trace_sample_rate: loader.get_with_default(PIPELINE, "trace_sample_rate", 0.0),
+            trace_report_interval_secs: loader.get_with_default(
+                PIPELINE,
+                "trace_report_interval_secs",
+                60,
+            ),
         };
 
         if config.counter_time_window_secs == 0 {
@@ -1100,46 +1763,214 @@ impl TaskConfig {
         config
     }
 
-    fn load_checker_config(loader: &IniLoader) -> anyhow::Result<Option<CheckerConfig>> {
-        if !Self::is_checker_enabled(loader)? {
-            return Ok(None);
+    fn load_http_headers(loader: &IniLoader) -> anyhow::Result<HashMap<String, String>> {
+        let config_str: String = loader.get_optional(SINKER, "headers");
+        if config_str.trim().is_empty() {
+            return Ok(HashMap::new());
         }
 
-        let default = CheckerConfig::default();
-        let sample_rate = match loader.ini.get(CHECKER, SAMPLE_RATE) {
-            Some(raw) if !raw.is_empty() => {
-                let sample_rate = raw.parse::<usize>().map_err(|_| {
-                    Error::ConfigError(format!(
-                        "config [checker].{}={}, can not be parsed as usize",
-                        SAMPLE_RATE, raw
-                    ))
-                })?;
-                if !(1..=100).contains(&sample_rate) {
-                    bail!(Error::ConfigError(format!(
-                        "config [checker].sample_rate must be between 1 and 100, got {}",
-                        sample_rate
-                    )));
-                }
-                Some(sample_rate as u8)
-            }
-            _ => None,
-        };
-        let config = CheckerConfig {
-            queue_size: loader.get_with_default(CHECKER, CHECKER_QUEUE_SIZE, default.queue_size),
-            max_connections: loader.get_with_default(
-                CHECKER,
-                MAX_CONNECTIONS,
-                default.max_connections,
-            ),
-            batch_size: loader.get_with_default(CHECKER, BATCH_SIZE, default.batch_size),
-            sample_rate,
-            output_full_row: loader.get_with_default(
-                CHECKER,
-                OUTPUT_FULL_ROW,
-                default.output_full_row,
-            ),
-            output_revise_sql: loader.get_with_default(
-                CHECKER,
+        // headers=json:{"Authorization":"Bearer xxx"}
+        serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)).map_err(|e| {
+            Error::ConfigError(format!("config [sinker].headers is not valid json: {}", e)).into()
+        })
+    }
+
+    fn load_type_mappings(loader: &IniLoader) -> anyhow::Result<HashMap<String, String>> {
+        let config_str: String = loader.get_optional(SINKER, "type_mappings");
+        if config_str.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // type_mappings=json:{"uuid":"binary(16)","public.orders.amount":"decimal(20,4)"}
+        serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)).map_err(|e| {
+            Error::ConfigError(format!(
+                "config [sinker].type_mappings is not valid json: {}",
+                e
+            ))
+            .into()
+        })
+    }
+
+    fn load_redis_table_keys(
+        loader: &IniLoader,
+    ) -> anyhow::Result<HashMap<(String, String), RedisTableKey>> {
+        let config_str: String = loader.get_optional(SINKER, TABLE_KEYS);
+        if config_str.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // table_keys=json:[{"db":"db_1","tb":"tb_1","key":"user:{id}","ttl_secs":3600}]
+        #[derive(Deserialize)]
+        struct TableKeyType {
+            db: String,
+            tb: String,
+            #[serde(flatten)]
+            table_key: RedisTableKey,
+        }
+        let items: Vec<TableKeyType> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [sinker].{} is not valid json: {}",
+                TABLE_KEYS, e
+            ))
+        })?;
+        Ok(items
+            .into_iter()
+            .map(|item| ((item.db, item.tb), item.table_key))
+            .collect())
+    }
+
+    fn load_opensearch_indices(
+        loader: &IniLoader,
+    ) -> anyhow::Result<HashMap<(String, String), String>> {
+        let config_str: String = loader.get_optional(SINKER, INDICES);
+        if config_str.trim().is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // indices=json:[{"db":"db_1","tb":"tb_1","index":"users"}]
+        #[derive(Deserialize)]
+        struct IndexType {
+            db: String,
+            tb: String,
+            index: String,
+        }
+        let items: Vec<IndexType> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [sinker].{} is not valid json: {}",
+                INDICES, e
+            ))
+        })?;
+        Ok(items
+            .into_iter()
+            .map(|item| ((item.db, item.tb), item.index))
+            .collect())
+    }
+
+    fn load_table_overrides(
+        loader: &IniLoader,
+        parallelizer: &ParallelizerConfig,
+    ) -> anyhow::Result<TableOverrides> {
+        let config_str: String = loader.get_optional(PIPELINE, TABLE_OVERRIDES);
+        if config_str.trim().is_empty() {
+            return Ok(TableOverrides::default());
+        }
+
+        // table_overrides=json:[{"db":"db_1","tb":"tb_1","batch_size":5000,"parallel_size":2}]
+        #[derive(Deserialize)]
+        struct TableOverrideType {
+            db: String,
+            tb: String,
+            #[serde(flatten)]
+            table_override: TableOverride,
+        }
+        let items: Vec<TableOverrideType> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [pipeline].{} is not valid json: {}",
+                TABLE_OVERRIDES, e
+            ))
+        })?;
+
+        let mut overrides = HashMap::new();
+        for item in items {
+            let name = format!("{}.{}", item.db, item.tb);
+            let o = item.table_override;
+            if o.batch_size == Some(0) || o.parallel_size == Some(0) {
+                bail!(Error::ConfigError(format!(
+                    "config [pipeline].{} for {}: batch_size and parallel_size must be greater than 0",
+                    TABLE_OVERRIDES, name
+                )));
+            }
+            if o.key_cols.as_ref().is_some_and(|cols| cols.is_empty()) {
+                bail!(Error::ConfigError(format!(
+                    "config [pipeline].{} for {}: key_cols must not be empty",
+                    TABLE_OVERRIDES, name
+                )));
+            }
+
+            if let Some(parallel_size) = o.parallel_size {
+                if !matches!(
+                    parallelizer.parallel_type(),
+                    ParallelType::Snapshot | ParallelType::RdbMerge | ParallelType::Mongo
+                ) {
+                    bail!(Error::ConfigError(format!(
+                        "config [pipeline].{} for {}: parallel_size is only supported by [parallelizer] parallel_type=snapshot/rdb_merge/mongo, got {}",
+                        TABLE_OVERRIDES, name, parallelizer.parallel_type()
+                    )));
+                }
+                if parallel_size > parallelizer.parallel_size() {
+                    bail!(Error::ConfigError(format!(
+                        "config [pipeline].{} for {}: parallel_size={} exceeds [parallelizer].{}={}",
+                        TABLE_OVERRIDES,
+                        name,
+                        parallel_size,
+                        PARALLEL_SIZE,
+                        parallelizer.parallel_size()
+                    )));
+                }
+            }
+
+            if let Some(existing) = overrides.get(&(item.db.clone(), item.tb.clone())) {
+                if existing != &o {
+                    bail!(Error::ConfigError(format!(
+                        "config [pipeline].{} has conflicting settings for {}",
+                        TABLE_OVERRIDES, name
+                    )));
+                }
+            }
+            overrides.insert((item.db, item.tb), o);
+        }
+        Ok(TableOverrides::new(overrides))
+    }
+
+    fn load_checker_config(loader: &IniLoader) -> anyhow::Result<Option<CheckerConfig>> {
+        if !Self::is_checker_enabled(loader)? {
+            return Ok(None);
+        }
+
+        let default = CheckerConfig::default();
+        let sample_rate = match loader.ini.get(CHECKER, SAMPLE_RATE) {
+            Some(raw) if !raw.is_empty() => {
+                let sample_rate = raw.parse::<usize>().map_err(|_| {
+                    Error::ConfigError(format!(
+                        "config [checker].{}={}, can not be parsed as usize",
+                        SAMPLE_RATE, raw
+                    ))
+                })?;
+                if !(1..=100).contains(&sample_rate) {
+                    bail!(Error::ConfigError(format!(
+                        "config [checker].sample_rate must be between 1 and 100, got {}",
+                        sample_rate
+                    )));
+                }
+                Some(sample_rate as u8)
+            }
+            _ => None,
+        };
+        let config = CheckerConfig {
+            queue_size: loader.get_with_default(CHECKER, CHECKER_QUEUE_SIZE, default.queue_size),
+            max_connections: loader.get_with_default(
+                CHECKER,
+                MAX_CONNECTIONS,
+                default.max_connections,
+            ),
+            batch_size: loader.get_with_default(CHECKER, BATCH_SIZE, default.batch_size),
+            sample_rate,
+            output_full_row: loader.get_with_default(
+                CHECKER,
+                OUTPUT_FULL_ROW,
+                default.output_full_row,
+            ),
+            output_revise_sql: loader.get_with_default(
+                CHECKER,
                 OUTPUT_REVISE_SQL,
                 default.output_revise_sql,
             ),
@@ -1154,6 +1985,17 @@ impl TaskConfig {
                 default.retry_interval_secs,
             ),
             max_retries: loader.get_with_default(CHECKER, MAX_RETRIES, default.max_retries),
+            snapshot_isolation: loader.get_with_default(
+                CHECKER,
+                "snapshot_isolation",
+                default.snapshot_isolation,
+            ),
+            review_rounds: loader.get_with_default(CHECKER, REVIEW_ROUNDS, default.review_rounds),
+            review_interval_secs: loader.get_with_default(
+                CHECKER,
+                "review_interval_secs",
+                default.review_interval_secs,
+            ),
             check_log_dir: loader.get_with_default(CHECKER, CHECK_LOG_DIR, default.check_log_dir),
             check_log_file_size: loader.get_with_default(
                 CHECKER,
@@ -1165,23 +2007,13 @@ impl TaskConfig {
                 CHECK_LOG_MAX_ROWS,
                 default.check_log_max_rows,
             ),
+            check_log_compress: loader.get_with_default(
+                CHECKER,
+                CHECK_LOG_COMPRESS,
+                default.check_log_compress,
+            ),
             check_log_s3: loader.get_with_default(CHECKER, CHECK_LOG_S3, default.check_log_s3),
-            s3_config: {
-                let bucket: String = loader.get_optional(CHECKER, "s3_bucket");
-                if bucket.is_empty() {
-                    None
-                } else {
-                    Some(S3Config {
-                        bucket,
-                        access_key: loader.get_optional(CHECKER, "s3_access_key_id"),
-                        secret_key: loader.get_optional(CHECKER, "s3_secret_access_key"),
-                        region: loader.get_optional(CHECKER, "s3_region"),
-                        endpoint: loader.get_optional(CHECKER, "s3_endpoint"),
-                        root_dir: loader.get_optional(CHECKER, "s3_root_dir"),
-                        root_url: loader.get_optional(CHECKER, "s3_root_url"),
-                    })
-                }
-            },
+            s3_config: Self::load_s3_config(loader, CHECKER),
             s3_key_prefix: loader.get_with_default(CHECKER, S3_KEY_PREFIX, default.s3_key_prefix),
             cdc_check_log_interval_secs: loader.get_with_default(
                 CHECKER,
@@ -1208,6 +2040,7 @@ impl TaskConfig {
             app_name: Some(APP_NAME.to_string()),
             is_direct_connection: None,
             is_cluster: None,
+            ..Default::default()
         }
     }
 
@@ -1225,6 +2058,8 @@ impl TaskConfig {
                 "check_result_stdout_only",
                 false,
             ),
+            log_dir_per_task: loader.get_optional(RUNTIME, "log_dir_per_task"),
+            log_format: loader.get_with_default(RUNTIME, "log_format", LogFormat::Text),
         })
     }
 
@@ -1258,6 +2093,7 @@ impl TaskConfig {
             tb_map: loader.get_optional(ROUTER, "tb_map"),
             col_map: loader.get_optional(ROUTER, "col_map"),
             topic_map: loader.get_optional(ROUTER, "topic_map"),
+            identifier_case: loader.get_optional(ROUTER, "identifier_case"),
         })
     }
 
@@ -1348,6 +2184,155 @@ impl TaskConfig {
         }))
     }
 
+    fn load_janitor_config(
+        loader: &IniLoader,
+        runtime: &RuntimeConfig,
+        sinker: &SinkerConfig,
+        checker: Option<&CheckerConfig>,
+    ) -> anyhow::Result<Option<JanitorConfig>> {
+        if !loader.ini.sections().contains(&JANITOR.to_string()) {
+            return Ok(None);
+        }
+
+        let mut dirs = vec![runtime.log_dir.clone()];
+        if let Some(checker) = checker {
+            dirs.push(checker.check_log_dir.clone());
+        }
+        if let SinkerConfig::RedisStatistic {
+            statistic_log_dir, ..
+        } = sinker
+        {
+            dirs.push(statistic_log_dir.clone());
+        }
+        let extra_dirs: String = loader.get_optional(JANITOR, "dirs");
+        dirs.extend(extra_dirs.split(',').map(|dir| dir.trim().to_string()));
+        dirs.retain(|dir| !dir.is_empty());
+        dirs.dedup();
+
+        let max_size: String = loader.get_optional(JANITOR, "max_size");
+        let max_size = if max_size.trim().is_empty() {
+            0
+        } else {
+            parse_size_limit(&max_size).map_err(|e| {
+                Error::ConfigError(format!(
+                    "invalid config [{}].max_size: {}, error: {}",
+                    JANITOR, max_size, e
+                ))
+            })?
+        };
+        let max_age_hours: u64 = loader.get_optional(JANITOR, "max_age_hours");
+        let disk_usage_warn_percent =
+            loader.get_with_default(JANITOR, "disk_usage_warn_percent", 90);
+        if disk_usage_warn_percent > 100 {
+            bail!(Error::ConfigError(format!(
+                "config [{}].disk_usage_warn_percent must be in [0, 100]",
+                JANITOR
+            )));
+        }
+
+        Ok(Some(JanitorConfig {
+            dirs,
+            interval_secs: loader.get_with_default(JANITOR, "interval_secs", 300),
+            max_age_secs: max_age_hours * 3600,
+            max_size,
+            disk_usage_warn_percent,
+        }))
+    }
+
+    fn load_circuit_breaker_config(
+        loader: &IniLoader,
+        sinker: &SinkerConfig,
+    ) -> anyhow::Result<Option<CircuitBreakerConfig>> {
+        if !loader.ini.sections().contains(&CIRCUIT_BREAKER.to_string()) {
+            return Ok(None);
+        }
+
+        // failed rows are retried in place by the mysql / pg sinkers
+        if !matches!(sinker, SinkerConfig::Mysql { .. } | SinkerConfig::Pg { .. }) {
+            bail!(Error::ConfigError(format!(
+                "config [{}] only supports [sinker] db_type=mysql/pg with sink_type=write",
+                CIRCUIT_BREAKER
+            )));
+        }
+        let trip_errors: Vec<String> = loader
+            .get_optional::<String>(CIRCUIT_BREAKER, "trip_errors")
+            .split(',')
+            .map(|error| error.trim().to_string())
+            .filter(|error| !error.is_empty())
+            .collect();
+        let probe_sql: String = loader.get_optional(CIRCUIT_BREAKER, "probe_sql");
+        if trip_errors.is_empty() && probe_sql.is_empty() {
+            bail!(Error::ConfigError(format!(
+                "config [{}] requires trip_errors or probe_sql, otherwise errors which never recover are retried forever",
+                CIRCUIT_BREAKER
+            )));
+        }
+        let probe_interval_secs =
+            loader.get_with_default(CIRCUIT_BREAKER, "probe_interval_secs", 10);
+        if probe_interval_secs == 0 {
+            bail!(Error::ConfigError(format!(
+                "config [{}].probe_interval_secs must be greater than 0",
+                CIRCUIT_BREAKER
+            )));
+        }
+
+        Ok(Some(CircuitBreakerConfig {
+            trip_errors,
+            probe_sql,
+            probe_interval_secs,
+            max_open_secs: loader.get_with_default(CIRCUIT_BREAKER, "max_open_secs", 3600),
+        }))
+    }
+
+    fn load_proxy_config(loader: &IniLoader) -> anyhow::Result<ProxyConfig> {
+        let config = ProxyConfig {
+            http_proxy: loader.get_optional(PROXY, "http_proxy"),
+            kafka_proxy: loader.get_optional(PROXY, "kafka_proxy"),
+            redis_proxy: loader.get_optional(PROXY, "redis_proxy"),
+            db_proxy: loader.get_optional(PROXY, "db_proxy"),
+        };
+        for proxy in [
+            &config.http_proxy,
+            &config.kafka_proxy,
+            &config.redis_proxy,
+            &config.db_proxy,
+        ] {
+            if !proxy.is_empty() {
+                ProxyTunnel::validate(proxy)?;
+            }
+        }
+        Ok(config)
+    }
+
+    fn load_struct_sync_config(
+        loader: &IniLoader,
+        extractor_basic: &BasicExtractorConfig,
+        sinker_basic: &BasicSinkerConfig,
+    ) -> anyhow::Result<Option<StructSyncConfig>> {
+        if !loader.ini.sections().contains(&STRUCT_SYNC.to_string()) {
+            return Ok(None);
+        }
+
+        if !matches!(extractor_basic.db_type, DbType::Mysql | DbType::Pg)
+            || extractor_basic.db_type != sinker_basic.db_type
+            || extractor_basic.url.is_empty()
+            || sinker_basic.url.is_empty()
+        {
+            bail!(Error::ConfigError(format!(
+                "config [{}] only supports mysql -> mysql and pg -> pg tasks with both [extractor].url and [sinker].url",
+                STRUCT_SYNC
+            )));
+        }
+        let interval_secs = loader.get_with_default(STRUCT_SYNC, "interval_secs", 300);
+        if interval_secs == 0 {
+            bail!(Error::ConfigError(format!(
+                "config [{}].interval_secs must be greater than 0",
+                STRUCT_SYNC
+            )));
+        }
+        Ok(Some(StructSyncConfig { interval_secs }))
+    }
+
     fn load_processor_config(loader: &IniLoader) -> anyhow::Result<Option<ProcessorConfig>> {
         if !loader.ini.sections().contains(&PROCESSOR.to_string()) {
             return Ok(None);
@@ -1365,6 +2350,217 @@ impl TaskConfig {
         Ok(Some(ProcessorConfig {
             lua_code_file,
             lua_code,
+            text_cleanup: loader.get_optional(PROCESSOR, "text_cleanup"),
+            mongo_projections: Self::load_mongo_projections(loader)?,
+            soft_deletes: Self::load_soft_deletes(loader)?,
+            redis_mappings: Self::load_redis_mappings(loader)?,
+        }))
+    }
+
+    fn load_redis_mappings(loader: &IniLoader) -> anyhow::Result<Vec<RedisMappingConfig>> {
+        let config_str: String = loader.get_optional(PROCESSOR, "redis_mappings");
+        if config_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let extractor_db_type: DbType = loader.get_required(EXTRACTOR, DB_TYPE);
+        let sinker_db_type: DbType = loader.get_required(SINKER, DB_TYPE);
+        if extractor_db_type != DbType::Redis
+            || !matches!(sinker_db_type, DbType::Mysql | DbType::Pg)
+        {
+            bail!(Error::ConfigError(
+                "config [processor].redis_mappings only supports redis sources and mysql / pg targets"
+                    .into()
+            ));
+        }
+
+        // redis_mappings=json:[{"key_pattern":"user:{id}","db":"db_1","tb":"users","value_col":"value"}]
+        let mappings: Vec<RedisMappingConfig> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [processor].redis_mappings is not valid json: {}",
+                e
+            ))
+        })?;
+        for mapping in mappings.iter() {
+            if mapping.db.is_empty() || mapping.tb.is_empty() {
+                bail!(Error::ConfigError(
+                    "config [processor].redis_mappings: db and tb are required".into()
+                ));
+            }
+            // the row key is extracted from the redis key, e.g. user:{id}
+            if !mapping.key_pattern.contains('{') || !mapping.key_pattern.contains('}') {
+                bail!(Error::ConfigError(format!(
+                    "config [processor].redis_mappings of {}.{}: key_pattern: [{}] has no {{col}}",
+                    mapping.db, mapping.tb, mapping.key_pattern
+                )));
+            }
+        }
+        Ok(mappings)
+    }
+
+    fn load_soft_deletes(loader: &IniLoader) -> anyhow::Result<Vec<SoftDeleteConfig>> {
+        let config_str: String = loader.get_optional(PROCESSOR, "soft_deletes");
+        if config_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // soft_deletes=json:[{"db":"db_1","tb":"tb_1","flag_col":"_deleted","flag_value":"1","time_col":"_deleted_at"}]
+        let soft_deletes: Vec<SoftDeleteConfig> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [processor].soft_deletes is not valid json: {}",
+                e
+            ))
+        })?;
+        for soft_delete in soft_deletes.iter() {
+            if soft_delete.db.is_empty() || soft_delete.tb.is_empty() {
+                bail!(Error::ConfigError(
+                    "config [processor].soft_deletes: db and tb are required".into()
+                ));
+            }
+            if soft_delete.flag_col.is_empty() && soft_delete.time_col.is_empty() {
+                bail!(Error::ConfigError(format!(
+                    "config [processor].soft_deletes of {}.{}: flag_col or time_col is required",
+                    soft_delete.db, soft_delete.tb
+                )));
+            }
+        }
+        Ok(soft_deletes)
+    }
+
+    fn load_mongo_projections(loader: &IniLoader) -> anyhow::Result<Vec<MongoProjection>> {
+        let config_str: String = loader.get_optional(PROCESSOR, "mongo_projections");
+        if config_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // mongo_projections=json:[{"db":"db_1","tb":"tb_1","include":[],"exclude":["logs"],"rename":{"name":"full_name"}}]
+        let projections: Vec<MongoProjection> = serde_json::from_str(
+            config_str.trim().trim_start_matches(JSON_PREFIX),
+        )
+        .map_err(|e| {
+            Error::ConfigError(format!(
+                "config [processor].mongo_projections is not valid json: {}",
+                e
+            ))
+        })?;
+        for projection in projections.iter() {
+            let paths = projection
+                .include
+                .iter()
+                .chain(projection.exclude.iter())
+                .chain(projection.rename.keys())
+                .chain(projection.rename.values());
+            for path in paths {
+                if path.is_empty() || path.split('.').next() == Some(MongoConstants::ID) {
+                    bail!(Error::ConfigError(format!(
+                        "config [processor].mongo_projections of {}.{}: _id or empty path: [{}] is not allowed",
+                        projection.db, projection.tb, path
+                    )));
+                }
+            }
+        }
+        Ok(projections)
+    }
+
+    fn load_distributed_config(
+        loader: &IniLoader,
+        extractor_basic: &BasicExtractorConfig,
+    ) -> anyhow::Result<Option<DistributedConfig>> {
+        if !loader.ini.sections().contains(&DISTRIBUTED.to_string()) {
+            return Ok(None);
+        }
+
+        // tables are the unit of work, which only makes sense for snapshot tasks
+        if !matches!(extractor_basic.extract_type, ExtractType::Snapshot)
+            || !matches!(
+                extractor_basic.db_type,
+                DbType::Mysql | DbType::Pg | DbType::Mongo
+            )
+        {
+            bail!(Error::ConfigError(format!(
+                "config [{}] only supports [extractor] extract_type=snapshot with db_type=mysql, pg or mongo",
+                DISTRIBUTED
+            )));
+        }
+
+        let role: DistributedRole = loader.get_required(DISTRIBUTED, "role");
+        let config = match role {
+            DistributedRole::Coordinator => DistributedConfig::Coordinator {
+                http_host: loader.get_with_default(DISTRIBUTED, "http_host", "0.0.0.0".to_string()),
+                http_port: loader.get_with_default(DISTRIBUTED, "http_port", 9100),
+                heartbeat_timeout_secs: loader.get_with_default(
+                    DISTRIBUTED,
+                    "heartbeat_timeout_secs",
+                    30,
+                ),
+                assign_batch_size: loader.get_with_default(DISTRIBUTED, "assign_batch_size", 1),
+            },
+            DistributedRole::Worker => DistributedConfig::Worker {
+                coordinator_url: loader.get_required(DISTRIBUTED, "coordinator_url"),
+                // must be unique among workers, a restarted worker may reuse its id
+                worker_id: loader.get_required(DISTRIBUTED, "worker_id"),
+                heartbeat_interval_secs: loader.get_with_default(
+                    DISTRIBUTED,
+                    HEARTBEAT_INTERVAL_SECS,
+                    5,
+                ),
+            },
+        };
+        Ok(Some(config))
+    }
+
+    fn load_subset_config(
+        loader: &IniLoader,
+        extractor_basic: &BasicExtractorConfig,
+        filter: &FilterConfig,
+    ) -> anyhow::Result<Option<SubsetConfig>> {
+        if !loader.ini.sections().contains(&SUBSET.to_string()) {
+            return Ok(None);
+        }
+
+        // the closure is resolved by foreign keys of the source before the snapshot
+        if !matches!(extractor_basic.extract_type, ExtractType::Snapshot)
+            || !matches!(extractor_basic.db_type, DbType::Mysql | DbType::Pg)
+        {
+            bail!(Error::ConfigError(format!(
+                "config [{}] only supports [extractor] extract_type=snapshot with db_type=mysql or pg",
+                SUBSET
+            )));
+        }
+        // conditions of all tables in the closure are decided by the seeds
+        if !filter.where_conditions.trim().is_empty() {
+            bail!(Error::ConfigError(format!(
+                "config [{}].where_conditions can not be used with [{}], set conditions of seeds instead",
+                FILTER, SUBSET
+            )));
+        }
+
+        // seeds=json:[{"db":"shop","tb":"orders","condition":"created_at >= '2024-06-01'"}]
+        let seeds: String = loader.get_required(SUBSET, "seeds");
+        let seeds: Vec<SubsetSeed> =
+            serde_json::from_str(seeds.trim().trim_start_matches(JSON_PREFIX)).map_err(|e| {
+                Error::ConfigError(format!(
+                    "config [{}].seeds is not valid json: {}",
+                    SUBSET, e
+                ))
+            })?;
+        if seeds.is_empty() {
+            bail!(Error::ConfigError(format!(
+                "config [{}].seeds can not be empty",
+                SUBSET
+            )));
+        }
+
+        Ok(Some(SubsetConfig {
+            seeds,
+            include_children: loader.get_optional(SUBSET, "include_children"),
+            max_depth: loader.get_with_default(SUBSET, "max_depth", 10),
         }))
     }
 
@@ -1413,6 +2609,22 @@ impl TaskConfig {
         }
     }
 
+    fn load_redis_module_config(loader: &IniLoader) -> RedisModuleConfig {
+        let module_types: String = loader.get_optional(EXTRACTOR, "module_types");
+        RedisModuleConfig {
+            module_types: module_types
+                .split(',')
+                .map(|i| i.trim().to_string())
+                .filter(|i| !i.is_empty())
+                .collect(),
+            unknown_module_policy: loader.get_with_default(
+                EXTRACTOR,
+                "unknown_module_policy",
+                UnknownModulePolicy::Fail,
+            ),
+        }
+    }
+
     #[cfg(feature = "metrics")]
     fn load_metrics_config(loader: &IniLoader) -> anyhow::Result<MetricsConfig> {
         let metrics_section = "metrics";
@@ -1442,12 +2654,14 @@ mod tests {
         sync::atomic::{AtomicU64, Ordering},
     };
 
-    use crate::config::parallelizer_config::{
-        ChunkPartitionerRebalanceCost, ChunkPartitionerRebalanceStrategy,
+    use crate::config::{
+        config_enums::{SqlHookErrorPolicy, SqlHookStage},
+        parallelizer_config::{ChunkPartitionerRebalanceCost, ChunkPartitionerRebalanceStrategy},
     };
 
     use super::{
-        CheckMode, ExtractorConfig, ParallelType, SinkerConfig, TaskConfig, TaskKind, TaskType,
+        CheckMode, ExtractorConfig, KafkaReplayUntil, LogFormat, ParallelType, ResumerConfig,
+        SinkerConfig, TaskConfig, TaskKind, TaskType,
     };
 
     static NEXT_CONFIG_ID: AtomicU64 = AtomicU64::new(0);
@@ -1895,6 +3109,155 @@ rebalance_max_partitions_per_sinker=0
         );
     }
 
+    #[test]
+    fn parallelizer_lag_priority_requires_merge_or_partition_parallel_types() {
+        let config_str = |parallel_type: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=cdc
+url=mysql://127.0.0.1:3306
+server_id=1
+
+[sinker]
+db_type=mysql
+sink_type=write
+url=mysql://127.0.0.1:3307
+
+[parallelizer]
+parallel_type={}
+parallel_size=4
+lag_priority=true
+"#,
+                parallel_type
+            )
+        };
+
+        for parallel_type in ["rdb_merge", "rdb_partition"] {
+            let config_path = write_temp_task_config(&config_str(parallel_type));
+            let config = TaskConfig::new(config_path.to_str().unwrap()).unwrap();
+            fs::remove_file(config_path).unwrap();
+            assert!(config.parallelizer.lag_priority());
+        }
+
+        let config_path = write_temp_task_config(&config_str("table"));
+        let err = TaskConfig::new(config_path.to_str().unwrap())
+            .err()
+            .unwrap()
+            .to_string();
+        fs::remove_file(config_path).unwrap();
+        assert_eq!(
+            err,
+            "config error: config [parallelizer].lag_priority is only supported by parallel_type=rdb_merge / rdb_partition / mongo, got: table"
+        );
+    }
+
+    #[test]
+    fn pipeline_transactional_requires_serial_rdb_cdc() {
+        let config_str = |sinker_db_type: &str, parallel_type: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=cdc
+url=mysql://127.0.0.1:3306
+server_id=1
+
+[sinker]
+db_type={}
+sink_type=write
+url=mysql://127.0.0.1:3307
+
+[parallelizer]
+parallel_type={}
+parallel_size=1
+
+[pipeline]
+transactional=true
+trx_max_held_mb=16
+"#,
+                sinker_db_type, parallel_type
+            )
+        };
+
+        let config_path = write_temp_task_config(&config_str("mysql", "serial"));
+        let config = TaskConfig::new(config_path.to_str().unwrap()).unwrap();
+        fs::remove_file(config_path).unwrap();
+        assert!(config.pipeline.transactional);
+        assert_eq!(config.pipeline.trx_max_held_mb, 16);
+        assert!(config.pipeline.trx_spill_dir.is_empty());
+
+        let config_path = write_temp_task_config(&config_str("mysql", "rdb_merge"));
+        let err = TaskConfig::new(config_path.to_str().unwrap())
+            .err()
+            .unwrap()
+            .to_string();
+        fs::remove_file(config_path).unwrap();
+        assert_eq!(
+            err,
+            "config error: config [pipeline].transactional=true requires [parallelizer] parallel_type=serial and parallel_size=1"
+        );
+
+        let config_path = write_temp_task_config(&config_str("kafka", "serial"));
+        let err = TaskConfig::new(config_path.to_str().unwrap())
+            .err()
+            .unwrap()
+            .to_string();
+        fs::remove_file(config_path).unwrap();
+        assert_eq!(
+            err,
+            "config error: config [pipeline].transactional=true requires [sinker] db_type=mysql / pg and sink_type=write without shards"
+        );
+    }
+
+    #[test]
+    fn file_sinker_s3_config() {
+        let config_str = |file_format: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=snapshot
+url=mysql://127.0.0.1:3306
+
+[sinker]
+db_type=mysql
+sink_type=file
+file_dir=/tmp/ape_dts_files
+file_format={}
+s3_bucket=bucket1
+s3_region=us-east-1
+s3_key_prefix=exports/task1
+"#,
+                file_format
+            )
+        };
+
+        let config_path = write_temp_task_config(&config_str("orc"));
+        let config = TaskConfig::new(config_path.to_str().unwrap()).unwrap();
+        fs::remove_file(config_path).unwrap();
+        let SinkerConfig::File {
+            s3_config: Some(s3_config),
+            s3_key_prefix,
+            ..
+        } = config.sinker
+        else {
+            panic!("unexpected sinker config: {:?}", config.sinker);
+        };
+        assert_eq!(s3_config.bucket, "bucket1");
+        assert_eq!(s3_config.region, "us-east-1");
+        assert_eq!(s3_key_prefix, "exports/task1");
+
+        let config_path = write_temp_task_config(&config_str("chunk"));
+        let err = TaskConfig::new(config_path.to_str().unwrap())
+            .err()
+            .unwrap()
+            .to_string();
+        fs::remove_file(config_path).unwrap();
+        assert_eq!(
+            err,
+            "config error: config [sinker].s3_bucket is not supported by file_format=chunk"
+        );
+    }
+
     #[test]
     fn sinker_batch_size_must_be_greater_than_zero() {
         let config_path = write_temp_task_config(
@@ -1921,4 +3284,267 @@ batch_size=0
             Ok(_) => panic!("expected config validation error"),
         }
     }
+
+    #[test]
+    fn table_overrides_inherit_and_validate() {
+        let build = |parallel_type: &str, overrides: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=cdc
+url=mysql://127.0.0.1:3306
+server_id=1
+
+[sinker]
+db_type=mysql
+sink_type=write
+url=mysql://127.0.0.1:3307
+batch_size=200
+
+[parallelizer]
+parallel_type={parallel_type}
+parallel_size=8
+
+[pipeline]
+table_overrides={overrides}
+"#
+            )
+        };
+
+        let config = load_temp_task_config(&build(
+            "rdb_merge",
+            r#"json:[{"db":"db_1","tb":"tb_1","batch_size":5000,"parallel_size":2},{"db":"db_1","tb":"tb_2","parallel_size":16}]"#,
+        ));
+        assert!(config.is_err(), "parallel_size above global should fail");
+
+        let config = load_temp_task_config(&build(
+            "rdb_merge",
+            r#"json:[{"db":"db_1","tb":"tb_1","batch_size":5000,"parallel_size":2},{"db":"db_1","tb":"tb_2","batch_size":10}]"#,
+        ))
+        .unwrap();
+        let overrides = &config.pipeline.table_overrides;
+        assert_eq!(overrides.batch_size("db_1", "tb_1", 200), 5000);
+        assert_eq!(overrides.parallel_size("db_1", "tb_1", 8), 2);
+        assert_eq!(overrides.parallel_size("db_1", "tb_2", 8), 8);
+        assert_eq!(overrides.batch_size("db_1", "tb_3", 200), 200);
+        assert_eq!(overrides.key_cols("db_1", "tb_1"), None);
+
+        let config = load_temp_task_config(&build(
+            "rdb_merge",
+            r#"json:[{"db":"db_1","tb":"tb_1","key_cols":["code","tenant_id"]}]"#,
+        ))
+        .unwrap();
+        let overrides = &config.pipeline.table_overrides;
+        assert_eq!(
+            overrides.key_cols("db_1", "tb_1"),
+            Some(&vec!["code".to_string(), "tenant_id".to_string()])
+        );
+        assert_eq!(overrides.parallel_size("db_1", "tb_1", 8), 8);
+
+        for (parallel_type, overrides) in [
+            (
+                "serial",
+                r#"json:[{"db":"db_1","tb":"tb_1","parallel_size":2}]"#,
+            ),
+            (
+                "rdb_merge",
+                r#"json:[{"db":"db_1","tb":"tb_1","batch_size":0}]"#,
+            ),
+            (
+                "rdb_merge",
+                r#"json:[{"db":"db_1","tb":"tb_1","key_cols":[]}]"#,
+            ),
+            (
+                "rdb_merge",
+                r#"json:[{"db":"db_1","tb":"tb_1","batch_size":1},{"db":"db_1","tb":"tb_1","batch_size":2}]"#,
+            ),
+        ] {
+            assert!(load_temp_task_config(&build(parallel_type, overrides)).is_err());
+        }
+    }
+
+    #[test]
+    fn sinker_sql_hooks() {
+        let build = |sql_hooks: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=snapshot
+url=mysql://127.0.0.1:3306
+
+[sinker]
+db_type=mysql
+sink_type=write
+url=mysql://127.0.0.1:3307
+sql_hooks={sql_hooks}
+"#
+            )
+        };
+
+        let config = load_temp_task_config(&build(
+            r#"json:[{"stage":"after_tb","tbs":"db_1.*","sql":"ANALYZE TABLE {schema}.{tb}","on_error":"warn"},{"stage":"before_task","sql":"SET GLOBAL sync_binlog=0"}]"#,
+        ))
+        .unwrap();
+        let SinkerConfig::Mysql { sql_hooks, .. } = &config.sinker else {
+            panic!("expected mysql sinker config");
+        };
+        assert_eq!(sql_hooks.len(), 2);
+        assert_eq!(sql_hooks[0].stage, SqlHookStage::AfterTb);
+        assert_eq!(sql_hooks[0].on_error, SqlHookErrorPolicy::Warn);
+        assert_eq!(sql_hooks[1].tbs, "*.*");
+        assert_eq!(sql_hooks[1].on_error, SqlHookErrorPolicy::Fail);
+
+        for sql_hooks in [
+            r#"json:[{"stage":"after_load","sql":"ANALYZE TABLE {schema}.{tb}"}]"#,
+            r#"json:[{"stage":"after_tb","sql":" "}]"#,
+            r#"json:[{"stage":"after_tb","sql":"SELECT 1","on_error":"ignore"}]"#,
+        ] {
+            assert!(load_temp_task_config(&build(sql_hooks)).is_err());
+        }
+    }
+
+    #[test]
+    fn kafka_replay_until() {
+        let build = |replay_until: &str| {
+            format!(
+                r#"[extractor]
+db_type=kafka
+extract_type=cdc
+url=127.0.0.1:9092
+group=ape_test
+topic=test
+replay_until={replay_until}
+
+[sinker]
+db_type=mysql
+sink_type=write
+url=mysql://127.0.0.1:3307
+"#
+            )
+        };
+
+        let replay_until = |config: &TaskConfig| match &config.extractor {
+            ExtractorConfig::Kafka { replay_until, .. } => replay_until.clone(),
+            _ => panic!("expected kafka extractor config"),
+        };
+        let config = load_temp_task_config(&build("offset:1000")).unwrap();
+        assert_eq!(replay_until(&config), Some(KafkaReplayUntil::Offset(1000)));
+        let config = load_temp_task_config(&build("2024-01-01 10:00:00")).unwrap();
+        assert_eq!(
+            replay_until(&config),
+            Some(KafkaReplayUntil::Time(1704103200000))
+        );
+        let config = load_temp_task_config(&build("")).unwrap();
+        assert_eq!(replay_until(&config), None);
+        assert!(load_temp_task_config(&build("offset:latest")).is_err());
+        assert!(load_temp_task_config(&build("yesterday")).is_err());
+
+        let until = KafkaReplayUntil::Time(1704103200000);
+        assert!(!until.is_passed(5, Some(1704103200000)));
+        assert!(until.is_passed(5, Some(1704103200001)));
+        assert!(!until.is_passed(5, None));
+        assert!(KafkaReplayUntil::Offset(5).is_passed(6, None));
+        assert!(!KafkaReplayUntil::Offset(5).is_passed(5, None));
+    }
+
+    #[test]
+    fn circuit_breaker_config() {
+        let build = |sink_type: &str, circuit_breaker: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=cdc
+url=mysql://127.0.0.1:3306
+server_id=1
+
+[sinker]
+db_type=mysql
+sink_type={sink_type}
+url=mysql://127.0.0.1:3307
+
+[parallelizer]
+parallel_type=rdb_merge
+
+{circuit_breaker}
+"#
+            )
+        };
+
+        let config = load_temp_task_config(&build("write", "")).unwrap();
+        assert!(config.circuit_breaker.is_none());
+
+        let config = load_temp_task_config(&build(
+            "write",
+            "[circuit_breaker]\ntrip_errors=1021, 1114,,disk full\nprobe_sql=SELECT 1",
+        ))
+        .unwrap();
+        let circuit_breaker = config.circuit_breaker.unwrap();
+        assert_eq!(
+            circuit_breaker.trip_errors,
+            vec!["1021", "1114", "disk full"]
+        );
+        assert_eq!(circuit_breaker.probe_interval_secs, 10);
+        assert_eq!(circuit_breaker.max_open_secs, 3600);
+
+        assert!(
+            load_temp_task_config(&build("dummy", "[circuit_breaker]\nprobe_sql=SELECT 1"))
+                .is_err()
+        );
+        assert!(
+            load_temp_task_config(&build("dummy", "[circuit_breaker]\ntrip_errors=1114")).is_err()
+        );
+        assert!(load_temp_task_config(&build(
+            "write",
+            "[circuit_breaker]\ntrip_errors=1114\nprobe_interval_secs=0"
+        ))
+        .is_err());
+        // neither trip_errors nor probe_sql
+        assert!(load_temp_task_config(&build(
+            "write",
+            "[circuit_breaker]\ntrip_errors=,\nmax_open_secs=0"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn runtime_log_dir_per_task() {
+        let build = |runtime: &str| {
+            format!(
+                r#"[extractor]
+db_type=mysql
+extract_type=cdc
+url=mysql://127.0.0.1:3306
+server_id=1
+
+[sinker]
+db_type=mysql
+sink_type=write
+url=mysql://127.0.0.1:3307
+
+[global]
+task_id=task_1
+
+[resumer]
+resume_type=from_log
+
+[runtime]
+log_dir=/tmp/logs
+{runtime}
+"#
+            )
+        };
+
+        let config = load_temp_task_config(&build("")).unwrap();
+        assert_eq!(config.runtime.log_dir, "/tmp/logs");
+        assert_eq!(config.runtime.log_format, LogFormat::Text);
+
+        let config =
+            load_temp_task_config(&build("log_dir_per_task=true\nlog_format=json")).unwrap();
+        assert_eq!(config.runtime.log_dir, "/tmp/logs/task_1");
+        assert_eq!(config.runtime.log_format, LogFormat::Json);
+        assert!(matches!(
+            config.resumer,
+            ResumerConfig::FromLog { log_dir, .. } if log_dir == "/tmp/logs/task_1"
+        ));
+    }
 }
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use super::ini_loader::IniLoader;
+
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 15;
+
+/// Timeouts of the connections of a stage, e.g. [extractor] / [sinker],
+/// meta fetchers share the timeouts of the stage they query.
+#[derive(Clone, Debug, Hash)]
+pub struct TimeoutConfig {
+    // max seconds to establish or acquire a connection
+    pub connect_timeout_secs: u64,
+    // max seconds of a statement, 0 for no limit
+    pub statement_timeout_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            statement_timeout_secs: 0,
+        }
+    }
+}
+
+impl TimeoutConfig {
+    pub fn from(loader: &IniLoader, section: &str) -> Self {
+        Self {
+            connect_timeout_secs: loader.get_with_default(
+                section,
+                "connect_timeout_secs",
+                DEFAULT_CONNECT_TIMEOUT_SECS,
+            ),
+            statement_timeout_secs: loader.get_optional(section, "statement_timeout_secs"),
+        }
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        (self.statement_timeout_secs > 0).then(|| Duration::from_secs(self.statement_timeout_secs))
+    }
+}
@@ -23,6 +23,14 @@ pub enum Error {
     #[error("sqlx error: {0}")]
     SqlxError(#[from] sqlx::Error),
 
+    #[error("query timeout in {stage}, schema: {schema}, tb: {tb}, sql: {sql}")]
+    QueryTimeout {
+        stage: String,
+        schema: String,
+        tb: String,
+        sql: String,
+    },
+
     #[error("unexpected error: {0}")]
     Unexpected(String),
 
@@ -1,6 +1,7 @@
 pub mod config;
 pub mod error;
 pub mod limiter;
+pub mod log_encoder;
 pub mod log_filter;
 pub mod logger;
 pub mod meta;
@@ -141,6 +141,7 @@ mod tests {
             CapacityLimiterConfig {
                 buffer_size,
                 buffer_memory_mb,
+                ..Default::default()
             },
         )
     }
@@ -149,9 +150,10 @@ mod tests {
     /// Do NOT combine with an active `max_bps` limiter (would panic on `acquire(0)`).
     fn record_item() -> DtItem {
         DtItem {
-            dt_data: DtData::Begin {},
+            dt_data: DtData::Begin { xid: String::new() },
             position: Position::None,
             data_origin_node: "test".to_string(),
+            trace: None,
         }
     }
 
@@ -172,10 +174,14 @@ mod tests {
                     )])),
                     data_size: data_size,
                     is_not_origin: false,
+                    position: None,
+                    query: None,
+                    meta_version: 0,
                 },
             },
             position: Position::None,
             data_origin_node: "test".to_string(),
+            trace: None,
         }
     }
 
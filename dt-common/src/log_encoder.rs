@@ -0,0 +1,104 @@
+use log::Record;
+use log4rs::{
+    config::{Deserialize, Deserializers},
+    encode::{Encode, Write},
+};
+use serde::Deserialize as SerdeDeserialize;
+use serde_json::{json, Value};
+
+// same as the time of text logs, e.g. 2024-02-29 01:25:09.554271
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+/// Encodes each record as a json line, e.g.
+/// {"time":"2024-02-29 01:25:09.554271","level":"INFO","logger":"default_logger","task_id":"task_1","message":"..."},
+/// so logs can be shipped to ELK / Loki and correlated by task_id.
+#[derive(Debug)]
+pub struct JsonLineEncoder {
+    task_id: String,
+}
+
+impl JsonLineEncoder {
+    pub fn new(task_id: String) -> Self {
+        Self { task_id }
+    }
+
+    /// Converts a json line back to the text layout: "{time} | {message}", which is what
+    /// readers of position.log / finished.log / monitor.log expect, other lines are kept.
+    pub fn to_text_line(line: &str) -> String {
+        if !line.starts_with('{') {
+            return line.to_string();
+        }
+        let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(line) else {
+            return line.to_string();
+        };
+        match (fields.get("time"), fields.get("message")) {
+            (Some(Value::String(time)), Some(Value::String(message))) => {
+                format!("{} | {}", time, message)
+            }
+            _ => line.to_string(),
+        }
+    }
+}
+
+impl Encode for JsonLineEncoder {
+    fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+        let line = json!({
+            "time": chrono::Utc::now().format(TIME_FORMAT).to_string(),
+            "level": record.level().as_str(),
+            "logger": record.target(),
+            "task_id": self.task_id,
+            "message": record.args().to_string(),
+        });
+        serde_json::to_writer(&mut *w, &line)?;
+        w.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, SerdeDeserialize)]
+pub struct JsonLineEncoderConfig {
+    #[serde(default)]
+    pub task_id: String,
+}
+
+/// Registered as encoder kind: json_line
+#[derive(Clone, Debug, Default)]
+pub struct JsonLineEncoderDeserializer;
+
+impl Deserialize for JsonLineEncoderDeserializer {
+    type Trait = dyn Encode;
+    type Config = JsonLineEncoderConfig;
+
+    fn deserialize(
+        &self,
+        config: JsonLineEncoderConfig,
+        _deserializers: &Deserializers,
+    ) -> anyhow::Result<Box<dyn Encode>> {
+        Ok(Box::new(JsonLineEncoder::new(config.task_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_text_line() {
+        let position = r#"current_position | {"type":"None"}"#;
+        let line = json!({
+            "time": "2024-02-29 01:25:09.554271",
+            "level": "INFO",
+            "logger": "position_logger",
+            "task_id": "task_1",
+            "message": position,
+        })
+        .to_string();
+        assert_eq!(
+            JsonLineEncoder::to_text_line(&line),
+            format!("2024-02-29 01:25:09.554271 | {}", position)
+        );
+
+        let text = format!("2024-02-29 01:25:09.554271 | {}", position);
+        assert_eq!(JsonLineEncoder::to_text_line(&text), text);
+    }
+}
@@ -33,6 +33,11 @@ macro_rules! log_sql {
     ($($arg:tt)+) => (log::log!(target: "sql_logger", log::Level::Info, $($arg)+));
 }
 
+#[macro_export(local_inner_macros)]
+macro_rules! log_report {
+    ($($arg:tt)+) => (log::log!(target: "report_logger", log::Level::Info, $($arg)+));
+}
+
 #[macro_export(local_inner_macros)]
 macro_rules! log_summary {
     ($($arg:tt)+) => (log::log!(target: "summary_logger", log::Level::Info, $($arg)+));
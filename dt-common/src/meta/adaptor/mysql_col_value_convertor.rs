@@ -275,6 +275,39 @@ impl MysqlColValueConvertor {
         Ok(col_value)
     }
 
+    /// Converts enum / set labels back to the numeric form used in binlog:
+    /// enum to the index of the label starting from 1, set to the bits of its labels.
+    /// Values not matching the column definition are kept as labels.
+    pub fn to_enum_set_index(col_type: &MysqlColType, value: ColValue) -> ColValue {
+        match (col_type, value) {
+            (MysqlColType::Enum { items }, ColValue::Enum2(v)) => {
+                match items.iter().position(|item| *item == v) {
+                    Some(i) => ColValue::Enum(i as u32 + 1),
+                    None => ColValue::Enum2(v),
+                }
+            }
+
+            (MysqlColType::Set { items }, ColValue::Set2(v)) => {
+                let bits = v
+                    .split(',')
+                    .filter(|label| !label.is_empty())
+                    .map(|label| {
+                        items
+                            .iter()
+                            .find(|(_, item)| item.as_str() == label)
+                            .map(|(bit, _)| *bit)
+                    })
+                    .try_fold(0, |bits, bit| bit.map(|bit| bits | bit));
+                match bits {
+                    Some(bits) => ColValue::Set(bits),
+                    None => ColValue::Set2(v),
+                }
+            }
+
+            (_, value) => value,
+        }
+    }
+
     pub fn from_str(col_type: &MysqlColType, value_str: &str) -> anyhow::Result<ColValue> {
         let value_str = value_str.to_string();
         let col_value =
@@ -572,6 +605,8 @@ impl MysqlColValueConvertor {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     fn point_wkb() -> Vec<u8> {
@@ -581,6 +616,41 @@ mod tests {
         value
     }
 
+    #[test]
+    fn enum_set_label_and_index_round_trip() {
+        let set_type = MysqlColType::Set {
+            items: HashMap::from([(1, "a".into()), (2, "b".into()), (4, "c".into())]),
+        };
+        let value = MysqlColValueConvertor::from_binlog(&set_type, ColumnValue::Set(5)).unwrap();
+        assert_eq!(value, ColValue::Set2("a,c".into()));
+        assert_eq!(
+            MysqlColValueConvertor::to_enum_set_index(&set_type, value),
+            ColValue::Set(5)
+        );
+        assert_eq!(
+            MysqlColValueConvertor::to_enum_set_index(&set_type, ColValue::Set2(String::new())),
+            ColValue::Set(0)
+        );
+        assert_eq!(
+            MysqlColValueConvertor::to_enum_set_index(&set_type, ColValue::Set2("a,d".into())),
+            ColValue::Set2("a,d".into())
+        );
+
+        let enum_type = MysqlColType::Enum {
+            items: vec!["x".into(), "y".into()],
+        };
+        let value = MysqlColValueConvertor::from_binlog(&enum_type, ColumnValue::Enum(2)).unwrap();
+        assert_eq!(value, ColValue::Enum2("y".into()));
+        assert_eq!(
+            MysqlColValueConvertor::to_enum_set_index(&enum_type, value),
+            ColValue::Enum(2)
+        );
+        assert_eq!(
+            MysqlColValueConvertor::to_enum_set_index(&enum_type, ColValue::None),
+            ColValue::None
+        );
+    }
+
     #[test]
     fn from_binlog_strips_mysql_spatial_srid_prefix() {
         let wkb = point_wkb();
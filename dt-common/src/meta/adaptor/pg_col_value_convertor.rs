@@ -211,4 +211,117 @@ impl PgColValueConvertor {
         };
         Ok(col_value)
     }
+
+    /// Converts pg array literals to json arrays, e.g. {1,NULL,"a b"} -> [1,null,"a b"],
+    /// {{1,2},{3,4}} -> [[1,2],[3,4]], used for targets without arrays, e.g. mysql json columns.
+    /// Unquoted numeric elements become json numbers, others become strings.
+    pub fn array_to_json(value: &str) -> Option<String> {
+        // [1:2]={1,2}, arrays with explicit bounds
+        let value = match value.split_once('=') {
+            Some((bounds, rest)) if bounds.starts_with('[') => rest,
+            _ => value,
+        };
+        let chars: Vec<char> = value.trim().chars().collect();
+        let mut pos = 0;
+        let array = Self::parse_array(&chars, &mut pos)?;
+        if pos != chars.len() {
+            return None;
+        }
+        Some(array.to_string())
+    }
+
+    fn parse_array(chars: &[char], pos: &mut usize) -> Option<serde_json::Value> {
+        if chars.get(*pos) != Some(&'{') {
+            return None;
+        }
+        *pos += 1;
+
+        let mut items = Vec::new();
+        loop {
+            while chars.get(*pos)?.is_whitespace() {
+                *pos += 1;
+            }
+            match chars.get(*pos)? {
+                '}' if items.is_empty() => {
+                    *pos += 1;
+                    return Some(serde_json::Value::Array(items));
+                }
+                '{' => items.push(Self::parse_array(chars, pos)?),
+                '"' => {
+                    *pos += 1;
+                    let mut item = String::new();
+                    loop {
+                        match chars.get(*pos)? {
+                            '\\' => {
+                                *pos += 1;
+                                item.push(*chars.get(*pos)?);
+                            }
+                            '"' => break,
+                            c => item.push(*c),
+                        }
+                        *pos += 1;
+                    }
+                    *pos += 1;
+                    items.push(serde_json::Value::String(item));
+                }
+                _ => {
+                    let start = *pos;
+                    while !matches!(chars.get(*pos)?, ',' | '}') {
+                        *pos += 1;
+                    }
+                    let item: String = chars[start..*pos].iter().collect();
+                    let item = item.trim();
+                    items.push(if item.eq_ignore_ascii_case("NULL") {
+                        serde_json::Value::Null
+                    } else if let Ok(number) = item.parse::<serde_json::Number>() {
+                        serde_json::Value::Number(number)
+                    } else {
+                        serde_json::Value::String(item.to_string())
+                    });
+                }
+            }
+
+            while chars.get(*pos)?.is_whitespace() {
+                *pos += 1;
+            }
+            match chars.get(*pos)? {
+                ',' => *pos += 1,
+                '}' => {
+                    *pos += 1;
+                    return Some(serde_json::Value::Array(items));
+                }
+                _ => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_array_to_json() {
+        let cases = [
+            ("{}", Some("[]")),
+            ("{1,2,NULL}", Some("[1,2,null]")),
+            ("{1.5,-2}", Some("[1.5,-2]")),
+            (
+                r#"{abc,"a b","c\"d","NULL"}"#,
+                Some(r#"["abc","a b","c\"d","NULL"]"#),
+            ),
+            ("{{1,2},{3,4}}", Some("[[1,2],[3,4]]")),
+            ("[0:1]={t,f}", Some(r#"["t","f"]"#)),
+            ("{1,2", None),
+            (r#"{"a":1}"#, None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                PgColValueConvertor::array_to_json(value).as_deref(),
+                expected,
+                "{}",
+                value
+            );
+        }
+    }
 }
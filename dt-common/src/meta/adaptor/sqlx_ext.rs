@@ -6,9 +6,11 @@ use sqlx::{
 };
 
 use crate::meta::{
+    adaptor::pg_col_value_convertor::PgColValueConvertor,
     col_value::ColValue,
     mysql::mysql_col_type::MysqlColType,
     pg::{pg_col_type::PgColType, pg_value_type::PgValueType},
+    time::dt_temporal::DtTemporal,
 };
 
 pub trait SqlxPgExt<'q> {
@@ -31,6 +33,12 @@ impl<'q> SqlxPgExt<'q> for Query<'q, Postgres, PgArguments> {
                     if col_type.alias == "oid" {
                         return self.bind(Oid(as_u32(value)));
                     }
+                    // bind as text to make pg report out of range instead of wrapping around
+                    if let ColValue::UnsignedLongLong(v) = value {
+                        if i64::try_from(*v).is_err() {
+                            return self.bind(v.to_string());
+                        }
+                    }
                     return self.bind(as_i64(value));
                 }
                 PgValueType::Float32 => return self.bind(as_f32(value)),
@@ -43,6 +51,12 @@ impl<'q> SqlxPgExt<'q> for Query<'q, Postgres, PgArguments> {
                 ColValue::Short(v) => self.bind(v),
                 ColValue::Long(v) => self.bind(v),
                 ColValue::LongLong(v) => self.bind(v),
+                // pg has no unsigned types, e.g. bigint unsigned is mapped to numeric(20),
+                // bind as text which the placeholder casts to the column type
+                ColValue::UnsignedTiny(v) => self.bind(v.to_string()),
+                ColValue::UnsignedShort(v) => self.bind(v.to_string()),
+                ColValue::UnsignedLong(v) => self.bind(v.to_string()),
+                ColValue::UnsignedLongLong(v) => self.bind(v.to_string()),
                 ColValue::Float(v) => self.bind(v),
                 ColValue::Double(v) => self.bind(v),
                 ColValue::Decimal(v) => self.bind(v),
@@ -210,10 +224,23 @@ impl<'q> SqlxMysqlExt<'q> for Query<'q, MySql, MySqlArguments> {
     fn bind_col_value<'b: 'q>(
         self,
         col_value: Option<&'b ColValue>,
-        _col_type: &MysqlColType,
+        col_type: &MysqlColType,
     ) -> Self {
         if let Some(value) = col_value {
             match value {
+                // values of pg sources
+                ColValue::Bool(v) => self.bind(v),
+                ColValue::String(v) if *col_type == MysqlColType::Json => {
+                    // pg arrays are mapped to mysql json columns
+                    match serde_json::from_str::<serde_json::Value>(v) {
+                        Ok(_) => self.bind(v),
+                        Err(_) => match PgColValueConvertor::array_to_json(v) {
+                            Some(json) => self.bind(json),
+                            None => self.bind(v),
+                        },
+                    }
+                }
+                ColValue::Json3(v) => self.bind(v.to_string()),
                 ColValue::Tiny(v) => self.bind(v),
                 ColValue::UnsignedTiny(v) => self.bind(v),
                 ColValue::Short(v) => self.bind(v),
@@ -225,10 +252,15 @@ impl<'q> SqlxMysqlExt<'q> for Query<'q, MySql, MySqlArguments> {
                 ColValue::Float(v) => self.bind(v),
                 ColValue::Double(v) => self.bind(v),
                 ColValue::Decimal(v) => self.bind(v),
-                ColValue::Time(v) => self.bind(v),
+                ColValue::Time(v) | ColValue::DateTime(v) | ColValue::Timestamp(v) => {
+                    // mysql rejects utc offsets of pg values, e.g. 2024-01-01 10:00:00.123456+08,
+                    // convert them to utc, the session time zone of sqlx
+                    match DtTemporal::parse(v).filter(|t| t.offset_secs.is_some()) {
+                        Some(temporal) => self.bind(temporal.to_naive_string(None)),
+                        None => self.bind(v),
+                    }
+                }
                 ColValue::Date(v) => self.bind(v),
-                ColValue::DateTime(v) => self.bind(v),
-                ColValue::Timestamp(v) => self.bind(v),
                 ColValue::Year(v) => self.bind(v),
                 ColValue::String(v) => self.bind(v),
                 ColValue::RawString(v) => self.bind(v),
@@ -1,6 +1,7 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use apache_avro::{from_avro_datum, to_avro_datum, types::Value, Schema};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 
 use crate::{
     config::config_enums::DbType,
@@ -8,6 +9,8 @@ use crate::{
         col_value::ColValue,
         ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
         dt_data::DtData,
+        position::Position,
+        provenance::Provenance,
         rdb_meta_manager::RdbMetaManager,
         rdb_tb_meta::RdbTbMeta,
         row_data::RowData,
@@ -20,7 +23,12 @@ use super::avro_converter_schema::{AvroConverterSchema, AvroFieldDef};
 #[derive(Clone)]
 pub struct AvroConverter {
     schema: Schema,
+    legacy_schema: Schema,
     pub with_field_defs: bool,
+    // attach source metadata of rows, requires positions attached to rows by the pipeline
+    pub with_provenance: bool,
+    // send decimals / dates / timestamps as avro logical types, requires meta of the table
+    pub with_logical_types: bool,
     pub meta_manager: Option<RdbMetaManager>,
 }
 
@@ -29,19 +37,39 @@ const AFTER: &str = "after";
 const EXTRA: &str = "extra";
 const OPERATION: &str = "operation";
 const DDL: &str = "ddl";
+const SNAPSHOT_FINISHED: &str = "snapshot_finished";
+const ROW_COUNT: &str = "row_count";
+const CDC_POSITION: &str = "cdc_position";
 const DB_TYPE: &str = "db_type";
 const DDL_TYPE: &str = "ddl_type";
 const QUERY: &str = "query";
 const SCHEMA: &str = "schema";
 const TB: &str = "tb";
 const FIELDS: &str = "fields";
+const PROVENANCE: &str = "provenance";
+const UNSCALED: &str = "unscaled";
+const PRECISION: &str = "precision";
+const SCALE: &str = "scale";
+const VALUE: &str = "value";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+const DATETIME_TZ_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%#z";
 
 impl AvroConverter {
-    pub fn new(meta_manager: Option<RdbMetaManager>, with_field_defs: bool) -> Self {
+    pub fn new(
+        meta_manager: Option<RdbMetaManager>,
+        with_field_defs: bool,
+        with_provenance: bool,
+        with_logical_types: bool,
+    ) -> Self {
         AvroConverter {
             schema: AvroConverterSchema::get_avro_schema(),
+            legacy_schema: AvroConverterSchema::get_legacy_avro_schema(),
             meta_manager,
             with_field_defs,
+            with_provenance,
+            with_logical_types,
         }
     }
 
@@ -74,6 +102,15 @@ impl AvroConverter {
         Ok(String::new())
     }
 
+    /// key columns of the row's table, empty if no meta manager
+    pub async fn get_id_cols(&mut self, row_data: &RowData) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .get_tb_meta(row_data)
+            .await?
+            .map(|tb_meta| tb_meta.id_cols.clone())
+            .unwrap_or_default())
+    }
+
     pub async fn row_data_to_avro_value(&mut self, row_data: &RowData) -> anyhow::Result<Vec<u8>> {
         let mut cols = vec![];
         let mut merge_cols = |col_values: &Option<HashMap<String, ColValue>>| {
@@ -89,8 +126,18 @@ impl AvroConverter {
         merge_cols(&row_data.after);
         cols.sort();
 
+        // without meta of the table, values are sent as strings like before
+        let decimal_types = if self.with_logical_types {
+            self.get_tb_meta(row_data)
+                .await?
+                .map(Self::get_decimal_types)
+        } else {
+            None
+        };
+
         // before
-        let (before_avro_values, before_avro_types) = Self::col_values_to_avro(&row_data.before);
+        let (before_avro_values, before_avro_types) =
+            Self::col_values_to_avro(&row_data.before, decimal_types.as_ref());
         let before = if let Value::Map(_) = &before_avro_values {
             Value::Union(1, Box::new(before_avro_values))
         } else {
@@ -98,7 +145,8 @@ impl AvroConverter {
         };
 
         // after
-        let (after_avro_values, after_avro_types) = Self::col_values_to_avro(&row_data.after);
+        let (after_avro_values, after_avro_types) =
+            Self::col_values_to_avro(&row_data.after, decimal_types.as_ref());
         let after = if let Value::Map(_) = &after_avro_values {
             Value::Union(1, Box::new(after_avro_values))
         } else {
@@ -138,6 +186,23 @@ impl AvroConverter {
             Value::Union(1, Box::new(apache_avro::to_value(fields).unwrap()))
         };
 
+        // original sql of the row, captured from mysql ROWS_QUERY events
+        let extra = if let Some(query) = &row_data.query {
+            let col_values =
+                HashMap::from([(QUERY.to_string(), ColValue::String(query.to_string()))]);
+            let (avro_values, _) = Self::col_values_to_avro(&Some(col_values), None);
+            Value::Union(1, Box::new(avro_values))
+        } else {
+            Value::Union(0, Box::new(Value::Null))
+        };
+
+        let provenance = match row_data.position.as_deref() {
+            Some(position) if self.with_provenance => Provenance::from_position(position)
+                .map(|provenance| Self::provenance_to_avro(&provenance))
+                .unwrap_or(Value::Union(0, Box::new(Value::Null))),
+            _ => Value::Union(0, Box::new(Value::Null)),
+        };
+
         let value = Value::Record(vec![
             (SCHEMA.into(), Value::String(row_data.schema.clone())),
             (TB.into(), Value::String(row_data.tb.clone())),
@@ -148,7 +213,8 @@ impl AvroConverter {
             (FIELDS.into(), fields),
             (BEFORE.into(), before),
             (AFTER.into(), after),
-            (EXTRA.into(), Value::Union(0, Box::new(Value::Null))),
+            (EXTRA.into(), extra),
+            (PROVENANCE.into(), provenance),
         ]);
         Ok(to_avro_datum(&self.schema, value)?)
     }
@@ -165,7 +231,7 @@ impl AvroConverter {
         );
         col_values.insert(QUERY.into(), ColValue::String(ddl_data.query));
 
-        let (avro_values, _) = Self::col_values_to_avro(&Some(col_values));
+        let (avro_values, _) = Self::col_values_to_avro(&Some(col_values), None);
         let extra = Value::Union(1, Box::new(avro_values));
 
         let value = Value::Record(vec![
@@ -176,14 +242,59 @@ impl AvroConverter {
             (BEFORE.into(), Value::Union(0, Box::new(Value::Null))),
             (AFTER.into(), Value::Union(0, Box::new(Value::Null))),
             (EXTRA.into(), extra),
+            (PROVENANCE.into(), Value::Union(0, Box::new(Value::Null))),
+        ]);
+        Ok(to_avro_datum(&self.schema, value)?)
+    }
+
+    /// end-of-snapshot watermark of a table: rows extracted and the cdc position
+    /// from which streaming for the table begins
+    pub fn snapshot_watermark_to_avro_value(
+        &self,
+        schema: &str,
+        tb: &str,
+        row_count: u64,
+        cdc_position: &Position,
+    ) -> anyhow::Result<Vec<u8>> {
+        let col_values = HashMap::from([
+            (ROW_COUNT.to_string(), ColValue::UnsignedLongLong(row_count)),
+            (
+                CDC_POSITION.to_string(),
+                ColValue::String(cdc_position.to_string()),
+            ),
+        ]);
+        let (avro_values, _) = Self::col_values_to_avro(&Some(col_values), None);
+
+        let value = Value::Record(vec![
+            (SCHEMA.into(), Value::String(schema.into())),
+            (TB.into(), Value::String(tb.into())),
+            (OPERATION.into(), Value::String(SNAPSHOT_FINISHED.into())),
+            (FIELDS.into(), Value::Union(0, Box::new(Value::Null))),
+            (BEFORE.into(), Value::Union(0, Box::new(Value::Null))),
+            (AFTER.into(), Value::Union(0, Box::new(Value::Null))),
+            (EXTRA.into(), Value::Union(1, Box::new(avro_values))),
+            (PROVENANCE.into(), Value::Union(0, Box::new(Value::Null))),
         ]);
         Ok(to_avro_datum(&self.schema, value)?)
     }
 
     pub fn avro_value_to_dt_data(&self, payload: Vec<u8>) -> anyhow::Result<DtData> {
-        let mut reader = payload.as_slice();
-        let value = from_avro_datum(&self.schema, &mut reader, None)?;
+        self.avro_value_to_dt_data_with_provenance(payload)
+            .map(|(dt_data, _)| dt_data)
+    }
+
+    /// Also returns the provenance of the message, None if it was sent without provenance.
+    pub fn avro_value_to_dt_data_with_provenance(
+        &self,
+        payload: Vec<u8>,
+    ) -> anyhow::Result<(DtData, Option<HashMap<String, ColValue>>)> {
+        // messages sent before provenance was added end before the provenance field
+        let value = match from_avro_datum(&self.schema, &mut payload.as_slice(), None) {
+            Ok(value) => value,
+            Err(_) => from_avro_datum(&self.legacy_schema, &mut payload.as_slice(), None)?,
+        };
         let mut avro_map = Self::avro_to_map(value);
+        let provenance = self.avro_to_col_values(avro_map.remove(PROVENANCE));
 
         let avro_to_string = |value: Option<Value>| {
             if let Some(Value::String(v)) = value {
@@ -196,20 +307,26 @@ impl AvroConverter {
         let tb = avro_to_string(avro_map.remove(TB));
         let operation = avro_to_string(avro_map.remove(OPERATION));
 
-        if operation == *DDL {
-            let get_extra_string = |extra: &Option<HashMap<String, ColValue>>, key: &str| {
-                if let Some(extra) = extra {
-                    if let Some(v) = extra.get(key) {
-                        return v.to_string();
-                    }
+        if operation == SNAPSHOT_FINISHED {
+            // control event, nothing to apply
+            return Ok((DtData::Heartbeat {}, provenance));
+        }
+
+        let get_extra_string = |extra: &Option<HashMap<String, ColValue>>, key: &str| {
+            if let Some(extra) = extra {
+                if let Some(v) = extra.get(key) {
+                    return v.to_string();
                 }
-                String::new()
-            };
-            let extra = self.avro_to_col_values(avro_map.remove(EXTRA));
+            }
+            String::new()
+        };
+        let extra = self.avro_to_col_values(avro_map.remove(EXTRA));
+
+        if operation == *DDL {
             let db_type = get_extra_string(&extra, DB_TYPE);
             let ddl_type = get_extra_string(&extra, DDL_TYPE);
             let query = get_extra_string(&extra, QUERY);
-            Ok(DtData::Ddl {
+            let dt_data = DtData::Ddl {
                 ddl_data: DdlData {
                     default_schema: schema,
                     query,
@@ -217,22 +334,39 @@ impl AvroConverter {
                     ddl_type: DdlType::from_str(&ddl_type)?,
                     ..Default::default()
                 },
-            })
+            };
+            Ok((dt_data, provenance))
         } else {
-            let _fields = self.avro_to_fields(avro_map.remove(FIELDS));
-            let before = self.avro_to_col_values(avro_map.remove(BEFORE));
-            let after = self.avro_to_col_values(avro_map.remove(AFTER));
-            Ok(DtData::Dml {
-                row_data: RowData::new(
-                    schema,
-                    tb,
-                    0,
-                    RowType::from_str(&operation)?,
-                    before,
-                    after,
-                ),
-            })
+            let fields = self.avro_to_fields(avro_map.remove(FIELDS));
+            let mut before = self.avro_to_col_values(avro_map.remove(BEFORE));
+            let mut after = self.avro_to_col_values(avro_map.remove(AFTER));
+            Self::restore_unsigned_long_longs(&fields, &mut before);
+            Self::restore_unsigned_long_longs(&fields, &mut after);
+            let mut row_data =
+                RowData::new(schema, tb, 0, RowType::from_str(&operation)?, before, after);
+            let query = get_extra_string(&extra, QUERY);
+            if !query.is_empty() {
+                row_data.query = Some(Arc::new(query));
+            }
+            Ok((DtData::Dml { row_data }, provenance))
+        }
+    }
+
+    fn provenance_to_avro(provenance: &Provenance) -> Value {
+        let mut avro_values = HashMap::new();
+        if let Ok(serde_json::Value::Object(map)) = serde_json::to_value(provenance) {
+            for (key, value) in map {
+                let avro_value = match value {
+                    serde_json::Value::Number(v) => {
+                        Value::Union(2, Box::new(Value::Long(v.as_i64().unwrap_or_default())))
+                    }
+                    serde_json::Value::String(v) => Value::Union(1, Box::new(Value::String(v))),
+                    _ => Value::Union(0, Box::new(Value::Null)),
+                };
+                avro_values.insert(key, avro_value);
+            }
         }
+        Value::Union(1, Box::new(Value::Map(avro_values)))
     }
 
     fn avro_to_fields(&self, value: Option<Value>) -> Vec<AvroFieldDef> {
@@ -266,8 +400,37 @@ impl AvroConverter {
         None
     }
 
+    // unsigned bigint values are decoded as Long or String, restore them by field defs
+    fn restore_unsigned_long_longs(
+        fields: &[AvroFieldDef],
+        col_values: &mut Option<HashMap<String, ColValue>>,
+    ) {
+        let Some(col_values) = col_values else {
+            return;
+        };
+        for field in fields.iter() {
+            let column_type = field.column_type.to_lowercase();
+            if !column_type.starts_with("bigint") || !column_type.contains("unsigned") {
+                continue;
+            }
+            let Some(col_value) = col_values.get_mut(&field.name) else {
+                continue;
+            };
+            let restored = match col_value {
+                ColValue::LongLong(v) => u64::try_from(*v).ok(),
+                ColValue::String(v) => v.parse::<u64>().ok(),
+                _ => None,
+            };
+            if let Some(v) = restored {
+                *col_value = ColValue::UnsignedLongLong(v);
+            }
+        }
+    }
+
+    // decimal_types: (precision, scale) of decimal columns, logical types are used only if set
     fn col_values_to_avro(
         col_values: &Option<HashMap<String, ColValue>>,
+        decimal_types: Option<&HashMap<String, (u32, u32)>>,
     ) -> (Value, HashMap<String, String>) {
         let mut avro_types = HashMap::new();
         if col_values.is_none() {
@@ -276,7 +439,11 @@ impl AvroConverter {
 
         let mut avro_values = HashMap::new();
         for (col, value) in col_values.as_ref().unwrap() {
-            let avro_value = Self::col_value_to_avro(value);
+            let avro_value = decimal_types
+                .and_then(|decimal_types| {
+                    Self::col_value_to_logical_avro(value, decimal_types.get(col))
+                })
+                .unwrap_or_else(|| Self::col_value_to_avro(value));
             let (union_position, avro_type) = match avro_value {
                 Value::Null => (0, "Null".to_string()),
                 Value::String(_) => (1, "String".to_string()),
@@ -284,6 +451,12 @@ impl AvroConverter {
                 Value::Double(_) => (3, "Double".to_string()),
                 Value::Bytes(_) => (4, "Bytes".to_string()),
                 Value::Boolean(_) => (5, "Boolean".to_string()),
+                Value::Record(ref fields) => match fields.first() {
+                    Some((_, Value::Bytes(_))) => (6, "Decimal".to_string()),
+                    Some((_, Value::Date(_))) => (7, "Date".to_string()),
+                    Some((_, Value::TimestampMillis(_))) => (8, "Timestamp".to_string()),
+                    _ => (0, String::new()),
+                },
                 // Not supported
                 _ => (0, String::new()),
             };
@@ -310,8 +483,11 @@ impl AvroConverter {
             ColValue::Bit(v) => Value::Long(*v as i64),
             ColValue::Set(v) => Value::Long(*v as i64),
             ColValue::Enum(v) => Value::Long(*v as i64),
-            // may lose precision
-            ColValue::UnsignedLongLong(v) => Value::Long(*v as i64),
+            // values beyond i64 are written as decimal strings to keep them exact
+            ColValue::UnsignedLongLong(v) => match i64::try_from(*v) {
+                Ok(v) => Value::Long(v),
+                Err(_) => Value::String(v.to_string()),
+            },
 
             ColValue::Float(v) => Value::Double(*v as f64),
             ColValue::Double(v) => Value::Double(*v),
@@ -340,6 +516,162 @@ impl AvroConverter {
         }
     }
 
+    /// Decimal -> AvroDecimal, Date -> AvroDate, DateTime / Timestamp -> AvroTimestamp,
+    /// None if the value can not be represented, e.g. 0000-00-00 or decimals beyond i128
+    fn col_value_to_logical_avro(
+        value: &ColValue,
+        decimal_type: Option<&(u32, u32)>,
+    ) -> Option<Value> {
+        match value {
+            ColValue::Decimal(v) => {
+                let (precision, scale) = decimal_type?;
+                let unscaled = Self::decimal_to_unscaled(v, *scale)?;
+                Some(Value::Record(vec![
+                    (UNSCALED.into(), Value::Bytes(unscaled)),
+                    (
+                        PRECISION.into(),
+                        Value::Int(i32::try_from(*precision).ok()?),
+                    ),
+                    (SCALE.into(), Value::Int(i32::try_from(*scale).ok()?)),
+                ]))
+            }
+            ColValue::Date(v) => {
+                let date = NaiveDate::parse_from_str(v.trim(), DATE_FORMAT).ok()?;
+                let days = date.signed_duration_since(DateTime::<Utc>::UNIX_EPOCH.date_naive());
+                Some(Value::Record(vec![(
+                    VALUE.into(),
+                    Value::Date(i32::try_from(days.num_days()).ok()?),
+                )]))
+            }
+            // values without offset are taken as utc, sub-millisecond digits are dropped
+            ColValue::DateTime(v) | ColValue::Timestamp(v) => {
+                let value = v.trim().replacen('T', " ", 1);
+                let value = value.strip_suffix('Z').unwrap_or(&value);
+                let datetime = DateTime::parse_from_str(value, DATETIME_TZ_FORMAT)
+                    .map(|v| v.naive_utc())
+                    .or_else(|_| NaiveDateTime::parse_from_str(value, DATETIME_FORMAT))
+                    .ok()?;
+                Some(Value::Record(vec![(
+                    VALUE.into(),
+                    Value::TimestampMillis(datetime.and_utc().timestamp_millis()),
+                )]))
+            }
+            _ => None,
+        }
+    }
+
+    fn logical_avro_to_col_value(fields: Vec<(String, Value)>) -> ColValue {
+        let fields: HashMap<String, Value> = fields.into_iter().collect();
+        let col_value = match (fields.get(UNSCALED), fields.get(SCALE), fields.get(VALUE)) {
+            (Some(Value::Bytes(unscaled)), Some(Value::Int(scale)), _) => {
+                Self::unscaled_to_decimal(unscaled, *scale as u32).map(ColValue::Decimal)
+            }
+            (_, _, Some(Value::Date(days))) => DateTime::<Utc>::UNIX_EPOCH
+                .date_naive()
+                .checked_add_signed(chrono::Duration::days(*days as i64))
+                .map(|date| ColValue::Date(date.format(DATE_FORMAT).to_string())),
+            (_, _, Some(Value::TimestampMillis(millis))) => {
+                DateTime::from_timestamp_millis(*millis)
+                    .map(|v| ColValue::Timestamp(v.format("%Y-%m-%d %H:%M:%S%.3f").to_string()))
+            }
+            _ => None,
+        };
+        col_value.unwrap_or(ColValue::None)
+    }
+
+    /// -123.4 with scale 2 -> -12340 as big-endian two's complement bytes, the encoding of
+    /// avro decimal, None if the value has more fraction digits than the scale
+    fn decimal_to_unscaled(value: &str, scale: u32) -> Option<Vec<u8>> {
+        let value = value.trim();
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        if !digits.chars().any(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        let frac_part = frac_part.trim_end_matches('0');
+        if frac_part.len() > scale as usize
+            || !int_part
+                .chars()
+                .chain(frac_part.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let unscaled_str = format!("{}{:0<width$}", int_part, frac_part, width = scale as usize);
+        let mut unscaled: i128 = if unscaled_str.is_empty() {
+            0
+        } else {
+            unscaled_str.parse().ok()?
+        };
+        if negative {
+            unscaled = -unscaled;
+        }
+
+        // drop leading bytes which only repeat the sign
+        let bytes = unscaled.to_be_bytes();
+        let mut start = 0;
+        while start < bytes.len() - 1
+            && ((bytes[start] == 0 && bytes[start + 1] & 0x80 == 0)
+                || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0))
+        {
+            start += 1;
+        }
+        Some(bytes[start..].to_vec())
+    }
+
+    fn unscaled_to_decimal(unscaled: &[u8], scale: u32) -> Option<String> {
+        if unscaled.is_empty() || unscaled.len() > 16 {
+            return None;
+        }
+        let fill = if unscaled[0] & 0x80 != 0 { 0xff } else { 0 };
+        let mut bytes = [fill; 16];
+        bytes[16 - unscaled.len()..].copy_from_slice(unscaled);
+        let unscaled = i128::from_be_bytes(bytes);
+
+        let scale = scale as usize;
+        let digits = format!("{:0>width$}", unscaled.unsigned_abs(), width = scale + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+        let sign = if unscaled < 0 { "-" } else { "" };
+        if frac_part.is_empty() {
+            Some(format!("{}{}", sign, int_part))
+        } else {
+            Some(format!("{}{}.{}", sign, int_part, frac_part))
+        }
+    }
+
+    /// decimal(10,2) unsigned -> (10, 2), numeric(5) -> (5, 0),
+    /// None if precision is not declared, e.g. numeric of pg
+    fn parse_decimal_type(origin_type: &str) -> Option<(u32, u32)> {
+        let origin_type = origin_type.trim().to_lowercase();
+        if !origin_type.starts_with("decimal") && !origin_type.starts_with("numeric") {
+            return None;
+        }
+        let (_, args) = origin_type.split_once('(')?;
+        let (args, _) = args.split_once(')')?;
+        let mut args = args.split(',').map(|v| v.trim().parse::<u32>());
+        let precision = args.next()?.ok()?;
+        let scale = match args.next() {
+            Some(v) => v.ok()?,
+            None => 0,
+        };
+        Some((precision, scale))
+    }
+
+    fn get_decimal_types(tb_meta: &RdbTbMeta) -> HashMap<String, (u32, u32)> {
+        tb_meta
+            .col_origin_type_map
+            .iter()
+            .filter_map(|(col, origin_type)| {
+                Self::parse_decimal_type(origin_type)
+                    .map(|decimal_type| (col.clone(), decimal_type))
+            })
+            .collect()
+    }
+
     fn avro_to_col_value(value: Value) -> ColValue {
         match value {
             Value::Long(v) => ColValue::LongLong(v),
@@ -349,6 +681,7 @@ impl AvroConverter {
             Value::Boolean(v) => ColValue::Bool(v),
             Value::Null => ColValue::None,
             Value::Union(_, v) => Self::avro_to_col_value(*v),
+            Value::Record(fields) => Self::logical_avro_to_col_value(fields),
             // NOT supported
             _ => ColValue::None,
         }
@@ -411,7 +744,7 @@ mod tests {
         after.insert(BOOLEAN_COL.into(), ColValue::Bool(true));
         after.insert(NULL_COL.into(), ColValue::None);
 
-        let mut avro_converter = AvroConverter::new(None, false);
+        let mut avro_converter = AvroConverter::new(None, false, false, false);
         let mut row_data = RowData::new(
             schema.into(),
             tb.into(),
@@ -433,11 +766,14 @@ mod tests {
         row_data.after = None;
         row_data.refresh_data_size();
         validate_row_data(&mut avro_converter, &row_data).await;
+        // with original sql
+        row_data.query = Some(Arc::new("DELETE FROM db1.tb1 WHERE long_col = 2".into()));
+        validate_row_data(&mut avro_converter, &row_data).await;
     }
 
     #[tokio::test]
     async fn test_ddl_data_to_avro() {
-        let mut avro_converter = AvroConverter::new(None, false);
+        let mut avro_converter = AvroConverter::new(None, false, false, false);
 
         let ddl_data = DdlData {
             default_schema: "db1".to_string(),
@@ -464,6 +800,214 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_avro_unsigned_long_long_round_trip() {
+        for v in [0, i64::MAX as u64, i64::MAX as u64 + 1, u64::MAX] {
+            let avro_value = AvroConverter::col_value_to_avro(&ColValue::UnsignedLongLong(v));
+            let mut col_values = Some(HashMap::from([(
+                LONG_COL.to_string(),
+                AvroConverter::avro_to_col_value(avro_value),
+            )]));
+
+            let fields = vec![AvroFieldDef {
+                name: LONG_COL.into(),
+                column_type: "bigint(20) unsigned".into(),
+                avro_type: String::new(),
+            }];
+            AvroConverter::restore_unsigned_long_longs(&fields, &mut col_values);
+            assert_eq!(
+                col_values.unwrap().remove(LONG_COL),
+                Some(ColValue::UnsignedLongLong(v))
+            );
+        }
+
+        // without field defs, large values are still exact as strings
+        assert_eq!(
+            AvroConverter::avro_to_col_value(AvroConverter::col_value_to_avro(
+                &ColValue::UnsignedLongLong(u64::MAX)
+            )),
+            ColValue::String(u64::MAX.to_string())
+        );
+    }
+
+    #[test]
+    fn test_avro_logical_types_round_trip() {
+        let avro_converter = AvroConverter::new(None, false, false, true);
+        let after = HashMap::from([
+            (
+                "decimal_col".to_string(),
+                ColValue::Decimal("-123.4".into()),
+            ),
+            (
+                "big_decimal_col".to_string(),
+                ColValue::Decimal("12345678901234567890.5".into()),
+            ),
+            ("numeric_col".to_string(), ColValue::Decimal("1.5".into())),
+            ("date_col".to_string(), ColValue::Date("2024-01-02".into())),
+            (
+                "zero_date_col".to_string(),
+                ColValue::Date("0000-00-00".into()),
+            ),
+            (
+                "datetime_col".to_string(),
+                ColValue::DateTime("2024-01-01 10:00:00.123456".into()),
+            ),
+            (
+                "timestamp_col".to_string(),
+                ColValue::Timestamp("2024-01-01 10:00:00+08".into()),
+            ),
+        ]);
+        let decimal_types = HashMap::from([
+            ("decimal_col".to_string(), (10, 2)),
+            ("big_decimal_col".to_string(), (30, 2)),
+        ]);
+        let (after_avro_values, after_avro_types) =
+            AvroConverter::col_values_to_avro(&Some(after), Some(&decimal_types));
+        assert_eq!(after_avro_types["decimal_col"], "Decimal");
+        assert_eq!(after_avro_types["numeric_col"], "String");
+        assert_eq!(after_avro_types["date_col"], "Date");
+        assert_eq!(after_avro_types["zero_date_col"], "String");
+        assert_eq!(after_avro_types["timestamp_col"], "Timestamp");
+
+        // encoded by the schema
+        let value = Value::Record(vec![
+            (SCHEMA.into(), Value::String("db1".into())),
+            (TB.into(), Value::String("tb1".into())),
+            (OPERATION.into(), Value::String(RowType::Insert.to_string())),
+            (FIELDS.into(), Value::Union(0, Box::new(Value::Null))),
+            (BEFORE.into(), Value::Union(0, Box::new(Value::Null))),
+            (AFTER.into(), Value::Union(1, Box::new(after_avro_values))),
+            (EXTRA.into(), Value::Union(0, Box::new(Value::Null))),
+            (PROVENANCE.into(), Value::Union(0, Box::new(Value::Null))),
+        ]);
+        let payload = to_avro_datum(&avro_converter.schema, value).unwrap();
+        let DtData::Dml { row_data } = avro_converter.avro_value_to_dt_data(payload).unwrap()
+        else {
+            panic!()
+        };
+
+        let after = row_data.after.unwrap();
+        let expected = [
+            ("decimal_col", ColValue::Decimal("-123.40".into())),
+            (
+                "big_decimal_col",
+                ColValue::Decimal("12345678901234567890.50".into()),
+            ),
+            ("numeric_col", ColValue::String("1.5".into())),
+            ("date_col", ColValue::Date("2024-01-02".into())),
+            ("zero_date_col", ColValue::String("0000-00-00".into())),
+            (
+                "datetime_col",
+                ColValue::Timestamp("2024-01-01 10:00:00.123".into()),
+            ),
+            (
+                "timestamp_col",
+                ColValue::Timestamp("2024-01-01 02:00:00.000".into()),
+            ),
+        ];
+        for (col, col_value) in expected {
+            assert_eq!(after.get(col), Some(&col_value), "{}", col);
+        }
+
+        assert_eq!(
+            AvroConverter::parse_decimal_type("decimal(10,2) unsigned"),
+            Some((10, 2))
+        );
+        assert_eq!(
+            AvroConverter::parse_decimal_type("numeric(5)"),
+            Some((5, 0))
+        );
+        assert_eq!(AvroConverter::parse_decimal_type("numeric"), None);
+    }
+
+    #[tokio::test]
+    async fn test_provenance_to_avro() {
+        let mut avro_converter = AvroConverter::new(None, false, true, false);
+        let after = HashMap::from([(LONG_COL.to_string(), ColValue::LongLong(1))]);
+        let mut row_data = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        row_data.position = Some(Arc::new(Position::MysqlCdc {
+            server_id: "1".into(),
+            binlog_filename: "mysql-bin.000003".into(),
+            next_event_position: 157,
+            gtid_set: String::new(),
+            timestamp: "2024-01-01 10:00:00.000".into(),
+            source_id: String::new(),
+        }));
+
+        let payload = avro_converter
+            .row_data_to_avro_value(&row_data)
+            .await
+            .unwrap();
+        let value = from_avro_datum(&avro_converter.schema, &mut payload.as_slice(), None).unwrap();
+        let provenance = avro_converter
+            .avro_to_col_values(AvroConverter::avro_to_map(value).remove(PROVENANCE))
+            .unwrap();
+        assert_eq!(
+            provenance.get("binlog_filename"),
+            Some(&ColValue::String("mysql-bin.000003".into()))
+        );
+        assert_eq!(
+            provenance.get("binlog_position"),
+            Some(&ColValue::LongLong(157))
+        );
+        assert_eq!(provenance.get("lsn"), None);
+
+        // messages of the legacy schema end before the null provenance
+        avro_converter.with_provenance = false;
+        let payload = avro_converter
+            .row_data_to_avro_value(&row_data)
+            .await
+            .unwrap();
+        let legacy_payload = payload[..payload.len() - 1].to_vec();
+        row_data.position = None;
+        for payload in [payload, legacy_payload] {
+            assert!(matches!(
+                avro_converter.avro_value_to_dt_data(payload).unwrap(),
+                DtData::Dml { row_data: decoded } if decoded == row_data
+            ));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_watermark_to_avro() {
+        let avro_converter = AvroConverter::new(None, false, false, false);
+        let cdc_position = Position::PgCdc {
+            lsn: "0/1A2B3C4".into(),
+            timestamp: String::new(),
+        };
+        let payload = avro_converter
+            .snapshot_watermark_to_avro_value("db1", "tb1", 100, &cdc_position)
+            .unwrap();
+
+        let value = from_avro_datum(&avro_converter.schema, &mut payload.as_slice(), None).unwrap();
+        let mut avro_map = AvroConverter::avro_to_map(value);
+        assert_eq!(
+            avro_map.remove(OPERATION),
+            Some(Value::String(SNAPSHOT_FINISHED.into()))
+        );
+        let extra = avro_converter
+            .avro_to_col_values(avro_map.remove(EXTRA))
+            .unwrap();
+        assert_eq!(extra.get(ROW_COUNT), Some(&ColValue::LongLong(100)));
+        assert_eq!(
+            extra.get(CDC_POSITION),
+            Some(&ColValue::String(cdc_position.to_string()))
+        );
+
+        // skipped when consumed by ape-dts
+        assert!(matches!(
+            avro_converter.avro_value_to_dt_data(payload).unwrap(),
+            DtData::Heartbeat {}
+        ));
+    }
+
     async fn validate_row_data(avro_converter: &mut AvroConverter, row_data: &RowData) {
         let payload = avro_converter
             .row_data_to_avro_value(row_data)
@@ -2,6 +2,30 @@ use apache_avro::Schema;
 
 pub struct AvroConverterSchema {}
 
+// provenance is appended as the last field, so consumers decoding by the schema without it
+// still read the other fields, and messages without it can still be decoded by the legacy schema
+const PROVENANCE_FIELD_STR: &str = r#"
+{
+    "name": "provenance",
+    "default": null,
+    "type": 
+    [
+        "null",
+        {
+            "type": "map",
+            "values": 
+            [
+                "null",
+                "string",
+                "long"
+            ]
+        }
+    ]
+}"#;
+
+// values of before / after with logical types are wrapped in named records, since an avro union
+// can not hold bytes / long twice, the branches are appended after the plain types so messages
+// without logical types are encoded the same as before
 const SCHEMA_STR: &str = r#"
 {
     "type": "record",
@@ -70,7 +94,51 @@ const SCHEMA_STR: &str = r#"
                             "long",
                             "double",
                             "bytes",
-                            "boolean"
+                            "boolean",
+                            {
+                                "name": "AvroDecimal",
+                                "type": "record",
+                                "fields": [
+                                    {
+                                        "name": "unscaled",
+                                        "type": "bytes"
+                                    },
+                                    {
+                                        "name": "precision",
+                                        "type": "int"
+                                    },
+                                    {
+                                        "name": "scale",
+                                        "type": "int"
+                                    }
+                                ]
+                            },
+                            {
+                                "name": "AvroDate",
+                                "type": "record",
+                                "fields": [
+                                    {
+                                        "name": "value",
+                                        "type": {
+                                            "type": "int",
+                                            "logicalType": "date"
+                                        }
+                                    }
+                                ]
+                            },
+                            {
+                                "name": "AvroTimestamp",
+                                "type": "record",
+                                "fields": [
+                                    {
+                                        "name": "value",
+                                        "type": {
+                                            "type": "long",
+                                            "logicalType": "timestamp-millis"
+                                        }
+                                    }
+                                ]
+                            }
                         ]
                     }
                 ]
@@ -93,7 +161,10 @@ const SCHEMA_STR: &str = r#"
                             "long",
                             "double",
                             "bytes",
-                            "boolean"
+                            "boolean",
+                            "AvroDecimal",
+                            "AvroDate",
+                            "AvroTimestamp"
                         ]
                     }
                 ]
@@ -127,6 +198,18 @@ const SCHEMA_STR: &str = r#"
 
 impl AvroConverterSchema {
     pub fn get_avro_schema() -> Schema {
+        let mut schema: serde_json::Value = serde_json::from_str(SCHEMA_STR).unwrap();
+        let provenance_field: serde_json::Value =
+            serde_json::from_str(PROVENANCE_FIELD_STR).unwrap();
+        schema["fields"]
+            .as_array_mut()
+            .unwrap()
+            .push(provenance_field);
+        Schema::parse(&schema).unwrap()
+    }
+
+    /// schema of messages sent before provenance was added
+    pub fn get_legacy_avro_schema() -> Schema {
         Schema::parse_str(SCHEMA_STR).unwrap()
     }
 }
@@ -4,6 +4,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+use ahash::AHasher;
 use anyhow::bail;
 use mongodb::bson::{Bson, Document};
 use serde::{Deserialize, Serialize, Serializer};
@@ -172,7 +173,66 @@ impl ColValue {
         if matches!(self, ColValue::None | ColValue::UnchangedToast) {
             return Ok(0);
         }
+        self.check_hashable()?;
 
+        let mut hasher = DefaultHasher::new();
+        self.to_option_string().hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    /// Feeds the value into hasher without formatting it to a string, for in-process hashing
+    /// on hot paths, e.g. row keys in merger / partitioner. Values equal by PartialEq feed the
+    /// same bytes, returns false without feeding anything if the value is NULL.
+    ///
+    /// Unlike hash_code, values of different variants, e.g. Long(1) and LongLong(1), are
+    /// hashed differently, so the results should never be persisted or compared across dbs.
+    pub fn hash_into<H: Hasher>(&self, hasher: &mut H) -> anyhow::Result<bool> {
+        if matches!(self, ColValue::None | ColValue::UnchangedToast) {
+            return Ok(false);
+        }
+        self.check_hashable()?;
+
+        std::mem::discriminant(self).hash(hasher);
+        match self {
+            ColValue::Bool(v) => v.hash(hasher),
+            ColValue::Tiny(v) => v.hash(hasher),
+            ColValue::UnsignedTiny(v) => v.hash(hasher),
+            ColValue::Short(v) => v.hash(hasher),
+            ColValue::UnsignedShort(v) | ColValue::Year(v) => v.hash(hasher),
+            ColValue::Long(v) => v.hash(hasher),
+            ColValue::UnsignedLong(v) | ColValue::Enum(v) => v.hash(hasher),
+            ColValue::LongLong(v) => v.hash(hasher),
+            ColValue::UnsignedLongLong(v) | ColValue::Bit(v) | ColValue::Set(v) => v.hash(hasher),
+            // 0.0 == -0.0
+            ColValue::Float(v) => (if *v == 0.0 { 0.0 } else { *v }).to_bits().hash(hasher),
+            ColValue::Double(v) => (if *v == 0.0 { 0.0 } else { *v }).to_bits().hash(hasher),
+            ColValue::Decimal(v)
+            | ColValue::Time(v)
+            | ColValue::Date(v)
+            | ColValue::DateTime(v)
+            | ColValue::Timestamp(v)
+            | ColValue::String(v)
+            | ColValue::Set2(v)
+            | ColValue::Enum2(v)
+            | ColValue::Json2(v) => v.hash(hasher),
+            ColValue::RawString(v) | ColValue::Blob(v) | ColValue::Json(v) => v.hash(hasher),
+            ColValue::Json3(v) => v.to_string().hash(hasher),
+            ColValue::MongoDoc(v) => Self::mongo_doc_to_string(v).hash(hasher),
+            ColValue::None | ColValue::UnchangedToast => {}
+        }
+        Ok(true)
+    }
+
+    /// Same as hash_code but computed by hash_into with ahash, 0 if the value is NULL.
+    pub fn fast_hash_code(&self) -> anyhow::Result<u64> {
+        let mut hasher = AHasher::default();
+        if !self.hash_into(&mut hasher)? {
+            return Ok(0);
+        }
+        Ok(hasher.finish())
+    }
+
+    fn check_hashable(&self) -> anyhow::Result<()> {
         if let ColValue::MongoDoc(doc) = self {
             // Reject nested Document/Array in _id as they're not reliably hashable
             for (key, value) in doc.iter() {
@@ -184,10 +244,7 @@ impl ColValue {
                 }
             }
         }
-
-        let mut hasher = DefaultHasher::new();
-        self.to_option_string().hash(&mut hasher);
-        Ok(hasher.finish())
+        Ok(())
     }
 
     pub fn type_name(&self) -> &'static str {
@@ -453,6 +510,26 @@ mod tests {
         assert!(v4.is_same_value(&ColValue::Long(7)));
     }
 
+    #[test]
+    fn test_fast_hash_code() {
+        assert_eq!(ColValue::None.fast_hash_code().unwrap(), 0);
+        assert_eq!(
+            ColValue::String("a".into()).fast_hash_code().unwrap(),
+            ColValue::String("a".into()).fast_hash_code().unwrap()
+        );
+        assert_eq!(
+            ColValue::Double(0.0).fast_hash_code().unwrap(),
+            ColValue::Double(-0.0).fast_hash_code().unwrap()
+        );
+        assert_ne!(
+            ColValue::Long(1).fast_hash_code().unwrap(),
+            ColValue::Long(2).fast_hash_code().unwrap()
+        );
+        assert!(ColValue::MongoDoc(mongodb::bson::doc! {"a": {"b": 1}})
+            .fast_hash_code()
+            .is_err());
+    }
+
     #[test]
     fn test_add_integer_128() {
         let cases = vec![
@@ -496,6 +573,24 @@ mod tests {
         tagged_col_value_map::serialize(&values, &mut json).unwrap();
     }
 
+    #[test]
+    fn test_unsigned_long_long_json_is_exact() {
+        let value = ColValue::UnsignedLongLong(u64::MAX);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            "18446744073709551615"
+        );
+
+        let values = BTreeMap::from([("id".to_string(), value)]);
+        let mut json = serde_json::Serializer::new(Vec::new());
+        tagged_col_value_map::serialize(&values, &mut json).unwrap();
+        let mut deserializer = serde_json::Deserializer::from_slice(&json.into_inner());
+        assert_eq!(
+            tagged_col_value_map::deserialize(&mut deserializer).unwrap(),
+            values
+        );
+    }
+
     #[test]
     fn test_tagged_col_value_def_is_exposed_from_meta() {
         let _ = std::any::type_name::<MetaTaggedColValueDef>();
@@ -55,6 +55,20 @@ impl DdlData {
         res
     }
 
+    /// Whether the ddl can run in a pg transaction block, database ddls and CONCURRENTLY index
+    /// ddls can not.
+    pub fn is_pg_transactional(&self) -> bool {
+        match &self.statement {
+            DdlStatement::CreateDatabase(_)
+            | DdlStatement::DropDatabase(_)
+            | DdlStatement::AlterDatabase(_) => false,
+            DdlStatement::PgCreateIndex(s) => !s.is_concurrently,
+            DdlStatement::PgDropIndex(s) => !s.is_concurrently,
+            DdlStatement::PgDropMultiIndex(s) => !s.is_concurrently,
+            _ => true,
+        }
+    }
+
     pub fn get_data_size(&self) -> u64 {
         self.to_sql().len() as u64
     }
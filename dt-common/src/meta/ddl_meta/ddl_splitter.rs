@@ -0,0 +1,332 @@
+use crate::config::config_enums::DbType;
+
+/// Splits a captured ddl text into single statements by `;`, e.g. pg event triggers capture
+/// `current_query()` which may hold several statements sent in one query.
+/// Separators in quotes, comments, pg dollar quoted bodies and `BEGIN ... END` bodies of
+/// CREATE statements, e.g. mysql triggers / procedures and pg `BEGIN ATOMIC` functions, are kept.
+pub struct DdlSplitter {
+    db_type: DbType,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Normal,
+    // ' " `
+    Quoted(char),
+    LineComment,
+    BlockComment,
+    // pg $tag$ ... $tag$
+    DollarQuoted,
+}
+
+impl DdlSplitter {
+    pub fn new(db_type: DbType) -> Self {
+        Self { db_type }
+    }
+
+    /// Statements of a captured ddl, mysql binlog query events hold one statement each and
+    /// are kept as they are, pg event triggers capture `current_query()` which is split.
+    pub fn split_captured(&self, sql: &str) -> Vec<String> {
+        match self.db_type {
+            DbType::Pg => self.split(sql),
+            _ => vec![sql.to_string()],
+        }
+    }
+
+    /// Returns trimmed statements without the trailing `;`, comments before each statement
+    /// are dropped, so are statements with only comments and whitespaces.
+    pub fn split(&self, sql: &str) -> Vec<String> {
+        let chars: Vec<char> = sql.chars().collect();
+        let is_mysql = matches!(self.db_type, DbType::Mysql | DbType::Tidb);
+        let mut statements = Vec::new();
+        let mut current = String::new();
+        let mut has_code = false;
+        let mut state = State::Normal;
+        let mut dollar_tag = String::new();
+        // first and last words of the current statement and depth of compound bodies in it
+        let mut first_word = String::new();
+        let mut last_word = String::new();
+        let mut depth = 0usize;
+
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            match state {
+                State::Normal => match c {
+                    ';' if depth == 0 => {
+                        Self::push_statement(&mut statements, &mut current, has_code);
+                        has_code = false;
+                        first_word.clear();
+                        last_word.clear();
+                        i += 1;
+                        continue;
+                    }
+                    '\'' | '"' => state = State::Quoted(c),
+                    '`' if is_mysql => state = State::Quoted(c),
+                    '-' if next == Some('-') => state = State::LineComment,
+                    '#' if is_mysql => state = State::LineComment,
+                    '/' if next == Some('*') => {
+                        state = State::BlockComment;
+                        current.push_str("/*");
+                        i += 2;
+                        continue;
+                    }
+                    _ if (c.is_alphabetic() || c == '_')
+                        && !(i > 0 && Self::is_ident_char(chars[i - 1])) =>
+                    {
+                        let word = Self::word(&chars, i);
+                        if !has_code {
+                            current.clear();
+                            has_code = true;
+                        }
+                        let upper_word = word.to_uppercase();
+                        if first_word.is_empty() {
+                            first_word = upper_word.clone();
+                        }
+                        let len = word.chars().count();
+                        let next_word = Self::next_word(&chars, i + len).to_uppercase();
+                        depth = Self::compound_depth(
+                            depth,
+                            &first_word,
+                            &last_word,
+                            &upper_word,
+                            &next_word,
+                        );
+                        last_word = upper_word;
+                        current.push_str(&word);
+                        i += len;
+                        continue;
+                    }
+                    // a dollar quote does not follow an identifier, e.g. col$1
+                    '$' if !is_mysql && !(i > 0 && Self::is_ident_char(chars[i - 1])) => {
+                        if let Some(tag) = Self::dollar_tag(&chars, i) {
+                            if !has_code {
+                                current.clear();
+                            }
+                            has_code = true;
+                            current.push_str(&tag);
+                            i += tag.chars().count();
+                            dollar_tag = tag;
+                            state = State::DollarQuoted;
+                            continue;
+                        }
+                    }
+                    _ => {}
+                },
+
+                State::Quoted(quote) => {
+                    if is_mysql && quote != '`' && c == '\\' && next.is_some() {
+                        // backslash escape of mysql strings
+                        current.push(c);
+                        current.push(next.unwrap());
+                        i += 2;
+                        continue;
+                    }
+                    if c == quote {
+                        if next == Some(quote) {
+                            // doubled quote escape
+                            current.push(c);
+                            current.push(quote);
+                            i += 2;
+                            continue;
+                        }
+                        state = State::Normal;
+                    }
+                }
+
+                State::LineComment => {
+                    if c == '\n' {
+                        state = State::Normal;
+                    }
+                }
+
+                State::BlockComment => {
+                    if c == '*' && next == Some('/') {
+                        current.push_str("*/");
+                        state = State::Normal;
+                        i += 2;
+                        continue;
+                    }
+                }
+
+                State::DollarQuoted => {
+                    if c == '$' && Self::dollar_tag(&chars, i).as_ref() == Some(&dollar_tag) {
+                        current.push_str(&dollar_tag);
+                        i += dollar_tag.chars().count();
+                        state = State::Normal;
+                        continue;
+                    }
+                }
+            }
+
+            if !has_code && !c.is_whitespace() && matches!(state, State::Normal | State::Quoted(_))
+            {
+                // comments before the statement are dropped
+                current.clear();
+                has_code = true;
+            }
+            current.push(c);
+            i += 1;
+        }
+
+        Self::push_statement(&mut statements, &mut current, has_code);
+        statements
+    }
+
+    fn push_statement(statements: &mut Vec<String>, current: &mut String, has_code: bool) {
+        let statement = current.trim();
+        if has_code && !statement.is_empty() {
+            statements.push(statement.to_string());
+        }
+        current.clear();
+    }
+
+    // BEGIN opens a compound body only in CREATE statements, so a pg transaction like
+    // BEGIN; ALTER ...; COMMIT; is still split. CASE opens a block inside a body which is
+    // closed by END or END CASE, while END IF / END LOOP ... close nothing opened here.
+    fn compound_depth(
+        depth: usize,
+        first_word: &str,
+        last_word: &str,
+        word: &str,
+        next_word: &str,
+    ) -> usize {
+        match word {
+            "BEGIN" if first_word == "CREATE" => depth + 1,
+            "CASE" if depth > 0 && last_word != "END" => depth + 1,
+            "END" if depth > 0 && !matches!(next_word, "IF" | "LOOP" | "WHILE" | "REPEAT") => {
+                depth - 1
+            }
+            _ => depth,
+        }
+    }
+
+    fn next_word(chars: &[char], start: usize) -> String {
+        let start = chars
+            .iter()
+            .skip(start)
+            .position(|c| !c.is_whitespace())
+            .map_or(chars.len(), |n| start + n);
+        Self::word(chars, start)
+    }
+
+    fn word(chars: &[char], start: usize) -> String {
+        chars
+            .iter()
+            .skip(start)
+            .take_while(|c| c.is_alphanumeric() || **c == '_')
+            .collect()
+    }
+
+    fn is_ident_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '$'
+    }
+
+    // $tag$ starting at chars[start], tag is empty or an identifier not starting with a digit,
+    // so positional params like $1 are not taken as dollar quotes
+    fn dollar_tag(chars: &[char], start: usize) -> Option<String> {
+        let mut tag = String::from("$");
+        for (n, c) in chars.iter().enumerate().skip(start + 1) {
+            if *c == '$' {
+                tag.push('$');
+                return Some(tag);
+            }
+            let valid = c.is_alphabetic() || *c == '_' || (n > start + 1 && c.is_ascii_digit());
+            if !valid {
+                return None;
+            }
+            tag.push(*c);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pg() {
+        let splitter = DdlSplitter::new(DbType::Pg);
+        let sql = r#"ALTER TABLE tb_1 ADD COLUMN c1 int;
+            -- comment; with separator
+            ALTER TABLE "tb;2" ADD COLUMN c2 varchar(10) DEFAULT 'a;''b';
+            /* block; comment */
+            CREATE FUNCTION f_1() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql;
+            CREATE FUNCTION f_2() RETURNS int AS $$ SELECT 2; $$ LANGUAGE sql ;;"#;
+        assert_eq!(
+            splitter.split(sql),
+            vec![
+                "ALTER TABLE tb_1 ADD COLUMN c1 int",
+                "ALTER TABLE \"tb;2\" ADD COLUMN c2 varchar(10) DEFAULT 'a;''b'",
+                "CREATE FUNCTION f_1() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql",
+                "CREATE FUNCTION f_2() RETURNS int AS $$ SELECT 2; $$ LANGUAGE sql",
+            ]
+        );
+
+        // only comments are left after the last separator
+        assert_eq!(
+            splitter.split("DROP TABLE tb_1; -- done"),
+            vec!["DROP TABLE tb_1"]
+        );
+        // BEGIN ATOMIC bodies are kept, transaction blocks are split
+        let sql = "CREATE FUNCTION f_3(a int) RETURNS int LANGUAGE sql BEGIN ATOMIC \
+            SELECT CASE WHEN a > 0 THEN 1 ELSE 0 END; SELECT a; END; \
+            BEGIN; ALTER TABLE tb_1 ADD COLUMN c3 int; COMMIT;";
+        assert_eq!(
+            splitter.split(sql),
+            vec![
+                "CREATE FUNCTION f_3(a int) RETURNS int LANGUAGE sql BEGIN ATOMIC \
+            SELECT CASE WHEN a > 0 THEN 1 ELSE 0 END; SELECT a; END",
+                "BEGIN",
+                "ALTER TABLE tb_1 ADD COLUMN c3 int",
+                "COMMIT",
+            ]
+        );
+        assert_eq!(
+            splitter.split_captured("DROP TABLE tb_1; DROP TABLE tb_2;"),
+            vec!["DROP TABLE tb_1", "DROP TABLE tb_2"]
+        );
+        // positional params are not dollar quotes
+        assert_eq!(
+            splitter.split("PREPARE p AS SELECT $1; DROP TABLE tb_1"),
+            vec!["PREPARE p AS SELECT $1", "DROP TABLE tb_1"]
+        );
+    }
+
+    #[test]
+    fn test_split_mysql() {
+        let splitter = DdlSplitter::new(DbType::Mysql);
+        let sql = "ALTER TABLE `tb;1` ADD COLUMN c1 varchar(10) DEFAULT 'a\\';b'; # comment;\nDROP TABLE tb_2";
+        assert_eq!(
+            splitter.split(sql),
+            vec![
+                "ALTER TABLE `tb;1` ADD COLUMN c1 varchar(10) DEFAULT 'a\\';b'",
+                "DROP TABLE tb_2",
+            ]
+        );
+        // compound bodies of triggers and procedures are kept
+        let sql = "CREATE TRIGGER tr_1 BEFORE INSERT ON tb_1 FOR EACH ROW BEGIN \
+            IF NEW.c1 < 0 THEN SET NEW.c1 = 0; END IF; \
+            CASE NEW.c2 WHEN 1 THEN SET NEW.c3 = 1; ELSE BEGIN END; END CASE; \
+            SET NEW.c4 = 1; END; DROP TABLE tb_2";
+        assert_eq!(
+            splitter.split(sql),
+            vec![
+                "CREATE TRIGGER tr_1 BEFORE INSERT ON tb_1 FOR EACH ROW BEGIN \
+            IF NEW.c1 < 0 THEN SET NEW.c1 = 0; END IF; \
+            CASE NEW.c2 WHEN 1 THEN SET NEW.c3 = 1; ELSE BEGIN END; END CASE; \
+            SET NEW.c4 = 1; END",
+                "DROP TABLE tb_2",
+            ]
+        );
+        // binlog query events are not split
+        let sql = "CREATE PROCEDURE p_1() BEGIN INSERT INTO tb_1 VALUES (1); END";
+        assert_eq!(splitter.split_captured(sql), vec![sql]);
+        assert_eq!(
+            splitter.split("CREATE TABLE tb_1 (id int)"),
+            vec!["CREATE TABLE tb_1 (id int)"]
+        );
+    }
+}
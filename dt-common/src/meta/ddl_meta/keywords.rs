@@ -5,6 +5,9 @@ use nom::error::{ErrorKind, ParseError};
 use nom::sequence::terminated;
 use nom::{IResult, InputLength};
 
+use crate::config::config_enums::DbType;
+use crate::utils::sql_util::SqlUtil;
+
 // copied from https://github.com/ms705/nom-sql/blob/master/src/keywords.rs
 
 // NOTE: Each keyword_$start_letter_to_$end_letter function uses `alt`,
@@ -196,7 +199,7 @@ pub fn sql_keyword(i: &[u8]) -> IResult<&[u8], &[u8]> {
 #[allow(dead_code)]
 pub fn escape_if_keyword(s: &str) -> String {
     if sql_keyword(s.as_bytes()).is_ok() {
-        format!("`{}`", s)
+        SqlUtil::quote_by_db_type(s, &DbType::Mysql)
     } else {
         s.to_owned()
     }
@@ -1,5 +1,6 @@
 pub mod ddl_data;
 pub mod ddl_parser;
+pub mod ddl_splitter;
 pub mod ddl_statement;
 pub mod ddl_type;
 mod keywords;
@@ -5,12 +5,16 @@ use super::{ddl_meta::ddl_data::DdlData, row_data::RowData, struct_meta::struct_
 use crate::meta::dcl_meta::dcl_data::DclData;
 use crate::meta::row_type::RowSqlType;
 use crate::meta::{position::Position, redis::redis_entry::RedisEntry};
+use crate::monitor::latency_tracer::ItemTrace;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DtItem {
     pub dt_data: DtData,
     pub position: Position,
     pub data_origin_node: String,
+    // set for items sampled by [pipeline] trace_sample_rate
+    #[serde(skip)]
+    pub trace: Option<ItemTrace>,
 }
 
 impl DtItem {
@@ -49,7 +53,11 @@ pub enum DtData {
     Dml {
         row_data: RowData,
     },
-    Begin {},
+    // xid is the id of the transaction, same in its Begin and Commit:
+    // gtid or {binlog_filename}:{position} for mysql, xid for pg
+    Begin {
+        xid: String,
+    },
     Commit {
         xid: String,
     },
@@ -77,6 +85,19 @@ impl DtData {
         matches!(self, DtData::Dcl { .. })
     }
 
+    /// items other than rows / redis entries, e.g. ddl, begin / commit and heartbeat
+    pub fn is_control(&self) -> bool {
+        !matches!(self, DtData::Dml { .. } | DtData::Redis { .. })
+    }
+
+    /// meta version of rows, None for other items
+    pub fn get_meta_version(&self) -> Option<u64> {
+        match &self {
+            DtData::Dml { row_data } => Some(row_data.meta_version),
+            _ => None,
+        }
+    }
+
     pub fn get_data_size(&self) -> u64 {
         match &self {
             DtData::Dml { row_data } => row_data.data_size as u64,
@@ -90,7 +111,7 @@ impl DtData {
 
     pub fn get_data_count(&self) -> usize {
         match &self {
-            DtData::Begin {} | DtData::Commit { .. } | DtData::Heartbeat {} => 0,
+            DtData::Begin { .. } | DtData::Commit { .. } | DtData::Heartbeat {} => 0,
             _ => 1,
         }
     }
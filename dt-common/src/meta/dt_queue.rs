@@ -1,12 +1,12 @@
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
+    atomic::{AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
 
 use concurrent_queue::{ConcurrentQueue, PopError, PushError};
 use tokio::{sync::Notify, time::timeout, time::Duration};
 
-use crate::limiter::buffer_limiter::BufferLimiter;
+use crate::{limiter::buffer_limiter::BufferLimiter, monitor::latency_tracer::LatencyTracer};
 
 use super::dt_data::DtItem;
 
@@ -19,15 +19,61 @@ pub enum DtQueuePopError {
     DequeueLimiter(#[source] anyhow::Error),
 }
 
+/// Items are popped in the order they are pushed, while data items (rows, redis entries) and
+/// control items (ddl, begin / commit, heartbeat...) are limited separately, so a burst of
+/// big rows never blocks pushing ddl or commit markers.
 pub struct DtQueue {
     queue: ConcurrentQueue<DtItem>,
-    check_memory: bool,
-    max_bytes: u64,
-    cur_bytes: AtomicU64,
+    data: QueueLimit,
+    control: QueueLimit,
     not_empty: Arc<Notify>,
-    not_full: Arc<Notify>,
+    // only applied to data items
     enqueue_limiter: Option<Arc<BufferLimiter>>,
     dequeue_limiter: Option<Arc<BufferLimiter>>,
+    // bytes of queued items plus data items popped but not sunk yet, 0 for no limit
+    memory_budget: u64,
+    in_flight_bytes: AtomicU64,
+    // samples data items pushed for latency tracing
+    tracer: Option<Arc<LatencyTracer>>,
+}
+
+struct QueueLimit {
+    capacity: usize,
+    // 0 for no limit
+    max_bytes: u64,
+    len: AtomicUsize,
+    cur_bytes: AtomicU64,
+    not_full: Notify,
+}
+
+impl QueueLimit {
+    fn new(capacity: usize, max_bytes: u64) -> Self {
+        Self {
+            capacity,
+            max_bytes,
+            len: AtomicUsize::new(0),
+            cur_bytes: AtomicU64::new(0),
+            not_full: Notify::new(),
+        }
+    }
+
+    #[inline(always)]
+    fn is_full(&self) -> bool {
+        self.len.load(Ordering::Acquire) >= self.capacity
+            || (self.max_bytes > 0 && self.cur_bytes.load(Ordering::Acquire) > self.max_bytes)
+    }
+
+    #[inline(always)]
+    fn add(&self, item_size: u64) {
+        self.len.fetch_add(1, Ordering::AcqRel);
+        self.cur_bytes.fetch_add(item_size, Ordering::AcqRel);
+    }
+
+    #[inline(always)]
+    fn sub(&self, item_size: u64) {
+        self.len.fetch_sub(1, Ordering::AcqRel);
+        self.cur_bytes.fetch_sub(item_size, Ordering::AcqRel);
+    }
 }
 
 impl DtQueue {
@@ -38,25 +84,52 @@ impl DtQueue {
         dequeue_limiter: Option<Arc<BufferLimiter>>,
     ) -> Self {
         Self {
-            queue: ConcurrentQueue::bounded(capacity),
-            max_bytes,
-            check_memory: max_bytes > 0,
-            cur_bytes: AtomicU64::new(0),
+            queue: ConcurrentQueue::bounded(capacity * 2),
+            data: QueueLimit::new(capacity, max_bytes),
+            control: QueueLimit::new(capacity, 0),
             not_empty: Arc::new(Notify::new()),
-            not_full: Arc::new(Notify::new()),
             enqueue_limiter,
             dequeue_limiter,
+            memory_budget: 0,
+            in_flight_bytes: AtomicU64::new(0),
+            tracer: None,
         }
     }
 
+    /// Blocks pushing data items while queued and in-flight bytes exceed the budget, popped
+    /// data items stay in flight until release_in_flight is called after they are sunk.
+    pub fn with_memory_budget(mut self, memory_budget: u64) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Limits of control items, the same count as data items by default.
+    pub fn with_control_capacity(mut self, capacity: usize, max_bytes: u64) -> Self {
+        let capacity = capacity.max(1);
+        self.queue = ConcurrentQueue::bounded(self.data.capacity + capacity);
+        self.control = QueueLimit::new(capacity, max_bytes);
+        self
+    }
+
+    /// Sampled data items carry a trace, taken by the pipeline once they are drained.
+    pub fn with_tracer(mut self, tracer: Option<Arc<LatencyTracer>>) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    pub fn tracer(&self) -> Option<Arc<LatencyTracer>> {
+        self.tracer.clone()
+    }
+
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
 
+    /// Whether data items reach the capacity or the memory budget
     #[inline(always)]
     pub fn is_full(&self) -> bool {
-        self.queue.is_full()
+        self.data.len.load(Ordering::Acquire) >= self.data.capacity || self.is_over_budget()
     }
 
     #[inline(always)]
@@ -64,63 +137,106 @@ impl DtQueue {
         self.queue.len()
     }
 
+    #[inline(always)]
+    pub fn data_len(&self) -> usize {
+        self.data.len.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    pub fn control_len(&self) -> usize {
+        self.control.len.load(Ordering::Relaxed)
+    }
+
+    /// Bytes of data items
     #[inline(always)]
     pub fn get_curr_size(&self) -> u64 {
-        self.cur_bytes.load(Ordering::Relaxed)
+        self.data.cur_bytes.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    pub fn get_control_size(&self) -> u64 {
+        self.control.cur_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Bytes of data items popped but not released
+    #[inline(always)]
+    pub fn get_in_flight_size(&self) -> u64 {
+        self.in_flight_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Called by the only consumer once all popped items are sunk.
+    pub fn release_in_flight(&self) {
+        if self.memory_budget == 0 || self.in_flight_bytes.swap(0, Ordering::AcqRel) == 0 {
+            return;
+        }
+        // several extractor workers may be waiting for the released bytes
+        self.data.not_full.notify_waiters();
+        self.data.not_full.notify_one();
     }
 
     pub async fn push(&self, mut item: DtItem) -> anyhow::Result<()> {
-        if let Some(enqueue_limiter) = &self.enqueue_limiter {
-            enqueue_limiter.acquire(&item).await?;
+        let is_control = item.dt_data.is_control();
+        let limit = self.limit(is_control);
+        if !is_control {
+            if let Some(tracer) = &self.tracer {
+                item.trace = tracer.sample();
+            }
+            if let Some(enqueue_limiter) = &self.enqueue_limiter {
+                enqueue_limiter.acquire(&item).await?;
+            }
         }
         let item_size = item.dt_data.get_data_size();
         loop {
-            if !self.queue.is_full() && !self.is_mem_full() {
-                let res = self.queue.push(item);
-                match res {
+            if !limit.is_full() && (is_control || !self.is_over_budget()) {
+                // count the item before pushing, so pop never sees it uncounted
+                limit.add(item_size);
+                if let Some(trace) = &mut item.trace {
+                    trace.enqueued_at = Some(std::time::Instant::now());
+                }
+                match self.queue.push(item) {
                     Ok(_) => {
-                        self.cur_bytes.fetch_add(item_size, Ordering::Release);
                         self.not_empty.notify_one();
                         return Ok(());
                     }
                     Err(PushError::Full(returned_item)) => {
+                        limit.sub(item_size);
                         item = returned_item;
                     }
-                    Err(e) => return Err(e.into()),
+                    Err(e) => {
+                        limit.sub(item_size);
+                        return Err(e.into());
+                    }
                 }
             }
-            self.not_full.notified().await;
+            limit.not_full.notified().await;
         }
     }
 
     pub async fn pop(&self) -> Result<DtItem, DtQueuePopError> {
         let item = self.queue.pop()?;
+        let is_control = item.dt_data.is_control();
+        let item_size = item.dt_data.get_data_size();
+        self.limit(is_control).sub(item_size);
+        if !is_control && self.memory_budget > 0 {
+            self.in_flight_bytes.fetch_add(item_size, Ordering::AcqRel);
+        }
+        // the other kind may be waiting for the shared queue if pushes raced
+        self.data.not_full.notify_one();
+        self.control.not_full.notify_one();
+
+        if is_control {
+            return Ok(item);
+        }
 
         if let Some(enqueue_limiter) = &self.enqueue_limiter {
             enqueue_limiter.release(&item).await;
         }
-        let dequeue_result = if let Some(dequeue_limiter) = &self.dequeue_limiter {
+        if let Some(dequeue_limiter) = &self.dequeue_limiter {
             match dequeue_limiter.acquire(&item).await {
-                Ok(()) => {
-                    dequeue_limiter.release(&item).await;
-                    Ok(())
-                }
-                Err(error) => Err(DtQueuePopError::DequeueLimiter(error)),
+                Ok(()) => dequeue_limiter.release(&item).await,
+                Err(error) => return Err(DtQueuePopError::DequeueLimiter(error)),
             }
-        } else {
-            Ok(())
-        };
-
-        if self.queue.is_empty() {
-            self.cur_bytes.store(0, Ordering::Release);
-        } else {
-            self.cur_bytes
-                .fetch_sub(item.dt_data.get_data_size(), Ordering::Release);
         }
-
-        self.not_full.notify_one();
-
-        dequeue_result?;
         Ok(item)
     }
 
@@ -129,11 +245,20 @@ impl DtQueue {
     }
 
     #[inline(always)]
-    fn is_mem_full(&self) -> bool {
-        if self.check_memory {
-            self.cur_bytes.load(Ordering::Acquire) > self.max_bytes
+    fn is_over_budget(&self) -> bool {
+        self.memory_budget > 0
+            && self.data.cur_bytes.load(Ordering::Acquire)
+                + self.control.cur_bytes.load(Ordering::Acquire)
+                + self.in_flight_bytes.load(Ordering::Acquire)
+                > self.memory_budget
+    }
+
+    #[inline(always)]
+    fn limit(&self, is_control: bool) -> &QueueLimit {
+        if is_control {
+            &self.control
         } else {
-            false
+            &self.data
         }
     }
 }
@@ -162,6 +287,7 @@ mod tests {
             dt_data: DtData::Heartbeat {},
             position: Position::None,
             data_origin_node: String::new(),
+            trace: None,
         }
     }
 
@@ -180,10 +306,14 @@ mod tests {
                     )])),
                     data_size,
                     is_not_origin: false,
+                    position: None,
+                    query: None,
+                    meta_version: 0,
                 },
             },
             position: Position::None,
             data_origin_node: String::new(),
+            trace: None,
         }
     }
 
@@ -205,6 +335,69 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn control_items_are_not_blocked_by_data_items() {
+        let queue = DtQueue::new(2, 100, None, None).with_control_capacity(1, 0);
+        queue.push(bytes_item(200)).await.unwrap();
+        queue.push(heartbeat_item()).await.unwrap();
+        assert_eq!(queue.get_curr_size(), 200);
+        assert_eq!((queue.data_len(), queue.control_len()), (1, 1));
+
+        // data items are blocked by buffer bytes, control items by their own capacity
+        assert!(
+            timeout(Duration::from_millis(50), queue.push(bytes_item(1)))
+                .await
+                .is_err()
+        );
+        assert!(
+            timeout(Duration::from_millis(50), queue.push(heartbeat_item()))
+                .await
+                .is_err()
+        );
+
+        // popped in the pushed order
+        let item = queue.pop().await.unwrap();
+        assert!(!item.dt_data.is_control());
+        let item = queue.pop().await.unwrap();
+        assert!(item.dt_data.is_control());
+        assert!(queue.is_empty());
+        assert_eq!((queue.data_len(), queue.get_curr_size()), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn memory_budget_counts_in_flight_items() {
+        let queue = Arc::new(DtQueue::new(8, 0, None, None).with_memory_budget(100));
+        queue.push(bytes_item(60)).await.unwrap();
+        queue.push(bytes_item(60)).await.unwrap();
+        assert!(queue.is_full());
+
+        // popped items are still counted until released
+        queue.pop().await.unwrap();
+        queue.pop().await.unwrap();
+        assert_eq!(queue.get_in_flight_size(), 120);
+        assert!(
+            timeout(Duration::from_millis(50), queue.push(bytes_item(1)))
+                .await
+                .is_err()
+        );
+        // control items are never blocked by the budget
+        queue.push(heartbeat_item()).await.unwrap();
+
+        let pusher_queue = queue.clone();
+        let pusher = tokio::spawn(async move { pusher_queue.push(bytes_item(1)).await });
+        sleep(Duration::from_millis(20)).await;
+        assert!(!pusher.is_finished());
+
+        queue.release_in_flight();
+        timeout(Duration::from_millis(200), pusher)
+            .await
+            .expect("pusher should wake after release")
+            .unwrap()
+            .unwrap();
+        assert_eq!(queue.get_in_flight_size(), 0);
+        assert_eq!(queue.get_curr_size(), 1);
+    }
+
     #[tokio::test]
     async fn pop_returns_dequeue_limiter_error_without_panicking() {
         let rate_config = RateLimiterConfig {
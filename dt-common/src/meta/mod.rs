@@ -14,6 +14,7 @@ pub mod mysql;
 pub mod order_key;
 pub mod pg;
 pub mod position;
+pub mod provenance;
 pub mod rdb_meta_manager;
 pub mod rdb_tb_meta;
 pub mod redis;
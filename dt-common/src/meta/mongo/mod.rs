@@ -2,5 +2,6 @@ pub mod mongo_cdc_source;
 pub mod mongo_constant;
 pub mod mongo_ddl;
 pub mod mongo_key;
+pub mod mongo_projection;
 pub mod mongo_shard;
 pub mod mongo_version;
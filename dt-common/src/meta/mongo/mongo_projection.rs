@@ -0,0 +1,245 @@
+use std::collections::BTreeMap;
+
+use mongodb::bson::{Bson, Document};
+use serde::Deserialize;
+
+use super::mongo_constant::MongoConstants;
+use crate::meta::{col_value::ColValue, row_data::RowData};
+
+/// Field projection and rename rules of a collection, paths are dotted, e.g. profile.email.
+///
+/// Fields under an excluded path are removed. If include is not empty, only fields under
+/// included paths are kept, excludes still apply inside them. Renames apply to the projected
+/// document. _id is always kept as it is.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct MongoProjection {
+    pub db: String,
+    pub tb: String,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    // source path -> target path
+    #[serde(default)]
+    pub rename: BTreeMap<String, String>,
+}
+
+impl MongoProjection {
+    /// false if the row is an update which only changes removed fields
+    pub fn project_row(&self, row_data: &mut RowData) -> bool {
+        let mut keep = true;
+        for col_values in [&mut row_data.before, &mut row_data.after]
+            .into_iter()
+            .flatten()
+        {
+            for (col, col_value) in col_values.iter_mut() {
+                let ColValue::MongoDoc(doc) = col_value else {
+                    continue;
+                };
+                match col.as_str() {
+                    MongoConstants::DOC | MongoConstants::PRE_IMAGE => {
+                        *doc = self.project_doc(doc);
+                    }
+                    MongoConstants::DIFF_DOC => {
+                        *doc = self.project_update_doc(doc);
+                        keep = !doc.is_empty();
+                    }
+                    // _id and shard keys
+                    _ => {}
+                }
+            }
+        }
+        keep
+    }
+
+    pub fn project_doc(&self, doc: &Document) -> Document {
+        let mut projected = self.project_fields(doc, "");
+        for (from, to) in self.rename.iter() {
+            if let Some(value) = Self::remove_path(&mut projected, from) {
+                Self::insert_path(&mut projected, to, value);
+            }
+        }
+        projected
+    }
+
+    /// {$set: {path: value}, $unset: {path: ""}} with dotted paths from cdc
+    pub fn project_update_doc(&self, update_doc: &Document) -> Document {
+        let mut projected = Document::new();
+        for (op, fields) in update_doc.iter() {
+            let Some(fields) = fields.as_document() else {
+                projected.insert(op, fields.clone());
+                continue;
+            };
+            let mut projected_fields = Document::new();
+            for (path, value) in fields.iter() {
+                if let Some(value) = self.project_value(path, value) {
+                    let (path, value) = self.rename_update_field(path, value);
+                    projected_fields.insert(path, value);
+                }
+            }
+            if !projected_fields.is_empty() {
+                projected.insert(op, projected_fields);
+            }
+        }
+        projected
+    }
+
+    fn project_fields(&self, doc: &Document, prefix: &str) -> Document {
+        let mut projected = Document::new();
+        for (key, value) in doc.iter() {
+            let path = if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            if let Some(value) = self.project_value(&path, value) {
+                projected.insert(key, value);
+            }
+        }
+        projected
+    }
+
+    fn project_value(&self, path: &str, value: &Bson) -> Option<Bson> {
+        if path == MongoConstants::ID {
+            return Some(value.clone());
+        }
+        if self.exclude.iter().any(|i| Self::is_under(path, i)) {
+            return None;
+        }
+
+        let included =
+            self.include.is_empty() || self.include.iter().any(|i| Self::is_under(path, i));
+        let has_rules_inside = self
+            .include
+            .iter()
+            .chain(self.exclude.iter())
+            .any(|i| Self::is_under(i, path) && i != path);
+        if !included && !has_rules_inside {
+            return None;
+        }
+
+        match value {
+            Bson::Document(doc) if has_rules_inside => {
+                let projected = self.project_fields(doc, path);
+                // a parent of included paths without any of them
+                if !included && projected.is_empty() {
+                    return None;
+                }
+                Some(Bson::Document(projected))
+            }
+            _ if included => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn rename_update_field(&self, path: &str, value: Bson) -> (String, Bson) {
+        for (from, to) in self.rename.iter() {
+            if Self::is_under(path, from) {
+                return (format!("{}{}", to, &path[from.len()..]), value);
+            }
+        }
+
+        // renames inside a document set as a whole
+        if let Bson::Document(mut doc) = value {
+            let prefix = format!("{}.", path);
+            for (from, to) in self.rename.iter() {
+                if let (Some(from), Some(to)) =
+                    (from.strip_prefix(&prefix), to.strip_prefix(&prefix))
+                {
+                    if let Some(v) = Self::remove_path(&mut doc, from) {
+                        Self::insert_path(&mut doc, to, v);
+                    }
+                }
+            }
+            return (path.to_string(), Bson::Document(doc));
+        }
+        (path.to_string(), value)
+    }
+
+    // whether path equals parent or is nested in it
+    fn is_under(path: &str, parent: &str) -> bool {
+        path == parent
+            || (path.starts_with(parent) && path.as_bytes().get(parent.len()) == Some(&b'.'))
+    }
+
+    fn remove_path(doc: &mut Document, path: &str) -> Option<Bson> {
+        match path.split_once('.') {
+            None => doc.remove(path),
+            Some((key, rest)) => match doc.get_mut(key) {
+                Some(Bson::Document(sub_doc)) => Self::remove_path(sub_doc, rest),
+                _ => None,
+            },
+        }
+    }
+
+    fn insert_path(doc: &mut Document, path: &str, value: Bson) {
+        match path.split_once('.') {
+            None => {
+                doc.insert(path, value);
+            }
+            Some((key, rest)) => {
+                if !matches!(doc.get(key), Some(Bson::Document(_))) {
+                    doc.insert(key, Document::new());
+                }
+                if let Some(Bson::Document(sub_doc)) = doc.get_mut(key) {
+                    Self::insert_path(sub_doc, rest, value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::*;
+
+    #[test]
+    fn test_project() {
+        let projection = MongoProjection {
+            include: vec!["name".into(), "profile".into(), "tags".into()],
+            exclude: vec!["profile.password".into()],
+            rename: BTreeMap::from([
+                ("name".to_string(), "full_name".to_string()),
+                ("profile.email".to_string(), "profile.mail".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        let doc = doc! {
+            "_id": 1,
+            "name": "a",
+            "age": 20,
+            "profile": {"email": "a@b.c", "password": "x", "city": "sz"},
+            "history": [1, 2, 3],
+        };
+        assert_eq!(
+            projection.project_doc(&doc),
+            doc! {
+                "_id": 1,
+                "profile": {"city": "sz", "mail": "a@b.c"},
+                "full_name": "a",
+            }
+        );
+
+        let update_doc = doc! {
+            "$set": {"name": "b", "age": 21, "profile.password": "y", "profile": {"email": "b@c.d", "password": "z"}},
+            "$unset": {"history": ""},
+        };
+        assert_eq!(
+            projection.project_update_doc(&update_doc),
+            doc! {"$set": {"full_name": "b", "profile": {"mail": "b@c.d"}}}
+        );
+
+        // parents of included paths keep only them
+        let projection = MongoProjection {
+            include: vec!["profile.email".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            projection.project_doc(&doc),
+            doc! {"_id": 1, "profile": {"email": "a@b.c"}}
+        );
+    }
+}
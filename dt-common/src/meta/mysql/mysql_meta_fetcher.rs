@@ -6,13 +6,16 @@ use sqlx::{mysql::MySqlRow, MySql, Pool, Row};
 
 use super::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta};
 use crate::{
-    config::config_enums::DbType,
+    config::{config_enums::DbType, pipeline_config::TableOverrides},
     error::Error,
     meta::{
         ddl_meta::ddl_data::DdlData, foreign_key::ForeignKey, rdb_meta_manager::RdbMetaManager,
         rdb_meta_manager::RDB_PRIMARY_KEY_FLAG, rdb_tb_meta::RdbTbMeta, row_data::RowData,
     },
-    utils::sql_util::SqlUtil,
+    utils::{
+        query_timeout::{QueryTimeout, META_STAGE},
+        sql_util::SqlUtil,
+    },
 };
 
 #[derive(Clone)]
@@ -21,6 +24,8 @@ pub struct MysqlMetaFetcher {
     pub cache: HashMap<String, MysqlTbMeta>,
     pub version: String,
     pub db_type: DbType,
+    // key_cols of [pipeline].table_overrides by target names, empty for source metas
+    pub key_overrides: TableOverrides,
 }
 
 const COLUMN_NAME: &str = "COLUMN_NAME";
@@ -31,6 +36,7 @@ const CHARACTER_SET_NAME: &str = "CHARACTER_SET_NAME";
 const NUMERIC_PRECISION: &str = "NUMERIC_PRECISION";
 const NUMERIC_SCALE: &str = "NUMERIC_SCALE";
 const IS_NULLABLE: &str = "IS_NULLABLE";
+const EXTRA: &str = "EXTRA";
 
 impl MysqlMetaFetcher {
     pub async fn new(conn_pool: Pool<MySql>) -> anyhow::Result<Self> {
@@ -50,6 +56,7 @@ impl MysqlMetaFetcher {
             cache: HashMap::new(),
             version: String::new(),
             db_type,
+            key_overrides: TableOverrides::default(),
         };
         me.init_version().await?;
         Ok(me)
@@ -84,7 +91,7 @@ impl MysqlMetaFetcher {
     ) -> anyhow::Result<&'a MysqlTbMeta> {
         let full_name = format!("{}.{}", schema, tb);
         if !self.cache.contains_key(&full_name) {
-            let (cols, col_origin_type_map, col_type_map, nullable_cols) =
+            let (cols, col_origin_type_map, col_type_map, nullable_cols, generated_cols) =
                 Self::parse_cols(&self.conn_pool, &self.db_type, schema, tb).await?;
             let key_map = Self::parse_keys(&self.conn_pool, schema, tb).await?;
             let (order_cols, partition_col, id_cols) =
@@ -95,7 +102,7 @@ impl MysqlMetaFetcher {
             // let (foreign_keys, ref_by_foreign_keys) =
             //     Self::get_foreign_keys(&self.conn_pool, &self.db_type, schema, tb).await?;
 
-            let basic = RdbTbMeta {
+            let mut basic = RdbTbMeta {
                 schema: schema.to_string(),
                 tb: tb.to_string(),
                 cols,
@@ -108,9 +115,13 @@ impl MysqlMetaFetcher {
                 foreign_keys,
                 ref_by_foreign_keys,
             };
+            if let Some(key_cols) = self.key_overrides.key_cols(schema, tb) {
+                basic.override_key(key_cols)?;
+            }
             let tb_meta = MysqlTbMeta {
                 basic,
                 col_type_map,
+                generated_cols,
             };
             self.cache.insert(full_name.clone(), tb_meta);
         }
@@ -127,11 +138,13 @@ impl MysqlMetaFetcher {
         HashMap<String, String>,
         HashMap<String, MysqlColType>,
         HashSet<String>,
+        HashSet<String>,
     )> {
         let mut cols = Vec::new();
         let mut col_origin_type_map = HashMap::new();
         let mut col_type_map = HashMap::new();
         let mut nullable_cols = HashSet::new();
+        let mut generated_cols = HashSet::new();
 
         let sql = if matches!(db_type, DbType::Mysql) {
             "SELECT * FROM information_schema.columns
@@ -152,7 +165,11 @@ impl MysqlMetaFetcher {
             sqlx::raw_sql(&sql).fetch(conn_pool)
         };
 
-        while let Some(row) = rows.try_next().await? {
+        while let Some(row) = rows
+            .try_next()
+            .await
+            .map_err(|error| QueryTimeout::map_err(error, META_STAGE, &sql, schema, tb))?
+        {
             let col = SqlUtil::try_get_mysql_string(&row, COLUMN_NAME)?;
             // Column and index names are not case sensitive on any platform, nor are column aliases.
             cols.push(col.clone());
@@ -162,6 +179,12 @@ impl MysqlMetaFetcher {
 
             let is_nullable =
                 SqlUtil::try_get_mysql_string(&row, IS_NULLABLE)?.to_lowercase() == "yes";
+            // EXTRA: VIRTUAL GENERATED / STORED GENERATED, not DEFAULT_GENERATED for expression defaults
+            let extra = SqlUtil::try_get_mysql_optional_string(&row, EXTRA)?.unwrap_or_default();
+            if MysqlTbMeta::is_generated_extra(&extra) {
+                generated_cols.insert(col.clone());
+            }
+
             if is_nullable {
                 nullable_cols.insert(col);
             }
@@ -173,7 +196,13 @@ impl MysqlMetaFetcher {
                     schema, tb
             )) }
         }
-        Ok((cols, col_origin_type_map, col_type_map, nullable_cols))
+        Ok((
+            cols,
+            col_origin_type_map,
+            col_type_map,
+            nullable_cols,
+            generated_cols,
+        ))
     }
 
     async fn get_col_type(row: &MySqlRow) -> anyhow::Result<(String, MysqlColType)> {
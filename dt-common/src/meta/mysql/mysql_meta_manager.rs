@@ -7,12 +7,19 @@ use super::{
     mysql_tb_meta::MysqlTbMeta,
 };
 use crate::meta::{mysql::mysql_col_type::MysqlColType, row_data::RowData};
-use crate::{config::config_enums::DbType, meta::ddl_meta::ddl_data::DdlData};
+use crate::{
+    config::{config_enums::DbType, pipeline_config::TableOverrides},
+    meta::ddl_meta::ddl_data::DdlData,
+};
 
 #[derive(Clone)]
 pub struct MysqlMetaManager {
     pub meta_center: Option<MysqlDbEngineMetaCenter>,
     pub meta_fetcher: MysqlMetaFetcher,
+    // bumped whenever cached metas are invalidated, e.g. by ddl, cdc rows are tagged with it
+    pub meta_version: u64,
+    // meta version of the latest rows the cached metas were used for
+    synced_meta_version: u64,
 }
 
 impl MysqlMetaManager {
@@ -34,10 +41,28 @@ impl MysqlMetaManager {
         Ok(Self {
             meta_center: None,
             meta_fetcher: MysqlMetaFetcher::new_mysql_compatible(conn_pool, db_type).await?,
+            meta_version: 0,
+            synced_meta_version: 0,
         })
     }
 
+    /// Replaces keys of target tables by key_cols of [pipeline].table_overrides.
+    pub fn with_key_overrides(mut self, key_overrides: TableOverrides) -> Self {
+        self.meta_fetcher.key_overrides = key_overrides;
+        self
+    }
+
+    /// Drops all cached metas once rows of another meta version arrive, the source schema
+    /// changed in between, and so may the target, e.g. by the ddl or out of band.
+    pub fn sync_meta_version(&mut self, row_meta_version: u64) {
+        if row_meta_version != self.synced_meta_version {
+            self.invalidate_cache("", "");
+            self.synced_meta_version = row_meta_version;
+        }
+    }
+
     pub fn invalidate_cache(&mut self, schema: &str, tb: &str) {
+        self.meta_version += 1;
         if let Some(meta_center) = &mut self.meta_center {
             meta_center.meta_fetcher.invalidate_cache(schema, tb);
         }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::Context;
 use serde::Serialize;
@@ -12,6 +12,8 @@ use super::mysql_col_type::MysqlColType;
 pub struct MysqlTbMeta {
     pub basic: RdbTbMeta,
     pub col_type_map: HashMap<String, MysqlColType>,
+    // virtual / stored generated columns, values can not be written to them
+    pub generated_cols: HashSet<String>,
 }
 
 impl std::fmt::Display for MysqlTbMeta {
@@ -30,4 +32,16 @@ impl MysqlTbMeta {
             .unwrap();
         Ok(col_type)
     }
+
+    #[inline(always)]
+    pub fn is_col_generated(&self, col: &str) -> bool {
+        self.generated_cols.contains(col)
+    }
+
+    /// Whether information_schema.columns.EXTRA is of a generated column,
+    /// DEFAULT_GENERATED of expression defaults is not.
+    pub fn is_generated_extra(extra: &str) -> bool {
+        let extra = extra.to_uppercase();
+        extra.contains("VIRTUAL GENERATED") || extra.contains("STORED GENERATED")
+    }
 }
@@ -1,8 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
+    config::pipeline_config::TableOverrides,
     error::Error,
     meta::{ddl_meta::ddl_data::DdlData, rdb_meta_manager::RDB_PRIMARY_KEY_FLAG},
+    utils::query_timeout::{QueryTimeout, META_STAGE},
 };
 use anyhow::{bail, Context};
 use futures::TryStreamExt;
@@ -21,6 +23,13 @@ pub struct PgMetaManager {
     pub type_registry: TypeRegistry,
     pub name_to_tb_meta: HashMap<String, PgTbMeta>,
     pub oid_to_tb_meta: HashMap<i32, PgTbMeta>,
+    // bumped whenever cached metas are invalidated or replaced, e.g. by ddl or relation
+    // messages of pgoutput, cdc rows are tagged with it
+    pub meta_version: u64,
+    // meta version of the latest rows the cached metas were used for
+    synced_meta_version: u64,
+    // key_cols of [pipeline].table_overrides by target names, empty for source metas
+    key_overrides: TableOverrides,
 }
 
 impl PgMetaManager {
@@ -31,11 +40,20 @@ impl PgMetaManager {
             type_registry,
             name_to_tb_meta: HashMap::new(),
             oid_to_tb_meta: HashMap::new(),
+            meta_version: 0,
+            synced_meta_version: 0,
+            key_overrides: TableOverrides::default(),
         };
         me.type_registry = me.type_registry.init().await?;
         Ok(me)
     }
 
+    /// Replaces keys of target tables by key_cols of [pipeline].table_overrides.
+    pub fn with_key_overrides(mut self, key_overrides: TableOverrides) -> Self {
+        self.key_overrides = key_overrides;
+        self
+    }
+
     pub async fn close(&self) -> anyhow::Result<()> {
         Ok(())
     }
@@ -50,6 +68,7 @@ impl PgMetaManager {
     }
 
     pub fn update_tb_meta_by_oid(&mut self, oid: i32, tb_meta: PgTbMeta) -> anyhow::Result<()> {
+        self.meta_version += 1;
         self.oid_to_tb_meta.insert(oid, tb_meta.clone());
         let full_name = format!(r#""{}"."{}""#, &tb_meta.basic.schema, &tb_meta.basic.tb);
         self.name_to_tb_meta.insert(full_name, tb_meta);
@@ -95,7 +114,7 @@ impl PgMetaManager {
             // let (foreign_keys, ref_by_foreign_keys) =
             //     Self::get_foreign_keys(&self.conn_pool, schema, tb).await?;
 
-            let basic = RdbTbMeta {
+            let mut basic = RdbTbMeta {
                 schema: schema.to_string(),
                 tb: tb.to_string(),
                 cols,
@@ -108,6 +127,9 @@ impl PgMetaManager {
                 foreign_keys,
                 ref_by_foreign_keys,
             };
+            if let Some(key_cols) = self.key_overrides.key_cols(schema, tb) {
+                basic.override_key(key_cols)?;
+            }
             let tb_meta = PgTbMeta {
                 oid,
                 col_type_map,
@@ -119,7 +141,17 @@ impl PgMetaManager {
         Ok(self.name_to_tb_meta.get(&full_name).unwrap())
     }
 
+    /// Drops all cached metas once rows of another meta version arrive, the source schema
+    /// changed in between, and so may the target, e.g. by the ddl or out of band.
+    pub fn sync_meta_version(&mut self, row_meta_version: u64) {
+        if row_meta_version != self.synced_meta_version {
+            self.invalidate_cache("", "");
+            self.synced_meta_version = row_meta_version;
+        }
+    }
+
     pub fn invalidate_cache_for_table(&mut self, schema: &str, tb: &str) {
+        self.meta_version += 1;
         if !schema.is_empty() && !tb.is_empty() {
             let full_name = format!(r#""{}"."{}""#, schema, tb);
             if let Some(tb_meta) = self.name_to_tb_meta.remove(&full_name) {
@@ -129,6 +161,7 @@ impl PgMetaManager {
     }
 
     pub fn invalidate_cache(&mut self, schema: &str, tb: &str) {
+        self.meta_version += 1;
         // TODO, if schema is not empty but tb is empty, only clear cache for the schema
         if !schema.is_empty() && !tb.is_empty() {
             let full_name = format!(r#""{}"."{}""#, schema, tb);
@@ -167,7 +200,11 @@ impl PgMetaManager {
             schema, tb
         );
         let mut rows = sqlx::query(&sql).fetch(conn_pool);
-        while let Some(row) = rows.try_next().await? {
+        while let Some(row) = rows
+            .try_next()
+            .await
+            .map_err(|error| QueryTimeout::map_err(error, META_STAGE, &sql, schema, tb))?
+        {
             let col: String = row.try_get("column_name")?;
             cols.push(col.clone());
 
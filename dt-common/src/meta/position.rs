@@ -5,6 +5,7 @@ use chrono::{DateTime, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
+use crate::log_encoder::JsonLineEncoder;
 use crate::log_error;
 use crate::meta::order_key::OrderKey;
 
@@ -28,6 +29,12 @@ pub enum Position {
         db_type: String,
         schema: String,
         tb: String,
+        // low watermark, set if [extractor].snapshot_watermark=true:
+        // rows extracted and the cdc position from which streaming must begin for this table
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        row_count: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cdc_position: Option<Box<Position>>,
     },
     MysqlCdc {
         server_id: String,
@@ -35,6 +42,9 @@ pub enum Position {
         next_event_position: u32,
         gtid_set: String,
         timestamp: String,
+        // id of the source instance in multi-source tasks, empty for [extractor].url
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        source_id: String,
     },
     PgCdc {
         lsn: String,
@@ -56,6 +66,11 @@ pub enum Position {
         now_db_id: i64,
         timestamp: String,
     },
+    // offline import, rows before row_index of the chunk file are pushed
+    ChunkFile {
+        file: String,
+        row_index: u64,
+    },
 }
 
 impl Position {
@@ -91,6 +106,8 @@ impl Position {
         if log.trim().is_empty() {
             return Position::None;
         }
+        // lines of [runtime] log_format=json
+        let log = &JsonLineEncoder::to_text_line(log.trim());
 
         let left = log.find('{');
         let right = log.rfind('}');
@@ -154,6 +171,8 @@ mod test {
             r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","order_key":{"composite":[["f_0","127"],["f_1","128"]]}}"#,
             r#"{"type":"Redis","repl_id":"repl-1","repl_port":10008,"repl_offset":123,"now_db_id":0,"timestamp":"2026-06-09 12:00:00.000"}"#,
             r#"{"type":"Redis","node_id":"node-1","address":"127.0.0.1:6371","repl_id":"repl-1","repl_port":10008,"repl_offset":123,"now_db_id":0,"timestamp":"2026-06-09 12:00:00.000"}"#,
+            r#"{"type":"RdbSnapshotFinished","db_type":"mysql","schema":"test_db_1","tb":"numeric_table"}"#,
+            r#"{"type":"RdbSnapshotFinished","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","row_count":10,"cdc_position":{"type":"MysqlCdc","server_id":"","binlog_filename":"mysql-bin.000003","next_event_position":157,"gtid_set":"","timestamp":""}}"#,
         ];
 
         let expected = [
@@ -163,6 +182,8 @@ mod test {
             r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","order_key":{"composite":[["f_0","127"],["f_1","128"]]}}"#,
             r#"{"type":"Redis","repl_id":"repl-1","repl_port":10008,"repl_offset":123,"now_db_id":0,"timestamp":"2026-06-09 12:00:00.000"}"#,
             r#"{"type":"Redis","node_id":"node-1","address":"127.0.0.1:6371","repl_id":"repl-1","repl_port":10008,"repl_offset":123,"now_db_id":0,"timestamp":"2026-06-09 12:00:00.000"}"#,
+            r#"{"type":"RdbSnapshotFinished","db_type":"mysql","schema":"test_db_1","tb":"numeric_table"}"#,
+            r#"{"type":"RdbSnapshotFinished","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","row_count":10,"cdc_position":{"type":"MysqlCdc","server_id":"","binlog_filename":"mysql-bin.000003","next_event_position":157,"gtid_set":"","timestamp":""}}"#,
         ];
 
         for (str, expected) in strs.iter().zip(expected.iter()) {
@@ -181,6 +202,7 @@ mod test {
             db_type,
             schema,
             tb,
+            ..
         } = Position::from_log(log1)
         {
             assert_eq!(db_type, "mysql");
@@ -219,6 +241,7 @@ mod test {
             db_type,
             schema,
             tb,
+            ..
         } = Position::from_log(log1)
         {
             assert_eq!(db_type, "mysql");
@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+use super::position::Position;
+
+/// Source metadata of a row, flattened from its position for downstream dedup / ordering,
+/// fields not known for the source are None.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct Provenance {
+    pub source_db_type: String,
+    // id of the source instance in multi-source mysql tasks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    // server_id of the mysql server which executed the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binlog_filename: Option<String>,
+    // end position of the binlog event of the row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binlog_position: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lsn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_token: Option<String>,
+    // commit time of the transaction for pg, event time for mysql / mongo, in utc
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_ts: Option<String>,
+}
+
+impl Provenance {
+    /// None for positions without a source, e.g. kafka, chunk files
+    pub fn from_position(position: &Position) -> Option<Self> {
+        let non_empty = |v: &str| (!v.is_empty()).then(|| v.to_string());
+        let provenance = match position {
+            Position::MysqlCdc {
+                server_id,
+                binlog_filename,
+                next_event_position,
+                timestamp,
+                source_id,
+                ..
+            } => Self {
+                source_db_type: "mysql".into(),
+                source_id: non_empty(source_id),
+                server_id: non_empty(server_id),
+                binlog_filename: non_empty(binlog_filename),
+                binlog_position: Some(*next_event_position),
+                commit_ts: non_empty(timestamp),
+                ..Default::default()
+            },
+            Position::PgCdc { lsn, timestamp } => Self {
+                source_db_type: "pg".into(),
+                lsn: non_empty(lsn),
+                commit_ts: non_empty(timestamp),
+                ..Default::default()
+            },
+            Position::MongoCdc {
+                resume_token,
+                timestamp,
+                ..
+            } => Self {
+                source_db_type: "mongo".into(),
+                resume_token: non_empty(resume_token),
+                commit_ts: non_empty(timestamp),
+                ..Default::default()
+            },
+            Position::RdbSnapshot { db_type, .. }
+            | Position::RdbSnapshotFinished { db_type, .. } => Self {
+                source_db_type: db_type.clone(),
+                ..Default::default()
+            },
+            _ => return None,
+        };
+        Some(provenance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_position() {
+        let position = Position::MysqlCdc {
+            server_id: "1".into(),
+            binlog_filename: "mysql-bin.000003".into(),
+            next_event_position: 157,
+            gtid_set: String::new(),
+            timestamp: "2024-01-01 10:00:00.000".into(),
+            source_id: String::new(),
+        };
+        assert_eq!(
+            serde_json::to_string(&Provenance::from_position(&position)).unwrap(),
+            r#"{"source_db_type":"mysql","server_id":"1","binlog_filename":"mysql-bin.000003","binlog_position":157,"commit_ts":"2024-01-01 10:00:00.000"}"#
+        );
+
+        let position = Position::PgCdc {
+            lsn: "0/1A2B3C4".into(),
+            timestamp: String::new(),
+        };
+        assert_eq!(
+            serde_json::to_string(&Provenance::from_position(&position)).unwrap(),
+            r#"{"source_db_type":"pg","lsn":"0/1A2B3C4"}"#
+        );
+        assert_eq!(Provenance::from_position(&Position::None), None);
+    }
+}
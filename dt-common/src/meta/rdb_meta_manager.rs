@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use anyhow::bail;
 
-use crate::error::Error;
+use crate::{config::pipeline_config::TableOverrides, error::Error};
 
 use super::{
     ddl_meta::ddl_data::DdlData, mysql::mysql_meta_manager::MysqlMetaManager,
@@ -32,6 +32,16 @@ impl RdbMetaManager {
         }
     }
 
+    /// Replaces keys of target tables by key_cols of [pipeline].table_overrides.
+    pub fn with_key_overrides(mut self, key_overrides: TableOverrides) -> Self {
+        if let Some(mysql_meta_manager) = self.mysql_meta_manager.take() {
+            self.mysql_meta_manager = Some(mysql_meta_manager.with_key_overrides(key_overrides));
+        } else if let Some(pg_meta_manager) = self.pg_meta_manager.take() {
+            self.pg_meta_manager = Some(pg_meta_manager.with_key_overrides(key_overrides));
+        }
+        self
+    }
+
     pub async fn close(&self) -> anyhow::Result<()> {
         if let Some(mysql_meta_manager) = &self.mysql_meta_manager {
             mysql_meta_manager.close().await?;
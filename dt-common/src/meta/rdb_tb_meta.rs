@@ -1,12 +1,20 @@
 use std::collections::{HashMap, HashSet};
 
+use anyhow::bail;
 use serde::Serialize;
 
 use crate::{
     config::config_enums::DbType,
-    meta::{col_value::ColValue, foreign_key::ForeignKey, order_key::OrderKey, position::Position},
+    error::Error,
+    meta::{
+        col_value::ColValue, foreign_key::ForeignKey, order_key::OrderKey, position::Position,
+        rdb_meta_manager::RDB_PRIMARY_KEY_FLAG,
+    },
 };
 
+// key_map name of the primary key found in table meta after it is replaced by key_cols
+const PHYSICAL_PRIMARY_KEY_FLAG: &str = "physical_primary";
+
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct RdbTbMeta {
     pub schema: String,
@@ -41,6 +49,29 @@ impl RdbTbMeta {
         self.nullable_cols.contains(col)
     }
 
+    /// Uses key_cols of [pipeline].table_overrides as the primary key. The physical primary
+    /// key is kept as a unique key, so rows changing it are still not partitioned.
+    pub fn override_key(&mut self, key_cols: &[String]) -> anyhow::Result<()> {
+        for col in key_cols.iter() {
+            if !self.has_col(col) {
+                bail!(Error::ConfigError(format!(
+                    "key_cols of [pipeline].table_overrides for {}.{}: column {} not found",
+                    self.schema, self.tb, col
+                )));
+            }
+        }
+
+        if let Some(cols) = self.key_map.remove(RDB_PRIMARY_KEY_FLAG) {
+            self.key_map
+                .insert(PHYSICAL_PRIMARY_KEY_FLAG.to_string(), cols);
+        }
+        self.key_map
+            .insert(RDB_PRIMARY_KEY_FLAG.to_string(), key_cols.to_vec());
+        self.id_cols = key_cols.to_vec();
+        self.partition_col = key_cols[0].clone();
+        Ok(())
+    }
+
     pub fn build_position(
         &self,
         db_type: &DbType,
@@ -90,3 +121,39 @@ impl RdbTbMeta {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_key() {
+        let mut tb_meta = RdbTbMeta {
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+            cols: vec!["uuid".into(), "code".into(), "name".into()],
+            key_map: HashMap::from([(RDB_PRIMARY_KEY_FLAG.to_string(), vec!["uuid".into()])]),
+            order_cols: vec!["uuid".into()],
+            partition_col: "uuid".into(),
+            id_cols: vec!["uuid".into()],
+            ..Default::default()
+        };
+
+        assert!(tb_meta.override_key(&["not_exists".into()]).is_err());
+        assert_eq!(tb_meta.id_cols, vec!["uuid".to_string()]);
+
+        tb_meta.override_key(&["code".into()]).unwrap();
+        assert_eq!(tb_meta.id_cols, vec!["code".to_string()]);
+        assert_eq!(tb_meta.partition_col, "code");
+        assert_eq!(
+            tb_meta.key_map.get(RDB_PRIMARY_KEY_FLAG),
+            Some(&vec!["code".to_string()])
+        );
+        assert_eq!(
+            tb_meta.key_map.get(PHYSICAL_PRIMARY_KEY_FLAG),
+            Some(&vec!["uuid".to_string()])
+        );
+        // snapshot extraction still orders by the physical key
+        assert_eq!(tb_meta.order_cols, vec!["uuid".to_string()]);
+    }
+}
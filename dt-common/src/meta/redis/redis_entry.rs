@@ -14,6 +14,8 @@ pub struct RedisEntry {
     pub timestamp_ms: u64,
 
     pub expire_ms: i64,
+    // absolute unix timestamp of expire_ms, only set for entries decoded from dump.rdb file
+    pub expire_at_ms: i64,
     pub key: RedisString,
     pub value: RedisObject,
     pub value_type_byte: u8,
@@ -34,6 +36,7 @@ impl RedisEntry {
             timestamp_ms: 0,
 
             expire_ms: 0,
+            expire_at_ms: 0,
             key: RedisString::new(),
             value: RedisObject::Unknown,
             raw_bytes: Vec::new(),
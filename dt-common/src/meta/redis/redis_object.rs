@@ -9,6 +9,7 @@ const TYPE_SET: &str = "set";
 const TYPE_ZSET: &str = "zset";
 const TYPE_MODULE: &str = "module";
 const TYPE_STREAM: &str = "stream";
+const TYPE_FUNCTION: &str = "function";
 const TYPE_UNKNOWN: &str = "unknown";
 
 #[derive(Debug, Clone)]
@@ -20,6 +21,7 @@ pub enum RedisObject {
     Zset(ZsetObject),
     Module(ModuleObject),
     Stream(StreamObject),
+    Function(FunctionObject),
     Unknown,
 }
 
@@ -33,6 +35,7 @@ impl RedisObject {
             Self::Zset(_) => TYPE_ZSET,
             Self::Module(_) => TYPE_MODULE,
             Self::Stream(_) => TYPE_STREAM,
+            Self::Function(_) => TYPE_FUNCTION,
             Self::Unknown => TYPE_UNKNOWN,
         }
         .to_string()
@@ -47,6 +50,7 @@ impl RedisObject {
             TYPE_ZSET => Self::Zset(ZsetObject::new()),
             TYPE_MODULE => Self::Module(ModuleObject::new()),
             TYPE_STREAM => Self::Stream(StreamObject::new()),
+            TYPE_FUNCTION => Self::Function(FunctionObject::new()),
             _ => Self::Unknown,
         }
     }
@@ -83,11 +87,31 @@ impl ListObject {
 }
 
 #[derive(Debug, Clone)]
-pub struct ModuleObject {}
+pub struct ModuleObject {
+    // module type name, e.g. ReJSON-RL
+    pub type_name: String,
+}
 
 impl ModuleObject {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            type_name: String::new(),
+        }
+    }
+}
+
+/// a function library (redis 7.0+), not bound to any key or db
+#[derive(Debug, Clone)]
+pub struct FunctionObject {
+    // library code, starts with: #!<engine> name=<library name>
+    pub code: RedisString,
+}
+
+impl FunctionObject {
+    pub fn new() -> Self {
+        Self {
+            code: RedisString::new(),
+        }
     }
 }
 
@@ -256,6 +280,26 @@ impl RedisCmd {
         }
     }
 
+    /// indexes of args which are db indexes: the target db of MOVE / COPY ... DB, the dbs of SWAPDB
+    pub fn get_db_arg_indexes(&self) -> Vec<usize> {
+        let indexes = match self.get_name().to_ascii_lowercase().as_str() {
+            // MOVE key db
+            "move" => vec![2],
+            // SWAPDB index1 index2
+            "swapdb" => vec![1, 2],
+            // COPY source destination [DB destination-db] [REPLACE]
+            "copy" => (3..self.args.len().saturating_sub(1))
+                .find(|i| self.get_str_arg(*i).eq_ignore_ascii_case("db"))
+                .map(|i| vec![i + 1])
+                .unwrap_or_default(),
+            _ => vec![],
+        };
+        indexes
+            .into_iter()
+            .filter(|i| *i < self.args.len())
+            .collect()
+    }
+
     pub fn args_to_string(&self) -> Vec<String> {
         let mut str_args = Vec::new();
         for arg in self.args.iter() {
@@ -335,6 +379,7 @@ impl RedisObject {
                 size + v.key.bytes.len()
             }
             RedisObject::Module(_) => 0,
+            RedisObject::Function(v) => v.code.bytes.len(),
             RedisObject::Unknown => 0,
         }
     }
@@ -1,5 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hasher,
+    sync::Arc,
+};
 
+use ahash::AHasher;
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -7,7 +12,7 @@ use sqlx::{mysql::MySqlRow, postgres::PgRow};
 
 use super::{
     col_value::ColValue, mysql::mysql_tb_meta::MysqlTbMeta, pg::pg_tb_meta::PgTbMeta,
-    rdb_tb_meta::RdbTbMeta, row_type::RowType,
+    position::Position, rdb_tb_meta::RdbTbMeta, row_type::RowType,
 };
 use crate::{
     config::config_enums::DbType,
@@ -30,6 +35,17 @@ pub struct RowData {
     pub after: Option<HashMap<String, ColValue>>,
     pub data_size: usize,
     pub is_not_origin: bool,
+    // source position of the row, only attached by the pipeline for sinkers that need it,
+    // e.g. to generate idempotency keys
+    #[serde(skip)]
+    pub position: Option<Arc<Position>>,
+    // original statement of the row, from mysql ROWS_QUERY events, shared by rows of the statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<Arc<String>>,
+    // meta version of the extractor when the row was parsed, rows of different versions are
+    // never sunk in the same batch, 0 if the extractor does not track versions
+    #[serde(skip)]
+    pub meta_version: u64,
 }
 
 impl std::fmt::Display for RowData {
@@ -56,6 +72,9 @@ impl RowData {
             after,
             data_size: 0,
             is_not_origin: false,
+            position: None,
+            query: None,
+            meta_version: 0,
         };
         me.data_size = me.get_data_malloc_size();
         me
@@ -90,11 +109,14 @@ impl RowData {
             after: self.before.clone(),
             data_size: self.data_size,
             is_not_origin: false,
+            position: self.position.clone(),
+            query: self.query.clone(),
+            meta_version: self.meta_version,
         }
     }
 
     pub fn split_update_row_data(self) -> (RowData, RowData) {
-        let delete = RowData::new_no_origin(
+        let mut delete = RowData::new_no_origin(
             self.schema.clone(),
             self.tb.clone(),
             self.chunk_id,
@@ -103,7 +125,7 @@ impl RowData {
             None,
         );
 
-        let insert = RowData::new_no_origin(
+        let mut insert = RowData::new_no_origin(
             self.schema,
             self.tb,
             self.chunk_id,
@@ -111,6 +133,12 @@ impl RowData {
             None,
             self.after,
         );
+        delete.position = self.position.clone();
+        insert.position = self.position;
+        delete.query = self.query.clone();
+        insert.query = self.query;
+        delete.meta_version = self.meta_version;
+        insert.meta_version = self.meta_version;
         (delete, insert)
     }
 
@@ -257,32 +285,34 @@ impl RowData {
             _ => self.before.as_ref().context("row_data before is missing")?,
         };
 
-        // refer to: https://docs.oracle.com/javase/6/docs/api/java/util/List.html#hashCode()
-        let mut hash_code = 1u128;
+        // all id col values are fed into one ahash hasher, which uses AES instructions when
+        // available (x86 aes / arm aes target features), rows with the same hash code are still
+        // compared by id col values in merger, so collisions only stop merging
+        let mut hasher = AHasher::default();
         for col in tb_meta.id_cols.iter() {
-            let col_hash_code = col_values
+            let hashed = col_values
                 .get(col)
                 .with_context(|| format!("missing id col value: {}", col))?
-                .hash_code()
+                .hash_into(&mut hasher)
                 .with_context(|| {
                     format!(
                         "unhashable _id value in schema: {}, tb: {}, col: {}",
                         tb_meta.schema, tb_meta.tb, col
                     )
                 })?;
-            // col_hash_code is 0 if col_value is ColValue::None,
+            // not hashed if col_value is ColValue::None,
             // consider following case,
             // create table a(id int, value int, unique key(id, value));
             // insert into a values(1, NULL);
             // delete from a where (id, value) in ((1, NULL));  // this won't work
             // delete from a where id=1 and value is NULL;  // this works
             // so here return 0 to stop merging to avoid batch deleting
-            if col_hash_code == 0 {
+            if !hashed {
                 return Ok(0);
             }
-            hash_code = 31 * hash_code + col_hash_code as u128;
         }
-        Ok(hash_code)
+        // 0 is reserved for unmergeable rows
+        Ok((hasher.finish() as u128).max(1))
     }
 
     pub fn contains_unchanged_toast(&self) -> bool {
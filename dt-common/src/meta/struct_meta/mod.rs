@@ -1,3 +1,4 @@
+pub mod pg_to_mysql_converter;
 pub mod statement;
 pub mod struct_data;
 pub mod structure;
@@ -0,0 +1,681 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::log_warn;
+
+use super::{
+    statement::{
+        mysql_create_database_statement::MysqlCreateDatabaseStatement,
+        mysql_create_table_statement::MysqlCreateTableStatement,
+        pg_create_table_statement::PgCreateTableStatement, struct_statement::StructStatement,
+    },
+    structure::{
+        column::{Column, ColumnDefault},
+        constraint::ConstraintType,
+        database::Database,
+        index::{Index, IndexColumn, IndexKind, IndexType},
+        table::Table,
+    },
+};
+
+// max length of varchar in utf8mb4, longer strings are mapped to longtext
+const MAX_VARCHAR_LENGTH: u64 = 16383;
+const MAX_CHAR_LENGTH: u64 = 255;
+const MAX_DECIMAL_PRECISION: u64 = 65;
+const MAX_DECIMAL_SCALE: u64 = 30;
+const MAX_TIME_PRECISION: u64 = 6;
+// prefix length of text / blob columns in secondary indexes
+const INDEX_PREFIX_LENGTH: u64 = 255;
+
+/// Converts pg structures to mysql, used by struct tasks of pg -> mysql.
+///
+/// Column types are mapped by the pg type name, e.g. jsonb -> json, arrays -> json,
+/// type_mappings overrides the defaults, keys: schema.tb.col, pg type, pg type without typmod,
+/// e.g. public.orders.amount / numeric(38,0) / numeric, values: mysql column types.
+#[derive(Debug, Clone, Default)]
+pub struct PgToMysqlConverter {
+    pub type_mappings: HashMap<String, String>,
+}
+
+impl PgToMysqlConverter {
+    /// statements without a mysql counterpart, e.g. pg rbac, udf, udt are returned as Unknown
+    pub fn convert(&self, statement: StructStatement) -> StructStatement {
+        match statement {
+            StructStatement::PgCreateSchema(s) => {
+                StructStatement::MysqlCreateDatabase(MysqlCreateDatabaseStatement {
+                    database: Database {
+                        name: s.schema.name,
+                        ..Default::default()
+                    },
+                })
+            }
+            StructStatement::PgCreateTable(s) => {
+                StructStatement::MysqlCreateTable(self.convert_table(s))
+            }
+            StructStatement::PgCreateRbac(_)
+            | StructStatement::PgCreateUdf(_)
+            | StructStatement::PgCreateUdt(_)
+            | StructStatement::PgCreateObject(_) => {
+                log_warn!(
+                    "pg rbac / udf / udt / view / trigger / routine skipped, not supported by mysql"
+                );
+                StructStatement::Unknown
+            }
+            _ => statement,
+        }
+    }
+
+    fn convert_table(&self, statement: PgCreateTableStatement) -> MysqlCreateTableStatement {
+        let PgCreateTableStatement {
+            table,
+            table_comments,
+            column_comments,
+            constraints,
+            indexes,
+            ..
+        } = statement;
+        let (schema, tb) = (table.schema_name.clone(), table.table_name.clone());
+
+        let mut pk_cols = Vec::new();
+        let mut mysql_indexes = Vec::new();
+        // indexes created along with pk / unique constraints
+        let mut constraint_names = HashSet::new();
+        for i in constraints.iter() {
+            match i.constraint_type {
+                ConstraintType::Primary => {
+                    pk_cols = Self::parse_col_list(&i.definition).unwrap_or_default();
+                    constraint_names.insert(i.constraint_name.clone());
+                }
+                ConstraintType::Unique => {
+                    if let Some(cols) = Self::parse_col_list(&i.definition) {
+                        mysql_indexes.push(Self::build_index(
+                            &i.constraint_name,
+                            IndexKind::Unique,
+                            cols,
+                        ));
+                    }
+                    constraint_names.insert(i.constraint_name.clone());
+                }
+                _ => {
+                    log_warn!(
+                        "constraint: {}.{}.{} skipped, not supported for pg -> mysql, definition: {}",
+                        schema,
+                        tb,
+                        i.constraint_name,
+                        i.definition
+                    );
+                }
+            }
+        }
+
+        for i in indexes.iter() {
+            if constraint_names.contains(&i.index_name) {
+                continue;
+            }
+            match Self::parse_index_cols(&i.definition) {
+                Some(cols) => {
+                    mysql_indexes.push(Self::build_index(&i.index_name, i.index_kind.clone(), cols))
+                }
+                None => log_warn!(
+                    "index: {}.{}.{} skipped, only btree / hash indexes on plain columns are supported for pg -> mysql, definition: {}",
+                    schema,
+                    tb,
+                    i.index_name,
+                    i.definition
+                ),
+            }
+        }
+
+        // the first column of an index can be auto_increment in mysql
+        let leading_cols: HashSet<&str> = pk_cols
+            .first()
+            .into_iter()
+            .chain(
+                mysql_indexes
+                    .iter()
+                    .filter_map(|i| i.columns.first().map(|c| &c.column_name)),
+            )
+            .map(|c| c.as_str())
+            .collect();
+
+        let mut columns = Vec::new();
+        for column in table.columns.iter() {
+            let mut mysql_column = self.convert_column(&schema, &tb, column, &leading_cols);
+            if pk_cols.contains(&column.column_name) {
+                mysql_column.column_key = "PRI".into();
+            }
+            if let Some(comment) = column_comments
+                .iter()
+                .find(|c| c.column_name == column.column_name)
+            {
+                mysql_column.column_comment = comment.comment.clone();
+            }
+            columns.push(mysql_column);
+        }
+
+        // text / blob columns in indexes require prefix lengths
+        for index in mysql_indexes.iter_mut() {
+            for index_col in index.columns.iter_mut() {
+                if let Some(column) = columns
+                    .iter()
+                    .find(|c| c.column_name == index_col.column_name)
+                {
+                    if Self::is_lob_type(&column.column_type) {
+                        index_col.prefix_length = Some(INDEX_PREFIX_LENGTH);
+                    }
+                }
+            }
+            index.database_name = schema.clone();
+            index.table_name = tb.clone();
+        }
+
+        MysqlCreateTableStatement {
+            table: Table {
+                database_name: schema,
+                table_name: tb,
+                table_comment: table_comments
+                    .first()
+                    .map(|c| c.comment.clone())
+                    .unwrap_or_default(),
+                columns,
+                ..Default::default()
+            },
+            constraints: vec![],
+            indexes: mysql_indexes,
+        }
+    }
+
+    fn convert_column(
+        &self,
+        schema: &str,
+        tb: &str,
+        column: &Column,
+        leading_cols: &HashSet<&str>,
+    ) -> Column {
+        let column_type =
+            self.get_mysql_col_type(schema, tb, &column.column_name, &column.column_type);
+        let is_integer = ["tinyint", "smallint", "mediumint", "int", "bigint"]
+            .contains(&Self::base_type(&column_type).as_str());
+
+        let mut extra = String::new();
+        let mut column_default = None;
+        let is_serial = match &column.column_default {
+            Some(ColumnDefault::Literal(v)) | Some(ColumnDefault::Expression(v)) => {
+                v.starts_with("nextval(")
+            }
+            None => false,
+        };
+        if column.generated.is_some() || is_serial {
+            if is_integer && leading_cols.contains(column.column_name.as_str()) {
+                extra = "auto_increment".into();
+            } else {
+                log_warn!(
+                    "auto increment of column: {}.{}.{} skipped, it is not an integer key column in mysql",
+                    schema,
+                    tb,
+                    column.column_name
+                );
+            }
+        } else if let Some(ColumnDefault::Literal(v)) | Some(ColumnDefault::Expression(v)) =
+            &column.column_default
+        {
+            column_default = Self::convert_default(v, &column_type);
+            if column_default.is_none() {
+                log_warn!(
+                    "default of column: {}.{}.{} skipped, not supported for pg -> mysql, default: {}",
+                    schema,
+                    tb,
+                    column.column_name,
+                    v
+                );
+            }
+        }
+
+        Column {
+            column_name: column.column_name.clone(),
+            ordinal_position: column.ordinal_position,
+            column_default,
+            is_nullable: column.is_nullable,
+            column_type,
+            extra,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_mysql_col_type(&self, schema: &str, tb: &str, col: &str, pg_type: &str) -> String {
+        let (type_name, args) = Self::split_typmod(pg_type);
+        for key in [
+            format!("{}.{}.{}", schema, tb, col),
+            pg_type.into(),
+            type_name.clone(),
+        ] {
+            if let Some(mysql_type) = self.type_mappings.get(&key) {
+                return mysql_type.clone();
+            }
+        }
+
+        if type_name.ends_with("[]") {
+            return "json".into();
+        }
+
+        let arg = |i: usize| args.get(i).copied();
+        match type_name.as_str() {
+            "smallint" => "smallint".into(),
+            "integer" => "int".into(),
+            "bigint" => "bigint".into(),
+            "oid" => "int unsigned".into(),
+            "real" => "float".into(),
+            "double precision" => "double".into(),
+            "numeric" => match (arg(0), arg(1)) {
+                (Some(p), s) => format!(
+                    "decimal({},{})",
+                    p.min(MAX_DECIMAL_PRECISION),
+                    s.unwrap_or(0).min(MAX_DECIMAL_SCALE)
+                ),
+                // numeric without precision keeps any scale, use the max of mysql
+                (None, _) => format!("decimal({},{})", MAX_DECIMAL_PRECISION, MAX_DECIMAL_SCALE),
+            },
+            "boolean" => "tinyint(1)".into(),
+            "character" => match arg(0).unwrap_or(1) {
+                n if n <= MAX_CHAR_LENGTH => format!("char({})", n),
+                n if n <= MAX_VARCHAR_LENGTH => format!("varchar({})", n),
+                _ => "longtext".into(),
+            },
+            "character varying" => match arg(0) {
+                Some(n) if n <= MAX_VARCHAR_LENGTH => format!("varchar({})", n),
+                _ => "longtext".into(),
+            },
+            "\"char\"" => "char(1)".into(),
+            "name" => "varchar(64)".into(),
+            "text" | "citext" | "xml" | "tsvector" | "tsquery" | "hstore" => "longtext".into(),
+            "bytea" => "longblob".into(),
+            // values with utc offsets are converted to utc when written
+            "timestamp without time zone" | "timestamp with time zone" => format!(
+                "datetime({})",
+                arg(0).unwrap_or(MAX_TIME_PRECISION).min(MAX_TIME_PRECISION)
+            ),
+            "time without time zone" => format!(
+                "time({})",
+                arg(0).unwrap_or(MAX_TIME_PRECISION).min(MAX_TIME_PRECISION)
+            ),
+            "time with time zone" => "varchar(32)".into(),
+            "date" => "date".into(),
+            "uuid" => "char(36)".into(),
+            "json" | "jsonb" => "json".into(),
+            "inet" | "cidr" => "varchar(43)".into(),
+            "macaddr" => "varchar(17)".into(),
+            "macaddr8" => "varchar(23)".into(),
+            "money" => "varchar(32)".into(),
+            "bit" | "bit varying" => match arg(0) {
+                Some(n) if n <= MAX_VARCHAR_LENGTH => format!("varchar({})", n),
+                _ => "longtext".into(),
+            },
+            t if t.starts_with("interval") => "varchar(64)".into(),
+            _ => {
+                log_warn!(
+                    "column: {}.{}.{} of pg type: {} mapped to longtext, set [sinker].type_mappings to override",
+                    schema,
+                    tb,
+                    col,
+                    pg_type
+                );
+                "longtext".into()
+            }
+        }
+    }
+
+    fn convert_default(pg_default: &str, mysql_type: &str) -> Option<ColumnDefault> {
+        let mut v = pg_default.trim();
+        while v.starts_with('(') && v.ends_with(')') {
+            v = &v[1..v.len() - 1];
+        }
+        if v.starts_with("NULL") {
+            return None;
+        }
+
+        let base_type = Self::base_type(mysql_type);
+        let literal = if let Some(rest) = v.strip_prefix('\'') {
+            // 'abc'::character varying, 'it''s'::text
+            let end = rest.rfind('\'')?;
+            rest[..end].replace("''", "'")
+        } else if v.parse::<f64>().is_ok() {
+            v.to_string()
+        } else if v == "true" || v == "false" {
+            String::from(if v == "true" { "1" } else { "0" })
+        } else {
+            let expr = v.to_lowercase();
+            return match expr.as_str() {
+                "now()"
+                | "current_timestamp"
+                | "transaction_timestamp()"
+                | "statement_timestamp()"
+                | "clock_timestamp()"
+                | "localtimestamp"
+                    if base_type == "datetime" =>
+                {
+                    // precision of the default must be the same as the column
+                    let precision = Self::split_typmod(mysql_type).1.first().copied();
+                    match precision {
+                        Some(p) if p > 0 => Some(ColumnDefault::Expression(format!(
+                            "CURRENT_TIMESTAMP({})",
+                            p
+                        ))),
+                        _ => Some(ColumnDefault::Expression("CURRENT_TIMESTAMP".into())),
+                    }
+                }
+                "current_date" if base_type == "date" => {
+                    Some(ColumnDefault::Expression("(CURRENT_DATE)".into()))
+                }
+                _ => None,
+            };
+        };
+
+        // literal defaults of text / blob / json columns are only supported as expressions
+        if Self::is_lob_type(mysql_type) {
+            return Some(ColumnDefault::Expression(format!(
+                "('{}')",
+                literal.replace('\'', "''")
+            )));
+        }
+        Some(ColumnDefault::Literal(literal))
+    }
+
+    fn build_index(name: &str, index_kind: IndexKind, cols: Vec<String>) -> Index {
+        Index {
+            index_name: name.into(),
+            index_kind,
+            index_type: IndexType::Btree,
+            columns: cols
+                .into_iter()
+                .enumerate()
+                .map(|(i, column_name)| IndexColumn {
+                    column_name,
+                    seq_in_index: i as u32 + 1,
+                    prefix_length: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// CREATE UNIQUE INDEX idx ON public.tb USING btree (a, b DESC),
+    /// None for expression / partial indexes and index methods other than btree / hash
+    fn parse_index_cols(definition: &str) -> Option<Vec<String>> {
+        let (_, rest) = definition.split_once(" USING ")?;
+        let method = rest.split_whitespace().next()?;
+        if method != "btree" && method != "hash" {
+            return None;
+        }
+        if rest.contains(" WHERE ") {
+            return None;
+        }
+        let cols_str = Self::get_parenthesized(rest)?;
+        let mut cols = Vec::new();
+        for item in Self::split_identifiers(cols_str) {
+            // a DESC NULLS FIRST
+            let col = Self::unquote_identifier(item.split_whitespace().next()?);
+            if col.contains('(') || col.contains(')') {
+                return None;
+            }
+            cols.push(col);
+        }
+        Some(cols)
+    }
+
+    /// PRIMARY KEY (id, "Name"), UNIQUE NULLS NOT DISTINCT (email)
+    fn parse_col_list(definition: &str) -> Option<Vec<String>> {
+        let cols_str = Self::get_parenthesized(definition)?;
+        Some(
+            Self::split_identifiers(cols_str)
+                .into_iter()
+                .map(|i| Self::unquote_identifier(&i))
+                .collect(),
+        )
+    }
+
+    // content of the first top level parentheses
+    fn get_parenthesized(text: &str) -> Option<&str> {
+        let start = text.find('(')?;
+        let (mut depth, mut in_quote) = (0, false);
+        for (i, c) in text[start..].char_indices() {
+            match c {
+                '"' => in_quote = !in_quote,
+                '(' if !in_quote => depth += 1,
+                ')' if !in_quote => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(&text[start + 1..start + i]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // split by commas out of quotes and parentheses
+    fn split_identifiers(text: &str) -> Vec<String> {
+        let (mut items, mut current) = (Vec::new(), String::new());
+        let (mut depth, mut in_quote) = (0, false);
+        for c in text.chars() {
+            match c {
+                '"' => in_quote = !in_quote,
+                '(' if !in_quote => depth += 1,
+                ')' if !in_quote => depth -= 1,
+                ',' if !in_quote && depth == 0 => {
+                    items.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            current.push(c);
+        }
+        if !current.trim().is_empty() {
+            items.push(current.trim().to_string());
+        }
+        items
+    }
+
+    fn unquote_identifier(identifier: &str) -> String {
+        let identifier = identifier.trim();
+        match identifier
+            .strip_prefix('"')
+            .and_then(|i| i.strip_suffix('"'))
+        {
+            Some(i) => i.replace("\"\"", "\""),
+            None => identifier.to_string(),
+        }
+    }
+
+    /// numeric(10,2) -> (numeric, [10, 2]),
+    /// timestamp(3) with time zone -> (timestamp with time zone, [3]),
+    /// character varying(10)[] -> (character varying[], [10])
+    fn split_typmod(pg_type: &str) -> (String, Vec<u64>) {
+        let pg_type = pg_type.trim();
+        match (pg_type.find('('), pg_type.find(')')) {
+            (Some(start), Some(end)) if start < end => {
+                let args = pg_type[start + 1..end]
+                    .split(',')
+                    .filter_map(|i| i.trim().parse().ok())
+                    .collect();
+                let type_name = format!("{}{}", &pg_type[..start], &pg_type[end + 1..]);
+                (type_name.trim().to_string(), args)
+            }
+            _ => (pg_type.to_string(), vec![]),
+        }
+    }
+
+    fn base_type(mysql_type: &str) -> String {
+        Self::split_typmod(mysql_type)
+            .0
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+
+    fn is_lob_type(mysql_type: &str) -> bool {
+        let base_type = Self::base_type(mysql_type);
+        base_type.ends_with("text") || base_type.ends_with("blob") || base_type == "json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{config_enums::DbType, filter_config::FilterConfig},
+        meta::struct_meta::structure::constraint::Constraint,
+        rdb_filter::RdbFilter,
+    };
+
+    fn column(name: &str, pg_type: &str, ordinal_position: u32) -> Column {
+        Column {
+            column_name: name.into(),
+            column_type: pg_type.into(),
+            ordinal_position,
+            is_nullable: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_get_mysql_col_type() {
+        let converter = PgToMysqlConverter {
+            type_mappings: HashMap::from([
+                ("uuid".to_string(), "binary(16)".to_string()),
+                (
+                    "public.tb_1.amount".to_string(),
+                    "decimal(20,4)".to_string(),
+                ),
+            ]),
+        };
+        let cases = [
+            ("integer", "int"),
+            ("numeric(10,2)", "decimal(10,2)"),
+            ("numeric(100,40)", "decimal(65,30)"),
+            ("numeric", "decimal(65,30)"),
+            ("character varying(100)", "varchar(100)"),
+            ("character varying", "longtext"),
+            ("character(300)", "varchar(300)"),
+            ("text", "longtext"),
+            ("bytea", "longblob"),
+            ("timestamp(3) with time zone", "datetime(3)"),
+            ("timestamp without time zone", "datetime(6)"),
+            ("time(0) without time zone", "time(0)"),
+            ("jsonb", "json"),
+            ("integer[]", "json"),
+            ("character varying(10)[]", "json"),
+            ("interval year to month", "varchar(64)"),
+            ("uuid", "binary(16)"),
+        ];
+        for (pg_type, mysql_type) in cases {
+            assert_eq!(
+                converter.get_mysql_col_type("public", "tb_1", "col", pg_type),
+                mysql_type,
+                "{}",
+                pg_type
+            );
+        }
+        assert_eq!(
+            converter.get_mysql_col_type("public", "tb_1", "amount", "numeric"),
+            "decimal(20,4)"
+        );
+    }
+
+    #[test]
+    fn test_convert_table() {
+        let mut id = column("id", "integer", 1);
+        id.is_nullable = false;
+        id.column_default = Some(ColumnDefault::Literal(
+            "nextval('tb_1_id_seq'::regclass)".into(),
+        ));
+        let mut name = column("Name", "character varying(50)", 2);
+        name.column_default = Some(ColumnDefault::Literal("'it''s'::character varying".into()));
+        let mut note = column("note", "text", 3);
+        note.column_default = Some(ColumnDefault::Literal("'none'::text".into()));
+        let mut created_at = column("created_at", "timestamp(3) with time zone", 4);
+        created_at.column_default = Some(ColumnDefault::Literal("now()".into()));
+
+        let constraint = |name: &str, constraint_type, definition: &str| Constraint {
+            database_name: String::new(),
+            schema_name: "public".into(),
+            table_name: "tb_1".into(),
+            constraint_name: name.into(),
+            constraint_type,
+            definition: definition.into(),
+        };
+        let index = |name: &str, definition: &str| Index {
+            index_name: name.into(),
+            definition: definition.into(),
+            ..Default::default()
+        };
+        let statement = PgCreateTableStatement {
+            table: Table {
+                schema_name: "public".into(),
+                table_name: "tb_1".into(),
+                columns: vec![id, name, note, created_at],
+                ..Default::default()
+            },
+            table_comments: vec![],
+            column_comments: vec![],
+            constraint_comments: vec![],
+            constraints: vec![
+                constraint("tb_1_pkey", ConstraintType::Primary, "PRIMARY KEY (id)"),
+                constraint(
+                    "tb_1_name_key",
+                    ConstraintType::Unique,
+                    r#"UNIQUE ("Name")"#,
+                ),
+                constraint("tb_1_check", ConstraintType::Check, "CHECK ((id > 0))"),
+            ],
+            indexes: vec![
+                index(
+                    "tb_1_pkey",
+                    "CREATE UNIQUE INDEX tb_1_pkey ON public.tb_1 USING btree (id)",
+                ),
+                index(
+                    "idx_note",
+                    "CREATE INDEX idx_note ON public.tb_1 USING btree (note, created_at DESC)",
+                ),
+                index(
+                    "idx_lower",
+                    "CREATE INDEX idx_lower ON public.tb_1 USING btree (lower(note))",
+                ),
+                index(
+                    "idx_gin",
+                    "CREATE INDEX idx_gin ON public.tb_1 USING gin (note)",
+                ),
+            ],
+            sequences: vec![],
+            sequence_owners: vec![],
+        };
+
+        let converter = PgToMysqlConverter::default();
+        let StructStatement::MysqlCreateTable(mut statement) =
+            converter.convert(StructStatement::PgCreateTable(statement))
+        else {
+            panic!("not converted to mysql create table");
+        };
+        let filter_config = FilterConfig {
+            do_structures: "*".into(),
+            ..Default::default()
+        };
+        let filter = RdbFilter::from_config(&filter_config, &DbType::Mysql).unwrap();
+        let sqls = statement.to_sqls(&filter).unwrap();
+        assert_eq!(
+            sqls[0].1,
+            "CREATE TABLE IF NOT EXISTS `public`.`tb_1` (`id` int auto_increment NOT NULL, \
+            `Name` varchar(50) DEFAULT 'it''s' NULL, \
+            `note` longtext DEFAULT ('none') NULL, \
+            `created_at` datetime(3) DEFAULT CURRENT_TIMESTAMP(3) NULL, \
+            PRIMARY KEY (`id`))"
+        );
+        assert_eq!(
+            sqls[1].1,
+            "ALTER TABLE `public`.`tb_1` ADD  INDEX `idx_note` (`note`(255),`created_at`) ,\
+            ADD UNIQUE INDEX `tb_1_name_key` (`Name`) "
+        );
+        assert_eq!(sqls.len(), 2);
+    }
+}
@@ -1,7 +1,9 @@
 pub mod mongo_create_collection_statement;
 pub mod mongo_shard_key_statement;
 pub mod mysql_create_database_statement;
+pub mod mysql_create_object_statement;
 pub mod mysql_create_table_statement;
+pub mod pg_create_object_statement;
 pub mod pg_create_rbac_statement;
 pub mod pg_create_schema_statement;
 pub mod pg_create_table_statement;
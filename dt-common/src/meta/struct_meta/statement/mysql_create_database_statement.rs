@@ -1,3 +1,4 @@
+use crate::quote_mysql as quote;
 use crate::rdb_filter::RdbFilter;
 
 use crate::meta::struct_meta::structure::{database::Database, structure_type::StructureType};
@@ -18,7 +19,10 @@ impl MysqlCreateDatabaseStatement {
             return Ok(sqls);
         }
 
-        let mut sql = format!(r#"CREATE DATABASE IF NOT EXISTS `{}`"#, self.database.name);
+        let mut sql = format!(
+            "CREATE DATABASE IF NOT EXISTS {}",
+            quote!(self.database.name)
+        );
         if !self.database.default_character_set_name.is_empty() {
             sql = format!(
                 "{} DEFAULT CHARACTER SET {}",
@@ -0,0 +1,216 @@
+use crate::quote_mysql as quote;
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::{
+    db_object::{MysqlDbObject, MysqlRoutine, MysqlTrigger, MysqlView},
+    structure_type::StructureType,
+};
+
+/// views, triggers and routines are created without DEFINER, i.e. owned by the target user
+#[derive(Debug, Clone)]
+pub struct MysqlCreateObjectStatement {
+    pub object: MysqlDbObject,
+}
+
+impl MysqlCreateObjectStatement {
+    pub fn structure_type(&self) -> StructureType {
+        match self.object {
+            MysqlDbObject::View(_) => StructureType::View,
+            MysqlDbObject::Trigger(_) => StructureType::Trigger,
+            MysqlDbObject::Routine(_) => StructureType::Routine,
+        }
+    }
+
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let mut sqls = Vec::new();
+        let structure_type = self.structure_type();
+        if filter.filter_structure(&structure_type) {
+            return Ok(sqls);
+        }
+
+        let (key, sql) = match &self.object {
+            MysqlDbObject::View(view) => (
+                format!("view.{}.{}", view.database_name, view.view_name),
+                Self::view_to_sql(view),
+            ),
+            MysqlDbObject::Trigger(trigger) => (
+                format!("trigger.{}.{}", trigger.database_name, trigger.trigger_name),
+                Self::trigger_to_sql(trigger),
+            ),
+            MysqlDbObject::Routine(routine) => (
+                format!(
+                    "routine.{}.{}.{}",
+                    routine.database_name,
+                    routine.routine_type.to_lowercase(),
+                    routine.routine_name
+                ),
+                Self::routine_to_sql(routine),
+            ),
+        };
+        sqls.push((key, sql));
+        Ok(sqls)
+    }
+
+    fn view_to_sql(view: &MysqlView) -> String {
+        let mut sql = "CREATE OR REPLACE ".to_string();
+        if !view.security_type.is_empty() {
+            sql.push_str(&format!("SQL SECURITY {} ", view.security_type));
+        }
+        sql.push_str(&format!(
+            "VIEW {}.{} AS {}",
+            quote!(view.database_name),
+            quote!(view.view_name),
+            view.definition
+        ));
+        match view.check_option.as_str() {
+            "" | "NONE" => {}
+            check_option => sql.push_str(&format!(" WITH {} CHECK OPTION", check_option)),
+        }
+        sql
+    }
+
+    fn trigger_to_sql(trigger: &MysqlTrigger) -> String {
+        format!(
+            "CREATE TRIGGER {}.{} {} {} ON {}.{} FOR EACH ROW {}",
+            quote!(trigger.database_name),
+            quote!(trigger.trigger_name),
+            trigger.action_timing,
+            trigger.event_manipulation,
+            quote!(trigger.database_name),
+            quote!(trigger.table_name),
+            trigger.action_statement
+        )
+    }
+
+    fn routine_to_sql(routine: &MysqlRoutine) -> String {
+        let params: Vec<String> = routine
+            .params
+            .iter()
+            .map(|p| {
+                let param = format!("{} {}", quote!(p.name), p.data_type);
+                if p.mode.is_empty() {
+                    param
+                } else {
+                    format!("{} {}", p.mode, param)
+                }
+            })
+            .collect();
+
+        let mut sql = format!(
+            "CREATE {} {}.{}({})",
+            routine.routine_type,
+            quote!(routine.database_name),
+            quote!(routine.routine_name),
+            params.join(", ")
+        );
+        if !routine.returns.is_empty() {
+            sql.push_str(&format!(" RETURNS {}", routine.returns));
+        }
+        if routine.is_deterministic {
+            sql.push_str(" DETERMINISTIC");
+        } else {
+            sql.push_str(" NOT DETERMINISTIC");
+        }
+        if !routine.sql_data_access.is_empty() {
+            sql.push_str(&format!(" {}", routine.sql_data_access));
+        }
+        if !routine.security_type.is_empty() {
+            sql.push_str(&format!(" SQL SECURITY {}", routine.security_type));
+        }
+        if !routine.comment.is_empty() {
+            sql.push_str(&format!(
+                " COMMENT '{}'",
+                routine.comment.replace('\'', "''")
+            ));
+        }
+        sql.push_str(&format!(" {}", routine.definition));
+        sql
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::struct_meta::structure::db_object::MysqlRoutineParam;
+
+    #[test]
+    fn test_view_to_sql() {
+        let view = MysqlView {
+            database_name: "db_1".into(),
+            view_name: "v`1".into(),
+            definition: "select `db_1`.`tb_1`.`id` AS `id` from `db_1`.`tb_1`".into(),
+            check_option: "CASCADED".into(),
+            security_type: "INVOKER".into(),
+        };
+        assert_eq!(
+            MysqlCreateObjectStatement::view_to_sql(&view),
+            "CREATE OR REPLACE SQL SECURITY INVOKER VIEW `db_1`.`v``1` AS select `db_1`.`tb_1`.`id` AS `id` from `db_1`.`tb_1` WITH CASCADED CHECK OPTION"
+        );
+    }
+
+    #[test]
+    fn test_trigger_to_sql() {
+        let trigger = MysqlTrigger {
+            database_name: "db_1".into(),
+            trigger_name: "trg_1".into(),
+            table_name: "tb_1".into(),
+            action_timing: "BEFORE".into(),
+            event_manipulation: "INSERT".into(),
+            action_statement: "SET NEW.updated_at = NOW()".into(),
+        };
+        assert_eq!(
+            MysqlCreateObjectStatement::trigger_to_sql(&trigger),
+            "CREATE TRIGGER `db_1`.`trg_1` BEFORE INSERT ON `db_1`.`tb_1` FOR EACH ROW SET NEW.updated_at = NOW()"
+        );
+    }
+
+    #[test]
+    fn test_routine_to_sql() {
+        let procedure = MysqlRoutine {
+            database_name: "db_1".into(),
+            routine_name: "p_1".into(),
+            routine_type: "PROCEDURE".into(),
+            params: vec![
+                MysqlRoutineParam {
+                    mode: "IN".into(),
+                    name: "a".into(),
+                    data_type: "int".into(),
+                },
+                MysqlRoutineParam {
+                    mode: "OUT".into(),
+                    name: "b".into(),
+                    data_type: "varchar(10)".into(),
+                },
+            ],
+            definition: "BEGIN SELECT 'x' INTO b; END".into(),
+            sql_data_access: "CONTAINS SQL".into(),
+            security_type: "DEFINER".into(),
+            comment: "it's".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            MysqlCreateObjectStatement::routine_to_sql(&procedure),
+            "CREATE PROCEDURE `db_1`.`p_1`(IN `a` int, OUT `b` varchar(10)) NOT DETERMINISTIC CONTAINS SQL SQL SECURITY DEFINER COMMENT 'it''s' BEGIN SELECT 'x' INTO b; END"
+        );
+
+        let function = MysqlRoutine {
+            database_name: "db_1".into(),
+            routine_name: "f_1".into(),
+            routine_type: "FUNCTION".into(),
+            params: vec![MysqlRoutineParam {
+                mode: String::new(),
+                name: "a".into(),
+                data_type: "int".into(),
+            }],
+            returns: "int".into(),
+            definition: "RETURN a + 1".into(),
+            is_deterministic: true,
+            sql_data_access: "NO SQL".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            MysqlCreateObjectStatement::routine_to_sql(&function),
+            "CREATE FUNCTION `db_1`.`f_1`(`a` int) RETURNS int DETERMINISTIC NO SQL RETURN a + 1"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::meta::struct_meta::structure::column::ColumnDefault;
+use crate::quote_mysql as quote;
 use crate::{config::config_enums::DbType, rdb_filter::RdbFilter};
 
 use crate::meta::struct_meta::structure::index::IndexType;
@@ -83,9 +84,9 @@ impl MysqlCreateTableStatement {
                 sqls.push((
                     key,
                     format!(
-                        "ALTER TABLE `{}`.`{}` {}",
-                        self.table.database_name,
-                        self.table.table_name,
+                        "ALTER TABLE {}.{} {}",
+                        quote!(self.table.database_name),
+                        quote!(self.table.table_name),
                         idx_appends.join(",")
                     ),
                 ))
@@ -112,16 +113,19 @@ impl MysqlCreateTableStatement {
             pk_str = format!(
                 ", PRIMARY KEY ({})",
                 pks.iter()
-                    .map(|x| format!("`{}`", x))
+                    .map(|x| quote!(x).to_string())
                     .collect::<Vec<String>>()
                     .join(",")
             )
         }
 
-        // Todo: table partition; column visible, generated(information_schema.column.GENERATION_EXPRESSION)
+        // Todo: table partition; column visible
         let mut sql = format!(
-            "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({}{})",
-            table.database_name, table.table_name, columns_sql, pk_str
+            "CREATE TABLE IF NOT EXISTS {}.{} ({}{})",
+            quote!(table.database_name),
+            quote!(table.table_name),
+            columns_sql,
+            pk_str
         );
 
         if !table.engine_name.is_empty() {
@@ -136,6 +140,14 @@ impl MysqlCreateTableStatement {
             sql = format!("{} COLLATE={}", sql, table.table_collation);
         }
 
+        if !table.row_format.is_empty() {
+            sql = format!("{} ROW_FORMAT={}", sql, table.row_format);
+        }
+
+        if !table.key_block_size.is_empty() {
+            sql = format!("{} KEY_BLOCK_SIZE={}", sql, table.key_block_size);
+        }
+
         if !table.table_comment.is_empty() {
             sql = format!("{} COMMENT='{}'", sql, Self::escape(&table.table_comment));
         }
@@ -143,65 +155,99 @@ impl MysqlCreateTableStatement {
         sql
     }
 
+    /// ALTER TABLE ... ADD COLUMN for a column missing in target, placed after the given
+    /// column, or first if None
+    pub fn add_column_sql(table: &Table, column: &Column, after: Option<&str>) -> String {
+        let position = match after {
+            Some(after) => format!("AFTER {}", quote!(after)),
+            None => "FIRST".to_string(),
+        };
+        format!(
+            "ALTER TABLE {}.{} ADD COLUMN {} {}",
+            quote!(table.database_name),
+            quote!(table.table_name),
+            Self::column_to_sql(column),
+            position
+        )
+    }
+
+    /// CREATE INDEX for an index missing in target
+    pub fn add_index_sql(index: &mut Index) -> String {
+        Self::index_to_sql(index)
+    }
+
     fn columns_to_sql(columns: &mut [Column]) -> (String, Vec<String>) {
         let (mut sql_lines, mut pks) = (Vec::new(), Vec::new());
 
         columns.sort_by(|c1, c2| c1.ordinal_position.cmp(&c2.ordinal_position));
         for i in columns.iter() {
-            let mut line = String::new();
-            line.push_str(&format!("`{}` {}", i.column_name, i.column_type));
-
-            if !i.character_set_name.is_empty() {
-                line.push_str(&format!(" CHARACTER SET {}", i.character_set_name))
+            sql_lines.push(Self::column_to_sql(i));
+            if i.column_key == "PRI" {
+                pks.push(i.column_name.clone());
             }
+        }
 
-            if !i.collation_name.is_empty() {
-                line.push_str(&format!(" COLLATE {}", i.collation_name))
-            }
+        (sql_lines.join(", "), pks)
+    }
 
-            match &i.column_default {
-                Some(ColumnDefault::Expression(v)) => line.push_str(&format!(" DEFAULT {}", v)),
-                Some(ColumnDefault::Literal(v)) => {
-                    if i.column_type.to_lowercase().starts_with("bit") {
-                        // https://github.com/apecloud/ape-dts/issues/319
-                        // CREATE TABLE a(b bit(1) default b'1');
-                        line.push_str(&format!(" DEFAULT {}", v))
-                    } else {
-                        line.push_str(&format!(" DEFAULT '{}'", Self::escape(v)))
-                    }
+    fn column_to_sql(i: &Column) -> String {
+        let mut line = String::new();
+        line.push_str(&format!("{} {}", quote!(i.column_name), i.column_type));
+
+        if !i.character_set_name.is_empty() {
+            line.push_str(&format!(" CHARACTER SET {}", i.character_set_name))
+        }
+
+        if !i.collation_name.is_empty() {
+            line.push_str(&format!(" COLLATE {}", i.collation_name))
+        }
+
+        match &i.column_default {
+            Some(ColumnDefault::Expression(v)) => line.push_str(&format!(" DEFAULT {}", v)),
+            Some(ColumnDefault::Literal(v)) => {
+                if i.column_type.to_lowercase().starts_with("bit") {
+                    // https://github.com/apecloud/ape-dts/issues/319
+                    // CREATE TABLE a(b bit(1) default b'1');
+                    line.push_str(&format!(" DEFAULT {}", v))
+                } else {
+                    line.push_str(&format!(" DEFAULT '{}'", Self::escape(v)))
                 }
-                _ => {}
             }
+            _ => {}
+        }
 
-            // auto_increment
-            // on update CURRENT_TIMESTAMP
-            // mysql 8.0:
-            //  DEFAULT_GENERATED
-            //  DEFAULT_GENERATED on update CURRENT_TIMESTAMP
+        // auto_increment
+        // on update CURRENT_TIMESTAMP
+        // mysql 8.0:
+        //  DEFAULT_GENERATED
+        //  DEFAULT_GENERATED on update CURRENT_TIMESTAMP
+        // generated columns: VIRTUAL GENERATED / STORED GENERATED
+        if let Some(expr) = &i.generated {
+            let storage = if i.extra.to_uppercase().contains("STORED") {
+                "STORED"
+            } else {
+                "VIRTUAL"
+            };
+            line.push_str(&format!(" GENERATED ALWAYS AS ({}) {}", expr, storage));
+        } else {
             let extra = i.extra.replacen("DEFAULT_GENERATED", "", 1);
             if !extra.is_empty() {
                 line.push_str(&format!(" {}", extra));
             }
+        }
 
-            let nullable = if !i.is_nullable {
-                String::from("NOT NULL")
-            } else {
-                String::from("NULL")
-            };
-
-            if !i.column_comment.is_empty() {
-                line.push_str(&format!(" COMMENT '{}'", Self::escape(&i.column_comment)))
-            }
-
-            line.push_str(&format!(" {}", nullable));
-            sql_lines.push(line);
+        let nullable = if !i.is_nullable {
+            String::from("NOT NULL")
+        } else {
+            String::from("NULL")
+        };
 
-            if i.column_key == "PRI" {
-                pks.push(i.column_name.clone());
-            }
+        if !i.column_comment.is_empty() {
+            line.push_str(&format!(" COMMENT '{}'", Self::escape(&i.column_comment)))
         }
 
-        (sql_lines.join(", "), pks)
+        line.push_str(&format!(" {}", nullable));
+        line
     }
 
     fn index_to_sql(index: &mut Index) -> String {
@@ -211,8 +257,12 @@ impl MysqlCreateTableStatement {
         // since only BETREE supported in both InnoDB and MyISAM
         // refer: https://dev.mysql.com/doc/refman/8.0/en/create-index.html
         let mut sql: String = format!(
-            "CREATE {} INDEX `{}` ON `{}`.`{}` ({}) ",
-            index.index_kind, index.index_name, index.database_name, index.table_name, columns_sql
+            "CREATE {} INDEX {} ON {}.{} ({}) ",
+            index.index_kind,
+            quote!(index.index_name),
+            quote!(index.database_name),
+            quote!(index.table_name),
+            columns_sql
         );
 
         if !index.comment.is_empty() {
@@ -229,12 +279,14 @@ impl MysqlCreateTableStatement {
         // since only BETREE supported in both InnoDB and MyISAM
         // refer: https://dev.mysql.com/doc/refman/8.0/en/create-index.html
         let mut sql: String = format!(
-            "ADD {} INDEX `{}` ({}) ",
-            index.index_kind, index.index_name, columns_sql
+            "ADD {} INDEX {} ({}) ",
+            index.index_kind,
+            quote!(index.index_name),
+            columns_sql
         );
 
         if !index.comment.is_empty() {
-            sql.push_str(&format!("COMMENT '{}' ", index.comment));
+            sql.push_str(&format!("COMMENT '{}' ", Self::escape(&index.comment)));
         }
 
         sql
@@ -250,9 +302,9 @@ impl MysqlCreateTableStatement {
             .filter(|x| !x.column_name.is_empty())
             .map(|x| {
                 if let Some(prefix_length) = x.prefix_length {
-                    format!("`{}`({})", x.column_name, prefix_length)
+                    format!("{}({})", quote!(x.column_name), prefix_length)
                 } else {
-                    format!("`{}`", x.column_name)
+                    quote!(x.column_name).to_string()
                 }
             })
             .collect::<Vec<String>>()
@@ -260,12 +312,12 @@ impl MysqlCreateTableStatement {
     }
 
     fn constraint_to_sql(constraint: &Constraint) -> String {
-        // TODO, check for escapes
+        // definition comes from information_schema / SHOW CREATE TABLE with identifiers quoted
         format!(
-            "ALTER TABLE `{}`.`{}` ADD CONSTRAINT `{}` {} {} ",
-            constraint.database_name,
-            constraint.table_name,
-            constraint.constraint_name,
+            "ALTER TABLE {}.{} ADD CONSTRAINT {} {} {} ",
+            quote!(constraint.database_name),
+            quote!(constraint.table_name),
+            quote!(constraint.constraint_name),
             constraint.constraint_type.to_str(DbType::Mysql),
             constraint.definition
         )
@@ -275,3 +327,50 @@ impl MysqlCreateTableStatement {
         text.replace('\'', "\'\'").to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::meta::struct_meta::structure::{constraint::ConstraintType, index::IndexColumn};
+
+    use super::*;
+
+    #[test]
+    fn test_exotic_identifiers() {
+        let constraint = Constraint {
+            database_name: "my db".into(),
+            schema_name: String::new(),
+            table_name: "order".into(),
+            constraint_name: "fk`1".into(),
+            constraint_type: ConstraintType::Foreign,
+            definition: "(`user id`) REFERENCES `my db`.`user`(`id`)".into(),
+        };
+        assert_eq!(
+            MysqlCreateTableStatement::constraint_to_sql(&constraint),
+            "ALTER TABLE `my db`.`order` ADD CONSTRAINT `fk``1` FOREIGN KEY (`user id`) REFERENCES `my db`.`user`(`id`) "
+        );
+
+        let mut index = Index {
+            database_name: "my db".into(),
+            table_name: "order".into(),
+            index_name: "idx`key".into(),
+            index_kind: IndexKind::Unique,
+            columns: vec![
+                IndexColumn {
+                    column_name: "select".into(),
+                    seq_in_index: 1,
+                    prefix_length: Some(10),
+                },
+                IndexColumn {
+                    column_name: "a`b".into(),
+                    seq_in_index: 2,
+                    prefix_length: None,
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            MysqlCreateTableStatement::index_to_sql(&mut index),
+            "CREATE UNIQUE INDEX `idx``key` ON `my db`.`order` (`select`(10),`a``b`) "
+        );
+    }
+}
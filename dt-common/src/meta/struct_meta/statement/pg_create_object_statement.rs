@@ -0,0 +1,48 @@
+use crate::quote_pg as quote;
+use crate::rdb_filter::RdbFilter;
+
+use crate::meta::struct_meta::structure::{db_object::PgDbObject, structure_type::StructureType};
+
+#[derive(Debug, Clone)]
+pub struct PgCreateObjectStatement {
+    pub object: PgDbObject,
+}
+
+impl PgCreateObjectStatement {
+    pub fn to_sqls(&self, filter: &RdbFilter) -> anyhow::Result<Vec<(String, String)>> {
+        let mut sqls = Vec::new();
+        let object = &self.object;
+        if filter.filter_structure(&object.object_type) {
+            return Ok(sqls);
+        }
+
+        let (key, sql) = match object.object_type {
+            StructureType::View => (
+                format!("view.{}.{}", object.schema_name, object.object_name),
+                format!(
+                    "CREATE OR REPLACE VIEW {}.{} AS {}",
+                    quote!(object.schema_name),
+                    quote!(object.object_name),
+                    object.definition
+                ),
+            ),
+            // trigger names are unique per table
+            StructureType::Trigger => (
+                format!(
+                    "trigger.{}.{}.{}",
+                    object.schema_name, object.table_name, object.object_name
+                ),
+                object.definition.clone(),
+            ),
+            _ => (
+                format!(
+                    "{}.{}.{}",
+                    object.object_type, object.schema_name, object.object_name
+                ),
+                object.definition.clone(),
+            ),
+        };
+        sqls.push((key, sql));
+        Ok(sqls)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::quote_pg as quote;
 use crate::rdb_filter::RdbFilter;
 
 use crate::meta::struct_meta::structure::{
@@ -23,7 +24,7 @@ impl PgCreateRbacStatement {
         for role in &self.roles {
             role_map.insert(role.name.clone(), role);
 
-            let mut sql = format!("CREATE ROLE \"{}\"", role.name);
+            let mut sql = format!("CREATE ROLE {}", quote!(role.name));
             let mut options = Vec::new();
 
             if role.rol_super {
@@ -81,8 +82,12 @@ impl PgCreateRbacStatement {
                         if param.is_empty() || value.is_empty() {
                             continue;
                         }
-                        let alter_sql =
-                            format!("ALTER ROLE \"{}\" SET {} TO '{}'", role.name, param, value);
+                        let alter_sql = format!(
+                            "ALTER ROLE {} SET {} TO '{}'",
+                            quote!(role.name),
+                            param,
+                            value
+                        );
                         sqls.push((
                             format!("rbac.role_config.{}.{}", role.name, param),
                             alter_sql,
@@ -94,7 +99,7 @@ impl PgCreateRbacStatement {
 
         for member in &self.members {
             if role_map.contains_key(&member.member) {
-                let mut sql = format!("GRANT \"{}\" TO \"{}\"", member.role, member.member);
+                let mut sql = format!("GRANT {} TO {}", quote!(member.role), quote!(member.member));
                 if member.admin_option {
                     sql = format!("{} WITH ADMIN OPTION", sql);
                 }
@@ -119,13 +124,14 @@ impl PgCreateRbacStatement {
 
 #[cfg(test)]
 mod tests {
-    use dashmap::DashMap;
+    use dashmap::{DashMap, DashSet};
 
     use super::*;
     use crate::config::config_enums::DbType;
     use crate::meta::struct_meta::structure::rbac::{PgPrivilege, PgRole, PgRoleMember};
     use crate::rdb_filter::RdbFilter;
     use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
 
     fn build_filter() -> RdbFilter {
         let mut filter = RdbFilter {
@@ -142,6 +148,9 @@ mod tests {
             do_ddls: HashSet::new(),
             ignore_cmds: HashSet::new(),
             where_conditions: HashMap::new(),
+            removed_tbs: Arc::new(DashSet::new()),
+            added_tbs: Arc::new(DashSet::new()),
+            patterns: Arc::new(DashMap::new()),
         };
         filter.do_structures.insert(StructureType::Rbac.to_string());
         filter
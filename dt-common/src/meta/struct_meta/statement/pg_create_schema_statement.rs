@@ -1,3 +1,4 @@
+use crate::quote_pg as quote;
 use crate::rdb_filter::RdbFilter;
 
 use crate::meta::struct_meta::structure::{schema::Schema, structure_type::StructureType};
@@ -19,7 +20,7 @@ impl PgCreateSchemaStatement {
         }
 
         let key = format!("schema.{}", self.schema.name);
-        let sql = format!(r#"CREATE SCHEMA IF NOT EXISTS "{}""#, self.schema.name);
+        let sql = format!("CREATE SCHEMA IF NOT EXISTS {}", quote!(self.schema.name));
         sqls.push((key, sql));
         Ok(sqls)
     }
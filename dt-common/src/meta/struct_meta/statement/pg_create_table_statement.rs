@@ -1,15 +1,19 @@
+use std::collections::HashSet;
+
 use anyhow::bail;
 
 use crate::config::config_enums::DbType;
 use crate::error::Error;
+use crate::log_warn;
 use crate::meta::ddl_meta::ddl_parser::DdlParser;
 use crate::meta::ddl_meta::ddl_statement::DdlStatement;
 use crate::meta::struct_meta::structure::column::ColumnDefault;
+use crate::quote_pg as quote;
 use crate::rdb_filter::RdbFilter;
 
 use crate::meta::struct_meta::structure::{
     column::Column,
-    comment::Comment,
+    comment::{Comment, CommentType},
     constraint::{Constraint, ConstraintType},
     index::{Index, IndexKind},
     sequence::Sequence,
@@ -23,6 +27,7 @@ pub struct PgCreateTableStatement {
     pub table: Table,
     pub table_comments: Vec<Comment>,
     pub column_comments: Vec<Comment>,
+    pub constraint_comments: Vec<Comment>,
     pub constraints: Vec<Constraint>,
     pub indexes: Vec<Index>,
     pub sequences: Vec<Sequence>,
@@ -38,7 +43,11 @@ impl PgCreateTableStatement {
             comment.table_name = dst_tb.to_string();
         }
 
-        for comment in self.column_comments.iter_mut() {
+        for comment in self
+            .column_comments
+            .iter_mut()
+            .chain(self.constraint_comments.iter_mut())
+        {
             comment.schema_name = dst_schema.to_string();
             comment.table_name = dst_tb.to_string();
         }
@@ -75,6 +84,16 @@ impl PgCreateTableStatement {
             let key = format!("table.{}.{}", self.table.schema_name, self.table.table_name);
             sqls.push((key, Self::table_to_sql(&mut self.table)));
 
+            for i in self.table.columns.iter() {
+                if let Some(sql) = Self::column_storage_to_sql(&self.table, i) {
+                    let key = format!(
+                        "column_storage.{}.{}.{}",
+                        self.table.schema_name, self.table.table_name, i.column_name
+                    );
+                    sqls.push((key, sql));
+                }
+            }
+
             for i in self.sequence_owners.iter() {
                 let key = format!(
                     "sequence_owner.{}.{}.{}",
@@ -82,7 +101,9 @@ impl PgCreateTableStatement {
                 );
                 sqls.push((key, Self::sequence_owner_to_sql(i)));
             }
+        }
 
+        if !filter.filter_structure(&StructureType::Comment) {
             for i in self.column_comments.iter() {
                 let key = format!(
                     "column_comment.{}.{}.{}",
@@ -97,6 +118,7 @@ impl PgCreateTableStatement {
             }
         }
 
+        let mut created_constraints = HashSet::new();
         for i in self.constraints.iter() {
             match i.constraint_type {
                 ConstraintType::Primary | ConstraintType::Unique => {
@@ -104,6 +126,16 @@ impl PgCreateTableStatement {
                         continue;
                     }
                 }
+                ConstraintType::Unknown => {
+                    log_warn!(
+                        "constraint: {}.{}.{} of unknown type skipped, definition: {}",
+                        i.schema_name,
+                        i.table_name,
+                        i.constraint_name,
+                        i.definition
+                    );
+                    continue;
+                }
                 _ => {
                     if filter.filter_structure(&StructureType::Constraint) {
                         continue;
@@ -116,9 +148,35 @@ impl PgCreateTableStatement {
                 i.schema_name, i.table_name, i.constraint_name
             );
             sqls.push((key, Self::constraint_to_sql(i)));
+            created_constraints.insert(&i.constraint_name);
+        }
+
+        // comments of constraints not created are skipped
+        if !filter.filter_structure(&StructureType::Comment) {
+            for i in self.constraint_comments.iter() {
+                if !created_constraints.contains(&i.constraint_name) {
+                    continue;
+                }
+                let key = format!(
+                    "constraint_comment.{}.{}.{}",
+                    i.schema_name, i.table_name, i.constraint_name
+                );
+                sqls.push((key, Self::comment_to_sql(i)));
+            }
         }
 
+        // the index of an exclusion constraint is created along with the constraint
+        let exclusion_constraints: Vec<&String> = self
+            .constraints
+            .iter()
+            .filter(|c| c.constraint_type == ConstraintType::Exclusion)
+            .map(|c| &c.constraint_name)
+            .collect();
         for i in self.indexes.iter() {
+            if exclusion_constraints.contains(&&i.index_name) {
+                continue;
+            }
+
             match i.index_kind {
                 IndexKind::Unique => {
                     if filter.filter_structure(&StructureType::Table) {
@@ -142,41 +200,84 @@ impl PgCreateTableStatement {
     fn table_to_sql(table: &mut Table) -> String {
         let columns_sql = Self::columns_to_sql(&mut table.columns);
         format!(
-            r#"CREATE TABLE IF NOT EXISTS "{}"."{}" ({})"#,
-            table.schema_name, table.table_name, columns_sql
+            "CREATE TABLE IF NOT EXISTS {}.{} ({})",
+            quote!(table.schema_name),
+            quote!(table.table_name),
+            columns_sql
         )
     }
 
-    fn columns_to_sql(columns: &mut [Column]) -> String {
-        let mut sql = String::new();
+    /// ALTER TABLE ... ADD COLUMN for a column missing in target
+    pub fn add_column_sql(table: &Table, column: &Column) -> String {
+        format!(
+            "ALTER TABLE {}.{} ADD COLUMN IF NOT EXISTS {}",
+            quote!(table.schema_name),
+            quote!(table.table_name),
+            Self::column_to_sql(column).trim_end()
+        )
+    }
+
+    /// CREATE INDEX IF NOT EXISTS for an index missing in target
+    pub fn add_index_sql(index: &Index) -> anyhow::Result<String> {
+        Self::index_to_sql(index)
+    }
 
+    fn columns_to_sql(columns: &mut [Column]) -> String {
         columns.sort_by(|a, b| a.ordinal_position.cmp(&b.ordinal_position));
-        for column in columns.iter() {
-            sql.push_str(format!(r#""{}" {} "#, column.column_name, column.column_type).as_str());
-            if !column.is_nullable {
-                sql.push_str("NOT NULL ");
-            }
-            match &column.column_default {
-                Some(ColumnDefault::Expression(v)) | Some(ColumnDefault::Literal(v)) => {
-                    sql.push_str(format!("DEFAULT {} ", v).as_str())
-                }
-                None => {}
+        columns
+            .iter()
+            .map(Self::column_to_sql)
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    fn column_to_sql(column: &Column) -> String {
+        let mut sql = format!("{} {} ", quote!(column.column_name), column.column_type);
+        if !column.is_nullable {
+            sql.push_str("NOT NULL ");
+        }
+        match &column.column_default {
+            Some(ColumnDefault::Expression(v)) | Some(ColumnDefault::Literal(v)) => {
+                sql.push_str(format!("DEFAULT {} ", v).as_str())
             }
-            if let Some(x) = &column.generated {
-                if x == "ALWAYS" {
-                    sql.push_str("GENERATED ALWAYS AS IDENTITY ")
-                } else {
-                    sql.push_str("GENERATED BY DEFAULT AS IDENTITY ")
-                }
+            None => {}
+        }
+        if let Some(x) = &column.generated {
+            if x == "ALWAYS" {
+                sql.push_str("GENERATED ALWAYS AS IDENTITY ")
+            } else {
+                sql.push_str("GENERATED BY DEFAULT AS IDENTITY ")
             }
-            sql.push(',');
         }
+        sql
+    }
 
-        if sql.ends_with(',') {
-            sql = sql[0..sql.len() - 1].to_string();
+    // STORAGE in column definitions is only supported by pg 16+
+    fn column_storage_to_sql(table: &Table, column: &Column) -> Option<String> {
+        let mut actions = Vec::new();
+        if !column.storage.is_empty() {
+            actions.push(format!(
+                "ALTER COLUMN {} SET STORAGE {}",
+                quote!(column.column_name),
+                column.storage
+            ));
         }
-
-        sql
+        if !column.compression.is_empty() {
+            actions.push(format!(
+                "ALTER COLUMN {} SET COMPRESSION {}",
+                quote!(column.column_name),
+                column.compression
+            ));
+        }
+        if actions.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "ALTER TABLE {}.{} {}",
+            quote!(table.schema_name),
+            quote!(table.table_name),
+            actions.join(", ")
+        ))
     }
 
     fn index_to_sql(index: &Index) -> anyhow::Result<String> {
@@ -197,16 +298,32 @@ impl PgCreateTableStatement {
     }
 
     fn comment_to_sql(comment: &Comment) -> String {
-        if comment.column_name.is_empty() {
-            format!(
-                r#"COMMENT ON TABLE "{}"."{}" is $${}$$"#,
-                comment.schema_name, comment.table_name, comment.comment
-            )
-        } else {
-            format!(
-                r#"COMMENT ON COLUMN "{}"."{}"."{}" IS $${}$$"#,
-                comment.schema_name, comment.table_name, comment.column_name, comment.comment
-            )
+        // escape string constant, works whether standard_conforming_strings is on or off
+        let text = format!(
+            "E'{}'",
+            comment.comment.replace('\\', "\\\\").replace('\'', "''")
+        );
+        match comment.comment_type {
+            CommentType::Table => format!(
+                "COMMENT ON TABLE {}.{} IS {}",
+                quote!(comment.schema_name),
+                quote!(comment.table_name),
+                text
+            ),
+            CommentType::Column => format!(
+                "COMMENT ON COLUMN {}.{}.{} IS {}",
+                quote!(comment.schema_name),
+                quote!(comment.table_name),
+                quote!(comment.column_name),
+                text
+            ),
+            CommentType::Constraint => format!(
+                "COMMENT ON CONSTRAINT {} ON {}.{} IS {}",
+                quote!(comment.constraint_name),
+                quote!(comment.schema_name),
+                quote!(comment.table_name),
+                text
+            ),
         }
     }
 
@@ -218,9 +335,9 @@ impl PgCreateTableStatement {
         };
 
         format!(
-            r#"CREATE SEQUENCE IF NOT EXISTS "{}"."{}" AS {} START {} INCREMENT by {} MINVALUE {} MAXVALUE {} {}"#,
-            sequence.schema_name,
-            sequence.sequence_name,
+            "CREATE SEQUENCE IF NOT EXISTS {}.{} AS {} START {} INCREMENT by {} MINVALUE {} MAXVALUE {} {}",
+            quote!(sequence.schema_name),
+            quote!(sequence.sequence_name),
             sequence.data_type,
             sequence.start_value,
             sequence.increment,
@@ -232,22 +349,153 @@ impl PgCreateTableStatement {
 
     fn sequence_owner_to_sql(sequence_owner: &SequenceOwner) -> String {
         format!(
-            r#"ALTER SEQUENCE "{}"."{}" OWNED BY "{}"."{}"."{}""#,
-            sequence_owner.schema_name,
-            sequence_owner.sequence_name,
-            sequence_owner.schema_name,
-            sequence_owner.table_name,
-            sequence_owner.column_name
+            "ALTER SEQUENCE {}.{} OWNED BY {}.{}.{}",
+            quote!(sequence_owner.schema_name),
+            quote!(sequence_owner.sequence_name),
+            quote!(sequence_owner.schema_name),
+            quote!(sequence_owner.table_name),
+            quote!(sequence_owner.column_name)
         )
     }
 
     fn constraint_to_sql(constraint: &Constraint) -> String {
         format!(
-            r#"ALTER TABLE "{}"."{}" ADD CONSTRAINT "{}" {}"#,
-            constraint.schema_name,
-            constraint.table_name,
-            constraint.constraint_name,
+            "ALTER TABLE {}.{} ADD CONSTRAINT {} {}",
+            quote!(constraint.schema_name),
+            quote!(constraint.table_name),
+            quote!(constraint.constraint_name),
             constraint.definition
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
+
+    use dashmap::{DashMap, DashSet};
+
+    use super::*;
+
+    fn build_filter(do_structures: &[StructureType]) -> RdbFilter {
+        RdbFilter {
+            db_type: DbType::Pg,
+            do_structures: do_structures.iter().map(|i| i.to_string()).collect(),
+            cache: DashMap::new(),
+            do_schemas: HashSet::new(),
+            ignore_schemas: HashSet::new(),
+            do_tbs: HashSet::new(),
+            ignore_tbs: HashSet::new(),
+            ignore_cols: HashMap::new(),
+            do_events: HashSet::new(),
+            do_dcls: HashSet::new(),
+            do_ddls: HashSet::new(),
+            ignore_cmds: HashSet::new(),
+            where_conditions: HashMap::new(),
+            removed_tbs: Arc::new(DashSet::new()),
+            added_tbs: Arc::new(DashSet::new()),
+            patterns: Arc::new(DashMap::new()),
+        }
+    }
+
+    fn comment(comment_type: CommentType, column: &str, constraint: &str, text: &str) -> Comment {
+        Comment {
+            comment_type,
+            database_name: String::new(),
+            schema_name: "public".into(),
+            table_name: "tb_1".into(),
+            column_name: column.into(),
+            constraint_name: constraint.into(),
+            comment: text.into(),
+        }
+    }
+
+    fn build_statement() -> PgCreateTableStatement {
+        let constraint = |name: &str, constraint_type, definition: &str| Constraint {
+            database_name: String::new(),
+            schema_name: "public".into(),
+            table_name: "tb_1".into(),
+            constraint_name: name.into(),
+            constraint_type,
+            definition: definition.into(),
+        };
+        PgCreateTableStatement {
+            table: Table {
+                schema_name: "public".into(),
+                table_name: "tb_1".into(),
+                ..Default::default()
+            },
+            table_comments: vec![comment(CommentType::Table, "", "", "it's $$ a \\ table")],
+            column_comments: vec![comment(CommentType::Column, "id", "", "id")],
+            constraint_comments: vec![
+                comment(CommentType::Constraint, "", "tb_1_id_check", "positive"),
+                comment(CommentType::Constraint, "", "tb_1_pkey", "pk"),
+            ],
+            constraints: vec![
+                constraint("tb_1_pkey", ConstraintType::Primary, "PRIMARY KEY (id)"),
+                constraint("tb_1_id_check", ConstraintType::Check, "CHECK ((id > 0))"),
+                constraint(
+                    "tb_1_period_excl",
+                    ConstraintType::Exclusion,
+                    "EXCLUDE USING gist (period WITH &&)",
+                ),
+            ],
+            indexes: vec![],
+            sequences: vec![],
+            sequence_owners: vec![],
+        }
+    }
+
+    #[test]
+    fn test_to_sqls_filtered_by_structure_type() {
+        let keys = |do_structures: &[StructureType]| -> Vec<String> {
+            build_statement()
+                .to_sqls(&build_filter(do_structures))
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect()
+        };
+
+        assert_eq!(
+            keys(&[StructureType::Table, StructureType::Constraint]),
+            vec![
+                "table.public.tb_1",
+                "constraint.public.tb_1.tb_1_pkey",
+                "constraint.public.tb_1.tb_1_id_check",
+                "constraint.public.tb_1.tb_1_period_excl",
+            ]
+        );
+        // comments of filtered constraints are skipped
+        assert_eq!(
+            keys(&[StructureType::Constraint, StructureType::Comment]),
+            vec![
+                "column_comment.public.tb_1.id",
+                "table_comment.public.tb_1",
+                "constraint.public.tb_1.tb_1_id_check",
+                "constraint.public.tb_1.tb_1_period_excl",
+                "constraint_comment.public.tb_1.tb_1_id_check",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comment_to_sql() {
+        let sqls: HashMap<String, String> = build_statement()
+            .to_sqls(&build_filter(&[StructureType::Comment]))
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            sqls["table_comment.public.tb_1"],
+            r#"COMMENT ON TABLE "public"."tb_1" IS E'it''s $$ a \\ table'"#
+        );
+        assert_eq!(
+            sqls["column_comment.public.tb_1.id"],
+            r#"COMMENT ON COLUMN "public"."tb_1"."id" IS E'id'"#
+        );
+    }
+}
@@ -10,7 +10,9 @@ use super::{
     mongo_create_collection_statement::MongoCreateCollectionStatement,
     mongo_shard_key_statement::MongoShardKeyStatement,
     mysql_create_database_statement::MysqlCreateDatabaseStatement,
+    mysql_create_object_statement::MysqlCreateObjectStatement,
     mysql_create_table_statement::MysqlCreateTableStatement,
+    pg_create_object_statement::PgCreateObjectStatement,
     pg_create_rbac_statement::PgCreateRbacStatement,
     pg_create_schema_statement::PgCreateSchemaStatement,
     pg_create_table_statement::PgCreateTableStatement,
@@ -27,6 +29,9 @@ pub enum StructStatement {
     PgCreateRbac(PgCreateRbacStatement),
     PgCreateUdf(PgCreateUdfStatement),
     PgCreateUdt(PgCreateUdtStatement),
+    // views, triggers and routines
+    MysqlCreateObject(MysqlCreateObjectStatement),
+    PgCreateObject(PgCreateObjectStatement),
     #[default]
     Unknown,
 }
@@ -43,7 +48,14 @@ impl StructStatement {
             Self::PgCreateRbac(s) => s.to_sqls(filter),
             Self::PgCreateUdf(s) => s.to_sqls(filter),
             Self::PgCreateUdt(s) => s.to_sqls(filter),
+            Self::MysqlCreateObject(s) => s.to_sqls(filter),
+            Self::PgCreateObject(s) => s.to_sqls(filter),
             _ => Ok(vec![]),
         }
     }
+
+    /// views, triggers and routines, sunk by [sinker].object_conflict_policy
+    pub fn is_db_object(&self) -> bool {
+        matches!(self, Self::MysqlCreateObject(_) | Self::PgCreateObject(_))
+    }
 }
@@ -11,6 +11,10 @@ pub struct Column {
     pub generated: Option<String>,
     pub character_set_name: String,
     pub collation_name: String,
+    // pg only, e.g. EXTERNAL, empty if it is the default storage of the column type
+    pub storage: String,
+    // pg 14+ only, e.g. lz4, empty for the default compression
+    pub compression: String,
 }
 
 #[derive(Clone, Debug, PartialEq)]
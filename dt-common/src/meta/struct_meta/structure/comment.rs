@@ -5,6 +5,8 @@ pub struct Comment {
     pub schema_name: String,
     pub table_name: String,
     pub column_name: String,
+    // pg only
+    pub constraint_name: String,
     pub comment: String,
 }
 
@@ -12,4 +14,6 @@ pub struct Comment {
 pub enum CommentType {
     Table,
     Column,
+    // pg only
+    Constraint,
 }
@@ -16,6 +16,8 @@ pub enum ConstraintType {
     Unique,
     Check,
     Foreign,
+    // pg only
+    Exclusion,
     Unknown,
 }
 
@@ -35,6 +37,7 @@ impl ConstraintType {
                 "u" | "117" => Self::Unique,
                 "c" | "99" => Self::Check,
                 "f" | "102" => Self::Foreign,
+                "x" | "120" => Self::Exclusion,
                 _ => Self::Unknown,
             },
 
@@ -49,7 +52,7 @@ impl ConstraintType {
                 Self::Unique => "UNIQUE",
                 Self::Foreign => "FOREIGN KEY",
                 Self::Check => "CHECK",
-                Self::Unknown => "unknown",
+                Self::Exclusion | Self::Unknown => "unknown",
             },
 
             DbType::Pg => match self {
@@ -57,6 +60,7 @@ impl ConstraintType {
                 Self::Unique => "u",
                 Self::Foreign => "f",
                 Self::Check => "c",
+                Self::Exclusion => "x",
                 Self::Unknown => "unknown",
             },
 
@@ -0,0 +1,72 @@
+use super::structure_type::StructureType;
+
+#[derive(Debug, Clone)]
+pub enum MysqlDbObject {
+    View(MysqlView),
+    Trigger(MysqlTrigger),
+    Routine(MysqlRoutine),
+}
+
+// Create View: https://dev.mysql.com/doc/refman/8.0/en/create-view.html
+#[derive(Debug, Clone, Default)]
+pub struct MysqlView {
+    pub database_name: String,
+    pub view_name: String,
+    // the select statement
+    pub definition: String,
+    // NONE, CASCADED or LOCAL
+    pub check_option: String,
+    // DEFINER or INVOKER
+    pub security_type: String,
+}
+
+// Create Trigger: https://dev.mysql.com/doc/refman/8.0/en/create-trigger.html
+#[derive(Debug, Clone, Default)]
+pub struct MysqlTrigger {
+    pub database_name: String,
+    pub trigger_name: String,
+    pub table_name: String,
+    // BEFORE or AFTER
+    pub action_timing: String,
+    // INSERT, UPDATE or DELETE
+    pub event_manipulation: String,
+    pub action_statement: String,
+}
+
+// Create Procedure / Function: https://dev.mysql.com/doc/refman/8.0/en/create-procedure.html
+#[derive(Debug, Clone, Default)]
+pub struct MysqlRoutine {
+    pub database_name: String,
+    pub routine_name: String,
+    // PROCEDURE or FUNCTION
+    pub routine_type: String,
+    pub params: Vec<MysqlRoutineParam>,
+    // return type of functions
+    pub returns: String,
+    pub definition: String,
+    pub is_deterministic: bool,
+    // CONTAINS SQL, NO SQL, READS SQL DATA or MODIFIES SQL DATA
+    pub sql_data_access: String,
+    pub security_type: String,
+    pub comment: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MysqlRoutineParam {
+    // IN, OUT or INOUT, empty for function params
+    pub mode: String,
+    pub name: String,
+    pub data_type: String,
+}
+
+/// A view, procedure or trigger of Postgres, definitions are from pg_get_viewdef /
+/// pg_get_functiondef / pg_get_triggerdef
+#[derive(Debug, Clone)]
+pub struct PgDbObject {
+    pub object_type: StructureType,
+    pub schema_name: String,
+    pub object_name: String,
+    // table of a trigger, empty for others
+    pub table_name: String,
+    pub definition: String,
+}
@@ -2,6 +2,7 @@ pub mod column;
 pub mod comment;
 pub mod constraint;
 pub mod database;
+pub mod db_object;
 pub mod index;
 pub mod rbac;
 pub mod schema;
@@ -25,6 +25,14 @@ pub enum StructureType {
     // to properly extract and migrate role-based access control settings to the target database
     #[strum(serialize = "rbac")]
     Rbac,
+    // views, triggers and routines are only migrated if listed in do_structures explicitly
+    #[strum(serialize = "view")]
+    View,
+    #[strum(serialize = "trigger")]
+    Trigger,
+    // MySQL procedures / functions, Postgres procedures
+    #[strum(serialize = "routine")]
+    Routine,
     #[strum(serialize = "unknown")]
     // MongoDB
     #[strum(serialize = "collection")]
@@ -34,3 +42,10 @@ pub enum StructureType {
 
     Unknown,
 }
+
+impl StructureType {
+    /// not covered by do_structures=*, e.g. triggers would fire again on rows synced to the target
+    pub fn is_opt_in(&self) -> bool {
+        matches!(self, Self::View | Self::Trigger | Self::Routine)
+    }
+}
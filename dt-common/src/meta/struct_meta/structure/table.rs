@@ -9,5 +9,8 @@ pub struct Table {
     pub table_comment: String,
     pub character_set: String,
     pub table_collation: String,
+    // mysql only, explicitly specified in CREATE TABLE, e.g. COMPRESSED / 8
+    pub row_format: String,
+    pub key_block_size: String,
     pub columns: Vec<Column>,
 }
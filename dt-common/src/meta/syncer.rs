@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Instant};
 
 use crate::meta::position::Position;
 
@@ -7,4 +7,16 @@ pub struct Syncer {
     pub received_position: Position,
     pub committed_position: Position,
     pub committed_positions: HashMap<String, Position>,
+    // set by the pipeline when [pipeline] idle_threshold_secs is enabled
+    pub last_data_time: Option<Instant>,
+    pub is_idle: bool,
+}
+
+impl Syncer {
+    /// Heartbeats only advance positions of an idle source, so they are skipped while rows are
+    /// sunk, including when the pipeline is busy or throttled and positions move by themselves.
+    pub fn should_heartbeat(&self, heartbeat_interval_secs: u64) -> bool {
+        self.last_data_time
+            .is_none_or(|time| time.elapsed().as_secs() >= heartbeat_interval_secs)
+    }
 }
@@ -6,7 +6,8 @@ use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer}
 use super::col_value::ColValue;
 
 // Serde definition for tagged ColValue maps, currently used to persist
-// checker-state primary keys for inconsistent rows.
+// checker-state primary keys for inconsistent rows, rows of chunk files and rows of
+// spilled transactions.
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "ColValue")]
 pub enum TaggedColValueDef {
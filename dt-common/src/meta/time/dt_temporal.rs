@@ -0,0 +1,231 @@
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDateTime, Timelike};
+
+use super::dt_utc_time::DtNaiveTime;
+
+const MAX_PRECISION: u32 = 6;
+
+/// Date / time / datetime / timestamp values carried as strings in ColValue, parsed with the
+/// fractional digits of the source kept as precision, e.g.
+/// 2024-01-01 10:00:00.123456+08, 2024-01-01T10:00:00.12Z, -838:59:59, 10:20:12+00, 2024-01-01.
+///
+/// Used to format values for targets which do not accept the source format, e.g. utc offsets
+/// of pg values for mysql / clickhouse, without losing sub-second precision.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DtTemporal {
+    // YYYY-MM-DD as is, e.g. 0000-00-00 of mysql, empty for time values
+    pub date: String,
+    pub time: Option<DtNaiveTime>,
+    // fractional digits of the source, at most 6
+    pub precision: u32,
+    // utc offset in seconds
+    pub offset_secs: Option<i32>,
+}
+
+impl DtTemporal {
+    /// None if the value is not recognized, e.g. infinity or BC dates of pg
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let bytes = value.as_bytes();
+        let (date, rest) = if bytes.len() >= 10 && bytes[4] == b'-' && bytes[7] == b'-' {
+            (&value[..10], value[10..].trim_start_matches([' ', 'T']))
+        } else {
+            ("", value)
+        };
+        if !date.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+            return None;
+        }
+
+        let mut temporal = Self {
+            date: date.to_string(),
+            ..Default::default()
+        };
+        if rest.is_empty() {
+            return (!date.is_empty()).then_some(temporal);
+        }
+
+        let (is_negative, body) = match rest.strip_prefix('-') {
+            Some(body) if date.is_empty() => (true, body),
+            _ => (false, rest),
+        };
+        let end = body
+            .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+            .unwrap_or(body.len());
+        let (time_str, offset_str) = body.split_at(end);
+        if time_str.split(':').count() != 3 {
+            return None;
+        }
+
+        let time_str = match time_str.split_once('.') {
+            Some((hms, fraction)) => {
+                temporal.precision = (fraction.len() as u32).min(MAX_PRECISION);
+                // digits beyond micros are truncated
+                format!("{}.{}", hms, &fraction[..temporal.precision as usize])
+            }
+            None => time_str.to_string(),
+        };
+        let mut time = DtNaiveTime::from_str(time_str.trim_end_matches('.')).ok()?;
+        time.is_negative = is_negative;
+        temporal.time = Some(time);
+        temporal.offset_secs = Self::parse_offset(offset_str.trim())?;
+        Some(temporal)
+    }
+
+    /// Formats without utc offset, values with offset are converted to utc, the fractional
+    /// part is truncated to the precision, the source precision if None.
+    pub fn to_naive_string(&self, precision: Option<u32>) -> String {
+        let utc = self.to_utc();
+        let Some(time) = &utc.time else {
+            return utc.date;
+        };
+
+        let precision = precision.unwrap_or(utc.precision).min(MAX_PRECISION);
+        let mut time_str = format!("{:02}:{:02}:{:02}", time.hour, time.minute, time.second);
+        if precision > 0 {
+            let fraction = time.microsecond / 10u32.pow(MAX_PRECISION - precision);
+            time_str = format!(
+                "{}.{:0width$}",
+                time_str,
+                fraction,
+                width = precision as usize
+            );
+        }
+        if time.is_negative {
+            time_str = format!("-{}", time_str);
+        }
+
+        if utc.date.is_empty() {
+            time_str
+        } else {
+            format!("{} {}", utc.date, time_str)
+        }
+    }
+
+    fn to_utc(&self) -> Self {
+        let (Some(offset_secs), Some(time)) = (self.offset_secs, &self.time) else {
+            return self.clone();
+        };
+        let mut utc = Self {
+            offset_secs: Some(0),
+            ..self.clone()
+        };
+        if offset_secs == 0 {
+            return utc;
+        }
+
+        if self.date.is_empty() {
+            // time with time zone of pg
+            let secs = (time.hour * 3600 + time.minute * 60 + time.second) as i64;
+            let secs = (secs - offset_secs as i64).rem_euclid(86400);
+            utc.time = Some(DtNaiveTime {
+                hour: (secs / 3600) as u32,
+                minute: (secs % 3600 / 60) as u32,
+                second: (secs % 60) as u32,
+                ..time.clone()
+            });
+            return utc;
+        }
+
+        let naive = format!(
+            "{} {:02}:{:02}:{:02}",
+            self.date, time.hour, time.minute, time.second
+        );
+        // zero dates of mysql can not be converted
+        if let Ok(naive) = NaiveDateTime::parse_from_str(&naive, "%Y-%m-%d %H:%M:%S") {
+            let naive = naive - Duration::seconds(offset_secs as i64);
+            utc.date = naive.format("%Y-%m-%d").to_string();
+            utc.time = Some(DtNaiveTime {
+                hour: naive.hour(),
+                minute: naive.minute(),
+                second: naive.second(),
+                ..time.clone()
+            });
+        }
+        utc
+    }
+
+    // Z, UTC, +08, +0800, +08:00, -03:30:00
+    fn parse_offset(offset: &str) -> Option<Option<i32>> {
+        if offset.is_empty() {
+            return Some(None);
+        }
+        if offset == "Z" || offset == "UTC" {
+            return Some(Some(0));
+        }
+
+        let (sign, digits) = match offset.as_bytes()[0] {
+            b'+' => (1, &offset[1..]),
+            b'-' => (-1, &offset[1..]),
+            _ => return None,
+        };
+        let digits: String = digits.chars().filter(|c| *c != ':').collect();
+        if !digits.bytes().all(|b| b.is_ascii_digit()) || ![2, 4, 6].contains(&digits.len()) {
+            return None;
+        }
+        let mut secs = 0;
+        for (i, unit) in [3600, 60, 1].iter().enumerate() {
+            if let Some(part) = digits.get(i * 2..i * 2 + 2) {
+                secs += part.parse::<i32>().ok()? * unit;
+            }
+        }
+        Some(Some(sign * secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_naive_string() {
+        let cases = [
+            (
+                "2024-01-01 10:00:00.123456",
+                None,
+                "2024-01-01 10:00:00.123456",
+            ),
+            (
+                "2024-01-01 10:00:00.123456",
+                Some(3),
+                "2024-01-01 10:00:00.123",
+            ),
+            ("2024-01-01 10:00:00.12", None, "2024-01-01 10:00:00.12"),
+            (
+                "2024-01-01 10:00:00.12",
+                Some(6),
+                "2024-01-01 10:00:00.120000",
+            ),
+            ("2024-01-01 10:00:00", Some(6), "2024-01-01 10:00:00.000000"),
+            (
+                "2024-01-01 10:00:00.123456789",
+                None,
+                "2024-01-01 10:00:00.123456",
+            ),
+            (
+                "2024-01-01 10:00:00.123456+00",
+                None,
+                "2024-01-01 10:00:00.123456",
+            ),
+            ("2024-01-01 02:00:00.5+08", None, "2023-12-31 18:00:00.5"),
+            (
+                "2024-01-01T10:00:00.000001Z",
+                None,
+                "2024-01-01 10:00:00.000001",
+            ),
+            ("2024-01-01 10:00:00-05:30", None, "2024-01-01 15:30:00"),
+            ("0000-00-00 00:00:00", None, "0000-00-00 00:00:00"),
+            ("2024-01-01", Some(6), "2024-01-01"),
+            ("-838:59:59.5", None, "-838:59:59.5"),
+            ("10:20:12.000001+08", None, "02:20:12.000001"),
+        ];
+        for (value, precision, expected) in cases {
+            let temporal = DtTemporal::parse(value).unwrap();
+            assert_eq!(temporal.to_naive_string(precision), expected, "{}", value);
+        }
+
+        for value in ["infinity", "2024-01-01 10:00:00 BC", "10:20", "abc"] {
+            assert!(DtTemporal::parse(value).is_none(), "{}", value);
+        }
+    }
+}
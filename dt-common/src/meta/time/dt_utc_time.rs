@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::error::Error;
 
-#[derive(Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct DtNaiveTime {
     pub is_negative: bool,
     pub hour: u32,
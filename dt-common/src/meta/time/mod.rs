@@ -1 +1,2 @@
+pub mod dt_temporal;
 pub mod dt_utc_time;
@@ -36,6 +36,8 @@ pub enum CounterType {
     QueuedRecordCurrent,
     #[strum(serialize = "queued_bytes")]
     QueuedByteCurrent,
+    #[strum(serialize = "queued_control_records")]
+    QueuedControlCurrent,
     #[strum(serialize = "checker_pending")]
     CheckerPending,
     #[strum(serialize = "sinked_records")]
@@ -46,6 +48,14 @@ pub enum CounterType {
     DDLRecordTotal,
     #[strum(serialize = "timestamp")]
     Timestamp,
+    #[strum(serialize = "adaptive_batch_size")]
+    AdaptiveBatchSize,
+    // millis between now and the source commit time of the latest extracted data
+    #[strum(serialize = "extractor_lag")]
+    ExtractorLag,
+    // 1 while sinking is paused by the circuit breaker, otherwise 0
+    #[strum(serialize = "circuit_breaker_open")]
+    CircuitBreakerOpen,
 }
 
 #[derive(EnumString, IntoStaticStr, Display, PartialEq, Eq, Hash, Clone)]
@@ -96,9 +106,13 @@ impl CounterType {
             | Self::SinkedByteTotal
             | Self::QueuedRecordCurrent
             | Self::QueuedByteCurrent
+            | Self::QueuedControlCurrent
             | Self::CheckerPending
             | Self::DDLRecordTotal
-            | Self::Timestamp => WindowType::NoWindow,
+            | Self::Timestamp
+            | Self::AdaptiveBatchSize
+            | Self::ExtractorLag
+            | Self::CircuitBreakerOpen => WindowType::NoWindow,
         }
     }
 
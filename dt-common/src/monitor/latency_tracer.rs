@@ -0,0 +1,273 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    log_info,
+    meta::dt_data::DtItem,
+    monitor::{task_metrics::TaskMetricsType, task_monitor_handle::TaskMonitorHandle},
+};
+
+// latencies kept per stage in a report window, samples beyond it are dropped
+const MAX_WINDOW_SAMPLES: usize = 100_000;
+
+/// Timestamps of a sampled item on its way from the extractor to the target.
+#[derive(Debug, Clone)]
+pub struct ItemTrace {
+    // handed to the buffer by the extractor
+    pub extracted_at: Instant,
+    // pushed into the buffer, after waiting for capacity / limiters
+    pub enqueued_at: Option<Instant>,
+    // popped from the buffer by the pipeline
+    pub drained_at: Option<Instant>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    // extracted -> enqueued
+    Enqueue,
+    // enqueued -> drained
+    Buffer,
+    // drained -> sink complete
+    Sink,
+    // extracted -> sink complete
+    Total,
+}
+
+const STAGES: [Stage; 4] = [Stage::Enqueue, Stage::Buffer, Stage::Sink, Stage::Total];
+
+impl Stage {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Enqueue => "enqueue",
+            Self::Buffer => "buffer",
+            Self::Sink => "sink",
+            Self::Total => "total",
+        }
+    }
+
+    fn metrics_types(&self) -> (TaskMetricsType, TaskMetricsType) {
+        match self {
+            Self::Enqueue => (
+                TaskMetricsType::TraceEnqueueLatencyP50,
+                TaskMetricsType::TraceEnqueueLatencyP99,
+            ),
+            Self::Buffer => (
+                TaskMetricsType::TraceBufferLatencyP50,
+                TaskMetricsType::TraceBufferLatencyP99,
+            ),
+            Self::Sink => (
+                TaskMetricsType::TraceSinkLatencyP50,
+                TaskMetricsType::TraceSinkLatencyP99,
+            ),
+            Self::Total => (
+                TaskMetricsType::TraceTotalLatencyP50,
+                TaskMetricsType::TraceTotalLatencyP99,
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct TraceWindow {
+    // latencies in micros, indexed by STAGES
+    latencies: [Vec<u64>; 4],
+    dropped: u64,
+}
+
+/// Samples one of every N data items pushed into the buffer and reports percentile
+/// latencies of each stage they pass through, by logs and task metrics.
+pub struct LatencyTracer {
+    sample_every: u64,
+    report_interval: Duration,
+    seen: AtomicU64,
+    window: Mutex<TraceWindow>,
+    last_report: Mutex<Instant>,
+}
+
+impl LatencyTracer {
+    /// Returns None if sample_rate is not positive.
+    pub fn new(sample_rate: f64, report_interval_secs: u64) -> Option<Self> {
+        if sample_rate <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            sample_every: (1.0 / sample_rate.min(1.0)).round().max(1.0) as u64,
+            report_interval: Duration::from_secs(report_interval_secs.max(1)),
+            seen: AtomicU64::new(0),
+            window: Mutex::new(TraceWindow::default()),
+            last_report: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Called by the buffer for each data item pushed.
+    pub fn sample(&self) -> Option<ItemTrace> {
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        (seen % self.sample_every == 0).then(|| ItemTrace {
+            extracted_at: Instant::now(),
+            enqueued_at: None,
+            drained_at: None,
+        })
+    }
+
+    /// Takes traces of drained items, to be recorded once the items are sunk.
+    pub fn take_traces(data: &mut [DtItem]) -> Vec<ItemTrace> {
+        let now = Instant::now();
+        data.iter_mut()
+            .filter_map(|item| item.trace.take())
+            .map(|mut trace| {
+                trace.drained_at = Some(now);
+                trace
+            })
+            .collect()
+    }
+
+    pub fn record(&self, traces: Vec<ItemTrace>) {
+        if traces.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let mut window = self.window.lock().unwrap();
+        for trace in traces {
+            let (Some(enqueued_at), Some(drained_at)) = (trace.enqueued_at, trace.drained_at)
+            else {
+                continue;
+            };
+            if window.latencies[0].len() >= MAX_WINDOW_SAMPLES {
+                window.dropped += 1;
+                continue;
+            }
+            let latencies = [
+                enqueued_at - trace.extracted_at,
+                drained_at - enqueued_at,
+                now - drained_at,
+                now - trace.extracted_at,
+            ];
+            for (i, latency) in latencies.iter().enumerate() {
+                window.latencies[i].push(latency.as_micros() as u64);
+            }
+        }
+    }
+
+    /// Logs and exports the percentiles of the window once report_interval passes.
+    pub fn try_report(&self, monitor: &TaskMonitorHandle) {
+        let elapsed = {
+            let mut last_report = self.last_report.lock().unwrap();
+            let elapsed = last_report.elapsed();
+            if elapsed < self.report_interval {
+                return;
+            }
+            *last_report = Instant::now();
+            elapsed
+        };
+
+        let mut window = std::mem::take(&mut *self.window.lock().unwrap());
+        if window.latencies[0].is_empty() {
+            return;
+        }
+
+        let mut stats = Vec::new();
+        for (i, stage) in STAGES.iter().enumerate() {
+            let latencies = &mut window.latencies[i];
+            latencies.sort_unstable();
+            let (p50, p90, p99, max) = (
+                Self::percentile(latencies, 50),
+                Self::percentile(latencies, 90),
+                Self::percentile(latencies, 99),
+                latencies.last().copied().unwrap_or_default(),
+            );
+            stats.push(format!(
+                "{}: p50={}us p90={}us p99={}us max={}us",
+                stage.name(),
+                p50,
+                p90,
+                p99,
+                max
+            ));
+            let (p50_type, p99_type) = stage.metrics_types();
+            monitor.set_no_window_metrics(p50_type, p50);
+            monitor.set_no_window_metrics(p99_type, p99);
+        }
+        log_info!(
+            "latency of {} sampled item(s) in the last {}s, dropped: {}, {}",
+            window.latencies[0].len(),
+            elapsed.as_secs(),
+            window.dropped,
+            stats.join(", ")
+        );
+    }
+
+    // nearest-rank percentile of sorted latencies
+    fn percentile(sorted: &[u64], percent: usize) -> u64 {
+        if sorted.is_empty() {
+            return 0;
+        }
+        let rank = (sorted.len() * percent).div_ceil(100).max(1);
+        sorted[rank - 1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::{dt_data::DtData, position::Position};
+
+    #[test]
+    fn test_sample() {
+        let tracer = LatencyTracer::new(0.25, 60).unwrap();
+        let sampled = (0..100).filter(|_| tracer.sample().is_some()).count();
+        assert_eq!(sampled, 25);
+
+        let tracer = LatencyTracer::new(2.0, 60).unwrap();
+        assert!((0..10).all(|_| tracer.sample().is_some()));
+
+        assert!(LatencyTracer::new(0.0, 60).is_none());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<u64> = (1..=100).collect();
+        assert_eq!(LatencyTracer::percentile(&sorted, 50), 50);
+        assert_eq!(LatencyTracer::percentile(&sorted, 99), 99);
+        assert_eq!(LatencyTracer::percentile(&[7], 99), 7);
+        assert_eq!(LatencyTracer::percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_take_and_record() {
+        let tracer = LatencyTracer::new(1.0, 60).unwrap();
+        let extracted_at = Instant::now() - Duration::from_millis(30);
+        let mut data = vec![
+            DtItem {
+                dt_data: DtData::Heartbeat {},
+                position: Position::None,
+                data_origin_node: String::new(),
+                trace: Some(ItemTrace {
+                    extracted_at,
+                    enqueued_at: Some(extracted_at + Duration::from_millis(10)),
+                    drained_at: None,
+                }),
+            },
+            DtItem {
+                dt_data: DtData::Heartbeat {},
+                position: Position::None,
+                data_origin_node: String::new(),
+                trace: None,
+            },
+        ];
+
+        let traces = LatencyTracer::take_traces(&mut data);
+        assert_eq!(traces.len(), 1);
+        assert!(data.iter().all(|item| item.trace.is_none()));
+
+        tracer.record(traces);
+        let window = tracer.window.lock().unwrap();
+        assert_eq!(window.latencies[0], vec![10_000]);
+        assert!(window.latencies[1][0] >= 20_000);
+        assert!(window.latencies[3][0] >= 30_000);
+    }
+}
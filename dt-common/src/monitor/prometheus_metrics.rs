@@ -111,6 +111,11 @@ impl PrometheusMetrics {
             "the bytes in pipeline queue",
             TaskMetricsType::PipelineQueueBytes,
         );
+        register_handler(
+            "pipeline_control_queue_size",
+            "the control items, e.g. ddl, begin / commit and heartbeat, in pipeline queue",
+            TaskMetricsType::PipelineControlQueueSize,
+        );
 
         register_handler(
             "sinker_rt_max",
@@ -229,6 +234,56 @@ impl PrometheusMetrics {
             "the average diff records per second of checker",
             TaskMetricsType::CheckerDiffRpsAvg,
         );
+        register_handler(
+            "artifact_bytes",
+            "the bytes of local artifacts in dirs managed by janitor",
+            TaskMetricsType::ArtifactBytes,
+        );
+        register_handler(
+            "artifact_volume_used_percent",
+            "the max used percent of volumes holding dirs managed by janitor",
+            TaskMetricsType::ArtifactVolumeUsedPercent,
+        );
+        register_handler(
+            "trace_enqueue_latency_p50",
+            "the p50 latency in micros of sampled items from extracted to pushed into the buffer",
+            TaskMetricsType::TraceEnqueueLatencyP50,
+        );
+        register_handler(
+            "trace_enqueue_latency_p99",
+            "the p99 latency in micros of sampled items from extracted to pushed into the buffer",
+            TaskMetricsType::TraceEnqueueLatencyP99,
+        );
+        register_handler(
+            "trace_buffer_latency_p50",
+            "the p50 latency in micros of sampled items from pushed into to drained from the buffer",
+            TaskMetricsType::TraceBufferLatencyP50,
+        );
+        register_handler(
+            "trace_buffer_latency_p99",
+            "the p99 latency in micros of sampled items from pushed into to drained from the buffer",
+            TaskMetricsType::TraceBufferLatencyP99,
+        );
+        register_handler(
+            "trace_sink_latency_p50",
+            "the p50 latency in micros of sampled items from drained from the buffer to sunk",
+            TaskMetricsType::TraceSinkLatencyP50,
+        );
+        register_handler(
+            "trace_sink_latency_p99",
+            "the p99 latency in micros of sampled items from drained from the buffer to sunk",
+            TaskMetricsType::TraceSinkLatencyP99,
+        );
+        register_handler(
+            "trace_total_latency_p50",
+            "the p50 latency in micros of sampled items from extracted to sunk",
+            TaskMetricsType::TraceTotalLatencyP50,
+        );
+        register_handler(
+            "trace_total_latency_p99",
+            "the p99 latency in micros of sampled items from extracted to sunk",
+            TaskMetricsType::TraceTotalLatencyP99,
+        );
 
         if let Some(task_type) = &self.task_type {
             match task_type.kind {
@@ -250,6 +305,11 @@ impl PrometheusMetrics {
                         "the timestamp of task",
                         TaskMetricsType::Timestamp,
                     );
+                    register_handler(
+                        "extractor_lag_ms",
+                        "the lag of extractor behind the source in millis",
+                        TaskMetricsType::Delay,
+                    );
                     register_handler(
                         "sinker_ddl_count",
                         "the count of DDL operations",
@@ -17,7 +17,7 @@ use strum::{Display, EnumString, IntoStaticStr};
 )]
 #[serde(rename_all = "snake_case")]
 pub enum TaskMetricsType {
-    // TODO
+    // lag of cdc extractors behind the source, in millis
     Delay,
     Timestamp,
     Progress,
@@ -57,6 +57,7 @@ pub enum TaskMetricsType {
 
     PipelineQueueSize,
     PipelineQueueBytes,
+    PipelineControlQueueSize,
 
     PipelineRecordSizeMax,
 
@@ -75,4 +76,18 @@ pub enum TaskMetricsType {
     SinkerSinkedBytes,
 
     SinkerDdlCount,
+
+    // local artifacts managed by the janitor
+    ArtifactBytes,
+    ArtifactVolumeUsedPercent,
+
+    // latencies of items sampled by [pipeline] trace_sample_rate, in micros
+    TraceEnqueueLatencyP50,
+    TraceEnqueueLatencyP99,
+    TraceBufferLatencyP50,
+    TraceBufferLatencyP99,
+    TraceSinkLatencyP50,
+    TraceSinkLatencyP99,
+    TraceTotalLatencyP50,
+    TraceTotalLatencyP99,
 }
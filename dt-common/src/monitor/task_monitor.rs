@@ -302,6 +302,10 @@ impl TaskMonitor {
             .or_insert(value);
     }
 
+    pub fn set_no_window_metrics(&self, metrics_type: TaskMetricsType, value: u64) {
+        self.no_window_metrics_map.insert(metrics_type, value);
+    }
+
     pub fn get_no_window_metric(&self, metrics_type: TaskMetricsType) -> u64 {
         self.no_window_metrics_map
             .get(&metrics_type)
@@ -652,6 +656,8 @@ impl TaskMonitor {
             .remove(&TaskMetricsType::PipelineQueueSize);
         self.no_window_metrics_map
             .remove(&TaskMetricsType::PipelineQueueBytes);
+        self.no_window_metrics_map
+            .remove(&TaskMetricsType::PipelineControlQueueSize);
     }
 
     async fn cleanup_monitors(&self) {
@@ -797,7 +803,14 @@ fn calc_nowindow_metrics(
 
     for (monitor_type, monitor) in calc_monitors {
         match monitor_type {
-            MonitorType::Extractor => {}
+            MonitorType::Extractor => {
+                metric_handler(
+                    &monitor,
+                    CounterType::ExtractorLag,
+                    TaskMetricsType::Delay,
+                    CalcType::Latest,
+                );
+            }
             MonitorType::Sinker => {}
             MonitorType::Checker => {
                 metric_handler(
@@ -826,6 +839,12 @@ fn calc_nowindow_metrics(
                     TaskMetricsType::PipelineQueueBytes,
                     CalcType::Latest,
                 );
+                metric_handler(
+                    &monitor,
+                    CounterType::QueuedControlCurrent,
+                    TaskMetricsType::PipelineControlQueueSize,
+                    CalcType::Latest,
+                );
                 batch_metrics_handler(
                     &monitor,
                     CounterType::DDLRecordTotal,
@@ -187,6 +187,12 @@ impl TaskMonitorHandle {
         }
     }
 
+    pub fn set_no_window_metrics(&self, metrics_type: TaskMetricsType, value: u64) {
+        if let Some(task_monitor) = &self.task_monitor {
+            task_monitor.set_no_window_metrics(metrics_type, value);
+        }
+    }
+
     pub fn build_monitor(&self, name: &str, task_id: &str) -> Arc<Monitor> {
         Arc::new(Monitor::new(
             name,
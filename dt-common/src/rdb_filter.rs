@@ -1,7 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use anyhow::Context;
-use dashmap::DashMap;
+use anyhow::bail;
+use dashmap::{DashMap, DashSet};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +15,7 @@ use crate::{
         config_token_parser::{ConfigTokenParser, TokenEscapePair},
         filter_config::FilterConfig,
     },
+    error::Error,
     meta::{
         ddl_meta::ddl_type::DdlType, row_type::RowType,
         struct_meta::structure::structure_type::StructureType,
@@ -41,11 +45,17 @@ pub struct RdbFilter {
     pub ignore_cmds: HashSet<String>,
     pub where_conditions: WhereConditions,
     pub cache: DashMap<(String, String), bool>,
+    // tables removed from a running task, shared by all clones of the filter
+    pub removed_tbs: Arc<DashSet<(String, String)>>,
+    // tables backfilled into a running task, shared by all clones of the filter
+    pub added_tbs: Arc<DashSet<(String, String)>>,
+    // compiled regexes of patterns, shared by all clones of the filter
+    pub patterns: Arc<DashMap<String, Regex>>,
 }
 
 impl RdbFilter {
     pub fn from_config(config: &FilterConfig, db_type: &DbType) -> anyhow::Result<Self> {
-        Ok(Self {
+        let filter = Self {
             db_type: db_type.to_owned(),
             do_schemas: Self::parse_single_tokens(&config.do_schemas, db_type)?,
             ignore_schemas: Self::parse_single_tokens(&config.ignore_schemas, db_type)?,
@@ -59,35 +69,68 @@ impl RdbFilter {
             ignore_cmds: Self::parse_single_tokens(&config.ignore_cmds, db_type)?,
             where_conditions: Self::parse_where_conditions(&config.where_conditions)?,
             cache: DashMap::new(),
-        })
+            removed_tbs: Arc::new(DashSet::new()),
+            added_tbs: Arc::new(DashSet::new()),
+            patterns: Arc::new(DashMap::new()),
+        };
+        filter.compile_patterns()?;
+        Ok(filter)
+    }
+
+    /// Compiles all patterns of dbs, tables and events, so invalid ones fail the task on
+    /// start instead of when data of the table comes.
+    pub fn compile_patterns(&self) -> anyhow::Result<()> {
+        let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
+        let single_tokens = self
+            .do_schemas
+            .iter()
+            .chain(self.ignore_schemas.iter())
+            .chain(self.do_events.iter());
+        let pair_tokens = self
+            .do_tbs
+            .iter()
+            .chain(self.ignore_tbs.iter())
+            .flat_map(|(schema, tb)| [schema, tb]);
+        for pattern in single_tokens.chain(pair_tokens) {
+            self.get_regex(pattern, &escape_pairs)?;
+        }
+        Ok(())
     }
 
     pub fn filter_schema(&self, schema: &str) -> bool {
         let tb = "*";
         let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
-        let filter = Self::contain_tb(&self.ignore_tbs, schema, tb, &escape_pairs)
-            || Self::contain_schema(&self.ignore_schemas, schema, &escape_pairs);
+        let filter = self.contain_tb(&self.ignore_tbs, schema, tb, &escape_pairs)
+            || self.contain_schema(&self.ignore_schemas, schema, &escape_pairs);
 
         if filter {
             return filter;
         }
 
         let do_tb_schemas: HashSet<String> = self.do_tbs.iter().map(|(d, _)| d.clone()).collect();
-        let keep = Self::contain_schema(&self.do_schemas, schema, &escape_pairs)
-            || Self::contain_schema(&do_tb_schemas, schema, &escape_pairs);
+        let keep = self.contain_schema(&self.do_schemas, schema, &escape_pairs)
+            || self.contain_schema(&do_tb_schemas, schema, &escape_pairs);
         !keep
     }
 
     pub fn filter_tb(&self, schema: &str, tb: &str) -> bool {
+        // checked before cache since removal may happen in another clone
+        if self.is_tb_removed(schema, tb) {
+            return true;
+        }
+        if self.is_tb_added(schema, tb) {
+            return false;
+        }
+
         if let Some(cache) = self.cache.get(&(schema.to_string(), tb.to_string())) {
             return *cache;
         }
 
         let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
-        let filter = Self::contain_tb(&self.ignore_tbs, schema, tb, &escape_pairs)
-            || Self::contain_schema(&self.ignore_schemas, schema, &escape_pairs);
-        let keep = Self::contain_tb(&self.do_tbs, schema, tb, &escape_pairs)
-            || Self::contain_schema(&self.do_schemas, schema, &escape_pairs);
+        let filter = self.contain_tb(&self.ignore_tbs, schema, tb, &escape_pairs)
+            || self.contain_schema(&self.ignore_schemas, schema, &escape_pairs);
+        let keep = self.contain_tb(&self.do_tbs, schema, tb, &escape_pairs)
+            || self.contain_schema(&self.do_schemas, schema, &escape_pairs);
 
         let filter = filter || !keep;
         self.cache
@@ -97,7 +140,10 @@ impl RdbFilter {
     }
 
     pub fn filter_event(&self, schema: &str, tb: &str, row_type: &RowType) -> bool {
-        if !Self::match_all(&self.do_events) && !self.do_events.contains(&row_type.to_string()) {
+        // events can also be patterns, e.g. r#insert|update#
+        if !Self::match_all(&self.do_events)
+            && !self.contain_schema(&self.do_events, &row_type.to_string(), &[])
+        {
             return true;
         }
         self.filter_tb(schema, tb)
@@ -128,6 +174,9 @@ impl RdbFilter {
     }
 
     pub fn filter_structure(&self, structure_type: &StructureType) -> bool {
+        if structure_type.is_opt_in() {
+            return !self.do_structures.contains(&structure_type.to_string());
+        }
         !Self::match_all(&self.do_structures)
             && !self.do_structures.contains(&structure_type.to_string())
     }
@@ -148,6 +197,65 @@ impl RdbFilter {
         self.do_tbs.insert((schema.into(), tb.into()));
     }
 
+    /// Parses tables in the same format as do_tbs and removes them from the running task,
+    /// returns the tables that were not removed before.
+    pub fn remove_tbs(&self, config_str: &str) -> anyhow::Result<Vec<(String, String)>> {
+        let mut removed = Vec::new();
+        let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
+        for (schema, tb) in Self::parse_pair_tokens(config_str, &self.db_type)? {
+            self.get_regex(&schema, &escape_pairs)?;
+            self.get_regex(&tb, &escape_pairs)?;
+            if self.removed_tbs.insert((schema.clone(), tb.clone())) {
+                removed.push((schema, tb));
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn is_tb_removed(&self, schema: &str, tb: &str) -> bool {
+        if self.removed_tbs.is_empty() {
+            return false;
+        }
+        self.removed_tbs
+            .iter()
+            .any(|removed| self.match_removed_tb(&removed, schema, tb))
+    }
+
+    pub fn match_removed_tb(&self, removed: &(String, String), schema: &str, tb: &str) -> bool {
+        self.match_tb_pattern(removed, schema, tb)
+    }
+
+    /// Parses tables in the same format as do_tbs and adds them to the running task, e.g. by
+    /// backfill, returns the tables that were not added before.
+    pub fn add_tbs(&self, config_str: &str) -> anyhow::Result<Vec<(String, String)>> {
+        let mut added = Vec::new();
+        let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
+        for (schema, tb) in Self::parse_pair_tokens(config_str, &self.db_type)? {
+            self.get_regex(&schema, &escape_pairs)?;
+            self.get_regex(&tb, &escape_pairs)?;
+            if self.added_tbs.insert((schema.clone(), tb.clone())) {
+                added.push((schema, tb));
+            }
+        }
+        Ok(added)
+    }
+
+    pub fn is_tb_added(&self, schema: &str, tb: &str) -> bool {
+        if self.added_tbs.is_empty() {
+            return false;
+        }
+        self.added_tbs
+            .iter()
+            .any(|added| self.match_tb_pattern(&added, schema, tb))
+    }
+
+    /// Matches a table against a (schema, tb) pair parsed from do_tbs format.
+    pub fn match_tb_pattern(&self, pattern: &(String, String), schema: &str, tb: &str) -> bool {
+        let escape_pairs = SqlUtil::get_escape_pairs(&self.db_type);
+        self.match_cached_token(&pattern.0, schema, &escape_pairs)
+            && self.match_cached_token(&pattern.1, tb, &escape_pairs)
+    }
+
     pub fn get_where_condition(&self, schema: &str, tb: &str) -> Option<&String> {
         self.where_conditions
             .get(&(schema.to_string(), tb.to_string()))
@@ -167,14 +275,15 @@ impl RdbFilter {
     }
 
     fn contain_tb(
+        &self,
         set: &HashSet<(String, String)>,
         schema: &str,
         tb: &str,
         escape_pairs: &[(char, char)],
     ) -> bool {
         for i in set.iter() {
-            if Self::match_token(&i.0, schema, escape_pairs)
-                && Self::match_token(&i.1, tb, escape_pairs)
+            if self.match_cached_token(&i.0, schema, escape_pairs)
+                && self.match_cached_token(&i.1, tb, escape_pairs)
             {
                 return true;
             }
@@ -182,9 +291,14 @@ impl RdbFilter {
         false
     }
 
-    fn contain_schema(set: &HashSet<String>, item: &str, escape_pairs: &[(char, char)]) -> bool {
+    fn contain_schema(
+        &self,
+        set: &HashSet<String>,
+        item: &str,
+        escape_pairs: &[(char, char)],
+    ) -> bool {
         for i in set.iter() {
-            if Self::match_token(i, item, escape_pairs) {
+            if self.match_cached_token(i, item, escape_pairs) {
                 return true;
             }
         }
@@ -200,7 +314,41 @@ impl RdbFilter {
             }
         }
 
-        let mut pattern = pattern.to_string();
+        Self::compile_pattern(pattern)
+            .map(|regex| regex.is_match(item))
+            .unwrap_or(false)
+    }
+
+    // same as match_token, with the compiled regex taken from the cache of the filter
+    fn match_cached_token(&self, pattern: &str, item: &str, escape_pairs: &[(char, char)]) -> bool {
+        // patterns from configs are compiled in from_config, others are exact names added
+        // by add_do_tb / add_ignore_tb which always compile
+        match self.get_regex(pattern, escape_pairs) {
+            Ok(Some(regex)) => regex.is_match(item),
+            _ => Self::match_token(pattern, item, escape_pairs),
+        }
+    }
+
+    fn get_regex(
+        &self,
+        pattern: &str,
+        escape_pairs: &[(char, char)],
+    ) -> anyhow::Result<Option<Regex>> {
+        for escape_pair in escape_pairs.iter() {
+            if SqlUtil::is_escaped(pattern, escape_pair) {
+                return Ok(None);
+            }
+        }
+        if let Some(regex) = self.patterns.get(pattern) {
+            return Ok(Some(regex.clone()));
+        }
+        let regex = Self::compile_pattern(pattern)?;
+        self.patterns.insert(pattern.to_string(), regex.clone());
+        Ok(Some(regex))
+    }
+
+    fn compile_pattern(token: &str) -> anyhow::Result<Regex> {
+        let mut pattern = token.to_string();
         if !pattern.starts_with(REGEX_ESCAPE_PAIR.0) || !pattern.ends_with(REGEX_ESCAPE_PAIR.1) {
             // only support 2 wildchars : '*' and '?', '.' is NOT supported
             // * : matching multiple chars
@@ -218,13 +366,16 @@ impl RdbFilter {
         }
         pattern = format!(r"^{}$", pattern);
 
-        Regex::new(&pattern)
-            .with_context(|| format!("invalid filter pattern: [{}]", pattern))
-            .unwrap()
-            .is_match(item)
+        match Regex::new(&pattern) {
+            Ok(regex) => Ok(regex),
+            Err(e) => bail!(Error::ConfigError(format!(
+                "invalid filter pattern: [{}], error: {}",
+                token, e
+            ))),
+        }
     }
 
-    fn parse_pair_tokens(
+    pub fn parse_pair_tokens(
         config_str: &str,
         db_type: &DbType,
     ) -> anyhow::Result<HashSet<(String, String)>> {
@@ -321,6 +472,29 @@ mod tests {
         assert!(tb_2.contains(&"f_3".to_string()));
     }
 
+    #[test]
+    fn test_filter_opt_in_structures() {
+        let build = |do_structures: &str| {
+            let config = FilterConfig {
+                do_structures: do_structures.into(),
+                ..Default::default()
+            };
+            RdbFilter::from_config(&config, &DbType::Mysql).unwrap()
+        };
+
+        let filter = build("*");
+        assert!(!filter.filter_structure(&StructureType::Table));
+        assert!(filter.filter_structure(&StructureType::View));
+        assert!(filter.filter_structure(&StructureType::Trigger));
+
+        let filter = build("table,view,routine");
+        assert!(!filter.filter_structure(&StructureType::Table));
+        assert!(filter.filter_structure(&StructureType::Index));
+        assert!(!filter.filter_structure(&StructureType::View));
+        assert!(!filter.filter_structure(&StructureType::Routine));
+        assert!(filter.filter_structure(&StructureType::Trigger));
+    }
+
     #[test]
     fn test_match_token_without_escape() {
         let escape_pairs = vec![];
@@ -1044,4 +1218,88 @@ mod tests {
         assert!(rdb_filter.filter_event("test_db_1", "aaaa", &RowType::Update));
         assert!(rdb_filter.filter_event("test_db_1", "aaaa", &RowType::Delete));
     }
+
+    #[test]
+    fn test_rdb_filter_regex() {
+        let db_type = DbType::Mysql;
+        let config = FilterConfig {
+            do_tbs: r"r#order_\d{4}#.*".to_string(),
+            ignore_tbs: r"r#order_\d{4}#.r#tmp_.*#".to_string(),
+            do_events: "r#insert|update#".to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_filter.filter_schema("order_2024"));
+        assert!(rdb_filter.filter_schema("order_24"));
+        assert!(!rdb_filter.filter_tb("order_2024", "tb_1"));
+        assert!(rdb_filter.filter_tb("order_2024", "tmp_1"));
+        assert!(rdb_filter.filter_tb("order_202", "tb_1"));
+
+        assert!(!rdb_filter.filter_event("order_2024", "tb_1", &RowType::Insert));
+        assert!(!rdb_filter.filter_event("order_2024", "tb_1", &RowType::Update));
+        assert!(rdb_filter.filter_event("order_2024", "tb_1", &RowType::Delete));
+
+        // compiled once and shared by clones
+        assert!(rdb_filter.patterns.contains_key(r"r#order_\d{4}#"));
+        let cloned = rdb_filter.clone();
+        assert!(Arc::ptr_eq(&rdb_filter.patterns, &cloned.patterns));
+
+        let config = FilterConfig {
+            do_tbs: "r#order_(#.*".to_string(),
+            ..Default::default()
+        };
+        let err = RdbFilter::from_config(&config, &db_type).unwrap_err();
+        assert!(err.to_string().contains("r#order_(#"));
+    }
+
+    #[test]
+    fn test_remove_tbs() {
+        let db_type = DbType::Mysql;
+        let config = FilterConfig {
+            do_schemas: "test_db_*".to_string(),
+            do_events: "*".to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        let cloned = rdb_filter.clone();
+        // fill cache of the clone before removal
+        assert!(!cloned.filter_tb("test_db_1", "tb_1"));
+
+        let removed = rdb_filter
+            .remove_tbs("test_db_1.tb_1,test_db_1.`tb_2`")
+            .unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(rdb_filter.remove_tbs("test_db_1.tb_1").unwrap().is_empty());
+
+        assert!(cloned.filter_tb("test_db_1", "tb_1"));
+        assert!(cloned.filter_event("test_db_1", "tb_2", &RowType::Insert));
+        assert!(!cloned.filter_tb("test_db_1", "tb_3"));
+    }
+
+    #[test]
+    fn test_add_tbs() {
+        let db_type = DbType::Mysql;
+        let config = FilterConfig {
+            do_tbs: "test_db_1.tb_1".to_string(),
+            do_events: "*".to_string(),
+            ..Default::default()
+        };
+        let rdb_filter = RdbFilter::from_config(&config, &db_type).unwrap();
+        let cloned = rdb_filter.clone();
+        // fill cache of the clone before adding
+        assert!(cloned.filter_tb("test_db_1", "tb_2"));
+
+        let added = rdb_filter.add_tbs("test_db_1.`tb_2`,test_db_2.*").unwrap();
+        assert_eq!(added.len(), 2);
+        assert!(rdb_filter.add_tbs("test_db_2.*").unwrap().is_empty());
+
+        assert!(!cloned.filter_tb("test_db_1", "tb_1"));
+        assert!(!cloned.filter_tb("test_db_1", "tb_2"));
+        assert!(!cloned.filter_event("test_db_2", "tb_1", &RowType::Insert));
+        assert!(cloned.filter_tb("test_db_1", "tb_3"));
+
+        // removal wins over adding
+        rdb_filter.remove_tbs("test_db_2.tb_1").unwrap();
+        assert!(cloned.filter_tb("test_db_2", "tb_1"));
+    }
 }
@@ -1,5 +1,7 @@
 pub mod file_util;
 pub mod limit_queue;
+pub mod proxy_tunnel;
+pub mod query_timeout;
 pub mod redis_util;
 pub mod serialize_util;
 pub mod sql_util;
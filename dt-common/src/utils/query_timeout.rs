@@ -0,0 +1,122 @@
+use std::{future::Future, time::Duration};
+
+use sqlx::mysql::MySqlDatabaseError;
+
+use crate::{error::Error, log_error};
+
+pub const EXTRACTOR_STAGE: &str = "extractor";
+pub const META_STAGE: &str = "meta";
+pub const SINKER_STAGE: &str = "sinker";
+
+// mysql: ER_QUERY_TIMEOUT, raised by max_execution_time
+const MYSQL_TIMEOUT_ERROR: u16 = 3024;
+// pg: query_canceled, raised by statement_timeout
+const PG_TIMEOUT_ERROR: &str = "57014";
+
+/// Surfaces timed out statements as [Error::QueryTimeout] with the stage, table and sql,
+/// whether they are cancelled by the server or by the client side timeout.
+pub struct QueryTimeout {}
+
+impl QueryTimeout {
+    pub fn is_timeout(error: &sqlx::Error) -> bool {
+        if let sqlx::Error::Database(db_error) = error {
+            if let Some(mysql_error) = db_error.try_downcast_ref::<MySqlDatabaseError>() {
+                return mysql_error.number() == MYSQL_TIMEOUT_ERROR;
+            }
+            return db_error
+                .code()
+                .is_some_and(|code| code.as_ref() == PG_TIMEOUT_ERROR);
+        }
+        false
+    }
+
+    pub fn is_timeout_error(error: &anyhow::Error) -> bool {
+        error.chain().any(|cause| {
+            matches!(
+                cause.downcast_ref::<Error>(),
+                Some(Error::QueryTimeout { .. })
+            )
+        })
+    }
+
+    /// Converts statements cancelled by server side timeouts, other errors are kept.
+    pub fn map_err(
+        error: sqlx::Error,
+        stage: &str,
+        sql: &str,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Error {
+        if Self::is_timeout(&error) {
+            Self::timeout_error(stage, sql, schema, tb)
+        } else {
+            error.into()
+        }
+    }
+
+    /// Runs a statement with a client side timeout, None for no limit.
+    pub async fn run<T, F>(
+        timeout: Option<Duration>,
+        stage: &str,
+        sql: &str,
+        schema: &str,
+        tb: &str,
+        future: F,
+    ) -> anyhow::Result<T>
+    where
+        F: Future<Output = Result<T, sqlx::Error>>,
+    {
+        let result = match timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, future).await {
+                Ok(result) => result,
+                Err(_) => return Err(Self::timeout_error(stage, sql, schema, tb)),
+            },
+            None => future.await,
+        };
+        result.map_err(|error| Self::map_err(error, stage, sql, schema, tb))
+    }
+
+    fn timeout_error(stage: &str, sql: &str, schema: &str, tb: &str) -> anyhow::Error {
+        let error = Error::QueryTimeout {
+            stage: stage.into(),
+            schema: schema.into(),
+            tb: tb.into(),
+            sql: sql.into(),
+        };
+        log_error!("{}", error);
+        error.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run() {
+        let result = QueryTimeout::run(
+            Some(Duration::from_millis(10)),
+            "sinker",
+            "SELECT SLEEP(1)",
+            "db_1",
+            "tb_1",
+            async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok::<_, sqlx::Error>(())
+            },
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(QueryTimeout::is_timeout_error(&error));
+        assert_eq!(
+            error.to_string(),
+            "query timeout in sinker, schema: db_1, tb: tb_1, sql: SELECT SLEEP(1)"
+        );
+
+        let result = QueryTimeout::run(None, "sinker", "", "", "", async {
+            Err::<(), _>(sqlx::Error::RowNotFound)
+        })
+        .await;
+        assert!(!QueryTimeout::is_timeout_error(&result.unwrap_err()));
+    }
+}
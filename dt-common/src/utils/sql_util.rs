@@ -1,3 +1,5 @@
+use std::fmt;
+
 use regex::Regex;
 use sqlx::{mysql::MySqlRow, ColumnIndex, Row};
 
@@ -16,8 +18,11 @@ macro_rules! quote_mysql {
         ""
     };
     ($s:expr) => {
-        // return borrowed str
-        format_args!("{}{}{}", MYSQL_ESCAPE, $s, MYSQL_ESCAPE)
+        // borrows the identifier, quoted when formatted
+        $crate::utils::sql_util::QuotedIdent::new(
+            AsRef::<str>::as_ref(&$s),
+            $crate::utils::sql_util::MYSQL_ESCAPE,
+        )
     };
 }
 
@@ -27,22 +32,76 @@ macro_rules! quote_pg {
         ""
     };
     ($s:expr) => {
-        format_args!("{}{}{}", PG_ESCAPE, $s, PG_ESCAPE)
+        $crate::utils::sql_util::QuotedIdent::new(
+            AsRef::<str>::as_ref(&$s),
+            $crate::utils::sql_util::PG_ESCAPE,
+        )
     };
 }
 
+/// An identifier enclosed by the escape char, embedded escape chars are doubled,
+/// so reserved words, spaces and quotes in names are kept as they are.
+pub struct QuotedIdent<'a> {
+    token: &'a str,
+    escape: char,
+}
+
+impl<'a> QuotedIdent<'a> {
+    pub fn new(token: &'a str, escape: char) -> Self {
+        Self { token, escape }
+    }
+}
+
+impl fmt::Display for QuotedIdent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.escape)?;
+        for c in self.token.chars() {
+            if c == self.escape {
+                write!(f, "{}", c)?;
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, "{}", self.escape)
+    }
+}
+
 impl SqlUtil {
     pub fn is_escaped(token: &str, escape_pair: &(char, char)) -> bool {
-        token.starts_with(escape_pair.0) && token.ends_with(escape_pair.1)
+        token.len() >= 2 && token.starts_with(escape_pair.0) && token.ends_with(escape_pair.1)
     }
 
     pub fn escape(token: &str, escape_pair: &(char, char)) -> String {
         if !Self::is_escaped(token, escape_pair) {
-            return format!(r#"{}{}{}"#, escape_pair.0, token, escape_pair.1);
+            return Self::quote(token, escape_pair);
         }
         token.to_string()
     }
 
+    /// Always quote the token, for identifiers from metadata which are never escaped,
+    /// use escape for names from configs which may be escaped already.
+    pub fn quote(token: &str, escape_pair: &(char, char)) -> String {
+        if escape_pair.0 == escape_pair.1 {
+            return QuotedIdent::new(token, escape_pair.0).to_string();
+        }
+        format!(r#"{}{}{}"#, escape_pair.0, token, escape_pair.1)
+    }
+
+    pub fn quote_by_db_type(token: &str, db_type: &DbType) -> String {
+        match Self::get_escape_pairs(db_type).first() {
+            Some(escape_pair) => Self::quote(token, escape_pair),
+            None => token.to_string(),
+        }
+    }
+
+    /// schema.tb with both quoted
+    pub fn quote_tb(schema: &str, tb: &str, db_type: &DbType) -> String {
+        format!(
+            "{}.{}",
+            Self::quote_by_db_type(schema, db_type),
+            Self::quote_by_db_type(tb, db_type)
+        )
+    }
+
     pub fn escape_by_db_type(token: &str, db_type: &DbType) -> String {
         let mut result = token.to_string();
         for escape_pair in Self::get_escape_pairs(db_type) {
@@ -55,10 +114,12 @@ impl SqlUtil {
         if !Self::is_escaped(token, escape_pair) {
             return token.to_string();
         }
-        token
-            .trim_start_matches(escape_pair.0)
-            .trim_end_matches(escape_pair.1)
-            .to_string()
+        let inner = &token[escape_pair.0.len_utf8()..token.len() - escape_pair.1.len_utf8()];
+        if escape_pair.0 == escape_pair.1 {
+            let escape = escape_pair.0.to_string();
+            return inner.replace(&escape.repeat(2), &escape);
+        }
+        inner.to_string()
     }
 
     pub fn unescape_by_db_type(token: &str, db_type: &DbType) -> String {
@@ -270,6 +331,50 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_quote_exotic_identifiers() {
+        let cases = [
+            ("order", "`order`", r#""order""#),
+            ("my table", "`my table`", r#""my table""#),
+            ("a`b", "`a``b`", r#""a`b""#),
+            (r#"a"b"#, r#"`a"b`"#, r#""a""b""#),
+            ("``", "``````", r#""``""#),
+            ("中文.表-1", "`中文.表-1`", r#""中文.表-1""#),
+        ];
+        for (token, mysql, pg) in cases {
+            assert_eq!(SqlUtil::quote_by_db_type(token, &DbType::Mysql), mysql);
+            assert_eq!(SqlUtil::quote_by_db_type(token, &DbType::Pg), pg);
+            assert_eq!(quote_mysql!(token).to_string(), mysql);
+            assert_eq!(quote_pg!(token).to_string(), pg);
+            assert_eq!(SqlUtil::unescape_by_db_type(mysql, &DbType::Mysql), token);
+            assert_eq!(SqlUtil::unescape_by_db_type(pg, &DbType::Pg), token);
+        }
+
+        assert_eq!(
+            SqlUtil::quote_tb("db`1", "select", &DbType::Mysql),
+            "`db``1`.`select`"
+        );
+        assert_eq!(
+            SqlUtil::quote_tb("public", r#"tb"1"#, &DbType::Pg),
+            r#""public"."tb""1""#
+        );
+        assert_eq!(SqlUtil::quote_by_db_type("tb", &DbType::Mongo), "tb");
+    }
+
+    #[test]
+    fn test_escape_identifiers() {
+        // tokens from configs may be escaped already
+        assert_eq!(SqlUtil::escape_by_db_type("`a b`", &DbType::Mysql), "`a b`");
+        assert_eq!(SqlUtil::escape_by_db_type("a`b", &DbType::Mysql), "`a``b`");
+        assert_eq!(SqlUtil::escape_by_db_type("`", &DbType::Mysql), "````");
+        assert_eq!(
+            SqlUtil::escape_by_db_type(r#"a"b"#, &DbType::Pg),
+            r#""a""b""#
+        );
+        assert_eq!(SqlUtil::unescape_by_db_type("`", &DbType::Mysql), "`");
+        assert_eq!(SqlUtil::unescape_by_db_type("a b", &DbType::Mysql), "a b");
+    }
+
     #[test]
     fn test_mysql_spatial_exprs() {
         assert_eq!(
@@ -32,13 +32,14 @@ mod tests {
 
     use crate::{
         config::{
-            config_enums::{DbType, ExtractType, SinkType},
+            config_enums::{DbType, ExtractType, IdentifierCase, SinkType},
             connection_auth_config::ConnectionAuthConfig,
             extractor_config::BasicExtractorConfig,
             filter_config::FilterConfig,
             limiter_config::RateLimiterConfig,
             router_config::RouterConfig,
             sinker_config::BasicSinkerConfig,
+            timeout_config::TimeoutConfig,
         },
         utils::task_util::TaskUtil,
     };
@@ -54,6 +55,7 @@ mod tests {
             rate_limiter: RateLimiterConfig::default(),
             app_name: None,
             is_direct_connection: None,
+            timeout: TimeoutConfig::default(),
         };
         let sinker_config = BasicSinkerConfig {
             db_type: DbType::Mysql,
@@ -66,6 +68,7 @@ mod tests {
             app_name: None,
             is_direct_connection: None,
             is_cluster: None,
+            ..Default::default()
         };
         let mut filter_config = FilterConfig {
             do_schemas: "db1,db2".to_string(),
@@ -85,6 +88,7 @@ mod tests {
             tb_map: "".to_string(),
             col_map: "".to_string(),
             topic_map: "".to_string(),
+            identifier_case: IdentifierCase::Preserve,
         };
         let mut generate_task_id = "".to_string();
         for _i in 0..10 {
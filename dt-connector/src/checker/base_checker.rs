@@ -53,6 +53,7 @@ pub enum CheckerTbMeta {
     Mysql(MysqlTbMeta),
     Pg(PgTbMeta),
     Mongo(RdbTbMeta),
+    ClickHouse(RdbTbMeta),
 }
 
 impl CheckerTbMeta {
@@ -60,7 +61,7 @@ impl CheckerTbMeta {
         match self {
             CheckerTbMeta::Mysql(m) => &m.basic,
             CheckerTbMeta::Pg(m) => &m.basic,
-            CheckerTbMeta::Mongo(m) => m,
+            CheckerTbMeta::Mongo(m) | CheckerTbMeta::ClickHouse(m) => m,
         }
     }
 
@@ -155,6 +156,10 @@ impl CheckerTbMeta {
         row_data: &RowData,
         match_full_row: bool,
     ) -> anyhow::Result<Option<String>> {
+        // ClickHouse targets are revised by replaying the check logs with ClickhouseSinker
+        if matches!(self, CheckerTbMeta::ClickHouse(_)) {
+            return Ok(None);
+        }
         macro_rules! build_query {
             ($meta:expr, $builder:ident) => {{
                 let meta_cow = if match_full_row {
@@ -173,6 +178,9 @@ impl CheckerTbMeta {
             CheckerTbMeta::Mysql(meta) => build_query!(meta, new_for_mysql),
             CheckerTbMeta::Pg(meta) => build_query!(meta, new_for_pg),
             CheckerTbMeta::Mongo(_) => unreachable!("Mongo handled before build_rdb_query"),
+            CheckerTbMeta::ClickHouse(_) => {
+                unreachable!("ClickHouse handled before build_rdb_query")
+            }
         }
     }
 }
@@ -196,6 +204,7 @@ pub struct CheckContext {
     pub check_log_dir: String,
     pub cdc_check_log_max_file_size: u64,
     pub cdc_check_log_max_rows: usize,
+    pub check_log_compress: bool,
     pub s3_output: Option<(Operator, String)>,
     pub cdc_check_log_interval_secs: u64,
     pub state_store: Option<Arc<CheckerStateStore>>,
@@ -224,6 +233,7 @@ impl Default for CheckContext {
             check_log_dir: String::new(),
             cdc_check_log_max_file_size: 1,
             cdc_check_log_max_rows: 1,
+            check_log_compress: false,
             s3_output: None,
             cdc_check_log_interval_secs: 30,
             state_store: None,
@@ -872,7 +882,7 @@ impl<C: Checker> DataChecker<C> {
 
             let (target_schema, target_tb) = match &self.ctx.router {
                 Some(router) => router.get_tb_map(schema, tb),
-                None => (schema.as_str(), tb.as_str()),
+                None => (schema.into(), tb.into()),
             };
             let (target_schema, target_tb) = (target_schema.to_string(), target_tb.to_string());
             self.checker
@@ -1167,8 +1177,11 @@ mod tests {
                 db_type: "mysql".to_string(),
                 schema: "src_schema".to_string(),
                 tb: "src_tb".to_string(),
+                row_count: None,
+                cdc_position: None,
             },
             data_origin_node: String::new(),
+            trace: None,
         };
 
         checker.handle_control_item(&item).await.unwrap();
@@ -7,6 +7,7 @@ use super::{
     RecheckKey,
 };
 use crate::checker::check_log::{CheckLog, CheckSummaryLog, CheckTableSummaryLog};
+use crate::checker::compressed_log;
 use crate::checker::state_store::{CheckerCheckpointCommit, CheckerStateRow};
 use dt_common::meta::{position::Position, row_data::RowData, row_type::RowType};
 use dt_common::{log_info, log_warn};
@@ -132,8 +133,16 @@ impl<C: Checker> DataChecker<C> {
                 sql_buf_builder.push_str(sql);
             }
         }
-        let miss_buf = miss_buf_builder.into_bytes();
-        let diff_buf = diff_buf_builder.into_bytes();
+        let mut miss_buf = miss_buf_builder.into_bytes();
+        let mut diff_buf = diff_buf_builder.into_bytes();
+        if self.ctx.check_log_compress {
+            if !miss_buf.is_empty() {
+                miss_buf = compressed_log::encode(&miss_buf)?;
+            }
+            if !diff_buf.is_empty() {
+                diff_buf = compressed_log::encode(&diff_buf)?;
+            }
+        }
         let sql_buf = sql_buf_builder.into_bytes();
 
         let summary = CheckSummaryLog {
@@ -156,6 +165,7 @@ impl<C: Checker> DataChecker<C> {
 
         Self::write_to_disk(
             &self.ctx.check_log_dir,
+            self.ctx.check_log_compress,
             write_optional_logs,
             &miss_buf,
             &diff_buf,
@@ -180,6 +190,7 @@ impl<C: Checker> DataChecker<C> {
 
     async fn write_to_disk(
         dir: &str,
+        compress: bool,
         write_optional_logs: bool,
         miss_buf: &[u8],
         diff_buf: &[u8],
@@ -191,14 +202,26 @@ impl<C: Checker> DataChecker<C> {
         let mut summary_with_newline = summary_buf.to_vec();
         summary_with_newline.push(b'\n');
         if write_optional_logs {
-            Self::write_optional_log(&path.join("miss.log"), miss_buf).await?;
-            Self::write_optional_log(&path.join("diff.log"), diff_buf).await?;
+            let (miss_file, diff_file) = Self::log_file_names(compress);
+            Self::write_optional_log(&path.join(miss_file), miss_buf).await?;
+            Self::write_optional_log(&path.join(diff_file), diff_buf).await?;
             Self::write_optional_log(&path.join("sql.log"), sql_buf).await?;
         }
         tokio::fs::write(path.join("summary.log"), summary_with_newline).await?;
         Ok(())
     }
 
+    fn log_file_names(compress: bool) -> (String, String) {
+        if compress {
+            (
+                compressed_log::compressed_log_name("miss.log"),
+                compressed_log::compressed_log_name("diff.log"),
+            )
+        } else {
+            ("miss.log".to_string(), "diff.log".to_string())
+        }
+    }
+
     async fn write_optional_log(path: &std::path::Path, buf: &[u8]) -> anyhow::Result<()> {
         if !buf.is_empty() {
             tokio::fs::write(path, buf).await?;
@@ -531,8 +554,9 @@ impl<C: Checker> DataChecker<C> {
         let Some((s3_client, key_prefix)) = &self.ctx.s3_output else {
             return Ok(());
         };
-        let miss_key = format!("{key_prefix}/miss.log");
-        let diff_key = format!("{key_prefix}/diff.log");
+        let (miss_file, diff_file) = Self::log_file_names(self.ctx.check_log_compress);
+        let miss_key = format!("{key_prefix}/{miss_file}");
+        let diff_key = format!("{key_prefix}/{diff_file}");
         let summary_key = format!("{key_prefix}/summary.log");
         let sql_key = format!("{key_prefix}/sql.log");
         s3_client.write(&summary_key, summary_buf.to_vec()).await?;
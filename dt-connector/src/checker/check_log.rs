@@ -145,6 +145,37 @@ impl CheckSummaryLog {
     }
 }
 
+/// One line of convergence.log, written after each round of a multi-round review.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReviewRoundLog {
+    pub round: u32,
+    pub check_log_dir: String,
+    // rows re-verified in this round, that is, rows in the miss / diff logs of the previous round
+    pub checked_count: usize,
+    pub resolved_count: usize,
+    pub miss_count: usize,
+    pub diff_count: usize,
+}
+
+impl ReviewRoundLog {
+    pub fn new(round: u32, check_log_dir: &str, summary: &CheckSummaryLog) -> Self {
+        Self {
+            round,
+            check_log_dir: check_log_dir.to_string(),
+            checked_count: summary.checked_count,
+            resolved_count: summary
+                .checked_count
+                .saturating_sub(summary.miss_count + summary.diff_count),
+            miss_count: summary.miss_count,
+            diff_count: summary.diff_count,
+        }
+    }
+
+    pub fn is_converged(&self) -> bool {
+        self.miss_count == 0 && self.diff_count == 0
+    }
+}
+
 pub fn to_json_line<T: Serialize>(value: &T) -> Option<String> {
     serde_json::to_string(value)
         .map_err(|e| {
@@ -163,6 +194,9 @@ pub struct StructCheckLog {
     pub src_sql: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dst_sql: Option<String>,
+    // column / key level differences of tables existing on both sides
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
 }
 
 impl StructCheckLog {
@@ -171,6 +205,7 @@ impl StructCheckLog {
             key: key.to_string(),
             src_sql,
             dst_sql,
+            details: Vec::new(),
         }
     }
 }
@@ -345,4 +380,28 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn review_round_log_counts_resolved_rows() {
+        let summary = CheckSummaryLog {
+            checked_count: 10,
+            miss_count: 2,
+            diff_count: 1,
+            ..Default::default()
+        };
+        let round_log = ReviewRoundLog::new(2, "/tmp/check/round_2", &summary);
+        assert_eq!(round_log.resolved_count, 7);
+        assert!(!round_log.is_converged());
+        assert_eq!(
+            json_line(&round_log),
+            json!({
+                "round": 2,
+                "check_log_dir": "/tmp/check/round_2",
+                "checked_count": 10,
+                "resolved_count": 7,
+                "miss_count": 2,
+                "diff_count": 1
+            })
+        );
+    }
 }
@@ -10,6 +10,7 @@ use super::{
     DataChecker, RecheckKey, RetryItem,
 };
 use crate::checker::check_log::{to_json_line, CheckLog, DiffColValue};
+use crate::checker::value_normalizer::ValueNormalizer;
 use crate::sinker::mongo::mongo_cmd;
 use dt_common::meta::{
     col_value::ColValue, mongo::mongo_constant::MongoConstants, pg::pg_value_type::PgValueType,
@@ -262,7 +263,8 @@ impl<C: Checker> DataChecker<C> {
         dst_val: &ColValue,
         tb_meta: &CheckerTbMeta,
     ) -> anyhow::Result<bool> {
-        if src_val.is_same_value(dst_val) {
+        // values of heterogeneous dbs may differ only in representation, e.g. 1.50 vs 1.5
+        if ValueNormalizer::is_same(src_val, dst_val) {
             return Ok(true);
         }
 
@@ -767,6 +769,13 @@ impl<C: Checker> DataChecker<C> {
     }
 
     async fn retry_check_item(&mut self, mut item: RetryItem) -> anyhow::Result<Option<RetryItem>> {
+        if self.ctx.source_checker.is_some() {
+            let Some(src_row) = self.refetch_source_row(&item.row).await? else {
+                // deleted on the source after being extracted, not a diff of the snapshot
+                return Ok(None);
+            };
+            item.row = src_row;
+        }
         let row_ref = &item.row;
         let tb_meta = self.checker.load_table_meta(row_ref).await?;
         let Some(key) = Self::lookup_match_key(&item.row, tb_meta.basic())? else {
@@ -800,6 +809,30 @@ impl<C: Checker> DataChecker<C> {
         Ok(None)
     }
 
+    /// Re-reads a mismatched row from the source, since it may have been updated after the
+    /// chunk containing it was extracted.
+    async fn refetch_source_row(&self, row: &RowData) -> anyhow::Result<Option<RowData>> {
+        let Some(source_checker) = self.ctx.source_checker.clone() else {
+            return Ok(Some(row.clone()));
+        };
+        let lookup_row = match &self.ctx.router {
+            Some(router) => router.reverse_route_row(row.clone()),
+            None => row.clone(),
+        };
+
+        let mut checker = source_checker.lock().await;
+        let tb_meta = checker.load_table_meta(&lookup_row).await?;
+        let src_row = checker
+            .fetch_rows_by_keys(tb_meta, std::slice::from_ref(&&lookup_row))
+            .await?
+            .into_iter()
+            .next();
+        Ok(match (src_row, &self.ctx.router) {
+            (Some(src_row), Some(router)) => Some(router.route_row(src_row)),
+            (src_row, _) => src_row,
+        })
+    }
+
     pub async fn drain_retries(&mut self) -> anyhow::Result<()> {
         while !self.retry_queue.is_empty() {
             let next_retry_at = self
@@ -1035,6 +1068,7 @@ mod tests {
                     },
                 ),
             ]),
+            ..Default::default()
         })
     }
 
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{Map, Value as JsonValue};
+
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    meta::{col_value::ColValue, rdb_tb_meta::RdbTbMeta, row_data::RowData, row_type::RowType},
+    utils::sql_util::SqlUtil,
+};
+
+use crate::checker::base_checker::{Checker, CheckerTbMeta, CHECKER_MAX_QUERY_BATCH};
+
+const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
+const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+
+/// Checks MySQL/PG source rows against ClickHouse tables written by ClickhouseSinker.
+/// Target values are converted back to the source value types before comparing, so that
+/// representation-only differences (DateTime precision, Decimal scale, hex-encoded binaries)
+/// are not reported as diffs.
+pub struct ClickhouseChecker {
+    http_client: Client,
+    url: String,
+    username: String,
+    password: String,
+    tb_infos: HashMap<(String, String), ClickhouseTbInfo>,
+}
+
+#[derive(Clone)]
+struct ClickhouseTbInfo {
+    meta: Arc<CheckerTbMeta>,
+    has_sign_col: bool,
+    use_final: bool,
+}
+
+#[async_trait]
+impl Checker for ClickhouseChecker {
+    async fn load_table_meta(
+        &mut self,
+        lookup_row: &RowData,
+    ) -> anyhow::Result<Arc<CheckerTbMeta>> {
+        let key = (lookup_row.schema.clone(), lookup_row.tb.clone());
+        if let Some(info) = self.tb_infos.get(&key) {
+            return Ok(info.meta.clone());
+        }
+        let info = self
+            .fetch_tb_info(&lookup_row.schema, &lookup_row.tb)
+            .await?;
+        let meta = info.meta.clone();
+        self.tb_infos.insert(key, info);
+        Ok(meta)
+    }
+
+    async fn fetch_rows_by_keys(
+        &mut self,
+        table_meta: Arc<CheckerTbMeta>,
+        lookup_rows: &[&RowData],
+    ) -> anyhow::Result<Vec<RowData>> {
+        let basic_meta = table_meta.basic();
+        let info = self
+            .tb_infos
+            .get(&(basic_meta.schema.clone(), basic_meta.tb.clone()))
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "ClickHouse table meta not loaded, schema: {}, tb: {}",
+                    basic_meta.schema, basic_meta.tb
+                )
+            })?;
+
+        let mut res = Vec::with_capacity(lookup_rows.len());
+        for chunk in lookup_rows.chunks(CHECKER_MAX_QUERY_BATCH) {
+            let src_rows: HashMap<String, &HashMap<String, ColValue>> = chunk
+                .iter()
+                .filter_map(|row| Self::key_values(row).ok())
+                .map(|values| (Self::src_match_key(values, &basic_meta.id_cols), values))
+                .collect();
+
+            let sql = Self::build_select_sql(&info, chunk)?;
+            for dst_row in self.query(&sql).await? {
+                let src_values = src_rows
+                    .get(&Self::dst_match_key(&dst_row, &basic_meta.id_cols))
+                    .copied();
+                res.push(Self::build_row_data(basic_meta, dst_row, src_values));
+            }
+        }
+        Ok(res)
+    }
+
+    async fn invalidate_meta_cache(&mut self, schema: &str, tb: &str) -> anyhow::Result<()> {
+        self.tb_infos.remove(&(schema.to_string(), tb.to_string()));
+        Ok(())
+    }
+}
+
+impl ClickhouseChecker {
+    pub fn new(
+        http_client: Client,
+        host: String,
+        port: String,
+        username: String,
+        password: String,
+    ) -> Self {
+        Self {
+            http_client,
+            url: format!("http://{}:{}/", host, port),
+            username,
+            password,
+            tb_infos: HashMap::new(),
+        }
+    }
+
+    async fn fetch_tb_info(&self, schema: &str, tb: &str) -> anyhow::Result<ClickhouseTbInfo> {
+        let sql = format!(
+            "SELECT engine, primary_key FROM system.tables WHERE database = {} AND name = {} FORMAT JSONEachRow",
+            Self::quote(schema),
+            Self::quote(tb)
+        );
+        let Some(tb_row) = self.query(&sql).await?.into_iter().next() else {
+            bail! {Error::MetadataError(format!(
+                "table not found in ClickHouse, schema: {}, tb: {}",
+                schema, tb
+            ))}
+        };
+        let engine = Self::json_text(tb_row.get("engine")).unwrap_or_default();
+        let id_cols: Vec<String> = Self::json_text(tb_row.get("primary_key"))
+            .unwrap_or_default()
+            .split(',')
+            .map(|col| col.trim().trim_matches('`').to_string())
+            .filter(|col| !col.is_empty())
+            .collect();
+        if id_cols.is_empty() {
+            bail! {Error::MetadataError(format!(
+                "ClickHouse table has no primary key, schema: {}, tb: {}",
+                schema, tb
+            ))}
+        }
+
+        let sql = format!(
+            "SELECT name FROM system.columns WHERE database = {} AND table = {} ORDER BY position FORMAT JSONEachRow",
+            Self::quote(schema),
+            Self::quote(tb)
+        );
+        let all_cols: Vec<String> = self
+            .query(&sql)
+            .await?
+            .iter()
+            .filter_map(|row| Self::json_text(row.get("name")))
+            .collect();
+        let has_sign_col = all_cols.iter().any(|col| col == SIGN_COL_NAME);
+        let cols = all_cols
+            .into_iter()
+            .filter(|col| col != SIGN_COL_NAME && col != TIMESTAMP_COL_NAME)
+            .collect();
+
+        let meta = RdbTbMeta {
+            schema: schema.to_string(),
+            tb: tb.to_string(),
+            cols,
+            id_cols,
+            ..Default::default()
+        };
+        Ok(ClickhouseTbInfo {
+            meta: Arc::new(CheckerTbMeta::ClickHouse(meta)),
+            has_sign_col,
+            // rows are deduplicated by ReplacingMergeTree in background merges only
+            use_final: engine.contains("ReplacingMergeTree")
+                || engine.contains("CollapsingMergeTree"),
+        })
+    }
+
+    fn build_select_sql(info: &ClickhouseTbInfo, rows: &[&RowData]) -> anyhow::Result<String> {
+        let basic_meta = info.meta.basic();
+        let id_cols = basic_meta
+            .id_cols
+            .iter()
+            .map(|col| SqlUtil::escape_by_db_type(col, &DbType::ClickHouse))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut tuples = Vec::with_capacity(rows.len());
+        for row in rows {
+            let values = Self::key_values(row)?;
+            let literals = basic_meta
+                .id_cols
+                .iter()
+                .map(|col| match Self::sink_text(values.get(col)) {
+                    Some(text) => Self::quote(&text),
+                    None => "NULL".to_string(),
+                })
+                .collect::<Vec<_>>();
+            tuples.push(format!("({})", literals.join(",")));
+        }
+
+        let mut sql = format!(
+            "SELECT * FROM {}.{}{} WHERE ({}) IN ({})",
+            SqlUtil::escape_by_db_type(&basic_meta.schema, &DbType::ClickHouse),
+            SqlUtil::escape_by_db_type(&basic_meta.tb, &DbType::ClickHouse),
+            if info.use_final { " FINAL" } else { "" },
+            id_cols,
+            tuples.join(",")
+        );
+        if info.has_sign_col {
+            sql.push_str(&format!(" AND {} = 0", SIGN_COL_NAME));
+        }
+        sql.push_str(" FORMAT JSONEachRow");
+        Ok(sql)
+    }
+
+    async fn query(&self, sql: &str) -> anyhow::Result<Vec<Map<String, JsonValue>>> {
+        let password = if self.password.is_empty() {
+            None
+        } else {
+            Some(self.password.clone())
+        };
+        let request = self
+            .http_client
+            .request(Method::POST, &self.url)
+            .basic_auth(&self.username, password)
+            .body(sql.to_string())
+            .build()?;
+        let response = self.http_client.execute(request).await?;
+        let status_code = response.status();
+        let response_text = response.text().await?;
+        if status_code != StatusCode::OK {
+            bail! {Error::HttpError(format!(
+                "ClickHouse query failed, status_code: {}, response_text: {:?}",
+                status_code, response_text
+            ))}
+        }
+
+        let mut rows = Vec::new();
+        for line in response_text.lines().filter(|line| !line.trim().is_empty()) {
+            rows.push(serde_json::from_str(line)?);
+        }
+        Ok(rows)
+    }
+
+    fn build_row_data(
+        basic_meta: &RdbTbMeta,
+        dst_row: Map<String, JsonValue>,
+        src_values: Option<&HashMap<String, ColValue>>,
+    ) -> RowData {
+        let mut after = HashMap::with_capacity(dst_row.len());
+        for (col, value) in dst_row {
+            if col == SIGN_COL_NAME || col == TIMESTAMP_COL_NAME {
+                continue;
+            }
+            let src_value = src_values.and_then(|values| values.get(&col));
+            after.insert(col, Self::to_col_value(&value, src_value));
+        }
+        RowData::new(
+            basic_meta.schema.clone(),
+            basic_meta.tb.clone(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    /// Converts a ClickHouse JSON value to the type of the source value, the source value
+    /// also decides the fractional digits of temporal and decimal values.
+    fn to_col_value(value: &JsonValue, src_value: Option<&ColValue>) -> ColValue {
+        let Some(text) = Self::json_text(Some(value)) else {
+            return ColValue::None;
+        };
+        let Some(src_value) = src_value else {
+            return ColValue::String(text);
+        };
+
+        let col_value = match src_value {
+            ColValue::Bool(_) => match value {
+                JsonValue::Bool(v) => Some(ColValue::Bool(*v)),
+                _ => text.parse::<i64>().ok().map(|v| ColValue::Bool(v != 0)),
+            },
+            ColValue::Tiny(_) => text.parse().ok().map(ColValue::Tiny),
+            ColValue::UnsignedTiny(_) => text.parse().ok().map(ColValue::UnsignedTiny),
+            ColValue::Short(_) => text.parse().ok().map(ColValue::Short),
+            ColValue::UnsignedShort(_) => text.parse().ok().map(ColValue::UnsignedShort),
+            ColValue::Long(_) => text.parse().ok().map(ColValue::Long),
+            ColValue::UnsignedLong(_) => text.parse().ok().map(ColValue::UnsignedLong),
+            ColValue::LongLong(_) => text.parse().ok().map(ColValue::LongLong),
+            ColValue::UnsignedLongLong(_) => text.parse().ok().map(ColValue::UnsignedLongLong),
+            ColValue::Year(_) => text.parse().ok().map(ColValue::Year),
+            ColValue::Bit(_) => text.parse().ok().map(ColValue::Bit),
+            ColValue::Set(_) => text.parse().ok().map(ColValue::Set),
+            ColValue::Enum(_) => text.parse().ok().map(ColValue::Enum),
+            ColValue::Float(_) => text.parse().ok().map(ColValue::Float),
+            ColValue::Double(_) => text.parse().ok().map(ColValue::Double),
+            ColValue::Decimal(v) => Some(ColValue::Decimal(Self::align_fraction(&text, v))),
+            ColValue::Time(v) => Some(ColValue::Time(Self::align_temporal(&text, v))),
+            ColValue::DateTime(v) => Some(ColValue::DateTime(Self::align_temporal(&text, v))),
+            ColValue::Timestamp(v) => Some(ColValue::Timestamp(Self::align_temporal(&text, v))),
+            ColValue::Date(_) => Some(ColValue::Date(text.clone())),
+            // ClickhouseSinker writes non-utf8 strings as hex
+            ColValue::RawString(_) => Some(ColValue::RawString(
+                Self::decode_hex(&text)
+                    .filter(|bytes| String::from_utf8(bytes.clone()).is_err())
+                    .unwrap_or_else(|| text.clone().into_bytes()),
+            )),
+            ColValue::Blob(_) => Some(ColValue::Blob(
+                Self::decode_hex(&text).unwrap_or_else(|| text.clone().into_bytes()),
+            )),
+            ColValue::Set2(_) => Some(ColValue::Set2(text.clone())),
+            ColValue::Enum2(_) => Some(ColValue::Enum2(text.clone())),
+            ColValue::Json(_) => Some(ColValue::Json(text.clone().into_bytes())),
+            ColValue::Json2(_) => Some(ColValue::Json2(text.clone())),
+            ColValue::Json3(_) => serde_json::from_str(&text).ok().map(ColValue::Json3),
+            _ => None,
+        };
+        col_value.unwrap_or(ColValue::String(text))
+    }
+
+    fn align_temporal(value: &str, src: &str) -> String {
+        // ClickhouseSinker trims the utc offset of pg values
+        match src.strip_suffix("+00") {
+            Some(src) => format!("{}+00", Self::align_fraction(value, src)),
+            None => Self::align_fraction(value, src),
+        }
+    }
+
+    /// Pads or trims the fractional part of value to the digits of src, non-zero digits
+    /// are never trimmed so that real differences are kept.
+    fn align_fraction(value: &str, src: &str) -> String {
+        let scale = src.split_once('.').map_or(0, |(_, frac)| frac.len());
+        let (int_part, frac) = value.split_once('.').unwrap_or((value, ""));
+        if !frac.bytes().all(|b| b.is_ascii_digit()) || frac.bytes().skip(scale).any(|b| b != b'0')
+        {
+            return value.to_string();
+        }
+
+        let frac = format!("{:0<scale$}", &frac[..frac.len().min(scale)]);
+        if frac.is_empty() {
+            int_part.to_string()
+        } else {
+            format!("{}.{}", int_part, frac)
+        }
+    }
+
+    fn key_values(row: &RowData) -> anyhow::Result<&HashMap<String, ColValue>> {
+        match row.row_type {
+            RowType::Delete => row.require_before(),
+            _ => row.require_after(),
+        }
+    }
+
+    fn src_match_key(values: &HashMap<String, ColValue>, id_cols: &[String]) -> String {
+        let texts = id_cols
+            .iter()
+            .map(|col| Self::sink_text(values.get(col)).map(|text| Self::canonical_text(&text)))
+            .collect::<Vec<_>>();
+        format!("{:?}", texts)
+    }
+
+    fn dst_match_key(row: &Map<String, JsonValue>, id_cols: &[String]) -> String {
+        let texts = id_cols
+            .iter()
+            .map(|col| Self::json_text(row.get(col)).map(|text| Self::canonical_text(&text)))
+            .collect::<Vec<_>>();
+        format!("{:?}", texts)
+    }
+
+    // drops trailing fractional zeros so that 1.50 / 1.5 or 10:00:00.000 / 10:00:00 match
+    fn canonical_text(text: &str) -> String {
+        let text = text.strip_suffix("+00").unwrap_or(text);
+        match text.rsplit_once('.') {
+            Some((int_part, frac)) if frac.bytes().all(|b| b.is_ascii_digit()) => {
+                let frac = frac.trim_end_matches('0');
+                if frac.is_empty() {
+                    int_part.to_string()
+                } else {
+                    format!("{}.{}", int_part, frac)
+                }
+            }
+            _ => text.to_string(),
+        }
+    }
+
+    /// Text of a source value as written by ClickhouseSinker.
+    fn sink_text(value: Option<&ColValue>) -> Option<String> {
+        match value? {
+            ColValue::RawString(v) => {
+                let (text, is_hex) = SqlUtil::binary_to_str(v);
+                Some(if is_hex { format!("0x{}", text) } else { text })
+            }
+            ColValue::Blob(v) => Some(format!("0x{}", hex::encode(v))),
+            ColValue::Time(v) | ColValue::Timestamp(v) => {
+                Some(v.trim_end_matches("+00").to_string())
+            }
+            value => value.to_option_string(),
+        }
+    }
+
+    fn json_text(value: Option<&JsonValue>) -> Option<String> {
+        match value? {
+            JsonValue::Null => None,
+            JsonValue::String(v) => Some(v.clone()),
+            v => Some(v.to_string()),
+        }
+    }
+
+    fn decode_hex(text: &str) -> Option<Vec<u8>> {
+        text.strip_prefix("0x").and_then(|v| hex::decode(v).ok())
+    }
+
+    fn quote(value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_align_fraction() {
+        assert_eq!(ClickhouseChecker::align_fraction("1.5", "2.50"), "1.50");
+        assert_eq!(ClickhouseChecker::align_fraction("1.500", "2.5"), "1.5");
+        assert_eq!(ClickhouseChecker::align_fraction("1.501", "2.5"), "1.501");
+        assert_eq!(ClickhouseChecker::align_fraction("3.000", "2"), "3");
+        assert_eq!(
+            ClickhouseChecker::align_temporal("2024-01-01 10:00:00", "2024-01-01 09:00:00.120+00"),
+            "2024-01-01 10:00:00.000+00"
+        );
+        assert_eq!(
+            ClickhouseChecker::align_temporal("2024-01-01 10:00:00.123000", "2024-01-01 10:00:00"),
+            "2024-01-01 10:00:00.123000"
+        );
+    }
+
+    #[test]
+    fn test_to_col_value() {
+        let src = ColValue::RawString(vec![0xff, 0x01]);
+        assert_eq!(
+            ClickhouseChecker::to_col_value(&JsonValue::String("0xff01".into()), Some(&src)),
+            src
+        );
+        // utf8 strings are written as they are even if they look like hex
+        let src = ColValue::RawString(b"0x41".to_vec());
+        assert_eq!(
+            ClickhouseChecker::to_col_value(&JsonValue::String("0x41".into()), Some(&src)),
+            src
+        );
+        assert_eq!(
+            ClickhouseChecker::to_col_value(
+                &JsonValue::String("18446744073709551615".into()),
+                Some(&ColValue::UnsignedLongLong(1))
+            ),
+            ColValue::UnsignedLongLong(u64::MAX)
+        );
+        assert_eq!(
+            ClickhouseChecker::to_col_value(&JsonValue::Null, Some(&ColValue::Long(1))),
+            ColValue::None
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context};
+use dt_common::error::Error;
+
+// Layout of a compressed check log:
+//   header:  HEADER_MAGIC
+//   chunks:  [payload_len u32][raw_len u32][line_count u32][crc32(raw) u32][zstd payload]
+//   index:   [offset u64][line_count u32] per chunk
+//   trailer: [chunk_count u32][crc32(index) u32][FOOTER_MAGIC]
+// All integers are little endian. Every chunk is an independent zstd frame, so a reader
+// can seek to any chunk through the index and verify it without touching the others.
+pub const COMPRESSED_LOG_EXT: &str = "zst";
+pub const DEFAULT_CHUNK_LINES: u32 = 4096;
+
+const HEADER_MAGIC: &[u8; 8] = b"APEDTCL\x01";
+const FOOTER_MAGIC: &[u8; 8] = b"APEDTIX\x01";
+const CHUNK_HEADER_LEN: u64 = 16;
+const INDEX_ENTRY_LEN: u64 = 12;
+const TRAILER_LEN: u64 = 16;
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkIndex {
+    pub offset: u64,
+    pub line_count: u32,
+}
+
+pub struct CompressedLogWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    chunk_lines: u32,
+    buf: Vec<u8>,
+    buf_lines: u32,
+    index: Vec<ChunkIndex>,
+}
+
+impl<W: Write> CompressedLogWriter<W> {
+    pub fn new(mut writer: W, chunk_lines: u32) -> anyhow::Result<Self> {
+        writer.write_all(HEADER_MAGIC)?;
+        Ok(Self {
+            writer,
+            offset: HEADER_MAGIC.len() as u64,
+            chunk_lines: chunk_lines.max(1),
+            buf: Vec::new(),
+            buf_lines: 0,
+            index: Vec::new(),
+        })
+    }
+
+    pub fn write_line(&mut self, line: &str) -> anyhow::Result<()> {
+        self.buf.extend_from_slice(line.as_bytes());
+        self.buf.push(b'\n');
+        self.buf_lines += 1;
+        if self.buf_lines >= self.chunk_lines {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.flush_chunk()?;
+
+        let mut index_buf = Vec::with_capacity(self.index.len() * INDEX_ENTRY_LEN as usize);
+        for chunk in self.index.iter() {
+            index_buf.extend_from_slice(&chunk.offset.to_le_bytes());
+            index_buf.extend_from_slice(&chunk.line_count.to_le_bytes());
+        }
+        self.writer.write_all(&index_buf)?;
+        self.writer
+            .write_all(&(self.index.len() as u32).to_le_bytes())?;
+        self.writer
+            .write_all(&crc32fast::hash(&index_buf).to_le_bytes())?;
+        self.writer.write_all(FOOTER_MAGIC)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn flush_chunk(&mut self) -> anyhow::Result<()> {
+        if self.buf_lines == 0 {
+            return Ok(());
+        }
+
+        let payload = zstd::bulk::compress(&self.buf, ZSTD_LEVEL)?;
+        self.writer
+            .write_all(&(payload.len() as u32).to_le_bytes())?;
+        self.writer
+            .write_all(&(self.buf.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&self.buf_lines.to_le_bytes())?;
+        self.writer
+            .write_all(&crc32fast::hash(&self.buf).to_le_bytes())?;
+        self.writer.write_all(&payload)?;
+
+        self.index.push(ChunkIndex {
+            offset: self.offset,
+            line_count: self.buf_lines,
+        });
+        self.offset += CHUNK_HEADER_LEN + payload.len() as u64;
+        self.buf.clear();
+        self.buf_lines = 0;
+        Ok(())
+    }
+}
+
+pub struct CompressedLogReader<R: Read + Seek> {
+    reader: R,
+    name: String,
+    index: Vec<ChunkIndex>,
+}
+
+impl CompressedLogReader<BufReader<File>> {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open compressed check log: [{:?}]", path))?;
+        Self::new(BufReader::new(file), &path.to_string_lossy())
+    }
+}
+
+impl<R: Read + Seek> CompressedLogReader<R> {
+    pub fn new(mut reader: R, name: &str) -> anyhow::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.seek(SeekFrom::Start(0))?;
+        reader
+            .read_exact(&mut magic)
+            .with_context(|| format!("compressed check log too short: [{}]", name))?;
+        if &magic != HEADER_MAGIC {
+            bail! {Error::Unexpected(format!(
+                "not a compressed check log: [{}]",
+                name
+            ))}
+        }
+
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        if file_len < HEADER_MAGIC.len() as u64 + TRAILER_LEN {
+            bail! {Error::Unexpected(format!(
+                "compressed check log is truncated: [{}]",
+                name
+            ))}
+        }
+        reader.seek(SeekFrom::Start(file_len - TRAILER_LEN))?;
+        let mut trailer = [0u8; TRAILER_LEN as usize];
+        reader.read_exact(&mut trailer)?;
+        if &trailer[8..] != FOOTER_MAGIC {
+            bail! {Error::Unexpected(format!(
+                "compressed check log is truncated, footer missing: [{}]",
+                name
+            ))}
+        }
+        let chunk_count = u32::from_le_bytes(trailer[0..4].try_into()?) as u64;
+        let index_crc = u32::from_le_bytes(trailer[4..8].try_into()?);
+
+        let index_len = chunk_count * INDEX_ENTRY_LEN;
+        if file_len < HEADER_MAGIC.len() as u64 + TRAILER_LEN + index_len {
+            bail! {Error::Unexpected(format!(
+                "compressed check log has an invalid chunk index: [{}]",
+                name
+            ))}
+        }
+        reader.seek(SeekFrom::Start(file_len - TRAILER_LEN - index_len))?;
+        let mut index_buf = vec![0u8; index_len as usize];
+        reader.read_exact(&mut index_buf)?;
+        if crc32fast::hash(&index_buf) != index_crc {
+            bail! {Error::Unexpected(format!(
+                "compressed check log chunk index checksum mismatch: [{}]",
+                name
+            ))}
+        }
+
+        let index = index_buf
+            .chunks_exact(INDEX_ENTRY_LEN as usize)
+            .map(|entry| ChunkIndex {
+                offset: u64::from_le_bytes(entry[0..8].try_into().unwrap()),
+                line_count: u32::from_le_bytes(entry[8..12].try_into().unwrap()),
+            })
+            .collect();
+
+        Ok(Self {
+            reader,
+            name: name.to_string(),
+            index,
+        })
+    }
+
+    pub fn chunks(&self) -> &[ChunkIndex] {
+        &self.index
+    }
+
+    pub fn line_count(&self) -> u64 {
+        self.index.iter().map(|i| i.line_count as u64).sum()
+    }
+
+    pub fn read_chunk(&mut self, chunk_id: usize) -> anyhow::Result<Vec<String>> {
+        let raw = self.read_raw_chunk(chunk_id)?;
+        let text = String::from_utf8(raw).with_context(|| {
+            format!(
+                "compressed check log chunk [{}] is not valid utf-8: [{}]",
+                chunk_id, self.name
+            )
+        })?;
+        let mut lines: Vec<String> = text.split('\n').map(|s| s.to_string()).collect();
+        // every line is written with a trailing '\n'
+        lines.pop();
+        Ok(lines)
+    }
+
+    /// Decompress every chunk and validate its checksum and line count, so corruption
+    /// is reported before any of the content is consumed.
+    pub fn verify(&mut self) -> anyhow::Result<()> {
+        for chunk_id in 0..self.index.len() {
+            self.read_raw_chunk(chunk_id)?;
+        }
+        Ok(())
+    }
+
+    fn read_raw_chunk(&mut self, chunk_id: usize) -> anyhow::Result<Vec<u8>> {
+        let Some(chunk) = self.index.get(chunk_id).cloned() else {
+            bail! {Error::Unexpected(format!(
+                "chunk [{}] out of range in compressed check log: [{}]",
+                chunk_id, self.name
+            ))}
+        };
+
+        self.reader.seek(SeekFrom::Start(chunk.offset))?;
+        let mut header = [0u8; CHUNK_HEADER_LEN as usize];
+        self.reader.read_exact(&mut header)?;
+        let payload_len = u32::from_le_bytes(header[0..4].try_into()?) as usize;
+        let raw_len = u32::from_le_bytes(header[4..8].try_into()?) as usize;
+        let line_count = u32::from_le_bytes(header[8..12].try_into()?);
+        let crc = u32::from_le_bytes(header[12..16].try_into()?);
+
+        let mut payload = vec![0u8; payload_len];
+        self.reader.read_exact(&mut payload).with_context(|| {
+            format!(
+                "compressed check log chunk [{}] is truncated: [{}]",
+                chunk_id, self.name
+            )
+        })?;
+        let raw = zstd::bulk::decompress(&payload, raw_len).with_context(|| {
+            format!(
+                "failed to decompress chunk [{}] in compressed check log: [{}]",
+                chunk_id, self.name
+            )
+        })?;
+
+        if raw.len() != raw_len
+            || crc32fast::hash(&raw) != crc
+            || line_count != chunk.line_count
+            || raw.iter().filter(|b| **b == b'\n').count() != line_count as usize
+        {
+            bail! {Error::Unexpected(format!(
+                "checksum mismatch in chunk [{}] of compressed check log: [{}]",
+                chunk_id, self.name
+            ))}
+        }
+        Ok(raw)
+    }
+}
+
+pub fn is_compressed_log(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(COMPRESSED_LOG_EXT))
+}
+
+pub fn compressed_log_name(file_name: &str) -> String {
+    format!("{}.{}", file_name, COMPRESSED_LOG_EXT)
+}
+
+/// Encode newline separated log content, as produced by the checkers, into the compressed format.
+pub fn encode(buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = CompressedLogWriter::new(Vec::new(), DEFAULT_CHUNK_LINES)?;
+    for line in buf.lines() {
+        writer.write_line(&line?)?;
+    }
+    writer.finish()
+}
+
+/// Compress a plain check log into [dst] and remove [src] once the result is fully written.
+pub fn compress_file(src: &Path, dst: &Path) -> anyhow::Result<()> {
+    let src_file =
+        File::open(src).with_context(|| format!("failed to open check log: [{:?}]", src))?;
+    let dst_file = File::create(dst)
+        .with_context(|| format!("failed to create compressed check log: [{:?}]", dst))?;
+
+    let mut writer = CompressedLogWriter::new(BufWriter::new(dst_file), DEFAULT_CHUNK_LINES)?;
+    for line in BufReader::new(src_file).lines() {
+        writer.write_line(&line?)?;
+    }
+    let dst_file = writer.finish()?.into_inner()?;
+    dst_file.sync_all()?;
+
+    std::fs::remove_file(src)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn build(lines: &[&str], chunk_lines: u32) -> Vec<u8> {
+        let mut writer = CompressedLogWriter::new(Vec::new(), chunk_lines).unwrap();
+        for line in lines {
+            writer.write_line(line).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn compressed_log_round_trip_by_chunk() {
+        let lines = ["l1", "", "l3", "l4", "l5"];
+        let buf = build(&lines, 2);
+
+        let mut reader = CompressedLogReader::new(Cursor::new(buf), "test").unwrap();
+        assert_eq!(reader.chunks().len(), 3);
+        assert_eq!(reader.line_count(), 5);
+        reader.verify().unwrap();
+
+        assert_eq!(reader.read_chunk(2).unwrap(), vec!["l5"]);
+        assert_eq!(reader.read_chunk(0).unwrap(), vec!["l1", ""]);
+        assert_eq!(reader.read_chunk(1).unwrap(), vec!["l3", "l4"]);
+        assert!(reader.read_chunk(3).is_err());
+    }
+
+    #[test]
+    fn compressed_log_empty() {
+        let mut reader =
+            CompressedLogReader::new(Cursor::new(encode(b"").unwrap()), "test").unwrap();
+        assert!(reader.chunks().is_empty());
+        reader.verify().unwrap();
+    }
+
+    #[test]
+    fn compressed_log_detects_corruption() {
+        let buf = build(&["l1", "l2", "l3"], 2);
+
+        // flip a byte inside the first chunk payload
+        let mut corrupted = buf.clone();
+        corrupted[HEADER_MAGIC.len() + CHUNK_HEADER_LEN as usize + 2] ^= 0xff;
+        let mut reader = CompressedLogReader::new(Cursor::new(corrupted), "test").unwrap();
+        assert!(reader.verify().is_err());
+
+        // truncated file loses its footer
+        let truncated = buf[..buf.len() - 4].to_vec();
+        assert!(CompressedLogReader::new(Cursor::new(truncated), "test").is_err());
+
+        // damaged index
+        let mut corrupted = buf.clone();
+        let index_pos = buf.len() - TRAILER_LEN as usize - 1;
+        corrupted[index_pos] ^= 0xff;
+        assert!(CompressedLogReader::new(Cursor::new(corrupted), "test").is_err());
+    }
+}
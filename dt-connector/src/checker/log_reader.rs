@@ -2,14 +2,51 @@ use std::{
     fs::{self, File},
     io::{BufRead, BufReader, Lines},
     path::PathBuf,
+    vec::IntoIter,
 };
 
 use anyhow::Context;
 
+use super::compressed_log::{self, CompressedLogReader};
+
+// other outputs of a check, which may sit in the same dir as miss / diff logs
+const NON_ROW_LOGS: [&str; 4] = ["summary.log", "sql.log", "report.log", "convergence.log"];
+
 pub struct LogReader {
     files: Vec<PathBuf>,
     file_index: usize,
-    lines: Option<Lines<BufReader<File>>>,
+    lines: Option<LogLines>,
+}
+
+enum LogLines {
+    Plain(Lines<BufReader<File>>),
+    Compressed {
+        reader: CompressedLogReader<BufReader<File>>,
+        next_chunk: usize,
+        lines: IntoIter<String>,
+    },
+}
+
+impl LogLines {
+    fn next(&mut self) -> anyhow::Result<Option<String>> {
+        match self {
+            Self::Plain(lines) => Ok(lines.next().transpose()?),
+            Self::Compressed {
+                reader,
+                next_chunk,
+                lines,
+            } => loop {
+                if let Some(line) = lines.next() {
+                    return Ok(Some(line));
+                }
+                if *next_chunk >= reader.chunks().len() {
+                    return Ok(None);
+                }
+                *lines = reader.read_chunk(*next_chunk)?.into_iter();
+                *next_chunk += 1;
+            },
+        }
+    }
 }
 
 impl LogReader {
@@ -24,6 +61,16 @@ impl LogReader {
         }
     }
 
+    /// Validate the checksums of all compressed logs in the dir, plain logs are skipped.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        for path in self.files.iter() {
+            if compressed_log::is_compressed_log(path) {
+                CompressedLogReader::open(path)?.verify()?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn nextval(&mut self) -> anyhow::Result<Option<String>> {
         if self.file_index >= self.files.len() {
             return Ok(None);
@@ -31,16 +78,22 @@ impl LogReader {
 
         if self.lines.is_none() {
             let path = &self.files[self.file_index];
-            if let Some(file_path) = path.to_str() {
+            if compressed_log::is_compressed_log(path) {
+                self.lines = Some(LogLines::Compressed {
+                    reader: CompressedLogReader::open(path)?,
+                    next_chunk: 0,
+                    lines: Vec::new().into_iter(),
+                });
+            } else if let Some(file_path) = path.to_str() {
                 let file = File::open(file_path)
                     .with_context(|| format!("failed to open file: [{}]", file_path))?;
-                self.lines = Some(BufReader::new(file).lines());
+                self.lines = Some(LogLines::Plain(BufReader::new(file).lines()));
             }
         }
 
         if let Some(lines) = self.lines.as_mut() {
-            if let Some(result) = lines.next() {
-                return Ok(Some(result?));
+            if let Some(result) = lines.next()? {
+                return Ok(Some(result));
             } else {
                 self.lines = None;
                 self.file_index += 1;
@@ -54,7 +107,11 @@ impl LogReader {
         let mut files = Vec::new();
         for entry in fs::read_dir(dir_path)? {
             let path = entry?.path();
-            if path.is_file() {
+            let is_row_log = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| !NON_ROW_LOGS.contains(&name));
+            if path.is_file() && is_row_log {
                 files.push(path);
             }
         }
@@ -1,13 +1,17 @@
 pub mod base_checker;
 pub mod check_log;
+pub mod clickhouse_checker;
+pub mod compressed_log;
 pub mod log_reader;
 pub mod mongo_checker;
 pub mod mysql_checker;
 pub mod pg_checker;
 pub mod state_store;
 pub mod struct_checker;
+pub mod value_normalizer;
 
 pub use base_checker::{CheckContext, Checker, CheckerHandle, CheckerTbMeta, DataCheckerHandle};
+pub use clickhouse_checker::ClickhouseChecker;
 pub use mongo_checker::MongoChecker;
 pub use mysql_checker::MysqlChecker;
 pub use pg_checker::PgChecker;
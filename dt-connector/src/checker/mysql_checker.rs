@@ -11,9 +11,12 @@ use dt_common::meta::{
 use crate::checker::base_checker::{Checker, CheckerTbMeta, CHECKER_MAX_QUERY_BATCH};
 use crate::rdb_query_builder::RdbQueryBuilder;
 
+const SNAPSHOT_TX_SQL: &str = "START TRANSACTION WITH CONSISTENT SNAPSHOT, READ ONLY";
+
 pub struct MysqlChecker {
     conn_pool: Pool<MySql>,
     meta_manager: MysqlMetaManager,
+    snapshot_isolation: bool,
 }
 
 #[async_trait]
@@ -40,16 +43,28 @@ impl Checker for MysqlChecker {
         };
         let qb = RdbQueryBuilder::new_for_mysql(mysql_meta, None);
 
+        // all chunks of one lookup read the same snapshot
+        let mut tx = if self.snapshot_isolation {
+            Some(self.conn_pool.begin_with(SNAPSHOT_TX_SQL).await?)
+        } else {
+            None
+        };
         let mut res = Vec::with_capacity(lookup_rows.len());
         for chunk in lookup_rows.chunks(CHECKER_MAX_QUERY_BATCH) {
             let query_info = qb.get_batch_select_query(chunk, 0, chunk.len())?;
             let query = qb.create_mysql_query(&query_info)?;
-            let mut rows = query.fetch(&self.conn_pool);
+            let mut rows = match tx.as_mut() {
+                Some(tx) => query.fetch(&mut **tx),
+                None => query.fetch(&self.conn_pool),
+            };
             while let Some(row) = rows.try_next().await? {
                 res.push(RowData::from_mysql_row(&row, mysql_meta, &None, None));
             }
         }
 
+        if let Some(tx) = tx {
+            tx.commit().await?;
+        }
         Ok(res)
     }
 
@@ -67,10 +82,15 @@ impl Checker for MysqlChecker {
 }
 
 impl MysqlChecker {
-    pub fn new(conn_pool: Pool<MySql>, meta_manager: MysqlMetaManager) -> Self {
+    pub fn new(
+        conn_pool: Pool<MySql>,
+        meta_manager: MysqlMetaManager,
+        snapshot_isolation: bool,
+    ) -> Self {
         Self {
             conn_pool,
             meta_manager,
+            snapshot_isolation,
         }
     }
 }
@@ -11,9 +11,12 @@ use dt_common::meta::{
 use crate::checker::base_checker::{Checker, CheckerTbMeta, CHECKER_MAX_QUERY_BATCH};
 use crate::rdb_query_builder::RdbQueryBuilder;
 
+const SNAPSHOT_TX_SQL: &str = "BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY";
+
 pub struct PgChecker {
     conn_pool: Pool<Postgres>,
     meta_manager: PgMetaManager,
+    snapshot_isolation: bool,
 }
 
 #[async_trait]
@@ -40,16 +43,28 @@ impl Checker for PgChecker {
         };
         let qb = RdbQueryBuilder::new_for_pg(pg_meta, None);
 
+        // all chunks of one lookup read the same snapshot
+        let mut tx = if self.snapshot_isolation {
+            Some(self.conn_pool.begin_with(SNAPSHOT_TX_SQL).await?)
+        } else {
+            None
+        };
         let mut res = Vec::with_capacity(lookup_rows.len());
         for chunk in lookup_rows.chunks(CHECKER_MAX_QUERY_BATCH) {
             let query_info = qb.get_batch_select_query(chunk, 0, chunk.len())?;
             let query = qb.create_pg_query(&query_info)?;
-            let mut rows = query.fetch(&self.conn_pool);
+            let mut rows = match tx.as_mut() {
+                Some(tx) => query.fetch(&mut **tx),
+                None => query.fetch(&self.conn_pool),
+            };
             while let Some(row) = rows.try_next().await? {
                 res.push(RowData::from_pg_row(&row, pg_meta, &None, None));
             }
         }
 
+        if let Some(tx) = tx {
+            tx.commit().await?;
+        }
         Ok(res)
     }
 
@@ -67,10 +82,15 @@ impl Checker for PgChecker {
 }
 
 impl PgChecker {
-    pub fn new(conn_pool: Pool<Postgres>, meta_manager: PgMetaManager) -> Self {
+    pub fn new(
+        conn_pool: Pool<Postgres>,
+        meta_manager: PgMetaManager,
+        snapshot_isolation: bool,
+    ) -> Self {
         Self {
             conn_pool,
             meta_manager,
+            snapshot_isolation,
         }
     }
 }
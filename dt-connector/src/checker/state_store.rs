@@ -75,7 +75,7 @@ impl CheckerStateStore {
             ResumerDbPool::Mongo(_) => {
                 anyhow::bail!("checker state store does not support MongoDB resumer backend")
             }
-            ResumerDbPool::Redis(_) => {
+            ResumerDbPool::Redis(_) | ResumerDbPool::Etcd(_) => {
                 anyhow::bail!("checker state store only supports MySQL/Postgres")
             }
         };
@@ -10,7 +10,7 @@ use tokio::time::sleep;
 
 use dt_common::{
     config::config_enums::DbType,
-    log_diff, log_info, log_miss, log_sql, log_summary,
+    log_diff, log_info, log_miss, log_report, log_sql, log_summary,
     meta::struct_meta::{struct_data::StructData, structure::structure_type::StructureType},
     monitor::{
         counter_type::CounterType, task_metrics::TaskMetricsType,
@@ -262,7 +262,11 @@ impl StructCheckerHandle {
             }
 
             if log_enabled {
-                let log = StructCheckLog::new(key, Some(src_sql.clone()), dst_sql);
+                let mut log = StructCheckLog::new(key, Some(src_sql.clone()), dst_sql);
+                if let Some(dst_sql) = log.dst_sql.as_ref().filter(|_| key.starts_with("table.")) {
+                    log.details = describe_table_diff(src_sql, dst_sql);
+                }
+                Self::report(&log, if is_miss { "MISSING" } else { "DIFF" });
                 if let Some(log) = to_json_line(&log) {
                     if is_miss {
                         log_miss!("{}", log);
@@ -284,6 +288,7 @@ impl StructCheckerHandle {
             }
             if log_enabled {
                 let log = StructCheckLog::new(&key, None, Some(dst_sql));
+                Self::report(&log, "EXTRA");
                 if let Some(log) = to_json_line(&log) {
                     log_diff!("{}", log);
                 }
@@ -291,6 +296,14 @@ impl StructCheckerHandle {
         }
 
         summary.is_consistent = summary.miss_count == 0 && summary.diff_count == 0;
+        if log_enabled {
+            log_report!(
+                "checked: {}, missing in target: {}, different or extra in target: {}",
+                summary.checked_count,
+                summary.miss_count,
+                summary.diff_count
+            );
+        }
         if output_revise_sql && sql_count > 0 {
             summary.sql_count = Some(sql_count);
         }
@@ -299,6 +312,23 @@ impl StructCheckerHandle {
         summary
     }
 
+    // human readable version of miss.log / diff.log
+    fn report(log: &StructCheckLog, kind: &str) {
+        log_report!("[{}] {}", kind, log.key);
+        if !log.details.is_empty() {
+            for detail in log.details.iter() {
+                log_report!("    {}", detail);
+            }
+            return;
+        }
+        if let Some(src_sql) = &log.src_sql {
+            log_report!("    source: {}", src_sql);
+        }
+        if let Some(dst_sql) = &log.dst_sql {
+            log_report!("    target: {}", dst_sql);
+        }
+    }
+
     pub async fn check_struct(
         &mut self,
         data: Vec<dt_common::meta::struct_meta::struct_data::StructData>,
@@ -364,3 +394,132 @@ impl StructCheckerHandle {
         Ok(())
     }
 }
+
+/// Compares the column and key definitions of two CREATE TABLE statements generated by the
+/// struct fetchers, returns nothing if they can not be parsed.
+fn describe_table_diff(src_sql: &str, dst_sql: &str) -> Vec<String> {
+    let (Some((src_cols, src_others, src_options)), Some((dst_cols, dst_others, dst_options))) =
+        (parse_table_defs(src_sql), parse_table_defs(dst_sql))
+    else {
+        return Vec::new();
+    };
+
+    let mut details = Vec::new();
+    for (name, src_def) in src_cols.iter() {
+        match dst_cols.iter().find(|(dst_name, _)| dst_name == name) {
+            None => details.push(format!("column {} is missing in target", name)),
+            Some((_, dst_def)) if dst_def != src_def => details.push(format!(
+                "column {} differs, source: {}, target: {}",
+                name, src_def, dst_def
+            )),
+            _ => {}
+        }
+    }
+    for (name, _) in dst_cols.iter() {
+        if !src_cols.iter().any(|(src_name, _)| src_name == name) {
+            details.push(format!("column {} only exists in target", name));
+        }
+    }
+    for def in src_others.iter().filter(|def| !dst_others.contains(def)) {
+        details.push(format!("{} is missing in target", def));
+    }
+    for def in dst_others.iter().filter(|def| !src_others.contains(def)) {
+        details.push(format!("{} only exists in target", def));
+    }
+    if src_options != dst_options {
+        details.push(format!(
+            "table options differ, source: {}, target: {}",
+            src_options, dst_options
+        ));
+    }
+    details
+}
+
+type TableDefs = (Vec<(String, String)>, Vec<String>, String);
+
+/// Splits `CREATE TABLE t (c1 ..., c2 ..., PRIMARY KEY (...)) options` into columns as
+/// (escaped name, definition), other definitions such as keys, and the table options.
+fn parse_table_defs(sql: &str) -> Option<TableDefs> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut body_start = None;
+    let mut part_start = 0;
+    let mut body_end = None;
+    for (i, c) in sql.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '`' | '"' | '\'' => quote = Some(c),
+            '(' => {
+                depth += 1;
+                if depth == 1 && body_start.is_none() {
+                    body_start = Some(i);
+                    part_start = i + 1;
+                }
+            }
+            ')' => {
+                depth -= 1;
+                if depth == 0 && body_end.is_none() {
+                    parts.push(sql[part_start..i].trim().to_string());
+                    body_end = Some(i);
+                }
+            }
+            ',' if depth == 1 && body_end.is_none() => {
+                parts.push(sql[part_start..i].trim().to_string());
+                part_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let body_end = body_end?;
+    let mut cols = Vec::new();
+    let mut others = Vec::new();
+    for part in parts {
+        let Some(q) = part.chars().next().filter(|c| *c == '`' || *c == '"') else {
+            others.push(part);
+            continue;
+        };
+        let name_end = part[1..].find(q)? + 2;
+        cols.push((
+            part[..name_end].to_string(),
+            part[name_end..].trim().to_string(),
+        ));
+    }
+    Some((cols, others, sql[body_end + 1..].trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_table_diff() {
+        let src = "CREATE TABLE `tb` (`id` int NOT NULL, `c1` varchar(10) DEFAULT 'a,b', `c2` int, PRIMARY KEY (`id`)) ENGINE=InnoDB";
+        let dst = "CREATE TABLE `tb` (`id` bigint NOT NULL, `c1` varchar(10) DEFAULT 'a,b', `c3` int) ENGINE=MyISAM";
+        assert_eq!(
+            describe_table_diff(src, dst),
+            vec![
+                "column `id` differs, source: int NOT NULL, target: bigint NOT NULL",
+                "column `c2` is missing in target",
+                "column `c3` only exists in target",
+                "PRIMARY KEY (`id`) is missing in target",
+                "table options differ, source: ENGINE=InnoDB, target: ENGINE=MyISAM",
+            ]
+        );
+
+        let src =
+            r#"CREATE TABLE IF NOT EXISTS "s"."t" ("id" integer NOT NULL, "v" numeric(10,2))"#;
+        let dst =
+            r#"CREATE TABLE IF NOT EXISTS "s"."t" ("id" integer NOT NULL, "v" numeric(12,2))"#;
+        assert_eq!(
+            describe_table_diff(src, dst),
+            vec![r#"column "v" differs, source: numeric(10,2), target: numeric(12,2)"#]
+        );
+    }
+}
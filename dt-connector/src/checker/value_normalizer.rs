@@ -0,0 +1,216 @@
+use chrono::{DateTime, NaiveDateTime};
+use dt_common::meta::col_value::ColValue;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+const DATETIME_TZ_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%#z";
+
+/// Canonical form of a column value, values read from different dbs compare equal when they
+/// only differ in representation, e.g. `1.50` and `1.5`, `2024-01-01T10:00:00+08:00` and
+/// `2024-01-01 02:00:00`, tinyint 1 and boolean true.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NormalizedValue {
+    Null,
+    // integers, decimals and booleans as canonical decimal strings
+    Number(String),
+    // bits of the f64, 0.0 for -0.0, all NaN are the same
+    Float(u64),
+    // date / time / datetime / timestamp, in utc if the value has an offset
+    Temporal(String),
+    Text(String),
+    Bytes(Vec<u8>),
+    Json(serde_json::Value),
+    Other(String),
+}
+
+pub struct ValueNormalizer {}
+
+impl ValueNormalizer {
+    pub fn is_same(src: &ColValue, dst: &ColValue) -> bool {
+        if src.is_same_value(dst) {
+            return true;
+        }
+        match (Self::normalize(src), Self::normalize(dst)) {
+            // pg bytea may be read as text in hex format
+            (NormalizedValue::Text(text), NormalizedValue::Bytes(bytes))
+            | (NormalizedValue::Bytes(bytes), NormalizedValue::Text(text)) => {
+                Self::decode_hex_text(&text).is_some_and(|decoded| decoded == bytes)
+            }
+            (v1, v2) => v1 == v2,
+        }
+    }
+
+    pub fn normalize(value: &ColValue) -> NormalizedValue {
+        match value {
+            ColValue::None | ColValue::UnchangedToast => NormalizedValue::Null,
+            ColValue::Bool(v) => NormalizedValue::Number((*v as u8).to_string()),
+            ColValue::Tiny(_)
+            | ColValue::UnsignedTiny(_)
+            | ColValue::Short(_)
+            | ColValue::UnsignedShort(_)
+            | ColValue::Long(_)
+            | ColValue::UnsignedLong(_)
+            | ColValue::LongLong(_)
+            | ColValue::UnsignedLongLong(_)
+            | ColValue::Year(_) => {
+                NormalizedValue::Number(value.to_option_string().unwrap_or_default())
+            }
+            // f32 is widened by its shortest text, so 0.1f32 equals 0.1f64
+            ColValue::Float(v) => Self::normalize_float(v.to_string().parse().unwrap_or(f64::NAN)),
+            ColValue::Double(v) => Self::normalize_float(*v),
+            ColValue::Decimal(v) => NormalizedValue::Number(Self::normalize_decimal(v)),
+            ColValue::Time(v)
+            | ColValue::Date(v)
+            | ColValue::DateTime(v)
+            | ColValue::Timestamp(v) => NormalizedValue::Temporal(Self::normalize_temporal(v)),
+            ColValue::String(v) | ColValue::Set2(v) | ColValue::Enum2(v) => {
+                NormalizedValue::Text(v.clone())
+            }
+            ColValue::RawString(v) => match String::from_utf8(v.clone()) {
+                Ok(v) => NormalizedValue::Text(v),
+                Err(e) => NormalizedValue::Bytes(e.into_bytes()),
+            },
+            ColValue::Blob(v) => NormalizedValue::Bytes(v.clone()),
+            ColValue::Json(v) => Self::normalize_json(&String::from_utf8_lossy(v)),
+            ColValue::Json2(v) => Self::normalize_json(v),
+            ColValue::Json3(v) => NormalizedValue::Json(v.clone()),
+            ColValue::Bit(_) | ColValue::Set(_) | ColValue::Enum(_) | ColValue::MongoDoc(_) => {
+                NormalizedValue::Other(value.to_option_string().unwrap_or_default())
+            }
+        }
+    }
+
+    fn normalize_float(v: f64) -> NormalizedValue {
+        let v = if v.is_nan() {
+            f64::NAN
+        } else if v == 0.0 {
+            0.0
+        } else {
+            v
+        };
+        NormalizedValue::Float(v.to_bits())
+    }
+
+    /// +001.500 -> 1.5, -0.00 -> 0, values with exponents are kept as they are
+    fn normalize_decimal(value: &str) -> String {
+        let value = value.trim();
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return value.to_string();
+        }
+
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        let int_part = int_part.trim_start_matches('0');
+        let frac_part = frac_part.trim_end_matches('0');
+        let mut normalized = if int_part.is_empty() {
+            "0".to_string()
+        } else {
+            int_part.to_string()
+        };
+        if !frac_part.is_empty() {
+            normalized.push('.');
+            normalized.push_str(frac_part);
+        }
+        if negative && normalized != "0" {
+            normalized.insert(0, '-');
+        }
+        normalized
+    }
+
+    /// 2024-01-01T10:00:00.500+08:00 -> 2024-01-01 02:00:00.5, 10:00:00.000 -> 10:00:00
+    fn normalize_temporal(value: &str) -> String {
+        let value = value.trim().replacen('T', " ", 1);
+        let value = value.strip_suffix('Z').unwrap_or(&value);
+        let datetime = DateTime::parse_from_str(value, DATETIME_TZ_FORMAT)
+            .map(|v| v.naive_utc())
+            .or_else(|_| NaiveDateTime::parse_from_str(value, DATETIME_FORMAT));
+        match datetime {
+            Ok(v) => Self::trim_fraction(&v.format("%Y-%m-%d %H:%M:%S%.9f").to_string()),
+            // date, time, or values out of chrono's range, e.g. 0000-00-00
+            Err(_) => Self::trim_fraction(value),
+        }
+    }
+
+    fn trim_fraction(value: &str) -> String {
+        match value.rsplit_once('.') {
+            Some((head, fraction)) if fraction.chars().all(|c| c.is_ascii_digit()) => {
+                let fraction = fraction.trim_end_matches('0');
+                if fraction.is_empty() {
+                    head.to_string()
+                } else {
+                    format!("{}.{}", head, fraction)
+                }
+            }
+            _ => value.to_string(),
+        }
+    }
+
+    fn normalize_json(value: &str) -> NormalizedValue {
+        match serde_json::from_str(value) {
+            Ok(v) => NormalizedValue::Json(v),
+            Err(_) => NormalizedValue::Text(value.to_string()),
+        }
+    }
+
+    fn decode_hex_text(text: &str) -> Option<Vec<u8>> {
+        let hex_str = text.strip_prefix("\\x")?;
+        hex::decode(hex_str).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_same() {
+        let same = |v1: ColValue, v2: ColValue| ValueNormalizer::is_same(&v1, &v2);
+
+        assert!(same(
+            ColValue::Decimal("001.500".into()),
+            ColValue::Decimal("1.5".into())
+        ));
+        assert!(same(ColValue::Decimal("-0.00".into()), ColValue::Long(0)));
+        assert!(same(ColValue::Decimal("10".into()), ColValue::LongLong(10)));
+        assert!(!same(
+            ColValue::Decimal("30.00".into()),
+            ColValue::Decimal("1.00".into())
+        ));
+
+        assert!(same(ColValue::Bool(true), ColValue::Tiny(1)));
+        assert!(same(ColValue::Float(0.1), ColValue::Double(0.1)));
+        assert!(same(ColValue::Double(-0.0), ColValue::Double(0.0)));
+
+        assert!(same(
+            ColValue::Timestamp("2024-01-01T10:00:00.500+08:00".into()),
+            ColValue::DateTime("2024-01-01 02:00:00.500000".into())
+        ));
+        assert!(same(
+            ColValue::Timestamp("2024-01-01 02:00:00Z".into()),
+            ColValue::Timestamp("2024-01-01 02:00:00.000".into())
+        ));
+        assert!(same(
+            ColValue::Time("10:00:00.000".into()),
+            ColValue::Time("10:00:00".into())
+        ));
+        assert!(!same(
+            ColValue::DateTime("2024-01-01 02:00:00".into()),
+            ColValue::DateTime("2024-01-01 02:00:01".into())
+        ));
+
+        assert!(same(
+            ColValue::String("\\x0102".into()),
+            ColValue::Blob(vec![1, 2])
+        ));
+        assert!(same(
+            ColValue::Json2(r#"{"a": 1, "b": [1, 2]}"#.into()),
+            ColValue::Json(br#"{"b":[1,2],"a":1}"#.to_vec())
+        ));
+        assert!(!same(
+            ColValue::String("1.0".into()),
+            ColValue::String("1".into())
+        ));
+    }
+}
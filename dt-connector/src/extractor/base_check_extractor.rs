@@ -1,6 +1,9 @@
-use std::str::FromStr;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
-use dt_common::log_info;
+use dt_common::{log_info, meta::row_data::RowData};
 
 use crate::{
     checker::{check_log::CheckLog, log_reader::LogReader},
@@ -24,6 +27,8 @@ impl BaseCheckExtractor {
         );
 
         let mut log_reader = LogReader::new(&self.check_log_dir);
+        // make sure no compressed log is corrupted before anything is revised
+        log_reader.verify()?;
         let mut batch = Vec::new();
 
         while let Some(log) = log_reader.nextval()? {
@@ -74,6 +79,77 @@ impl BaseCheckExtractor {
         same_tb && same_log_type && !any_col_none
     }
 
+    /// ids of rows changed in target since the check, e.g. by a running cdc task,
+    /// revising them may overwrite newer changes with stale rows.
+    /// target_rows are the current target rows keyed by `row_id_key`
+    pub fn get_changed_ids_since_check(
+        check_logs: &[CheckLog],
+        id_cols: &[String],
+        target_rows: &HashMap<String, RowData>,
+    ) -> HashSet<String> {
+        let mut changed_ids = HashSet::new();
+        for check_log in check_logs.iter() {
+            let id = Self::check_log_id_key(check_log, id_cols);
+            if Self::is_changed_since_check(check_log, target_rows.get(&id)) {
+                changed_ids.insert(id);
+            }
+        }
+        changed_ids
+    }
+
+    /// sorted id cols of a batch, all check logs in a batch are of the same table
+    pub fn get_id_cols(check_logs: &[CheckLog]) -> Vec<String> {
+        let mut id_cols: Vec<String> = check_logs[0].id_col_values.keys().cloned().collect();
+        id_cols.sort();
+        id_cols
+    }
+
+    pub fn row_id_key(row_data: &RowData, id_cols: &[String]) -> String {
+        let values: Vec<Option<String>> = id_cols
+            .iter()
+            .map(|col| {
+                row_data
+                    .after
+                    .as_ref()
+                    .and_then(|after| after.get(col))
+                    .and_then(|v| v.to_option_string())
+            })
+            .collect();
+        format!("{:?}", values)
+    }
+
+    fn check_log_id_key(check_log: &CheckLog, id_cols: &[String]) -> String {
+        let values: Vec<Option<String>> = id_cols
+            .iter()
+            .map(|col| check_log.id_col_values.get(col).cloned().flatten())
+            .collect();
+        format!("{:?}", values)
+    }
+
+    fn is_changed_since_check(check_log: &CheckLog, target_row: Option<&RowData>) -> bool {
+        let is_miss = check_log.diff_col_values.is_empty();
+        let Some(target_row) = target_row else {
+            // a diff row has been deleted from target
+            return !is_miss;
+        };
+        if is_miss {
+            // a miss row has been inserted into target
+            return true;
+        }
+
+        // compare with the target values recorded by the check
+        let after = target_row.after.as_ref();
+        check_log
+            .diff_col_values
+            .iter()
+            .any(|(col, diff_col_value)| {
+                let current = after
+                    .and_then(|after| after.get(col))
+                    .and_then(|v| v.to_option_string());
+                current != diff_col_value.dst
+            })
+    }
+
     fn is_any_col_none(check_log: &CheckLog) -> bool {
         for i in check_log.id_col_values.values() {
             if i.is_none() {
@@ -83,3 +159,74 @@ impl BaseCheckExtractor {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::checker::check_log::DiffColValue;
+    use dt_common::meta::{col_value::ColValue, row_type::RowType};
+
+    fn check_log(diff_dst: Option<&str>) -> CheckLog {
+        let mut diff_col_values = HashMap::new();
+        if let Some(dst) = diff_dst {
+            diff_col_values.insert(
+                "name".to_string(),
+                DiffColValue {
+                    src: Some("src".to_string()),
+                    dst: Some(dst.to_string()),
+                    src_type: None,
+                    dst_type: None,
+                },
+            );
+        }
+        CheckLog {
+            schema: "db1".to_string(),
+            tb: "tb1".to_string(),
+            target_schema: None,
+            target_tb: None,
+            id_col_values: HashMap::from([("id".to_string(), Some("1".to_string()))]),
+            diff_col_values,
+            src_row: None,
+            dst_row: None,
+        }
+    }
+
+    fn target_row(name: &str) -> RowData {
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String(name.to_string())),
+        ]);
+        RowData::new(
+            "db1".to_string(),
+            "tb1".to_string(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        )
+    }
+
+    #[test]
+    fn test_get_changed_ids_since_check() {
+        let id_cols = vec!["id".to_string()];
+        let key = BaseCheckExtractor::row_id_key(&target_row("dst"), &id_cols);
+        let changed = |check_log: CheckLog, target_row: Option<RowData>| {
+            let mut target_rows = HashMap::new();
+            if let Some(row_data) = target_row {
+                target_rows.insert(key.clone(), row_data);
+            }
+            !BaseCheckExtractor::get_changed_ids_since_check(&[check_log], &id_cols, &target_rows)
+                .is_empty()
+        };
+
+        // diff row unchanged in target
+        assert!(!changed(check_log(Some("dst")), Some(target_row("dst"))));
+        // diff row updated or deleted in target
+        assert!(changed(check_log(Some("dst")), Some(target_row("new"))));
+        assert!(changed(check_log(Some("dst")), None));
+        // miss row still missing, or inserted into target
+        assert!(!changed(check_log(None), None));
+        assert!(changed(check_log(None), Some(target_row("new"))));
+    }
+}
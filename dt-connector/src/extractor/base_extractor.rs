@@ -19,11 +19,12 @@ use dt_common::{
         dt_queue::DtQueue,
         struct_meta::struct_data::StructData,
     },
+    monitor::counter_type::CounterType,
     utils::sql_util::SqlUtil,
 };
 use dt_common::{
     meta::{
-        ddl_meta::ddl_parser::DdlParser,
+        ddl_meta::{ddl_parser::DdlParser, ddl_splitter::DdlSplitter},
         dt_data::{DtData, DtItem},
         position::Position,
         row_data::RowData,
@@ -33,7 +34,7 @@ use dt_common::{
 
 use crate::{data_marker::DataMarker, rdb_router::RdbRouter};
 
-use super::extractor_monitor::ExtractorMonitor;
+use super::{extractor_monitor::ExtractorMonitor, extractor_progress::ExtractorProgress};
 
 pub struct ExtractState {
     pub monitor: ExtractorMonitor,
@@ -143,6 +144,7 @@ pub struct BaseExtractor {
     pub buffer: Arc<DtQueue>,
     pub router: Option<RdbRouter>,
     pub shut_down: Arc<AtomicBool>,
+    pub progress: ExtractorProgress,
 }
 
 impl BaseExtractor {
@@ -156,11 +158,21 @@ impl BaseExtractor {
         state.monitor.counters.pushed_record_count += dt_data.get_data_count() as u64;
         state.monitor.counters.pushed_data_size += dt_data.get_data_size();
         state.monitor.try_flush(false).await;
+        if self.progress.update(&position) {
+            if let Some(lag) = self.progress.lag_millis() {
+                state.monitor.monitor.set_counter(
+                    &state.monitor.default_task_id,
+                    CounterType::ExtractorLag,
+                    lag,
+                );
+            }
+        }
 
         let item = DtItem {
             dt_data,
             position,
             data_origin_node,
+            trace: None,
         };
         log_debug!("extracted item: {:?}", item);
         self.buffer.push(item).await
@@ -232,33 +244,38 @@ impl BaseExtractor {
             .await
     }
 
+    /// Parses each statement of a captured ddl text, only pg ddl texts may hold several,
+    /// statements failed to parse are logged and skipped.
     pub async fn parse_ddl(
         &self,
         db_type: &DbType,
         schema: &str,
         query: &str,
-    ) -> anyhow::Result<Option<DdlData>> {
+    ) -> anyhow::Result<Vec<DdlData>> {
         let parser = DdlParser::new(db_type.to_owned());
-        let parse_result = parser.parse(query);
-        if let Err(err) = parse_result {
-            let error = format!("failed to parse ddl, will try ignore it, please execute the ddl manually in target, sql: {}, error: {}", query, err);
-            log_error!("{}", error);
-            bail! {Error::Unexpected(error)}
-        }
-
-        // case 1, execute: use db_1; create table tb_1(id int);
-        // binlog query.schema == db_1, schema from DdlParser == None
-        // case 2, execute: create table db_1.tb_1(id int);
-        // binlog query.schema == empty, schema from DdlParser == db_1
-        // case 3, execute: use db_1; create table db_2.tb_1(id int);
-        // binlog query.schema == db_1, schema from DdlParser == db_2
-        if let Some(mut ddl_data) = parse_result? {
-            ddl_data.default_schema = schema.to_string();
-            ddl_data.query = query.to_string();
-            Ok(Some(ddl_data))
-        } else {
-            Ok(None)
+        let mut res = Vec::new();
+        for statement in DdlSplitter::new(db_type.to_owned()).split_captured(query) {
+            let parse_result = match parser.parse(&statement) {
+                Ok(parse_result) => parse_result,
+                Err(err) => {
+                    log_error!("failed to parse ddl, will try ignore it, please execute the ddl manually in target, sql: {}, error: {}", statement, err);
+                    continue;
+                }
+            };
+
+            // case 1, execute: use db_1; create table tb_1(id int);
+            // binlog query.schema == db_1, schema from DdlParser == None
+            // case 2, execute: create table db_1.tb_1(id int);
+            // binlog query.schema == empty, schema from DdlParser == db_1
+            // case 3, execute: use db_1; create table db_2.tb_1(id int);
+            // binlog query.schema == db_1, schema from DdlParser == db_2
+            if let Some(mut ddl_data) = parse_result {
+                ddl_data.default_schema = schema.to_string();
+                ddl_data.query = statement;
+                res.push(ddl_data);
+            }
         }
+        Ok(res)
     }
 
     pub async fn parse_dcl(
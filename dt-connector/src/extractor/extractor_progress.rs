@@ -0,0 +1,90 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
+
+use chrono::Utc;
+
+use dt_common::meta::position::Position;
+
+// positions of cdc events may be large, e.g. gtid_set, so they are not cloned for every event
+const UPDATE_INTERVAL_MILLIS: u64 = 100;
+
+/// The read position of an extractor, shared with monitors and the code embedding the task,
+/// can be queried while the extractor is running.
+#[derive(Clone, Default)]
+pub struct ExtractorProgress {
+    position: Arc<RwLock<Position>>,
+    updated_at_millis: Arc<AtomicU64>,
+}
+
+impl ExtractorProgress {
+    /// returns true if the position is refreshed
+    pub fn update(&self, position: &Position) -> bool {
+        if matches!(position, Position::None) {
+            return false;
+        }
+
+        let now = Self::now_millis();
+        let updated_at = self.updated_at_millis.load(Ordering::Relaxed);
+        if now.saturating_sub(updated_at) < UPDATE_INTERVAL_MILLIS {
+            return false;
+        }
+        self.updated_at_millis.store(now, Ordering::Relaxed);
+        if let Ok(mut current) = self.position.write() {
+            *current = position.clone();
+        }
+        true
+    }
+
+    /// the position of the latest extracted data
+    pub fn position(&self) -> Position {
+        self.position
+            .read()
+            .map(|position| position.clone())
+            .unwrap_or_default()
+    }
+
+    /// millis between now and the source commit time of the latest extracted data,
+    /// None if the position has no timestamp, e.g. snapshot positions.
+    /// it keeps growing when the source is idle, unless heartbeat is enabled.
+    pub fn lag_millis(&self) -> Option<u64> {
+        let timestamp = self.position.read().ok()?.to_timestamp();
+        if timestamp == 0 {
+            return None;
+        }
+        Some(Self::now_millis().saturating_sub(timestamp))
+    }
+
+    fn now_millis() -> u64 {
+        Utc::now().timestamp_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_lag() {
+        let progress = ExtractorProgress::default();
+        assert_eq!(progress.position(), Position::None);
+        assert_eq!(progress.lag_millis(), None);
+
+        let timestamp = Position::format_timestamp_millis(Utc::now().timestamp_millis() - 5000);
+        let position = Position::PgCdc {
+            lsn: "0/5D65CB0".into(),
+            timestamp,
+        };
+        assert!(progress.update(&position));
+        assert_eq!(progress.position(), position);
+        assert!(progress.lag_millis().is_some_and(|lag| lag >= 5000));
+
+        // throttled
+        assert!(!progress.update(&Position::PgCdc {
+            lsn: "0/5D65CC0".into(),
+            timestamp: String::new(),
+        }));
+        assert_eq!(progress.position(), position);
+    }
+}
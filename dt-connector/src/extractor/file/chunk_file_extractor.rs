@@ -0,0 +1,187 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use crate::{
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+    },
+    sinker::file::chunk_file::{ChunkMeta, ChunkReader},
+    Extractor,
+};
+use dt_common::{
+    log_info,
+    meta::{position::Position, row_type::RowType, syncer::Syncer},
+    rdb_filter::RdbFilter,
+};
+
+/// imports chunk files exported by FileSinker with file_format=chunk,
+/// chunks already imported by previous runs are skipped.
+pub struct ChunkFileExtractor {
+    pub file_dir: String,
+    pub filter: RdbFilter,
+    pub syncer: Arc<Mutex<Syncer>>,
+    pub base_extractor: BaseExtractor,
+    pub extract_state: ExtractState,
+}
+
+// a chunk whose rows are all pushed, waiting for them to be sunk
+struct PendingChunk {
+    path: PathBuf,
+    file: String,
+    last_row_index: u64,
+}
+
+#[async_trait]
+impl Extractor for ChunkFileExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        let chunks = self.list_chunks()?;
+        log_info!(
+            "ChunkFileExtractor starts, file_dir: {}, chunks to import: {}",
+            self.file_dir,
+            chunks.len()
+        );
+
+        let mut pending = VecDeque::new();
+        for chunk_path in chunks.iter() {
+            if let Some(chunk) = self.extract_chunk(chunk_path).await? {
+                pending.push_back(chunk);
+            }
+            self.mark_committed(&mut pending).await?;
+        }
+
+        log_info!(
+            "end extracting chunk files, all count: {}",
+            self.extract_state.monitor.counters.pushed_record_count
+        );
+        self.base_extractor
+            .wait_task_finish(&mut self.extract_state)
+            .await?;
+
+        // the pipeline sinks the remaining data and commits its final position after shut down
+        while !pending.is_empty() {
+            self.mark_committed(&mut pending).await?;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Ok(())
+    }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
+}
+
+impl ChunkFileExtractor {
+    fn list_chunks(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut chunks = Vec::new();
+        for schema_dir in Self::list_dirs(Path::new(&self.file_dir))? {
+            for tb_dir in Self::list_dirs(&schema_dir)? {
+                for chunk_path in ChunkMeta::list_chunks(&tb_dir)? {
+                    if ChunkMeta::imported_path(&chunk_path).exists() {
+                        log_info!("chunk already imported: {}", chunk_path.display());
+                        continue;
+                    }
+                    chunks.push(chunk_path);
+                }
+            }
+        }
+        Ok(chunks)
+    }
+
+    fn list_dirs(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut dirs = Vec::new();
+        for entry in
+            fs::read_dir(dir).with_context(|| format!("failed to read dir: [{}]", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            }
+        }
+        dirs.sort();
+        Ok(dirs)
+    }
+
+    async fn extract_chunk(&mut self, chunk_path: &Path) -> anyhow::Result<Option<PendingChunk>> {
+        let meta = ChunkMeta::load(chunk_path)?;
+        meta.verify(chunk_path)?;
+
+        let mut reader = ChunkReader::open(chunk_path)?;
+        let (schema, tb) = (&reader.header.schema, &reader.header.tb);
+        if self.filter.filter_event(schema, tb, &RowType::Insert) {
+            log_info!("schema: {}, tb: {}, insert events filtered", schema, tb);
+            Self::mark_imported(chunk_path)?;
+            return Ok(None);
+        }
+
+        let file = chunk_path.display().to_string();
+        let mut row_index = 0;
+        while let Some(row_data) = reader.next_row()? {
+            self.extract_state.record_extracted_metrics_row(&row_data);
+            let position = Position::ChunkFile {
+                file: file.clone(),
+                row_index,
+            };
+            self.base_extractor
+                .push_row(&mut self.extract_state, row_data, position)
+                .await?;
+            row_index += 1;
+        }
+
+        if row_index != meta.rows {
+            log_info!(
+                "chunk rows mismatch, file: {}, expect: {}, actual: {}",
+                file,
+                meta.rows,
+                row_index
+            );
+        }
+        if row_index == 0 {
+            Self::mark_imported(chunk_path)?;
+            return Ok(None);
+        }
+        Ok(Some(PendingChunk {
+            path: chunk_path.to_path_buf(),
+            file,
+            last_row_index: row_index - 1,
+        }))
+    }
+
+    async fn mark_committed(&self, pending: &mut VecDeque<PendingChunk>) -> anyhow::Result<()> {
+        let Position::ChunkFile { file, row_index } =
+            self.syncer.lock().await.committed_position.clone()
+        else {
+            return Ok(());
+        };
+        // chunks are pushed in order, those before the committed one are all sunk
+        let Some(index) = pending.iter().position(|chunk| chunk.file == file) else {
+            return Ok(());
+        };
+        let finished = if row_index >= pending[index].last_row_index {
+            index + 1
+        } else {
+            index
+        };
+        for chunk in pending.drain(..finished) {
+            Self::mark_imported(&chunk.path)?;
+        }
+        Ok(())
+    }
+
+    fn mark_imported(chunk_path: &Path) -> anyhow::Result<()> {
+        let path = ChunkMeta::imported_path(chunk_path);
+        fs::write(&path, "")
+            .with_context(|| format!("failed to write file: [{}]", path.display()))?;
+        log_info!("chunk imported: {}", chunk_path.display());
+        Ok(())
+    }
+}
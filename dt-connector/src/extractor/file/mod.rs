@@ -0,0 +1 @@
+pub mod chunk_file_extractor;
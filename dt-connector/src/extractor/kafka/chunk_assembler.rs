@@ -0,0 +1,105 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::bail;
+
+use dt_common::log_warn;
+
+/// Reassembles payloads split into chunks by the kafka sinker with [sinker].max_message_bytes.
+/// Chunks of a payload are produced in order to the same partition, but may interleave with
+/// messages of other sinkers, so a few incomplete payloads are kept at the same time.
+pub struct ChunkAssembler {
+    pending: HashMap<String, Vec<Option<Vec<u8>>>>,
+    // chunk ids by arrival, the oldest incomplete payload is dropped beyond capacity
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl ChunkAssembler {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            pending: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the whole payload once all chunks of it are received.
+    pub fn push(
+        &mut self,
+        id: &str,
+        index: usize,
+        count: usize,
+        chunk: &[u8],
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        if index >= count {
+            bail!(
+                "invalid kafka chunk, id: {}, index: {}, count: {}",
+                id,
+                index,
+                count
+            );
+        }
+
+        if !self.pending.contains_key(id) {
+            if self.order.len() >= self.capacity {
+                if let Some(oldest) = self.order.pop_front() {
+                    log_warn!("incomplete kafka chunks dropped, id: {}", oldest);
+                    self.pending.remove(&oldest);
+                }
+            }
+            self.pending.insert(id.to_string(), vec![None; count]);
+            self.order.push_back(id.to_string());
+        }
+
+        let chunks = self.pending.get_mut(id).unwrap();
+        if chunks.len() != count {
+            bail!(
+                "kafka chunk count mismatch, id: {}, expected: {}, got: {}",
+                id,
+                chunks.len(),
+                count
+            );
+        }
+        // redelivered chunks overwrite the former ones
+        chunks[index] = Some(chunk.to_vec());
+        if chunks.iter().any(|chunk| chunk.is_none()) {
+            return Ok(None);
+        }
+
+        let chunks = self.pending.remove(id).unwrap();
+        self.order.retain(|i| i != id);
+        Ok(Some(chunks.into_iter().flatten().flatten().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push() {
+        let mut assembler = ChunkAssembler::new(2);
+        assert_eq!(assembler.push("a", 0, 2, b"12").unwrap(), None);
+        // interleaved with another payload
+        assert_eq!(assembler.push("b", 1, 2, b"cd").unwrap(), None);
+        assert_eq!(
+            assembler.push("a", 1, 2, b"34").unwrap(),
+            Some(b"1234".to_vec())
+        );
+        assert_eq!(
+            assembler.push("b", 0, 2, b"ab").unwrap(),
+            Some(b"abcd".to_vec())
+        );
+        assert!(assembler.pending.is_empty());
+        assert!(assembler.order.is_empty());
+
+        // the oldest incomplete payload is dropped beyond capacity
+        assembler.push("c", 0, 2, b"1").unwrap();
+        assembler.push("d", 0, 2, b"1").unwrap();
+        assembler.push("e", 0, 2, b"1").unwrap();
+        assert!(!assembler.pending.contains_key("c"));
+        assert_eq!(assembler.push("c", 1, 2, b"2").unwrap(), None);
+
+        assert!(assembler.push("f", 2, 2, b"1").is_err());
+    }
+}
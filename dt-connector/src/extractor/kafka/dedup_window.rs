@@ -0,0 +1,49 @@
+use std::collections::{HashSet, VecDeque};
+
+/// Keys of the latest events, the oldest key leaves once the window is full.
+pub struct DedupWindow {
+    capacity: usize,
+    keys: HashSet<u64>,
+    order: VecDeque<u64>,
+}
+
+impl DedupWindow {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            keys: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// false if the key is already in the window
+    pub fn insert(&mut self, key: u64) -> bool {
+        if !self.keys.insert(key) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut window = DedupWindow::new(2);
+        assert!(window.insert(1));
+        assert!(!window.insert(1));
+        assert!(window.insert(2));
+        assert!(window.insert(3));
+        // 1 has left the window
+        assert!(window.insert(1));
+        assert!(!window.insert(3));
+    }
+}
@@ -1,9 +1,15 @@
-use std::sync::Arc;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context;
 use async_trait::async_trait;
 use rdkafka::{
     consumer::{Consumer, StreamConsumer},
+    message::{BorrowedMessage, Headers},
     ClientConfig, Message, Offset, TopicPartitionList,
 };
 use tokio::sync::Mutex;
@@ -11,13 +17,31 @@ use tokio::sync::Mutex;
 use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+        kafka::{chunk_assembler::ChunkAssembler, dedup_window::DedupWindow},
         resumer::recovery::Recovery,
     },
+    sinker::{
+        idempotency_key::IDEMPOTENCY_KEY_HEADER,
+        kafka::{
+            large_value::{
+                KafkaLargeValue, CHUNK_COUNT_HEADER, CHUNK_ID_HEADER, CHUNK_INDEX_HEADER,
+                COMPRESSED_COLS_HEADER,
+            },
+            proxy_context::KafkaProxyContext,
+        },
+    },
     Extractor,
 };
 use dt_common::{
-    log_info, log_warn,
-    meta::{avro::avro_converter::AvroConverter, position::Position, syncer::Syncer},
+    config::extractor_config::KafkaReplayUntil,
+    log_debug, log_info, log_warn,
+    meta::{
+        avro::avro_converter::AvroConverter, col_value::ColValue, ddl_meta::ddl_data::DdlData,
+        dt_data::DtData, position::Position, syncer::Syncer,
+    },
+    rdb_filter::RdbFilter,
+    utils::time_util::TimeUtil,
 };
 
 pub struct KafkaExtractor {
@@ -30,8 +54,18 @@ pub struct KafkaExtractor {
     pub offset: i64,
     pub ack_interval_secs: u64,
     pub avro_converter: AvroConverter,
+    // ddls are applied only if allowed by [filter] do_ddls
+    pub filter: RdbFilter,
     pub syncer: Arc<Mutex<Syncer>>,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+    // drops rows redelivered within the window, None to disable
+    pub dedup_window: Option<DedupWindow>,
+    // stop point of a point-in-time replay, None to consume forever
+    pub replay_until: Option<KafkaReplayUntil>,
+    // payloads chunked by the sinker with [sinker].max_message_bytes
+    pub chunk_assembler: ChunkAssembler,
+    // [proxy] kafka_proxy, empty to connect brokers directly
+    pub proxy: String,
 }
 
 #[async_trait]
@@ -58,41 +92,257 @@ impl Extractor for KafkaExtractor {
             self.offset
         );
         let consumer = self.create_consumer();
+        if self.replay_until.is_some() {
+            return self.replay_avro(consumer).await;
+        }
         self.extract_avro(consumer).await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl KafkaExtractor {
-    async fn extract_avro(&mut self, consumer: StreamConsumer) -> anyhow::Result<()> {
+    async fn extract_avro(
+        &mut self,
+        consumer: StreamConsumer<KafkaProxyContext>,
+    ) -> anyhow::Result<()> {
         loop {
             let msg = consumer
                 .recv()
                 .await
                 .with_context(|| format!("KafkaCdcExtractor failed, topic: {}", self.topic))?;
-            if let Some(payload) = msg.payload() {
-                let dt_data = self
-                    .avro_converter
-                    .avro_value_to_dt_data(payload.to_vec())?;
-                let position = Position::Kafka {
-                    topic: self.topic.clone(),
-                    partition: self.partition,
-                    offset: msg.offset(),
-                };
-                self.base_extractor
-                    .push_dt_data(&mut self.extract_state, dt_data, position)
+            if let Some((payload, dt_data, provenance)) = self.decode_msg(&msg)? {
+                self.push_msg(&msg, &payload, dt_data, provenance.is_some())
                     .await?;
             }
         }
     }
 
-    fn create_consumer(&self) -> StreamConsumer {
+    // consumes up to replay_until and stops, or at the end of the partition if it comes first
+    async fn replay_avro(
+        &mut self,
+        consumer: StreamConsumer<KafkaProxyContext>,
+    ) -> anyhow::Result<()> {
+        let replay_until = self.replay_until.clone().unwrap();
+        let (_, high_offset) = consumer
+            .fetch_watermarks(&self.topic, self.partition, Duration::from_secs(30))
+            .with_context(|| format!("fetch watermarks failed, topic: {}", self.topic))?;
+        log_info!(
+            "replay until: {:?}, end offset of partition: {}",
+            replay_until,
+            high_offset
+        );
+
+        if self.offset < high_offset {
+            loop {
+                let msg = consumer
+                    .recv()
+                    .await
+                    .with_context(|| format!("KafkaCdcExtractor failed, topic: {}", self.topic))?;
+                if let Some((payload, dt_data, provenance)) = self.decode_msg(&msg)? {
+                    let commit_millis = Self::commit_millis(&msg, &provenance);
+                    if replay_until.is_passed(msg.offset(), commit_millis) {
+                        log_info!(
+                            "replay reached the stop point, offset: {}, commit millis: {:?}",
+                            msg.offset(),
+                            commit_millis
+                        );
+                        break;
+                    }
+                    self.push_msg(&msg, &payload, dt_data, provenance.is_some())
+                        .await?;
+                }
+                if msg.offset() >= high_offset - 1 {
+                    log_warn!(
+                        "replay reached the end of partition before the stop point, offset: {}",
+                        msg.offset()
+                    );
+                    break;
+                }
+            }
+        } else {
+            log_warn!(
+                "nothing to replay, start offset: {}, end offset of partition: {}",
+                self.offset,
+                high_offset
+            );
+        }
+
+        self.base_extractor
+            .wait_task_finish(&mut self.extract_state)
+            .await
+    }
+
+    // reassembles chunks and restores compressed columns, None if more chunks are expected
+    fn decode_msg(
+        &mut self,
+        msg: &BorrowedMessage,
+    ) -> anyhow::Result<Option<(Vec<u8>, DtData, Option<HashMap<String, ColValue>>)>> {
+        let Some(payload) = msg.payload() else {
+            return Ok(None);
+        };
+        let payload = match Self::chunk_info(msg)? {
+            Some((id, index, count)) => {
+                match self.chunk_assembler.push(&id, index, count, payload)? {
+                    Some(payload) => payload,
+                    None => return Ok(None),
+                }
+            }
+            None => payload.to_vec(),
+        };
+
+        let (mut dt_data, provenance) = self
+            .avro_converter
+            .avro_value_to_dt_data_with_provenance(payload.clone())?;
+        if let Some(header) = Self::get_header(msg, COMPRESSED_COLS_HEADER) {
+            if let DtData::Dml { row_data } = &mut dt_data {
+                KafkaLargeValue::decompress(row_data, header)?;
+            }
+        }
+        Ok(Some((payload, dt_data, provenance)))
+    }
+
+    fn chunk_info(msg: &BorrowedMessage) -> anyhow::Result<Option<(String, usize, usize)>> {
+        let Some(id) = Self::get_header(msg, CHUNK_ID_HEADER) else {
+            return Ok(None);
+        };
+        let id = String::from_utf8_lossy(id).to_string();
+        let parse = |key: &str| -> anyhow::Result<usize> {
+            let value = Self::get_header(msg, key).unwrap_or_default();
+            std::str::from_utf8(value)?
+                .parse()
+                .with_context(|| format!("invalid header {}, chunk id: {}", key, id))
+        };
+        let index = parse(CHUNK_INDEX_HEADER)?;
+        let count = parse(CHUNK_COUNT_HEADER)?;
+        Ok(Some((id, index, count)))
+    }
+
+    fn get_header<'a>(msg: &'a BorrowedMessage, key: &str) -> Option<&'a [u8]> {
+        msg.headers().and_then(|headers| {
+            headers
+                .iter()
+                .find(|header| header.key == key)
+                .and_then(|header| header.value)
+        })
+    }
+
+    async fn push_msg(
+        &mut self,
+        msg: &BorrowedMessage<'_>,
+        payload: &[u8],
+        dt_data: DtData,
+        with_provenance: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(dedup_window) = &mut self.dedup_window {
+            let key = Self::dedup_key(msg, payload, &dt_data, with_provenance);
+            if key.is_some_and(|key| !dedup_window.insert(key)) {
+                log_debug!(
+                    "duplicate row dropped, topic: {}, offset: {}",
+                    self.topic,
+                    msg.offset()
+                );
+                return Ok(());
+            }
+        }
+        let position = Position::Kafka {
+            topic: self.topic.clone(),
+            partition: self.partition,
+            offset: msg.offset(),
+        };
+        if let DtData::Ddl { ddl_data } = dt_data {
+            return self.push_ddl(ddl_data, position).await;
+        }
+        self.base_extractor
+            .push_dt_data(&mut self.extract_state, dt_data, position)
+            .await
+    }
+
+    // ddls in messages only carry the query, parsed again so rdb sinkers can execute them
+    async fn push_ddl(&mut self, ddl_data: DdlData, position: Position) -> anyhow::Result<()> {
+        if self.filter.filter_all_ddl() {
+            return Ok(());
+        }
+
+        // parse errors are logged, the ddl should be executed in target manually
+        let ddls = self
+            .base_extractor
+            .parse_ddl(&ddl_data.db_type, &ddl_data.default_schema, &ddl_data.query)
+            .await?;
+
+        for sub_ddl_data in ddls
+            .into_iter()
+            .flat_map(|ddl_data| ddl_data.split_to_multi())
+        {
+            let (db, tb) = sub_ddl_data.get_schema_tb();
+            if self.filter.filter_ddl(&db, &tb, &sub_ddl_data.ddl_type) {
+                continue;
+            }
+            self.avro_converter
+                .refresh_meta(std::slice::from_ref(&sub_ddl_data));
+            self.base_extractor
+                .push_ddl(&mut self.extract_state, sub_ddl_data, position.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    // commit time of the source transaction from provenance, the message time if not produced
+    // with [sinker].with_provenance=true
+    fn commit_millis(
+        msg: &BorrowedMessage,
+        provenance: &Option<HashMap<String, ColValue>>,
+    ) -> Option<i64> {
+        let commit_ts =
+            provenance
+                .as_ref()
+                .and_then(|provenance| match provenance.get("commit_ts") {
+                    Some(ColValue::String(commit_ts)) => {
+                        TimeUtil::datetime_from_utc_str(commit_ts).ok()
+                    }
+                    _ => None,
+                });
+        match commit_ts {
+            Some(commit_ts) => Some(commit_ts.timestamp_millis()),
+            None => msg.timestamp().to_millis(),
+        }
+    }
+
+    // only rows with a source identity are deduplicated, i.e. the idempotency key header or
+    // the provenance in payload, identical rows without them may be different changes
+    fn dedup_key(
+        msg: &BorrowedMessage,
+        payload: &[u8],
+        dt_data: &DtData,
+        with_provenance: bool,
+    ) -> Option<u64> {
+        if !matches!(dt_data, DtData::Dml { .. }) {
+            return None;
+        }
+
+        let idempotency_key = Self::get_header(msg, IDEMPOTENCY_KEY_HEADER);
+        let mut hasher = DefaultHasher::new();
+        match idempotency_key {
+            Some(key) => key.hash(&mut hasher),
+            // the payload has schema, tb, row values and the source position
+            None if with_provenance => payload.hash(&mut hasher),
+            None => return None,
+        }
+        Some(hasher.finish())
+    }
+
+    fn create_consumer(&self) -> StreamConsumer<KafkaProxyContext> {
         let mut config = ClientConfig::new();
         config.set("bootstrap.servers", &self.url);
         config.set("group.id", &self.group);
         config.set("auto.offset.reset", "latest");
         config.set("session.timeout.ms", "10000");
 
-        let consumer: StreamConsumer = config.create().unwrap();
+        let consumer: StreamConsumer<KafkaProxyContext> = config
+            .create_with_context(KafkaProxyContext::new(&self.proxy))
+            .unwrap();
         // only support extract data from one topic, one partition
         let mut tpl = TopicPartitionList::new();
         if self.offset >= 0 {
@@ -1 +1,3 @@
+pub mod chunk_assembler;
+pub mod dedup_window;
 pub mod kafka_extractor;
@@ -2,6 +2,8 @@ pub mod base_check_extractor;
 pub mod base_extractor;
 pub mod base_splitter;
 pub mod extractor_monitor;
+pub mod extractor_progress;
+pub mod file;
 pub mod kafka;
 pub mod mongo;
 pub mod mysql;
@@ -20,6 +20,7 @@ use tokio::{sync::Mutex, time::Instant};
 use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
         resumer::recovery::Recovery,
     },
     Extractor,
@@ -335,6 +336,10 @@ impl Extractor for MongoCdcExtractor {
         self.mongo_client.clone().shutdown().await;
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl MongoCdcExtractor {
@@ -22,6 +22,7 @@ use crate::{
     extractor::{
         base_check_extractor::BaseCheckExtractor,
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
     },
     BatchCheckExtractor, Extractor,
 };
@@ -52,6 +53,10 @@ impl Extractor for MongoCheckExtractor {
         self.mongo_client.clone().shutdown().await;
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 #[async_trait]
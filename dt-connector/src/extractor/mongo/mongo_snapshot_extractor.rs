@@ -1,17 +1,22 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
 
 use anyhow::{anyhow, bail};
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use mongodb::{
-    bson::{doc, Document},
-    options::FindOptions,
-    Client,
+    bson::{doc, oid::ObjectId, Document, Timestamp},
+    options::{ReadConcern, ReadPreference, SelectionCriteria},
+    Client, Collection, Cursor,
 };
 
 use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
         estimated_sample_limit,
+        extractor_progress::ExtractorProgress,
         resumer::recovery::Recovery,
         snapshot_chunk_id_generator::SnapshotChunkIdGenerator,
         snapshot_dispatcher::SnapshotDispatcher,
@@ -19,10 +24,12 @@ use crate::{
     Extractor,
 };
 use dt_common::{
-    config::config_enums::{DbType, RdbParallelType},
+    config::config_enums::{DbType, MongoReadConcern, MongoReadPreference, RdbParallelType},
+    error::Error,
     log_error, log_info,
     meta::{
         col_value::ColValue,
+        dt_data::DtData,
         mongo::{mongo_constant::MongoConstants, mongo_key::MongoKey},
         order_key::OrderKey,
         position::Position,
@@ -39,27 +46,65 @@ pub struct MongoSnapshotExtractor {
     pub parallel_type: RdbParallelType,
     pub parallel_size: usize,
     pub batch_size: u32,
+    pub read_concern: MongoReadConcern,
+    pub read_preference: MongoReadPreference,
+    // set on start if read_concern=snapshot, all collections are read at it
+    pub at_cluster_time: Option<Timestamp>,
     pub mongo_client: Client,
     pub sample_rate: Option<u8>,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
     pub filter: RdbFilter,
 }
 
+// at most this many chunks are split for a collection, chunks may then be bigger than batch_size
+const MAX_SPLIT_CHUNKS: u64 = 1024;
+// _ids sampled per chunk to pick boundaries of collections not keyed by ObjectId
+const SAMPLES_PER_CHUNK: u64 = 20;
+
+/// A range of _id: (lower, upper], None for unbounded.
+#[derive(Debug, Clone, PartialEq)]
+struct MongoChunk {
+    chunk_id: u64,
+    lower: Option<MongoKey>,
+    upper: Option<MongoKey>,
+}
+
+struct MongoChunkDispatchState {
+    chunks: VecDeque<MongoChunk>,
+    // upper bounds of finished chunks not checkpointed yet, as chunks may finish out of order
+    finished_uppers: HashMap<u64, Option<MongoKey>>,
+    next_checkpoint_id: u64,
+    // of the table, checkpoints are pushed by it
+    extract_state: ExtractState,
+    count: u64,
+}
+
 #[async_trait]
 impl Extractor for MongoSnapshotExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
         if self.parallel_size < 1 {
             bail!("parallel_size must be greater than 0");
         }
-        if matches!(self.parallel_type, RdbParallelType::Chunk) {
-            bail!("mongo snapshot extractor does not support parallel_type=chunk");
+        if self.read_concern == MongoReadConcern::Snapshot {
+            let cluster_time = self.get_cluster_time().await?;
+            log_info!(
+                "mongo snapshot reads at cluster time: {}, {}",
+                cluster_time.time,
+                cluster_time.increment
+            );
+            self.at_cluster_time = Some(cluster_time);
         }
 
         let tables = self.collect_tables();
         let this = self.clone_for_dispatch();
+        // with parallel_type=chunk, tables are extracted one by one, each by parallel_size cursors
+        let tb_parallel_size = match self.parallel_type {
+            RdbParallelType::Chunk => 1,
+            RdbParallelType::Table => self.parallel_size,
+        };
         SnapshotDispatcher::dispatch_table_work_source(
             tables,
-            self.parallel_size,
+            tb_parallel_size,
             "mongo table worker",
             move |(db, tb)| {
                 let this = this.clone_for_dispatch();
@@ -76,6 +121,10 @@ impl Extractor for MongoSnapshotExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl MongoSnapshotExtractor {
@@ -97,6 +146,9 @@ impl MongoSnapshotExtractor {
             parallel_type: self.parallel_type.clone(),
             parallel_size: self.parallel_size,
             batch_size: self.batch_size,
+            read_concern: self.read_concern.clone(),
+            read_preference: self.read_preference.clone(),
+            at_cluster_time: self.at_cluster_time,
             mongo_client: self.mongo_client.clone(),
             sample_rate: self.sample_rate,
             recovery: self.recovery.clone(),
@@ -116,11 +168,15 @@ impl MongoSnapshotExtractor {
             self.batch_size
         );
 
+        // chunks are checkpointed once all chunks before them are finished
+        let is_chunk = matches!(self.parallel_type, RdbParallelType::Chunk);
         let resume_key = if let Some(handler) = &self.recovery {
             if let Some(Position::RdbSnapshot {
                 order_key: Some(OrderKey::Single((_, Some(value)))),
                 ..
-            }) = handler.get_snapshot_resume_position(&db, &tb, false).await
+            }) = handler
+                .get_snapshot_resume_position(&db, &tb, is_chunk)
+                .await
             {
                 let key = Self::parse_resume_key(&value)?;
                 log_info!(
@@ -138,80 +194,88 @@ impl MongoSnapshotExtractor {
             None
         };
 
-        let collection = self.mongo_client.database(&db).collection::<Document>(&tb);
+        let collection = self.collection(&db, &tb);
         let estimated_count = if self
             .sample_rate
             .filter(|rate| (1..100).contains(rate))
             .is_some()
         {
-            collection.estimated_document_count().await?
+            self.estimated_count(&collection).await?
         } else {
             0
         };
         let sample_limit = estimated_sample_limit(self.sample_rate, estimated_count);
-        let mut find_options = FindOptions::builder()
-            .sort(doc! {MongoConstants::ID: 1})
-            .batch_size(self.batch_size)
-            .build();
-        if let Some(limit) = sample_limit.and_then(|limit| i64::try_from(limit).ok()) {
-            find_options.limit = Some(limit);
-        }
-        let filter = resume_key
-            .as_ref()
-            .map(Self::build_resume_filter)
-            .unwrap_or_default();
-        let mut find = collection
-            .find(filter)
-            .sort(doc! {MongoConstants::ID: 1})
-            .batch_size(self.batch_size);
-        if let Some(limit) = find_options.limit {
-            find = find.limit(limit);
-        }
-        let mut cursor = find.await?;
-        let mut chunk_id_generator = SnapshotChunkIdGenerator::new(self.batch_size as usize);
-        while cursor.advance().await? {
-            let doc = cursor.deserialize_current().map_err(|e| {
-                log_error!("error deserializing {}.{} document: {}", db, tb, e);
-                e
-            })?;
 
-            let key = MongoKey::from_doc(&doc).ok_or(anyhow!(
-                "skip {}.{} document without `_id`: {:?}",
+        let chunks = if is_chunk && sample_limit.is_none() {
+            self.split_chunks(&collection, resume_key.as_ref()).await?
+        } else {
+            Vec::new()
+        };
+        let count = if chunks.len() > 1 {
+            log_info!(
+                "{}.{} is split into {} chunks by [{}]",
                 db,
                 tb,
-                doc
-            ))?;
-
-            let after = Self::build_after_cols(&doc);
-            let row_data = RowData::new(
-                db.clone(),
-                tb.clone(),
-                chunk_id_generator.next_row_chunk_id(),
-                RowType::Insert,
-                None,
-                Some(after),
+                chunks.len(),
+                MongoConstants::ID
             );
-            let position = Position::RdbSnapshot {
-                db_type: DbType::Mongo.to_string(),
-                schema: db.clone(),
-                tb: tb.clone(),
-                order_key: Some(OrderKey::Single((
-                    MongoConstants::ID.into(),
-                    Some(key.to_string()),
-                ))),
-            };
+            let state;
+            (state, extract_state) = self.extract_chunks(&db, &tb, chunks, extract_state).await?;
+            state
+        } else {
+            let filter = Self::build_chunk_filter(resume_key.as_ref(), None);
+            let limit = sample_limit.and_then(|limit| i64::try_from(limit).ok());
+            let mut cursor = self.find(&db, &tb, filter, limit).await?;
+            let mut chunk_id_generator = SnapshotChunkIdGenerator::new(self.batch_size as usize);
+            while cursor.advance().await? {
+                let doc = cursor.deserialize_current().map_err(|e| {
+                    log_error!("error deserializing {}.{} document: {}", db, tb, e);
+                    e
+                })?;
 
-            base_extractor
-                .push_row(&mut extract_state, row_data, position)
-                .await?;
-        }
+                let key = MongoKey::from_doc(&doc).ok_or(anyhow!(
+                    "skip {}.{} document without `_id`: {:?}",
+                    db,
+                    tb,
+                    doc
+                ))?;
+
+                let after = Self::build_after_cols(&doc);
+                let row_data = RowData::new(
+                    db.clone(),
+                    tb.clone(),
+                    chunk_id_generator.next_row_chunk_id(),
+                    RowType::Insert,
+                    None,
+                    Some(after),
+                );
+                let position = Self::build_position(&db, &tb, &key);
+
+                base_extractor
+                    .push_row(&mut extract_state, row_data, position)
+                    .await?;
+            }
+            extract_state.monitor.counters.pushed_record_count
+        };
 
         log_info!(
             "end extracting data from {}.{}, all count: {}",
             db,
             tb,
-            extract_state.monitor.counters.pushed_record_count
+            count
         );
+        // documents read at the cluster time are consistent with a change stream started from it
+        let (row_count, cdc_position) = match self.at_cluster_time {
+            Some(cluster_time) => (
+                Some(count),
+                Some(Box::new(Position::MongoCdc {
+                    resume_token: String::new(),
+                    operation_time: cluster_time.time,
+                    timestamp: Position::format_timestamp_millis(cluster_time.time as i64 * 1000),
+                })),
+            ),
+            None => (None, None),
+        };
         // push schema and table info without routing.
         base_extractor
             .push_snapshot_finished(
@@ -220,6 +284,8 @@ impl MongoSnapshotExtractor {
                     db_type: DbType::Mongo.to_string(),
                     schema: db.clone(),
                     tb: tb.clone(),
+                    row_count,
+                    cdc_position,
                 },
             )
             .await?;
@@ -227,17 +293,343 @@ impl MongoSnapshotExtractor {
         Ok(())
     }
 
-    fn build_resume_filter(key: &MongoKey) -> Document {
+    // extracts chunks by up to parallel_size cursors, returns the count and the table extract state
+    async fn extract_chunks(
+        &self,
+        db: &str,
+        tb: &str,
+        chunks: Vec<MongoChunk>,
+        extract_state: ExtractState,
+    ) -> anyhow::Result<(u64, ExtractState)> {
+        let this = Arc::new(self.clone_for_dispatch());
+        let state = MongoChunkDispatchState {
+            next_checkpoint_id: chunks.first().map(|c| c.chunk_id).unwrap_or_default(),
+            chunks: chunks.into(),
+            finished_uppers: HashMap::new(),
+            extract_state,
+            count: 0,
+        };
+
+        let state = SnapshotDispatcher::dispatch_work_source(
+            state,
+            self.parallel_size,
+            "mongo chunk worker",
+            |mut state: MongoChunkDispatchState| async move {
+                let work = state.chunks.pop_front().map(|chunk| {
+                    let extract_state =
+                        SnapshotDispatcher::fork_extract_state(&state.extract_state);
+                    (chunk, extract_state)
+                });
+                Ok((state, work))
+            },
+            {
+                let this = Arc::clone(&this);
+                let (db, tb) = (db.to_string(), tb.to_string());
+                move |(chunk, extract_state)| {
+                    let this = Arc::clone(&this);
+                    let (db, tb) = (db.clone(), tb.clone());
+                    async move { this.extract_chunk(db, tb, chunk, extract_state).await }
+                }
+            },
+            |mut state: MongoChunkDispatchState, (chunk, count): (MongoChunk, u64)| {
+                let this = Arc::clone(&this);
+                let (db, tb) = (db.to_string(), tb.to_string());
+                async move {
+                    state.count += count;
+                    state.finished_uppers.insert(chunk.chunk_id, chunk.upper);
+                    let mut checkpoint = None;
+                    while let Some(upper) = state.finished_uppers.remove(&state.next_checkpoint_id)
+                    {
+                        state.next_checkpoint_id += 1;
+                        checkpoint = upper.or(checkpoint);
+                    }
+                    if let Some(key) = checkpoint {
+                        let commit = DtData::Commit { xid: String::new() };
+                        this.base_extractor
+                            .push_dt_data(
+                                &mut state.extract_state,
+                                commit,
+                                Self::build_position(&db, &tb, &key),
+                            )
+                            .await?;
+                    }
+                    Ok(state)
+                }
+            },
+        )
+        .await?;
+        Ok((state.count, state.extract_state))
+    }
+
+    async fn extract_chunk(
+        &self,
+        db: String,
+        tb: String,
+        chunk: MongoChunk,
+        mut extract_state: ExtractState,
+    ) -> anyhow::Result<(MongoChunk, u64)> {
+        let filter = Self::build_chunk_filter(chunk.lower.as_ref(), chunk.upper.as_ref());
+        let mut cursor = self.find(&db, &tb, filter, None).await?;
+        let mut count = 0;
+        while cursor.advance().await? {
+            let doc = cursor.deserialize_current().map_err(|e| {
+                log_error!("error deserializing {}.{} document: {}", db, tb, e);
+                e
+            })?;
+            let row_data = RowData::new(
+                db.clone(),
+                tb.clone(),
+                chunk.chunk_id,
+                RowType::Insert,
+                None,
+                Some(Self::build_after_cols(&doc)),
+            );
+            // positions of chunks are pushed as checkpoints once chunks before them are finished
+            self.base_extractor
+                .push_row(&mut extract_state, row_data, Position::None)
+                .await?;
+            count += 1;
+        }
+        extract_state.monitor.try_flush(true).await;
+        Ok((chunk, count))
+    }
+
+    // splits the collection by _id into chunks of about batch_size documents,
+    // empty if the collection is too small to split
+    async fn split_chunks(
+        &self,
+        collection: &Collection<Document>,
+        resume_key: Option<&MongoKey>,
+    ) -> anyhow::Result<Vec<MongoChunk>> {
+        let estimated_count = self.estimated_count(collection).await?;
+        let chunk_count = estimated_count
+            .div_ceil(self.batch_size.max(1) as u64)
+            .min(MAX_SPLIT_CHUNKS);
+        if chunk_count <= 1 {
+            return Ok(Vec::new());
+        }
+
+        let filter = Self::build_chunk_filter(resume_key, None);
+        let min_key = self.find_edge_key(collection, filter.clone(), 1).await?;
+        let max_key = self.find_edge_key(collection, filter.clone(), -1).await?;
+        let boundaries = match (&min_key, &max_key) {
+            (Some(MongoKey::ObjectId(min)), Some(MongoKey::ObjectId(max))) => {
+                Self::object_id_boundaries(min, max, chunk_count)
+            }
+            (Some(_), Some(_)) => Vec::new(),
+            // no documents left
+            _ => return Ok(Vec::new()),
+        };
+        // _ids not of ObjectId, or ObjectIds generated within a few seconds
+        let boundaries = if boundaries.is_empty() {
+            let pipeline = vec![
+                doc! {"$match": filter},
+                doc! {"$sample": {"size": (chunk_count * SAMPLES_PER_CHUNK) as i64}},
+                doc! {"$project": {MongoConstants::ID: 1}},
+                doc! {"$sort": {MongoConstants::ID: 1}},
+            ];
+            let mut aggregate = collection.aggregate(pipeline);
+            if let Some(criteria) = self.selection_criteria() {
+                aggregate = aggregate.selection_criteria(criteria);
+            }
+            let samples: Vec<Document> = aggregate.await?.try_collect().await?;
+            let sampled_keys = samples.iter().filter_map(MongoKey::from_doc).collect();
+            Self::pick_boundaries(sampled_keys, chunk_count)
+        } else {
+            boundaries
+        };
+
+        Ok(Self::build_chunks(resume_key.cloned(), boundaries))
+    }
+
+    fn build_chunks(resume_key: Option<MongoKey>, boundaries: Vec<MongoKey>) -> Vec<MongoChunk> {
+        let mut chunk_id_generator = SnapshotChunkIdGenerator::default();
+        let mut chunks = Vec::with_capacity(boundaries.len() + 1);
+        let mut lower = resume_key;
+        for boundary in boundaries {
+            chunks.push(MongoChunk {
+                chunk_id: chunk_id_generator.next_chunk_id(),
+                lower: lower.replace(boundary.clone()),
+                upper: Some(boundary),
+            });
+        }
+        chunks.push(MongoChunk {
+            chunk_id: chunk_id_generator.next_chunk_id(),
+            lower,
+            upper: None,
+        });
+        chunks
+    }
+
+    // ObjectIds evenly spaced by their timestamps, empty if they span less seconds than chunks
+    fn object_id_boundaries(min: &ObjectId, max: &ObjectId, chunk_count: u64) -> Vec<MongoKey> {
+        let min_secs = (min.timestamp().timestamp_millis() / 1000) as u64;
+        let max_secs = (max.timestamp().timestamp_millis() / 1000) as u64;
+        let step = max_secs.saturating_sub(min_secs) / chunk_count;
+        if step == 0 {
+            return Vec::new();
+        }
+        (1..chunk_count)
+            .map(|i| {
+                let mut bytes = [0u8; 12];
+                bytes[..4].copy_from_slice(&((min_secs + step * i) as u32).to_be_bytes());
+                MongoKey::ObjectId(ObjectId::from_bytes(bytes))
+            })
+            .collect()
+    }
+
+    // every (len / chunk_count)th of sorted sampled keys, duplicates removed
+    fn pick_boundaries(sorted_keys: Vec<MongoKey>, chunk_count: u64) -> Vec<MongoKey> {
+        let len = sorted_keys.len() as u64;
+        let mut boundaries: Vec<MongoKey> = Vec::new();
+        for i in 1..chunk_count {
+            let index = (i * len / chunk_count) as usize;
+            let Some(key) = sorted_keys.get(index) else {
+                break;
+            };
+            if boundaries.last() != Some(key) {
+                boundaries.push(key.clone());
+            }
+        }
+        boundaries
+    }
+
+    async fn find_edge_key(
+        &self,
+        collection: &Collection<Document>,
+        filter: Document,
+        order: i32,
+    ) -> anyhow::Result<Option<MongoKey>> {
+        let mut find_one = collection
+            .find_one(filter)
+            .sort(doc! {MongoConstants::ID: order})
+            .projection(doc! {MongoConstants::ID: 1});
+        if let Some(criteria) = self.selection_criteria() {
+            find_one = find_one.selection_criteria(criteria);
+        }
+        Ok(find_one.await?.as_ref().and_then(MongoKey::from_doc))
+    }
+
+    async fn find(
+        &self,
+        db: &str,
+        tb: &str,
+        filter: Document,
+        limit: Option<i64>,
+    ) -> anyhow::Result<Cursor<Document>> {
+        if let Some(cluster_time) = self.at_cluster_time {
+            // the driver pins atClusterTime only for snapshot sessions, which can not be shared by workers
+            let mut command = doc! {
+                "find": tb,
+                "filter": filter,
+                "sort": {MongoConstants::ID: 1},
+                "batchSize": self.batch_size as i64,
+                "readConcern": {"level": "snapshot", "atClusterTime": cluster_time},
+            };
+            if let Some(limit) = limit {
+                command.insert("limit", limit);
+            }
+            let mut run_cursor_command = self.mongo_client.database(db).run_cursor_command(command);
+            if let Some(criteria) = self.selection_criteria() {
+                run_cursor_command = run_cursor_command.selection_criteria(criteria);
+            }
+            return Ok(run_cursor_command.await?);
+        }
+
+        let mut find = self
+            .collection(db, tb)
+            .find(filter)
+            .sort(doc! {MongoConstants::ID: 1})
+            .batch_size(self.batch_size);
+        if let Some(limit) = limit {
+            find = find.limit(limit);
+        }
+        if self.read_concern == MongoReadConcern::Majority {
+            find = find.read_concern(ReadConcern::majority());
+        }
+        if let Some(criteria) = self.selection_criteria() {
+            find = find.selection_criteria(criteria);
+        }
+        Ok(find.await?)
+    }
+
+    async fn estimated_count(&self, collection: &Collection<Document>) -> anyhow::Result<u64> {
+        let mut count = collection.estimated_document_count();
+        if let Some(criteria) = self.selection_criteria() {
+            count = count.selection_criteria(criteria);
+        }
+        Ok(count.await?)
+    }
+
+    fn collection(&self, db: &str, tb: &str) -> Collection<Document> {
+        self.mongo_client.database(db).collection::<Document>(tb)
+    }
+
+    // None to read from the primary, as by default
+    fn selection_criteria(&self) -> Option<SelectionCriteria> {
+        let read_preference = match self.read_preference {
+            MongoReadPreference::Primary => return None,
+            MongoReadPreference::PrimaryPreferred => {
+                ReadPreference::PrimaryPreferred { options: None }
+            }
+            MongoReadPreference::Secondary => ReadPreference::Secondary { options: None },
+            MongoReadPreference::SecondaryPreferred => {
+                ReadPreference::SecondaryPreferred { options: None }
+            }
+            MongoReadPreference::Nearest => ReadPreference::Nearest { options: None },
+        };
+        Some(SelectionCriteria::ReadPreference(read_preference))
+    }
+
+    fn build_position(db: &str, tb: &str, key: &MongoKey) -> Position {
+        Position::RdbSnapshot {
+            db_type: DbType::Mongo.to_string(),
+            schema: db.to_string(),
+            tb: tb.to_string(),
+            order_key: Some(OrderKey::Single((
+                MongoConstants::ID.into(),
+                Some(key.to_string()),
+            ))),
+        }
+    }
+
+    async fn get_cluster_time(&self) -> anyhow::Result<Timestamp> {
+        let response = self
+            .mongo_client
+            .database("admin")
+            .run_command(doc! {"hello": 1})
+            .await?;
+        if let Ok(operation_time) = response.get_timestamp("operationTime") {
+            return Ok(operation_time);
+        }
+        if let Ok(cluster_time) = response
+            .get_document("$clusterTime")
+            .and_then(|doc| doc.get_timestamp("clusterTime"))
+        {
+            return Ok(cluster_time);
+        }
+        bail!(Error::ConfigError(
+            "[extractor] read_concern=snapshot requires a replica set or sharded cluster".into()
+        ))
+    }
+
+    // filter of _id in (lower, upper], None for unbounded
+    fn build_chunk_filter(lower: Option<&MongoKey>, upper: Option<&MongoKey>) -> Document {
         // use $expr to order multiple types of _id.
         // for single type of _id, this has the same performance as filter like {"_id": {"$gt": key}}.
         // ref https://www.mongodb.com/docs/manual/reference/operator/query/expr/
-        doc! {
-            "$expr": {
-                "$gt": [
-                    format!("${}", MongoConstants::ID),
-                    key.to_mongo_id(),
-                ],
-            },
+        let id = format!("${}", MongoConstants::ID);
+        let mut conditions = Vec::new();
+        if let Some(lower) = lower {
+            conditions.push(doc! {"$gt": [id.as_str(), lower.to_mongo_id()]});
+        }
+        if let Some(upper) = upper {
+            conditions.push(doc! {"$lte": [id.as_str(), upper.to_mongo_id()]});
+        }
+        match conditions.len() {
+            0 => Document::new(),
+            1 => doc! {"$expr": conditions.remove(0)},
+            _ => doc! {"$expr": {"$and": conditions}},
         }
     }
 
@@ -262,3 +654,95 @@ impl MongoSnapshotExtractor {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object_id(secs: u32) -> ObjectId {
+        let mut bytes = [0u8; 12];
+        bytes[..4].copy_from_slice(&secs.to_be_bytes());
+        ObjectId::from_bytes(bytes)
+    }
+
+    #[test]
+    fn test_object_id_boundaries() {
+        let boundaries =
+            MongoSnapshotExtractor::object_id_boundaries(&object_id(1000), &object_id(1400), 4);
+        assert_eq!(
+            boundaries,
+            vec![
+                MongoKey::ObjectId(object_id(1100)),
+                MongoKey::ObjectId(object_id(1200)),
+                MongoKey::ObjectId(object_id(1300)),
+            ]
+        );
+
+        // generated within fewer seconds than chunks
+        assert!(MongoSnapshotExtractor::object_id_boundaries(
+            &object_id(1000),
+            &object_id(1002),
+            4
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_pick_boundaries() {
+        let keys: Vec<MongoKey> = (0..10).map(MongoKey::Int32).collect();
+        assert_eq!(
+            MongoSnapshotExtractor::pick_boundaries(keys, 3),
+            vec![MongoKey::Int32(3), MongoKey::Int32(6)]
+        );
+
+        let keys = vec![MongoKey::Int32(1); 10];
+        assert_eq!(
+            MongoSnapshotExtractor::pick_boundaries(keys, 4),
+            vec![MongoKey::Int32(1)]
+        );
+    }
+
+    #[test]
+    fn test_build_chunks() {
+        let chunks = MongoSnapshotExtractor::build_chunks(
+            Some(MongoKey::Int32(0)),
+            vec![MongoKey::Int32(5), MongoKey::Int32(10)],
+        );
+        assert_eq!(
+            chunks,
+            vec![
+                MongoChunk {
+                    chunk_id: 1,
+                    lower: Some(MongoKey::Int32(0)),
+                    upper: Some(MongoKey::Int32(5)),
+                },
+                MongoChunk {
+                    chunk_id: 2,
+                    lower: Some(MongoKey::Int32(5)),
+                    upper: Some(MongoKey::Int32(10)),
+                },
+                MongoChunk {
+                    chunk_id: 3,
+                    lower: Some(MongoKey::Int32(10)),
+                    upper: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_chunk_filter() {
+        assert!(MongoSnapshotExtractor::build_chunk_filter(None, None).is_empty());
+        assert_eq!(
+            MongoSnapshotExtractor::build_chunk_filter(Some(&MongoKey::Int32(1)), None),
+            doc! {"$expr": {"$gt": ["$_id", 1]}}
+        );
+        assert_eq!(
+            MongoSnapshotExtractor::build_chunk_filter(
+                Some(&MongoKey::Int32(1)),
+                Some(&MongoKey::Int32(5))
+            ),
+            doc! {"$expr": {"$and": [{"$gt": ["$_id", 1]}, {"$lte": ["$_id", 5]}]}}
+        );
+    }
+}
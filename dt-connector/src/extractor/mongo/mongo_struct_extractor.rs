@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use mongodb::Client;
 
 use crate::{
-    extractor::base_extractor::{BaseExtractor, ExtractState},
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+    },
     meta_fetcher::mongo::mongo_struct_fetcher::MongoStructFetcher,
     Extractor,
 };
@@ -49,6 +52,10 @@ impl Extractor for MongoStructExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl MongoStructExtractor {
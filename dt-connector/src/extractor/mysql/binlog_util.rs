@@ -1,7 +1,9 @@
-use dt_common::{log_info, utils::sql_util::SqlUtil, utils::time_util::TimeUtil};
+use dt_common::{
+    log_info, meta::position::Position, utils::sql_util::SqlUtil, utils::time_util::TimeUtil,
+};
 use futures::TryStreamExt;
 use mysql_binlog_connector_rust::{binlog_client::BinlogClient, event::event_data::EventData};
-use sqlx::{MySql, Pool};
+use sqlx::{MySql, Pool, Row};
 
 pub struct BinlogUtil {}
 
@@ -74,6 +76,70 @@ impl BinlogUtil {
         }
     }
 
+    /// Current binlog file + position and executed gtid set, None if binlog is disabled.
+    pub async fn get_binlog_status(conn_pool: &Pool<MySql>) -> anyhow::Result<Option<Position>> {
+        // SHOW MASTER STATUS is replaced by SHOW BINARY LOG STATUS since 8.4
+        let row = match sqlx::query("SHOW MASTER STATUS")
+            .fetch_optional(conn_pool)
+            .await
+        {
+            Ok(row) => row,
+            Err(_) => {
+                sqlx::query("SHOW BINARY LOG STATUS")
+                    .fetch_optional(conn_pool)
+                    .await?
+            }
+        };
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let binlog_filename: String = row.try_get("File")?;
+        let binlog_position: u64 = row.try_get("Position")?;
+        let gtid_set: String = row.try_get("Executed_Gtid_Set").unwrap_or_default();
+        Ok(Some(Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename,
+            next_event_position: u32::try_from(binlog_position)?,
+            gtid_set: gtid_set.replace('\n', ""),
+            timestamp: String::new(),
+            source_id: String::new(),
+        }))
+    }
+
+    pub async fn is_gtid_mode_on(conn_pool: &Pool<MySql>) -> bool {
+        // gtid_mode does not exist in mariadb, whose gtids are not supported
+        sqlx::query("SELECT @@GLOBAL.gtid_mode AS gtid_mode")
+            .fetch_one(conn_pool)
+            .await
+            .and_then(|row| row.try_get::<String, _>("gtid_mode"))
+            .is_ok_and(|gtid_mode| gtid_mode.eq_ignore_ascii_case("ON"))
+    }
+
+    /// Whether binlogs after gtid_set are all kept, that is, gtid_purged is a subset of gtid_set.
+    pub async fn is_gtid_set_available(
+        conn_pool: &Pool<MySql>,
+        gtid_set: &str,
+    ) -> anyhow::Result<bool> {
+        let row = sqlx::query("SELECT GTID_SUBSET(@@GLOBAL.gtid_purged, ?) AS available")
+            .bind(gtid_set)
+            .fetch_one(conn_pool)
+            .await?;
+        Ok(row.try_get::<i64, _>("available")? == 1)
+    }
+
+    /// Whether all transactions in gtid_set have been executed, that is, the host is not behind.
+    pub async fn is_gtid_set_executed(
+        conn_pool: &Pool<MySql>,
+        gtid_set: &str,
+    ) -> anyhow::Result<bool> {
+        let row = sqlx::query("SELECT GTID_SUBSET(?, @@GLOBAL.gtid_executed) AS executed")
+            .bind(gtid_set)
+            .fetch_one(conn_pool)
+            .await?;
+        Ok(row.try_get::<i64, _>("executed")? == 1)
+    }
+
     async fn get_binary_logs(conn_pool: &Pool<MySql>) -> anyhow::Result<Vec<String>> {
         let mut binlogs = Vec::new();
         let sql = "SHOW BINARY LOGS";
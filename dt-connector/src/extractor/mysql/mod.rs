@@ -1,6 +1,7 @@
 pub mod binlog_util;
 pub mod mysql_cdc_extractor;
 pub mod mysql_check_extractor;
+pub mod mysql_multi_source_extractor;
 pub mod mysql_snapshot_extractor;
 pub mod mysql_snapshot_splitter;
 pub mod mysql_struct_extractor;
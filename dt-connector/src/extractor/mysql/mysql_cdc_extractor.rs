@@ -1,6 +1,7 @@
 use std::{
     cmp,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -11,11 +12,17 @@ use std::{
 use anyhow::bail;
 use async_recursion::async_recursion;
 use async_trait::async_trait;
-use sqlx::{mysql::MySqlArguments, query::Query, MySql, Pool};
+use sqlx::{
+    mysql::{MySqlArguments, MySqlConnectOptions, MySqlPoolOptions},
+    query::Query,
+    MySql, Pool,
+};
 use tokio::{sync::Mutex, time::Instant};
 
 use mysql_binlog_connector_rust::{
     binlog_client::{BinlogClient, StartPosition},
+    binlog_error::BinlogError,
+    column::column_value::ColumnValue,
     command::gtid_set::GtidSet,
     event::{
         event_data::EventData, event_header::EventHeader, query_event::QueryEvent,
@@ -26,6 +33,7 @@ use mysql_binlog_connector_rust::{
 use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
         mysql::binlog_util::BinlogUtil,
         resumer::recovery::Recovery,
     },
@@ -63,17 +71,41 @@ pub struct MysqlCdcExtractor {
     pub heartbeat_tb: String,
     pub keepalive_idle_secs: u64,
     pub keepalive_interval_secs: u64,
+    pub capture_rows_query: bool,
+    // keep the numeric form of enum / set values in binlog instead of resolving labels
+    pub enum_set_as_index: bool,
+    // other hosts of the same replication topology, e.g. replicas of url
+    pub failover_urls: Vec<String>,
     pub syncer: Arc<Mutex<Syncer>>,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+    // id of the source in multi-source tasks, tagged to positions, empty for [extractor].url
+    pub source_id: String,
+    // false if run by MysqlMultiSourceExtractor, which waits for all sources
+    pub wait_task_finish: bool,
 }
 
 struct Context {
     binlog_filename: String,
     table_map_event_map: HashMap<u64, TableMapEvent>,
+    // ids of tables filtered out, whose row events are skipped until the id is mapped again
+    filtered_table_ids: HashSet<u64>,
     gtid_set: Option<GtidSet>,
+    // gtid_set before the gtid of the current transaction was added, None if no transaction is
+    // in progress, failover resumes from it so the unfinished transaction is extracted again
+    gtid_set_before_tx: Option<String>,
+    // statement of the latest ROWS_QUERY event, attached to the row events following it
+    rows_query: Option<Arc<String>>,
+    // id of the current transaction carried by Begin / Commit: the gtid if gtid_mode is ON,
+    // otherwise {binlog_filename}:{next_event_position of BEGIN}
+    xid: String,
 }
 
 const QUERY_BEGIN: &str = "BEGIN";
+// transactions on non-transactional tables, e.g. MyISAM, end with a query event instead of xid
+const QUERY_COMMIT: &str = "COMMIT";
+const QUERY_ROLLBACK: &str = "ROLLBACK";
+const FAILOVER_MAX_ROUNDS: u32 = 12;
+const FAILOVER_RETRY_INTERVAL_MILLIS: u64 = 5000;
 
 #[async_trait]
 impl Extractor for MysqlCdcExtractor {
@@ -88,8 +120,9 @@ impl Extractor for MysqlCdcExtractor {
             .await?;
         }
 
+        let mut resumed = false;
         if let Some(recovery) = &self.recovery {
-            if let Some(position) = recovery.get_cdc_resume_position().await {
+            if let Some(position) = self.get_recovery_position(recovery).await {
                 match &position {
                     Position::MysqlCdc {
                         binlog_filename,
@@ -100,6 +133,7 @@ impl Extractor for MysqlCdcExtractor {
                         self.binlog_filename = binlog_filename.to_owned();
                         self.binlog_position = next_event_position.to_owned();
                         self.gtid_set = gtid_set.to_owned();
+                        resumed = true;
                         log_info!(
                             "cdc recovery from binlogfile:[{}], binlog_position:[{}], gtid_set:[{}]",
                             binlog_filename,
@@ -118,7 +152,8 @@ impl Extractor for MysqlCdcExtractor {
         }
 
         log_info!(
-            "MysqlCdcExtractor starts, binlog_filename: {}, binlog_position: {}, gtid_enabled: {}, gtid_set: {}, heartbeat_interval_secs: {}, heartbeat_tb: {}",
+            "MysqlCdcExtractor starts, source_id: {}, binlog_filename: {}, binlog_position: {}, gtid_enabled: {}, gtid_set: {}, heartbeat_interval_secs: {}, heartbeat_tb: {}",
+            self.source_id,
             self.binlog_filename,
             self.binlog_position,
             self.gtid_enabled,
@@ -126,7 +161,39 @@ impl Extractor for MysqlCdcExtractor {
             self.heartbeat_interval_secs,
             self.heartbeat_tb
         );
-        self.extract_internal().await?;
+        // start heartbeat
+        self.start_heartbeat(self.base_extractor.shut_down.clone())?;
+
+        let hosts: Vec<String> = std::iter::once(self.url.clone())
+            .chain(self.failover_urls.iter().cloned())
+            .collect();
+        let mut host_index = 0;
+        loop {
+            match self.extract_internal(resumed).await {
+                Ok(()) => break,
+                // only broken binlog streams are recovered, other errors are not host related
+                Err(e) if hosts.len() > 1 && e.downcast_ref::<BinlogError>().is_some() => {
+                    if self.gtid_set.is_empty() {
+                        bail! {Error::ExtractorError(format!(
+                            "binlog stream broken and failover requires gtid_mode ON, error: {}",
+                            e
+                        ))}
+                    }
+                    log_warn!(
+                        "binlog stream of host: {} broken, error: {}, start failover from gtid_set: {}",
+                        Self::host_of(&hosts[host_index]),
+                        e,
+                        self.gtid_set
+                    );
+                    self.failover(&hosts, &mut host_index).await?;
+                    resumed = true;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if !self.wait_task_finish {
+            return Ok(());
+        }
         self.base_extractor
             .wait_task_finish(&mut self.extract_state)
             .await
@@ -135,17 +202,33 @@ impl Extractor for MysqlCdcExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         self.meta_manager.close().await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl MysqlCdcExtractor {
-    async fn extract_internal(&mut self) -> anyhow::Result<()> {
-        let start_position = if self.gtid_enabled && !self.gtid_set.is_empty() {
-            StartPosition::Gtid(self.gtid_set.clone())
-        } else if !self.binlog_filename.is_empty() {
-            StartPosition::BinlogPosition(self.binlog_filename.clone(), self.binlog_position)
-        } else {
-            StartPosition::Latest {}
-        };
+    async fn get_recovery_position(
+        &self,
+        recovery: &Arc<dyn Recovery + Send + Sync>,
+    ) -> Option<Position> {
+        if self.source_id.is_empty() {
+            return recovery.get_cdc_resume_position().await;
+        }
+        // positions of all sources are recorded in a multi-source task
+        recovery
+            .get_cdc_resume_positions()
+            .await
+            .into_iter()
+            .find(|position| {
+                matches!(position, Position::MysqlCdc { source_id, .. } if *source_id == self.source_id)
+            })
+    }
+
+    async fn extract_internal(&mut self, resumed: bool) -> anyhow::Result<()> {
+        let gtid_mode_on = BinlogUtil::is_gtid_mode_on(&self.conn_pool).await;
+        let start_position = self.resolve_start_position(gtid_mode_on, resumed).await?;
 
         let url = ConnectionAuthConfig::merge_url_with_auth(&self.url, &self.connection_auth)
             .map_err(|e| {
@@ -165,22 +248,43 @@ impl MysqlCdcExtractor {
         let mut ctx = Context {
             binlog_filename: self.binlog_filename.clone(),
             table_map_event_map: HashMap::new(),
+            filtered_table_ids: HashSet::new(),
             gtid_set: None,
+            gtid_set_before_tx: None,
+            rows_query: None,
+            xid: String::new(),
         };
-        if self.gtid_enabled {
+        // track gtid_set along with binlog file + position whenever the start gtid_set is known,
+        // so the task can be resumed by either of them
+        if gtid_mode_on && !self.gtid_set.is_empty() {
             ctx.gtid_set = Some(GtidSet::new(self.gtid_set.as_str())?);
+        } else if gtid_mode_on {
+            log_warn!(
+                "gtid_set of start position unknown, only binlog file + position will be recorded"
+            );
         }
 
-        // start heartbeat
-        self.start_heartbeat(self.base_extractor.shut_down.clone())?;
-
         loop {
             if self.extract_state.time_filter.ended {
                 stream.close().await?;
                 return Ok(());
             }
 
-            let (header, data) = stream.read().await?;
+            let (header, data) = match stream.read().await {
+                Ok(event) => event,
+                Err(e) => {
+                    // positions of binlog file differ between hosts, only gtid_set is kept
+                    if let Some(gtid_set) = &ctx.gtid_set {
+                        self.gtid_set = ctx
+                            .gtid_set_before_tx
+                            .clone()
+                            .unwrap_or_else(|| gtid_set.to_string());
+                        self.binlog_filename.clear();
+                        self.binlog_position = 0;
+                    }
+                    return Err(e.into());
+                }
+            };
             match data {
                 EventData::Rotate(r) => {
                     ctx.binlog_filename = r.binlog_filename;
@@ -204,8 +308,8 @@ impl MysqlCdcExtractor {
             data
         );
 
-        // TODO, get server_id from source mysql
-        let server_id = String::new();
+        // server_id of the server which executed the transaction, kept through replication
+        let server_id = header.server_id.to_string();
         let timestamp = Position::format_timestamp_millis(header.timestamp as i64 * 1000);
         let mut gtid_set_str = String::new();
         if let Some(gtid_set) = &ctx.gtid_set {
@@ -217,41 +321,83 @@ impl MysqlCdcExtractor {
             next_event_position: header.next_event_position,
             gtid_set: gtid_set_str,
             timestamp,
+            source_id: self.source_id.clone(),
         };
 
         match data {
             EventData::Gtid(g) => {
                 if let Some(gtid_set) = ctx.gtid_set.as_mut() {
+                    if !self.failover_urls.is_empty() {
+                        ctx.gtid_set_before_tx = Some(gtid_set.to_string());
+                    }
                     gtid_set.add(&g.gtid)?;
                 }
+                ctx.xid = g.gtid;
             }
 
             EventData::TableMap(d) => {
+                if self.filter_tb(&d) {
+                    ctx.filtered_table_ids.insert(d.table_id);
+                } else {
+                    ctx.filtered_table_ids.remove(&d.table_id);
+                }
                 ctx.table_map_event_map.insert(d.table_id, d);
             }
 
+            // rows of filtered tables are counted without being checked one by one
+            EventData::WriteRows(w) if ctx.filtered_table_ids.contains(&w.table_id) => {
+                self.extract_state.record_extracted_metrics(
+                    w.rows.len() as u64,
+                    size_of_val(w.rows.as_slice()) as u64,
+                );
+            }
+            EventData::UpdateRows(u) if ctx.filtered_table_ids.contains(&u.table_id) => {
+                self.extract_state.record_extracted_metrics(
+                    u.rows.len() as u64,
+                    size_of_val(u.rows.as_slice()) as u64,
+                );
+            }
+            EventData::DeleteRows(d) if ctx.filtered_table_ids.contains(&d.table_id) => {
+                self.extract_state.record_extracted_metrics(
+                    d.rows.len() as u64,
+                    size_of_val(d.rows.as_slice()) as u64,
+                );
+            }
+
+            // binlog_transaction_compression=ON (8.0.20+), the whole transaction except the gtid
+            // event is compressed into one payload, which is decompressed by the binlog client
             EventData::TransactionPayload(event) => {
+                log_debug!(
+                    "transaction payload decompressed, binlog: {}:{}, inner events: {}",
+                    ctx.binlog_filename,
+                    header.next_event_position,
+                    event.uncompressed_events.len()
+                );
                 for (mut inner_header, data) in event.uncompressed_events {
-                    // headers of uncompressed events have no next_event_position,
-                    // use header of TransactionPayload instead
+                    // headers of uncompressed events have no next_event_position, use header of
+                    // TransactionPayload instead, so the transaction is resumed from its end
                     inner_header.next_event_position = header.next_event_position;
+                    if inner_header.timestamp == 0 {
+                        inner_header.timestamp = header.timestamp;
+                    }
                     self.parse_events(inner_header, data, ctx).await?;
                 }
             }
 
             EventData::WriteRows(mut w) => {
+                let table_map_event = ctx.table_map_event_map.get(&w.table_id).unwrap();
+                if self.filter_event(table_map_event, RowType::Insert) {
+                    self.extract_state.record_extracted_metrics(
+                        w.rows.len() as u64,
+                        size_of_val(w.rows.as_slice()) as u64,
+                    );
+                    return Ok(());
+                }
                 for event in w.rows.iter_mut() {
-                    let table_map_event = ctx.table_map_event_map.get(&w.table_id).unwrap();
-                    if self.filter_event(table_map_event, RowType::Insert) {
-                        self.extract_state
-                            .record_extracted_metrics(1, size_of_val(event) as u64);
-                        continue;
-                    }
-
                     let col_values = self
                         .parse_row_data(table_map_event, &w.included_columns, event)
                         .await?;
-                    let row_data = RowData::new(
+                    let mut row_data = RowData::new(
                         table_map_event.database_name.clone(),
                         table_map_event.table_name.clone(),
                         0,
@@ -259,26 +405,28 @@ impl MysqlCdcExtractor {
                         None,
                         Some(col_values),
                     );
+                    row_data.query = ctx.rows_query.clone();
                     self.push_row_to_buf(row_data, position.clone()).await?;
                 }
             }
 
             EventData::UpdateRows(mut u) => {
+                let table_map_event = ctx.table_map_event_map.get(&u.table_id).unwrap();
+                if self.filter_event(table_map_event, RowType::Update) {
+                    self.extract_state.record_extracted_metrics(
+                        u.rows.len() as u64,
+                        size_of_val(u.rows.as_slice()) as u64,
+                    );
+                    return Ok(());
+                }
                 for event in u.rows.iter_mut() {
-                    let table_map_event = ctx.table_map_event_map.get(&u.table_id).unwrap();
-                    if self.filter_event(table_map_event, RowType::Update) {
-                        self.extract_state
-                            .record_extracted_metrics(1, size_of_val(event) as u64);
-                        continue;
-                    }
-
                     let col_values_before = self
                         .parse_row_data(table_map_event, &u.included_columns_before, &mut event.0)
                         .await?;
                     let col_values_after = self
                         .parse_row_data(table_map_event, &u.included_columns_after, &mut event.1)
                         .await?;
-                    let row_data = RowData::new(
+                    let mut row_data = RowData::new(
                         table_map_event.database_name.clone(),
                         table_map_event.table_name.clone(),
                         0,
@@ -286,23 +434,25 @@ impl MysqlCdcExtractor {
                         Some(col_values_before),
                         Some(col_values_after),
                     );
+                    row_data.query = ctx.rows_query.clone();
                     self.push_row_to_buf(row_data, position.clone()).await?;
                 }
             }
 
             EventData::DeleteRows(mut d) => {
+                let table_map_event = ctx.table_map_event_map.get(&d.table_id).unwrap();
+                if self.filter_event(table_map_event, RowType::Delete) {
+                    self.extract_state.record_extracted_metrics(
+                        d.rows.len() as u64,
+                        size_of_val(d.rows.as_slice()) as u64,
+                    );
+                    return Ok(());
+                }
                 for event in d.rows.iter_mut() {
-                    let table_map_event = ctx.table_map_event_map.get(&d.table_id).unwrap();
-                    if self.filter_event(table_map_event, RowType::Delete) {
-                        self.extract_state
-                            .record_extracted_metrics(1, size_of_val(event) as u64);
-                        continue;
-                    }
-
                     let col_values = self
                         .parse_row_data(table_map_event, &d.included_columns, event)
                         .await?;
-                    let row_data = RowData::new(
+                    let mut row_data = RowData::new(
                         table_map_event.database_name.clone(),
                         table_map_event.table_name.clone(),
                         0,
@@ -310,29 +460,52 @@ impl MysqlCdcExtractor {
                         Some(col_values),
                         None,
                     );
+                    row_data.query = ctx.rows_query.clone();
                     self.push_row_to_buf(row_data, position.clone()).await?;
                 }
             }
 
-            EventData::Query(query) => {
-                if query.query == QUERY_BEGIN {
-                    BaseExtractor::update_time_filter(
-                        &mut self.extract_state.time_filter,
-                        header.timestamp,
-                        &position,
-                    );
+            EventData::RowsQuery(rows_query) => {
+                if self.capture_rows_query {
+                    ctx.rows_query = Some(Arc::new(rows_query.query));
                 }
+            }
+
+            EventData::Query(query) => {
+                ctx.rows_query = None;
+                match query.query.as_str() {
+                    QUERY_BEGIN => {
+                        BaseExtractor::update_time_filter(
+                            &mut self.extract_state.time_filter,
+                            header.timestamp,
+                            &position,
+                        );
+                        if ctx.xid.is_empty() {
+                            ctx.xid =
+                                format!("{}:{}", ctx.binlog_filename, header.next_event_position);
+                        }
+                        let begin = DtData::Begin {
+                            xid: ctx.xid.clone(),
+                        };
+                        self.base_extractor
+                            .push_dt_data(&mut self.extract_state, begin, position.clone())
+                            .await?;
+                    }
+
+                    QUERY_COMMIT | QUERY_ROLLBACK => self.push_commit(ctx, position).await?,
 
-                self.handle_query_event(query, position.clone()).await?;
+                    // ddl / dcl, each is a transaction by itself
+                    _ => {
+                        ctx.xid.clear();
+                        self.handle_query_event(query, position.clone()).await?;
+                        ctx.gtid_set_before_tx = None;
+                    }
+                }
             }
 
-            EventData::Xid(xid) => {
-                let commit = DtData::Commit {
-                    xid: xid.xid.to_string(),
-                };
-                self.base_extractor
-                    .push_dt_data(&mut self.extract_state, commit, position.clone())
-                    .await?;
+            EventData::Xid(_) => {
+                ctx.rows_query = None;
+                self.push_commit(ctx, position).await?;
             }
 
             _ => {}
@@ -341,11 +514,157 @@ impl MysqlCdcExtractor {
         Ok(())
     }
 
+    async fn push_commit(&mut self, ctx: &mut Context, position: Position) -> anyhow::Result<()> {
+        ctx.gtid_set_before_tx = None;
+        let commit = DtData::Commit {
+            xid: std::mem::take(&mut ctx.xid),
+        };
+        self.base_extractor
+            .push_dt_data(&mut self.extract_state, commit, position)
+            .await
+    }
+
+    async fn resolve_start_position(
+        &mut self,
+        gtid_mode_on: bool,
+        resumed: bool,
+    ) -> anyhow::Result<StartPosition> {
+        if self.gtid_enabled && !gtid_mode_on {
+            if self.binlog_filename.is_empty() {
+                bail! {Error::ExtractorError(
+                    "gtid_enabled is set but gtid_mode is not ON in source mysql".into(),
+                )}
+            }
+            log_warn!("gtid_mode is not ON in source mysql, start from binlog file + position");
+        }
+
+        // start from latest, fetch both file + position and gtid_set of the same point
+        if self.binlog_filename.is_empty() && self.gtid_set.is_empty() {
+            if let Some(Position::MysqlCdc {
+                binlog_filename,
+                next_event_position,
+                gtid_set,
+                ..
+            }) = BinlogUtil::get_binlog_status(&self.conn_pool).await?
+            {
+                self.binlog_filename = binlog_filename;
+                self.binlog_position = next_event_position;
+                self.gtid_set = gtid_set;
+            }
+        }
+
+        // gtid is preferred when resuming since it survives binlog file changes, e.g. failover
+        let use_gtid = gtid_mode_on
+            && !self.gtid_set.is_empty()
+            && (self.gtid_enabled || resumed || self.binlog_filename.is_empty());
+        if use_gtid {
+            if BinlogUtil::is_gtid_set_available(&self.conn_pool, &self.gtid_set).await? {
+                return Ok(StartPosition::Gtid(self.gtid_set.clone()));
+            }
+            if self.binlog_filename.is_empty() {
+                bail! {Error::ExtractorError(format!(
+                    "binlogs after gtid_set: {} have been purged in source mysql",
+                    self.gtid_set
+                ))}
+            }
+            log_warn!(
+                "binlogs after gtid_set: {} have been purged in source mysql, start from binlog file + position",
+                self.gtid_set
+            );
+        }
+
+        if !self.binlog_filename.is_empty() {
+            Ok(StartPosition::BinlogPosition(
+                self.binlog_filename.clone(),
+                self.binlog_position,
+            ))
+        } else {
+            Ok(StartPosition::Latest {})
+        }
+    }
+
+    /// Switches to the next available host in turns, the failed host included since it may recover.
+    async fn failover(&mut self, hosts: &[String], host_index: &mut usize) -> anyhow::Result<()> {
+        for round in 1..=FAILOVER_MAX_ROUNDS {
+            for i in 1..=hosts.len() {
+                let index = (*host_index + i) % hosts.len();
+                let host = Self::host_of(&hosts[index]);
+                match self.check_failover_host(&hosts[index]).await {
+                    Ok(conn_pool) => {
+                        log_info!("failover to host: {}, gtid_set: {}", host, self.gtid_set);
+                        self.url = hosts[index].clone();
+                        // binlog of the new host is parsed with metadata of the new host
+                        self.meta_manager.meta_fetcher.conn_pool = conn_pool.clone();
+                        self.conn_pool = conn_pool;
+                        *host_index = index;
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        log_warn!(
+                            "failover round: {}, host: {} not available, error: {}",
+                            round,
+                            host,
+                            e
+                        );
+                    }
+                }
+            }
+            TimeUtil::sleep_millis(FAILOVER_RETRY_INTERVAL_MILLIS).await;
+        }
+
+        bail! {Error::ExtractorError(format!(
+            "failover failed, no host available for gtid_set: {}",
+            self.gtid_set
+        ))}
+    }
+
+    /// A host can be switched to if it keeps binlogs after gtid_set and has executed all of
+    /// gtid_set, so no transaction is missed or extracted from a host behind the previous one.
+    async fn check_failover_host(&self, url: &str) -> anyhow::Result<Pool<MySql>> {
+        let final_url = ConnectionAuthConfig::merge_url_with_auth(url, &self.connection_auth)?;
+        let mut conn_options = MySqlConnectOptions::from_str(&final_url)?;
+        if let Some(ssl) = self.connection_auth.ssl_config() {
+            conn_options = ssl.apply_mysql(conn_options);
+        }
+        let conn_pool = MySqlPoolOptions::new()
+            .max_connections(self.conn_pool.options().get_max_connections())
+            .acquire_timeout(Duration::from_secs(15))
+            .idle_timeout(Some(Duration::from_secs(5 * 60)))
+            .connect_with(conn_options)
+            .await?;
+
+        let error = if !BinlogUtil::is_gtid_mode_on(&conn_pool).await {
+            "gtid_mode is not ON"
+        } else if !BinlogUtil::is_gtid_set_available(&conn_pool, &self.gtid_set).await? {
+            "binlogs after gtid_set have been purged"
+        } else if !BinlogUtil::is_gtid_set_executed(&conn_pool, &self.gtid_set).await? {
+            "gtid_set not executed yet"
+        } else {
+            return Ok(conn_pool);
+        };
+        conn_pool.close().await;
+        bail! {Error::ExtractorError(error.into())}
+    }
+
+    // url without user and password, for logs
+    fn host_of(url: &str) -> String {
+        url::Url::parse(url)
+            .map(|u| {
+                format!(
+                    "{}:{}",
+                    u.host_str().unwrap_or_default(),
+                    u.port().unwrap_or_default()
+                )
+            })
+            .unwrap_or_default()
+    }
+
     async fn push_row_to_buf(
         &mut self,
-        row_data: RowData,
+        mut row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
+        row_data.meta_version = self.meta_manager.meta_version;
         self.base_extractor
             .push_row(&mut self.extract_state, row_data, position)
             .await
@@ -386,8 +705,11 @@ impl MysqlCdcExtractor {
             }
 
             let col_type = tb_meta.get_col_type(col)?;
-            let raw_value = event.column_values.remove(i);
-            let value = MysqlColValueConvertor::from_binlog(col_type, raw_value)?;
+            let value = match event.column_values.remove(i) {
+                ColumnValue::Enum(v) if self.enum_set_as_index => ColValue::Enum(v),
+                ColumnValue::Set(v) if self.enum_set_as_index => ColValue::Set(v),
+                raw_value => MysqlColValueConvertor::from_binlog(col_type, raw_value)?,
+            };
             data.insert(col.clone(), value);
         }
         Ok(data)
@@ -404,10 +726,6 @@ impl MysqlCdcExtractor {
             return Ok(());
         }
 
-        if query.query == QUERY_BEGIN {
-            return Ok(());
-        }
-
         if !self.filter.filter_all_dcl() {
             if let Ok(Some(dcl_data)) = self
                 .base_extractor
@@ -424,11 +742,11 @@ impl MysqlCdcExtractor {
         }
 
         if !self.filter.filter_all_ddl() {
-            if let Ok(Some(ddl_data)) = self
+            let ddls = self
                 .base_extractor
                 .parse_ddl(&DbType::Mysql, &query.schema, &query.query)
-                .await
-            {
+                .await?;
+            for ddl_data in ddls {
                 for sub_ddl_data in ddl_data.clone().split_to_multi() {
                     let (db, tb) = sub_ddl_data.get_schema_tb();
                     // invalidate metadata cache
@@ -447,14 +765,19 @@ impl MysqlCdcExtractor {
                 if let Some(meta_center) = &mut self.meta_manager.meta_center {
                     meta_center.sync_from_ddl(&ddl_data).await?;
                 }
-
-                return Ok(());
             }
         }
 
         Ok(())
     }
 
+    // whether rows of the table are filtered regardless of their row types
+    fn filter_tb(&self, table_map_event: &TableMapEvent) -> bool {
+        let db = &table_map_event.database_name;
+        let tb = &table_map_event.table_name;
+        self.filter.filter_tb(db, tb) && !self.extract_state.is_data_marker_info(db, tb)
+    }
+
     fn filter_event(&mut self, table_map_event: &TableMapEvent, row_type: RowType) -> bool {
         let db = &table_map_event.database_name;
         let tb = &table_map_event.table_name;
@@ -477,8 +800,9 @@ impl MysqlCdcExtractor {
 
         self.filter.add_ignore_tb(&db_tb[0], &db_tb[1]);
 
-        let (server_id, heartbeat_interval_secs, syncer, conn_pool) = (
+        let (server_id, source_id, heartbeat_interval_secs, syncer, conn_pool) = (
             self.server_id,
+            self.source_id.clone(),
             self.heartbeat_interval_secs,
             self.syncer.clone(),
             self.conn_pool.clone(),
@@ -488,9 +812,17 @@ impl MysqlCdcExtractor {
             let mut start_time = Instant::now();
             while !shut_down.load(Ordering::Acquire) {
                 if start_time.elapsed().as_secs() >= heartbeat_interval_secs {
-                    Self::heartbeat(server_id, &db_tb[0], &db_tb[1], &syncer, &conn_pool)
+                    if syncer
+                        .lock()
+                        .await
+                        .should_heartbeat(heartbeat_interval_secs)
+                    {
+                        Self::heartbeat(
+                            server_id, &source_id, &db_tb[0], &db_tb[1], &syncer, &conn_pool,
+                        )
                         .await
                         .unwrap();
+                    }
                     start_time = Instant::now();
                 }
                 TimeUtil::sleep_millis(1000 * heartbeat_interval_secs).await;
@@ -502,44 +834,38 @@ impl MysqlCdcExtractor {
 
     async fn heartbeat(
         server_id: u64,
+        source_id: &str,
         db: &str,
         tb: &str,
         syncer: &Arc<Mutex<Syncer>>,
         conn_pool: &Pool<MySql>,
     ) -> anyhow::Result<()> {
-        let (received_binlog_filename, received_next_event_position, received_timestamp) =
-            if let Position::MysqlCdc {
-                binlog_filename,
-                next_event_position,
-                timestamp,
-                ..
-            } = &syncer.lock().await.received_position
-            {
-                (
-                    binlog_filename.to_owned(),
-                    *next_event_position,
-                    timestamp.to_owned(),
-                )
-            } else {
-                (String::new(), 0, String::new())
-            };
-
-        let (flushed_binlog_filename, flushed_next_event_position, flushed_timestamp) =
-            if let Position::MysqlCdc {
+        // positions in syncer may be of other sources in multi-source tasks
+        let is_of_source = |position: &Position| matches!(position, Position::MysqlCdc { source_id: id, .. } if id == source_id);
+        let position_of_source = |position: &Position| match position {
+            Position::MysqlCdc {
                 binlog_filename,
                 next_event_position,
                 timestamp,
                 ..
-            } = &syncer.lock().await.committed_position
-            {
-                (
-                    binlog_filename.to_owned(),
-                    *next_event_position,
-                    timestamp.to_owned(),
-                )
-            } else {
-                (String::new(), 0, String::new())
-            };
+            } if is_of_source(position) => (
+                binlog_filename.to_owned(),
+                *next_event_position,
+                timestamp.to_owned(),
+            ),
+            _ => (String::new(), 0, String::new()),
+        };
+        let (received_binlog_filename, received_next_event_position, received_timestamp) =
+            position_of_source(&syncer.lock().await.received_position);
+        let (flushed_binlog_filename, flushed_next_event_position, flushed_timestamp) = {
+            let syncer = syncer.lock().await;
+            let committed_position = syncer
+                .committed_positions
+                .values()
+                .find(|position| is_of_source(position))
+                .unwrap_or(&syncer.committed_position);
+            position_of_source(committed_position)
+        };
 
         // CREATE TABLE test_db_1.ape_dts_heartbeat(
         //     server_id INT UNSIGNED,
@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use dt_common::log_info;
 use dt_common::meta::{
     adaptor::mysql_col_value_convertor::MysqlColValueConvertor,
     col_value::ColValue,
@@ -9,15 +8,17 @@ use dt_common::meta::{
     row_type::RowType,
 };
 use dt_common::rdb_filter::RdbFilter;
+use dt_common::{log_info, log_warn};
 use futures::TryStreamExt;
 use sqlx::{MySql, Pool};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     checker::check_log::CheckLog,
     extractor::{
         base_check_extractor::BaseCheckExtractor,
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
     },
     rdb_query_builder::RdbQueryBuilder,
     BatchCheckExtractor, Extractor,
@@ -32,6 +33,9 @@ pub struct MysqlCheckExtractor {
     pub check_log_dir: String,
     pub batch_size: usize,
     pub replay_diff_as_update: bool,
+    // set to skip rows changed in target since the check
+    pub target_conn_pool: Option<Pool<MySql>>,
+    pub target_meta_manager: Option<MysqlMetaManager>,
 }
 
 #[async_trait]
@@ -51,6 +55,10 @@ impl Extractor for MysqlCheckExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 #[async_trait]
@@ -59,6 +67,10 @@ impl BatchCheckExtractor for MysqlCheckExtractor {
         let db = &check_logs[0].schema;
         let tb = &check_logs[0].tb;
         let is_diff = !check_logs[0].diff_col_values.is_empty();
+        let id_cols = BaseCheckExtractor::get_id_cols(check_logs);
+        let changed_ids = self
+            .get_changed_ids_since_check(check_logs, &id_cols)
+            .await?;
         let tb_meta = self.meta_manager.get_tb_meta(db, tb).await?;
         let check_row_data_items = Self::build_check_row_data_items(check_logs, tb_meta)?;
 
@@ -75,6 +87,17 @@ impl BatchCheckExtractor for MysqlCheckExtractor {
         let mut rows = query.fetch(&self.conn_pool);
         while let Some(row) = rows.try_next().await? {
             let mut row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols, None);
+            if !changed_ids.is_empty()
+                && changed_ids.contains(&BaseCheckExtractor::row_id_key(&row_data, &id_cols))
+            {
+                log_warn!(
+                    "skip revising row changed in target since the check, schema: {}, tb: {}, row: {}",
+                    db,
+                    tb,
+                    row_data
+                );
+                continue;
+            }
 
             if is_diff && self.replay_diff_as_update {
                 row_data.row_type = RowType::Update;
@@ -90,6 +113,50 @@ impl BatchCheckExtractor for MysqlCheckExtractor {
 }
 
 impl MysqlCheckExtractor {
+    async fn get_changed_ids_since_check(
+        &mut self,
+        check_logs: &[CheckLog],
+        id_cols: &[String],
+    ) -> anyhow::Result<HashSet<String>> {
+        let (Some(conn_pool), Some(meta_manager)) =
+            (&self.target_conn_pool, &mut self.target_meta_manager)
+        else {
+            return Ok(HashSet::new());
+        };
+
+        let db = check_logs[0]
+            .target_schema
+            .as_ref()
+            .unwrap_or(&check_logs[0].schema);
+        let tb = check_logs[0]
+            .target_tb
+            .as_ref()
+            .unwrap_or(&check_logs[0].tb);
+        let tb_meta = meta_manager.get_tb_meta(db, tb).await?;
+        let check_row_data_items = Self::build_check_row_data_items(check_logs, tb_meta)?;
+
+        let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, None);
+        let batch_refs: Vec<&RowData> = check_row_data_items.iter().collect();
+        let query_info = if check_logs.len() == 1 {
+            query_builder.get_select_query(&check_row_data_items[0])?
+        } else {
+            query_builder.get_batch_select_query(&batch_refs, 0, batch_refs.len())?
+        };
+        let query = query_builder.create_mysql_query(&query_info)?;
+
+        let mut target_rows = HashMap::new();
+        let mut rows = query.fetch(conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let row_data = RowData::from_mysql_row(&row, tb_meta, &None, None);
+            target_rows.insert(BaseCheckExtractor::row_id_key(&row_data, id_cols), row_data);
+        }
+        Ok(BaseCheckExtractor::get_changed_ids_since_check(
+            check_logs,
+            id_cols,
+            &target_rows,
+        ))
+    }
+
     fn build_check_row_data_items(
         check_logs: &[CheckLog],
         tb_meta: &MysqlTbMeta,
@@ -0,0 +1,88 @@
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use tokio::task::JoinSet;
+
+use crate::{
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+        mysql::mysql_cdc_extractor::MysqlCdcExtractor,
+    },
+    Extractor,
+};
+use dt_common::{error::Error, log_info};
+
+/// Extracts binlogs of several mysql instances into the same pipeline, e.g. to consolidate
+/// shards of a business into one target.
+///
+/// Each source is extracted by its own MysqlCdcExtractor, positions are tagged with the source id
+/// so sources are resumed separately. The task fails if any source fails.
+pub struct MysqlMultiSourceExtractor {
+    pub base_extractor: BaseExtractor,
+    pub extract_state: ExtractState,
+    // built with wait_task_finish=false
+    pub extractors: Vec<MysqlCdcExtractor>,
+}
+
+#[async_trait]
+impl Extractor for MysqlMultiSourceExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "MysqlMultiSourceExtractor starts, sources: {}",
+            self.extractors
+                .iter()
+                .map(|i| Self::source_name(&i.source_id))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        let mut join_set = JoinSet::new();
+        for mut extractor in self.extractors.drain(..) {
+            join_set.spawn(async move {
+                let source = Self::source_name(&extractor.source_id).to_string();
+                let result = extractor
+                    .extract()
+                    .await
+                    .with_context(|| format!("mysql source [{}] failed", source));
+                let close_result = extractor.close().await;
+                result.and(close_result)
+            });
+        }
+
+        while let Some(result) = join_set.join_next().await {
+            let err = match result {
+                Ok(Ok(())) => continue,
+                Ok(Err(err)) => err,
+                Err(err) => {
+                    Error::ExtractorError(format!("mysql source task failed: {err}")).into()
+                }
+            };
+            self.base_extractor
+                .shut_down
+                .store(true, std::sync::atomic::Ordering::Release);
+            bail!(err);
+        }
+
+        self.base_extractor
+            .wait_task_finish(&mut self.extract_state)
+            .await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
+}
+
+impl MysqlMultiSourceExtractor {
+    fn source_name(source_id: &str) -> &str {
+        if source_id.is_empty() {
+            "default"
+        } else {
+            source_id
+        }
+    }
+}
@@ -6,14 +6,15 @@ use std::{
 use anyhow::{anyhow, bail};
 use async_trait::async_trait;
 use futures::TryStreamExt;
-use sqlx::{MySql, Pool, Row};
+use sqlx::{mysql::MySqlRow, MySql, Pool, Row};
 
 use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
         base_splitter::SnapshotChunk,
         estimated_sample_limit,
-        mysql::mysql_snapshot_splitter::MySqlSnapshotSplitter,
+        extractor_progress::ExtractorProgress,
+        mysql::{binlog_util::BinlogUtil, mysql_snapshot_splitter::MySqlSnapshotSplitter},
         rdb_snapshot_extract_statement::{OrderKeyPredicateType, RdbSnapshotExtractStatement},
         resumer::recovery::Recovery,
         snapshot_chunk_id_generator::SnapshotChunkIdGenerator,
@@ -22,9 +23,9 @@ use crate::{
     },
     Extractor,
 };
-use dt_common::utils::sql_util::MYSQL_ESCAPE;
+use dt_common::utils::sql_util::SqlUtil;
 use dt_common::{
-    config::config_enums::{DbType, RdbParallelType},
+    config::config_enums::{DbType, RdbParallelType, SnapshotTableOrder},
     log_debug, log_info,
     meta::{
         adaptor::{mysql_col_value_convertor::MysqlColValueConvertor, sqlx_ext::SqlxMysqlExt},
@@ -40,7 +41,10 @@ use dt_common::{
     },
     quote_mysql,
     rdb_filter::RdbFilter,
-    utils::serialize_util::SerializeUtil,
+    utils::{
+        query_timeout::{QueryTimeout, EXTRACTOR_STAGE},
+        serialize_util::SerializeUtil,
+    },
 };
 
 use quote_mysql as quote;
@@ -50,6 +54,7 @@ pub struct MysqlSnapshotExtractor {
     pub extract_state: ExtractState,
     pub parallel_size: usize,
     pub db_tbs: HashMap<String, Vec<String>>,
+    pub table_order: SnapshotTableOrder,
 }
 
 #[derive(Clone)]
@@ -62,7 +67,12 @@ pub struct MysqlSnapshotShared {
     pub batch_size: usize,
     pub parallel_type: RdbParallelType,
     pub sample_rate: Option<u8>,
+    // fixed rows extracted for each table, takes precedence over sample_rate
+    pub sample_rows: Option<usize>,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+    pub snapshot_watermark: bool,
+    // emit enum / set values as numeric indexes instead of labels
+    pub enum_set_as_index: bool,
 }
 
 enum MysqlSnapshotWork {
@@ -116,12 +126,13 @@ impl Extractor for MysqlSnapshotExtractor {
             bail!("parallel_size must be greater than 0");
         }
 
-        let tables = self.collect_tables();
+        let tables = self.collect_tables().await?;
         log_info!(
-            "MysqlSnapshotExtractor starts, tables: {}, parallel_type: {:?}, parallel_size: {}",
+            "MysqlSnapshotExtractor starts, tables: {}, parallel_type: {:?}, parallel_size: {}, table_order: {}",
             tables.len(),
             self.shared.parallel_type,
-            self.parallel_size
+            self.parallel_size,
+            self.table_order
         );
 
         let state = MysqlSnapshotDispatchState {
@@ -151,10 +162,14 @@ impl Extractor for MysqlSnapshotExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl MysqlSnapshotExtractor {
-    fn collect_tables(&self) -> Vec<SnapshotTableId> {
+    async fn collect_tables(&self) -> anyhow::Result<Vec<SnapshotTableId>> {
         let mut tables = Vec::new();
         for (db, tbs) in &self.db_tbs {
             for tb in tbs {
@@ -164,7 +179,37 @@ impl MysqlSnapshotExtractor {
                 });
             }
         }
-        tables
+
+        if self.table_order != SnapshotTableOrder::None {
+            let sizes = self.estimate_tb_sizes().await?;
+            SnapshotDispatcher::order_tables(&mut tables, &sizes, &self.table_order);
+        }
+        Ok(tables)
+    }
+
+    // estimated on-disk size of each table from the catalog statistics
+    async fn estimate_tb_sizes(&self) -> anyhow::Result<HashMap<SnapshotTableId, u64>> {
+        let mut sizes = HashMap::new();
+        if self.db_tbs.is_empty() {
+            return Ok(sizes);
+        }
+
+        let sql = format!(
+            "SELECT table_schema, table_name, CAST(COALESCE(data_length, 0) + COALESCE(index_length, 0) AS UNSIGNED) FROM information_schema.tables WHERE table_schema IN ({})",
+            self.db_tbs
+                .keys()
+                .map(|db| format!("'{}'", db.replace('\'', "''")))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let mut rows = sqlx::query(&sql).fetch(&self.shared.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let schema = SqlUtil::try_get_mysql_string(&row, 0)?;
+            let tb = SqlUtil::try_get_mysql_string(&row, 1)?;
+            let size: u64 = row.try_get(2)?;
+            sizes.insert(SnapshotTableId { schema, tb }, size);
+        }
+        Ok(sizes)
     }
 
     async fn next_work(
@@ -174,8 +219,23 @@ impl MysqlSnapshotExtractor {
             return Ok((state, Some(work)));
         }
 
-        let Some(table_id) = state.pending_tables.pop_front() else {
-            return Ok((state, None));
+        let table_id = loop {
+            let Some(table_id) = state.pending_tables.pop_front() else {
+                return Ok((state, None));
+            };
+            // the table may be removed from the running task after it was scheduled
+            if !state
+                .shared
+                .filter
+                .is_tb_removed(&table_id.schema, &table_id.tb)
+            {
+                break table_id;
+            }
+            log_info!(
+                "schema: {}, tb: {}, removed from task, skip snapshot",
+                table_id.schema,
+                table_id.tb
+            );
         };
 
         let work = state.prepare_table_work(table_id).await?;
@@ -422,12 +482,24 @@ impl MysqlSnapshotExtractor {
             .get_ignore_cols(&tb_meta.basic.schema, &tb_meta.basic.tb)
             .cloned();
         let mut rows = query.fetch(&shared.conn_pool);
-        while let Some(row) = rows.try_next().await? {
+        let sql = if matches!(start_value, ColValue::None) {
+            &sql_le
+        } else {
+            &sql_range
+        };
+        while let Some(row) = rows.try_next().await.map_err(|error| {
+            QueryTimeout::map_err(
+                error,
+                EXTRACTOR_STAGE,
+                sql,
+                &tb_meta.basic.schema,
+                &tb_meta.basic.tb,
+            )
+        })? {
             extracted_cnt += 1;
             partition_col_value =
                 MysqlColValueConvertor::from_query(&row, &partition_col, &partition_col_type)?;
-            let row_data =
-                RowData::from_mysql_row(&row, &tb_meta, &ignore_cols.as_ref(), Some(chunk_id));
+            let row_data = shared.build_row_data(&row, &tb_meta, &ignore_cols.as_ref(), chunk_id);
             shared
                 .base_extractor
                 .push_row(&mut extract_state, row_data, Position::None)
@@ -465,6 +537,8 @@ struct MysqlActiveTable {
     _monitor_guard: TableMonitorGuard,
     tb_meta: MysqlTbMeta,
     extracted_count: u64,
+    // cdc position fetched before the table is extracted, set if snapshot_watermark is enabled
+    cdc_position: Option<Position>,
     mode: MysqlActiveTableMode,
 }
 
@@ -502,6 +576,7 @@ impl MysqlSnapshotDispatchState {
         }
         let schema = table_id.schema.clone();
         let tb = table_id.tb.clone();
+        let cdc_position = active_table.cdc_position.take().map(Box::new);
         log_info!(
             "end extracting data from {}.{}, all count: {}",
             quote!(&table_id.schema),
@@ -517,6 +592,8 @@ impl MysqlSnapshotDispatchState {
                     db_type: DbType::Mysql.to_string(),
                     schema: schema.clone(),
                     tb: tb.clone(),
+                    row_count: cdc_position.as_ref().map(|_| active_table.extracted_count),
+                    cdc_position,
                 },
             )
             .await?;
@@ -553,6 +630,12 @@ impl MysqlSnapshotDispatchState {
             .to_owned();
         table_ctx.sample_limit = table_ctx.estimate_sample_limit(&tb_meta).await?;
         let active_mode = table_ctx.prepare_active_mode(&tb_meta).await?;
+        // fetched before any row is read, so streaming from it covers all changes during the snapshot
+        let cdc_position = if self.shared.snapshot_watermark {
+            Some(self.shared.fetch_cdc_position().await?)
+        } else {
+            None
+        };
         log_debug!(
             "prepared extract mode for {}.{}",
             quote!(&table_id.schema),
@@ -567,6 +650,7 @@ impl MysqlSnapshotDispatchState {
                 _monitor_guard: monitor_guard,
                 tb_meta: tb_meta.clone(),
                 extracted_count: 0,
+                cdc_position,
                 mode: active_mode,
             },
         );
@@ -701,6 +785,38 @@ impl MysqlSnapshotDispatchState {
     }
 }
 
+impl MysqlSnapshotShared {
+    fn build_row_data(
+        &self,
+        row: &MySqlRow,
+        tb_meta: &MysqlTbMeta,
+        ignore_cols: &Option<&HashSet<String>>,
+        chunk_id: u64,
+    ) -> RowData {
+        let mut row_data = RowData::from_mysql_row(row, tb_meta, ignore_cols, Some(chunk_id));
+        if self.enum_set_as_index {
+            if let Some(after) = row_data.after.as_mut() {
+                for (col, value) in after.iter_mut() {
+                    if let Some(col_type) = tb_meta.col_type_map.get(col) {
+                        *value = MysqlColValueConvertor::to_enum_set_index(
+                            col_type,
+                            std::mem::replace(value, ColValue::None),
+                        );
+                    }
+                }
+            }
+        }
+        row_data
+    }
+
+    async fn fetch_cdc_position(&self) -> anyhow::Result<Position> {
+        let Some(position) = BinlogUtil::get_binlog_status(&self.conn_pool).await? else {
+            bail!("failed to fetch binlog position for snapshot watermark, binlog may be disabled");
+        };
+        Ok(position)
+    }
+}
+
 #[derive(Clone)]
 struct MysqlTableCtx {
     shared: MysqlSnapshotShared,
@@ -935,7 +1051,9 @@ impl MysqlTableCtx {
         let mut chunk_id_generator = SnapshotChunkIdGenerator::new(self.shared.batch_size);
         while let Some(row) = rows.try_next().await? {
             let row_chunk_id = chunk_id_generator.next_row_chunk_id();
-            let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols, Some(row_chunk_id));
+            let row_data = self
+                .shared
+                .build_row_data(&row, tb_meta, &ignore_cols, row_chunk_id);
             self.shared
                 .base_extractor
                 .push_row(extract_state, row_data, Position::None)
@@ -1013,6 +1131,11 @@ impl MysqlTableCtx {
             let order_col_type = tb_meta.get_col_type(order_col)?;
             loop {
                 let bind_values = start_values.clone();
+                let sql = if start_from_beginning {
+                    &sql_from_beginning
+                } else {
+                    &sql_from_value
+                };
                 let query = if start_from_beginning {
                     start_from_beginning = false;
                     sqlx::query(&sql_from_beginning)
@@ -1023,7 +1146,15 @@ impl MysqlTableCtx {
 
                 let mut rows = query.fetch(&self.shared.conn_pool);
                 let mut slice_count = 0usize;
-                while let Some(row) = rows.try_next().await? {
+                while let Some(row) = rows.try_next().await.map_err(|error| {
+                    QueryTimeout::map_err(
+                        error,
+                        EXTRACTOR_STAGE,
+                        sql,
+                        &tb_meta.basic.schema,
+                        &tb_meta.basic.tb,
+                    )
+                })? {
                     if self
                         .sample_limit
                         .is_some_and(|limit| extracted_count >= limit as u64)
@@ -1039,7 +1170,8 @@ impl MysqlTableCtx {
                     let row_chunk_id = chunk_id_generator.next_row_chunk_id();
 
                     let row_data =
-                        RowData::from_mysql_row(&row, tb_meta, &ignore_cols, Some(row_chunk_id));
+                        self.shared
+                            .build_row_data(&row, tb_meta, &ignore_cols, row_chunk_id);
                     let position = tb_meta.basic.build_position_for_single_col(
                         &DbType::Mysql,
                         order_col,
@@ -1063,6 +1195,11 @@ impl MysqlTableCtx {
         } else {
             loop {
                 let bind_values = start_values.clone();
+                let sql = if start_from_beginning {
+                    &sql_from_beginning
+                } else {
+                    &sql_from_value
+                };
                 let query = if start_from_beginning {
                     start_from_beginning = false;
                     sqlx::query(&sql_from_beginning)
@@ -1077,7 +1214,15 @@ impl MysqlTableCtx {
 
                 let mut rows = query.fetch(&self.shared.conn_pool);
                 let mut slice_count = 0usize;
-                while let Some(row) = rows.try_next().await? {
+                while let Some(row) = rows.try_next().await.map_err(|error| {
+                    QueryTimeout::map_err(
+                        error,
+                        EXTRACTOR_STAGE,
+                        sql,
+                        &tb_meta.basic.schema,
+                        &tb_meta.basic.tb,
+                    )
+                })? {
                     if self
                         .sample_limit
                         .is_some_and(|limit| extracted_count >= limit as u64)
@@ -1097,7 +1242,8 @@ impl MysqlTableCtx {
                     let row_chunk_id = chunk_id_generator.next_row_chunk_id();
 
                     let row_data =
-                        RowData::from_mysql_row(&row, tb_meta, &ignore_cols, Some(row_chunk_id));
+                        self.shared
+                            .build_row_data(&row, tb_meta, &ignore_cols, row_chunk_id);
                     let position = tb_meta.basic.build_position(&DbType::Mysql, &start_values);
                     self.shared
                         .base_extractor
@@ -1175,7 +1321,9 @@ impl MysqlTableCtx {
         while let Some(row) = rows.try_next().await? {
             extracted_count += 1;
             let row_chunk_id = chunk_id_generator.next_row_chunk_id();
-            let row_data = RowData::from_mysql_row(&row, tb_meta, &ignore_cols, Some(row_chunk_id));
+            let row_data = self
+                .shared
+                .build_row_data(&row, tb_meta, &ignore_cols, row_chunk_id);
             self.shared
                 .base_extractor
                 .push_row(extract_state, row_data, Position::None)
@@ -1185,6 +1333,9 @@ impl MysqlTableCtx {
     }
 
     async fn estimate_sample_limit(&self, tb_meta: &MysqlTbMeta) -> anyhow::Result<Option<usize>> {
+        if self.shared.sample_rows.is_some() {
+            return Ok(self.shared.sample_rows);
+        }
         if self
             .shared
             .sample_rate
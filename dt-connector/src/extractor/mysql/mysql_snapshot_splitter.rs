@@ -11,7 +11,7 @@ use dt_common::meta::{
     rdb_tb_meta::RdbTbMeta,
 };
 use dt_common::quote_mysql;
-use dt_common::utils::sql_util::*;
+use dt_common::utils::sql_util::SqlUtil;
 use dt_common::{log_debug, log_info};
 use futures::TryStreamExt;
 use sqlx::{MySql, Pool, Row};
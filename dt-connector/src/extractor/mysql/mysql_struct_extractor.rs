@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use sqlx::{MySql, Pool};
 
 use crate::{
-    extractor::base_extractor::{BaseExtractor, ExtractState},
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+    },
     meta_fetcher::mysql::mysql_struct_fetcher::MysqlStructFetcher,
     Extractor,
 };
@@ -13,7 +16,10 @@ use dt_common::{
     log_info, log_warn,
     meta::{
         mysql::mysql_meta_manager::MysqlMetaManager,
-        struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
+        struct_meta::{
+            statement::struct_statement::StructStatement, struct_data::StructData,
+            structure::structure_type::StructureType,
+        },
     },
     rdb_filter::RdbFilter,
 };
@@ -41,6 +47,8 @@ impl Extractor for MysqlStructExtractor {
             self.extract_internal(db_chunk.into_iter().collect())
                 .await?;
         }
+        // views, routines and triggers of all dbs, after all tables are created
+        self.extract_db_objects().await?;
         self.base_extractor
             .wait_task_finish(&mut self.extract_state)
             .await
@@ -49,6 +57,10 @@ impl Extractor for MysqlStructExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl MysqlStructExtractor {
@@ -76,6 +88,32 @@ impl MysqlStructExtractor {
         Ok(())
     }
 
+    async fn extract_db_objects(&mut self) -> anyhow::Result<()> {
+        let meta_manager = MysqlMetaManager::new(self.conn_pool.clone()).await?;
+        let mut fetcher = MysqlStructFetcher {
+            conn_pool: self.conn_pool.to_owned(),
+            dbs: self.dbs.iter().cloned().collect(),
+            filter: Some(self.filter.to_owned()),
+            meta_manager,
+        };
+
+        let mut statements = Vec::new();
+        if !self.filter.filter_structure(&StructureType::View) {
+            statements.extend(fetcher.get_create_view_statements().await?);
+        }
+        if !self.filter.filter_structure(&StructureType::Routine) {
+            statements.extend(fetcher.get_create_routine_statements().await?);
+        }
+        if !self.filter.filter_structure(&StructureType::Trigger) {
+            statements.extend(fetcher.get_create_trigger_statements().await?);
+        }
+        for statement in statements {
+            self.push_dt_data(StructStatement::MysqlCreateObject(statement))
+                .await?;
+        }
+        Ok(())
+    }
+
     pub async fn push_dt_data(&mut self, statement: StructStatement) -> anyhow::Result<()> {
         let struct_data = StructData {
             schema: "".to_string(),
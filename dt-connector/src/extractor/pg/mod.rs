@@ -1,6 +1,7 @@
 pub mod pg_cdc_client;
 pub mod pg_cdc_extractor;
 pub mod pg_check_extractor;
+pub mod pg_exported_snapshot;
 pub mod pg_snapshot_extractor;
 pub mod pg_snapshot_splitter;
 pub mod pg_struct_extractor;
@@ -12,13 +12,18 @@ use url::Url;
 
 use dt_common::{
     config::{
+        config_enums::DbType,
         connection_auth_config::ConnectionAuthConfig,
         ssl_config::{SslConfig, SslMode},
     },
     error::Error,
     log_info, log_warn,
+    utils::sql_util::SqlUtil,
 };
 
+/// start_lsn to start from the confirmed_flush_lsn of an existing slot
+pub const START_LSN_CONFIRMED_FLUSH: &str = "confirmed_flush_lsn";
+
 pub struct PgCdcClient {
     pub url: String,
     pub connection_auth: ConnectionAuthConfig,
@@ -26,6 +31,9 @@ pub struct PgCdcClient {
     pub pub_name: String,
     pub start_lsn: String,
     pub recreate_slot_if_exists: bool,
+    // tables the publication should cover, None to create it for all tables.
+    // reset to None if an existing publication is for all tables
+    pub pub_tables: Option<Vec<(String, String)>>,
 }
 
 impl PgCdcClient {
@@ -58,13 +66,22 @@ impl PgCdcClient {
     }
 
     fn build_replication_config(&self) -> anyhow::Result<(Config, SslConfig)> {
-        let (sanitized_url, url_ssl_config) = Self::parse_url_ssl_config(&self.url)?;
-        let mut config: Config = Config::from_str(&sanitized_url)?;
+        let (mut config, ssl_config) = Self::build_config(&self.url, &self.connection_auth)?;
         config.replication_mode(ReplicationMode::Logical);
+        Ok((config, ssl_config))
+    }
+
+    /// tokio-postgres config and the effective ssl config of the url and auth
+    pub(crate) fn build_config(
+        url: &str,
+        connection_auth: &ConnectionAuthConfig,
+    ) -> anyhow::Result<(Config, SslConfig)> {
+        let (sanitized_url, url_ssl_config) = Self::parse_url_ssl_config(url)?;
+        let mut config: Config = Config::from_str(&sanitized_url)?;
 
         let mut effective_ssl_config = url_ssl_config.unwrap_or_else(Self::disabled_ssl_config);
 
-        match &self.connection_auth {
+        match connection_auth {
             ConnectionAuthConfig::Basic { username, password } => {
                 config.user(username);
                 if let Some(password) = password {
@@ -97,7 +114,7 @@ impl PgCdcClient {
         }
     }
 
-    fn build_tls_connector(ssl_config: &SslConfig) -> anyhow::Result<MakeTlsConnector> {
+    pub(crate) fn build_tls_connector(ssl_config: &SslConfig) -> anyhow::Result<MakeTlsConnector> {
         let mut builder = SslConnector::builder(SslMethod::tls())?;
 
         match ssl_config.ssl_mode {
@@ -170,34 +187,42 @@ impl PgCdcClient {
         }
     }
 
-    async fn prepare_slot(&self, client: &Client) -> anyhow::Result<(String, String)> {
+    async fn prepare_slot(&mut self, client: &Client) -> anyhow::Result<(String, String)> {
         let mut start_lsn = self.start_lsn.clone();
+        let from_confirmed_flush = start_lsn == START_LSN_CONFIRMED_FLUSH;
+        if from_confirmed_flush && self.recreate_slot_if_exists {
+            bail!(Error::ConfigError(format!(
+                "start_lsn={} conflicts with recreate_slot_if_exists=true",
+                START_LSN_CONFIRMED_FLUSH
+            )));
+        }
 
-        // create publication for all tables if not exists
         let pub_name = if self.pub_name.is_empty() {
             format!("{}_publication_for_all_tables", self.slot_name)
         } else {
             self.pub_name.clone()
         };
-        let query = format!(
-            "SELECT * FROM {} WHERE pubname = '{}'",
-            "pg_catalog.pg_publication", pub_name
-        );
-        let res = client.simple_query(&query).await?;
-        let pub_exists = res.len() > 1;
-        log_info!("publication: {} exists: {}", pub_name, pub_exists);
-
-        if !pub_exists {
-            let query = format!("CREATE PUBLICATION {} FOR ALL TABLES", pub_name);
-            log_info!("execute: {}", query);
-            client.simple_query(&query).await?;
-        }
+        self.prepare_publication(client, &pub_name).await?;
 
         // check slot exists
         let (slot_exists, confirmed_flush_lsn) = self.check_slot_status(client).await?;
         let mut create_slot = !slot_exists;
 
-        if slot_exists {
+        if from_confirmed_flush {
+            // a new slot starts from now, changes before it would be lost silently
+            if !slot_exists || confirmed_flush_lsn.is_empty() {
+                bail!(Error::ExtractorError(format!(
+                    "start_lsn={} requires an existing slot: {} with confirmed_flush_lsn",
+                    START_LSN_CONFIRMED_FLUSH, self.slot_name
+                )));
+            }
+            log_info!(
+                "start from confirmed_flush_lsn: {} of slot: {}",
+                confirmed_flush_lsn,
+                self.slot_name
+            );
+            start_lsn = confirmed_flush_lsn;
+        } else if slot_exists {
             if confirmed_flush_lsn.is_empty() {
                 // should never happen
                 create_slot = true;
@@ -254,6 +279,113 @@ impl PgCdcClient {
         Ok((pub_name, start_lsn))
     }
 
+    /// Creates the publication if not exists, adds the missing tables of pub_tables to an existing one.
+    async fn prepare_publication(&mut self, client: &Client, pub_name: &str) -> anyhow::Result<()> {
+        let query = format!(
+            "SELECT * FROM {} WHERE pubname = '{}'",
+            "pg_catalog.pg_publication", pub_name
+        );
+        let res = client.simple_query(&query).await?;
+        let pub_row = res.iter().find_map(|message| match message {
+            Row(row) => Some(row),
+            _ => None,
+        });
+        log_info!("publication: {} exists: {}", pub_name, pub_row.is_some());
+
+        let Some(pub_row) = pub_row else {
+            let query = Self::build_create_publication_sql(pub_name, &self.pub_tables);
+            log_info!("execute: {}", query);
+            client.simple_query(&query).await?;
+            return Ok(());
+        };
+
+        // changes of operations not published are lost silently
+        for (col, operation) in [
+            ("pubinsert", "insert"),
+            ("pubupdate", "update"),
+            ("pubdelete", "delete"),
+        ] {
+            if pub_row.get(col) == Some("f") {
+                log_warn!(
+                    "publication: {} does not publish {}, such changes will not be captured",
+                    pub_name,
+                    operation
+                );
+            }
+        }
+
+        let Some(pub_tables) = &self.pub_tables else {
+            return Ok(());
+        };
+        if pub_row.get("puballtables") == Some("t") {
+            log_info!("publication: {} is for all tables", pub_name);
+            self.pub_tables = None;
+            return Ok(());
+        }
+
+        let query = format!(
+            "SELECT schemaname, tablename FROM {} WHERE pubname = '{}'",
+            "pg_catalog.pg_publication_tables", pub_name
+        );
+        let mut published = Vec::new();
+        for message in client.simple_query(&query).await? {
+            if let Row(row) = message {
+                published.push((
+                    row.get("schemaname").unwrap_or_default().to_string(),
+                    row.get("tablename").unwrap_or_default().to_string(),
+                ));
+            }
+        }
+        let missing: Vec<(String, String)> = pub_tables
+            .iter()
+            .filter(|tb| !published.contains(tb))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            let query = Self::build_add_pub_tables_sql(pub_name, &missing);
+            log_info!("execute: {}", query);
+            client.simple_query(&query).await?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn build_create_publication_sql(
+        pub_name: &str,
+        pub_tables: &Option<Vec<(String, String)>>,
+    ) -> String {
+        match pub_tables {
+            None => format!("CREATE PUBLICATION {} FOR ALL TABLES", pub_name),
+            // tables are added when they appear
+            Some(tbs) if tbs.is_empty() => format!("CREATE PUBLICATION {}", pub_name),
+            Some(tbs) => format!(
+                "CREATE PUBLICATION {} FOR TABLE {}",
+                pub_name,
+                Self::join_tbs(tbs)
+            ),
+        }
+    }
+
+    pub(crate) fn build_add_pub_tables_sql(pub_name: &str, tbs: &[(String, String)]) -> String {
+        format!(
+            "ALTER PUBLICATION {} ADD TABLE {}",
+            pub_name,
+            Self::join_tbs(tbs)
+        )
+    }
+
+    fn join_tbs(tbs: &[(String, String)]) -> String {
+        tbs.iter()
+            .map(|(schema, tb)| {
+                format!(
+                    "{}.{}",
+                    SqlUtil::escape_by_db_type(schema, &DbType::Pg),
+                    SqlUtil::escape_by_db_type(tb, &DbType::Pg)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     async fn check_slot_status(&self, client: &Client) -> anyhow::Result<(bool, String)> {
         // check slot exists
         let query = format!(
@@ -267,18 +399,61 @@ impl PgCdcClient {
         let mut confirmed_flush_lsn = String::new();
         if slot_exists {
             if let Row(row) = &res[0] {
-                confirmed_flush_lsn = row.get("confirmed_flush_lsn").unwrap().to_string()
+                confirmed_flush_lsn = row.get("confirmed_flush_lsn").unwrap().to_string();
+                // a recreated slot does not need to match
+                if !self.recreate_slot_if_exists {
+                    self.verify_slot(
+                        row.get("plugin").unwrap_or_default(),
+                        row.get("database").unwrap_or_default(),
+                        row.get("active").unwrap_or_default(),
+                        row.get("active_pid").unwrap_or_default(),
+                    )?;
+                }
             }
             log_info!("slot confirmed_flush_lsn: {}", confirmed_flush_lsn);
         }
         Ok((slot_exists, confirmed_flush_lsn))
     }
 
+    fn verify_slot(
+        &self,
+        plugin: &str,
+        database: &str,
+        active: &str,
+        active_pid: &str,
+    ) -> anyhow::Result<()> {
+        if plugin != "pgoutput" {
+            bail!(Error::ExtractorError(format!(
+                "slot: {} was created with plugin: {}, pgoutput is required, drop the slot or set recreate_slot_if_exists=true",
+                self.slot_name, plugin
+            )));
+        }
+
+        let (config, _) = Self::build_config(&self.url, &self.connection_auth)?;
+        if let Some(dbname) = config.get_dbname() {
+            if !database.is_empty() && database != dbname {
+                bail!(Error::ExtractorError(format!(
+                    "slot: {} belongs to database: {}, but the task connects to database: {}",
+                    self.slot_name, database, dbname
+                )));
+            }
+        }
+
+        if active == "t" {
+            bail!(Error::ExtractorError(format!(
+                "slot: {} is in use by another connection, pid: {}, is another task running with the same slot?",
+                self.slot_name, active_pid
+            )));
+        }
+        Ok(())
+    }
+
     async fn start_replication(
         &mut self,
         client: &Client,
     ) -> anyhow::Result<(LogicalReplicationStream, String)> {
         let (pub_name, start_lsn) = self.prepare_slot(client).await?;
+        self.pub_name = pub_name.clone();
 
         // set extra_float_digits to max so no precision will lose
         client.simple_query("SET extra_float_digits=3").await?;
@@ -315,6 +490,7 @@ mod tests {
             pub_name: String::new(),
             start_lsn: String::new(),
             recreate_slot_if_exists: false,
+            pub_tables: None,
         }
     }
 
@@ -374,6 +550,30 @@ mod tests {
         assert_eq!(config.get_dbname(), Some("test_db"));
     }
 
+    #[test]
+    fn build_publication_sql() {
+        let tbs = vec![
+            ("public".to_string(), "tb_1".to_string()),
+            ("Test".to_string(), "Tb2".to_string()),
+        ];
+        assert_eq!(
+            PgCdcClient::build_create_publication_sql("pub1", &None),
+            "CREATE PUBLICATION pub1 FOR ALL TABLES"
+        );
+        assert_eq!(
+            PgCdcClient::build_create_publication_sql("pub1", &Some(vec![])),
+            "CREATE PUBLICATION pub1"
+        );
+        assert_eq!(
+            PgCdcClient::build_create_publication_sql("pub1", &Some(tbs.clone())),
+            r#"CREATE PUBLICATION pub1 FOR TABLE "public"."tb_1", "Test"."Tb2""#
+        );
+        assert_eq!(
+            PgCdcClient::build_add_pub_tables_sql("pub1", &tbs[1..]),
+            r#"ALTER PUBLICATION pub1 ADD TABLE "Test"."Tb2""#
+        );
+    }
+
     #[test]
     fn build_replication_config_defaults_to_disable_without_ssl_config() {
         let client = build_client(
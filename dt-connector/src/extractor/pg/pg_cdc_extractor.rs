@@ -9,6 +9,7 @@ use std::{
     time::UNIX_EPOCH,
 };
 
+use anyhow::{bail, Context};
 use async_trait::async_trait;
 use futures::StreamExt;
 use postgres_protocol::message::backend::{
@@ -16,18 +17,19 @@ use postgres_protocol::message::backend::{
     LogicalReplicationMessage::{
         Begin, Commit, Delete, Insert, Origin, Relation, Truncate, Type, Update,
     },
-    RelationBody,
+    RelationBody, ReplicaIdentity,
     ReplicationMessage::*,
     TupleData, UpdateBody,
 };
 use postgres_types::PgLsn;
-use sqlx::{postgres::PgArguments, query::Query, Pool, Postgres};
+use sqlx::{postgres::PgArguments, query::Query, Pool, Postgres, Row};
 use tokio::{sync::Mutex, time::Duration, time::Instant};
 use tokio_postgres::replication::LogicalReplicationStream;
 
 use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
         pg::pg_cdc_client::PgCdcClient,
         resumer::recovery::Recovery,
     },
@@ -35,16 +37,20 @@ use crate::{
 };
 use dt_common::{
     config::{
-        config_enums::DbType, config_token_parser::ConfigTokenParser,
+        config_enums::{DbType, ReplicaIdentityPolicy},
+        config_token_parser::ConfigTokenParser,
         connection_auth_config::ConnectionAuthConfig,
     },
+    error::Error,
     log_error, log_info, log_warn,
     meta::{
         adaptor::pg_col_value_convertor::PgColValueConvertor,
         col_value::ColValue,
+        ddl_meta::ddl_type::DdlType,
         dt_data::DtData,
         pg::{pg_meta_manager::PgMetaManager, pg_tb_meta::PgTbMeta},
         position::Position,
+        rdb_meta_manager::RDB_PRIMARY_KEY_FLAG,
         rdb_tb_meta::RdbTbMeta,
         row_data::RowData,
         row_type::RowType,
@@ -64,12 +70,16 @@ pub struct PgCdcExtractor {
     pub connection_auth: ConnectionAuthConfig,
     pub slot_name: String,
     pub pub_name: String,
+    // create the publication for tables matched by filter instead of all tables,
+    // and add new tables to it when their CREATE TABLE is captured
+    pub pub_for_filtered_tables: bool,
     pub start_lsn: String,
     pub recreate_slot_if_exists: bool,
     pub keepalive_interval_secs: u64,
     pub heartbeat_interval_secs: u64,
     pub heartbeat_tb: String,
     pub ddl_meta_tb: String,
+    pub replica_identity_policy: ReplicaIdentityPolicy,
     pub syncer: Arc<Mutex<Syncer>>,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
 }
@@ -105,6 +115,12 @@ impl Extractor for PgCdcExtractor {
             self.heartbeat_tb,
             self.ddl_meta_tb,
         );
+        if self.extract_state.time_filter.start_timestamp > 0 {
+            log_info!(
+                "transactions committed before timestamp: {} are skipped",
+                self.extract_state.time_filter.start_timestamp
+            );
+        }
         self.extract_internal().await?;
         self.base_extractor
             .wait_task_finish(&mut self.extract_state)
@@ -114,10 +130,26 @@ impl Extractor for PgCdcExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         self.meta_manager.close().await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl PgCdcExtractor {
     async fn extract_internal(&mut self) -> anyhow::Result<()> {
+        // setup ddl capture
+        let ddl_meta =
+            ConfigTokenParser::parse_config(&self.ddl_meta_tb, &DbType::Pg, &['.'], None)?;
+        if ddl_meta.len() == 2 {
+            self.filter.add_do_tb(&ddl_meta[0], &ddl_meta[1]);
+        }
+
+        let pub_tables = if self.pub_for_filtered_tables {
+            Some(self.get_pub_tables().await?)
+        } else {
+            None
+        };
         let mut cdc_client = PgCdcClient {
             url: self.url.clone(),
             connection_auth: self.connection_auth.clone(),
@@ -125,16 +157,13 @@ impl PgCdcExtractor {
             slot_name: self.slot_name.clone(),
             start_lsn: self.start_lsn.clone(),
             recreate_slot_if_exists: self.recreate_slot_if_exists,
+            pub_tables,
         };
         let (stream, actual_start_lsn) = cdc_client.connect().await?;
         tokio::pin!(stream);
-
-        // setup ddl capture
-        let ddl_meta =
-            ConfigTokenParser::parse_config(&self.ddl_meta_tb, &DbType::Pg, &['.'], None)?;
-        if ddl_meta.len() == 2 {
-            self.filter.add_do_tb(&ddl_meta[0], &ddl_meta[1]);
-        }
+        self.pub_name = cdc_client.pub_name.clone();
+        // an existing publication for all tables needs no new tables
+        self.pub_for_filtered_tables = cdc_client.pub_tables.is_some();
 
         // start heartbeat
         self.start_heartbeat(self.base_extractor.shut_down.clone())?;
@@ -173,7 +202,6 @@ impl PgCdcExtractor {
                             self.decode_relation(&relation).await?;
                         }
 
-                        // do not push Begin into buffer to accelerate sinking
                         Begin(begin) => {
                             position = get_position(&last_tx_end_lsn, begin.timestamp());
                             xid = begin.xid().to_string();
@@ -184,6 +212,10 @@ impl PgCdcExtractor {
                                 timestamp as u32,
                                 &position,
                             );
+                            let begin = DtData::Begin { xid: xid.clone() };
+                            self.base_extractor
+                                .push_dt_data(&mut self.extract_state, begin, position.clone())
+                                .await?;
                         }
 
                         Commit(commit) => {
@@ -312,6 +344,7 @@ impl PgCdcExtractor {
 
         // align the column order of tb_meta to that of the wal log
         tb_meta.basic.cols = col_names;
+        self.check_replica_identity(&tb_meta, event.replica_identity())?;
         self.meta_manager
             .update_tb_meta_by_oid(event.rel_id() as i32, tb_meta)?;
         Ok(())
@@ -371,14 +404,17 @@ impl PgCdcExtractor {
             self.parse_row_data(&tb_meta, old_tuple.tuple_data())?
         } else if let Some(key_tuple) = event.key_tuple() {
             self.parse_row_data(&tb_meta, key_tuple.tuple_data())?
-        } else if !basic.id_cols.is_empty() {
+        } else if !basic.key_map.is_empty() {
+            // key not changed, take key values from the new tuple
             let mut col_values_tmp = HashMap::new();
             for col in basic.id_cols.iter() {
                 col_values_tmp.insert(col.to_string(), col_values_after.get(col).unwrap().clone());
             }
             col_values_tmp
         } else {
-            HashMap::new()
+            // id_cols are all cols of a table without key, they can not be taken from the new tuple
+            self.skip_without_before_image(&tb_meta, RowType::Update, size_of_val(event));
+            return Ok(());
         };
 
         let row_data = RowData::new(
@@ -411,7 +447,8 @@ impl PgCdcExtractor {
         } else if let Some(key_tuple) = event.key_tuple() {
             self.parse_row_data(&tb_meta, key_tuple.tuple_data())?
         } else {
-            HashMap::new()
+            self.skip_without_before_image(&tb_meta, RowType::Delete, size_of_val(event));
+            return Ok(());
         };
 
         let row_data = RowData::new(
@@ -460,16 +497,23 @@ impl PgCdcExtractor {
         let _tag = get_string(row_data, "tag");
         let schema = get_string(row_data, "schema");
 
-        if let Ok(Some(ddl_data)) = self
+        let ddls = self
             .base_extractor
             .parse_ddl(&DbType::Pg, &schema, &ddl_text)
-            .await
-        {
+            .await?;
+        for ddl_data in ddls {
             for ddl_data in ddl_data.split_to_multi() {
                 // invalidate metadata cache
                 self.meta_manager.invalidate_cache_by_ddl_data(&ddl_data);
                 let (schema, tb) = ddl_data.get_schema_tb();
 
+                if self.pub_for_filtered_tables
+                    && ddl_data.ddl_type == DdlType::CreateTable
+                    && !self.filter.filter_tb(&schema, &tb)
+                {
+                    self.add_pub_table(&schema, &tb).await?;
+                }
+
                 if !self.filter.filter_ddl(&schema, &tb, &ddl_data.ddl_type) {
                     self.base_extractor
                         .push_ddl(&mut self.extract_state, ddl_data, position.clone())
@@ -524,9 +568,10 @@ impl PgCdcExtractor {
 
     async fn push_row_to_buf(
         &mut self,
-        row_data: RowData,
+        mut row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
+        row_data.meta_version = self.meta_manager.meta_version;
         self.base_extractor
             .push_row(&mut self.extract_state, row_data, position)
             .await
@@ -542,6 +587,47 @@ impl PgCdcExtractor {
         filtered
     }
 
+    /// update/delete events carry no before images if the table has neither primary key nor
+    /// REPLICA IDENTITY FULL, they would be sunk with empty WHERE filters on the target.
+    fn check_replica_identity(
+        &self,
+        tb_meta: &PgTbMeta,
+        replica_identity: &ReplicaIdentity,
+    ) -> anyhow::Result<()> {
+        let has_pk = tb_meta.basic.key_map.contains_key(RDB_PRIMARY_KEY_FLAG);
+        let replica_identity = match replica_identity {
+            ReplicaIdentity::Full | ReplicaIdentity::Index => return Ok(()),
+            ReplicaIdentity::Default if has_pk => return Ok(()),
+            ReplicaIdentity::Default => "DEFAULT",
+            ReplicaIdentity::Nothing => "NOTHING",
+        };
+
+        let (schema, tb) = (&tb_meta.basic.schema, &tb_meta.basic.tb);
+        match self.replica_identity_policy {
+            ReplicaIdentityPolicy::Fail => bail! {Error::ExtractorError(format!(
+                "schema: {}, tb: {}, REPLICA IDENTITY {} without primary key, update/delete events have no before images, run: ALTER TABLE \"{}\".\"{}\" REPLICA IDENTITY FULL, or set [extractor] replica_identity_policy=skip",
+                schema, tb, replica_identity, schema, tb
+            ))},
+            ReplicaIdentityPolicy::Skip => log_warn!(
+                "schema: {}, tb: {}, REPLICA IDENTITY {} without primary key, update/delete events without before images will be skipped",
+                schema,
+                tb,
+                replica_identity
+            ),
+        }
+        Ok(())
+    }
+
+    fn skip_without_before_image(&mut self, tb_meta: &PgTbMeta, row_type: RowType, size: usize) {
+        log_warn!(
+            "schema: {}, tb: {}, {} event skipped since it has no before image",
+            tb_meta.basic.schema,
+            tb_meta.basic.tb,
+            row_type
+        );
+        self.extract_state.record_extracted_metrics(1, size as u64);
+    }
+
     fn mock_pg_tb_meta(schema: &str, tb: &str, oid: i32) -> PgTbMeta {
         PgTbMeta {
             basic: RdbTbMeta {
@@ -554,6 +640,53 @@ impl PgCdcExtractor {
         }
     }
 
+    /// Existing tables matched by filter, plus the heartbeat table.
+    async fn get_pub_tables(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let heartbeat_tb =
+            ConfigTokenParser::parse_config(&self.heartbeat_tb, &DbType::Pg, &['.'], None)?;
+        let sql = "SELECT schemaname, tablename FROM pg_catalog.pg_tables 
+            WHERE schemaname NOT IN ('pg_catalog', 'information_schema')";
+        let mut tbs = Vec::new();
+        for row in sqlx::query(sql).fetch_all(&self.conn_pool).await? {
+            let schema: String = row.try_get("schemaname")?;
+            let tb: String = row.try_get("tablename")?;
+            let is_heartbeat_tb =
+                heartbeat_tb.len() == 2 && heartbeat_tb[0] == schema && heartbeat_tb[1] == tb;
+            if is_heartbeat_tb || !self.filter.filter_tb(&schema, &tb) {
+                tbs.push((schema, tb));
+            }
+        }
+        log_info!("tables for publication: {}", tbs.len());
+        Ok(tbs)
+    }
+
+    async fn add_pub_table(&self, schema: &str, tb: &str) -> anyhow::Result<()> {
+        let sql = "SELECT 1 FROM pg_catalog.pg_publication_tables 
+            WHERE pubname = $1 AND schemaname = $2 AND tablename = $3";
+        let published = sqlx::query(sql)
+            .bind(&self.pub_name)
+            .bind(schema)
+            .bind(tb)
+            .fetch_optional(&self.conn_pool)
+            .await?
+            .is_some();
+        if published {
+            return Ok(());
+        }
+
+        // changes of the table before it is added to the publication are not captured
+        let sql = PgCdcClient::build_add_pub_tables_sql(
+            &self.pub_name,
+            &[(schema.to_string(), tb.to_string())],
+        );
+        log_info!("execute: {}", sql);
+        sqlx::query(&sql)
+            .execute(&self.conn_pool)
+            .await
+            .with_context(|| format!("failed to add table to publication: [{}]", sql))?;
+        Ok(())
+    }
+
     fn start_heartbeat(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
         let schema_tb = self.base_extractor.precheck_heartbeat(
             self.heartbeat_interval_secs,
@@ -576,15 +709,21 @@ impl PgCdcExtractor {
             let mut start_time = Instant::now();
             while !shut_down.load(Ordering::Acquire) {
                 if start_time.elapsed().as_secs() >= heartbeat_interval_secs {
-                    Self::heartbeat(
-                        &slot_name,
-                        &schema_tb[0],
-                        &schema_tb[1],
-                        &syncer,
-                        &conn_pool,
-                    )
-                    .await
-                    .unwrap();
+                    if syncer
+                        .lock()
+                        .await
+                        .should_heartbeat(heartbeat_interval_secs)
+                    {
+                        Self::heartbeat(
+                            &slot_name,
+                            &schema_tb[0],
+                            &schema_tb[1],
+                            &syncer,
+                            &conn_pool,
+                        )
+                        .await
+                        .unwrap();
+                    }
                     start_time = Instant::now();
                 }
                 TimeUtil::sleep_millis(1000 * heartbeat_interval_secs).await;
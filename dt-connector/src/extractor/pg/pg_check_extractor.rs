@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 
@@ -7,7 +7,7 @@ use futures::TryStreamExt;
 use sqlx::{Pool, Postgres};
 
 use dt_common::{
-    log_info,
+    log_info, log_warn,
     meta::{
         adaptor::pg_col_value_convertor::PgColValueConvertor,
         col_value::ColValue,
@@ -24,6 +24,7 @@ use crate::{
     extractor::{
         base_check_extractor::BaseCheckExtractor,
         base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
     },
     rdb_query_builder::RdbQueryBuilder,
     BatchCheckExtractor, Extractor,
@@ -38,6 +39,9 @@ pub struct PgCheckExtractor {
     pub check_log_dir: String,
     pub batch_size: usize,
     pub replay_diff_as_update: bool,
+    // set to skip rows changed in target since the check
+    pub target_conn_pool: Option<Pool<Postgres>>,
+    pub target_meta_manager: Option<PgMetaManager>,
 }
 
 #[async_trait]
@@ -57,6 +61,10 @@ impl Extractor for PgCheckExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 #[async_trait]
@@ -65,6 +73,10 @@ impl BatchCheckExtractor for PgCheckExtractor {
         let schema = &check_logs[0].schema;
         let tb = &check_logs[0].tb;
         let is_diff = !check_logs[0].diff_col_values.is_empty();
+        let id_cols = BaseCheckExtractor::get_id_cols(check_logs);
+        let changed_ids = self
+            .get_changed_ids_since_check(check_logs, &id_cols)
+            .await?;
         let tb_meta = self.meta_manager.get_tb_meta(schema, tb).await?.to_owned();
         let check_row_data_items = self.build_check_row_data_items(check_logs, &tb_meta)?;
 
@@ -81,6 +93,17 @@ impl BatchCheckExtractor for PgCheckExtractor {
         let mut rows = query.fetch(&self.conn_pool);
         while let Some(row) = rows.try_next().await? {
             let mut row_data = RowData::from_pg_row(&row, &tb_meta, &ignore_cols, None);
+            if !changed_ids.is_empty()
+                && changed_ids.contains(&BaseCheckExtractor::row_id_key(&row_data, &id_cols))
+            {
+                log_warn!(
+                    "skip revising row changed in target since the check, schema: {}, tb: {}, row: {}",
+                    schema,
+                    tb,
+                    row_data
+                );
+                continue;
+            }
 
             if is_diff && self.replay_diff_as_update {
                 row_data.row_type = RowType::Update;
@@ -97,10 +120,63 @@ impl BatchCheckExtractor for PgCheckExtractor {
 }
 
 impl PgCheckExtractor {
+    async fn get_changed_ids_since_check(
+        &mut self,
+        check_logs: &[CheckLog],
+        id_cols: &[String],
+    ) -> anyhow::Result<HashSet<String>> {
+        let (Some(conn_pool), Some(meta_manager)) =
+            (&self.target_conn_pool, &mut self.target_meta_manager)
+        else {
+            return Ok(HashSet::new());
+        };
+
+        let schema = check_logs[0]
+            .target_schema
+            .as_ref()
+            .unwrap_or(&check_logs[0].schema);
+        let tb = check_logs[0]
+            .target_tb
+            .as_ref()
+            .unwrap_or(&check_logs[0].tb);
+        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
+        let check_row_data_items =
+            Self::build_check_row_data_items_with(check_logs, &tb_meta, meta_manager)?;
+
+        let query_builder = RdbQueryBuilder::new_for_pg(&tb_meta, None);
+        let batch_refs: Vec<&RowData> = check_row_data_items.iter().collect();
+        let query_info = if check_logs.len() == 1 {
+            query_builder.get_select_query(&check_row_data_items[0])?
+        } else {
+            query_builder.get_batch_select_query(&batch_refs, 0, batch_refs.len())?
+        };
+        let query = query_builder.create_pg_query(&query_info)?;
+
+        let mut target_rows = HashMap::new();
+        let mut rows = query.fetch(conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let row_data = RowData::from_pg_row(&row, &tb_meta, &None, None);
+            target_rows.insert(BaseCheckExtractor::row_id_key(&row_data, id_cols), row_data);
+        }
+        Ok(BaseCheckExtractor::get_changed_ids_since_check(
+            check_logs,
+            id_cols,
+            &target_rows,
+        ))
+    }
+
     fn build_check_row_data_items(
         &mut self,
         check_logs: &[CheckLog],
         tb_meta: &PgTbMeta,
+    ) -> anyhow::Result<Vec<RowData>> {
+        Self::build_check_row_data_items_with(check_logs, tb_meta, &mut self.meta_manager)
+    }
+
+    fn build_check_row_data_items_with(
+        check_logs: &[CheckLog],
+        tb_meta: &PgTbMeta,
+        meta_manager: &mut PgMetaManager,
     ) -> anyhow::Result<Vec<RowData>> {
         let mut result = Vec::new();
         for check_log in check_logs.iter() {
@@ -108,7 +184,7 @@ impl PgCheckExtractor {
             for (col, value) in check_log.id_col_values.iter() {
                 let col_type = tb_meta.get_col_type(col)?;
                 let col_value = value.as_deref().map_or(Ok(ColValue::None), |v| {
-                    PgColValueConvertor::from_str(col_type, v, &mut self.meta_manager)
+                    PgColValueConvertor::from_str(col_type, v, meta_manager)
                 })?;
                 after.insert(col.to_string(), col_value);
             }
@@ -0,0 +1,79 @@
+use futures::stream::BoxStream;
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    Pool, Postgres, Transaction,
+};
+
+const REPEATABLE_READ_SQL: &str = "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY";
+
+/// A repeatable read transaction exporting its snapshot by pg_export_snapshot(),
+/// it must be kept open until all readers importing the snapshot have started.
+pub struct PgExportedSnapshot {
+    tx: Transaction<'static, Postgres>,
+    pub snapshot_id: String,
+}
+
+impl PgExportedSnapshot {
+    pub async fn export(conn_pool: &Pool<Postgres>) -> anyhow::Result<Self> {
+        let mut tx = conn_pool.begin().await?;
+        sqlx::query(REPEATABLE_READ_SQL).execute(&mut *tx).await?;
+        let snapshot_id: String = sqlx::query_scalar("SELECT pg_export_snapshot()")
+            .fetch_one(&mut *tx)
+            .await?;
+        Ok(Self { tx, snapshot_id })
+    }
+
+    pub async fn release(self) -> anyhow::Result<()> {
+        self.tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Reads rows either from the pool, or in a transaction importing the exported snapshot,
+/// so parallel chunks of a table see the same data.
+pub struct PgSnapshotReader<'a> {
+    conn_pool: &'a Pool<Postgres>,
+    tx: Option<Transaction<'static, Postgres>>,
+}
+
+impl<'a> PgSnapshotReader<'a> {
+    pub async fn begin(
+        conn_pool: &'a Pool<Postgres>,
+        snapshot_id: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let tx = match snapshot_id {
+            Some(snapshot_id) => {
+                let mut tx = conn_pool.begin().await?;
+                sqlx::query(REPEATABLE_READ_SQL).execute(&mut *tx).await?;
+                // snapshot ids are generated by the server, e.g. 00000003-0000001B-1
+                let sql = format!(
+                    "SET TRANSACTION SNAPSHOT '{}'",
+                    snapshot_id.replace('\'', "''")
+                );
+                sqlx::query(&sql).execute(&mut *tx).await?;
+                Some(tx)
+            }
+            None => None,
+        };
+        Ok(Self { conn_pool, tx })
+    }
+
+    pub fn fetch<'e, 'q: 'e>(
+        &'e mut self,
+        query: Query<'q, Postgres, PgArguments>,
+    ) -> BoxStream<'e, Result<PgRow, sqlx::Error>> {
+        match &mut self.tx {
+            Some(tx) => query.fetch(&mut **tx),
+            None => query.fetch(self.conn_pool),
+        }
+    }
+
+    // dropping the reader without finish rolls back the read-only transaction
+    pub async fn finish(self) -> anyhow::Result<()> {
+        if let Some(tx) = self.tx {
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+}
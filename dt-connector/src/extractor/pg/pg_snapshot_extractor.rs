@@ -13,7 +13,11 @@ use crate::{
         base_extractor::{BaseExtractor, ExtractState},
         base_splitter::SnapshotChunk,
         estimated_sample_limit,
-        pg::pg_snapshot_splitter::PgSnapshotSplitter,
+        extractor_progress::ExtractorProgress,
+        pg::{
+            pg_exported_snapshot::{PgExportedSnapshot, PgSnapshotReader},
+            pg_snapshot_splitter::PgSnapshotSplitter,
+        },
         rdb_snapshot_extract_statement::{OrderKeyPredicateType, RdbSnapshotExtractStatement},
         resumer::recovery::Recovery,
         snapshot_chunk_id_generator::SnapshotChunkIdGenerator,
@@ -22,9 +26,8 @@ use crate::{
     },
     Extractor,
 };
-use dt_common::utils::sql_util::PG_ESCAPE;
 use dt_common::{
-    config::config_enums::{DbType, RdbParallelType},
+    config::config_enums::{DbType, RdbParallelType, SnapshotTableOrder},
     log_debug, log_info,
     meta::{
         adaptor::{pg_col_value_convertor::PgColValueConvertor, sqlx_ext::SqlxPgExt},
@@ -37,7 +40,10 @@ use dt_common::{
     },
     quote_pg,
     rdb_filter::RdbFilter,
-    utils::serialize_util::SerializeUtil,
+    utils::{
+        query_timeout::{QueryTimeout, EXTRACTOR_STAGE},
+        serialize_util::SerializeUtil,
+    },
 };
 
 use quote_pg as quote;
@@ -47,6 +53,8 @@ pub struct PgSnapshotExtractor {
     pub extract_state: ExtractState,
     pub parallel_size: usize,
     pub schema_tbs: HashMap<String, Vec<String>>,
+    pub table_order: SnapshotTableOrder,
+    pub consistent_snapshot: bool,
 }
 
 #[derive(Clone)]
@@ -59,7 +67,12 @@ pub struct PgSnapshotShared {
     pub batch_size: usize,
     pub parallel_type: RdbParallelType,
     pub sample_rate: Option<u8>,
+    // fixed rows extracted for each table, takes precedence over sample_rate
+    pub sample_rows: Option<usize>,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+    pub snapshot_watermark: bool,
+    // set when consistent_snapshot is enabled, imported by all row reads
+    pub exported_snapshot: Option<String>,
 }
 
 enum PgSnapshotWork {
@@ -113,14 +126,28 @@ impl Extractor for PgSnapshotExtractor {
             bail!("parallel_size must be greater than 0");
         }
 
-        let tables = self.collect_tables();
+        let tables = self.collect_tables().await?;
         log_info!(
-            "PgSnapshotExtractor starts, tables: {}, parallel_type: {:?}, parallel_size: {}",
+            "PgSnapshotExtractor starts, tables: {}, parallel_type: {:?}, parallel_size: {}, table_order: {}",
             tables.len(),
             self.shared.parallel_type,
-            self.parallel_size
+            self.parallel_size,
+            self.table_order
         );
 
+        // the exporting transaction must stay open until all reads have imported the snapshot
+        let exported_snapshot = if self.consistent_snapshot {
+            let exported_snapshot = PgExportedSnapshot::export(&self.shared.conn_pool).await?;
+            log_info!(
+                "PgSnapshotExtractor reads from exported snapshot: {}",
+                exported_snapshot.snapshot_id
+            );
+            self.shared.exported_snapshot = Some(exported_snapshot.snapshot_id.clone());
+            Some(exported_snapshot)
+        } else {
+            None
+        };
+
         let state = PgSnapshotDispatchState {
             shared: self.shared.clone(),
             root_extract_state: SnapshotDispatcher::fork_extract_state(&self.extract_state),
@@ -139,6 +166,10 @@ impl Extractor for PgSnapshotExtractor {
         )
         .await?;
 
+        if let Some(exported_snapshot) = exported_snapshot {
+            exported_snapshot.release().await?;
+        }
+
         self.shared
             .base_extractor
             .wait_task_finish(&mut self.extract_state)
@@ -148,10 +179,14 @@ impl Extractor for PgSnapshotExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl PgSnapshotExtractor {
-    fn collect_tables(&self) -> Vec<SnapshotTableId> {
+    async fn collect_tables(&self) -> anyhow::Result<Vec<SnapshotTableId>> {
         let mut tables = Vec::new();
         for (schema, tbs) in &self.schema_tbs {
             for tb in tbs {
@@ -161,7 +196,38 @@ impl PgSnapshotExtractor {
                 });
             }
         }
-        tables
+
+        if self.table_order != SnapshotTableOrder::None {
+            let sizes = self.estimate_tb_sizes().await?;
+            SnapshotDispatcher::order_tables(&mut tables, &sizes, &self.table_order);
+        }
+        Ok(tables)
+    }
+
+    // estimated on-disk size of each table from the catalog statistics
+    async fn estimate_tb_sizes(&self) -> anyhow::Result<HashMap<SnapshotTableId, u64>> {
+        let mut sizes = HashMap::new();
+        if self.schema_tbs.is_empty() {
+            return Ok(sizes);
+        }
+
+        let sql = format!(
+            "SELECT n.nspname::text, c.relname::text, pg_total_relation_size(c.oid) FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace WHERE c.relkind IN ('r', 'p') AND n.nspname IN ({})",
+            self.schema_tbs
+                .keys()
+                .map(|schema| format!("'{}'", schema.replace('\'', "''")))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let mut rows = sqlx::query(&sql).fetch(&self.shared.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let schema: String = row.try_get(0)?;
+            let tb: String = row.try_get(1)?;
+            let size: i64 = row.try_get(2)?;
+            let size = size.max(0) as u64;
+            sizes.insert(SnapshotTableId { schema, tb }, size);
+        }
+        Ok(sizes)
     }
 
     async fn next_work(
@@ -171,8 +237,23 @@ impl PgSnapshotExtractor {
             return Ok((state, Some(work)));
         }
 
-        let Some(table_id) = state.pending_tables.pop_front() else {
-            return Ok((state, None));
+        let table_id = loop {
+            let Some(table_id) = state.pending_tables.pop_front() else {
+                return Ok((state, None));
+            };
+            // the table may be removed from the running task after it was scheduled
+            if !state
+                .shared
+                .filter
+                .is_tb_removed(&table_id.schema, &table_id.tb)
+            {
+                break table_id;
+            }
+            log_info!(
+                "schema: {}, tb: {}, removed from task, skip snapshot",
+                table_id.schema,
+                table_id.tb
+            );
         };
 
         let work = state.prepare_table_work(table_id).await?;
@@ -418,8 +499,22 @@ impl PgSnapshotExtractor {
             .filter
             .get_ignore_cols(&tb_meta.basic.schema, &tb_meta.basic.tb)
             .cloned();
-        let mut rows = query.fetch(&shared.conn_pool);
-        while let Some(row) = rows.try_next().await? {
+        let mut reader = shared.begin_read().await?;
+        let mut rows = reader.fetch(query);
+        let sql = if matches!(start_value, ColValue::None) {
+            &sql_le
+        } else {
+            &sql_range
+        };
+        while let Some(row) = rows.try_next().await.map_err(|error| {
+            QueryTimeout::map_err(
+                error,
+                EXTRACTOR_STAGE,
+                sql,
+                &tb_meta.basic.schema,
+                &tb_meta.basic.tb,
+            )
+        })? {
             extracted_cnt += 1;
             partition_col_value =
                 PgColValueConvertor::from_query(&row, &partition_col, &partition_col_type)?;
@@ -430,6 +525,8 @@ impl PgSnapshotExtractor {
                 .push_row(&mut extract_state, row_data, Position::None)
                 .await?;
         }
+        drop(rows);
+        reader.finish().await?;
         extract_state.monitor.try_flush(true).await;
         Ok((chunk_id, extracted_cnt, partition_col_value))
     }
@@ -462,6 +559,8 @@ struct PgActiveTable {
     _monitor_guard: TableMonitorGuard,
     tb_meta: PgTbMeta,
     extracted_count: u64,
+    // cdc position fetched before the table is extracted, set if snapshot_watermark is enabled
+    cdc_position: Option<Position>,
     mode: PgActiveTableMode,
 }
 
@@ -499,6 +598,7 @@ impl PgSnapshotDispatchState {
         }
         let schema = table_id.schema.clone();
         let tb = table_id.tb.clone();
+        let cdc_position = active_table.cdc_position.take().map(Box::new);
         log_info!(
             "end extracting data from {}.{}, all count: {}",
             quote!(&table_id.schema),
@@ -514,6 +614,8 @@ impl PgSnapshotDispatchState {
                     db_type: DbType::Pg.to_string(),
                     schema: schema.clone(),
                     tb: tb.clone(),
+                    row_count: cdc_position.as_ref().map(|_| active_table.extracted_count),
+                    cdc_position,
                 },
             )
             .await?;
@@ -550,6 +652,12 @@ impl PgSnapshotDispatchState {
             .to_owned();
         table_ctx.sample_limit = table_ctx.estimate_sample_limit(&tb_meta).await?;
         let active_mode = table_ctx.prepare_active_mode(&tb_meta).await?;
+        // fetched before any row is read, so streaming from it covers all changes during the snapshot
+        let cdc_position = if self.shared.snapshot_watermark {
+            Some(self.shared.fetch_cdc_position().await?)
+        } else {
+            None
+        };
 
         self.active_tables.insert(
             table_id.clone(),
@@ -559,6 +667,7 @@ impl PgSnapshotDispatchState {
                 _monitor_guard: monitor_guard,
                 tb_meta: tb_meta.clone(),
                 extracted_count: 0,
+                cdc_position,
                 mode: active_mode,
             },
         );
@@ -693,6 +802,23 @@ impl PgSnapshotDispatchState {
     }
 }
 
+impl PgSnapshotShared {
+    async fn begin_read(&self) -> anyhow::Result<PgSnapshotReader<'_>> {
+        PgSnapshotReader::begin(&self.conn_pool, self.exported_snapshot.as_deref()).await
+    }
+
+    async fn fetch_cdc_position(&self) -> anyhow::Result<Position> {
+        let lsn: String = sqlx::query("SELECT pg_current_wal_lsn()::text")
+            .fetch_one(&self.conn_pool)
+            .await?
+            .try_get(0)?;
+        Ok(Position::PgCdc {
+            lsn,
+            timestamp: String::new(),
+        })
+    }
+}
+
 #[derive(Clone)]
 struct PgTableCtx {
     shared: PgSnapshotShared,
@@ -924,7 +1050,8 @@ impl PgTableCtx {
         }
         let sql = stmt.build()?;
 
-        let mut rows = sqlx::query(&sql).fetch(&self.shared.conn_pool);
+        let mut reader = self.shared.begin_read().await?;
+        let mut rows = reader.fetch(sqlx::query(&sql));
         let mut chunk_id_generator = SnapshotChunkIdGenerator::new(self.shared.batch_size);
         while let Some(row) = rows.try_next().await? {
             let row_chunk_id = chunk_id_generator.next_row_chunk_id();
@@ -934,6 +1061,8 @@ impl PgTableCtx {
                 .push_row(extract_state, row_data, Position::None)
                 .await?;
         }
+        drop(rows);
+        reader.finish().await?;
         Ok(extract_state.monitor.counters.pushed_record_count - base_count)
     }
 
@@ -1006,6 +1135,11 @@ impl PgTableCtx {
             let order_col_type = tb_meta.get_col_type(order_col)?;
             loop {
                 let bind_values = start_values.clone();
+                let sql = if start_from_beginning {
+                    &sql_from_beginning
+                } else {
+                    &sql_from_value
+                };
                 let query = if start_from_beginning {
                     start_from_beginning = false;
                     sqlx::query(&sql_from_beginning)
@@ -1014,9 +1148,18 @@ impl PgTableCtx {
                         .bind_col_value(bind_values.get(order_col), order_col_type)
                 };
 
-                let mut rows = query.fetch(&self.shared.conn_pool);
+                let mut reader = self.shared.begin_read().await?;
+                let mut rows = reader.fetch(query);
                 let mut slice_count = 0usize;
-                while let Some(row) = rows.try_next().await? {
+                while let Some(row) = rows.try_next().await.map_err(|error| {
+                    QueryTimeout::map_err(
+                        error,
+                        EXTRACTOR_STAGE,
+                        sql,
+                        &tb_meta.basic.schema,
+                        &tb_meta.basic.tb,
+                    )
+                })? {
                     if self
                         .sample_limit
                         .is_some_and(|limit| extracted_count >= limit as u64)
@@ -1044,6 +1187,8 @@ impl PgTableCtx {
                         .push_row(extract_state, row_data, position)
                         .await?;
                 }
+                drop(rows);
+                reader.finish().await?;
 
                 if self
                     .sample_limit
@@ -1056,6 +1201,11 @@ impl PgTableCtx {
         } else {
             loop {
                 let bind_values = start_values.clone();
+                let sql = if start_from_beginning {
+                    &sql_from_beginning
+                } else {
+                    &sql_from_value
+                };
                 let query = if start_from_beginning {
                     start_from_beginning = false;
                     sqlx::query(&sql_from_beginning)
@@ -1068,9 +1218,18 @@ impl PgTableCtx {
                     query
                 };
 
-                let mut rows = query.fetch(&self.shared.conn_pool);
+                let mut reader = self.shared.begin_read().await?;
+                let mut rows = reader.fetch(query);
                 let mut slice_count = 0usize;
-                while let Some(row) = rows.try_next().await? {
+                while let Some(row) = rows.try_next().await.map_err(|error| {
+                    QueryTimeout::map_err(
+                        error,
+                        EXTRACTOR_STAGE,
+                        sql,
+                        &tb_meta.basic.schema,
+                        &tb_meta.basic.tb,
+                    )
+                })? {
                     if self
                         .sample_limit
                         .is_some_and(|limit| extracted_count >= limit as u64)
@@ -1096,6 +1255,8 @@ impl PgTableCtx {
                         .push_row(extract_state, row_data, position)
                         .await?;
                 }
+                drop(rows);
+                reader.finish().await?;
 
                 if self
                     .sample_limit
@@ -1163,7 +1324,8 @@ impl PgTableCtx {
         }
         let sql_for_null = stmt.build()?;
 
-        let mut rows = sqlx::query(&sql_for_null).fetch(&self.shared.conn_pool);
+        let mut reader = self.shared.begin_read().await?;
+        let mut rows = reader.fetch(sqlx::query(&sql_for_null));
         while let Some(row) = rows.try_next().await? {
             extracted_count += 1;
             let row_chunk_id = chunk_id_generator.next_row_chunk_id();
@@ -1173,10 +1335,15 @@ impl PgTableCtx {
                 .push_row(extract_state, row_data, Position::None)
                 .await?;
         }
+        drop(rows);
+        reader.finish().await?;
         Ok(extracted_count)
     }
 
     async fn estimate_sample_limit(&self, tb_meta: &PgTbMeta) -> anyhow::Result<Option<usize>> {
+        if self.shared.sample_rows.is_some() {
+            return Ok(self.shared.sample_rows);
+        }
         if self
             .shared
             .sample_rate
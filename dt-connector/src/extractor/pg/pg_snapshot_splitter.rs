@@ -12,7 +12,6 @@ use dt_common::meta::{
     rdb_tb_meta::RdbTbMeta,
 };
 use dt_common::quote_pg;
-use dt_common::utils::sql_util::*;
 use futures::TryStreamExt;
 use sqlx::{Pool, Postgres, Row};
 
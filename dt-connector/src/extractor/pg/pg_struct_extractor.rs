@@ -4,7 +4,10 @@ use async_trait::async_trait;
 use sqlx::{Pool, Postgres};
 
 use crate::{
-    extractor::base_extractor::{BaseExtractor, ExtractState},
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+    },
     meta_fetcher::pg::pg_struct_fetcher::PgStructFetcher,
     Extractor,
 };
@@ -43,9 +46,14 @@ impl Extractor for PgStructExtractor {
                 "PgStructExtractor extracts schemas: {}",
                 schema_chunk.join(",")
             );
-            let do_global_struct = idx == last_idx && self.do_global_structs;
-            self.extract_internal(schema_chunk.into_iter().collect(), do_global_struct)
-                .await?;
+            let is_last_chunk = idx == last_idx;
+            let do_global_struct = is_last_chunk && self.do_global_structs;
+            self.extract_internal(
+                schema_chunk.into_iter().collect(),
+                do_global_struct,
+                is_last_chunk,
+            )
+            .await?;
         }
         self.base_extractor
             .wait_task_finish(&mut self.extract_state)
@@ -55,6 +63,10 @@ impl Extractor for PgStructExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl PgStructExtractor {
@@ -62,6 +74,7 @@ impl PgStructExtractor {
         &mut self,
         schemas: HashSet<String>,
         do_global_structs: bool,
+        do_db_objects: bool,
     ) -> anyhow::Result<()> {
         let mut pg_fetcher = PgStructFetcher {
             conn_pool: self.conn_pool.to_owned(),
@@ -99,6 +112,11 @@ impl PgStructExtractor {
                 .await?;
         }
 
+        // views, procedures and triggers of all schemas, after all tables are created
+        if do_db_objects {
+            self.extract_db_objects().await?;
+        }
+
         if do_global_structs && !self.filter.filter_structure(&StructureType::Rbac) {
             // do rbac init
             let rbac_statements = pg_fetcher.get_create_rbac_statements().await?;
@@ -111,6 +129,30 @@ impl PgStructExtractor {
         Ok(())
     }
 
+    async fn extract_db_objects(&mut self) -> anyhow::Result<()> {
+        let mut pg_fetcher = PgStructFetcher {
+            conn_pool: self.conn_pool.to_owned(),
+            schemas: self.schemas.iter().cloned().collect(),
+            filter: Some(self.filter.to_owned()),
+        };
+
+        let mut statements = Vec::new();
+        if !self.filter.filter_structure(&StructureType::View) {
+            statements.extend(pg_fetcher.get_create_view_statements().await?);
+        }
+        if !self.filter.filter_structure(&StructureType::Routine) {
+            statements.extend(pg_fetcher.get_create_routine_statements().await?);
+        }
+        if !self.filter.filter_structure(&StructureType::Trigger) {
+            statements.extend(pg_fetcher.get_create_trigger_statements().await?);
+        }
+        for statement in statements {
+            self.push_dt_data(StructStatement::PgCreateObject(statement))
+                .await?;
+        }
+        Ok(())
+    }
+
     pub async fn push_dt_data(&mut self, statement: StructStatement) -> anyhow::Result<()> {
         let struct_data = StructData {
             schema: "".to_string(),
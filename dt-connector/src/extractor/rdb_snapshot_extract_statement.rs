@@ -395,6 +395,7 @@ mod tests {
         MysqlTbMeta {
             basic,
             col_type_map,
+            ..Default::default()
         }
     }
 
@@ -420,6 +421,7 @@ mod tests {
         MysqlTbMeta {
             basic,
             col_type_map,
+            ..Default::default()
         }
     }
 
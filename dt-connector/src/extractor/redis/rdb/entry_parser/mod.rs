@@ -36,3 +36,20 @@ const RDB_TYPE_HASH_METADATA_PRE_GA: u8 = 22;
 const RDB_TYPE_HASH_LISTPACK_EX_PRE_GA: u8 = 23;
 const RDB_TYPE_HASH_METADATA: u8 = 24;
 const RDB_TYPE_HASH_LISTPACK_EX: u8 = 25;
+
+/// The lowest redis version able to load a value of the rdb type from a DUMP payload,
+/// RESTORE fails with "Bad data format" on older versions.
+pub fn min_restore_version(type_byte: u8) -> f32 {
+    match type_byte {
+        RDB_TYPE_STRING | RDB_TYPE_LIST | RDB_TYPE_SET | RDB_TYPE_ZSET | RDB_TYPE_HASH => 2.6,
+        RDB_TYPE_HASH_ZIPMAP..=RDB_TYPE_HASH_ZIPLIST => 2.6,
+        RDB_TYPE_LIST_QUICKLIST => 3.2,
+        RDB_TYPE_ZSET_2 | RDB_TYPE_MODULE | RDB_TYPE_MODULE_2 => 4.0,
+        RDB_TYPE_STREAM_LISTPACKS => 5.0,
+        RDB_TYPE_HASH_LISTPACK..=RDB_TYPE_STREAM_LISTPACKS_2 => 7.0,
+        RDB_TYPE_SET_LISTPACK | RDB_TYPE_STREAM_LISTPACKS_3 => 7.2,
+        RDB_TYPE_HASH_METADATA_PRE_GA | RDB_TYPE_HASH_LISTPACK_EX_PRE_GA => 7.4,
+        RDB_TYPE_HASH_METADATA | RDB_TYPE_HASH_LISTPACK_EX => 8.0,
+        _ => f32::MAX,
+    }
+}
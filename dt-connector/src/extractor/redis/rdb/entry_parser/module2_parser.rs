@@ -16,20 +16,23 @@ impl ModuleParser {
         key: RedisString,
         type_byte: u8,
     ) -> anyhow::Result<ModuleObject> {
+        let module_id = reader.read_length().await?;
+        let module_name = Self::module_type_name_by_id(module_id);
+        // values of version 1 have no opcodes, so their length is unknown to skip them
         if type_byte == super::RDB_TYPE_MODULE {
             bail! {Error::RedisRdbError(format!(
-                "module type with version 1 is not supported, key=[{}]",
+                "module type [{}] with version 1 is not supported and can not be skipped, key=[{}]",
+                module_name,
                 String::from(key)
             ))}
         }
 
-        let module_id = reader.read_length().await?;
-        let module_name = Self::module_type_name_by_id(module_id);
-
         log_info!("load module2 type: [{}] with raw", module_name);
         Self::skip_module_data(reader).await?;
 
-        Ok(ModuleObject::new())
+        let mut obj = ModuleObject::new();
+        obj.type_name = module_name;
+        Ok(obj)
     }
 
     pub fn module_type_name_by_id(module_id: u64) -> String {
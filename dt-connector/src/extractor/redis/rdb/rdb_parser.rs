@@ -3,12 +3,16 @@ use sqlx::types::chrono;
 
 use super::{entry_parser::entry_parser::EntryParser, reader::rdb_reader::RdbReader};
 use crate::extractor::redis::{rdb::entry_parser::module2_parser::ModuleParser, StreamReader};
-use dt_common::meta::redis::{redis_entry::RedisEntry, redis_object::RedisCmd};
-use dt_common::{error::Error, log_debug, log_info};
+use dt_common::config::{config_enums::UnknownModulePolicy, extractor_config::RedisModuleConfig};
+use dt_common::meta::redis::{
+    redis_entry::RedisEntry,
+    redis_object::{FunctionObject, RedisCmd, RedisObject},
+};
+use dt_common::{error::Error, log_debug, log_info, log_warn};
 
 const K_FLAG_SLOT_INFO: u8 = 0xf4; // (244) (Redis 7.4+) RDB_OPCODE_SLOT_INFO: slot info
-const _K_FLAG_FUNCTION2: u8 = 0xf5; // (245) function library data
-const _K_FLAG_FUNCTION: u8 = 0xf6; // (246) old function library data for 7.0 rc1 and rc2
+const K_FLAG_FUNCTION2: u8 = 0xf5; // (245) function library data
+const K_FLAG_FUNCTION: u8 = 0xf6; // (246) old function library data for 7.0 rc1 and rc2
 const K_FLAG_MODULE_AUX: u8 = 0xf7; // (247) Module auxiliary data.
 const K_FLAG_IDLE: u8 = 0xf8; // (248) LRU idle time.
 const K_FLAG_FREQ: u8 = 0xf9; // (249) LFU frequency.
@@ -31,10 +35,12 @@ pub struct RdbParser<'a> {
     pub repl_stream_db_id: i64,
     pub now_db_id: i64,
     pub expire_ms: i64,
+    pub expire_at_ms: i64,
     pub idle: i64,
     pub freq: i64,
 
     pub is_end: bool,
+    pub module_config: RedisModuleConfig,
 }
 
 impl RdbParser<'_> {
@@ -62,6 +68,27 @@ impl RdbParser<'_> {
                 self.reader.read_length().await?; // slot size
                 self.reader.read_length().await?; // expires slot size
             }
+            K_FLAG_FUNCTION2 => {
+                self.reader.copy_raw = true;
+                let code = self.reader.read_string().await?;
+                self.reader.copy_raw = false;
+                log_info!("RDB function library: {:?}", code);
+
+                let mut entry = RedisEntry::new();
+                entry.is_base = true;
+                entry.db_id = self.now_db_id;
+                entry.raw_bytes = self.reader.drain_raw_bytes();
+                entry.value = RedisObject::Function(FunctionObject { code });
+                entry.value_type_byte = type_byte;
+                return Ok(Some(entry));
+            }
+
+            K_FLAG_FUNCTION => {
+                bail! {Error::RedisRdbError(
+                    "function library data of redis 7.0 rc1/rc2 is not supported".into()
+                )}
+            }
+
             K_FLAG_MODULE_AUX => {
                 let module_id = self.reader.read_length().await?; // module id
                 let module_name = ModuleParser::module_type_name_by_id(module_id);
@@ -150,8 +177,8 @@ impl RdbParser<'_> {
             }
 
             K_FLAG_EXPIRE_MS => {
-                let mut expire_ms = self.reader.read_u64().await? as i64;
-                expire_ms -= chrono::Utc::now().timestamp_millis();
+                self.expire_at_ms = self.reader.read_u64().await? as i64;
+                let mut expire_ms = self.expire_at_ms - chrono::Utc::now().timestamp_millis();
                 if expire_ms < 0 {
                     expire_ms = 1
                 }
@@ -159,8 +186,8 @@ impl RdbParser<'_> {
             }
 
             K_FLAG_EXPIRE => {
-                let mut expire_ms = self.reader.read_u32().await? as i64 * 1000;
-                expire_ms -= chrono::Utc::now().timestamp_millis();
+                self.expire_at_ms = self.reader.read_u32().await? as i64 * 1000;
+                let mut expire_ms = self.expire_at_ms - chrono::Utc::now().timestamp_millis();
                 if expire_ms < 0 {
                     expire_ms = 1
                 }
@@ -193,16 +220,44 @@ impl RdbParser<'_> {
                         error
                     ))}
                 } else {
+                    let raw_bytes = self.reader.drain_raw_bytes();
+                    let value = value.unwrap();
+                    if let RedisObject::Module(module) = &value {
+                        if !self.is_module_type_included(&module.type_name) {
+                            match self.module_config.unknown_module_policy {
+                                UnknownModulePolicy::Skip => {
+                                    log_warn!(
+                                        "skip module key: {}, module type: {}",
+                                        String::from(key),
+                                        module.type_name
+                                    );
+                                    self.expire_ms = 0;
+                                    self.expire_at_ms = 0;
+                                    return Ok(None);
+                                }
+                                UnknownModulePolicy::Fail => {
+                                    bail! {Error::RedisRdbError(format!(
+                                        "module type not in [extractor].module_types, key: {}, module type: {}",
+                                        String::from(key),
+                                        module.type_name
+                                    ))}
+                                }
+                            }
+                        }
+                    }
+
                     let mut entry = RedisEntry::new();
                     entry.is_base = true;
                     entry.db_id = self.now_db_id;
-                    entry.raw_bytes = self.reader.drain_raw_bytes();
+                    entry.raw_bytes = raw_bytes;
                     entry.key = key;
-                    entry.value = value.unwrap();
+                    entry.value = value;
                     entry.value_type_byte = type_byte;
                     entry.expire_ms = self.expire_ms;
+                    entry.expire_at_ms = self.expire_at_ms;
                     // reset expire_ms
                     self.expire_ms = 0;
+                    self.expire_at_ms = 0;
                     return Ok(Some(entry));
                 }
             }
@@ -210,4 +265,119 @@ impl RdbParser<'_> {
 
         Ok(None)
     }
+
+    // module values are copied raw and restored, types outside a non-empty list are unknown
+    fn is_module_type_included(&self, type_name: &str) -> bool {
+        self.module_config.module_types.is_empty()
+            || self
+                .module_config
+                .module_types
+                .iter()
+                .any(|i| i == type_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const MODULE_TYPE: &str = "ReJSON-RL";
+
+    fn module_id(type_name: &str) -> u64 {
+        let char_set: Vec<char> =
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+                .chars()
+                .collect();
+        let id = type_name.chars().fold(0u64, |id, c| {
+            (id << 6) | char_set.iter().position(|i| *i == c).unwrap() as u64
+        });
+        // encoding version in the lowest 10 bits
+        (id << 10) | 3
+    }
+
+    // a module key followed by a string key k2
+    fn rdb_bytes(type_byte: u8) -> Vec<u8> {
+        let mut bytes = vec![type_byte, 2, b'k', b'1', 0x81];
+        bytes.extend_from_slice(&module_id(MODULE_TYPE).to_be_bytes());
+        // uint opcode with value 5, then eof opcode
+        bytes.extend_from_slice(&[2, 5, 0]);
+        bytes.extend_from_slice(&[0, 2, b'k', b'2', 1, b'v']);
+        bytes
+    }
+
+    async fn load_entries(
+        bytes: &[u8],
+        module_types: &[&str],
+        unknown_module_policy: UnknownModulePolicy,
+    ) -> anyhow::Result<Vec<RedisEntry>> {
+        let mut cursor = Cursor::new(bytes);
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut cursor);
+        let reader = RdbReader {
+            conn: &mut stream_reader,
+            rdb_length: bytes.len(),
+            position: 0,
+            copy_raw: false,
+            raw_bytes: Vec::new(),
+        };
+        let mut parser = RdbParser {
+            reader,
+            repl_stream_db_id: 0,
+            now_db_id: 0,
+            expire_ms: 0,
+            expire_at_ms: 0,
+            idle: 0,
+            freq: 0,
+            is_end: false,
+            module_config: RedisModuleConfig {
+                module_types: module_types.iter().map(|i| i.to_string()).collect(),
+                unknown_module_policy,
+            },
+        };
+
+        let mut entries = Vec::new();
+        while parser.reader.position < bytes.len() {
+            if let Some(entry) = parser.load_entry().await? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    #[tokio::test]
+    async fn test_unknown_module_policy() {
+        let bytes = rdb_bytes(7);
+        // all module types are synced by default
+        let entries = load_entries(&bytes, &[], UnknownModulePolicy::Fail)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // module types not listed are unknown
+        let entries = load_entries(&bytes, &["MBbloom--"], UnknownModulePolicy::Skip)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(String::from(entries[0].key.clone()), "k2");
+
+        let err = load_entries(&bytes, &["MBbloom--"], UnknownModulePolicy::Fail)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(MODULE_TYPE));
+
+        let entries = load_entries(&bytes, &[MODULE_TYPE], UnknownModulePolicy::Fail)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(&entries[0].value, RedisObject::Module(m) if m.type_name == MODULE_TYPE));
+
+        // values of version 1 can not be skipped by any policy
+        for policy in [UnknownModulePolicy::Skip, UnknownModulePolicy::Fail] {
+            let err = load_entries(&rdb_bytes(6), &[MODULE_TYPE], policy)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains(MODULE_TYPE));
+        }
+    }
 }
@@ -9,6 +9,7 @@ use crate::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
         extractor_monitor::ExtractorMonitor,
+        extractor_progress::ExtractorProgress,
         redis::{
             redis_client::RedisClient,
             redis_psync_extractor::{RedisPsyncExtractor, RedisPsyncNode},
@@ -18,7 +19,10 @@ use crate::{
     Extractor,
 };
 use dt_common::{
-    config::{config_enums::ExtractType, connection_auth_config::ConnectionAuthConfig},
+    config::{
+        config_enums::ExtractType, connection_auth_config::ConnectionAuthConfig,
+        extractor_config::RedisModuleConfig,
+    },
     error::Error,
     log_info, log_warn,
     meta::{position::Position, redis::cluster_node::ClusterNode, syncer::Syncer},
@@ -39,6 +43,7 @@ pub struct RedisClusterPsyncExtractor {
     pub filter: RdbFilter,
     pub extract_type: ExtractType,
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+    pub module_config: RedisModuleConfig,
 }
 
 #[async_trait]
@@ -85,6 +90,10 @@ impl Extractor for RedisClusterPsyncExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl RedisClusterPsyncExtractor {
@@ -229,6 +238,7 @@ impl RedisClusterPsyncExtractor {
                 heartbeat_hash_tag,
             }),
             wait_task_finish: false,
+            module_config: self.module_config.clone(),
         })
     }
 
@@ -13,6 +13,7 @@ use tokio::{sync::Mutex, time::Instant};
 use super::redis_client::RedisClient;
 use crate::extractor::{
     base_extractor::{BaseExtractor, ExtractState},
+    extractor_progress::ExtractorProgress,
     redis::{
         rdb::{rdb_parser::RdbParser, reader::rdb_reader::RdbReader},
         redis_resp_types::Value,
@@ -25,6 +26,7 @@ use dt_common::{
     config::{
         config_enums::{DbType, ExtractType},
         config_token_parser::{ConfigTokenParser, TokenEscapePair},
+        extractor_config::RedisModuleConfig,
     },
     error::Error,
     log_debug, log_error, log_info, log_position, log_warn,
@@ -55,6 +57,7 @@ pub struct RedisPsyncExtractor {
     pub recovery: Option<Arc<dyn Recovery + Send + Sync>>,
     pub cluster_node: Option<RedisPsyncNode>,
     pub wait_task_finish: bool,
+    pub module_config: RedisModuleConfig,
 }
 
 #[derive(Clone)]
@@ -127,6 +130,10 @@ impl Extractor for RedisPsyncExtractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         self.conn.close().await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl RedisPsyncExtractor {
@@ -224,9 +231,11 @@ impl RedisPsyncExtractor {
             repl_stream_db_id: 0,
             now_db_id: self.now_db_id,
             expire_ms: 0,
+            expire_at_ms: 0,
             idle: 0,
             freq: 0,
             is_end: false,
+            module_config: self.module_config.clone(),
         };
 
         let version = parser.load_meta().await?;
@@ -358,7 +367,7 @@ impl RedisPsyncExtractor {
                     // 1, only the first command following MULTI be considered as data marker info.
                     // 2, data_marker will be reset following EXEC.
                     self.extract_state
-                        .refresh_and_check_data_marker(&DtData::Begin {});
+                        .refresh_and_check_data_marker(&DtData::Begin { xid: String::new() });
                     // ignore MULTI & EXEC
                     continue;
                 }
@@ -562,6 +571,7 @@ impl RedisPsyncExtractor {
         let mut conn = RedisClient::new(&self.conn.url, &self.conn.connection_auth).await?;
         let heartbeat_interval_secs = self.heartbeat_interval_secs;
         let key = key.to_string();
+        let syncer = self.syncer.clone();
 
         tokio::spawn(async move {
             // set db
@@ -577,7 +587,13 @@ impl RedisPsyncExtractor {
             let mut start_time = Instant::now();
             while !shut_down.load(Ordering::Acquire) {
                 if start_time.elapsed().as_secs() >= heartbeat_interval_secs {
-                    Self::heartbeat(&key, &mut conn).await.unwrap();
+                    if syncer
+                        .lock()
+                        .await
+                        .should_heartbeat(heartbeat_interval_secs)
+                    {
+                        Self::heartbeat(&key, &mut conn).await.unwrap();
+                    }
                     start_time = Instant::now();
                 }
                 TimeUtil::sleep_millis(1000 * heartbeat_interval_secs).await;
@@ -611,15 +627,50 @@ impl RedisPsyncExtractor {
     ) -> anyhow::Result<()> {
         // currently only support db filter
         entry.data_size = entry.get_data_malloc_size();
-        if filter.filter_schema(&entry.db_id.to_string()) {
-            extract_state.record_extracted_metrics(1, entry.data_size as u64);
-            base_extractor
-                .push_dt_data(extract_state, DtData::Heartbeat {}, position)
-                .await
+        // SWAPDB does not depend on the selected db
+        let is_swapdb = !entry.is_base && entry.cmd.get_name().eq_ignore_ascii_case("swapdb");
+        let data_size = entry.data_size;
+        let entry = if !is_swapdb && filter.filter_schema(&entry.db_id.to_string()) {
+            None
         } else {
-            base_extractor
-                .push_dt_data(extract_state, DtData::Redis { entry }, position)
-                .await
+            Self::filter_cross_db_cmd(filter, entry)
+        };
+
+        match entry {
+            Some(entry) => {
+                base_extractor
+                    .push_dt_data(extract_state, DtData::Redis { entry }, position)
+                    .await
+            }
+            None => {
+                extract_state.record_extracted_metrics(1, data_size as u64);
+                base_extractor
+                    .push_dt_data(extract_state, DtData::Heartbeat {}, position)
+                    .await
+            }
+        }
+    }
+
+    // commands touching other dbs: a key moved to a filtered db is deleted,
+    // others can not be replayed partially and are skipped
+    fn filter_cross_db_cmd(filter: &RdbFilter, mut entry: RedisEntry) -> Option<RedisEntry> {
+        if entry.is_base {
+            return Some(entry);
+        }
+        let touches_filtered_db = entry
+            .cmd
+            .get_db_arg_indexes()
+            .into_iter()
+            .any(|i| filter.filter_schema(&entry.cmd.get_str_arg(i)));
+        if !touches_filtered_db {
+            return Some(entry);
+        }
+
+        if entry.cmd.get_name().eq_ignore_ascii_case("move") {
+            entry.cmd = RedisCmd::from_args(vec![b"DEL".to_vec(), entry.cmd.args[1].clone()]);
+            return Some(entry);
         }
+        log_warn!("cmd: [{}] touches filtered dbs, skip it", entry.cmd);
+        None
     }
 }
@@ -4,7 +4,10 @@ use std::{cmp, collections::HashMap};
 use url::Url;
 
 use crate::{
-    extractor::base_extractor::{BaseExtractor, ExtractState},
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+    },
     Extractor,
 };
 use dt_common::{
@@ -34,6 +37,10 @@ impl Extractor for RedisReshardExtractor {
             .wait_task_finish(&mut self.extract_state)
             .await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl RedisReshardExtractor {
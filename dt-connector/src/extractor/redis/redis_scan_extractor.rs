@@ -16,7 +16,10 @@ use dt_common::{
 use redis::{Connection, Value};
 
 use crate::{
-    extractor::base_extractor::{BaseExtractor, ExtractState},
+    extractor::{
+        base_extractor::{BaseExtractor, ExtractState},
+        extractor_progress::ExtractorProgress,
+    },
     Extractor,
 };
 
@@ -81,6 +84,10 @@ impl Extractor for RedisScanExtractor {
             .wait_task_finish(&mut self.extract_state)
             .await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 impl RedisScanExtractor {
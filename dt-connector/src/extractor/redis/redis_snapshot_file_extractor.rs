@@ -3,16 +3,19 @@ use tokio::{fs::metadata, fs::File, io::AsyncReadExt};
 
 use super::StreamReader;
 use crate::extractor::base_extractor::{BaseExtractor, ExtractState};
+use crate::extractor::extractor_progress::ExtractorProgress;
 use crate::extractor::redis::rdb::rdb_parser::RdbParser;
 use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
 use crate::extractor::redis::redis_psync_extractor::RedisPsyncExtractor;
 use crate::Extractor;
+use dt_common::config::extractor_config::RedisModuleConfig;
 use dt_common::log_info;
 use dt_common::meta::position::Position;
 use dt_common::rdb_filter::RdbFilter;
 
 pub struct RedisSnapshotFileExtractor {
     pub file_path: String,
+    pub module_config: RedisModuleConfig,
     pub filter: RdbFilter,
     pub base_extractor: BaseExtractor,
     pub extract_state: ExtractState,
@@ -47,9 +50,11 @@ impl Extractor for RedisSnapshotFileExtractor {
             repl_stream_db_id: 0,
             now_db_id: 0,
             expire_ms: 0,
+            expire_at_ms: 0,
             idle: 0,
             freq: 0,
             is_end: false,
+            module_config: self.module_config.clone(),
         };
 
         let version = parser.load_meta().await?;
@@ -79,6 +84,10 @@ impl Extractor for RedisSnapshotFileExtractor {
             .wait_task_finish(&mut self.extract_state)
             .await
     }
+
+    fn progress(&self) -> Option<ExtractorProgress> {
+        Some(self.base_extractor.progress.clone())
+    }
 }
 
 #[async_trait]
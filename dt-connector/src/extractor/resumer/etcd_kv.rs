@@ -0,0 +1,154 @@
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{header, Client};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::extractor::resumer::EtcdResumerConn;
+use dt_common::config::connection_auth_config::ConnectionAuthConfig;
+
+/// Minimal kv client over the json gateway of etcd v3, e.g. http://127.0.0.1:2379,
+/// keys and values are base64 encoded in requests and responses.
+pub struct EtcdKv {
+    client: Client,
+    url: String,
+    // token of etcd auth, if username is configured
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RangeResponse {
+    #[serde(default)]
+    kvs: Vec<KeyValue>,
+}
+
+#[derive(Deserialize)]
+struct KeyValue {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct AuthResponse {
+    token: String,
+}
+
+impl EtcdKv {
+    pub async fn new(conn: &EtcdResumerConn) -> Result<Self> {
+        let mut kv = Self {
+            client: Client::new(),
+            url: conn.url.trim_end_matches('/').to_string(),
+            token: None,
+        };
+        kv.authenticate(&conn.connection_auth).await?;
+        Ok(kv)
+    }
+
+    async fn authenticate(&mut self, connection_auth: &ConnectionAuthConfig) -> Result<()> {
+        let (username, password) = match connection_auth {
+            ConnectionAuthConfig::Basic { username, password }
+            | ConnectionAuthConfig::BasicSsl {
+                username: Some(username),
+                password,
+                ..
+            } => (username, password.clone().unwrap_or_default()),
+            _ => return Ok(()),
+        };
+        let body = json!({ "name": username, "password": password });
+        let response: AuthResponse = serde_json::from_value(
+            self.post("/v3/auth/authenticate", body)
+                .await
+                .context("failed to authenticate to etcd")?,
+        )?;
+        self.token = Some(response.token);
+        Ok(())
+    }
+
+    pub async fn put(&self, key: &str, value: &str) -> Result<()> {
+        let body = json!({ "key": STANDARD.encode(key), "value": STANDARD.encode(value) });
+        self.post("/v3/kv/put", body)
+            .await
+            .with_context(|| format!("failed to put etcd key: {}", key))?;
+        Ok(())
+    }
+
+    /// (key, value) of all keys under prefix
+    pub async fn get_prefix(&self, prefix: &str) -> Result<Vec<(String, String)>> {
+        let body = json!({
+            "key": STANDARD.encode(prefix),
+            "range_end": STANDARD.encode(Self::prefix_end(prefix)),
+        });
+        let response: RangeResponse = serde_json::from_value(
+            self.post("/v3/kv/range", body)
+                .await
+                .with_context(|| format!("failed to get etcd keys with prefix: {}", prefix))?,
+        )?;
+
+        let decode = |s: &str| -> Result<String> { Ok(String::from_utf8(STANDARD.decode(s)?)?) };
+        response
+            .kvs
+            .iter()
+            .map(|kv| Ok((decode(&kv.key)?, decode(&kv.value)?)))
+            .collect()
+    }
+
+    pub async fn delete_prefix(&self, prefix: &str) -> Result<()> {
+        let body = json!({
+            "key": STANDARD.encode(prefix),
+            "range_end": STANDARD.encode(Self::prefix_end(prefix)),
+        });
+        self.post("/v3/kv/deleterange", body)
+            .await
+            .with_context(|| format!("failed to delete etcd keys with prefix: {}", prefix))?;
+        Ok(())
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value> {
+        let mut request = self
+            .client
+            .post(format!("{}{}", self.url, path))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(token) = &self.token {
+            request = request.header(header::AUTHORIZATION, token);
+        }
+
+        let response = request.send().await?;
+        let status_code = response.status();
+        let response_text = response.text().await?;
+        if !status_code.is_success() {
+            bail!(
+                "etcd request {} failed, status_code: {}, response_text: {:?}",
+                path,
+                status_code,
+                response_text
+            );
+        }
+        Ok(serde_json::from_str(&response_text)?)
+    }
+
+    // the smallest key greater than all keys with the prefix
+    fn prefix_end(prefix: &str) -> Vec<u8> {
+        let mut end = prefix.as_bytes().to_vec();
+        while let Some(last) = end.pop() {
+            if last < u8::MAX {
+                end.push(last + 1);
+                return end;
+            }
+        }
+        // all keys
+        vec![0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_end() {
+        assert_eq!(EtcdKv::prefix_end("a/b/"), b"a/b0".to_vec());
+        assert_eq!(EtcdKv::prefix_end(""), vec![0]);
+    }
+}
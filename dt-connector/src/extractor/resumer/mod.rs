@@ -18,8 +18,10 @@ use dt_common::{
     log_info, log_warn,
     meta::position::Position,
 };
+pub mod etcd_kv;
 pub mod recorder;
 pub mod recovery;
+pub mod snapshot_manifest;
 pub mod utils;
 
 const CURRENT_POSITION_LOG_FLAG: &str = "| current_position |";
@@ -34,6 +36,7 @@ pub enum ResumerDbPool {
     Postgres(Pool<Postgres>),
     Mongo(Client),
     Redis(RedisResumerConn),
+    Etcd(EtcdResumerConn),
 }
 
 #[derive(Clone, Debug)]
@@ -44,6 +47,12 @@ pub struct RedisResumerConn {
     pub hash_tag: Option<String>,
 }
 
+#[derive(Clone, Debug)]
+pub struct EtcdResumerConn {
+    pub url: String,
+    pub connection_auth: ConnectionAuthConfig,
+}
+
 #[derive(Clone, Display, EnumString, IntoStaticStr, Debug, PartialEq, Eq)]
 pub enum ResumerType {
     SnapshotDoing,
@@ -8,6 +8,7 @@ use mongodb::{
 use sqlx::query;
 
 use crate::extractor::resumer::{
+    etcd_kv::EtcdKv,
     recorder::Recorder,
     utils::{RedisResumerRecord, ResumerUtil},
     ResumerDbPool, ResumerType,
@@ -228,6 +229,14 @@ impl DatabaseRecorder {
                 }
                 Ok(())
             }
+            ResumerDbPool::Etcd(etcd_conn) => {
+                if is_init {
+                    let prefix =
+                        ResumerUtil::get_etcd_resumer_prefix(&self.schema, &self.table, task_id);
+                    EtcdKv::new(etcd_conn).await?.delete_prefix(&prefix).await?;
+                }
+                Ok(())
+            }
         }
     }
 
@@ -360,6 +369,24 @@ impl Recorder for DatabaseRecorder {
                     .with_context(|| format!("failed to set Redis resumer key: {}", key))?;
                 Ok(())
             }
+            ResumerDbPool::Etcd(etcd_conn) => {
+                let position_key = ResumerUtil::get_key_from_position(position);
+                let key = ResumerUtil::get_etcd_resumer_key(
+                    &self.schema,
+                    &self.table,
+                    &self.task_id,
+                    &resumer_type.to_string(),
+                    &position_key,
+                );
+                let record = RedisResumerRecord {
+                    resumer_type: resumer_type.to_string(),
+                    position_key,
+                    position_data: position.to_string(),
+                };
+                let value = serde_json::to_string(&record)
+                    .context("failed to serialize etcd resumer record")?;
+                EtcdKv::new(etcd_conn).await?.put(&key, &value).await
+            }
         }
     }
 }
@@ -8,6 +8,7 @@ use mongodb::bson::doc;
 use sqlx::{query, Error, Row};
 
 use crate::extractor::resumer::{
+    etcd_kv::EtcdKv,
     recovery::Recovery,
     utils::{RedisResumerRecord, ResumerUtil},
     ResumerDbPool, ResumerType,
@@ -258,6 +259,21 @@ impl DatabaseRecovery {
                     );
                 }
             }
+            ResumerDbPool::Etcd(etcd_conn) => {
+                let prefix =
+                    ResumerUtil::get_etcd_resumer_prefix(&self.schema, &self.table, &self.task_id);
+                for (key, value) in EtcdKv::new(etcd_conn).await?.get_prefix(&prefix).await? {
+                    let record: RedisResumerRecord =
+                        serde_json::from_str(&value).with_context(|| {
+                            format!("failed to parse etcd resumer value for key: {}", key)
+                        })?;
+                    self.cache_resumer_record(
+                        &record.resumer_type,
+                        record.position_key,
+                        Some(record.position_data),
+                    );
+                }
+            }
         }
         Ok(())
     }
@@ -0,0 +1,164 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use dt_common::log_info;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TbSnapshotStatus {
+    Pending,
+    InProgress,
+    Done,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+    schema: String,
+    tb: String,
+    status: TbSnapshotStatus,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ManifestFile {
+    tables: Vec<ManifestEntry>,
+}
+
+/// Progress of the tables in a snapshot task, persisted to disk so that a restarted task
+/// skips the tables already done.
+pub struct SnapshotManifest {
+    path: String,
+    tbs: DashMap<(String, String), TbSnapshotStatus>,
+    dirty: AtomicBool,
+}
+
+impl SnapshotManifest {
+    pub async fn load(path: &str) -> anyhow::Result<Self> {
+        let tbs = DashMap::new();
+        if Path::new(path).exists() {
+            let content = fs::read_to_string(path).await?;
+            let manifest: ManifestFile = serde_json::from_str(&content)
+                .map_err(|err| anyhow::anyhow!("invalid snapshot manifest {}: {}", path, err))?;
+            for entry in manifest.tables {
+                tbs.insert((entry.schema, entry.tb), entry.status);
+            }
+            log_info!("loaded snapshot manifest: {}, tables: {}", path, tbs.len());
+        }
+
+        Ok(Self {
+            path: path.to_string(),
+            tbs,
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    pub fn is_done(&self, schema: &str, tb: &str) -> bool {
+        self.get_status(schema, tb) == Some(TbSnapshotStatus::Done)
+    }
+
+    pub fn get_status(&self, schema: &str, tb: &str) -> Option<TbSnapshotStatus> {
+        self.tbs
+            .get(&(schema.to_string(), tb.to_string()))
+            .map(|status| *status)
+    }
+
+    /// Tables interrupted in a previous run keep their in_progress status.
+    pub fn add_pending(&self, schema: &str, tb: &str) {
+        let key = (schema.to_string(), tb.to_string());
+        if !self.tbs.contains_key(&key) {
+            self.tbs.insert(key, TbSnapshotStatus::Pending);
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    pub fn mark_in_progress(&self, schema: &str, tb: &str) {
+        if self.get_status(schema, tb) == Some(TbSnapshotStatus::Pending) {
+            self.set_status(schema, tb, TbSnapshotStatus::InProgress);
+        }
+    }
+
+    pub fn mark_done(&self, schema: &str, tb: &str) {
+        self.set_status(schema, tb, TbSnapshotStatus::Done);
+    }
+
+    fn set_status(&self, schema: &str, tb: &str, status: TbSnapshotStatus) {
+        self.tbs
+            .insert((schema.to_string(), tb.to_string()), status);
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    pub async fn persist(&self) -> anyhow::Result<()> {
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
+
+        let mut tables: Vec<ManifestEntry> = self
+            .tbs
+            .iter()
+            .map(|entry| ManifestEntry {
+                schema: entry.key().0.clone(),
+                tb: entry.key().1.clone(),
+                status: *entry.value(),
+            })
+            .collect();
+        tables.sort_by(|a, b| (&a.schema, &a.tb).cmp(&(&b.schema, &b.tb)));
+        let content = serde_json::to_string_pretty(&ManifestFile { tables })?;
+
+        // write to a temp file first so that a crash never leaves a truncated manifest
+        let tmp_path = format!("{}.tmp", self.path);
+        if let Err(err) = async {
+            fs::write(&tmp_path, content).await?;
+            fs::rename(&tmp_path, &self.path).await
+        }
+        .await
+        {
+            self.dirty.store(true, Ordering::Release);
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshot_manifest() {
+        let path =
+            std::env::temp_dir().join(format!("snapshot_manifest_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let manifest = SnapshotManifest::load(path).await.unwrap();
+        manifest.add_pending("db_1", "tb_1");
+        manifest.add_pending("db_1", "tb_2");
+        manifest.add_pending("db_1", "tb_3");
+        manifest.mark_in_progress("db_1", "tb_1");
+        manifest.mark_in_progress("db_1", "tb_2");
+        manifest.mark_done("db_1", "tb_2");
+        manifest.persist().await.unwrap();
+
+        let manifest = SnapshotManifest::load(path).await.unwrap();
+        manifest.add_pending("db_1", "tb_1");
+        assert_eq!(
+            manifest.get_status("db_1", "tb_1"),
+            Some(TbSnapshotStatus::InProgress)
+        );
+        assert!(manifest.is_done("db_1", "tb_2"));
+        assert_eq!(
+            manifest.get_status("db_1", "tb_3"),
+            Some(TbSnapshotStatus::Pending)
+        );
+        // done tables are never reverted
+        manifest.mark_in_progress("db_1", "tb_2");
+        assert!(manifest.is_done("db_1", "tb_2"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -11,8 +11,8 @@ use sqlx::{
 use url::Url;
 
 use crate::extractor::resumer::{
-    RedisResumerConn, ResumerDbPool, ResumerType, DEFAULT_POSITION_KEY, DEFAULT_RESUMER_SCHEMA,
-    DEFAULT_RESUMER_TABLE,
+    EtcdResumerConn, RedisResumerConn, ResumerDbPool, ResumerType, DEFAULT_POSITION_KEY,
+    DEFAULT_RESUMER_SCHEMA, DEFAULT_RESUMER_TABLE,
 };
 use dt_common::{
     config::{config_enums::DbType, connection_auth_config::ConnectionAuthConfig},
@@ -27,6 +27,7 @@ pub struct ResumerUtil {}
 const REDIS_RESUMER_KEY_PREFIX: &str = "apedts:resumer";
 const REDIS_SCAN_COUNT: usize = 1000;
 
+// value of a position key in Redis and etcd
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct RedisResumerRecord {
     pub resumer_type: String,
@@ -157,6 +158,10 @@ impl ResumerUtil {
                     }))
                 }
             }
+            DbType::Etcd => Ok(ResumerDbPool::Etcd(EtcdResumerConn {
+                url: url.to_string(),
+                connection_auth: connection_auth.clone(),
+            })),
             _ => {
                 bail!(
                     "unsupported database type for DatabaseRecorder: {:?}",
@@ -208,6 +213,26 @@ impl ResumerUtil {
         }
     }
 
+    /// positions of a task are kept under /{schema}/{table}/{task_id}/ of table_full_name
+    pub fn get_etcd_resumer_prefix(schema: &str, table: &str, task_id: &str) -> String {
+        format!("/{}/{}/{}/", schema, table, task_id)
+    }
+
+    pub fn get_etcd_resumer_key(
+        schema: &str,
+        table: &str,
+        task_id: &str,
+        resumer_type: &str,
+        position_key: &str,
+    ) -> String {
+        format!(
+            "{}{}/{}",
+            Self::get_etcd_resumer_prefix(schema, table, task_id),
+            resumer_type,
+            position_key
+        )
+    }
+
     pub fn scan_redis_keys(conn: &mut Connection, pattern: &str) -> Result<Vec<String>> {
         let mut cursor = 0_u64;
         let mut keys = Vec::new();
@@ -240,6 +265,10 @@ impl ResumerUtil {
             } => {
                 format!("{}-{}", topic, partition)
             }
+            // sources of a multi-source task are resumed separately
+            Position::MysqlCdc { source_id, .. } if !source_id.is_empty() => {
+                format!("mysql-source-{}", source_id)
+            }
             Position::Redis {
                 node_id, address, ..
             } => {
@@ -269,7 +298,7 @@ impl ResumerUtil {
 #[cfg(test)]
 mod tests {
     use crate::extractor::resumer::{
-        utils::ResumerUtil, DEFAULT_RESUMER_SCHEMA, DEFAULT_RESUMER_TABLE,
+        utils::ResumerUtil, DEFAULT_POSITION_KEY, DEFAULT_RESUMER_SCHEMA, DEFAULT_RESUMER_TABLE,
     };
     use dt_common::meta::position::Position;
 
@@ -320,6 +349,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mysql_source_position_uses_source_id_as_resumer_key() {
+        let mut position = Position::MysqlCdc {
+            server_id: "1".to_string(),
+            binlog_filename: "mysql-bin.000001".to_string(),
+            next_event_position: 157,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+            source_id: "src_2".to_string(),
+        };
+        assert_eq!(
+            ResumerUtil::get_key_from_position(&position),
+            "mysql-source-src_2"
+        );
+
+        if let Position::MysqlCdc { source_id, .. } = &mut position {
+            source_id.clear();
+        }
+        assert_eq!(
+            ResumerUtil::get_key_from_position(&position),
+            DEFAULT_POSITION_KEY
+        );
+    }
+
     #[test]
     fn redis_resumer_key_uses_hash_tag_for_cluster() {
         assert_eq!(
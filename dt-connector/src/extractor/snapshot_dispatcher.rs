@@ -1,13 +1,20 @@
-use std::{collections::VecDeque, future::Future, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::Arc,
+};
 
 use anyhow::{anyhow, bail};
 use tokio::task::JoinSet;
 
-use dt_common::monitor::task_monitor_handle::TaskMonitorHandle;
+use dt_common::{
+    config::config_enums::SnapshotTableOrder, monitor::task_monitor_handle::TaskMonitorHandle,
+};
 
 use super::{
     base_extractor::ExtractState,
     extractor_monitor::{ExtractorCounters, ExtractorMonitor},
+    snapshot_types::SnapshotTableId,
 };
 
 pub struct TableMonitorGuard {
@@ -152,4 +159,59 @@ impl SnapshotDispatcher {
 
         (table_state, guard)
     }
+
+    /// Sorts tables by estimated size, tables without statistics are treated as empty.
+    /// Ties are ordered by name so that the schedule is stable between runs.
+    pub fn order_tables(
+        tables: &mut [SnapshotTableId],
+        sizes: &HashMap<SnapshotTableId, u64>,
+        order: &SnapshotTableOrder,
+    ) {
+        let size_of = |table: &SnapshotTableId| sizes.get(table).copied().unwrap_or_default();
+        let by_name =
+            |a: &SnapshotTableId, b: &SnapshotTableId| (&a.schema, &a.tb).cmp(&(&b.schema, &b.tb));
+        match order {
+            SnapshotTableOrder::None => {}
+            SnapshotTableOrder::SizeDesc => {
+                tables.sort_by(|a, b| size_of(b).cmp(&size_of(a)).then_with(|| by_name(a, b)))
+            }
+            SnapshotTableOrder::SizeAsc => {
+                tables.sort_by(|a, b| size_of(a).cmp(&size_of(b)).then_with(|| by_name(a, b)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::config::config_enums::SnapshotTableOrder;
+
+    use super::SnapshotDispatcher;
+    use crate::extractor::snapshot_types::SnapshotTableId;
+
+    fn table(tb: &str) -> SnapshotTableId {
+        SnapshotTableId {
+            schema: "db".to_string(),
+            tb: tb.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_order_tables() {
+        let sizes = HashMap::from([(table("a"), 10), (table("b"), 300), (table("c"), 20)]);
+        let names =
+            |tables: &[SnapshotTableId]| tables.iter().map(|t| t.tb.clone()).collect::<Vec<_>>();
+
+        let mut tables = vec![table("d"), table("a"), table("b"), table("c")];
+        SnapshotDispatcher::order_tables(&mut tables, &sizes, &SnapshotTableOrder::SizeDesc);
+        assert_eq!(names(&tables), vec!["b", "c", "a", "d"]);
+
+        SnapshotDispatcher::order_tables(&mut tables, &sizes, &SnapshotTableOrder::SizeAsc);
+        assert_eq!(names(&tables), vec!["d", "a", "c", "b"]);
+
+        SnapshotDispatcher::order_tables(&mut tables, &sizes, &SnapshotTableOrder::None);
+        assert_eq!(names(&tables), vec!["d", "a", "c", "b"]);
+    }
 }
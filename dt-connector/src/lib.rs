@@ -14,9 +14,10 @@ pub mod sinker;
 use async_trait::async_trait;
 use checker::check_log::CheckLog;
 use dt_common::meta::{
-    dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem, row_data::RowData,
-    struct_meta::struct_data::StructData,
+    dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem, position::Position,
+    row_data::RowData, struct_meta::struct_data::StructData,
 };
+use extractor::extractor_progress::ExtractorProgress;
 #[async_trait]
 pub trait Sinker {
     async fn sink_dml(&mut self, mut _data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
@@ -51,6 +52,22 @@ pub trait Sinker {
         Ok(())
     }
 
+    // emit the end-of-snapshot watermark of a table (Position::RdbSnapshotFinished) downstream,
+    // called on one sinker only
+    async fn sink_snapshot_watermark(&mut self, _position: &Position) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    // rows sunk between begin_trx and commit_trx are applied in one target transaction,
+    // refer to [pipeline] transactional
+    async fn begin_trx(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn commit_trx(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     fn get_id(&self) -> String {
         String::new()
     }
@@ -63,6 +80,21 @@ pub trait Extractor {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// shared handle of the read position, take it before extract() to query while running
+    fn progress(&self) -> Option<ExtractorProgress> {
+        None
+    }
+
+    fn current_position(&self) -> Position {
+        self.progress()
+            .map(|progress| progress.position())
+            .unwrap_or_default()
+    }
+
+    fn lag_millis(&self) -> Option<u64> {
+        self.progress().and_then(|progress| progress.lag_millis())
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,67 @@
+use std::collections::{HashMap, HashSet};
+
+/// Orders items so that each comes after the items it depends on, e.g. views selecting from
+/// other views. Dependencies not in items are ignored, and the original order is kept otherwise.
+pub fn sort_by_dependencies<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> String,
+    deps: &HashMap<String, HashSet<String>>,
+) -> Vec<T> {
+    let keys: Vec<String> = items.iter().map(&key).collect();
+    let index_of: HashMap<&str, usize> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, k)| (k.as_str(), i))
+        .collect();
+
+    let mut order = Vec::with_capacity(items.len());
+    let mut visited = vec![false; items.len()];
+    for i in 0..items.len() {
+        // iterative dfs, pushes an item once all its dependencies are pushed
+        let mut stack = vec![(i, false)];
+        while let Some((j, deps_pushed)) = stack.pop() {
+            if deps_pushed {
+                order.push(j);
+                continue;
+            }
+            if visited[j] {
+                continue;
+            }
+            visited[j] = true;
+            stack.push((j, true));
+            if let Some(item_deps) = deps.get(&keys[j]) {
+                let mut dep_indexes: Vec<usize> = item_deps
+                    .iter()
+                    .filter_map(|dep| index_of.get(dep.as_str()).copied())
+                    .filter(|&k| !visited[k])
+                    .collect();
+                dep_indexes.sort_unstable_by(|a, b| b.cmp(a));
+                stack.extend(dep_indexes.into_iter().map(|k| (k, false)));
+            }
+        }
+    }
+
+    let mut items: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| items[i].take()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_by_dependencies() {
+        let items = vec!["v_3", "v_1", "v_2", "v_4"];
+        let deps = HashMap::from([
+            ("v_3".to_string(), HashSet::from(["v_2".to_string()])),
+            (
+                "v_2".to_string(),
+                HashSet::from(["v_1".to_string(), "tb_1".to_string()]),
+            ),
+            // cycles do not loop forever
+            ("v_4".to_string(), HashSet::from(["v_4".to_string()])),
+        ]);
+        let sorted = sort_by_dependencies(items, |s| s.to_string(), &deps);
+        assert_eq!(sorted, vec!["v_1", "v_2", "v_3", "v_4"]);
+    }
+}
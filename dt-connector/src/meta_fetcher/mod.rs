@@ -1,3 +1,4 @@
+pub mod dependency_sorter;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
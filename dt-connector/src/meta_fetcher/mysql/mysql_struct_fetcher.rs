@@ -10,26 +10,39 @@ use sqlx::{mysql::MySqlRow, MySql, Pool, Row};
 use dt_common::{
     config::config_enums::DbType,
     error::Error,
+    log_warn,
     meta::{
-        mysql::{mysql_col_type::MysqlColType, mysql_meta_manager::MysqlMetaManager},
+        mysql::{
+            mysql_col_type::MysqlColType, mysql_meta_manager::MysqlMetaManager,
+            mysql_tb_meta::MysqlTbMeta,
+        },
         struct_meta::{
             statement::{
                 mysql_create_database_statement::MysqlCreateDatabaseStatement,
+                mysql_create_object_statement::MysqlCreateObjectStatement,
                 mysql_create_table_statement::MysqlCreateTableStatement,
             },
             structure::{
                 column::{Column, ColumnDefault},
                 constraint::{Constraint, ConstraintType},
                 database::Database,
+                db_object::{
+                    MysqlDbObject, MysqlRoutine, MysqlRoutineParam, MysqlTrigger, MysqlView,
+                },
                 index::{Index, IndexColumn, IndexKind, IndexType},
                 table::Table,
             },
         },
     },
+    quote_mysql,
     rdb_filter::RdbFilter,
     utils::sql_util::SqlUtil,
 };
 
+use crate::meta_fetcher::dependency_sorter;
+
+use quote_mysql as quote;
+
 pub struct MysqlStructFetcher {
     pub conn_pool: Pool<MySql>,
     pub dbs: HashSet<String>,
@@ -74,6 +87,174 @@ impl MysqlStructFetcher {
         Ok(results)
     }
 
+    /// views of self.dbs, each after the views it selects from
+    pub async fn get_create_view_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<MysqlCreateObjectStatement>> {
+        if self.dbs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME, VIEW_DEFINITION, CHECK_OPTION, SECURITY_TYPE
+            FROM information_schema.views
+            WHERE TABLE_SCHEMA IN ({})
+            ORDER BY TABLE_SCHEMA, TABLE_NAME",
+            self.get_dbs_str()
+        );
+        let mut views = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            views.push(MysqlView {
+                database_name: Self::get_str_with_null(&row, "TABLE_SCHEMA")?,
+                view_name: Self::get_str_with_null(&row, "TABLE_NAME")?,
+                definition: Self::get_str_with_null(&row, "VIEW_DEFINITION")?,
+                check_option: Self::get_str_with_null(&row, "CHECK_OPTION")?,
+                security_type: Self::get_str_with_null(&row, "SECURITY_TYPE")?,
+            });
+        }
+
+        // definitions keep qualified names like `db`.`tb`, which tell the views referenced
+        let view_key =
+            |v: &MysqlView| format!("{}.{}", quote!(v.database_name), quote!(v.view_name));
+        let keys: Vec<String> = views.iter().map(view_key).collect();
+        let mut deps = HashMap::new();
+        for view in views.iter() {
+            let view_deps: HashSet<String> = keys
+                .iter()
+                .filter(|k| view.definition.contains(k.as_str()))
+                .cloned()
+                .collect();
+            deps.insert(view_key(view), view_deps);
+        }
+
+        Ok(
+            dependency_sorter::sort_by_dependencies(views, view_key, &deps)
+                .into_iter()
+                .map(|v| MysqlCreateObjectStatement {
+                    object: MysqlDbObject::View(v),
+                })
+                .collect(),
+        )
+    }
+
+    /// procedures and functions of self.dbs
+    pub async fn get_create_routine_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<MysqlCreateObjectStatement>> {
+        if self.dbs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT SPECIFIC_SCHEMA, SPECIFIC_NAME, ROUTINE_TYPE, PARAMETER_MODE, PARAMETER_NAME, DTD_IDENTIFIER
+            FROM information_schema.parameters
+            WHERE SPECIFIC_SCHEMA IN ({}) AND ORDINAL_POSITION > 0
+            ORDER BY SPECIFIC_SCHEMA, SPECIFIC_NAME, ORDINAL_POSITION",
+            self.get_dbs_str()
+        );
+        let mut params: HashMap<(String, String, String), Vec<MysqlRoutineParam>> = HashMap::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let key = (
+                Self::get_str_with_null(&row, "SPECIFIC_SCHEMA")?,
+                Self::get_str_with_null(&row, "SPECIFIC_NAME")?,
+                Self::get_str_with_null(&row, "ROUTINE_TYPE")?,
+            );
+            params.entry(key).or_default().push(MysqlRoutineParam {
+                mode: Self::get_str_with_null(&row, "PARAMETER_MODE")?,
+                name: Self::get_str_with_null(&row, "PARAMETER_NAME")?,
+                data_type: Self::get_str_with_null(&row, "DTD_IDENTIFIER")?,
+            });
+        }
+
+        let sql = format!(
+            "SELECT ROUTINE_SCHEMA, ROUTINE_NAME, ROUTINE_TYPE, DTD_IDENTIFIER, ROUTINE_DEFINITION,
+                IS_DETERMINISTIC, SQL_DATA_ACCESS, SECURITY_TYPE, ROUTINE_COMMENT
+            FROM information_schema.routines
+            WHERE ROUTINE_SCHEMA IN ({})
+            ORDER BY ROUTINE_SCHEMA, ROUTINE_TYPE, ROUTINE_NAME",
+            self.get_dbs_str()
+        );
+        let mut results = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let key = (
+                Self::get_str_with_null(&row, "ROUTINE_SCHEMA")?,
+                Self::get_str_with_null(&row, "ROUTINE_NAME")?,
+                Self::get_str_with_null(&row, "ROUTINE_TYPE")?,
+            );
+            let definition = Self::get_str_with_null(&row, "ROUTINE_DEFINITION")?;
+            if definition.is_empty() {
+                // only visible to the definer or users with SHOW_ROUTINE / SELECT on mysql.proc
+                log_warn!(
+                    "definition of {} {}.{} is not visible, skipped",
+                    key.2,
+                    key.0,
+                    key.1
+                );
+                continue;
+            }
+
+            let returns = if key.2 == "FUNCTION" {
+                Self::get_str_with_null(&row, "DTD_IDENTIFIER")?
+            } else {
+                String::new()
+            };
+            results.push(MysqlCreateObjectStatement {
+                object: MysqlDbObject::Routine(MysqlRoutine {
+                    params: params.remove(&key).unwrap_or_default(),
+                    database_name: key.0,
+                    routine_name: key.1,
+                    routine_type: key.2,
+                    returns,
+                    definition,
+                    is_deterministic: Self::get_str_with_null(&row, "IS_DETERMINISTIC")? == "YES",
+                    sql_data_access: Self::get_str_with_null(&row, "SQL_DATA_ACCESS")?,
+                    security_type: Self::get_str_with_null(&row, "SECURITY_TYPE")?,
+                    comment: Self::get_str_with_null(&row, "ROUTINE_COMMENT")?,
+                }),
+            });
+        }
+        Ok(results)
+    }
+
+    /// triggers of tables not filtered, in their action order
+    pub async fn get_create_trigger_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<MysqlCreateObjectStatement>> {
+        if self.dbs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT TRIGGER_SCHEMA, TRIGGER_NAME, EVENT_OBJECT_TABLE, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT
+            FROM information_schema.triggers
+            WHERE TRIGGER_SCHEMA IN ({})
+            ORDER BY TRIGGER_SCHEMA, EVENT_OBJECT_TABLE, ACTION_TIMING, EVENT_MANIPULATION, ACTION_ORDER",
+            self.get_dbs_str()
+        );
+        let mut results = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let trigger = MysqlTrigger {
+                database_name: Self::get_str_with_null(&row, "TRIGGER_SCHEMA")?,
+                trigger_name: Self::get_str_with_null(&row, "TRIGGER_NAME")?,
+                table_name: Self::get_str_with_null(&row, "EVENT_OBJECT_TABLE")?,
+                action_timing: Self::get_str_with_null(&row, "ACTION_TIMING")?,
+                event_manipulation: Self::get_str_with_null(&row, "EVENT_MANIPULATION")?,
+                action_statement: Self::get_str_with_null(&row, "ACTION_STATEMENT")?,
+            };
+            if self.filter_tb(&trigger.database_name, &trigger.table_name) {
+                continue;
+            }
+            results.push(MysqlCreateObjectStatement {
+                object: MysqlDbObject::Trigger(trigger),
+            });
+        }
+        Ok(results)
+    }
+
     // Create Database: https://dev.mysql.com/doc/refman/8.0/en/create-database.html
     async fn get_databases(&mut self, db: &str) -> anyhow::Result<Vec<Database>> {
         let (db_filter, target_dbs) = if !db.is_empty() {
@@ -164,6 +345,7 @@ impl MysqlStructFetcher {
                 t.ENGINE,
                 t.TABLE_COMMENT,
                 t.TABLE_COLLATION,
+                t.CREATE_OPTIONS,
                 c.COLUMN_NAME,
                 c.ORDINAL_POSITION,
                 c.COLUMN_DEFAULT,
@@ -171,6 +353,7 @@ impl MysqlStructFetcher {
                 c.COLUMN_TYPE,
                 c.COLUMN_KEY,
                 c.EXTRA,
+                c.GENERATION_EXPRESSION,
                 c.COLUMN_COMMENT,
                 c.CHARACTER_SET_NAME,
                 c.COLLATION_NAME
@@ -200,6 +383,11 @@ impl MysqlStructFetcher {
             let extra = Self::get_str_with_null(&row, "EXTRA")?;
             let column_name = Self::get_str_with_null(&row, "COLUMN_NAME")?;
             let column_type = Self::get_str_with_null(&row, "COLUMN_TYPE")?;
+            // 8.0 escapes quotes in GENERATION_EXPRESSION, e.g. concat(`a`,_utf8mb4\' \',`b`)
+            let generated = MysqlTbMeta::is_generated_extra(&extra)
+                .then(|| Self::get_str_with_null(&row, "GENERATION_EXPRESSION"))
+                .transpose()?
+                .map(|expr| expr.replace("\\'", "'"));
             let column_default = if let Some(column_default_str) =
                 SqlUtil::try_get_mysql_optional_string(&row, "COLUMN_DEFAULT")?
             {
@@ -221,7 +409,7 @@ impl MysqlStructFetcher {
                 column_comment: Self::get_str_with_null(&row, "COLUMN_COMMENT")?,
                 character_set_name: Self::get_str_with_null(&row, "CHARACTER_SET_NAME")?,
                 collation_name: Self::get_str_with_null(&row, "COLLATION_NAME")?,
-                generated: None,
+                generated,
             };
 
             let key = (db.clone(), tb.clone());
@@ -230,6 +418,8 @@ impl MysqlStructFetcher {
             } else {
                 let table_collation = Self::get_str_with_null(&row, "TABLE_COLLATION")?;
                 let charset = Self::get_charset_by_collation(&table_collation);
+                let (row_format, key_block_size) =
+                    Self::parse_create_options(&Self::get_str_with_null(&row, "CREATE_OPTIONS")?);
                 results.insert(
                     key,
                     Table {
@@ -240,6 +430,8 @@ impl MysqlStructFetcher {
                         table_comment,
                         character_set: charset,
                         table_collation,
+                        row_format,
+                        key_block_size,
                         columns: vec![column],
                     },
                 );
@@ -249,6 +441,23 @@ impl MysqlStructFetcher {
         Ok(results)
     }
 
+    // CREATE_OPTIONS holds options explicitly specified in CREATE TABLE,
+    // e.g. row_format=COMPRESSED KEY_BLOCK_SIZE=8 partitioned
+    fn parse_create_options(create_options: &str) -> (String, String) {
+        let (mut row_format, mut key_block_size) = (String::new(), String::new());
+        for option in create_options.split_whitespace() {
+            let Some((name, value)) = option.split_once('=') else {
+                continue;
+            };
+            if name.eq_ignore_ascii_case("row_format") {
+                row_format = value.to_uppercase();
+            } else if name.eq_ignore_ascii_case("key_block_size") {
+                key_block_size = value.to_string();
+            }
+        }
+        (row_format, key_block_size)
+    }
+
     async fn parse_column_default(
         &mut self,
         col_type: &str,
@@ -428,6 +637,13 @@ impl MysqlStructFetcher {
             return Ok(results);
         };
 
+        // table_constraints.ENFORCED was introduced with check_constraints in MySQL 8.0.16,
+        // but mariadb has check_constraints only
+        let enforced_col = if self.has_table_constraints_enforced().await? {
+            "tc.ENFORCED"
+        } else {
+            "'YES' AS ENFORCED"
+        };
         let constraint_type_str = ConstraintType::Check.to_str(DbType::Mysql);
         let sql = format!(
             "SELECT
@@ -435,13 +651,14 @@ impl MysqlStructFetcher {
                 tc.TABLE_NAME,
                 tc.CONSTRAINT_NAME,
                 tc.CONSTRAINT_TYPE,
+                {},
                 cc.CHECK_CLAUSE 
             FROM information_schema.table_constraints tc 
             LEFT JOIN information_schema.check_constraints cc 
             ON tc.CONSTRAINT_SCHEMA = cc.CONSTRAINT_SCHEMA AND tc.CONSTRAINT_NAME = cc.CONSTRAINT_NAME 
             WHERE {} 
             AND tc.CONSTRAINT_TYPE='{}' ", 
-            tb_filter, constraint_type_str
+            enforced_col, tb_filter, constraint_type_str
         );
 
         let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
@@ -450,7 +667,10 @@ impl MysqlStructFetcher {
             let table_name = Self::get_str_with_null(&row, "TABLE_NAME")?;
             let constraint_name = Self::get_str_with_null(&row, "CONSTRAINT_NAME")?;
             let check_clause = Self::get_str_with_null(&row, "CHECK_CLAUSE")?;
-            let definition = self.unescape(check_clause).await?;
+            let mut definition = self.unescape(check_clause).await?;
+            if Self::get_str_with_null(&row, "ENFORCED")?.eq_ignore_ascii_case("NO") {
+                definition.push_str(" NOT ENFORCED");
+            }
             let constraint = Constraint {
                 database_name: database_name.clone(),
                 schema_name: String::new(),
@@ -521,8 +741,11 @@ impl MysqlStructFetcher {
             let referenced_table_name = Self::get_str_with_null(&row, "REFERENCED_TABLE_NAME")?;
             let referenced_column_name = Self::get_str_with_null(&row, "REFERENCED_COLUMN_NAME")?;
             let definition = format!(
-                "(`{}`) REFERENCES `{}`.`{}`(`{}`)",
-                column_name, database_name, referenced_table_name, referenced_column_name
+                "({}) REFERENCES {}.{}({})",
+                quote!(column_name),
+                quote!(database_name),
+                quote!(referenced_table_name),
+                quote!(referenced_column_name)
             );
             let constraint = Constraint {
                 database_name: database_name.clone(),
@@ -537,6 +760,16 @@ impl MysqlStructFetcher {
         Ok(results)
     }
 
+    async fn has_table_constraints_enforced(&mut self) -> anyhow::Result<bool> {
+        let sql = "SELECT COUNT(*) FROM information_schema.columns
+            WHERE table_schema = 'information_schema' AND table_name = 'TABLE_CONSTRAINTS' AND column_name = 'ENFORCED'";
+        let count: i64 = sqlx::query(sql)
+            .fetch_one(&self.conn_pool)
+            .await?
+            .try_get(0)?;
+        Ok(count > 0)
+    }
+
     async fn get_information_schema_tables(&mut self) -> anyhow::Result<HashSet<String>> {
         let mut tbs = HashSet::new();
         let sql = "SELECT table_name FROM information_schema.tables WHERE table_schema = 'information_schema'";
@@ -642,3 +875,27 @@ impl MysqlStructFetcher {
             .join(",")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_create_options() {
+        let cases = [
+            ("", ("", "")),
+            ("partitioned", ("", "")),
+            (
+                "row_format=COMPRESSED KEY_BLOCK_SIZE=8",
+                ("COMPRESSED", "8"),
+            ),
+            ("stats_persistent=0 row_format=dynamic", ("DYNAMIC", "")),
+        ];
+        for (create_options, (row_format, key_block_size)) in cases {
+            assert_eq!(
+                MysqlStructFetcher::parse_create_options(create_options),
+                (row_format.to_string(), key_block_size.to_string())
+            );
+        }
+    }
+}
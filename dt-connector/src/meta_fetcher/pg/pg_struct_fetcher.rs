@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use anyhow::bail;
 use dt_common::meta::struct_meta::{
     statement::{
+        pg_create_object_statement::PgCreateObjectStatement,
         pg_create_rbac_statement::PgCreateRbacStatement,
         pg_create_schema_statement::PgCreateSchemaStatement,
         pg_create_table_statement::PgCreateTableStatement,
@@ -13,11 +14,13 @@ use dt_common::meta::struct_meta::{
         column::{Column, ColumnDefault},
         comment::{Comment, CommentType},
         constraint::{Constraint, ConstraintType},
+        db_object::PgDbObject,
         index::{Index, IndexKind},
         rbac::{PgPrivilege, PgRole, PgRoleMember},
         schema::Schema,
         sequence::Sequence,
         sequence_owner::SequenceOwner,
+        structure_type::StructureType,
         table::Table,
         user_defined::{PgUdf, PgUdt, PgUdtType},
     },
@@ -25,7 +28,7 @@ use dt_common::meta::struct_meta::{
 use dt_common::{
     config::{config_enums::DbType, config_token_parser::ConfigTokenParser},
     error::Error,
-    log_error, log_info, log_warn,
+    log_error, log_info, log_warn, quote_pg,
     rdb_filter::RdbFilter,
     utils::sql_util::SqlUtil,
 };
@@ -33,6 +36,9 @@ use futures::TryStreamExt;
 use sqlx::{postgres::PgRow, Pool, Postgres, Row};
 
 use super::pg_struct_check_fetcher::PgStructCheckFetcher;
+use crate::meta_fetcher::dependency_sorter;
+
+use quote_pg as quote;
 
 pub struct PgStructFetcher {
     pub conn_pool: Pool<Postgres>,
@@ -70,6 +76,7 @@ impl PgStructFetcher {
         let mut indexes = self.get_indexes(sch, tb).await?;
         let mut column_comments = self.get_column_comments(sch, tb).await?;
         let mut table_comments = self.get_table_comments(sch, tb).await?;
+        let mut constraint_comments = self.get_constraint_comments(sch, tb).await?;
 
         for (schema_table_name, table) in tables {
             let table_sequences = self.get_table_sequences(&table, &mut sequences).await?;
@@ -81,6 +88,7 @@ impl PgStructFetcher {
                 indexes: self.get_result(&mut indexes, &schema_table_name),
                 column_comments: self.get_result(&mut column_comments, &schema_table_name),
                 table_comments: self.get_result(&mut table_comments, &schema_table_name),
+                constraint_comments: self.get_result(&mut constraint_comments, &schema_table_name),
             };
             results.push(statement);
         }
@@ -149,6 +157,166 @@ impl PgStructFetcher {
         Ok(results)
     }
 
+    /// views of self.schemas, each after the views it selects from
+    pub async fn get_create_view_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<PgCreateObjectStatement>> {
+        if self.schemas.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT n.nspname AS schema_name,
+                c.relname AS view_name,
+                pg_catalog.pg_get_viewdef(c.oid) AS definition
+            FROM pg_catalog.pg_class c
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind = 'v' AND n.nspname IN ({})
+            ORDER BY c.oid",
+            self.get_schemas_str()
+        );
+        let mut views = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let schema_name = Self::get_str_with_null(&row, "schema_name")?;
+            if self.filter_schema(&schema_name) {
+                continue;
+            }
+            views.push(PgDbObject {
+                object_type: StructureType::View,
+                schema_name,
+                object_name: Self::get_str_with_null(&row, "view_name")?,
+                table_name: String::new(),
+                definition: Self::get_str_with_null(&row, "definition")?,
+            });
+        }
+
+        // a view depends on the relations used by its rewrite rule
+        let sql = format!(
+            "SELECT DISTINCT vn.nspname AS view_schema,
+                v.relname AS view_name,
+                rn.nspname AS ref_schema,
+                r.relname AS ref_name
+            FROM pg_catalog.pg_depend d
+            JOIN pg_catalog.pg_rewrite rw ON rw.oid = d.objid
+            JOIN pg_catalog.pg_class v ON v.oid = rw.ev_class
+            JOIN pg_catalog.pg_namespace vn ON vn.oid = v.relnamespace
+            JOIN pg_catalog.pg_class r ON r.oid = d.refobjid
+            JOIN pg_catalog.pg_namespace rn ON rn.oid = r.relnamespace
+            WHERE d.classid = 'pg_rewrite'::regclass
+                AND d.refclassid = 'pg_class'::regclass
+                AND v.relkind = 'v'
+                AND r.relkind = 'v'
+                AND v.oid <> r.oid
+                AND vn.nspname IN ({})",
+            self.get_schemas_str()
+        );
+        let mut deps: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let view_key = format!(
+                "{}.{}",
+                Self::get_str_with_null(&row, "view_schema")?,
+                Self::get_str_with_null(&row, "view_name")?
+            );
+            let ref_key = format!(
+                "{}.{}",
+                Self::get_str_with_null(&row, "ref_schema")?,
+                Self::get_str_with_null(&row, "ref_name")?
+            );
+            deps.entry(view_key).or_default().insert(ref_key);
+        }
+
+        let view_key = |v: &PgDbObject| format!("{}.{}", v.schema_name, v.object_name);
+        Ok(
+            dependency_sorter::sort_by_dependencies(views, view_key, &deps)
+                .into_iter()
+                .map(|object| PgCreateObjectStatement { object })
+                .collect(),
+        )
+    }
+
+    /// procedures of self.schemas, functions are migrated as udf
+    pub async fn get_create_routine_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<PgCreateObjectStatement>> {
+        let mut results = Vec::new();
+        let sql = "SELECT
+                n.nspname AS schema_name,
+                p.proname AS procedure_name,
+                pg_catalog.pg_get_functiondef(p.oid) AS create_statement
+            FROM pg_catalog.pg_proc p
+            JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+            LEFT JOIN pg_catalog.pg_depend d
+                ON d.classid = 'pg_proc'::regclass
+                AND d.objid   = p.oid
+                AND d.deptype = 'e'
+            WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+            AND p.prokind = 'p'
+            AND d.objid IS NULL
+            ORDER BY p.oid
+        ";
+
+        let mut rows = sqlx::query(sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let schema_name = Self::get_str_with_null(&row, "schema_name")?;
+            if !self.schemas.contains(&schema_name) || self.filter_schema(&schema_name) {
+                continue;
+            }
+            results.push(PgCreateObjectStatement {
+                object: PgDbObject {
+                    object_type: StructureType::Routine,
+                    schema_name,
+                    object_name: Self::get_str_with_null(&row, "procedure_name")?,
+                    table_name: String::new(),
+                    definition: Self::get_str_with_null(&row, "create_statement")?,
+                },
+            });
+        }
+        Ok(results)
+    }
+
+    /// user triggers of tables not filtered
+    pub async fn get_create_trigger_statements(
+        &mut self,
+    ) -> anyhow::Result<Vec<PgCreateObjectStatement>> {
+        if self.schemas.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = format!(
+            "SELECT n.nspname AS schema_name,
+                c.relname AS table_name,
+                t.tgname AS trigger_name,
+                pg_catalog.pg_get_triggerdef(t.oid) AS definition
+            FROM pg_catalog.pg_trigger t
+            JOIN pg_catalog.pg_class c ON c.oid = t.tgrelid
+            JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+            WHERE NOT t.tgisinternal AND n.nspname IN ({})
+            ORDER BY n.nspname, c.relname, t.tgname",
+            self.get_schemas_str()
+        );
+        let mut results = Vec::new();
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let schema_name = Self::get_str_with_null(&row, "schema_name")?;
+            let table_name = Self::get_str_with_null(&row, "table_name")?;
+            if self.filter_tb(&schema_name, &table_name) {
+                continue;
+            }
+            results.push(PgCreateObjectStatement {
+                object: PgDbObject {
+                    object_type: StructureType::Trigger,
+                    schema_name,
+                    object_name: Self::get_str_with_null(&row, "trigger_name")?,
+                    table_name,
+                    definition: Self::get_str_with_null(&row, "definition")?,
+                },
+            });
+        }
+        Ok(results)
+    }
+
     pub async fn get_udt_statements(&mut self) -> anyhow::Result<Vec<PgCreateUdtStatement>> {
         let mut results = Vec::new();
 
@@ -538,22 +706,42 @@ impl PgStructFetcher {
             }
         }
 
-        // get column types
+        // get column types, storages and compressions
+        if results.is_empty() {
+            return Ok(results);
+        }
+        let with_compression = self.get_server_version_num().await? >= 140000;
         for ((table_schema, table_name), table) in results.iter_mut() {
-            let column_types = self.get_column_types(table_schema, table_name).await?;
+            let mut column_attrs = self
+                .get_column_attrs(table_schema, table_name, with_compression)
+                .await?;
             for column in table.columns.iter_mut() {
-                column.column_type = column_types.get(&column.column_name).unwrap().to_owned();
+                let (column_type, storage, compression) =
+                    column_attrs.remove(&column.column_name).unwrap();
+                column.column_type = column_type;
+                column.storage = storage;
+                column.compression = compression;
             }
         }
 
         Ok(results)
     }
 
-    async fn get_column_types(
+    async fn get_server_version_num(&self) -> anyhow::Result<i32> {
+        let row = sqlx::query("SELECT current_setting('server_version_num')::int AS version_num")
+            .fetch_one(&self.conn_pool)
+            .await?;
+        Ok(row.try_get("version_num")?)
+    }
+
+    // column name -> (column type, storage, compression),
+    // storage is empty if it is the default of the column type, compression is empty if not set
+    async fn get_column_attrs(
         &mut self,
         schema: &str,
         tb: &str,
-    ) -> anyhow::Result<HashMap<String, String>> {
+        with_compression: bool,
+    ) -> anyhow::Result<HashMap<String, (String, String, String)>> {
         let fetcher = PgStructCheckFetcher {
             conn_pool: self.conn_pool.clone(),
         };
@@ -568,12 +756,21 @@ impl PgStructFetcher {
             );
         }
 
+        // attcompression is added in pg 14
+        let compression = if with_compression {
+            "a.attcompression::text"
+        } else {
+            "''"
+        };
         let sql = format!(
             "SELECT a.attname AS column_name, 
-                pg_catalog.format_type(a.atttypid, a.atttypmod) AS column_type
+                pg_catalog.format_type(a.atttypid, a.atttypmod) AS column_type,
+                CASE WHEN a.attstorage <> t.typstorage THEN a.attstorage::text ELSE '' END AS storage,
+                {} AS compression
             FROM pg_catalog.pg_attribute a
+            JOIN pg_catalog.pg_type t ON t.oid = a.atttypid
             WHERE a.attrelid = '{}' AND a.attnum > 0;",
-            oid
+            compression, oid
         );
 
         let mut results = HashMap::new();
@@ -581,7 +778,22 @@ impl PgStructFetcher {
         while let Some(row) = rows.try_next().await? {
             let column_name: String = Self::get_str_with_null(&row, "column_name")?;
             let column_type: String = Self::get_str_with_null(&row, "column_type")?;
-            results.insert(column_name, column_type);
+            let storage = match Self::get_str_with_null(&row, "storage")?.as_str() {
+                "p" => "PLAIN",
+                "e" => "EXTERNAL",
+                "m" => "MAIN",
+                "x" => "EXTENDED",
+                _ => "",
+            };
+            let compression = match Self::get_str_with_null(&row, "compression")?.as_str() {
+                "p" => "pglz",
+                "l" => "lz4",
+                _ => "",
+            };
+            results.insert(
+                column_name,
+                (column_type, storage.to_string(), compression.to_string()),
+            );
         }
 
         Ok(results)
@@ -621,6 +833,7 @@ impl PgStructFetcher {
             JOIN pg_catalog.pg_namespace nsp
                 ON nsp.oid = connamespace
             WHERE {} 
+            AND con.contype IN ('p', 'u', 'c', 'f', 'x')
             ORDER BY nsp.nspname,rel.relname",
             tb_filter
         );
@@ -737,7 +950,8 @@ impl PgStructFetcher {
             LEFT JOIN pg_namespace n
                 ON n.oid = c.relnamespace
             LEFT JOIN pg_description d
-                ON c.oid = d.objoid  AND d.objsubid = 0
+                ON c.oid = d.objoid AND d.objsubid = 0
+                AND d.classoid = 'pg_catalog.pg_class'::regclass
             WHERE {} 
             AND d.description IS NOT null",
             tb_filter
@@ -756,6 +970,69 @@ impl PgStructFetcher {
                 schema_name: schema_name.clone(),
                 table_name: table_name.clone(),
                 column_name: String::new(),
+                constraint_name: String::new(),
+                comment: Self::get_str_with_null(&row, "description")?,
+            };
+            self.push_to_results(&mut results, &schema_name, &table_name, comment);
+        }
+
+        Ok(results)
+    }
+
+    async fn get_constraint_comments(
+        &mut self,
+        sch: &str,
+        tb: &str,
+    ) -> anyhow::Result<HashMap<(String, String), Vec<Comment>>> {
+        let mut results = HashMap::new();
+
+        let tb_filter = if !sch.is_empty() {
+            if !self.schemas.contains(sch) {
+                return Ok(results);
+            }
+            if !tb.is_empty() {
+                format!("nsp.nspname='{}' AND rel.relname = '{}'", sch, tb)
+            } else {
+                format!("nsp.nspname = '{}'", sch)
+            }
+        } else if !self.schemas.is_empty() {
+            format!("nsp.nspname IN ({})", self.get_schemas_str())
+        } else {
+            return Ok(results);
+        };
+
+        let sql = format!(
+            "SELECT nsp.nspname,
+                rel.relname,
+                con.conname AS constraint_name,
+                d.description
+            FROM pg_catalog.pg_constraint con
+            JOIN pg_catalog.pg_class rel
+                ON rel.oid = con.conrelid
+            JOIN pg_catalog.pg_namespace nsp
+                ON nsp.oid = connamespace
+            JOIN pg_catalog.pg_description d
+                ON d.objoid = con.oid
+                AND d.classoid = 'pg_catalog.pg_constraint'::regclass
+            WHERE {}
+            AND con.contype IN ('p', 'u', 'c', 'f', 'x')",
+            tb_filter
+        );
+
+        let mut rows = sqlx::query(&sql).fetch(&self.conn_pool);
+        while let Some(row) = rows.try_next().await? {
+            let (schema_name, table_name): (String, String) = (
+                Self::get_str_with_null(&row, "nspname")?,
+                Self::get_str_with_null(&row, "relname")?,
+            );
+
+            let comment = Comment {
+                comment_type: CommentType::Constraint,
+                database_name: String::new(),
+                schema_name: schema_name.clone(),
+                table_name: table_name.clone(),
+                column_name: String::new(),
+                constraint_name: Self::get_str_with_null(&row, "constraint_name")?,
                 comment: Self::get_str_with_null(&row, "description")?,
             };
             self.push_to_results(&mut results, &schema_name, &table_name, comment);
@@ -818,6 +1095,7 @@ impl PgStructFetcher {
                 schema_name: schema_name.clone(),
                 table_name: table_name.clone(),
                 column_name,
+                constraint_name: String::new(),
                 comment: Self::get_str_with_null(&row, "comment")?,
             };
             self.push_to_results(&mut results, &schema_name, &table_name, comment);
@@ -1069,7 +1347,7 @@ impl PgStructFetcher {
 
                 let quoted_columns = columns_vec
                     .iter()
-                    .map(|col| format!("\"{}\"", col))
+                    .map(|col| quote!(col).to_string())
                     .collect::<Vec<_>>()
                     .join(", ");
 
@@ -1081,8 +1359,13 @@ impl PgStructFetcher {
 
                 // Format: GRANT SELECT (column1, column2) ON table_name TO role_name [WITH GRANT OPTION]
                 let grant_command = format!(
-                    "GRANT {} ({}) ON \"{}\".\"{}\" TO \"{}\"{}",
-                    privilege_type, quoted_columns, schema, table, grantee, grant_option
+                    "GRANT {} ({}) ON {}.{} TO {}{}",
+                    privilege_type,
+                    quoted_columns,
+                    quote!(schema),
+                    quote!(table),
+                    quote!(grantee),
+                    grant_option
                 );
 
                 results.push(PgPrivilege {
@@ -1166,8 +1449,12 @@ impl PgStructFetcher {
 
             // format: GRANT USAGE, SELECT ON SEQUENCE schema.sequence_name TO role_name [WITH GRANT OPTION]
             let grant_command = format!(
-                "GRANT {} ON SEQUENCE \"{}\".\"{}\" TO \"{}\"{}",
-                privileges_str, schema, sequence, grantee, grant_option
+                "GRANT {} ON SEQUENCE {}.{} TO {}{}",
+                privileges_str,
+                quote!(schema),
+                quote!(sequence),
+                quote!(grantee),
+                grant_option
             );
 
             results.push(PgPrivilege {
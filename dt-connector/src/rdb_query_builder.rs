@@ -1,4 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
 
 use anyhow::{bail, Context};
 use sqlx::{mysql::MySqlArguments, postgres::PgArguments, query::Query, MySql, Postgres};
@@ -34,6 +37,8 @@ pub struct RdbQueryBuilder<'a> {
     ignore_cols: Option<&'a HashSet<String>>,
     pg_tb_meta: Option<&'a PgTbMeta>,
     mysql_tb_meta: Option<&'a MysqlTbMeta>,
+    // cols written by insert / update, generated cols are excluded
+    sink_cols: Cow<'a, Vec<String>>,
 }
 
 impl RdbQueryBuilder<'_> {
@@ -42,12 +47,26 @@ impl RdbQueryBuilder<'_> {
         tb_meta: &'a MysqlTbMeta,
         ignore_cols: Option<&'a HashSet<String>>,
     ) -> RdbQueryBuilder<'a> {
+        let sink_cols = if tb_meta.generated_cols.is_empty() {
+            Cow::Borrowed(&tb_meta.basic.cols)
+        } else {
+            Cow::Owned(
+                tb_meta
+                    .basic
+                    .cols
+                    .iter()
+                    .filter(|col| !tb_meta.is_col_generated(col))
+                    .cloned()
+                    .collect(),
+            )
+        };
         RdbQueryBuilder {
             rdb_tb_meta: &tb_meta.basic,
             pg_tb_meta: None,
             mysql_tb_meta: Some(tb_meta),
             db_type: DbType::Mysql,
             ignore_cols,
+            sink_cols,
         }
     }
 
@@ -62,6 +81,7 @@ impl RdbQueryBuilder<'_> {
             mysql_tb_meta: None,
             db_type: DbType::Pg,
             ignore_cols,
+            sink_cols: Cow::Borrowed(&tb_meta.basic.cols),
         }
     }
 
@@ -199,8 +219,8 @@ impl RdbQueryBuilder<'_> {
         let mut placeholder_index = 1;
         let mut row_values = Vec::with_capacity(batch_size);
         for _ in 0..batch_size {
-            let mut col_values = Vec::with_capacity(self.rdb_tb_meta.cols.len());
-            for col in self.rdb_tb_meta.cols.iter() {
+            let mut col_values = Vec::with_capacity(self.sink_cols.len());
+            for col in self.sink_cols.iter() {
                 col_values.push(self.get_placeholder(placeholder_index, col)?);
                 placeholder_index += 1;
             }
@@ -211,16 +231,16 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES{}",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&self.sink_cols).join(","),
             row_values.join(",")
         );
 
-        let mut cols = Vec::with_capacity(batch_size.saturating_mul(self.rdb_tb_meta.cols.len()));
-        let mut binds = Vec::with_capacity(batch_size.saturating_mul(self.rdb_tb_meta.cols.len()));
+        let mut cols = Vec::with_capacity(batch_size.saturating_mul(self.sink_cols.len()));
+        let mut binds = Vec::with_capacity(batch_size.saturating_mul(self.sink_cols.len()));
         for row_data in data.iter().skip(start_index).take(batch_size) {
             malloc_size += row_data.data_size;
             let after = row_data.require_after()?;
-            for col_name in self.rdb_tb_meta.cols.iter() {
+            for col_name in self.sink_cols.iter() {
                 cols.push(col_name.clone());
                 binds.push(after.get(col_name));
             }
@@ -385,18 +405,18 @@ impl RdbQueryBuilder<'_> {
         row_data: &'a RowData,
         placeholder: bool,
     ) -> anyhow::Result<RdbQueryInfo<'a>> {
-        let mut cols = Vec::with_capacity(self.rdb_tb_meta.cols.len());
-        let mut binds = Vec::with_capacity(self.rdb_tb_meta.cols.len());
+        let mut cols = Vec::with_capacity(self.sink_cols.len());
+        let mut binds = Vec::with_capacity(self.sink_cols.len());
         let after = row_data.require_after()?;
-        for col_name in self.rdb_tb_meta.cols.iter() {
+        for col_name in self.sink_cols.iter() {
             cols.push(col_name.clone());
             binds.push(after.get(col_name));
         }
 
-        let mut col_values = Vec::with_capacity(self.rdb_tb_meta.cols.len());
-        for i in 0..self.rdb_tb_meta.cols.len() {
+        let mut col_values = Vec::with_capacity(self.sink_cols.len());
+        for i in 0..self.sink_cols.len() {
             let sql_value =
-                self.get_sql_value(i + 1, &self.rdb_tb_meta.cols[i], &binds[i], placeholder)?;
+                self.get_sql_value(i + 1, &self.sink_cols[i], &binds[i], placeholder)?;
             col_values.push(sql_value);
         }
 
@@ -404,7 +424,7 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES({})",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&self.sink_cols).join(","),
             col_values.join(",")
         );
 
@@ -458,7 +478,7 @@ impl RdbQueryBuilder<'_> {
         let mut set_cols = Vec::new();
         let mut set_pairs = Vec::new();
         // pin the order of cols
-        for col in self.rdb_tb_meta.cols.iter() {
+        for col in self.sink_cols.iter() {
             let Some(col_value) = after.get(col) else {
                 continue;
             };
@@ -858,8 +878,9 @@ impl RdbQueryBuilder<'_> {
             if col_type.schema_name != "pg_catalog" {
                 // for user-defined types, we need to add schema name as prefix, otherwise it will cause error
                 return Ok(format!(
-                    "${}::\"{}\".\"{}\"",
-                    index, col_type.schema_name, col_type.alias
+                    "${}::{}",
+                    index,
+                    SqlUtil::quote_tb(&col_type.schema_name, &col_type.alias, &DbType::Pg)
                 ));
             }
             let col_type_name = col_type.get_alias();
@@ -912,6 +933,7 @@ mod tests {
 
     use dt_common::meta::{
         col_value::ColValue,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
         pg::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
         rdb_tb_meta::RdbTbMeta,
         row_data::RowData,
@@ -1365,4 +1387,68 @@ mod tests {
             .contains(r#"WHERE ctid IN (SELECT ctid FROM "public"."t1" WHERE"#));
         assert!(query_info.sql.contains("LIMIT 1"));
     }
+
+    #[test]
+    fn test_mysql_insert_and_update_skip_generated_cols() {
+        let tb_meta = MysqlTbMeta {
+            basic: RdbTbMeta {
+                schema: "db1".to_string(),
+                tb: "t1".to_string(),
+                cols: vec!["id".to_string(), "v".to_string(), "v2".to_string()],
+                key_map: HashMap::from([("primary".to_string(), vec!["id".to_string()])]),
+                id_cols: vec!["id".to_string()],
+                ..Default::default()
+            },
+            col_type_map: HashMap::from([
+                ("id".to_string(), MysqlColType::Int { unsigned: false }),
+                ("v".to_string(), MysqlColType::Int { unsigned: false }),
+                ("v2".to_string(), MysqlColType::Int { unsigned: false }),
+            ]),
+            generated_cols: HashSet::from(["v2".to_string()]),
+        };
+        let builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
+        let col_values = |id: i32, v: i32| {
+            HashMap::from([
+                ("id".to_string(), ColValue::Long(id)),
+                ("v".to_string(), ColValue::Long(v)),
+                ("v2".to_string(), ColValue::Long(v * 2)),
+            ])
+        };
+
+        let insert = RowData::new(
+            "db1".to_string(),
+            "t1".to_string(),
+            0,
+            RowType::Insert,
+            None,
+            Some(col_values(1, 1)),
+        );
+        let query_info = builder.get_query_info(&insert, true).unwrap();
+        assert_eq!(
+            query_info.sql,
+            "REPLACE INTO `db1`.`t1`(`id`,`v`) VALUES(?,?)"
+        );
+        assert_eq!(query_info.cols, vec!["id", "v"]);
+
+        let data = [insert];
+        let (query_info, _) = builder.get_batch_insert_query(&data, 0, 1, false).unwrap();
+        assert_eq!(
+            query_info.sql,
+            "INSERT INTO `db1`.`t1`(`id`,`v`) VALUES(?,?)"
+        );
+
+        let update = RowData::new(
+            "db1".to_string(),
+            "t1".to_string(),
+            0,
+            RowType::Update,
+            Some(col_values(1, 1)),
+            Some(col_values(1, 2)),
+        );
+        let query_info = builder.get_query_info(&update, false).unwrap();
+        assert_eq!(
+            query_info.sql,
+            "UPDATE `db1`.`t1` SET `id`=?,`v`=? WHERE `id` = ?"
+        );
+    }
 }
@@ -1,15 +1,21 @@
 use anyhow::{bail, Context, Ok};
 use dt_common::{
     config::{
-        config_enums::DbType, config_token_parser::ConfigTokenParser, router_config::RouterConfig,
+        config_enums::{DbType, IdentifierCase},
+        config_token_parser::ConfigTokenParser,
+        filter_config::FilterConfig,
+        router_config::RouterConfig,
+        sinker_config::ShardConfig,
     },
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement},
+        redis::redis_object::RedisCmd,
         struct_meta::{statement::struct_statement::StructStatement, struct_data::StructData},
     },
+    rdb_filter::RdbFilter,
     utils::sql_util::SqlUtil,
 };
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
 use dt_common::meta::{col_value::ColValue, row_data::RowData};
 use serde::{Deserialize, Serialize};
@@ -35,6 +41,12 @@ struct RdbRouterInner {
     tb_map: TbMap,
     // HashMap<(src_schema, src_tb), HashMap<src_col, dst_col>>
     col_map: TbColMap,
+    // forward only, dst names in maps are converted when parsed
+    #[serde(default)]
+    identifier_case: IdentifierCase,
+    // forward only, added to all dst schemas, e.g. per source of a multi-source task
+    #[serde(default)]
+    schema_prefix: String,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -69,20 +81,32 @@ impl RdbRouter {
         self.forward.has_route_rules()
     }
 
-    pub fn get_schema_map<'a>(&'a self, schema: &'a str) -> &'a str {
+    /// Adds schema_prefix to all dst schemas of router, a router is created if None.
+    pub fn with_schema_prefix(router: Option<Self>, schema_prefix: &str) -> Option<Self> {
+        if schema_prefix.is_empty() {
+            return router;
+        }
+        let mut router = router.unwrap_or_default();
+        router.forward.schema_prefix = schema_prefix.to_string();
+        Some(router)
+    }
+
+    pub fn get_schema_map<'a>(&'a self, schema: &'a str) -> Cow<'a, str> {
         self.forward.get_schema_map(schema)
     }
 
+    /// Names without db_map / tb_map are returned as they are, including those converted
+    /// by identifier_case, whose source names are unknown.
     pub fn reverse_get_schema_map<'a>(&'a self, schema: &'a str) -> &'a str {
-        self.reverse.get_schema_map(schema)
+        self.reverse.get_schema_map_borrowed(schema)
     }
 
-    pub fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (&'a str, &'a str) {
+    pub fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (Cow<'a, str>, Cow<'a, str>) {
         self.forward.get_tb_map(schema, tb)
     }
 
     pub fn reverse_get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (&'a str, &'a str) {
-        self.reverse.get_tb_map(schema, tb)
+        self.reverse.get_tb_map_borrowed(schema, tb)
     }
 
     pub fn get_col_map(&self, schema: &str, tb: &str) -> Option<&HashMap<String, String>> {
@@ -125,6 +149,19 @@ impl RdbRouter {
         self.forward.route_redis_db_id(db_id)
     }
 
+    /// db indexes in args of MOVE / COPY ... DB / SWAPDB are mapped by db_map
+    pub fn route_redis_cmd(&self, cmd: &RedisCmd) -> anyhow::Result<RedisCmd> {
+        let mut routed_cmd = cmd.clone();
+        for i in cmd.get_db_arg_indexes() {
+            let db_id = cmd
+                .get_str_arg(i)
+                .parse::<i64>()
+                .with_context(|| format!("invalid Redis db index in cmd: [{}]", cmd))?;
+            routed_cmd.args[i] = self.route_redis_db_id(db_id)?.to_string().into_bytes();
+        }
+        Ok(routed_cmd)
+    }
+
     pub fn validate_redis_db_map(&self, is_cluster: bool) -> anyhow::Result<()> {
         self.forward.validate_redis_db_map()?;
         if is_cluster {
@@ -159,6 +196,8 @@ impl RdbRouter {
             schema_map,
             tb_map,
             col_map,
+            identifier_case: IdentifierCase::Preserve,
+            schema_prefix: String::new(),
         };
         let reverse = inner.reverse();
         Self {
@@ -176,39 +215,75 @@ impl RdbRouterInner {
                 schema_map,
                 tb_map,
                 col_map,
+                identifier_case,
                 ..
             } => {
-                let schema_map = Self::parse_schema_map(schema_map, db_type)?;
-                let tb_map = Self::parse_tb_map(tb_map, db_type)?;
+                let case = |name: String| identifier_case.apply(&name).into_owned();
+                let schema_map = Self::parse_schema_map(schema_map, db_type)?
+                    .into_iter()
+                    .map(|(src, dst)| (src, case(dst)))
+                    .collect();
+                let tb_map = Self::parse_tb_map(tb_map, db_type)?
+                    .into_iter()
+                    .map(|(src, (dst_schema, dst_tb))| (src, (case(dst_schema), case(dst_tb))))
+                    .collect();
                 let col_map = Self::parse_col_map(col_map)?;
                 Ok(Self {
                     schema_map,
                     tb_map,
                     col_map,
+                    identifier_case: identifier_case.clone(),
+                    schema_prefix: String::new(),
                 })
             }
         }
     }
 
     fn has_route_rules(&self) -> bool {
-        !self.schema_map.is_empty() || !self.tb_map.is_empty() || !self.col_map.is_empty()
+        !self.schema_map.is_empty()
+            || !self.tb_map.is_empty()
+            || !self.col_map.is_empty()
+            || self.identifier_case != IdentifierCase::Preserve
+            || !self.schema_prefix.is_empty()
+    }
+
+    fn get_schema_map<'a>(&'a self, schema: &'a str) -> Cow<'a, str> {
+        let dst_schema = match self.schema_map.get(schema) {
+            Some(dst_schema) => Cow::Borrowed(dst_schema.as_str()),
+            None => self.identifier_case.apply(schema),
+        };
+        self.add_schema_prefix(dst_schema)
+    }
+
+    fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (Cow<'a, str>, Cow<'a, str>) {
+        if let Some((dst_schema, dst_tb)) = self.tb_map.get(&(schema.into(), tb.into())) {
+            return (
+                self.add_schema_prefix(Cow::Borrowed(dst_schema)),
+                Cow::Borrowed(dst_tb),
+            );
+        }
+        (self.get_schema_map(schema), self.identifier_case.apply(tb))
+    }
+
+    fn add_schema_prefix<'a>(&self, schema: Cow<'a, str>) -> Cow<'a, str> {
+        if self.schema_prefix.is_empty() || schema.is_empty() {
+            return schema;
+        }
+        Cow::Owned(format!("{}{}", self.schema_prefix, schema))
     }
 
-    fn get_schema_map<'a>(&'a self, schema: &'a str) -> &'a str {
+    fn get_schema_map_borrowed<'a>(&'a self, schema: &'a str) -> &'a str {
         if let Some(dst_schema) = self.schema_map.get(schema) {
             return dst_schema;
         }
         schema
     }
 
-    fn get_tb_map<'a>(&'a self, schema: &'a str, tb: &'a str) -> (&'a str, &'a str) {
+    fn get_tb_map_borrowed<'a>(&'a self, schema: &'a str, tb: &'a str) -> (&'a str, &'a str) {
         if let Some((dst_schema, dst_tb)) = self.tb_map.get(&(schema.into(), tb.into())) {
             return (dst_schema, dst_tb);
         }
-        if let Some(dst_schema) = self.schema_map.get(schema) {
-            return (dst_schema, tb);
-        }
-        (schema, tb)
+        (self.get_schema_map_borrowed(schema), tb)
     }
 
     fn get_col_map(&self, schema: &str, tb: &str) -> Option<&HashMap<String, String>> {
@@ -241,6 +316,8 @@ impl RdbRouterInner {
             schema_map: reverse_schema_map,
             tb_map: reverse_tb_map,
             col_map: reverse_tb_col_map,
+            identifier_case: IdentifierCase::Preserve,
+            schema_prefix: String::new(),
         }
     }
 
@@ -321,7 +398,7 @@ impl RdbRouterInner {
             StructStatement::MysqlCreateTable(s) => {
                 let (schema, tb) = (s.table.database_name.clone(), s.table.table_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb)
             }
 
             StructStatement::MysqlCreateDatabase(s) => {
@@ -332,21 +409,21 @@ impl RdbRouterInner {
             StructStatement::MongoCreateCollection(s) => {
                 let (schema, tb) = (s.database_name.clone(), s.collection_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb)
             }
 
             StructStatement::MongoShardKey(s) => {
                 let ns = s.shard_collection.ns.clone();
                 if let Some((schema, tb)) = ns.split_once('.') {
                     let (dst_schema, dst_tb) = self.get_tb_map(schema, tb);
-                    s.route(schema, tb, dst_schema, dst_tb)
+                    s.route(schema, tb, &dst_schema, &dst_tb)
                 }
             }
 
             StructStatement::PgCreateTable(s) => {
                 let (schema, tb) = (s.table.schema_name.clone(), s.table.table_name.clone());
                 let (dst_schema, dst_tb) = self.get_tb_map(&schema, &tb);
-                s.route(dst_schema, dst_tb)
+                s.route(&dst_schema, &dst_tb)
             }
 
             StructStatement::PgCreateSchema(s) => {
@@ -503,13 +580,99 @@ impl RdbTopicRouterInner {
     }
 }
 
+/// Picks the target of rows in a sharded task by source db / tb, index 0 is the default target.
+#[derive(Debug, Clone)]
+pub struct ShardRouter {
+    // (name, filter of tbs) for targets other than the default one
+    targets: Vec<(String, RdbFilter)>,
+    hash_filter: Option<RdbFilter>,
+}
+
+impl ShardRouter {
+    pub fn from_config(config: &ShardConfig, db_type: &DbType) -> anyhow::Result<Self> {
+        let to_filter = |tbs: &str| {
+            let config = FilterConfig {
+                do_tbs: tbs.to_string(),
+                ..Default::default()
+            };
+            RdbFilter::from_config(&config, db_type)
+        };
+
+        let mut targets = Vec::new();
+        for target in config.targets.iter() {
+            targets.push((target.name.clone(), to_filter(&target.tbs)?));
+        }
+        let hash_filter = if config.hash_tbs.is_empty() {
+            None
+        } else {
+            Some(to_filter(&config.hash_tbs)?)
+        };
+        Ok(Self {
+            targets,
+            hash_filter,
+        })
+    }
+
+    /// Count of targets, including the default one.
+    pub fn target_count(&self) -> usize {
+        self.targets.len() + 1
+    }
+
+    pub fn get_target_name(&self, index: usize) -> &str {
+        if index == 0 {
+            "default"
+        } else {
+            &self.targets[index - 1].0
+        }
+    }
+
+    pub fn is_hash_tb(&self, schema: &str, tb: &str) -> bool {
+        self.hash_filter
+            .as_ref()
+            .is_some_and(|filter| !filter.filter_tb(schema, tb))
+    }
+
+    /// None if rows of the table are spread to all targets by hash of key values.
+    pub fn get_target_by_tb(&self, schema: &str, tb: &str) -> Option<usize> {
+        if self.is_hash_tb(schema, tb) {
+            return None;
+        }
+        let index = self
+            .targets
+            .iter()
+            .position(|(_, filter)| !filter.filter_tb(schema, tb))
+            .map_or(0, |i| i + 1);
+        Some(index)
+    }
+
+    /// Same key values always go to the same target, across restarts of the task.
+    pub fn get_target_by_hash(&self, key_values: &[Option<String>]) -> usize {
+        let mut hasher = crc32fast::Hasher::new();
+        for value in key_values {
+            match value {
+                Some(v) => {
+                    hasher.update(&[1]);
+                    hasher.update(&(v.len() as u64).to_le_bytes());
+                    hasher.update(v.as_bytes());
+                }
+                None => hasher.update(&[0]),
+            }
+        }
+        hasher.finalize() as usize % self.target_count()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use dt_common::config::{config_enums::DbType, router_config::RouterConfig};
+    use dt_common::config::{
+        config_enums::{DbType, IdentifierCase},
+        router_config::RouterConfig,
+        sinker_config::{ShardConfig, ShardTargetConfig},
+    };
 
-    use super::{RdbRouter, TbColMap, TbMap};
+    use super::{RdbRouter, ShardRouter, TbColMap, TbMap};
 
     #[test]
     fn test_parse_ignore_cols() {
@@ -628,13 +791,15 @@ mod tests {
             tb_map: tb_map_str.into(),
             col_map: col_map_str.into(),
             topic_map: topic_map.into(),
+            identifier_case: IdentifierCase::Preserve,
         };
         let router = RdbRouter::from_config(&config, &DbType::Mysql)
             .unwrap()
             .unwrap();
 
         let assert_tb_map = |src_db: &str, src_tb: &str, dst_db: &str, dst_tb: &str| {
-            assert_eq!(router.get_tb_map(src_db, src_tb), (dst_db, dst_tb));
+            let (schema, tb) = router.get_tb_map(src_db, src_tb);
+            assert_eq!((schema.as_ref(), tb.as_ref()), (dst_db, dst_tb));
         };
         let assert_col_map = |src_db: &str, src_tb: &str, col_map: &HashMap<String, String>| {
             assert_eq!(router.get_col_map(src_db, src_tb).unwrap(), col_map)
@@ -666,6 +831,29 @@ mod tests {
         assert_eq!(router.get_topic("db:2", "tb:1"), "test");
     }
 
+    #[test]
+    fn test_schema_prefix() {
+        assert!(RdbRouter::with_schema_prefix(None, "").is_none());
+
+        let router = RdbRouter::with_schema_prefix(None, "s2_").unwrap();
+        let (schema, tb) = router.get_tb_map("db_1", "tb_1");
+        assert_eq!((schema.as_ref(), tb.as_ref()), ("s2_db_1", "tb_1"));
+
+        let config = RouterConfig::Rdb {
+            schema_map: "db_1:dst_1".into(),
+            tb_map: "db_2.tb_2:dst_2.dst_tb_2".into(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            identifier_case: IdentifierCase::Preserve,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
+        let router = RdbRouter::with_schema_prefix(router, "s2_").unwrap();
+        assert_eq!(router.get_schema_map("db_1"), "s2_dst_1");
+        assert_eq!(router.get_schema_map("db_3"), "s2_db_3");
+        let (schema, tb) = router.get_tb_map("db_2", "tb_2");
+        assert_eq!((schema.as_ref(), tb.as_ref()), ("s2_dst_2", "dst_tb_2"));
+    }
+
     #[test]
     fn test_topic_only_router_does_not_enable_table_route() {
         let config = RouterConfig::Rdb {
@@ -673,15 +861,14 @@ mod tests {
             tb_map: String::new(),
             col_map: String::new(),
             topic_map: "*.*:test".into(),
+            identifier_case: IdentifierCase::Preserve,
         };
         let router = RdbRouter::from_config(&config, &DbType::Mysql).unwrap();
 
         assert!(router.is_none());
         let topic_router = RdbRouter::from_config_for_topic(&config, &DbType::Mysql).unwrap();
-        assert_eq!(
-            topic_router.get_tb_map("src_db", "src_tb"),
-            ("src_db", "src_tb")
-        );
+        let (schema, tb) = topic_router.get_tb_map("src_db", "src_tb");
+        assert_eq!((schema.as_ref(), tb.as_ref()), ("src_db", "src_tb"));
         assert_eq!(
             topic_router.reverse_get_tb_map("dst_db", "dst_tb"),
             ("dst_db", "dst_tb")
@@ -703,6 +890,31 @@ mod tests {
         assert_eq!(router.route_redis_db_id(4).unwrap(), 4);
     }
 
+    #[test]
+    fn test_route_redis_cmd() {
+        let db_map = RdbRouter::parse_schema_map("0:1,2:3", &DbType::Redis).unwrap();
+        let router =
+            RdbRouter::from_maps_for_test(db_map, HashMap::new(), HashMap::new(), HashMap::new());
+
+        let route = |args: &[&str]| {
+            router
+                .route_redis_cmd(&RedisCmd::from_str_args(args))
+                .unwrap()
+                .args_to_string()
+        };
+        assert_eq!(route(&["MOVE", "k", "2"]), vec!["MOVE", "k", "3"]);
+        assert_eq!(route(&["swapdb", "0", "2"]), vec!["swapdb", "1", "3"]);
+        assert_eq!(
+            route(&["COPY", "db", "k2", "db", "0", "REPLACE"]),
+            vec!["COPY", "db", "k2", "db", "1", "REPLACE"]
+        );
+        assert_eq!(route(&["COPY", "k1", "k2"]), vec!["COPY", "k1", "k2"]);
+        assert_eq!(route(&["SET", "2", "0"]), vec!["SET", "2", "0"]);
+        assert!(router
+            .route_redis_cmd(&RedisCmd::from_str_args(&["MOVE", "k", "x"]))
+            .is_err());
+    }
+
     #[test]
     fn test_redis_db_map_validation() {
         let db_map = RdbRouter::parse_schema_map("0:abc", &DbType::Redis).unwrap();
@@ -720,4 +932,87 @@ mod tests {
             RdbRouter::from_maps_for_test(db_map, HashMap::new(), HashMap::new(), HashMap::new());
         router.validate_redis_db_map(true).unwrap();
     }
+
+    #[test]
+    fn test_identifier_case() {
+        let config = RouterConfig::Rdb {
+            schema_map: "Src_Db:Dst_Db".into(),
+            tb_map: "Src_Db.Orders:Dst_Db.Orders_V2".into(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            identifier_case: IdentifierCase::Lower,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mysql)
+            .unwrap()
+            .unwrap();
+
+        let get_tb_map = |schema: &str, tb: &str| {
+            let (schema, tb) = router.get_tb_map(schema, tb);
+            (schema.to_string(), tb.to_string())
+        };
+        assert_eq!(
+            get_tb_map("Src_Db", "Orders"),
+            ("dst_db".into(), "orders_v2".into())
+        );
+        assert_eq!(
+            get_tb_map("Src_Db", "Items"),
+            ("dst_db".into(), "items".into())
+        );
+        assert_eq!(
+            get_tb_map("Other", "Items"),
+            ("other".into(), "items".into())
+        );
+        // names in maps are converted, so reverse routes are kept
+        assert_eq!(
+            router.reverse_get_tb_map("dst_db", "orders_v2"),
+            ("Src_Db", "Orders")
+        );
+        assert_eq!(router.reverse_get_schema_map("dst_db"), "Src_Db");
+
+        // only identifier_case also enables the router
+        let config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: String::new(),
+            col_map: String::new(),
+            topic_map: String::new(),
+            identifier_case: IdentifierCase::Upper,
+        };
+        let router = RdbRouter::from_config(&config, &DbType::Mysql)
+            .unwrap()
+            .unwrap();
+        assert_eq!(router.get_schema_map("db_1"), "DB_1");
+    }
+
+    #[test]
+    fn test_shard_router() {
+        let config = ShardConfig {
+            targets: vec![
+                ShardTargetConfig {
+                    name: "shard_1".into(),
+                    url: String::new(),
+                    tbs: "db_1.orders_*".into(),
+                },
+                ShardTargetConfig {
+                    name: "shard_2".into(),
+                    url: String::new(),
+                    tbs: "db_2.*".into(),
+                },
+            ],
+            hash_tbs: "db_1.users".into(),
+        };
+        let router = ShardRouter::from_config(&config, &DbType::Mysql).unwrap();
+        assert_eq!(router.target_count(), 3);
+        assert_eq!(router.get_target_name(0), "default");
+        assert_eq!(router.get_target_name(2), "shard_2");
+
+        assert_eq!(router.get_target_by_tb("db_1", "orders_2024"), Some(1));
+        assert_eq!(router.get_target_by_tb("db_2", "tb_1"), Some(2));
+        assert_eq!(router.get_target_by_tb("db_1", "items"), Some(0));
+        assert_eq!(router.get_target_by_tb("db_1", "users"), None);
+
+        let key = vec![Some("1".to_string()), None];
+        let target = router.get_target_by_hash(&key);
+        assert!(target < 3);
+        assert_eq!(router.get_target_by_hash(&key), target);
+    }
 }
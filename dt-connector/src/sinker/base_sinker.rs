@@ -1,4 +1,5 @@
 use dt_common::{
+    config::pipeline_config::TableOverrides,
     meta::row_data::RowData,
     monitor::{counter_type::CounterType, task_monitor_handle::TaskMonitorHandle},
     utils::limit_queue::LimitedQueue,
@@ -10,6 +11,7 @@ use crate::rdb_router::RdbRouter;
 pub struct BaseSinker {
     pub monitor: TaskMonitorHandle,
     pub monitor_interval: u64,
+    pub table_overrides: TableOverrides,
 }
 
 impl BaseSinker {
@@ -17,6 +19,22 @@ impl BaseSinker {
         Self {
             monitor,
             monitor_interval,
+            table_overrides: TableOverrides::default(),
+        }
+    }
+
+    pub fn with_table_overrides(mut self, table_overrides: TableOverrides) -> Self {
+        self.table_overrides = table_overrides;
+        self
+    }
+
+    /// batch_size of the table the rows belong to, rows of a batch are from the same table
+    pub fn batch_size_for(&self, rows: &[RowData], default: usize) -> usize {
+        match rows.first() {
+            Some(first) => self
+                .table_overrides
+                .batch_size(&first.schema, &first.tb, default),
+            None => default,
         }
     }
 
@@ -133,11 +151,16 @@ impl BaseSinker {
 #[macro_export(local_inner_macros)]
 macro_rules! call_batch_fn {
     ($self:ident, $data:ident, $batch_fn:expr) => {
+        call_batch_fn!($self, $data, $batch_fn, $self.batch_size)
+    };
+
+    ($self:ident, $data:ident, $batch_fn:expr, $max_batch_size:expr) => {
         let all_count = $data.len();
+        let max_batch_size = $max_batch_size;
         let mut sinked_count = 0;
 
         loop {
-            let mut batch_size = $self.batch_size;
+            let mut batch_size = max_batch_size;
             if all_count - sinked_count < batch_size {
                 batch_size = all_count - sinked_count;
             }
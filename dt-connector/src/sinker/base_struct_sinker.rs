@@ -6,13 +6,20 @@ use tokio::time::Instant;
 
 use crate::sinker::base_sinker::BaseSinker;
 use dt_common::{
-    config::config_enums::ConflictPolicyEnum, error::Error, log_error, log_info,
-    meta::struct_meta::struct_data::StructData, rdb_filter::RdbFilter,
+    config::config_enums::{ConflictPolicyEnum, DbType},
+    error::Error,
+    log_error, log_info, log_warn,
+    meta::struct_meta::{
+        struct_data::StructData,
+        structure::constraint::{Constraint, ConstraintType},
+    },
+    rdb_filter::RdbFilter,
     utils::limit_queue::LimitedQueue,
 };
 
 pub struct BaseStructSinker {}
 
+#[derive(Clone)]
 pub enum DBConnPool {
     MySQL(Pool<MySql>),
     PostgreSQL(Pool<Postgres>),
@@ -22,6 +29,7 @@ impl BaseStructSinker {
     pub async fn sink_structs(
         conn_pool: &DBConnPool,
         conflict_policy: &ConflictPolicyEnum,
+        object_conflict_policy: &ConflictPolicyEnum,
         data: Vec<StructData>,
         filter: &RdbFilter,
         base_sinker: &BaseSinker,
@@ -33,6 +41,12 @@ impl BaseStructSinker {
         let mut data_len = 0;
         for mut struct_data in data {
             data_len += 1;
+            // views, triggers and routines may fail by missing dependencies or privileges
+            let conflict_policy = if struct_data.statement.is_db_object() {
+                object_conflict_policy
+            } else {
+                conflict_policy
+            };
             for (_, sql) in struct_data.statement.to_sqls(filter)?.iter() {
                 log_info!("ddl begin: {}", sql);
                 let start_time = Instant::now();
@@ -71,7 +85,32 @@ impl BaseStructSinker {
         Ok(())
     }
 
-    async fn execute(pool: &DBConnPool, sql: &str) -> anyhow::Result<()> {
+    /// CHECK / EXCLUSION constraints can not be translated to targets of other engines
+    pub fn warn_skipped_constraints(constraints: &[Constraint], target_db_type: &DbType) {
+        for i in constraints.iter() {
+            if !matches!(
+                i.constraint_type,
+                ConstraintType::Check | ConstraintType::Exclusion
+            ) {
+                continue;
+            }
+            let schema = if i.database_name.is_empty() {
+                &i.schema_name
+            } else {
+                &i.database_name
+            };
+            log_warn!(
+                "constraint: {}.{}.{} skipped, not supported by {}, definition: {}",
+                schema,
+                i.table_name,
+                i.constraint_name,
+                target_db_type,
+                i.definition
+            );
+        }
+    }
+
+    pub async fn execute(pool: &DBConnPool, sql: &str) -> anyhow::Result<()> {
         match pool {
             DBConnPool::MySQL(pool) => match query(sql).execute(pool).await {
                 Ok(_) => Ok(()),
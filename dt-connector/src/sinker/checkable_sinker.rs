@@ -1,8 +1,8 @@
 use async_trait::async_trait;
 use dt_common::log_warn;
 use dt_common::meta::{
-    dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem, row_data::RowData,
-    struct_meta::struct_data::StructData,
+    dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem, position::Position,
+    row_data::RowData, struct_meta::struct_data::StructData,
 };
 
 use crate::{checker::DataCheckerHandle, Sinker};
@@ -78,6 +78,18 @@ impl<S: CheckableSink + Send> Sinker for SinkerWithChecker<S> {
         delegate_inner!(self, handle_control_item(item))
     }
 
+    async fn sink_snapshot_watermark(&mut self, position: &Position) -> anyhow::Result<()> {
+        delegate_inner!(self, sink_snapshot_watermark(position))
+    }
+
+    async fn begin_trx(&mut self) -> anyhow::Result<()> {
+        delegate_inner!(self, begin_trx())
+    }
+
+    async fn commit_trx(&mut self) -> anyhow::Result<()> {
+        delegate_inner!(self, commit_trx())
+    }
+
     fn get_id(&self) -> String {
         self.inner.get_id()
     }
@@ -0,0 +1,351 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use dt_common::{
+    config::circuit_breaker_config::CircuitBreakerConfig,
+    log_info, log_warn,
+    monitor::{counter_type::CounterType, task_monitor_handle::TaskMonitorHandle},
+};
+use sqlx::{mysql::MySqlDatabaseError, MySql, Pool, Postgres};
+use tokio::sync::{Mutex, Notify};
+
+/// Target health check of [circuit_breaker] probe_sql, the target is healthy if the sql
+/// returns any row, e.g. SELECT 1 FROM lag_view WHERE lag_secs < 30.
+pub enum TargetProbe {
+    Mysql {
+        conn_pool: Pool<MySql>,
+        sql: String,
+    },
+    Pg {
+        conn_pool: Pool<Postgres>,
+        sql: String,
+    },
+}
+
+impl TargetProbe {
+    pub async fn probe(&self) -> anyhow::Result<()> {
+        let (row_found, sql) = match self {
+            Self::Mysql { conn_pool, sql } => (
+                sqlx::query(sql).fetch_optional(conn_pool).await?.is_some(),
+                sql,
+            ),
+            Self::Pg { conn_pool, sql } => (
+                sqlx::query(sql).fetch_optional(conn_pool).await?.is_some(),
+                sql,
+            ),
+        };
+        if !row_found {
+            bail!("target probe returned no rows, sql: {}", sql)
+        }
+        Ok(())
+    }
+}
+
+/// Shared by the pipeline, which probes before pulling data, and the mysql / pg sinkers,
+/// which retry failed rows in place.
+pub type SharedCircuitBreaker = Arc<Mutex<CircuitBreaker>>;
+
+/// Pauses sinking while the target is unhealthy, e.g. out of disk or far behind in replication.
+///
+/// The breaker opens on sink errors listed in trip_errors or a failed probe, the sinker keeps
+/// the failed rows and the pipeline stops pulling data until the probe passes, or until the
+/// next retry if there is no probe, extraction pauses once the buffer is full.
+///
+/// The lock is only taken to read or update the state, waiters sleep without it and are all
+/// woken once any of them closes the breaker.
+pub struct CircuitBreaker {
+    trip_errors: Vec<String>,
+    probe: Option<Arc<TargetProbe>>,
+    probe_interval: Duration,
+    // 0 to wait until the target recovers
+    max_open_secs: u64,
+    opened_at: Option<Instant>,
+    closed_at: Option<Instant>,
+    closed: Arc<Notify>,
+    last_probe: Instant,
+    // circuit_breaker_open of the pipeline
+    monitor: TaskMonitorHandle,
+}
+
+impl CircuitBreaker {
+    pub fn new(
+        config: &CircuitBreakerConfig,
+        probe: Option<TargetProbe>,
+        monitor: TaskMonitorHandle,
+    ) -> Self {
+        Self {
+            trip_errors: config.trip_errors.clone(),
+            probe: probe.map(Arc::new),
+            probe_interval: Duration::from_secs(config.probe_interval_secs),
+            max_open_secs: config.max_open_secs,
+            opened_at: None,
+            closed_at: None,
+            closed: Arc::new(Notify::new()),
+            last_probe: Instant::now(),
+            monitor,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.opened_at.is_some()
+    }
+
+    /// Whether a sink error opens the breaker, other errors fail the task as before.
+    pub fn should_trip(&self, error: &anyhow::Error) -> bool {
+        if self.trip_errors.is_empty() {
+            return true;
+        }
+        let codes: Vec<String> = error.chain().flat_map(Self::error_codes).collect();
+        let message = format!("{:#}", error);
+        self.trip_errors
+            .iter()
+            .any(|trip| codes.contains(trip) || message.contains(trip.as_str()))
+    }
+
+    /// Probes the target while the breaker is closed, the probe error is returned if the
+    /// breaker is opened by it.
+    pub async fn check(&mut self) -> Option<anyhow::Error> {
+        let probe = self.probe.as_ref()?;
+        if self.is_open() || self.last_probe.elapsed() < self.probe_interval {
+            return None;
+        }
+        self.last_probe = Instant::now();
+        let error = probe.probe().await.err()?;
+        self.open(&error);
+        Some(error)
+    }
+
+    pub fn open(&mut self, error: &anyhow::Error) {
+        if self.opened_at.is_none() {
+            log_warn!("circuit breaker opened, sinking paused, error: {:#}", error);
+            self.opened_at = Some(Instant::now());
+            self.set_open_counter(true);
+        }
+    }
+
+    pub fn close(&mut self) {
+        if let Some(opened_at) = self.opened_at.take() {
+            log_info!(
+                "circuit breaker closed after {} ms, sinking resumed",
+                opened_at.elapsed().as_millis()
+            );
+            self.set_open_counter(false);
+            self.closed_at = Some(Instant::now());
+            self.closed.notify_waiters();
+        }
+    }
+
+    /// Called by sinkers on a sink error, opens the breaker and waits until the target
+    /// recovers so the failed rows can be retried, the error is returned if it does not trip.
+    /// Returns at once if the breaker was closed after the failed attempt started.
+    pub async fn wait_to_retry(
+        breaker: &SharedCircuitBreaker,
+        error: anyhow::Error,
+        attempt_at: Instant,
+    ) -> anyhow::Result<()> {
+        {
+            let mut breaker = breaker.lock().await;
+            if !breaker.should_trip(&error) {
+                return Err(error);
+            }
+            if !breaker.is_open() && breaker.closed_at.is_some_and(|at| at > attempt_at) {
+                return Ok(());
+            }
+            breaker.open(&error);
+        }
+        Self::wait_until_recovered(breaker, error).await
+    }
+
+    /// Waits until the probe passes or another waiter closes the breaker, or for one probe
+    /// interval if there is no probe, the error is returned if the breaker has been open for
+    /// over max_open_secs.
+    pub async fn wait_until_recovered(
+        breaker: &SharedCircuitBreaker,
+        error: anyhow::Error,
+    ) -> anyhow::Result<()> {
+        let (opened_at, probe, probe_interval, max_open_secs, closed) = {
+            let mut breaker = breaker.lock().await;
+            (
+                *breaker.opened_at.get_or_insert_with(Instant::now),
+                breaker.probe.clone(),
+                breaker.probe_interval,
+                breaker.max_open_secs,
+                breaker.closed.clone(),
+            )
+        };
+        let mut error = error;
+        loop {
+            if max_open_secs > 0 && opened_at.elapsed() >= Duration::from_secs(max_open_secs) {
+                return Err(error.context(format!(
+                    "target not recovered in circuit breaker max_open_secs: {}",
+                    max_open_secs
+                )));
+            }
+
+            let notified = closed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if !breaker.lock().await.is_open() {
+                return Ok(());
+            }
+            tokio::select! {
+                _ = &mut notified => return Ok(()),
+                _ = tokio::time::sleep(probe_interval) => {}
+            }
+
+            let Some(probe) = &probe else {
+                return Ok(());
+            };
+            breaker.lock().await.last_probe = Instant::now();
+            match probe.probe().await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    log_warn!("circuit breaker still open, probe error: {:#}", e);
+                    error = e;
+                }
+            }
+        }
+    }
+
+    fn set_open_counter(&self, open: bool) {
+        self.monitor.set_counter(
+            self.monitor.default_task_id(),
+            CounterType::CircuitBreakerOpen,
+            open as u64,
+        );
+    }
+
+    // mysql error number and sqlstate, or pg sqlstate
+    fn error_codes(cause: &(dyn std::error::Error + 'static)) -> Vec<String> {
+        let mut codes = Vec::new();
+        if let Some(sqlx::Error::Database(db_error)) = cause.downcast_ref::<sqlx::Error>() {
+            if let Some(mysql_error) = db_error.try_downcast_ref::<MySqlDatabaseError>() {
+                codes.push(mysql_error.number().to_string());
+            }
+            if let Some(code) = db_error.code() {
+                codes.push(code.to_string());
+            }
+        }
+        // errors of PgPipeline
+        if let Some(pg_error) = cause.downcast_ref::<tokio_postgres::Error>() {
+            if let Some(code) = pg_error.code() {
+                codes.push(code.code().to_string());
+            }
+        }
+        codes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared_breaker(probe_interval_secs: u64, max_open_secs: u64) -> SharedCircuitBreaker {
+        let config = CircuitBreakerConfig {
+            trip_errors: vec!["53100".into(), "disk full".into()],
+            probe_sql: String::new(),
+            probe_interval_secs,
+            max_open_secs,
+        };
+        Arc::new(Mutex::new(CircuitBreaker::new(
+            &config,
+            None,
+            TaskMonitorHandle::default(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker() {
+        let breaker = shared_breaker(1, 1);
+        {
+            let mut breaker = breaker.lock().await;
+            assert!(breaker.should_trip(&anyhow::anyhow!("mysql error: disk full")));
+            assert!(!breaker.should_trip(&anyhow::anyhow!("duplicate entry")));
+            assert!(breaker.check().await.is_none());
+        }
+
+        // without probe, the batch is retried after a probe interval
+        let error = anyhow::anyhow!("disk full");
+        breaker.lock().await.open(&error);
+        assert!(breaker.lock().await.is_open());
+        assert!(CircuitBreaker::wait_until_recovered(&breaker, error)
+            .await
+            .is_ok());
+        assert!(
+            CircuitBreaker::wait_until_recovered(&breaker, anyhow::anyhow!("disk full"))
+                .await
+                .is_err()
+        );
+        breaker.lock().await.close();
+        assert!(!breaker.lock().await.is_open());
+
+        // errors not in trip_errors are returned at once
+        let attempt_at = Instant::now();
+        assert!(CircuitBreaker::wait_to_retry(
+            &breaker,
+            anyhow::anyhow!("duplicate entry"),
+            attempt_at
+        )
+        .await
+        .is_err());
+        assert!(!breaker.lock().await.is_open());
+        assert!(
+            CircuitBreaker::wait_to_retry(&breaker, anyhow::anyhow!("disk full"), attempt_at)
+                .await
+                .is_ok()
+        );
+        assert!(breaker.lock().await.is_open());
+
+        let breaker = CircuitBreaker::new(
+            &CircuitBreakerConfig::default(),
+            None,
+            TaskMonitorHandle::default(),
+        );
+        assert!(breaker.should_trip(&anyhow::anyhow!("duplicate entry")));
+    }
+
+    #[tokio::test]
+    async fn test_shared_by_sinkers() {
+        // a probe interval far longer than the test, waiters only return once woken
+        let breaker = shared_breaker(600, 0);
+        let attempt_at = Instant::now();
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let breaker = breaker.clone();
+                tokio::spawn(async move {
+                    CircuitBreaker::wait_to_retry(
+                        &breaker,
+                        anyhow::anyhow!("disk full"),
+                        attempt_at,
+                    )
+                    .await
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // waiters do not hold the lock, so the pipeline and other sinkers are not blocked
+        assert!(breaker.try_lock().unwrap().is_open());
+
+        // the first sinker to recover closes the breaker, the others retry at once
+        breaker.lock().await.close();
+        for waiter in waiters {
+            tokio::time::timeout(Duration::from_secs(1), waiter)
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+        }
+
+        // a sinker failed before the breaker was closed retries without reopening it
+        assert!(
+            CircuitBreaker::wait_to_retry(&breaker, anyhow::anyhow!("disk full"), attempt_at)
+                .await
+                .is_ok()
+        );
+        assert!(!breaker.lock().await.is_open());
+    }
+}
@@ -1,15 +1,24 @@
-use std::{cmp, collections::HashMap};
+use std::{
+    cmp,
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 use anyhow::bail;
 use async_trait::async_trait;
 use chrono::Utc;
+use openssl::sha::Sha256;
 use reqwest::{Client, Method, Response, StatusCode};
-use tokio::time::Instant;
+use tokio::time::{sleep, Instant};
 
 use dt_common::{
     config::config_enums::DbType,
     error::Error,
-    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+    log_warn,
+    meta::{
+        col_value::ColValue, position::Position, row_data::RowData, row_type::RowType,
+        time::dt_temporal::DtTemporal,
+    },
     utils::{limit_queue::LimitedQueue, sql_util::SqlUtil},
 };
 
@@ -17,6 +26,8 @@ use crate::{call_batch_fn, sinker::base_sinker::BaseSinker, Sinker};
 
 const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
+// retries of inserts failed by network errors, only when the insert has a deduplication token
+const DEDUP_INSERT_MAX_RETRIES: u32 = 3;
 
 #[derive(Clone)]
 pub struct ClickhouseSinker {
@@ -28,6 +39,9 @@ pub struct ClickhouseSinker {
     pub password: String,
     pub base_sinker: BaseSinker,
     pub sync_timestamp: i64,
+    pub insert_dedup_token: bool,
+    // whether the table uses a Replicated* engine, which deduplicates inserts by token
+    pub replicated_tbs: HashMap<(String, String), bool>,
 }
 
 #[async_trait]
@@ -37,7 +51,8 @@ impl Sinker for ClickhouseSinker {
             return Ok(());
         }
 
-        call_batch_fn!(self, data, Self::batch_sink);
+        let batch_size = self.base_sinker.batch_size_for(&data, self.batch_size);
+        call_batch_fn!(self, data, Self::batch_sink, batch_size);
         Ok(())
     }
 }
@@ -68,6 +83,16 @@ impl ClickhouseSinker {
         let db = SqlUtil::escape_by_db_type(&data[start_index].schema, &DbType::ClickHouse);
         let tb = SqlUtil::escape_by_db_type(&data[start_index].tb, &DbType::ClickHouse);
         self.sync_timestamp = cmp::max(Utc::now().timestamp_millis(), self.sync_timestamp + 1);
+        // computed before the rows are converted
+        let dedup_token = if self.insert_dedup_token
+            && self
+                .is_replicated_tb(&data[start_index].schema, &data[start_index].tb)
+                .await?
+        {
+            Self::build_dedup_token(&data[start_index..start_index + batch_size])?
+        } else {
+            None
+        };
 
         let mut data_size = 0;
         // build stream load data
@@ -91,15 +116,36 @@ impl ClickhouseSinker {
 
         // curl -X POST -d @data.json 'http://localhost:8123/?query=INSERT%20INTO%test_db.tb_1%20FORMAT%20JSON' --user admin:123456
         let body = serde_json::to_string(&load_data)?;
-        let url = format!(
+        let mut url = format!(
             "http://{}:{}/?query=INSERT INTO {}.{} FORMAT JSON",
             self.host, self.port, db, tb
         );
-        let request = self.build_request(&url, body)?;
+        if let Some(token) = &dedup_token {
+            url.push_str(&format!("&insert_deduplication_token={}", token));
+        }
 
         let start_time = Instant::now();
         let mut rts = LimitedQueue::new(1);
-        let response = self.http_client.execute(request).await?;
+        let mut retries = 0;
+        let response = loop {
+            let request = self.build_request(&url, body.clone())?;
+            match self.http_client.execute(request).await {
+                Ok(response) => break response,
+                // a retried insert with the same token is deduplicated by the server
+                Err(err) if dedup_token.is_some() && retries < DEDUP_INSERT_MAX_RETRIES => {
+                    retries += 1;
+                    log_warn!(
+                        "insert into {}.{} failed, retry: {}, error: {}",
+                        db,
+                        tb,
+                        retries,
+                        err
+                    );
+                    sleep(Duration::from_secs(retries as u64)).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
         rts.push((start_time.elapsed().as_millis() as u64, 1));
         let task_id = self
             .base_sinker
@@ -113,6 +159,70 @@ impl ClickhouseSinker {
         Ok(data_size)
     }
 
+    async fn is_replicated_tb(&mut self, schema: &str, tb: &str) -> anyhow::Result<bool> {
+        let key = (schema.to_string(), tb.to_string());
+        if let Some(replicated) = self.replicated_tbs.get(&key) {
+            return Ok(*replicated);
+        }
+
+        let sql = format!(
+            "SELECT engine FROM system.tables WHERE database = '{}' AND name = '{}' FORMAT TabSeparated",
+            Self::escape_literal(schema),
+            Self::escape_literal(tb)
+        );
+        let url = format!("http://{}:{}/", self.host, self.port);
+        let request = self.build_request(&url, sql)?;
+        let response = self.http_client.execute(request).await?;
+        let status_code = response.status();
+        let engine = response.text().await?;
+        if status_code != StatusCode::OK {
+            bail! {Error::HttpError(format!(
+                "query table engine failed, status_code: {}, response_text: {:?}",
+                status_code, engine
+            ))}
+        }
+
+        let replicated = engine.trim().starts_with("Replicated");
+        self.replicated_tbs.insert(key, replicated);
+        Ok(replicated)
+    }
+
+    /// Token derived from the positions and contents of the batch, so that the same batch
+    /// re-sent gets the same token, while batches split from one source event do not.
+    /// None if any row has no position.
+    fn build_dedup_token(data: &[RowData]) -> anyhow::Result<Option<String>> {
+        let Some(first) = data.first() else {
+            return Ok(None);
+        };
+
+        let mut hasher = Sha256::new();
+        hasher.update(serde_json::to_string(&(&first.schema, &first.tb))?.as_bytes());
+        for row_data in data {
+            match row_data.position.as_deref() {
+                None | Some(Position::None) => return Ok(None),
+                Some(position) => hasher.update(position.to_string().as_bytes()),
+            }
+            // sorted since HashMap iteration order differs between processes
+            let sorted = |col_values: &Option<HashMap<String, ColValue>>| {
+                col_values
+                    .as_ref()
+                    .map(|v| v.iter().collect::<BTreeMap<_, _>>())
+            };
+            let row_type: &str = (&row_data.row_type).into();
+            let identity = serde_json::to_string(&(
+                row_type,
+                sorted(&row_data.before),
+                sorted(&row_data.after),
+            ))?;
+            hasher.update(identity.as_bytes());
+        }
+        Ok(Some(hex::encode(hasher.finish())))
+    }
+
+    fn escape_literal(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('\'', "\\'")
+    }
+
     fn convert_col_values(col_values: &mut HashMap<String, ColValue>) -> anyhow::Result<()> {
         let mut new_col_values: HashMap<String, ColValue> = HashMap::new();
         for (col, col_value) in col_values.iter() {
@@ -134,11 +244,12 @@ impl ClickhouseSinker {
                 }
 
                 ColValue::Time(v) | ColValue::Timestamp(v) => {
-                    // postgres: 10:20:12+00 -> 10:20:12, 2019-02-10 11:35:00+00 -> 2019-02-10 11:35:00
-                    new_col_values.insert(
-                        col.to_owned(),
-                        ColValue::String(v.trim_end_matches("+00").to_string()),
-                    );
+                    // postgres: 10:20:12+00 -> 10:20:12, 2019-02-10 11:35:00.123456+08 -> 2019-02-10 03:35:00.123456
+                    let value = match DtTemporal::parse(v) {
+                        Some(temporal) => temporal.to_naive_string(None),
+                        None => v.trim_end_matches("+00").to_string(),
+                    };
+                    new_col_values.insert(col.to_owned(), ColValue::String(value));
                 }
 
                 _ => {}
@@ -197,3 +308,56 @@ impl ClickhouseSinker {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn build_row(next_event_position: u32, id: i64) -> RowData {
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::LongLong(id)),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]);
+        let mut row_data = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        row_data.position = Some(Arc::new(Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+            source_id: String::new(),
+        }));
+        row_data
+    }
+
+    #[test]
+    fn test_build_dedup_token() {
+        let batch = vec![build_row(100, 1), build_row(100, 2)];
+        let token = ClickhouseSinker::build_dedup_token(&batch).unwrap();
+        assert!(token.is_some());
+        assert_eq!(
+            token,
+            ClickhouseSinker::build_dedup_token(&batch.clone()).unwrap()
+        );
+
+        // rows of the same binlog event in different batches
+        let other = vec![build_row(100, 3), build_row(100, 4)];
+        assert_ne!(token, ClickhouseSinker::build_dedup_token(&other).unwrap());
+
+        let mut no_position = batch.clone();
+        no_position[1].position = None;
+        assert_eq!(
+            ClickhouseSinker::build_dedup_token(&no_position).unwrap(),
+            None
+        );
+    }
+}
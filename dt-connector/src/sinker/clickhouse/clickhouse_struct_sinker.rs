@@ -1,9 +1,9 @@
-use crate::{rdb_router::RdbRouter, Sinker};
+use crate::{rdb_router::RdbRouter, sinker::base_struct_sinker::BaseStructSinker, Sinker};
 
 use anyhow::bail;
 use clickhouse::Client;
 use dt_common::{
-    config::config_enums::ConflictPolicyEnum,
+    config::config_enums::{ConflictPolicyEnum, DbType},
     log_error, log_info,
     meta::{
         mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
@@ -15,11 +15,14 @@ use dt_common::{
             structure::{column::Column, table::Table},
         },
     },
+    quote_mysql,
     rdb_filter::RdbFilter,
 };
 
 use async_trait::async_trait;
 
+use quote_mysql as quote;
+
 const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const SIGN_COL_TYPE: &str = "Int8";
 const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
@@ -42,13 +45,17 @@ impl Sinker for ClickhouseStructSinker {
             match i.statement {
                 StructStatement::MysqlCreateDatabase(statement) => {
                     let sql = format!(
-                        "CREATE DATABASE IF NOT EXISTS `{}`",
-                        statement.database.name
+                        "CREATE DATABASE IF NOT EXISTS {}",
+                        quote!(statement.database.name)
                     );
                     self.execute_sql(&sql).await?;
                 }
 
                 StructStatement::MysqlCreateTable(statement) => {
+                    BaseStructSinker::warn_skipped_constraints(
+                        &statement.constraints,
+                        &DbType::ClickHouse,
+                    );
                     let (schema, tb) = if let Some(router) = &self.router {
                         router.reverse_get_tb_map(
                             &statement.table.database_name,
@@ -71,11 +78,18 @@ impl Sinker for ClickhouseStructSinker {
                 }
 
                 StructStatement::PgCreateSchema(statement) => {
-                    let sql = format!("CREATE DATABASE IF NOT EXISTS `{}`", statement.schema.name);
+                    let sql = format!(
+                        "CREATE DATABASE IF NOT EXISTS {}",
+                        quote!(statement.schema.name)
+                    );
                     self.execute_sql(&sql).await?;
                 }
 
                 StructStatement::PgCreateTable(statement) => {
+                    BaseStructSinker::warn_skipped_constraints(
+                        &statement.constraints,
+                        &DbType::ClickHouse,
+                    );
                     let (schema, tb) = if let Some(router) = &self.router {
                         router.reverse_get_tb_map(
                             &statement.table.schema_name,
@@ -126,8 +140,12 @@ impl ClickhouseStructSinker {
         }
 
         // sign and timestamp cols
-        dst_cols.push(format!("`{}` {}", SIGN_COL_NAME, SIGN_COL_TYPE));
-        dst_cols.push(format!("`{}` {}", TIMESTAMP_COL_NAME, TIMESTAMP_COL_TYPE));
+        dst_cols.push(format!("{} {}", quote!(SIGN_COL_NAME), SIGN_COL_TYPE));
+        dst_cols.push(format!(
+            "{} {}",
+            quote!(TIMESTAMP_COL_NAME),
+            TIMESTAMP_COL_TYPE
+        ));
 
         // engine, default: ReplacingMergeTree
         let schema = if mysql_tb_meta.is_some() {
@@ -136,18 +154,18 @@ impl ClickhouseStructSinker {
             &table.schema_name
         };
         let mut sql = format!(
-            "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({}) ENGINE = ReplacingMergeTree(`{}`)",
-            schema,
-            table.table_name,
+            "CREATE TABLE IF NOT EXISTS {}.{} ({}) ENGINE = ReplacingMergeTree({})",
+            quote!(schema),
+            quote!(table.table_name),
             dst_cols.join(", "),
-            TIMESTAMP_COL_NAME
+            quote!(TIMESTAMP_COL_NAME)
         );
 
         if !rdb_tb_meta.id_cols.is_empty() {
             let order_by = rdb_tb_meta
                 .id_cols
                 .iter()
-                .map(|i| format!("`{}`", i))
+                .map(|i| quote!(i).to_string())
                 .collect::<Vec<String>>()
                 .join(",");
             sql = format!("{} PRIMARY KEY ({}) ORDER BY ({})", sql, order_by, order_by);
@@ -169,9 +187,9 @@ impl ClickhouseStructSinker {
 
         // Nested type Array() cannot be inside Nullable type
         let mut dst_col = if column.is_nullable && !dst_col_type.starts_with("Array") {
-            format!("`{}` Nullable({})", col, dst_col_type)
+            format!("{} Nullable({})", quote!(col), dst_col_type)
         } else {
-            format!("`{}` {}", col, dst_col_type)
+            format!("{} {}", quote!(col), dst_col_type)
         };
 
         if !column.column_comment.is_empty() {
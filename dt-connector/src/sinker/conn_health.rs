@@ -0,0 +1,213 @@
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use dt_common::{log_info, log_warn};
+use sqlx::mysql::MySqlDatabaseError;
+
+const RETRY_INTERVAL_MILLIS: u64 = 500;
+const MAX_RETRY_INTERVAL_MILLIS: u64 = 10_000;
+
+// mysql: ER_SERVER_SHUTDOWN, ER_CONNECTION_KILLED, CR_CONNECTION_ERROR, CR_CONN_HOST_ERROR,
+// CR_SERVER_GONE_ERROR, CR_SERVER_LOST
+const MYSQL_CONN_ERRORS: [u16; 6] = [1053, 1927, 2002, 2003, 2006, 2013];
+// pg: admin_shutdown, crash_shutdown, cannot_connect_now, class 08 is checked by prefix
+const PG_CONN_ERRORS: [&str; 3] = ["57P01", "57P02", "57P03"];
+
+/// Keeps sinkers alive through short outages of the target, e.g. failovers and restarts.
+///
+/// Idle connections are checked before the next batch, a batch failed by a lost connection
+/// pauses the pipeline and reconnects until the target recovers or the grace period runs out.
+#[derive(Clone, Debug, Default)]
+pub struct ConnHealth {
+    // 0 to fail at once, as without health checks
+    pub grace_period_secs: u64,
+    // 0 to disable checks of idle connections
+    pub check_interval_secs: u64,
+    pub last_active: Option<Instant>,
+}
+
+impl ConnHealth {
+    pub fn new(grace_period_secs: u64, check_interval_secs: u64) -> Self {
+        Self {
+            grace_period_secs,
+            check_interval_secs,
+            last_active: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.grace_period_secs > 0
+    }
+
+    pub fn needs_check(&self) -> bool {
+        self.check_interval_secs > 0
+            && self.last_active.is_some_and(|last_active| {
+                last_active.elapsed() >= Duration::from_secs(self.check_interval_secs)
+            })
+    }
+
+    pub fn mark_active(&mut self) {
+        self.last_active = Some(Instant::now());
+    }
+
+    pub fn is_connection_error(error: &anyhow::Error) -> bool {
+        error.chain().any(|cause| {
+            if let Some(sqlx_error) = cause.downcast_ref::<sqlx::Error>() {
+                return match sqlx_error {
+                    sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::Protocol(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+                    | sqlx::Error::WorkerCrashed => true,
+                    sqlx::Error::Database(db_error) => {
+                        if let Some(mysql_error) = db_error.try_downcast_ref::<MySqlDatabaseError>()
+                        {
+                            return MYSQL_CONN_ERRORS.contains(&mysql_error.number());
+                        }
+                        db_error
+                            .code()
+                            .is_some_and(|code| Self::is_pg_conn_code(&code))
+                    }
+                    _ => false,
+                };
+            }
+            // errors of PgPipeline
+            if let Some(pg_error) = cause.downcast_ref::<tokio_postgres::Error>() {
+                return pg_error.is_closed()
+                    || pg_error
+                        .code()
+                        .is_some_and(|code| Self::is_pg_conn_code(code.code()));
+            }
+            if let Some(redis_error) = cause.downcast_ref::<redis::RedisError>() {
+                return Self::is_redis_conn_error(redis_error);
+            }
+            if let Some(kafka_error) = cause.downcast_ref::<kafka::error::Error>() {
+                return Self::is_kafka_conn_error(kafka_error);
+            }
+            cause.downcast_ref::<std::io::Error>().is_some()
+        })
+    }
+
+    pub fn is_redis_conn_error(error: &redis::RedisError) -> bool {
+        error.is_io_error()
+            || error.is_connection_dropped()
+            || error.is_connection_refusal()
+            || error.is_timeout()
+    }
+
+    pub fn is_kafka_conn_error(error: &kafka::error::Error) -> bool {
+        matches!(
+            error,
+            kafka::error::Error::Io(_) | kafka::error::Error::NoHostReachable
+        )
+    }
+
+    fn is_pg_conn_code(code: &str) -> bool {
+        code.starts_with("08") || PG_CONN_ERRORS.contains(&code)
+    }
+
+    /// Pauses and calls reconnect until it succeeds, the error is returned if the target
+    /// is not back within the grace period.
+    pub async fn recover<T, F, Fut>(
+        &mut self,
+        target: &str,
+        error: anyhow::Error,
+        mut reconnect: F,
+    ) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        if !self.is_enabled() {
+            return Err(error);
+        }
+
+        let grace_period = Duration::from_secs(self.grace_period_secs);
+        let start_time = Instant::now();
+        let mut error = error;
+        let mut retries = 0;
+        loop {
+            let elapsed = start_time.elapsed();
+            if elapsed >= grace_period {
+                return Err(error.context(format!(
+                    "{} target not recovered in grace period: {}s",
+                    target, self.grace_period_secs
+                )));
+            }
+
+            retries += 1;
+            let backoff = self.backoff(retries).min(grace_period - elapsed);
+            log_warn!(
+                "{} target connection lost, pipeline paused, reconnect: {} in {} ms, error: {}",
+                target,
+                retries,
+                backoff.as_millis(),
+                error
+            );
+            tokio::time::sleep(backoff).await;
+
+            match reconnect().await {
+                Ok(conn) => {
+                    log_info!(
+                        "{} target reconnected after {} ms",
+                        target,
+                        start_time.elapsed().as_millis()
+                    );
+                    self.mark_active();
+                    return Ok(conn);
+                }
+                Err(e) => error = e,
+            }
+        }
+    }
+
+    fn backoff(&self, retries: u32) -> Duration {
+        Duration::from_millis(
+            RETRY_INTERVAL_MILLIS
+                .saturating_mul(1u64 << retries.saturating_sub(1).min(16))
+                .min(MAX_RETRY_INTERVAL_MILLIS),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recover() {
+        let error = anyhow::Error::from(sqlx::Error::PoolTimedOut);
+        assert!(ConnHealth::is_connection_error(&error));
+        assert!(!ConnHealth::is_connection_error(&anyhow::anyhow!(
+            "duplicate entry"
+        )));
+
+        // disabled, the error is returned without reconnecting
+        let mut conn_health = ConnHealth::new(0, 30);
+        let result = conn_health
+            .recover("mysql", error, || async { Ok(()) })
+            .await;
+        assert!(result.is_err());
+        assert!(!conn_health.needs_check());
+
+        let mut conn_health = ConnHealth::new(5, 30);
+        let mut attempts = 0;
+        let result = conn_health
+            .recover("mysql", anyhow::anyhow!("server lost"), || {
+                attempts += 1;
+                let attempt = attempts;
+                async move {
+                    if attempt < 2 {
+                        anyhow::bail!("server lost")
+                    }
+                    Ok(attempt)
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 2);
+        assert!(conn_health.last_active.is_some());
+    }
+}
@@ -0,0 +1,780 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use sqlx::Row;
+
+use dt_common::{
+    config::config_enums::{DbType, DdlGuardPolicy},
+    error::Error,
+    log_warn,
+    meta::ddl_meta::{ddl_data::DdlData, ddl_statement::DdlStatement},
+};
+
+use crate::sinker::base_struct_sinker::DBConnPool;
+
+/// checks captured ddls against the current target structure before they are applied,
+/// so a ddl which does not fit the target is not applied halfway in heterogeneous pipelines
+#[derive(Clone)]
+pub struct DdlGuard {
+    pub policy: DdlGuardPolicy,
+    pub target_conn_pool: DBConnPool,
+}
+
+#[derive(Debug, PartialEq)]
+enum ColumnChange {
+    Add {
+        col: String,
+        if_not_exists: bool,
+    },
+    Drop {
+        col: String,
+        if_exists: bool,
+    },
+    // MODIFY / CHANGE of mysql, ALTER ... TYPE of pg
+    Modify {
+        col: String,
+        new_col: String,
+        col_type: String,
+    },
+    Rename {
+        col: String,
+        new_col: String,
+    },
+    // SET DEFAULT, SET NOT NULL ...
+    Alter {
+        col: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+enum TypeFamily {
+    // rank by size, tinyint: 1 .. bigint: 5
+    Integer(u8),
+    Decimal,
+    // float: 1, double: 2
+    Float(u8),
+    Text,
+    Binary,
+    Temporal,
+    Other,
+}
+
+struct ColType {
+    base: String,
+    args: Vec<u64>,
+    unsigned: bool,
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+impl DdlGuard {
+    /// Whether the ddl should be applied, fails if it does not fit and the policy is halt.
+    pub async fn check(&self, ddl_data: &DdlData) -> anyhow::Result<bool> {
+        let Some(reason) = self.find_conflict(ddl_data).await? else {
+            return Ok(true);
+        };
+
+        match self.policy {
+            DdlGuardPolicy::None => Ok(true),
+            DdlGuardPolicy::Warn => {
+                log_warn!(
+                    "ddl does not fit the target: {}, apply it anyway, sql: {}",
+                    reason,
+                    ddl_data.to_sql()
+                );
+                Ok(true)
+            }
+            DdlGuardPolicy::Skip => {
+                log_warn!(
+                    "ddl does not fit the target: {}, skipped, sql: {}",
+                    reason,
+                    ddl_data.to_sql()
+                );
+                Ok(false)
+            }
+            DdlGuardPolicy::Halt => bail!(Error::SinkerError(format!(
+                "ddl does not fit the target: {}, sql: {}",
+                reason,
+                ddl_data.to_sql()
+            ))),
+        }
+    }
+
+    async fn find_conflict(&self, ddl_data: &DdlData) -> anyhow::Result<Option<String>> {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        // (whether the table should exist, None if not checked; column changes)
+        let (tb_should_exist, changes) = match &ddl_data.statement {
+            DdlStatement::MysqlCreateTable(s) => ((!s.if_not_exists).then_some(false), vec![]),
+            DdlStatement::PgCreateTable(s) => ((!s.if_not_exists).then_some(false), vec![]),
+            DdlStatement::DropTable(s) => ((!s.if_exists).then_some(true), vec![]),
+            DdlStatement::MysqlAlterTable(s) => (Some(true), Self::parse_alter_table(&s.unparsed)),
+            DdlStatement::PgAlterTable(s) => (
+                (!s.if_exists).then_some(true),
+                Self::parse_alter_table(&s.unparsed),
+            ),
+            DdlStatement::PgAlterTableRename(s) => ((!s.if_exists).then_some(true), vec![]),
+            DdlStatement::PgAlterTableSetSchema(s) => ((!s.if_exists).then_some(true), vec![]),
+            DdlStatement::MysqlAlterTableRename(_)
+            | DdlStatement::RenameTable(_)
+            | DdlStatement::MysqlTruncateTable(_)
+            | DdlStatement::PgTruncateTable(_)
+            | DdlStatement::MysqlCreateIndex(_)
+            | DdlStatement::PgCreateIndex(_) => (Some(true), vec![]),
+            _ => return Ok(None),
+        };
+
+        let target_cols = self.fetch_target_cols(&schema, &tb).await?;
+        let Some(target_cols) = target_cols else {
+            if tb_should_exist == Some(true) {
+                return Ok(Some(format!("table `{}`.`{}` does not exist", schema, tb)));
+            }
+            return Ok(None);
+        };
+        if tb_should_exist == Some(false) {
+            return Ok(Some(format!("table `{}`.`{}` already exists", schema, tb)));
+        }
+
+        if matches!(
+            ddl_data.statement,
+            DdlStatement::MysqlAlterTableRename(_)
+                | DdlStatement::RenameTable(_)
+                | DdlStatement::PgAlterTableRename(_)
+        ) {
+            let (new_schema, new_tb) = ddl_data.get_rename_to_schema_tb();
+            if !new_tb.is_empty()
+                && self
+                    .fetch_target_cols(&new_schema, &new_tb)
+                    .await?
+                    .is_some()
+            {
+                return Ok(Some(format!(
+                    "table `{}`.`{}` already exists",
+                    new_schema, new_tb
+                )));
+            }
+        }
+
+        let db_type = self.db_type();
+        Ok(Self::find_col_conflict(&target_cols, &changes, &db_type)
+            .map(|reason| format!("{} in `{}`.`{}`", reason, schema, tb)))
+    }
+
+    fn find_col_conflict(
+        target_cols: &HashMap<String, String>,
+        changes: &[ColumnChange],
+        db_type: &DbType,
+    ) -> Option<String> {
+        // mysql column names are case insensitive
+        let normalize = |col: &str| match db_type {
+            DbType::Mysql => col.to_lowercase(),
+            _ => col.to_string(),
+        };
+        let mut cols: HashMap<String, String> = target_cols
+            .iter()
+            .map(|(col, col_type)| (normalize(col), col_type.clone()))
+            .collect();
+
+        // clauses of a statement are applied in order
+        for change in changes.iter() {
+            match change {
+                ColumnChange::Add { col, if_not_exists } => {
+                    if cols.contains_key(&normalize(col)) && !if_not_exists {
+                        return Some(format!("column `{}` already exists", col));
+                    }
+                    cols.insert(normalize(col), String::new());
+                }
+                ColumnChange::Drop { col, if_exists } => {
+                    if cols.remove(&normalize(col)).is_none() && !if_exists {
+                        return Some(format!("column `{}` does not exist", col));
+                    }
+                }
+                ColumnChange::Modify {
+                    col,
+                    new_col,
+                    col_type,
+                } => {
+                    let Some(old_type) = cols.remove(&normalize(col)) else {
+                        return Some(format!("column `{}` does not exist", col));
+                    };
+                    if normalize(col) != normalize(new_col)
+                        && cols.contains_key(&normalize(new_col))
+                    {
+                        return Some(format!("column `{}` already exists", new_col));
+                    }
+                    if !old_type.is_empty()
+                        && !Self::is_type_compatible(&old_type, col_type, db_type)
+                    {
+                        return Some(format!(
+                            "column `{}` changes from type: {} to: {}, existing values may not fit",
+                            col,
+                            old_type,
+                            col_type.trim()
+                        ));
+                    }
+                    cols.insert(normalize(new_col), col_type.clone());
+                }
+                ColumnChange::Rename { col, new_col } => {
+                    let Some(old_type) = cols.remove(&normalize(col)) else {
+                        return Some(format!("column `{}` does not exist", col));
+                    };
+                    if cols.contains_key(&normalize(new_col)) {
+                        return Some(format!("column `{}` already exists", new_col));
+                    }
+                    cols.insert(normalize(new_col), old_type);
+                }
+                ColumnChange::Alter { col } => {
+                    if !cols.contains_key(&normalize(col)) {
+                        return Some(format!("column `{}` does not exist", col));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Columns of the target table with their types, None if the table does not exist.
+    async fn fetch_target_cols(
+        &self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<Option<HashMap<String, String>>> {
+        let mut cols = HashMap::new();
+        match &self.target_conn_pool {
+            DBConnPool::MySQL(pool) => {
+                let sql = "SELECT COLUMN_NAME, COLUMN_TYPE FROM information_schema.columns
+                    WHERE table_schema = ? AND table_name = ?";
+                let rows = sqlx::query(sql)
+                    .bind(schema)
+                    .bind(tb)
+                    .fetch_all(pool)
+                    .await?;
+                if rows.is_empty() {
+                    return Ok(None);
+                }
+                for row in rows {
+                    let col: String = row.try_get(0)?;
+                    let col_type: String = row.try_get(1)?;
+                    cols.insert(col, col_type);
+                }
+            }
+            DBConnPool::PostgreSQL(pool) => {
+                // a pg table may have no columns
+                let sql = "SELECT a.attname::text, format_type(a.atttypid, a.atttypmod)
+                    FROM pg_catalog.pg_class c
+                    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+                    LEFT JOIN pg_catalog.pg_attribute a
+                        ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+                    WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('r', 'p')";
+                let rows = sqlx::query(sql)
+                    .bind(schema)
+                    .bind(tb)
+                    .fetch_all(pool)
+                    .await?;
+                if rows.is_empty() {
+                    return Ok(None);
+                }
+                for row in rows {
+                    let col: Option<String> = row.try_get(0)?;
+                    let col_type: Option<String> = row.try_get(1)?;
+                    if let (Some(col), Some(col_type)) = (col, col_type) {
+                        cols.insert(col, col_type);
+                    }
+                }
+            }
+        }
+        Ok(Some(cols))
+    }
+
+    fn db_type(&self) -> DbType {
+        match self.target_conn_pool {
+            DBConnPool::MySQL(_) => DbType::Mysql,
+            DBConnPool::PostgreSQL(_) => DbType::Pg,
+        }
+    }
+
+    /// Column changes of the clauses after ALTER TABLE tb, clauses not about columns are ignored.
+    fn parse_alter_table(unparsed: &str) -> Vec<ColumnChange> {
+        let tokens = Self::tokenize(unparsed);
+        tokens
+            .split(|t| !t.quoted && t.text == ",")
+            .filter_map(Self::parse_alter_clause)
+            .collect()
+    }
+
+    fn parse_alter_clause(tokens: &[Token]) -> Option<ColumnChange> {
+        const NOT_COLUMNS: [&str; 14] = [
+            "INDEX",
+            "KEY",
+            "CONSTRAINT",
+            "PRIMARY",
+            "UNIQUE",
+            "FOREIGN",
+            "FULLTEXT",
+            "SPATIAL",
+            "CHECK",
+            "PARTITION",
+            "DEFAULT",
+            "TO",
+            "AS",
+            "ORDER",
+        ];
+        let is_kw = |i: usize, kw: &str| {
+            tokens
+                .get(i)
+                .is_some_and(|t| !t.quoted && t.text.eq_ignore_ascii_case(kw))
+        };
+        let is_col = |i: usize| {
+            tokens.get(i).is_some_and(|t| {
+                t.quoted
+                    || (!t.text.starts_with('(')
+                        && !NOT_COLUMNS.iter().any(|kw| t.text.eq_ignore_ascii_case(kw)))
+            })
+        };
+        // unquoted identifiers are case folded
+        let col = |i: usize| {
+            let token = &tokens[i];
+            if token.quoted {
+                token.text.clone()
+            } else {
+                token.text.to_lowercase()
+            }
+        };
+        let type_of = |start: usize, end: usize| {
+            tokens[start.min(end)..end]
+                .iter()
+                .map(|t| t.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let mut i = 1;
+        let has_column_kw = is_kw(i, "COLUMN");
+        if has_column_kw {
+            i += 1;
+        }
+
+        if is_kw(0, "ADD") {
+            let if_not_exists = is_kw(i, "IF") && is_kw(i + 1, "NOT") && is_kw(i + 2, "EXISTS");
+            if if_not_exists {
+                i += 3;
+            }
+            return is_col(i).then(|| ColumnChange::Add {
+                col: col(i),
+                if_not_exists,
+            });
+        }
+
+        if is_kw(0, "DROP") {
+            let if_exists = is_kw(i, "IF") && is_kw(i + 1, "EXISTS");
+            if if_exists {
+                i += 2;
+            }
+            return is_col(i).then(|| ColumnChange::Drop {
+                col: col(i),
+                if_exists,
+            });
+        }
+
+        if is_kw(0, "MODIFY") && is_col(i) {
+            return Some(ColumnChange::Modify {
+                col: col(i),
+                new_col: col(i),
+                col_type: type_of(i + 1, tokens.len()),
+            });
+        }
+
+        if is_kw(0, "CHANGE") && is_col(i) && is_col(i + 1) {
+            return Some(ColumnChange::Modify {
+                col: col(i),
+                new_col: col(i + 1),
+                col_type: type_of(i + 2, tokens.len()),
+            });
+        }
+
+        if is_kw(0, "ALTER") && is_col(i) {
+            // ALTER [COLUMN] col [SET DATA] TYPE type [USING ...]
+            let type_pos = (i + 1..tokens.len()).find(|j| is_kw(*j, "TYPE"));
+            if let Some(type_pos) = type_pos {
+                let end = (type_pos..tokens.len())
+                    .find(|j| is_kw(*j, "USING") || is_kw(*j, "COLLATE"))
+                    .unwrap_or(tokens.len());
+                return Some(ColumnChange::Modify {
+                    col: col(i),
+                    new_col: col(i),
+                    col_type: type_of(type_pos + 1, end),
+                });
+            }
+            return Some(ColumnChange::Alter { col: col(i) });
+        }
+
+        // RENAME [COLUMN] col TO new_col, but not RENAME TO / RENAME INDEX
+        if is_kw(0, "RENAME") && is_col(i) && is_kw(i + 1, "TO") && is_col(i + 2) {
+            return Some(ColumnChange::Rename {
+                col: col(i),
+                new_col: col(i + 2),
+            });
+        }
+        None
+    }
+
+    /// Splits by whitespace and top level commas, quoted identifiers are unquoted,
+    /// parenthesized groups and string literals are kept as single tokens.
+    fn tokenize(sql: &str) -> Vec<Token> {
+        let chars: Vec<char> = sql.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() {
+                i += 1;
+            } else if c == ',' {
+                tokens.push(Token {
+                    text: ",".into(),
+                    quoted: false,
+                });
+                i += 1;
+            } else if c == '`' || c == '"' {
+                // identifier, the quote is escaped by doubling
+                let mut text = String::new();
+                i += 1;
+                while i < chars.len() {
+                    if chars[i] == c {
+                        if chars.get(i + 1) == Some(&c) {
+                            text.push(c);
+                            i += 2;
+                            continue;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    text.push(chars[i]);
+                    i += 1;
+                }
+                tokens.push(Token { text, quoted: true });
+            } else {
+                let start = i;
+                let mut depth = 0;
+                let mut in_literal = false;
+                while i < chars.len() {
+                    let c = chars[i];
+                    if in_literal {
+                        if c == '\'' {
+                            in_literal = false;
+                        }
+                    } else if c == '\'' {
+                        in_literal = true;
+                    } else if c == '(' {
+                        depth += 1;
+                    } else if c == ')' {
+                        depth -= 1;
+                    } else if depth <= 0 && (c.is_whitespace() || c == ',') {
+                        break;
+                    }
+                    i += 1;
+                }
+                tokens.push(Token {
+                    text: chars[start..i].iter().collect(),
+                    quoted: false,
+                });
+            }
+        }
+        tokens
+    }
+
+    /// Whether values of old_type can be kept in new_type, types not known are not checked.
+    fn is_type_compatible(old_type: &str, new_type: &str, db_type: &DbType) -> bool {
+        let old = ColType::parse(old_type);
+        let new = ColType::parse(new_type);
+        let (old_family, new_family) = (old.family(), new.family());
+        if old_family == TypeFamily::Other || new_family == TypeFamily::Other {
+            return true;
+        }
+
+        match (&old_family, &new_family) {
+            (TypeFamily::Integer(o), TypeFamily::Integer(n)) => {
+                if old.unsigned == new.unsigned {
+                    n >= o
+                } else {
+                    old.unsigned && n > o
+                }
+            }
+            (TypeFamily::Integer(o), TypeFamily::Decimal) => match new.decimal(db_type) {
+                None => true,
+                Some((p, s)) => p.saturating_sub(s) >= ColType::integer_digits(*o),
+            },
+            // 24 / 53 bits of mantissa
+            (TypeFamily::Integer(o), TypeFamily::Float(n)) => {
+                (*n == 1 && *o <= 2) || (*n == 2 && *o <= 4)
+            }
+            (TypeFamily::Decimal, TypeFamily::Decimal) => {
+                match (old.decimal(db_type), new.decimal(db_type)) {
+                    (_, None) => true,
+                    (None, Some(_)) => false,
+                    (Some((op, os)), Some((np, ns))) => {
+                        np.saturating_sub(ns) >= op.saturating_sub(os) && ns >= os
+                    }
+                }
+            }
+            (TypeFamily::Float(o), TypeFamily::Float(n)) => n >= o,
+            (TypeFamily::Text, TypeFamily::Text) | (TypeFamily::Binary, TypeFamily::Binary) => {
+                match (old.max_len(db_type), new.max_len(db_type)) {
+                    (_, None) => true,
+                    (None, Some(_)) => false,
+                    (Some(o), Some(n)) => n >= o,
+                }
+            }
+            (TypeFamily::Temporal, TypeFamily::Temporal) => {
+                let widened = match (old.base.as_str(), new.base.as_str()) {
+                    (o, n) if o == n => true,
+                    ("date", n) => ["datetime", "timestamp", "timestamptz"].contains(&n),
+                    ("timestamp", n) => ["datetime", "timestamptz"].contains(&n),
+                    ("time", "timetz") => true,
+                    _ => false,
+                };
+                widened && (old.base == "date" || new.fsp(db_type) >= old.fsp(db_type))
+            }
+            (_, TypeFamily::Text) => match new.max_len(db_type) {
+                None => true,
+                Some(n) => old.text_len(db_type).is_some_and(|needed| n >= needed),
+            },
+            _ => false,
+        }
+    }
+}
+
+impl ColType {
+    fn parse(col_type: &str) -> Self {
+        let col_type = col_type
+            .trim()
+            .to_lowercase()
+            .replace("character varying", "varchar")
+            .replace("double precision", "double")
+            .replace(" without time zone", "");
+        let with_tz = col_type.contains(" with time zone");
+        let col_type = col_type.replace(" with time zone", "");
+
+        let base_end = col_type
+            .find(|c: char| c == '(' || c.is_whitespace())
+            .unwrap_or(col_type.len());
+        let mut base = col_type[..base_end].to_string();
+        let rest = col_type[base_end..].trim_start();
+        let args = match rest.strip_prefix('(').and_then(|v| v.split(')').next()) {
+            Some(args) => args
+                .split(',')
+                .filter_map(|v| v.trim().parse::<u64>().ok())
+                .collect(),
+            None => vec![],
+        };
+        if with_tz {
+            base.push_str("tz");
+        }
+        let unsigned = col_type.split_whitespace().any(|v| v == "unsigned");
+        Self {
+            base,
+            args,
+            unsigned,
+        }
+    }
+
+    fn family(&self) -> TypeFamily {
+        match self.base.as_str() {
+            "tinyint" => TypeFamily::Integer(1),
+            "smallint" | "int2" | "smallserial" => TypeFamily::Integer(2),
+            "mediumint" => TypeFamily::Integer(3),
+            "int" | "integer" | "int4" | "serial" => TypeFamily::Integer(4),
+            "bigint" | "int8" | "bigserial" => TypeFamily::Integer(5),
+            "decimal" | "numeric" | "dec" | "fixed" => TypeFamily::Decimal,
+            "float" | "real" | "float4" => TypeFamily::Float(1),
+            "double" | "float8" => TypeFamily::Float(2),
+            "char" | "varchar" | "character" | "bpchar" | "nchar" | "nvarchar" | "tinytext"
+            | "text" | "mediumtext" | "longtext" => TypeFamily::Text,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bytea" => {
+                TypeFamily::Binary
+            }
+            "date" | "time" | "timetz" | "datetime" | "timestamp" | "timestamptz" => {
+                TypeFamily::Temporal
+            }
+            _ => TypeFamily::Other,
+        }
+    }
+
+    /// max length of text / binary types, None if unbounded
+    fn max_len(&self, db_type: &DbType) -> Option<u64> {
+        match self.base.as_str() {
+            "tinytext" | "tinyblob" => Some(255),
+            "text" | "blob" if *db_type == DbType::Mysql => Some(65_535),
+            "mediumtext" | "mediumblob" => Some(16_777_215),
+            "longtext" | "longblob" => Some(4_294_967_295),
+            "char" | "character" | "bpchar" | "nchar" | "binary" => {
+                Some(self.args.first().cloned().unwrap_or(1))
+            }
+            "varchar" | "nvarchar" | "varbinary" => self.args.first().cloned(),
+            _ => None,
+        }
+    }
+
+    /// (precision, scale), None if unbounded
+    fn decimal(&self, db_type: &DbType) -> Option<(u64, u64)> {
+        match self.args.as_slice() {
+            [] if *db_type == DbType::Mysql => Some((10, 0)),
+            [] => None,
+            [p] => Some((*p, 0)),
+            [p, s, ..] => Some((*p, *s)),
+        }
+    }
+
+    /// fractional seconds precision
+    fn fsp(&self, db_type: &DbType) -> u64 {
+        let default = if *db_type == DbType::Pg { 6 } else { 0 };
+        self.args.first().cloned().unwrap_or(default)
+    }
+
+    /// text length needed to keep values of the type
+    fn text_len(&self, db_type: &DbType) -> Option<u64> {
+        match self.family() {
+            TypeFamily::Integer(rank) => Some(Self::integer_digits(rank) + 1),
+            TypeFamily::Decimal => self.decimal(db_type).map(|(p, _)| p + 2),
+            TypeFamily::Float(_) => Some(24),
+            TypeFamily::Temporal => Some(32),
+            _ => None,
+        }
+    }
+
+    fn integer_digits(rank: u8) -> u64 {
+        match rank {
+            1 => 3,
+            2 => 5,
+            3 => 8,
+            4 => 10,
+            _ => 20,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_alter_table() {
+        let changes = DdlGuard::parse_alter_table(
+            " ADD COLUMN `c 1` varchar(10) DEFAULT 'a,b', ADD INDEX idx_1 (c2), DROP COLUMN IF EXISTS c3, \
+            MODIFY c4 decimal(10, 2) NOT NULL, CHANGE c5 c6 int, RENAME COLUMN c7 TO c8, RENAME TO tb_2",
+        );
+        assert_eq!(
+            changes,
+            vec![
+                ColumnChange::Add {
+                    col: "c 1".into(),
+                    if_not_exists: false
+                },
+                ColumnChange::Drop {
+                    col: "c3".into(),
+                    if_exists: true
+                },
+                ColumnChange::Modify {
+                    col: "c4".into(),
+                    new_col: "c4".into(),
+                    col_type: "decimal(10, 2) NOT NULL".into()
+                },
+                ColumnChange::Modify {
+                    col: "c5".into(),
+                    new_col: "c6".into(),
+                    col_type: "int".into()
+                },
+                ColumnChange::Rename {
+                    col: "c7".into(),
+                    new_col: "c8".into()
+                },
+            ]
+        );
+
+        let changes = DdlGuard::parse_alter_table(
+            r#" ALTER COLUMN "C1" SET DATA TYPE character varying(20) USING "C1"::text, ALTER c2 SET NOT NULL"#,
+        );
+        assert_eq!(
+            changes,
+            vec![
+                ColumnChange::Modify {
+                    col: "C1".into(),
+                    new_col: "C1".into(),
+                    col_type: "character varying(20)".into()
+                },
+                ColumnChange::Alter { col: "c2".into() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_col_conflict() {
+        let target_cols = HashMap::from([
+            ("id".to_string(), "int(11)".to_string()),
+            ("Name".to_string(), "varchar(50)".to_string()),
+        ]);
+        let conflict = |sql: &str| {
+            DdlGuard::find_col_conflict(
+                &target_cols,
+                &DdlGuard::parse_alter_table(sql),
+                &DbType::Mysql,
+            )
+        };
+
+        assert_eq!(
+            conflict(" ADD COLUMN c1 int, MODIFY name varchar(100)"),
+            None
+        );
+        assert_eq!(conflict(" DROP COLUMN id, ADD id bigint"), None);
+        assert_eq!(
+            conflict(" ADD COLUMN name text"),
+            Some("column `name` already exists".into())
+        );
+        assert_eq!(
+            conflict(" DROP c1"),
+            Some("column `c1` does not exist".into())
+        );
+        assert_eq!(
+            conflict(" MODIFY id smallint"),
+            Some(
+                "column `id` changes from type: int(11) to: smallint, existing values may not fit"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_is_type_compatible() {
+        let compatible = |old: &str, new: &str, db_type: &DbType| {
+            DdlGuard::is_type_compatible(old, new, db_type)
+        };
+        let mysql = &DbType::Mysql;
+        let pg = &DbType::Pg;
+
+        assert!(compatible("int(11)", "bigint", mysql));
+        assert!(compatible("int unsigned", "bigint", mysql));
+        assert!(!compatible("int unsigned", "int", mysql));
+        assert!(!compatible("bigint", "int", mysql));
+        assert!(compatible("int", "decimal(12,2)", mysql));
+        assert!(!compatible("int", "decimal(10,2)", mysql));
+        assert!(compatible("decimal(10,2)", "decimal(12,4)", mysql));
+        assert!(!compatible("decimal(10,2)", "decimal(10,4)", mysql));
+        assert!(compatible("numeric(10,2)", "numeric", pg));
+        assert!(compatible("varchar(50)", "text", mysql));
+        assert!(!compatible("varchar(50)", "varchar(20)", mysql));
+        assert!(!compatible("text", "varchar(100)", pg));
+        assert!(compatible("character varying(100)", "text", pg));
+        assert!(compatible("int", "varchar(20)", mysql));
+        assert!(!compatible("varchar(20)", "int", mysql));
+        assert!(compatible("date", "datetime(3)", mysql));
+        assert!(!compatible("datetime(6)", "datetime", mysql));
+        assert!(compatible(
+            "timestamp(3) without time zone",
+            "timestamp with time zone",
+            pg
+        ));
+        assert!(!compatible("timestamp with time zone", "timestamp", pg));
+        assert!(compatible("enum('a','b')", "enum('a')", mysql));
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use dt_common::{
+    error::Error,
+    meta::{col_value::ColValue, row_data::RowData, row_type::RowType, tagged_col_value_map},
+};
+
+// chunk files of offline migration: {file_dir}/{schema}/{tb}/{sinker_id}-{seq}.chunk.zst
+//   line 1: ChunkHeader, the table meta
+//   line 2..: ChunkRow, values with their ColValue types
+// a chunk is written as .tmp and renamed once finished, then its ChunkMeta is written
+// into {chunk}.meta.json, so chunks without meta are incomplete and never imported.
+// {chunk}.imported is created once all rows of the chunk are sunk by the import task.
+pub const CHUNK_FILE_EXT: &str = "chunk.zst";
+pub const CHUNK_TMP_FILE_EXT: &str = "tmp";
+pub const CHUNK_META_FILE_EXT: &str = "meta.json";
+pub const CHUNK_IMPORTED_FILE_EXT: &str = "imported";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkHeader {
+    pub schema: String,
+    pub tb: String,
+    // column name -> value type, e.g. LongLong, String, Decimal
+    pub cols: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ChunkRow {
+    pub row_type: RowType,
+    // after for insert / update, before for delete
+    #[serde(with = "tagged_col_value_map")]
+    pub values: BTreeMap<String, ColValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChunkMeta {
+    pub file: String,
+    pub rows: u64,
+    // size and crc32 of the compressed chunk file
+    pub size: u64,
+    pub crc32: u32,
+}
+
+impl ChunkHeader {
+    pub fn from_row_data(row_data: &RowData) -> Self {
+        let cols = ChunkRow::get_col_values(row_data)
+            .map(|col_values| {
+                col_values
+                    .iter()
+                    .map(|(col, v)| (col.clone(), v.type_name().to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            schema: row_data.schema.clone(),
+            tb: row_data.tb.clone(),
+            cols,
+        }
+    }
+}
+
+impl ChunkRow {
+    pub fn from_row_data(row_data: &RowData) -> Self {
+        let values = Self::get_col_values(row_data)
+            .map(|col_values| {
+                col_values
+                    .iter()
+                    .map(|(col, v)| (col.clone(), v.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            row_type: row_data.row_type.clone(),
+            values,
+        }
+    }
+
+    pub fn into_row_data(self, header: &ChunkHeader) -> RowData {
+        let values: HashMap<String, ColValue> = self.values.into_iter().collect();
+        let (before, after) = match self.row_type {
+            RowType::Delete => (Some(values), None),
+            _ => (None, Some(values)),
+        };
+        RowData::new(
+            header.schema.clone(),
+            header.tb.clone(),
+            0,
+            self.row_type,
+            before,
+            after,
+        )
+    }
+
+    pub fn get_col_values(row_data: &RowData) -> Option<&HashMap<String, ColValue>> {
+        match row_data.row_type {
+            RowType::Delete => row_data.before.as_ref(),
+            _ => row_data.after.as_ref(),
+        }
+    }
+}
+
+impl ChunkMeta {
+    pub fn meta_path(chunk_path: &Path) -> PathBuf {
+        Self::sibling_path(chunk_path, CHUNK_META_FILE_EXT)
+    }
+
+    pub fn imported_path(chunk_path: &Path) -> PathBuf {
+        Self::sibling_path(chunk_path, CHUNK_IMPORTED_FILE_EXT)
+    }
+
+    pub fn tmp_path(chunk_path: &Path) -> PathBuf {
+        Self::sibling_path(chunk_path, CHUNK_TMP_FILE_EXT)
+    }
+
+    fn sibling_path(chunk_path: &Path, ext: &str) -> PathBuf {
+        let mut path = chunk_path.as_os_str().to_owned();
+        path.push(".");
+        path.push(ext);
+        PathBuf::from(path)
+    }
+
+    pub fn build(chunk_path: &Path, rows: u64) -> anyhow::Result<Self> {
+        let (size, crc32) = Self::checksum(chunk_path)?;
+        let file = chunk_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self {
+            file,
+            rows,
+            size,
+            crc32,
+        })
+    }
+
+    pub fn write(&self, chunk_path: &Path) -> anyhow::Result<()> {
+        let path = Self::meta_path(chunk_path);
+        fs::write(&path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write file: [{}]", path.display()))
+    }
+
+    pub fn load(chunk_path: &Path) -> anyhow::Result<Self> {
+        let path = Self::meta_path(chunk_path);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read file: [{}]", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("invalid chunk meta: [{}]", path.display()))
+    }
+
+    pub fn verify(&self, chunk_path: &Path) -> anyhow::Result<()> {
+        let (size, crc32) = Self::checksum(chunk_path)?;
+        if size != self.size || crc32 != self.crc32 {
+            bail!(Error::Unexpected(format!(
+                "chunk file corrupted: [{}], expect size: {}, crc32: {}, actual size: {}, crc32: {}",
+                chunk_path.display(),
+                self.size,
+                self.crc32,
+                size,
+                crc32
+            )));
+        }
+        Ok(())
+    }
+
+    fn checksum(path: &Path) -> anyhow::Result<(u64, u32)> {
+        let mut file =
+            File::open(path).with_context(|| format!("failed to open: [{}]", path.display()))?;
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut size = 0;
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+        }
+        Ok((size, hasher.finalize()))
+    }
+
+    /// finished chunks in a table dir, sorted by name
+    pub fn list_chunks(tb_dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let suffix = format!(".{}", CHUNK_FILE_EXT);
+        let mut chunks = Vec::new();
+        for entry in fs::read_dir(tb_dir)
+            .with_context(|| format!("failed to read dir: [{}]", tb_dir.display()))?
+        {
+            let path = entry?.path();
+            let is_chunk = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().ends_with(&suffix));
+            if is_chunk && Self::meta_path(&path).exists() {
+                chunks.push(path);
+            }
+        }
+        chunks.sort();
+        Ok(chunks)
+    }
+}
+
+pub struct ChunkReader {
+    pub header: ChunkHeader,
+    lines: std::io::Lines<BufReader<zstd::stream::read::Decoder<'static, BufReader<File>>>>,
+}
+
+impl ChunkReader {
+    pub fn open(chunk_path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(chunk_path)
+            .with_context(|| format!("failed to open: [{}]", chunk_path.display()))?;
+        let decoder = zstd::stream::read::Decoder::new(file)?;
+        let mut lines = BufReader::new(decoder).lines();
+        let Some(header) = lines.next() else {
+            bail!(Error::Unexpected(format!(
+                "chunk file without header: [{}]",
+                chunk_path.display()
+            )));
+        };
+        let header = serde_json::from_str(&header?)
+            .with_context(|| format!("invalid chunk header: [{}]", chunk_path.display()))?;
+        Ok(Self { header, lines })
+    }
+
+    pub fn next_row(&mut self) -> anyhow::Result<Option<RowData>> {
+        match self.lines.next() {
+            Some(line) => {
+                let row: ChunkRow = serde_json::from_str(&line?)?;
+                Ok(Some(row.into_row_data(&self.header)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_row_round_trip() {
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::UnsignedLongLong(u64::MAX)),
+            ("price".to_string(), ColValue::Decimal("1.10".into())),
+            ("data".to_string(), ColValue::Blob(vec![0, 1, 255])),
+            ("note".to_string(), ColValue::None),
+        ]);
+        let row_data = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+
+        let header = ChunkHeader::from_row_data(&row_data);
+        assert_eq!(header.cols.get("price"), Some(&"Decimal".to_string()));
+
+        let line = serde_json::to_string(&ChunkRow::from_row_data(&row_data)).unwrap();
+        let row: ChunkRow = serde_json::from_str(&line).unwrap();
+        assert_eq!(row.into_row_data(&header), row_data);
+    }
+}
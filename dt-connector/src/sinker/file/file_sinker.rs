@@ -0,0 +1,554 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use flate2::{write::GzEncoder, Compression};
+use opendal::Operator;
+use serde::Serialize;
+use serde_json::json;
+
+use dt_common::{
+    config::config_enums::{FileFormat, OrcCompression},
+    log_info,
+    meta::{
+        col_value::ColValue, position::Position, rdb_meta_manager::RdbMetaManager,
+        row_data::RowData, row_type::RowType,
+    },
+};
+
+use super::{
+    chunk_file::{ChunkHeader, ChunkMeta, ChunkRow, CHUNK_FILE_EXT},
+    orc_file::{OrcFileWriter, OrcType, ORC_FILE_EXT},
+};
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+
+const ROW_TYPE_COL_NAME: &str = "_ape_dts_row_type";
+const SNAPSHOT_FINISHED_FILE_NAME: &str = "_snapshot_finished.json";
+
+/// writes rows into local files, one dir per table: {file_dir}/{schema}/{tb}/{sinker_id}-{seq}.{csv|jsonl}[.gz],
+/// a new file is started once the current one exceeds max_file_size (counted before compression).
+/// chunk files are always zstd compressed, refer to chunk_file for the layout.
+/// orc files compress their streams by orc_compression, refer to orc_file for the layout.
+/// if s3_client is set, finished files are uploaded to {s3_key_prefix}/{schema}/{tb}/ and removed locally
+pub struct FileSinker {
+    pub sinker_id: usize,
+    pub file_dir: String,
+    pub file_format: FileFormat,
+    pub max_file_size: u64,
+    pub gzip: bool,
+    pub orc_compression: OrcCompression,
+    // orc only, field types are taken from tb_meta of the source table if set
+    pub meta_manager: Option<RdbMetaManager>,
+    pub s3_client: Option<Operator>,
+    pub s3_key_prefix: String,
+    pub router: Option<RdbRouter>,
+    pub writers: HashMap<(String, String), TableFileWriter>,
+    pub base_sinker: BaseSinker,
+}
+
+pub struct TableFileWriter {
+    path: PathBuf,
+    writer: FileWriter,
+    written_size: u64,
+    // csv, chunk and orc only, columns of the header line / orc fields
+    cols: Vec<String>,
+    // chunk only, rows are written into a tmp file until finished
+    chunk: bool,
+    rows: u64,
+}
+
+enum FileWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::stream::write::Encoder<'static, BufWriter<File>>),
+    // rows are buffered into stripes instead of written as lines
+    Orc(OrcFileWriter<BufWriter<File>>),
+}
+
+#[derive(Serialize)]
+struct JsonlRow<'a> {
+    schema: &'a str,
+    tb: &'a str,
+    row_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<BTreeMap<&'a str, &'a ColValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<BTreeMap<&'a str, &'a ColValue>>,
+}
+
+#[async_trait]
+impl Sinker for FileSinker {
+    async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut data_size = 0;
+        for row_data in data.iter() {
+            data_size += row_data.get_data_size();
+            self.write_row(row_data).await?;
+        }
+        for writer in self.writers.values_mut() {
+            writer.writer.flush()?;
+        }
+
+        let task_id = self
+            .base_sinker
+            .source_task_id_for_rows(&data, &self.router);
+        self.base_sinker.ensure_monitor_for(&task_id);
+        self.base_sinker
+            .update_batch_monitor_for(&task_id, data.len() as u64, data_size)
+            .await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        let writers: Vec<TableFileWriter> = self.writers.drain().map(|(_, w)| w).collect();
+        for writer in writers {
+            self.finish_writer(writer).await?;
+        }
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
+    async fn sink_snapshot_watermark(&mut self, position: &Position) -> anyhow::Result<()> {
+        if let Position::RdbSnapshotFinished {
+            schema,
+            tb,
+            row_count,
+            cdc_position: Some(cdc_position),
+            ..
+        } = position
+        {
+            // the finished position keeps source names, files are named by routed names
+            let (schema, tb) = match &self.router {
+                Some(router) => router.get_tb_map(schema, tb),
+                None => (schema.into(), tb.into()),
+            };
+            let dir = Path::new(&self.file_dir).join(&*schema).join(&*tb);
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("failed to create dir: [{}]", dir.display()))?;
+
+            let path = dir.join(SNAPSHOT_FINISHED_FILE_NAME);
+            let content = json!({
+                "schema": schema,
+                "tb": tb,
+                "row_count": row_count,
+                "cdc_position": cdc_position,
+            });
+            fs::write(&path, content.to_string())
+                .with_context(|| format!("failed to write file: [{}]", path.display()))?;
+            log_info!("file sinker wrote snapshot watermark {}", path.display());
+            self.upload(&path).await?;
+        }
+        Ok(())
+    }
+}
+
+impl FileSinker {
+    async fn write_row(&mut self, row_data: &RowData) -> anyhow::Result<()> {
+        let key = (row_data.schema.clone(), row_data.tb.clone());
+        if matches!(
+            self.file_format,
+            FileFormat::Csv | FileFormat::Chunk | FileFormat::Orc
+        ) {
+            // start a new file if columns changed, e.g. caused by ddl
+            let cols = Self::get_csv_cols(row_data);
+            if self.writers.get(&key).is_some_and(|w| w.cols != cols) {
+                if let Some(writer) = self.writers.remove(&key) {
+                    self.finish_writer(writer).await?;
+                }
+            }
+        }
+
+        if !self.writers.contains_key(&key) {
+            let writer = self.open_writer(row_data).await?;
+            self.writers.insert(key.clone(), writer);
+        }
+        let writer = self.writers.get_mut(&key).unwrap();
+
+        match self.file_format {
+            FileFormat::Csv => writer.write_line(&Self::build_csv_line(row_data, &writer.cols))?,
+            FileFormat::Jsonl => writer.write_line(&Self::build_jsonl_line(row_data)?)?,
+            FileFormat::Chunk => {
+                writer.write_line(&serde_json::to_string(&ChunkRow::from_row_data(row_data))?)?
+            }
+            FileFormat::Orc => writer.write_orc_row(row_data)?,
+        }
+        writer.rows += 1;
+
+        if writer.written_size >= self.max_file_size {
+            if let Some(writer) = self.writers.remove(&key) {
+                self.finish_writer(writer).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn open_writer(&mut self, row_data: &RowData) -> anyhow::Result<TableFileWriter> {
+        let dir = Path::new(&self.file_dir)
+            .join(&row_data.schema)
+            .join(&row_data.tb);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create dir: [{}]", dir.display()))?;
+
+        let chunk = self.file_format == FileFormat::Chunk;
+        let orc = self.file_format == FileFormat::Orc;
+        let mut ext = self.file_format.to_string();
+        if chunk {
+            ext = CHUNK_FILE_EXT.to_string();
+        } else if orc {
+            ext = ORC_FILE_EXT.to_string();
+        } else if self.gzip {
+            ext.push_str(".gz");
+        }
+        // never overwrite files left by previous runs, including unfinished chunks and uploaded files
+        let mut seq = 0;
+        let path = loop {
+            let path = dir.join(format!("{}-{:06}.{}", self.sinker_id, seq, ext));
+            if !path.exists()
+                && !ChunkMeta::tmp_path(&path).exists()
+                && !self.exists_in_s3(&path).await?
+            {
+                break path;
+            }
+            seq += 1;
+        };
+        let orc_types = if orc {
+            self.get_orc_types(row_data).await?
+        } else {
+            None
+        };
+
+        let create_path = if chunk {
+            ChunkMeta::tmp_path(&path)
+        } else {
+            path.clone()
+        };
+        let file = File::create(&create_path)
+            .with_context(|| format!("failed to create file: [{}]", create_path.display()))?;
+        let writer = if chunk {
+            FileWriter::Zstd(zstd::stream::write::Encoder::new(
+                BufWriter::new(file),
+                zstd::DEFAULT_COMPRESSION_LEVEL,
+            )?)
+        } else if orc {
+            let mut cols = vec![ROW_TYPE_COL_NAME.to_string()];
+            cols.extend(Self::get_csv_cols(row_data));
+            let writer =
+                OrcFileWriter::new(BufWriter::new(file), cols, self.orc_compression.clone())?;
+            match orc_types {
+                Some(types) => FileWriter::Orc(writer.with_types(types)),
+                None => FileWriter::Orc(writer),
+            }
+        } else if self.gzip {
+            FileWriter::Gzip(GzEncoder::new(BufWriter::new(file), Compression::default()))
+        } else {
+            FileWriter::Plain(BufWriter::new(file))
+        };
+        log_info!("file sinker: {} opened {}", self.sinker_id, path.display());
+
+        let mut table_writer = TableFileWriter {
+            path,
+            writer,
+            written_size: 0,
+            cols: Vec::new(),
+            chunk,
+            rows: 0,
+        };
+        match self.file_format {
+            FileFormat::Csv => {
+                table_writer.cols = Self::get_csv_cols(row_data);
+                let mut header = vec![ROW_TYPE_COL_NAME.to_string()];
+                header.extend(table_writer.cols.iter().map(|c| Self::escape_csv(c)));
+                table_writer.write_line(&header.join(","))?;
+            }
+            FileFormat::Chunk => {
+                table_writer.cols = Self::get_csv_cols(row_data);
+                let header = serde_json::to_string(&ChunkHeader::from_row_data(row_data))?;
+                table_writer.write_line(&header)?;
+            }
+            FileFormat::Orc => table_writer.cols = Self::get_csv_cols(row_data),
+            FileFormat::Jsonl => {}
+        }
+        Ok(table_writer)
+    }
+
+    /// Types of the orc fields by tb_meta of the source table, None if there is no tb_meta, e.g. for mongo,
+    /// columns not found in tb_meta are kept as strings.
+    async fn get_orc_types(&mut self, row_data: &RowData) -> anyhow::Result<Option<Vec<OrcType>>> {
+        let Some(meta_manager) = self.meta_manager.as_mut() else {
+            return Ok(None);
+        };
+
+        // rows have been routed by extractor, tb_meta is found by the source names
+        let (schema, tb, col_map) = match &self.router {
+            Some(router) => {
+                let (schema, tb) = router.reverse_get_tb_map(&row_data.schema, &row_data.tb);
+                let col_map = router.reverse_get_col_map(&row_data.schema, &row_data.tb);
+                (schema, tb, col_map)
+            }
+            None => (row_data.schema.as_str(), row_data.tb.as_str(), None),
+        };
+        let src_cols: Vec<String> = Self::get_csv_cols(row_data)
+            .into_iter()
+            .map(|col| {
+                col_map
+                    .and_then(|col_map| col_map.get(&col))
+                    .cloned()
+                    .unwrap_or(col)
+            })
+            .collect();
+
+        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
+        // columns added by ddl are missing in the cached tb_meta
+        if src_cols
+            .iter()
+            .any(|col| !tb_meta.col_origin_type_map.contains_key(col))
+        {
+            meta_manager.invalidate_cache(schema, tb);
+        }
+        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
+
+        let mut types = vec![OrcType::String];
+        types.extend(src_cols.iter().map(|col| {
+            tb_meta
+                .col_origin_type_map
+                .get(col)
+                .map(|origin_type| OrcType::from_origin_type(origin_type))
+                .unwrap_or(OrcType::String)
+        }));
+        Ok(Some(types))
+    }
+
+    async fn finish_writer(&self, writer: TableFileWriter) -> anyhow::Result<()> {
+        let path = writer.path.clone();
+        writer.finish()?;
+        self.upload(&path).await
+    }
+
+    async fn upload(&self, path: &Path) -> anyhow::Result<()> {
+        let Some(s3_client) = &self.s3_client else {
+            return Ok(());
+        };
+        let key = self.get_s3_key(path)?;
+        let buf =
+            fs::read(path).with_context(|| format!("failed to read file: [{}]", path.display()))?;
+        s3_client.write(&key, buf).await?;
+        fs::remove_file(path)
+            .with_context(|| format!("failed to remove file: [{}]", path.display()))?;
+        log_info!("file sinker uploaded {} to s3 key: {}", path.display(), key);
+        Ok(())
+    }
+
+    async fn exists_in_s3(&self, path: &Path) -> anyhow::Result<bool> {
+        match &self.s3_client {
+            Some(s3_client) => Ok(s3_client.exists(&self.get_s3_key(path)?).await?),
+            None => Ok(false),
+        }
+    }
+
+    // {s3_key_prefix}/{schema}/{tb}/{file_name}, the same layout as under file_dir
+    fn get_s3_key(&self, path: &Path) -> anyhow::Result<String> {
+        let mut parts = Vec::new();
+        let prefix = self.s3_key_prefix.trim_matches('/');
+        if !prefix.is_empty() {
+            parts.push(prefix.to_string());
+        }
+        for component in path.strip_prefix(&self.file_dir)?.components() {
+            parts.push(component.as_os_str().to_string_lossy().into_owned());
+        }
+        Ok(parts.join("/"))
+    }
+
+    fn get_csv_cols(row_data: &RowData) -> Vec<String> {
+        let mut cols: Vec<String> = Self::get_csv_col_values(row_data)
+            .map(|col_values| col_values.keys().cloned().collect())
+            .unwrap_or_default();
+        cols.sort();
+        cols
+    }
+
+    fn get_csv_col_values(row_data: &RowData) -> Option<&HashMap<String, ColValue>> {
+        match row_data.row_type {
+            RowType::Delete => row_data.before.as_ref(),
+            _ => row_data.after.as_ref(),
+        }
+    }
+
+    fn build_csv_line(row_data: &RowData, cols: &[String]) -> String {
+        let mut fields = vec![row_data.row_type.to_string()];
+        let col_values = Self::get_csv_col_values(row_data);
+        for col in cols {
+            let value = col_values
+                .and_then(|col_values| col_values.get(col))
+                .and_then(|v| v.to_option_string());
+            match value {
+                Some(v) => fields.push(Self::escape_csv(&v)),
+                // distinguish NULL from empty string
+                None => fields.push(String::new()),
+            }
+        }
+        fields.join(",")
+    }
+
+    fn escape_csv(value: &str) -> String {
+        if value.is_empty() {
+            return "\"\"".to_string();
+        }
+        if value.contains([',', '"', '\n', '\r']) {
+            return format!("\"{}\"", value.replace('"', "\"\""));
+        }
+        value.to_string()
+    }
+
+    fn build_jsonl_line(row_data: &RowData) -> anyhow::Result<String> {
+        let row = JsonlRow {
+            schema: &row_data.schema,
+            tb: &row_data.tb,
+            row_type: (&row_data.row_type).into(),
+            before: Self::ordered_col_values(&row_data.before),
+            after: Self::ordered_col_values(&row_data.after),
+        };
+        Ok(serde_json::to_string(&row)?)
+    }
+
+    fn ordered_col_values(
+        col_values: &Option<HashMap<String, ColValue>>,
+    ) -> Option<BTreeMap<&str, &ColValue>> {
+        col_values
+            .as_ref()
+            .map(|col_values| col_values.iter().map(|(k, v)| (k.as_str(), v)).collect())
+    }
+}
+
+impl TableFileWriter {
+    fn write_line(&mut self, line: &str) -> anyhow::Result<()> {
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.written_size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    // the size of orc rows is counted by their data size
+    fn write_orc_row(&mut self, row_data: &RowData) -> anyhow::Result<()> {
+        let FileWriter::Orc(writer) = &mut self.writer else {
+            bail!("file writer is not orc");
+        };
+        let col_values = FileSinker::get_csv_col_values(row_data);
+        let mut values = vec![ColValue::String(row_data.row_type.to_string())];
+        values.extend(self.cols.iter().map(|col| {
+            col_values
+                .and_then(|col_values| col_values.get(col))
+                .cloned()
+                .unwrap_or(ColValue::None)
+        }));
+        writer.write_row(values, row_data.get_data_size())?;
+        self.written_size += row_data.get_data_size();
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        let file = match self.writer {
+            FileWriter::Plain(writer) => writer.into_inner()?,
+            FileWriter::Gzip(encoder) => encoder.finish()?.into_inner()?,
+            FileWriter::Zstd(encoder) => encoder.finish()?.into_inner()?,
+            FileWriter::Orc(writer) => writer.finish()?.into_inner()?,
+        };
+        file.sync_all()?;
+
+        if self.chunk {
+            // a chunk is visible to importers only after both rename and meta are done
+            let tmp_path = ChunkMeta::tmp_path(&self.path);
+            fs::rename(&tmp_path, &self.path)
+                .with_context(|| format!("failed to rename file: [{}]", tmp_path.display()))?;
+            ChunkMeta::build(&self.path, self.rows)?.write(&self.path)?;
+        }
+        log_info!("file sinker closed {}", self.path.display());
+        Ok(())
+    }
+}
+
+impl FileWriter {
+    fn write_all(&mut self, buf: &[u8]) -> anyhow::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.write_all(buf)?,
+            Self::Gzip(encoder) => encoder.write_all(buf)?,
+            Self::Zstd(encoder) => encoder.write_all(buf)?,
+            Self::Orc(_) => bail!("orc files are not written by lines"),
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush()?,
+            Self::Gzip(encoder) => encoder.flush()?,
+            Self::Zstd(encoder) => encoder.flush()?,
+            // buffered rows are written by stripes
+            Self::Orc(_) => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_row(row_type: RowType, cols: Vec<(&str, ColValue)>) -> RowData {
+        let col_values: HashMap<String, ColValue> =
+            cols.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        let (before, after) = match row_type {
+            RowType::Delete => (Some(col_values), None),
+            _ => (None, Some(col_values)),
+        };
+        RowData::new("db1".into(), "tb1".into(), 0, row_type, before, after)
+    }
+
+    #[test]
+    fn test_build_csv_line() {
+        let row = build_row(
+            RowType::Insert,
+            vec![
+                ("id", ColValue::Long(1)),
+                ("name", ColValue::String("a,\"b\"".into())),
+                ("empty", ColValue::String(String::new())),
+                ("null", ColValue::None),
+            ],
+        );
+        let cols = FileSinker::get_csv_cols(&row);
+        assert_eq!(cols, vec!["empty", "id", "name", "null"]);
+        assert_eq!(
+            FileSinker::build_csv_line(&row, &cols),
+            "insert,\"\",1,\"a,\"\"b\"\"\","
+        );
+
+        let row = build_row(RowType::Delete, vec![("id", ColValue::Long(2))]);
+        assert_eq!(
+            FileSinker::build_csv_line(&row, &FileSinker::get_csv_cols(&row)),
+            "delete,2"
+        );
+    }
+
+    #[test]
+    fn test_build_jsonl_line() {
+        let row = build_row(
+            RowType::Insert,
+            vec![
+                ("name", ColValue::String("a".into())),
+                ("id", ColValue::Long(1)),
+            ],
+        );
+        assert_eq!(
+            FileSinker::build_jsonl_line(&row).unwrap(),
+            r#"{"schema":"db1","tb":"tb1","row_type":"insert","after":{"id":1,"name":"a"}}"#
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod chunk_file;
+pub mod file_sinker;
+pub mod orc_file;
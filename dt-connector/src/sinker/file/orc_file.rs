@@ -0,0 +1,624 @@
+use std::io::Write;
+
+use flate2::{write::DeflateEncoder, Compression};
+
+use dt_common::{config::config_enums::OrcCompression, meta::col_value::ColValue};
+
+// orc files of the file sinker: {file_dir}/{schema}/{tb}/{sinker_id}-{seq}.orc
+//   column 0 is the root struct, its fields are the columns given to OrcFileWriter::new
+//   field types are given by OrcFileWriter::with_types, taken from tb_meta by OrcType::from_origin_type,
+//   otherwise decided by the values of the first stripe, refer to OrcType::from_values
+//   all fields are DIRECT encoded with rle v1, no row indexes or column statistics are written
+// refer to: https://orc.apache.org/specification/ORCv1/
+pub const ORC_FILE_EXT: &str = "orc";
+
+const ORC_MAGIC: &[u8] = b"ORC";
+const COMPRESSION_BLOCK_SIZE: usize = 256 * 1024;
+// rows are buffered and written as a stripe once their size exceeds it
+const STRIPE_SIZE: u64 = 64 * 1024 * 1024;
+// ORC_135
+const WRITER_VERSION: u64 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrcType {
+    Boolean,
+    Long,
+    Double,
+    String,
+    Binary,
+}
+
+pub struct OrcFileWriter<W: Write> {
+    writer: W,
+    compression: OrcCompression,
+    cols: Vec<String>,
+    // empty until the first stripe is written, unless given by with_types
+    types: Vec<OrcType>,
+    rows: Vec<Vec<ColValue>>,
+    buffered_size: u64,
+    // bytes written, including the header
+    offset: u64,
+    stripes: Vec<StripeInfo>,
+    total_rows: u64,
+}
+
+struct StripeInfo {
+    offset: u64,
+    data_length: u64,
+    footer_length: u64,
+    rows: u64,
+}
+
+#[derive(Default)]
+struct ColumnStreams {
+    present: Option<Vec<u8>>,
+    data: Vec<u8>,
+    length: Option<Vec<u8>>,
+}
+
+// enum values of orc_proto.proto
+const STREAM_PRESENT: u64 = 0;
+const STREAM_DATA: u64 = 1;
+const STREAM_LENGTH: u64 = 2;
+const ENCODING_DIRECT: u64 = 0;
+const TYPE_STRUCT: u64 = 12;
+
+impl OrcType {
+    /// Type of a column by its type in tb_meta, e.g. int(11) of mysql or int4 of pg,
+    /// types without an exact orc counterpart are kept as strings.
+    pub fn from_origin_type(origin_type: &str) -> Self {
+        let origin_type = origin_type.to_lowercase();
+        // pg arrays
+        if origin_type.starts_with('_') || origin_type.ends_with("[]") {
+            return Self::String;
+        }
+
+        let base_type = origin_type.split(['(', ' ']).next().unwrap_or_default();
+        match base_type {
+            "bool" | "boolean" => Self::Boolean,
+            // unsigned bigints may exceed the range of orc bigint
+            "bigint" if origin_type.contains("unsigned") => Self::String,
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2"
+            | "int4" | "int8" | "smallserial" | "serial" | "bigserial" | "year" => Self::Long,
+            "float" | "double" | "real" | "float4" | "float8" => Self::Double,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bytea" => {
+                Self::Binary
+            }
+            // decimals, temporal values, texts, etc.
+            _ => Self::String,
+        }
+    }
+
+    /// Type of a column by its non null values, values of different types are kept as strings.
+    pub fn from_values<'a>(values: impl Iterator<Item = &'a ColValue>) -> Self {
+        let mut orc_type = None;
+        for value in values {
+            let value_type = match value {
+                ColValue::None | ColValue::UnchangedToast => continue,
+                ColValue::Bool(_) => Self::Boolean,
+                ColValue::Tiny(_)
+                | ColValue::UnsignedTiny(_)
+                | ColValue::Short(_)
+                | ColValue::UnsignedShort(_)
+                | ColValue::Long(_)
+                | ColValue::UnsignedLong(_)
+                | ColValue::LongLong(_)
+                | ColValue::Year(_) => Self::Long,
+                ColValue::Float(_) | ColValue::Double(_) => Self::Double,
+                ColValue::Blob(_) => Self::Binary,
+                // decimals, temporal values and unsigned bigints are kept as strings
+                _ => Self::String,
+            };
+            match orc_type {
+                None => orc_type = Some(value_type),
+                Some(t) if t != value_type => return Self::String,
+                _ => {}
+            }
+        }
+        orc_type.unwrap_or(Self::String)
+    }
+
+    fn kind(&self) -> u64 {
+        match self {
+            Self::Boolean => 0,
+            Self::Long => 4,
+            Self::Double => 6,
+            Self::String => 7,
+            Self::Binary => 8,
+        }
+    }
+
+    fn encode<'a>(&self, values: impl Iterator<Item = &'a ColValue>) -> ColumnStreams {
+        let mut streams = ColumnStreams::default();
+        let present: Vec<bool> = match self {
+            Self::Boolean => {
+                let values: Vec<Option<bool>> = values.map(Self::to_bool).collect();
+                let bools: Vec<bool> = values.iter().flatten().copied().collect();
+                streams.data = encode_bool_rle(&bools);
+                values.iter().map(Option::is_some).collect()
+            }
+            Self::Long => {
+                let values: Vec<Option<i64>> = values.map(Self::to_i64).collect();
+                let ints: Vec<u64> = values.iter().flatten().map(|v| zigzag(*v)).collect();
+                streams.data = encode_int_rle(&ints);
+                values.iter().map(Option::is_some).collect()
+            }
+            Self::Double => {
+                let values: Vec<Option<f64>> = values.map(Self::to_f64).collect();
+                for v in values.iter().flatten() {
+                    streams.data.extend_from_slice(&v.to_le_bytes());
+                }
+                values.iter().map(Option::is_some).collect()
+            }
+            Self::String | Self::Binary => {
+                let values: Vec<Option<Vec<u8>>> = values.map(Self::to_bytes).collect();
+                let mut lengths = Vec::new();
+                for v in values.iter().flatten() {
+                    lengths.push(v.len() as u64);
+                    streams.data.extend_from_slice(v);
+                }
+                streams.length = Some(encode_int_rle(&lengths));
+                values.iter().map(Option::is_some).collect()
+            }
+        };
+        if present.contains(&false) {
+            streams.present = Some(encode_bool_rle(&present));
+        }
+        streams
+    }
+
+    fn to_bool(value: &ColValue) -> Option<bool> {
+        match value {
+            ColValue::Bool(v) => Some(*v),
+            _ => match value.to_option_string()?.as_str() {
+                "1" | "true" => Some(true),
+                "0" | "false" => Some(false),
+                _ => None,
+            },
+        }
+    }
+
+    fn to_i64(value: &ColValue) -> Option<i64> {
+        match value {
+            ColValue::Tiny(v) => Some(*v as i64),
+            ColValue::UnsignedTiny(v) => Some(*v as i64),
+            ColValue::Short(v) => Some(*v as i64),
+            ColValue::UnsignedShort(v) => Some(*v as i64),
+            ColValue::Long(v) => Some(*v as i64),
+            ColValue::UnsignedLong(v) => Some(*v as i64),
+            ColValue::LongLong(v) => Some(*v),
+            ColValue::Year(v) => Some(*v as i64),
+            _ => value.to_option_string()?.parse().ok(),
+        }
+    }
+
+    fn to_f64(value: &ColValue) -> Option<f64> {
+        match value {
+            ColValue::Float(v) => Some(*v as f64),
+            ColValue::Double(v) => Some(*v),
+            _ => value.to_option_string()?.parse().ok(),
+        }
+    }
+
+    fn to_bytes(value: &ColValue) -> Option<Vec<u8>> {
+        match value {
+            ColValue::Blob(v) => Some(v.clone()),
+            _ => value.to_option_string().map(String::into_bytes),
+        }
+    }
+}
+
+impl<W: Write> OrcFileWriter<W> {
+    pub fn new(
+        mut writer: W,
+        cols: Vec<String>,
+        compression: OrcCompression,
+    ) -> anyhow::Result<Self> {
+        writer.write_all(ORC_MAGIC)?;
+        Ok(Self {
+            writer,
+            compression,
+            cols,
+            types: Vec::new(),
+            rows: Vec::new(),
+            buffered_size: 0,
+            offset: ORC_MAGIC.len() as u64,
+            stripes: Vec::new(),
+            total_rows: 0,
+        })
+    }
+
+    /// Types of cols, in the same order, instead of deciding them by the values of the first stripe.
+    pub fn with_types(mut self, types: Vec<OrcType>) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// values are in the order of cols, size is only used to decide when to write a stripe
+    pub fn write_row(&mut self, values: Vec<ColValue>, size: u64) -> anyhow::Result<()> {
+        self.rows.push(values);
+        self.buffered_size += size;
+        if self.buffered_size >= STRIPE_SIZE {
+            self.write_stripe()?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.write_stripe()?;
+        if self.types.is_empty() {
+            self.types = vec![OrcType::String; self.cols.len()];
+        }
+
+        // no metadata section, i.e. no stripe statistics
+        let footer = self.compress(&self.build_footer())?;
+        self.writer.write_all(&footer)?;
+
+        let mut post_script = ProtoWriter::default();
+        post_script.uint(1, footer.len() as u64);
+        post_script.uint(2, self.compression_kind());
+        post_script.uint(3, COMPRESSION_BLOCK_SIZE as u64);
+        post_script.packed(4, &[0, 12]);
+        post_script.uint(5, 0);
+        post_script.uint(6, WRITER_VERSION);
+        post_script.bytes(8000, ORC_MAGIC);
+        self.writer.write_all(&post_script.buf)?;
+        self.writer.write_all(&[post_script.buf.len() as u8])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_stripe(&mut self) -> anyhow::Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        if self.types.is_empty() {
+            self.types = (0..self.cols.len())
+                .map(|i| OrcType::from_values(self.rows.iter().filter_map(|row| row.get(i))))
+                .collect();
+        }
+
+        let none = ColValue::None;
+        let mut stripe_footer = ProtoWriter::default();
+        let mut data_length = 0;
+        for (i, orc_type) in self.types.iter().enumerate() {
+            let column = i as u64 + 1;
+            let streams = orc_type.encode(self.rows.iter().map(|row| row.get(i).unwrap_or(&none)));
+            for (kind, stream) in [
+                (STREAM_PRESENT, streams.present),
+                (STREAM_DATA, Some(streams.data)),
+                (STREAM_LENGTH, streams.length),
+            ] {
+                let Some(stream) = stream else {
+                    continue;
+                };
+                let stream = self.compress(&stream)?;
+                self.writer.write_all(&stream)?;
+                data_length += stream.len() as u64;
+
+                let mut stream_info = ProtoWriter::default();
+                stream_info.uint(1, kind);
+                stream_info.uint(2, column);
+                stream_info.uint(3, stream.len() as u64);
+                stripe_footer.bytes(1, &stream_info.buf);
+            }
+        }
+        // encodings of the root struct and each field
+        for _ in 0..=self.types.len() {
+            let mut encoding = ProtoWriter::default();
+            encoding.uint(1, ENCODING_DIRECT);
+            stripe_footer.bytes(2, &encoding.buf);
+        }
+        let stripe_footer = self.compress(&stripe_footer.buf)?;
+        self.writer.write_all(&stripe_footer)?;
+
+        let rows = self.rows.len() as u64;
+        self.stripes.push(StripeInfo {
+            offset: self.offset,
+            data_length,
+            footer_length: stripe_footer.len() as u64,
+            rows,
+        });
+        self.offset += data_length + stripe_footer.len() as u64;
+        self.total_rows += rows;
+        self.rows.clear();
+        self.buffered_size = 0;
+        Ok(())
+    }
+
+    fn build_footer(&self) -> Vec<u8> {
+        let mut footer = ProtoWriter::default();
+        footer.uint(1, ORC_MAGIC.len() as u64);
+        footer.uint(2, self.offset);
+        for stripe in self.stripes.iter() {
+            let mut stripe_info = ProtoWriter::default();
+            stripe_info.uint(1, stripe.offset);
+            stripe_info.uint(2, 0);
+            stripe_info.uint(3, stripe.data_length);
+            stripe_info.uint(4, stripe.footer_length);
+            stripe_info.uint(5, stripe.rows);
+            footer.bytes(3, &stripe_info.buf);
+        }
+
+        let mut root = ProtoWriter::default();
+        root.uint(1, TYPE_STRUCT);
+        root.packed(2, &(1..=self.types.len() as u64).collect::<Vec<u64>>());
+        for col in self.cols.iter() {
+            root.bytes(3, col.as_bytes());
+        }
+        footer.bytes(4, &root.buf);
+        for orc_type in self.types.iter() {
+            let mut field = ProtoWriter::default();
+            field.uint(1, orc_type.kind());
+            footer.bytes(4, &field.buf);
+        }
+
+        footer.uint(6, self.total_rows);
+        footer.uint(8, 0);
+        footer.buf
+    }
+
+    fn compression_kind(&self) -> u64 {
+        match self.compression {
+            OrcCompression::None => 0,
+            OrcCompression::Zlib => 1,
+            OrcCompression::Zstd => 5,
+        }
+    }
+
+    /// Splits a stream into blocks with 3 bytes headers: (block length << 1) | is original
+    fn compress(&self, raw: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if self.compression == OrcCompression::None {
+            return Ok(raw.to_vec());
+        }
+
+        let mut result = Vec::with_capacity(raw.len());
+        for block in raw.chunks(COMPRESSION_BLOCK_SIZE) {
+            let compressed = match self.compression {
+                OrcCompression::Zlib => {
+                    // zlib of orc is raw deflate without the zlib header
+                    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                    encoder.write_all(block)?;
+                    encoder.finish()?
+                }
+                _ => zstd::bulk::compress(block, zstd::DEFAULT_COMPRESSION_LEVEL)?,
+            };
+            let (body, is_original) = if compressed.len() < block.len() {
+                (compressed.as_slice(), 0)
+            } else {
+                (block, 1)
+            };
+            let header = ((body.len() as u32) << 1) | is_original;
+            result.extend_from_slice(&header.to_le_bytes()[..3]);
+            result.extend_from_slice(body);
+        }
+        Ok(result)
+    }
+}
+
+/// Encoder of the protobuf messages in orc_proto.proto
+#[derive(Default)]
+struct ProtoWriter {
+    buf: Vec<u8>,
+}
+
+impl ProtoWriter {
+    fn uint(&mut self, field: u64, value: u64) {
+        write_varint(&mut self.buf, field << 3);
+        write_varint(&mut self.buf, value);
+    }
+
+    fn bytes(&mut self, field: u64, value: &[u8]) {
+        write_varint(&mut self.buf, (field << 3) | 2);
+        write_varint(&mut self.buf, value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+
+    fn packed(&mut self, field: u64, values: &[u64]) {
+        let mut buf = Vec::new();
+        for value in values {
+            write_varint(&mut buf, *value);
+        }
+        self.bytes(field, &buf);
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+// rle v1, only literal runs: a header byte of -count, then count varints
+fn encode_int_rle(values: &[u64]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for run in values.chunks(128) {
+        buf.push((run.len() as u8).wrapping_neg());
+        for value in run {
+            write_varint(&mut buf, *value);
+        }
+    }
+    buf
+}
+
+// byte rle, only literal runs: a header byte of -count, then count bytes
+fn encode_byte_rle(values: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for run in values.chunks(128) {
+        buf.push((run.len() as u8).wrapping_neg());
+        buf.extend_from_slice(run);
+    }
+    buf
+}
+
+// bits are packed from the most significant one, then encoded by byte rle
+fn encode_bool_rle(values: &[bool]) -> Vec<u8> {
+    let bytes: Vec<u8> = values
+        .chunks(8)
+        .map(|bits| {
+            bits.iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, bit)| byte | ((*bit as u8) << (7 - i)))
+        })
+        .collect();
+    encode_byte_rle(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_rle() {
+        assert_eq!(
+            encode_int_rle(&[zigzag(1), zigzag(-1), 300]),
+            vec![0xfd, 2, 1, 0xac, 2]
+        );
+        assert_eq!(encode_int_rle(&vec![0; 130]).len(), 1 + 128 + 1 + 2);
+        assert_eq!(
+            encode_bool_rle(&[true, false, true, true, false, false, false, false, true]),
+            vec![0xfe, 0b1011_0000, 0b1000_0000]
+        );
+    }
+
+    #[test]
+    fn test_orc_type_from_values() {
+        let values = [ColValue::None, ColValue::Long(1), ColValue::LongLong(2)];
+        assert_eq!(OrcType::from_values(values.iter()), OrcType::Long);
+        let values = [ColValue::Long(1), ColValue::Double(2.0)];
+        assert_eq!(OrcType::from_values(values.iter()), OrcType::String);
+        assert_eq!(
+            OrcType::from_values([ColValue::None].iter()),
+            OrcType::String
+        );
+    }
+
+    #[test]
+    fn test_orc_type_from_origin_type() {
+        for (origin_type, orc_type) in [
+            ("int(11)", OrcType::Long),
+            ("bigint(20) unsigned", OrcType::String),
+            ("int8", OrcType::Long),
+            ("double", OrcType::Double),
+            ("float8", OrcType::Double),
+            ("bool", OrcType::Boolean),
+            ("varbinary(16)", OrcType::Binary),
+            ("bytea", OrcType::Binary),
+            ("decimal(10,2)", OrcType::String),
+            ("datetime(3)", OrcType::String),
+            ("_int4", OrcType::String),
+        ] {
+            assert_eq!(OrcType::from_origin_type(origin_type), orc_type);
+        }
+    }
+
+    #[test]
+    fn test_write_file() {
+        for compression in [
+            OrcCompression::None,
+            OrcCompression::Zlib,
+            OrcCompression::Zstd,
+        ] {
+            let mut writer =
+                OrcFileWriter::new(Vec::new(), vec!["id".into(), "name".into()], compression)
+                    .unwrap();
+            writer
+                .write_row(vec![ColValue::Long(1), ColValue::String("a".into())], 1)
+                .unwrap();
+            writer
+                .write_row(vec![ColValue::Long(2), ColValue::None], 1)
+                .unwrap();
+            let buf = writer.finish().unwrap();
+
+            assert_eq!(&buf[..3], ORC_MAGIC);
+            let post_script_len = *buf.last().unwrap() as usize;
+            let post_script = &buf[buf.len() - 1 - post_script_len..buf.len() - 1];
+            assert!(post_script.ends_with(ORC_MAGIC));
+        }
+    }
+
+    // the encoder and footer are hand-rolled, so files are checked by a real orc reader
+    #[test]
+    fn test_read_by_orc_reader() {
+        for compression in [
+            OrcCompression::None,
+            OrcCompression::Zlib,
+            OrcCompression::Zstd,
+        ] {
+            let cols = vec![
+                "id".into(),
+                "score".into(),
+                "flag".into(),
+                "data".into(),
+                "name".into(),
+            ];
+            let types = vec![
+                OrcType::Long,
+                OrcType::Double,
+                OrcType::Boolean,
+                OrcType::Binary,
+                OrcType::String,
+            ];
+            let mut writer = OrcFileWriter::new(Vec::new(), cols, compression)
+                .unwrap()
+                .with_types(types);
+            for i in 0..300 {
+                // all NULL in the only stripe, the type is still taken from with_types
+                let flag = ColValue::None;
+                let score = if i % 2 == 0 {
+                    ColValue::Double(i as f64 / 2.0)
+                } else {
+                    ColValue::None
+                };
+                let name = if i % 3 == 0 {
+                    ColValue::None
+                } else {
+                    ColValue::String(format!("name_{}", i))
+                };
+                let values = vec![
+                    ColValue::LongLong(i - 150),
+                    score,
+                    flag,
+                    ColValue::Blob(vec![i as u8; 3]),
+                    name,
+                ];
+                writer.write_row(values, 1).unwrap();
+            }
+            let buf = writer.finish().unwrap();
+
+            let reader =
+                orc_rust::arrow_reader::ArrowReaderBuilder::try_new(bytes::Bytes::from(buf))
+                    .unwrap()
+                    .build();
+            let batches: Vec<_> = reader.map(Result::unwrap).collect();
+            assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 300);
+
+            let schema = batches[0].schema();
+            let fields: Vec<(String, String)> = schema
+                .fields()
+                .iter()
+                .map(|f| (f.name().clone(), format!("{:?}", f.data_type())))
+                .collect();
+            assert_eq!(
+                fields,
+                vec![
+                    ("id".to_string(), "Int64".to_string()),
+                    ("score".to_string(), "Float64".to_string()),
+                    ("flag".to_string(), "Boolean".to_string()),
+                    ("data".to_string(), "Binary".to_string()),
+                    ("name".to_string(), "Utf8".to_string()),
+                ]
+            );
+            let null_counts: Vec<usize> = (0..5)
+                .map(|i| batches.iter().map(|b| b.column(i).null_count()).sum())
+                .collect();
+            assert_eq!(null_counts, vec![0, 150, 300, 0, 100]);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use reqwest::{header, Client, StatusCode};
+use serde::Serialize;
+use tokio::time::Instant;
+
+use dt_common::{
+    error::Error,
+    log_warn,
+    meta::{
+        col_value::ColValue, position::Position, provenance::Provenance,
+        rdb_meta_manager::RdbMetaManager, row_data::RowData,
+    },
+    utils::limit_queue::LimitedQueue,
+};
+
+use crate::{
+    call_batch_fn,
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker,
+        idempotency_key::{
+            build_batch_idempotency_key, build_idempotency_key, IDEMPOTENCY_KEY_HEADER,
+        },
+    },
+    Sinker,
+};
+
+const MAX_BACKOFF_MILLIS: u64 = 60_000;
+
+/// POSTs batches of rows as json to an http endpoint (webhook), body:
+/// {"rows":[{"schema":"db1","tb":"tb1","operation":"insert","position":{..},"provenance":{..},"before":{..},"after":{..}}]}
+///
+/// with idempotency_key, each row has an "idempotency_key" and the request has the batch key in
+/// the ape-dts-idempotency-key header.
+///
+/// a batch is retried with exponential backoff until the endpoint returns 2xx or max_retries is reached,
+/// the task fails then and restarts from the last checkpoint, so rows are delivered at least once.
+pub struct HttpSinker {
+    pub url: String,
+    pub batch_size: usize,
+    // with the configured headers and timeout
+    pub http_client: Client,
+    pub max_retries: u32,
+    pub retry_interval_ms: u64,
+    pub with_provenance: bool,
+    pub idempotency_key: bool,
+    // to get key columns of idempotency keys, all columns are used without it
+    pub meta_manager: Option<RdbMetaManager>,
+    // rows have been routed by extractor, router is only used to find the source table
+    pub router: Option<RdbRouter>,
+    pub base_sinker: BaseSinker,
+}
+
+#[derive(Serialize)]
+struct HttpBatch<'a> {
+    rows: Vec<HttpRow<'a>>,
+}
+
+#[derive(Serialize)]
+struct HttpRow<'a> {
+    schema: &'a str,
+    tb: &'a str,
+    operation: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position: Option<&'a Position>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provenance: Option<Provenance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idempotency_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<BTreeMap<&'a str, &'a ColValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<BTreeMap<&'a str, &'a ColValue>>,
+}
+
+impl<'a> HttpRow<'a> {
+    fn from_row_data(
+        row_data: &'a RowData,
+        with_provenance: bool,
+        idempotency_key: Option<String>,
+    ) -> Self {
+        let provenance = match row_data.position.as_deref() {
+            Some(position) if with_provenance => Provenance::from_position(position),
+            _ => None,
+        };
+        Self {
+            schema: &row_data.schema,
+            tb: &row_data.tb,
+            operation: (&row_data.row_type).into(),
+            position: row_data.position.as_deref(),
+            provenance,
+            idempotency_key,
+            before: Self::ordered_col_values(&row_data.before),
+            after: Self::ordered_col_values(&row_data.after),
+        }
+    }
+
+    fn ordered_col_values(
+        col_values: &'a Option<HashMap<String, ColValue>>,
+    ) -> Option<BTreeMap<&'a str, &'a ColValue>> {
+        col_values
+            .as_ref()
+            .map(|col_values| col_values.iter().map(|(k, v)| (k.as_str(), v)).collect())
+    }
+}
+
+#[async_trait]
+impl Sinker for HttpSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        call_batch_fn!(self, data, Self::send_batch);
+        Ok(())
+    }
+}
+
+impl HttpSinker {
+    async fn send_batch(
+        &mut self,
+        data: &mut [RowData],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let rows = &mut data[sinked_count..sinked_count + batch_size];
+        let task_id = self.base_sinker.task_id_for_rows(rows);
+        self.base_sinker.ensure_monitor_for(&task_id);
+
+        let mut data_size = 0;
+        for row_data in rows.iter_mut() {
+            data_size += row_data.get_data_size();
+            row_data.convert_raw_string();
+        }
+        let mut row_keys = Vec::new();
+        if self.idempotency_key {
+            for row_data in rows.iter() {
+                let id_cols = self.get_id_cols(row_data).await?;
+                row_keys.push(build_idempotency_key(row_data, &id_cols)?);
+            }
+        }
+        let batch_key = if self.idempotency_key {
+            Some(build_batch_idempotency_key(&row_keys))
+        } else {
+            None
+        };
+
+        let batch = HttpBatch {
+            rows: rows
+                .iter()
+                .enumerate()
+                .map(|(i, row_data)| {
+                    HttpRow::from_row_data(row_data, self.with_provenance, row_keys.get(i).cloned())
+                })
+                .collect(),
+        };
+        let body = serde_json::to_string(&batch)?;
+
+        let start_time = Instant::now();
+        let mut rts = LimitedQueue::new(1);
+        self.post(body, batch_key.as_deref()).await?;
+        rts.push((start_time.elapsed().as_millis() as u64, batch_size as u64));
+
+        self.base_sinker
+            .update_batch_monitor_for(&task_id, batch_size as u64, data_size)
+            .await?;
+        self.base_sinker.update_monitor_rt_for(&task_id, &rts).await
+    }
+
+    async fn get_id_cols(&mut self, row_data: &RowData) -> anyhow::Result<Vec<String>> {
+        let Some(meta_manager) = self.meta_manager.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let (schema, tb) = Self::get_source_tb(self.router.as_ref(), row_data);
+        let tb_meta = meta_manager.get_tb_meta(schema, tb).await?;
+        Ok(Self::route_id_cols(
+            self.router.as_ref(),
+            schema,
+            tb,
+            &tb_meta.id_cols,
+        ))
+    }
+
+    fn get_source_tb<'a>(
+        router: Option<&'a RdbRouter>,
+        row_data: &'a RowData,
+    ) -> (&'a str, &'a str) {
+        match router {
+            Some(router) => router.reverse_get_tb_map(&row_data.schema, &row_data.tb),
+            None => (&row_data.schema, &row_data.tb),
+        }
+    }
+
+    // id cols of the source table, named as in the routed rows
+    fn route_id_cols(
+        router: Option<&RdbRouter>,
+        schema: &str,
+        tb: &str,
+        id_cols: &[String],
+    ) -> Vec<String> {
+        let col_map = router.and_then(|router| router.get_col_map(schema, tb));
+        id_cols
+            .iter()
+            .map(|col| {
+                col_map
+                    .and_then(|col_map| col_map.get(col))
+                    .cloned()
+                    .unwrap_or_else(|| col.clone())
+            })
+            .collect()
+    }
+
+    async fn post(&self, body: String, idempotency_key: Option<&str>) -> anyhow::Result<()> {
+        let mut retries = 0;
+        loop {
+            let mut request = self
+                .http_client
+                .post(&self.url)
+                .header(header::CONTENT_TYPE, "application/json");
+            if let Some(idempotency_key) = idempotency_key {
+                request = request.header(IDEMPOTENCY_KEY_HEADER, idempotency_key);
+            }
+            let result = request.body(body.clone()).send().await;
+
+            let (retryable, err) = match result {
+                Ok(response) => {
+                    let status_code = response.status();
+                    if status_code.is_success() {
+                        return Ok(());
+                    }
+                    let response_text = response.text().await.unwrap_or_default();
+                    (
+                        Self::is_retryable(status_code),
+                        format!(
+                            "status_code: {}, response_text: {:?}",
+                            status_code, response_text
+                        ),
+                    )
+                }
+                // connection errors and timeouts
+                Err(err) => (true, err.to_string()),
+            };
+
+            if !retryable || retries >= self.max_retries {
+                bail! {Error::HttpError(format!(
+                    "http sinker request failed, url: {}, retries: {}, {}",
+                    self.url, retries, err
+                ))}
+            }
+
+            let backoff_millis = Self::backoff_millis(self.retry_interval_ms, retries);
+            log_warn!(
+                "http sinker request failed, url: {}, retry in {} ms, {}",
+                self.url,
+                backoff_millis,
+                err
+            );
+            tokio::time::sleep(Duration::from_millis(backoff_millis)).await;
+            retries += 1;
+        }
+    }
+
+    pub(crate) fn is_retryable(status_code: StatusCode) -> bool {
+        status_code.is_server_error()
+            || status_code == StatusCode::TOO_MANY_REQUESTS
+            || status_code == StatusCode::REQUEST_TIMEOUT
+    }
+
+    pub(crate) fn backoff_millis(retry_interval_ms: u64, retries: u32) -> u64 {
+        retry_interval_ms
+            .saturating_mul(1u64 << retries.min(16))
+            .min(MAX_BACKOFF_MILLIS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::{
+        config::{config_enums::DbType, router_config::RouterConfig},
+        meta::row_type::RowType,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_http_row() {
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]);
+        let row_data = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        assert_eq!(
+            serde_json::to_string(&HttpRow::from_row_data(&row_data, true, None)).unwrap(),
+            r#"{"schema":"db1","tb":"tb1","operation":"insert","after":{"id":1,"name":"a"}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&HttpRow::from_row_data(&row_data, false, Some("k1".into())))
+                .unwrap(),
+            r#"{"schema":"db1","tb":"tb1","operation":"insert","idempotency_key":"k1","after":{"id":1,"name":"a"}}"#
+        );
+    }
+
+    #[test]
+    fn test_backoff_millis() {
+        assert_eq!(HttpSinker::backoff_millis(500, 0), 500);
+        assert_eq!(HttpSinker::backoff_millis(500, 3), 4000);
+        assert_eq!(HttpSinker::backoff_millis(500, 10), MAX_BACKOFF_MILLIS);
+        assert!(!HttpSinker::is_retryable(StatusCode::BAD_REQUEST));
+        assert!(HttpSinker::is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[test]
+    fn test_id_cols_of_renamed_tb() {
+        let router_config = RouterConfig::Rdb {
+            schema_map: String::new(),
+            tb_map: "src_db.src_tb:dst_db.dst_tb".into(),
+            col_map: r#"json:[{"db":"src_db","tb":"src_tb","col_map":{"id":"dst_id"}}]"#.into(),
+            topic_map: String::new(),
+            identifier_case: Default::default(),
+        };
+        let router = RdbRouter::from_config(&router_config, &DbType::Mysql)
+            .unwrap()
+            .unwrap();
+        let row_data = |id: i64| {
+            let after = HashMap::from([
+                ("dst_id".to_string(), ColValue::Long(id)),
+                ("name".to_string(), ColValue::String("a".into())),
+            ]);
+            RowData::new(
+                "dst_db".into(),
+                "dst_tb".into(),
+                0,
+                RowType::Insert,
+                None,
+                Some(after),
+            )
+        };
+
+        // tb_meta is looked up by the source names, key cols are read by the routed names
+        let row_data_1 = row_data(1);
+        let (schema, tb) = HttpSinker::get_source_tb(Some(&router), &row_data_1);
+        assert_eq!((schema, tb), ("src_db", "src_tb"));
+        let id_cols = HttpSinker::route_id_cols(Some(&router), schema, tb, &["id".to_string()]);
+        assert_eq!(id_cols, vec!["dst_id".to_string()]);
+        assert_ne!(
+            build_idempotency_key(&row_data_1, &id_cols).unwrap(),
+            build_idempotency_key(&row_data(2), &id_cols).unwrap()
+        );
+
+        assert_eq!(
+            HttpSinker::get_source_tb(None, &row_data_1),
+            ("dst_db", "dst_tb")
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use dt_common::meta::{col_value::ColValue, row_data::RowData, row_type::RowType};
+
+/// message header carrying the idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "ape-dts-idempotency-key";
+
+/// Builds a stable key for a row event: sha256 of source position + schema + tb + row type + key values,
+/// the same event re-sent after a restart (at-least-once) gets the same key,
+/// so downstream systems can dedup without parsing the payload.
+///
+/// if key_cols is empty (e.g. no table meta), all columns of the row are used.
+pub fn build_idempotency_key(row_data: &RowData, key_cols: &[String]) -> anyhow::Result<String> {
+    let position = row_data
+        .position
+        .as_ref()
+        .map(|p| p.to_string())
+        .unwrap_or_default();
+
+    let empty = HashMap::new();
+    let col_values = match row_data.row_type {
+        RowType::Insert => row_data.after.as_ref(),
+        RowType::Update | RowType::Delete => row_data.before.as_ref(),
+    }
+    .unwrap_or(&empty);
+
+    let mut key_values: Vec<(&str, &ColValue)> = if key_cols.is_empty() {
+        col_values.iter().map(|(k, v)| (k.as_str(), v)).collect()
+    } else {
+        key_cols
+            .iter()
+            .map(|col| (col.as_str(), col_values.get(col).unwrap_or(&ColValue::None)))
+            .collect()
+    };
+    key_values.sort_by(|a, b| a.0.cmp(b.0));
+
+    let row_type: &str = (&row_data.row_type).into();
+    let identity = serde_json::to_string(&(
+        position,
+        &row_data.schema,
+        &row_data.tb,
+        row_type,
+        key_values,
+    ))?;
+    Ok(hex::encode(openssl::sha::sha256(identity.as_bytes())))
+}
+
+/// Key of a batch sent in one request, the row key for a single row, otherwise sha256 of the
+/// row keys in order, so the same batch sent again gets the same key.
+pub fn build_batch_idempotency_key(row_keys: &[String]) -> String {
+    match row_keys {
+        [row_key] => row_key.clone(),
+        _ => hex::encode(openssl::sha::sha256(row_keys.join(",").as_bytes())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use dt_common::meta::position::Position;
+
+    use super::*;
+
+    fn build_row(position: &str, name: &str) -> RowData {
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::LongLong(1)),
+            ("name".to_string(), ColValue::String(name.into())),
+        ]);
+        let mut row_data = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        row_data.position = Some(Arc::new(Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: "mysql-bin.000001".into(),
+            next_event_position: position.parse().unwrap(),
+            gtid_set: String::new(),
+            timestamp: String::new(),
+            source_id: String::new(),
+        }));
+        row_data
+    }
+
+    #[test]
+    fn test_build_idempotency_key() {
+        let key_cols = vec!["id".to_string()];
+        let key = build_idempotency_key(&build_row("100", "a"), &key_cols).unwrap();
+        assert_eq!(key.len(), 64);
+
+        // stable for the same event
+        assert_eq!(
+            key,
+            build_idempotency_key(&build_row("100", "a"), &key_cols).unwrap()
+        );
+        // non key columns are ignored
+        assert_eq!(
+            key,
+            build_idempotency_key(&build_row("100", "b"), &key_cols).unwrap()
+        );
+        // the same row at another position is another event
+        assert_ne!(
+            key,
+            build_idempotency_key(&build_row("200", "a"), &key_cols).unwrap()
+        );
+        // a single row batch has the row key
+        assert_eq!(build_batch_idempotency_key(&[key.clone()]), key);
+        let other_key = build_idempotency_key(&build_row("200", "a"), &key_cols).unwrap();
+        assert_ne!(
+            build_batch_idempotency_key(&[key.clone(), other_key.clone()]),
+            build_batch_idempotency_key(&[other_key, key.clone()])
+        );
+        // all columns are used without key columns
+        assert_ne!(
+            build_idempotency_key(&build_row("100", "a"), &[]).unwrap(),
+            build_idempotency_key(&build_row("100", "b"), &[]).unwrap()
+        );
+    }
+}
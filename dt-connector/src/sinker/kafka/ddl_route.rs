@@ -0,0 +1,50 @@
+use dt_common::meta::ddl_meta::ddl_data::DdlData;
+
+use crate::rdb_router::RdbRouter;
+
+/// Topics and keys of ddl messages sent by kafka sinkers.
+///
+/// By default a ddl goes to the topic of its default schema without a key. If [sinker] ddl_topic
+/// or ddl_topic_suffix is set, ddls are keyed by their tables, so ddls of a table stay in one
+/// partition and keep their order.
+#[derive(Clone, Default)]
+pub struct KafkaDdlRoute {
+    // all ddls go to this topic
+    pub ddl_topic: String,
+    // ddls go to the topic of their tables with this suffix, e.g. test_ddl
+    pub ddl_topic_suffix: String,
+}
+
+impl KafkaDdlRoute {
+    pub fn is_enabled(&self) -> bool {
+        !self.ddl_topic.is_empty() || !self.ddl_topic_suffix.is_empty()
+    }
+
+    pub fn get_topic(&self, router: &RdbRouter, ddl_data: &DdlData) -> String {
+        if !self.is_enabled() {
+            return router.get_topic(&ddl_data.default_schema, "").to_string();
+        }
+        if !self.ddl_topic.is_empty() {
+            return self.ddl_topic.clone();
+        }
+        let (schema, tb) = ddl_data.get_schema_tb();
+        format!(
+            "{}{}",
+            router.get_topic(&schema, &tb),
+            self.ddl_topic_suffix
+        )
+    }
+
+    pub fn get_key(&self, ddl_data: &DdlData) -> String {
+        if !self.is_enabled() {
+            return String::new();
+        }
+        // ddls of databases, e.g. create database, are keyed by the database
+        let (schema, tb) = ddl_data.get_schema_tb();
+        if tb.is_empty() {
+            schema
+        } else {
+            format!("{}.{}", schema, tb)
+        }
+    }
+}
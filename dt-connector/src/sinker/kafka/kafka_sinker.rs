@@ -1,20 +1,57 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
-use kafka::producer::{Producer, Record};
+use kafka::{
+    client::Compression,
+    producer::{Producer, Record, RequiredAcks},
+};
 use tokio::time::Instant;
 
 use dt_common::{
-    meta::{avro::avro_converter::AvroConverter, ddl_meta::ddl_data::DdlData, row_data::RowData},
+    meta::{
+        avro::avro_converter::AvroConverter, ddl_meta::ddl_data::DdlData, position::Position,
+        row_data::RowData,
+    },
     utils::limit_queue::LimitedQueue,
 };
 
-use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    call_batch_fn,
+    rdb_router::RdbRouter,
+    sinker::{base_sinker::BaseSinker, conn_health::ConnHealth, kafka::ddl_route::KafkaDdlRoute},
+    Sinker,
+};
+
+type KafkaRecord<'a> = Record<'a, String, Vec<u8>>;
 
 pub struct KafkaSinker {
     pub batch_size: usize,
     pub router: RdbRouter,
     pub producer: Producer,
+    // to create the producer again after the brokers are lost
+    pub producer_config: KafkaProducerConfig,
     pub avro_converter: AvroConverter,
     pub base_sinker: BaseSinker,
+    pub conn_health: ConnHealth,
+    pub ddl_route: KafkaDdlRoute,
+}
+
+#[derive(Clone)]
+pub struct KafkaProducerConfig {
+    pub brokers: Vec<String>,
+    pub ack_timeout_secs: u64,
+    pub required_acks: RequiredAcks,
+    pub compression: Compression,
+}
+
+impl KafkaProducerConfig {
+    pub fn create_producer(&self) -> anyhow::Result<Producer> {
+        Ok(Producer::from_hosts(self.brokers.clone())
+            .with_ack_timeout(Duration::from_secs(self.ack_timeout_secs))
+            .with_required_acks(self.required_acks)
+            .with_compression(self.compression)
+            .create()?)
+    }
 }
 
 #[async_trait]
@@ -29,25 +66,71 @@ impl Sinker for KafkaSinker {
     }
 
     async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
-        let mut messages = Vec::new();
+        let mut topics_and_keys = Vec::new();
+        let mut payloads = Vec::new();
         for ddl_data in data {
-            let topic = self.router.get_topic(&ddl_data.default_schema, "");
-            let payload = self.avro_converter.ddl_data_to_avro_value(ddl_data).await?;
-            messages.push(Record {
-                key: String::new(),
+            topics_and_keys.push((
+                self.ddl_route.get_topic(&self.router, &ddl_data),
+                self.ddl_route.get_key(&ddl_data),
+            ));
+            payloads.push(self.avro_converter.ddl_data_to_avro_value(ddl_data).await?);
+        }
+        // ddls are never sunk together with dmls, so the ones before are acked already
+        let messages: Vec<KafkaRecord> = topics_and_keys
+            .iter()
+            .zip(payloads)
+            .map(|((topic, key), payload)| Record {
+                key: key.clone(),
                 value: payload,
-                topic,
+                topic: topic.as_str(),
                 partition: -1,
-            });
-        }
-        self.producer.send_all(&messages)?;
-        Ok(())
+            })
+            .collect();
+        Self::send_all(
+            &mut self.producer,
+            &self.producer_config,
+            &mut self.conn_health,
+            &messages,
+        )
+        .await
     }
 
     async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
         self.avro_converter.refresh_meta(&data);
         Ok(())
     }
+
+    async fn sink_snapshot_watermark(&mut self, position: &Position) -> anyhow::Result<()> {
+        if let Position::RdbSnapshotFinished {
+            schema,
+            tb,
+            row_count,
+            cdc_position: Some(cdc_position),
+            ..
+        } = position
+        {
+            let payload = self.avro_converter.snapshot_watermark_to_avro_value(
+                schema,
+                tb,
+                row_count.unwrap_or_default(),
+                cdc_position,
+            )?;
+            let messages = [Record {
+                key: String::new(),
+                value: payload,
+                topic: self.router.get_topic(schema, tb),
+                partition: -1,
+            }];
+            Self::send_all(
+                &mut self.producer,
+                &self.producer_config,
+                &mut self.conn_health,
+                &messages,
+            )
+            .await?;
+        }
+        Ok(())
+    }
 }
 
 impl KafkaSinker {
@@ -83,7 +166,13 @@ impl KafkaSinker {
         //       making it impossible to see individual broker RT. This can be optimized in the future.
         let start_time = Instant::now();
         let mut rts = LimitedQueue::new(1);
-        self.producer.send_all(&messages)?;
+        Self::send_all(
+            &mut self.producer,
+            &self.producer_config,
+            &mut self.conn_health,
+            &messages,
+        )
+        .await?;
         rts.push((
             start_time.elapsed().as_millis() as u64,
             messages.len() as u64,
@@ -94,4 +183,32 @@ impl KafkaSinker {
             .await?;
         self.base_sinker.update_monitor_rt_for(&task_id, &rts).await
     }
+
+    // fields are passed separately since topics of messages borrow the router
+    async fn send_all(
+        producer: &mut Producer,
+        producer_config: &KafkaProducerConfig,
+        conn_health: &mut ConnHealth,
+        messages: &[KafkaRecord<'_>],
+    ) -> anyhow::Result<()> {
+        loop {
+            match producer.send_all(messages) {
+                Err(error)
+                    if conn_health.is_enabled() && ConnHealth::is_kafka_conn_error(&error) =>
+                {
+                    // messages acked before the failure are sent again
+                    *producer = conn_health
+                        .recover("kafka", error.into(), || async {
+                            producer_config.create_producer()
+                        })
+                        .await?;
+                }
+                result => {
+                    result?;
+                    conn_health.mark_active();
+                    return Ok(());
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use dt_common::{
+    config::{
+        config_enums::DbType, filter_config::FilterConfig, sinker_config::KafkaCompressColsConfig,
+    },
+    meta::{col_value::ColValue, row_data::RowData},
+    rdb_filter::RdbFilter,
+};
+
+// json of CompressedCols, columns in the payload compressed by zstd
+pub const COMPRESSED_COLS_HEADER: &str = "ape-dts-compressed-cols";
+// a payload larger than max_message_bytes is sent as chunks with the same id and key
+pub const CHUNK_ID_HEADER: &str = "ape-dts-chunk-id";
+pub const CHUNK_INDEX_HEADER: &str = "ape-dts-chunk-index";
+pub const CHUNK_COUNT_HEADER: &str = "ape-dts-chunk-count";
+
+// room left in each chunk for the key, headers and the record overhead
+const CHUNK_OVERHEAD_BYTES: usize = 1024;
+const ZSTD_LEVEL: i32 = 3;
+
+/// Value type of a compressed column before compression, to be restored by the extractor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressedKind {
+    String,
+    Bytes,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompressedCols {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub before: HashMap<String, CompressedKind>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub after: HashMap<String, CompressedKind>,
+}
+
+/// Compresses configured columns of rows and splits large payloads into chunks,
+/// the kafka extractor reverses both by message headers.
+#[derive(Clone)]
+pub struct KafkaLargeValue {
+    pub compress_cols: Vec<(RdbFilter, HashSet<String>)>,
+    pub compress_min_bytes: usize,
+    pub max_message_bytes: usize,
+}
+
+impl KafkaLargeValue {
+    pub fn new(
+        configs: &[KafkaCompressColsConfig],
+        compress_min_bytes: usize,
+        max_message_bytes: usize,
+        db_type: &DbType,
+    ) -> anyhow::Result<Self> {
+        let mut compress_cols = Vec::new();
+        for config in configs.iter() {
+            let filter_config = FilterConfig {
+                do_tbs: config.tbs.clone(),
+                ..Default::default()
+            };
+            let filter = RdbFilter::from_config(&filter_config, db_type)?;
+            compress_cols.push((filter, config.cols.iter().cloned().collect()));
+        }
+        Ok(Self {
+            compress_cols,
+            compress_min_bytes,
+            max_message_bytes,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.compress_cols.is_empty() || self.max_message_bytes > 0
+    }
+
+    /// Compresses the configured columns of the source table in place, returns the value of
+    /// [COMPRESSED_COLS_HEADER], None if no value is compressed.
+    pub fn compress(
+        &self,
+        row_data: &mut RowData,
+        src_schema: &str,
+        src_tb: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let cols: HashSet<&String> = self
+            .compress_cols
+            .iter()
+            .filter(|(filter, _)| !filter.filter_tb(src_schema, src_tb))
+            .flat_map(|(_, cols)| cols.iter())
+            .collect();
+        if cols.is_empty() {
+            return Ok(None);
+        }
+
+        let compressed = CompressedCols {
+            before: self.compress_image(&mut row_data.before, &cols)?,
+            after: self.compress_image(&mut row_data.after, &cols)?,
+        };
+        if compressed == CompressedCols::default() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(&compressed)?))
+    }
+
+    fn compress_image(
+        &self,
+        image: &mut Option<HashMap<String, ColValue>>,
+        cols: &HashSet<&String>,
+    ) -> anyhow::Result<HashMap<String, CompressedKind>> {
+        let mut compressed = HashMap::new();
+        let Some(image) = image else {
+            return Ok(compressed);
+        };
+        for (col, value) in image.iter_mut() {
+            if !cols.contains(col) {
+                continue;
+            }
+            let (kind, raw) = match value {
+                ColValue::String(v) | ColValue::Json2(v) => (CompressedKind::String, v.as_bytes()),
+                ColValue::Blob(v) | ColValue::Json(v) => (CompressedKind::Bytes, v.as_slice()),
+                _ => continue,
+            };
+            if raw.len() < self.compress_min_bytes {
+                continue;
+            }
+            let compressed_value = zstd::bulk::compress(raw, ZSTD_LEVEL)?;
+            *value = ColValue::Blob(compressed_value);
+            compressed.insert(col.clone(), kind);
+        }
+        Ok(compressed)
+    }
+
+    /// Restores columns compressed by [KafkaLargeValue::compress], header is the value of
+    /// [COMPRESSED_COLS_HEADER].
+    pub fn decompress(row_data: &mut RowData, header: &[u8]) -> anyhow::Result<()> {
+        let compressed: CompressedCols = serde_json::from_slice(header)
+            .with_context(|| format!("invalid header {}", COMPRESSED_COLS_HEADER))?;
+        Self::decompress_image(&mut row_data.before, &compressed.before)?;
+        Self::decompress_image(&mut row_data.after, &compressed.after)
+    }
+
+    fn decompress_image(
+        image: &mut Option<HashMap<String, ColValue>>,
+        compressed: &HashMap<String, CompressedKind>,
+    ) -> anyhow::Result<()> {
+        let Some(image) = image else {
+            return Ok(());
+        };
+        for (col, kind) in compressed.iter() {
+            let Some(value) = image.get_mut(col) else {
+                continue;
+            };
+            let ColValue::Blob(v) = value else {
+                bail!("compressed column {} is not bytes", col);
+            };
+            let raw = zstd::stream::decode_all(v.as_slice())
+                .with_context(|| format!("failed to decompress column {}", col))?;
+            *value = match kind {
+                CompressedKind::String => ColValue::String(String::from_utf8(raw)?),
+                CompressedKind::Bytes => ColValue::Blob(raw),
+            };
+        }
+        Ok(())
+    }
+
+    /// Max payload bytes of each chunk for a message with the key, None if not chunked.
+    pub fn chunk_size(&self, payload_len: usize, key_len: usize) -> Option<usize> {
+        if self.max_message_bytes == 0 {
+            return None;
+        }
+        let chunk_size = self
+            .max_message_bytes
+            .saturating_sub(key_len + CHUNK_OVERHEAD_BYTES)
+            .max(1);
+        (payload_len > chunk_size).then_some(chunk_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dt_common::meta::row_type::RowType;
+
+    #[test]
+    fn test_compress_and_decompress() {
+        let configs = vec![KafkaCompressColsConfig {
+            tbs: "db_1.tb_1".into(),
+            cols: vec!["content".into(), "attachment".into(), "id".into()],
+        }];
+        let large_value = KafkaLargeValue::new(&configs, 16, 0, &DbType::Mysql).unwrap();
+
+        let after = HashMap::from([
+            ("id".to_string(), ColValue::LongLong(1)),
+            ("content".to_string(), ColValue::String("a".repeat(1024))),
+            ("attachment".to_string(), ColValue::Blob(vec![7; 1024])),
+            ("title".to_string(), ColValue::String("b".repeat(1024))),
+        ]);
+        let row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after.clone()),
+        );
+
+        // other tables are not compressed
+        let mut other = row_data.clone();
+        assert!(large_value
+            .compress(&mut other, "db_1", "tb_2")
+            .unwrap()
+            .is_none());
+
+        let mut compressed = row_data.clone();
+        let header = large_value
+            .compress(&mut compressed, "db_1", "tb_1")
+            .unwrap()
+            .unwrap();
+        let compressed_after = compressed.after.as_ref().unwrap();
+        assert!(
+            matches!(compressed_after.get("content"), Some(ColValue::Blob(v)) if v.len() < 1024)
+        );
+        assert!(
+            matches!(compressed_after.get("attachment"), Some(ColValue::Blob(v)) if v.len() < 1024)
+        );
+        assert_eq!(compressed_after.get("id"), Some(&ColValue::LongLong(1)));
+        assert_eq!(compressed_after.get("title"), after.get("title"));
+
+        KafkaLargeValue::decompress(&mut compressed, header.as_bytes()).unwrap();
+        assert_eq!(compressed.after, Some(after));
+    }
+
+    #[test]
+    fn test_compress_min_bytes() {
+        let configs = vec![KafkaCompressColsConfig {
+            tbs: "*.*".into(),
+            cols: vec!["content".into()],
+        }];
+        let large_value = KafkaLargeValue::new(&configs, 1024, 0, &DbType::Mysql).unwrap();
+        let after = HashMap::from([("content".to_string(), ColValue::String("a".into()))]);
+        let mut row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+        assert!(large_value
+            .compress(&mut row_data, "db_1", "tb_1")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_chunk_size() {
+        let large_value = KafkaLargeValue::new(&[], 0, 0, &DbType::Mysql).unwrap();
+        assert_eq!(large_value.chunk_size(10_000_000, 10), None);
+
+        let large_value = KafkaLargeValue::new(&[], 0, 10_000, &DbType::Mysql).unwrap();
+        assert_eq!(large_value.chunk_size(8_000, 10), None);
+        assert_eq!(large_value.chunk_size(10_000, 10), Some(8_966));
+    }
+}
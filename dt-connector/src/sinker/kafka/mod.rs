@@ -1,2 +1,5 @@
+pub mod ddl_route;
 pub mod kafka_sinker;
+pub mod large_value;
+pub mod proxy_context;
 pub mod rdkafka_sinker;
@@ -0,0 +1,52 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::{SocketAddr, ToSocketAddrs},
+    sync::{Arc, Mutex},
+};
+
+use rdkafka::{consumer::ConsumerContext, ClientContext};
+use tokio::runtime::Handle;
+
+use dt_common::utils::proxy_tunnel::ProxyTunnel;
+
+/// Context of rdkafka producers and consumers, brokers are reached through [proxy] kafka_proxy
+/// by a local tunnel per broker, librdkafka has no proxy support of its own.
+#[derive(Clone)]
+pub struct KafkaProxyContext {
+    proxy: String,
+    handle: Handle,
+    tunnels: Arc<Mutex<HashMap<(String, u16), SocketAddr>>>,
+}
+
+impl KafkaProxyContext {
+    /// Empty proxy to connect brokers directly, must be created in a tokio runtime.
+    pub fn new(proxy: &str) -> Self {
+        Self {
+            proxy: proxy.to_string(),
+            handle: Handle::current(),
+            tunnels: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl ClientContext for KafkaProxyContext {
+    // called by librdkafka threads for the bootstrap servers and each broker in metadata
+    fn resolve_broker_addr(&self, host: &str, port: u16) -> Result<Vec<SocketAddr>, io::Error> {
+        if self.proxy.is_empty() {
+            return (host, port).to_socket_addrs().map(|addrs| addrs.collect());
+        }
+
+        let mut tunnels = self.tunnels.lock().unwrap();
+        let key = (host.to_string(), port);
+        if let Some(local_addr) = tunnels.get(&key) {
+            return Ok(vec![*local_addr]);
+        }
+        let local_addr = ProxyTunnel::start(&self.handle, &self.proxy, host, port)
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        tunnels.insert(key, local_addr);
+        Ok(vec![local_addr])
+    }
+}
+
+impl ConsumerContext for KafkaProxyContext {}
@@ -1,25 +1,54 @@
 use std::cmp;
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
-use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::{
+    message::{Header, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord, Producer},
+};
 use tokio::{time::Duration, time::Instant};
 
 use dt_common::{
-    meta::{avro::avro_converter::AvroConverter, row_data::RowData},
+    log_error,
+    meta::{
+        avro::avro_converter::AvroConverter, ddl_meta::ddl_data::DdlData, position::Position,
+        row_data::RowData,
+    },
     utils::limit_queue::LimitedQueue,
 };
 
-use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use crate::{
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker,
+        idempotency_key::{build_idempotency_key, IDEMPOTENCY_KEY_HEADER},
+        kafka::{
+            ddl_route::KafkaDdlRoute,
+            large_value::{
+                KafkaLargeValue, CHUNK_COUNT_HEADER, CHUNK_ID_HEADER, CHUNK_INDEX_HEADER,
+                COMPRESSED_COLS_HEADER,
+            },
+            proxy_context::KafkaProxyContext,
+        },
+    },
+    Sinker,
+};
 
-// Deprecated: use KafkaSinker instead
+// Deprecated: use KafkaSinker instead, except for idempotency keys, lz4 / zstd compression,
+// linger_ms, transactions, column compression and chunking which are not supported by KafkaSinker
 pub struct RdkafkaSinker {
     pub batch_size: usize,
     pub router: RdbRouter,
-    pub producer: FutureProducer,
+    pub producer: FutureProducer<KafkaProxyContext>,
     pub avro_converter: AvroConverter,
     pub base_sinker: BaseSinker,
     pub queue_timeout_secs: u64,
+    pub idempotency_key: bool,
+    pub ddl_route: KafkaDdlRoute,
+    // each sink call is produced in a transaction, the pipeline records positions of the data
+    // only after the call returns, so a batch is either committed before its position or aborted
+    pub transactional: bool,
+    pub large_value: KafkaLargeValue,
 }
 
 #[async_trait]
@@ -29,11 +58,124 @@ impl Sinker for RdkafkaSinker {
             return Ok(());
         }
 
-        self.send_avro(data.as_mut_slice()).await
+        self.begin_transaction()?;
+        let result = self.send_avro(data.as_mut_slice()).await;
+        self.end_transaction(result).await
+    }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.begin_transaction()?;
+        let result = self.send_ddls(data).await;
+        self.end_transaction(result).await
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        self.avro_converter.refresh_meta(&data);
+        Ok(())
+    }
+
+    async fn sink_snapshot_watermark(&mut self, position: &Position) -> anyhow::Result<()> {
+        if let Position::RdbSnapshotFinished {
+            schema,
+            tb,
+            row_count,
+            cdc_position: Some(cdc_position),
+            ..
+        } = position
+        {
+            let payload = self.avro_converter.snapshot_watermark_to_avro_value(
+                schema,
+                tb,
+                row_count.unwrap_or_default(),
+                cdc_position,
+            )?;
+            self.begin_transaction()?;
+            let record = FutureRecord::to(self.router.get_topic(schema, tb))
+                .payload(&payload)
+                .key("");
+            let result = match self
+                .producer
+                .send(record, Duration::from_secs(self.queue_timeout_secs))
+                .await
+            {
+                Ok(_) => Ok(()),
+                Err(err) => Err(anyhow::anyhow!(
+                    "failed in kafka producer, error: {:?}",
+                    err
+                )),
+            };
+            self.end_transaction(result).await?;
+        }
+        Ok(())
     }
 }
 
 impl RdkafkaSinker {
+    /// Fences producers of previous runs with the same transactional id and aborts
+    /// their pending transactions, must be called once before any message is sent.
+    pub async fn init_transactions(&self) -> anyhow::Result<()> {
+        if !self.transactional {
+            return Ok(());
+        }
+        let producer = self.producer.clone();
+        let timeout = Duration::from_secs(self.queue_timeout_secs);
+        tokio::task::spawn_blocking(move || producer.init_transactions(timeout))
+            .await?
+            .context("failed to init kafka transactions")
+    }
+
+    fn begin_transaction(&self) -> anyhow::Result<()> {
+        if self.transactional {
+            self.producer
+                .begin_transaction()
+                .context("failed to begin kafka transaction")?;
+        }
+        Ok(())
+    }
+
+    // commit if all messages were delivered, otherwise abort so none of them is visible
+    // to read_committed consumers, they are sent again from the last position after restart
+    async fn end_transaction(&self, result: anyhow::Result<()>) -> anyhow::Result<()> {
+        if !self.transactional {
+            return result;
+        }
+
+        let producer = self.producer.clone();
+        let timeout = Duration::from_secs(self.queue_timeout_secs);
+        match result {
+            Ok(()) => tokio::task::spawn_blocking(move || producer.commit_transaction(timeout))
+                .await?
+                .context("failed to commit kafka transaction"),
+            Err(err) => {
+                if let Err(abort_err) =
+                    tokio::task::spawn_blocking(move || producer.abort_transaction(timeout)).await?
+                {
+                    log_error!("failed to abort kafka transaction, error: {:?}", abort_err);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn send_ddls(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        let queue_timeout = Duration::from_secs(self.queue_timeout_secs);
+        // sent one by one so ddls of a table keep their order
+        for ddl_data in data {
+            let topic = self.ddl_route.get_topic(&self.router, &ddl_data);
+            let key = self.ddl_route.get_key(&ddl_data);
+            let payload = self.avro_converter.ddl_data_to_avro_value(ddl_data).await?;
+            let record = FutureRecord::to(&topic).payload(&payload).key(&key);
+            if let Err(err) = self.producer.send(record, queue_timeout).await {
+                bail!(format!("failed in kafka producer, error: {:?}", err));
+            }
+        }
+        Ok(())
+    }
+
     async fn send_avro(&mut self, data: &mut [RowData]) -> anyhow::Result<()> {
         let task_id = self.base_sinker.task_id_for_rows(data);
         self.base_sinker.ensure_monitor_for(&task_id);
@@ -52,16 +194,84 @@ impl RdkafkaSinker {
             let topic = self.router.get_topic(&row_data.schema, &row_data.tb);
             let key = self.avro_converter.row_data_to_avro_key(row_data).await?;
             let payload = self.avro_converter.row_data_to_avro_value(row_data).await?;
+            let idempotency_key = if self.idempotency_key {
+                let id_cols = self.avro_converter.get_id_cols(row_data).await?;
+                Some(build_idempotency_key(row_data, &id_cols)?)
+            } else {
+                None
+            };
+            // compressed after the key and idempotency key are built from the original values
+            let compressed_cols = if self.large_value.is_enabled() {
+                // rows carry routed names
+                let (src_schema, src_tb) = self
+                    .router
+                    .reverse_get_tb_map(&row_data.schema, &row_data.tb);
+                let (src_schema, src_tb) = (src_schema.to_string(), src_tb.to_string());
+                self.large_value.compress(row_data, &src_schema, &src_tb)?
+            } else {
+                None
+            };
+            let payload = self.avro_converter.row_data_to_avro_value(row_data).await?;
+            let chunk_size = self.large_value.chunk_size(payload.len(), key.len());
 
             // The send operation on the topic returns a future, which will be
             // completed once the result or failure from Kafka is received.
             let delivery_status = async move {
-                producer
-                    .send(
-                        FutureRecord::to(topic).payload(&payload).key(&key),
-                        queue_timeout,
-                    )
-                    .await
+                let headers = |chunk: Option<(&str, String, String)>| {
+                    let mut headers = OwnedHeaders::new();
+                    if let Some(idempotency_key) = &idempotency_key {
+                        headers = headers.insert(Header {
+                            key: IDEMPOTENCY_KEY_HEADER,
+                            value: Some(idempotency_key),
+                        });
+                    }
+                    if let Some(compressed_cols) = &compressed_cols {
+                        headers = headers.insert(Header {
+                            key: COMPRESSED_COLS_HEADER,
+                            value: Some(compressed_cols),
+                        });
+                    }
+                    if let Some((chunk_id, chunk_index, chunk_count)) = chunk {
+                        headers = headers
+                            .insert(Header {
+                                key: CHUNK_ID_HEADER,
+                                value: Some(chunk_id),
+                            })
+                            .insert(Header {
+                                key: CHUNK_INDEX_HEADER,
+                                value: Some(&chunk_index),
+                            })
+                            .insert(Header {
+                                key: CHUNK_COUNT_HEADER,
+                                value: Some(&chunk_count),
+                            });
+                    }
+                    headers
+                };
+
+                let Some(chunk_size) = chunk_size else {
+                    let mut record = FutureRecord::to(topic).payload(&payload).key(&key);
+                    if idempotency_key.is_some() || compressed_cols.is_some() {
+                        record = record.headers(headers(None));
+                    }
+                    return producer.send(record, queue_timeout).await.map(|_| ());
+                };
+
+                // chunks share the key so they go to the same partition in order
+                let chunk_id = uuid::Uuid::new_v4().to_string();
+                let chunk_count = payload.len().div_ceil(chunk_size);
+                for (chunk_index, chunk) in payload.chunks(chunk_size).enumerate() {
+                    let record = FutureRecord::to(topic)
+                        .payload(chunk)
+                        .key(&key)
+                        .headers(headers(Some((
+                            chunk_id.as_str(),
+                            chunk_index.to_string(),
+                            chunk_count.to_string(),
+                        ))));
+                    producer.send(record, queue_timeout).await?;
+                }
+                Ok(())
             };
             futures.push(delivery_status);
         }
@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sqlx::mysql::MySqlDatabaseError;
+
+const MAX_BACKOFF_MILLIS: u64 = 10_000;
+
+// mysql: ER_LOCK_WAIT_TIMEOUT, ER_LOCK_DEADLOCK
+const MYSQL_LOCK_ERRORS: [u16; 2] = [1205, 1213];
+// pg: serialization_failure, deadlock_detected, lock_not_available
+const PG_LOCK_ERRORS: [&str; 3] = ["40001", "40P01", "55P03"];
+
+/// Retries rows failed by lock conflicts of the target, e.g. deadlocks and lock wait timeouts.
+///
+/// The failed transaction is rolled back, the rows are sunk again in smaller transactions
+/// with replace enabled, so rows committed before the failure are re-applied idempotently.
+#[derive(Clone, Debug, Default)]
+pub struct LockRetry {
+    // 0 to disable
+    pub max_retries: u32,
+    pub retry_interval_ms: u64,
+}
+
+impl LockRetry {
+    pub fn is_lock_conflict(error: &anyhow::Error) -> bool {
+        error.chain().any(|cause| {
+            if let Some(sqlx::Error::Database(db_error)) = cause.downcast_ref::<sqlx::Error>() {
+                if let Some(mysql_error) = db_error.try_downcast_ref::<MySqlDatabaseError>() {
+                    return MYSQL_LOCK_ERRORS.contains(&mysql_error.number());
+                }
+                return db_error
+                    .code()
+                    .is_some_and(|code| PG_LOCK_ERRORS.contains(&code.as_ref()));
+            }
+            // errors of PgPipeline
+            if let Some(pg_error) = cause.downcast_ref::<tokio_postgres::Error>() {
+                return pg_error
+                    .code()
+                    .is_some_and(|code| PG_LOCK_ERRORS.contains(&code.code()));
+            }
+            false
+        })
+    }
+
+    /// exponential backoff with up to 50% jitter, so conflicting sinkers do not retry together
+    pub fn backoff(&self, retries: u32) -> Duration {
+        let base = self
+            .retry_interval_ms
+            .saturating_mul(1u64 << retries.saturating_sub(1).min(16))
+            .min(MAX_BACKOFF_MILLIS);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as u64)
+            .unwrap_or_default();
+        Duration::from_millis(base + nanos % (base / 2 + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let lock_retry = LockRetry {
+            max_retries: 3,
+            retry_interval_ms: 200,
+        };
+        for (retries, min) in [(1, 200), (2, 400), (3, 800), (20, MAX_BACKOFF_MILLIS)] {
+            let backoff = lock_retry.backoff(retries).as_millis() as u64;
+            assert!(backoff >= min && backoff <= min + min / 2, "{}", backoff);
+        }
+        assert!(!LockRetry::is_lock_conflict(&anyhow::anyhow!("deadlock")));
+    }
+}
@@ -1,12 +1,24 @@
 pub mod base_sinker;
 pub mod base_struct_sinker;
 pub mod checkable_sinker;
+pub mod circuit_breaker;
 pub mod clickhouse;
+pub mod conn_health;
+pub mod ddl_guard;
 pub mod dummy_sinker;
+pub mod file;
+pub mod http_sinker;
+pub mod idempotency_key;
 pub mod kafka;
+pub mod lock_retry;
 pub mod mongo;
 pub mod mysql;
+pub mod opensearch_sinker;
 pub mod pg;
 pub mod redis;
+pub mod shard_sinker;
+pub mod sql_hook;
 pub mod sql_sinker;
 pub mod starrocks;
+pub mod table_creator;
+pub mod validate_sinker;
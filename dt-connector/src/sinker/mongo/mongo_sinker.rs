@@ -47,12 +47,13 @@ impl Sinker for MongoSinker {
         if !batch {
             self.serial_sink(&data).await?;
         } else {
+            let batch_size = self.base_sinker.batch_size_for(&data, self.batch_size);
             match data[0].row_type {
                 RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+                    call_batch_fn!(self, data, Self::batch_insert, batch_size);
                 }
                 RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
+                    call_batch_fn!(self, data, Self::batch_delete, batch_size);
                 }
                 _ => self.serial_sink(&data).await?,
             }
@@ -86,12 +87,13 @@ impl CheckableSink for MongoSinker {
         if !batch {
             self.serial_sink(data).await?;
         } else {
+            let batch_size = self.base_sinker.batch_size_for(&data, self.batch_size);
             match data[0].row_type {
                 RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+                    call_batch_fn!(self, data, Self::batch_insert, batch_size);
                 }
                 RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
+                    call_batch_fn!(self, data, Self::batch_delete, batch_size);
                 }
                 _ => self.serial_sink(data).await?,
             }
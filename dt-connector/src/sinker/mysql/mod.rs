@@ -1,2 +1,3 @@
+pub mod mysql_load_data;
 pub mod mysql_sinker;
 pub mod mysql_struct_sinker;
@@ -0,0 +1,259 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use sqlx::{MySql, Pool};
+
+use dt_common::{
+    config::config_enums::DbType,
+    log_warn,
+    meta::{
+        col_value::ColValue,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        row_data::RowData,
+    },
+    utils::sql_util::SqlUtil,
+};
+
+/// Fast path of snapshot inserts for mysql -> mysql, rows of a batch are written to a
+/// tab separated file and loaded by `LOAD DATA INFILE` instead of binding values row by row.
+///
+/// The file is read by the target server, so the dir must be reachable by the same path
+/// from both DTS and the target, and allowed by the target's secure_file_priv.
+/// Once loading fails, the fast path is disabled for all sinkers of the task and
+/// batches fall back to INSERT statements.
+#[derive(Clone)]
+pub struct MysqlLoadData {
+    pub dir: String,
+    disabled: Arc<AtomicBool>,
+    file_seq: Arc<AtomicU64>,
+}
+
+impl MysqlLoadData {
+    pub fn new(dir: &str) -> Self {
+        Self {
+            dir: dir.trim_end_matches('/').to_string(),
+            disabled: Arc::new(AtomicBool::new(false)),
+            file_seq: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled.load(Ordering::Relaxed)
+    }
+
+    /// Returns false if the table or rows are not supported, the caller inserts them instead.
+    pub async fn load(
+        &self,
+        conn_pool: &Pool<MySql>,
+        tb_meta: &MysqlTbMeta,
+        data: &[RowData],
+        replace: bool,
+        data_marker_sql: Option<String>,
+    ) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let cols = Self::get_load_cols(tb_meta);
+        if cols.is_empty() {
+            return false;
+        }
+        let Some(content) = Self::build_file_content(data, &cols) else {
+            return false;
+        };
+
+        let seq = self.file_seq.fetch_add(1, Ordering::Relaxed);
+        let path = PathBuf::from(&self.dir).join(format!(
+            "{}.{}.{}.{}.tsv",
+            tb_meta.basic.schema,
+            tb_meta.basic.tb,
+            std::process::id(),
+            seq
+        ));
+        let sql = Self::get_load_data_sql(&path, tb_meta, &cols, replace);
+
+        let result = self
+            .write_and_load(conn_pool, &path, &content, &sql, data_marker_sql)
+            .await;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        if let Err(error) = result {
+            self.disabled.store(true, Ordering::Relaxed);
+            log_warn!(
+                "load data failed, fall back to insert, schema: {}, tb: {}, error: {}",
+                tb_meta.basic.schema,
+                tb_meta.basic.tb,
+                error
+            );
+            return false;
+        }
+        true
+    }
+
+    async fn write_and_load(
+        &self,
+        conn_pool: &Pool<MySql>,
+        path: &Path,
+        content: &[u8],
+        sql: &str,
+        data_marker_sql: Option<String>,
+    ) -> anyhow::Result<()> {
+        tokio::fs::write(path, content).await?;
+        // LOAD DATA can not be prepared, send it by the text protocol
+        if let Some(data_marker_sql) = data_marker_sql {
+            let mut tx = conn_pool.begin().await?;
+            sqlx::raw_sql(&data_marker_sql).execute(&mut *tx).await?;
+            sqlx::raw_sql(sql).execute(&mut *tx).await?;
+            tx.commit().await?;
+        } else {
+            sqlx::raw_sql(sql).execute(conn_pool).await?;
+        }
+        Ok(())
+    }
+
+    /// Cols written by LOAD DATA, empty if any col type can not be loaded from text as is.
+    fn get_load_cols(tb_meta: &MysqlTbMeta) -> Vec<String> {
+        let mut cols = Vec::with_capacity(tb_meta.basic.cols.len());
+        for col in tb_meta.basic.cols.iter() {
+            if tb_meta.is_col_generated(col) {
+                continue;
+            }
+            let Ok(col_type) = tb_meta.get_col_type(col) else {
+                return Vec::new();
+            };
+            if !Self::is_col_type_supported(col_type) {
+                return Vec::new();
+            }
+            cols.push(col.clone());
+        }
+        cols
+    }
+
+    fn is_col_type_supported(col_type: &MysqlColType) -> bool {
+        match col_type {
+            // the file is loaded as binary, strings are kept as utf8 bytes
+            MysqlColType::Char { charset, .. }
+            | MysqlColType::Varchar { charset, .. }
+            | MysqlColType::TinyText { charset, .. }
+            | MysqlColType::MediumText { charset, .. }
+            | MysqlColType::Text { charset, .. }
+            | MysqlColType::LongText { charset, .. } => {
+                charset.starts_with("utf8") || charset == "ascii" || charset == "binary"
+            }
+            MysqlColType::Bit | MysqlColType::Unknown => false,
+            _ => !col_type.is_spatial(),
+        }
+    }
+
+    fn get_load_data_sql(
+        path: &Path,
+        tb_meta: &MysqlTbMeta,
+        cols: &Vec<String>,
+        replace: bool,
+    ) -> String {
+        let db_type = DbType::Mysql;
+        format!(
+            "LOAD DATA INFILE '{}' {}INTO TABLE {}.{} CHARACTER SET binary FIELDS TERMINATED BY '\\t' ESCAPED BY '\\\\' LINES TERMINATED BY '\\n' ({})",
+            path.to_string_lossy()
+                .replace('\\', "\\\\")
+                .replace('\'', "\\'"),
+            if replace { "REPLACE " } else { "" },
+            SqlUtil::escape_by_db_type(&tb_meta.basic.schema, &db_type),
+            SqlUtil::escape_by_db_type(&tb_meta.basic.tb, &db_type),
+            SqlUtil::escape_cols(cols, &db_type).join(",")
+        )
+    }
+
+    /// One line per row, NULL as \N. None if any value can not be written as text.
+    fn build_file_content(data: &[RowData], cols: &[String]) -> Option<Vec<u8>> {
+        let mut content = Vec::with_capacity(data.iter().map(|i| i.data_size).sum::<usize>());
+        for row_data in data.iter() {
+            let after = row_data.after.as_ref()?;
+            for (i, col) in cols.iter().enumerate() {
+                if i > 0 {
+                    content.push(b'\t');
+                }
+                Self::append_col_value(&mut content, after.get(col).unwrap_or(&ColValue::None))?;
+            }
+            content.push(b'\n');
+        }
+        Some(content)
+    }
+
+    fn append_col_value(content: &mut Vec<u8>, col_value: &ColValue) -> Option<()> {
+        match col_value {
+            ColValue::None => content.extend_from_slice(b"\\N"),
+            ColValue::Bool(v) => content.push(if *v { b'1' } else { b'0' }),
+            ColValue::Blob(v) | ColValue::RawString(v) => Self::append_escaped(content, v),
+            // enum / set indexes are accepted by LOAD DATA as numbers
+            ColValue::Tiny(_)
+            | ColValue::UnsignedTiny(_)
+            | ColValue::Short(_)
+            | ColValue::UnsignedShort(_)
+            | ColValue::Long(_)
+            | ColValue::UnsignedLong(_)
+            | ColValue::LongLong(_)
+            | ColValue::UnsignedLongLong(_)
+            | ColValue::Float(_)
+            | ColValue::Double(_)
+            | ColValue::Decimal(_)
+            | ColValue::Time(_)
+            | ColValue::Date(_)
+            | ColValue::DateTime(_)
+            | ColValue::Timestamp(_)
+            | ColValue::Year(_)
+            | ColValue::String(_)
+            | ColValue::Set(_)
+            | ColValue::Enum(_)
+            | ColValue::Set2(_)
+            | ColValue::Enum2(_)
+            | ColValue::Json2(_)
+            | ColValue::Json3(_) => {
+                Self::append_escaped(content, col_value.to_option_string()?.as_bytes())
+            }
+            // binary json, bits, mongo docs and unchanged toasts
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn append_escaped(content: &mut Vec<u8>, value: &[u8]) {
+        for b in value {
+            match b {
+                b'\\' => content.extend_from_slice(b"\\\\"),
+                b'\t' => content.extend_from_slice(b"\\t"),
+                b'\n' => content.extend_from_slice(b"\\n"),
+                b'\r' => content.extend_from_slice(b"\\r"),
+                0 => content.extend_from_slice(b"\\0"),
+                _ => content.push(*b),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_col_value() {
+        let mut content = Vec::new();
+        for col_value in [
+            ColValue::None,
+            ColValue::Long(-1),
+            ColValue::String("a\tb\nc\\d".into()),
+            ColValue::Blob(vec![0, b'\r', 0xff]),
+            ColValue::Bool(true),
+        ] {
+            MysqlLoadData::append_col_value(&mut content, &col_value).unwrap();
+            content.push(b'|');
+        }
+        assert_eq!(content, b"\\N|-1|a\\tb\\nc\\\\d|\\0\\r\xff|1|".to_vec());
+        assert!(MysqlLoadData::append_col_value(&mut content, &ColValue::Bit(1)).is_none());
+    }
+}
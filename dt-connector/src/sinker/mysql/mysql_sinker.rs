@@ -1,21 +1,37 @@
 use std::{cmp, str::FromStr, sync::Arc, time::Duration};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use async_trait::async_trait;
 use sqlx::{
     mysql::{MySqlConnectOptions, MySqlPoolOptions},
-    MySql, Pool,
+    MySql, Pool, Transaction,
+};
+use tokio::{
+    sync::{Mutex, RwLock},
+    time::Instant,
 };
-use tokio::{sync::RwLock, time::Instant};
 
 use crate::sinker::checkable_sinker::CheckableSink;
 use crate::{
-    call_batch_fn, data_marker::DataMarker, rdb_query_builder::RdbQueryBuilder,
-    rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker,
+    call_batch_fn,
+    data_marker::DataMarker,
+    rdb_query_builder::RdbQueryBuilder,
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker,
+        circuit_breaker::{CircuitBreaker, SharedCircuitBreaker},
+        conn_health::ConnHealth,
+        ddl_guard::DdlGuard,
+        lock_retry::LockRetry,
+        mysql::mysql_load_data::MysqlLoadData,
+        sql_hook::SqlHook,
+        table_creator::TableCreator,
+    },
+    Sinker,
 };
 use dt_common::{
-    config::connection_auth_config::ConnectionAuthConfig,
-    log_error, log_info,
+    config::{config_enums::ConflictPolicyEnum, connection_auth_config::ConnectionAuthConfig},
+    log_error, log_info, log_warn,
     meta::{
         dcl_meta::dcl_data::DclData,
         ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
@@ -25,10 +41,12 @@ use dt_common::{
         row_data::RowData,
         row_type::RowType,
     },
-    utils::limit_queue::LimitedQueue,
+    utils::{
+        limit_queue::LimitedQueue,
+        query_timeout::{QueryTimeout, SINKER_STAGE},
+    },
 };
 
-#[derive(Clone)]
 pub struct MysqlSinker {
     pub url: String,
     pub connection_auth: ConnectionAuthConfig,
@@ -39,6 +57,23 @@ pub struct MysqlSinker {
     pub base_sinker: BaseSinker,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub replace: bool,
+    pub table_creator: Option<Arc<Mutex<TableCreator>>>,
+    // runs before_tb / after_tb hooks of snapshot tables, None if [sinker] sql_hooks has none
+    pub sql_hook: Option<Arc<Mutex<SqlHook>>>,
+    pub lock_retry: LockRetry,
+    pub conn_health: ConnHealth,
+    // waits for the target to recover on tripping errors, None if [circuit_breaker] is absent
+    pub circuit_breaker: Option<SharedCircuitBreaker>,
+    // checks ddls against the target before applying, None if [sinker] ddl_guard_policy=none
+    pub ddl_guard: Option<DdlGuard>,
+    // whether to skip a failed ddl or fail the task
+    pub ddl_conflict_policy: ConflictPolicyEnum,
+    // client side limit of each sink statement, None if [sinker] statement_timeout_secs=0
+    pub statement_timeout: Option<Duration>,
+    // loads snapshot batches by LOAD DATA INFILE, None if [sinker] load_data_dir is not set
+    pub load_data: Option<MysqlLoadData>,
+    // the target transaction opened by begin_trx, serial sinks run in it until commit_trx
+    pub trx: Option<Transaction<'static, MySql>>,
 }
 
 #[async_trait]
@@ -47,22 +82,11 @@ impl Sinker for MysqlSinker {
         if data.is_empty() {
             return Ok(());
         }
-
-        if !batch {
-            self.serial_sink(&data).await?;
-        } else {
-            match data[0].row_type {
-                RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
-                }
-                RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
-                }
-                _ => self.serial_sink(&data).await?,
-            }
-        }
-
-        Ok(())
+        // rows of a batch share the same meta version
+        self.meta_manager.sync_meta_version(data[0].meta_version);
+        self.auto_create_tables(&data).await?;
+        self.run_before_tb_hooks(&data).await?;
+        self.sink_rows(&mut data, batch).await
     }
 
     async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
@@ -73,6 +97,12 @@ impl Sinker for MysqlSinker {
         let mut last_monitor_time = Instant::now();
 
         for ddl_data in data.iter() {
+            if let Some(ddl_guard) = &self.ddl_guard {
+                if !ddl_guard.check(ddl_data).await? {
+                    continue;
+                }
+            }
+
             let sql = ddl_data.to_sql();
             data_size += ddl_data.get_data_size();
             data_len += 1;
@@ -106,10 +136,16 @@ impl Sinker for MysqlSinker {
                 .idle_timeout(Some(Duration::from_secs(5 * 60)))
                 .connect_with(conn_options)
                 .await?;
-            query.execute(&conn_pool).await?;
+            let result = query.execute(&conn_pool).await;
+            conn_pool.close().await;
+            if let Err(err) = result {
+                if self.ddl_conflict_policy != ConflictPolicyEnum::Ignore {
+                    bail!(err);
+                }
+                log_error!("failed to sink ddl, ignored, sql: {}, error: {}", sql, err);
+            }
 
             rts.push((start_time.elapsed().as_millis() as u64, 1));
-            conn_pool.close().await;
 
             if last_monitor_time.elapsed().as_secs() >= monitor_interval {
                 self.base_sinker
@@ -171,10 +207,34 @@ impl Sinker for MysqlSinker {
             let (routed_schema, routed_tb) = if let Some(router) = &self.router {
                 router.get_tb_map(schema, tb)
             } else {
-                (schema.as_str(), tb.as_str())
+                (schema.into(), tb.into())
             };
             self.meta_manager
-                .invalidate_cache_for_table(routed_schema, routed_tb);
+                .invalidate_cache_for_table(&routed_schema, &routed_tb);
+            if let Some(sql_hook) = &self.sql_hook {
+                sql_hook.lock().await.after_tb(schema, tb).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn begin_trx(&mut self) -> anyhow::Result<()> {
+        self.check_conn().await?;
+        let mut tx = self.conn_pool.begin().await?;
+        if let Some(sql) = self.get_data_marker_sql().await {
+            sqlx::query(&sql)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+        }
+        self.trx = Some(tx);
+        Ok(())
+    }
+
+    async fn commit_trx(&mut self) -> anyhow::Result<()> {
+        if let Some(tx) = self.trx.take() {
+            tx.commit().await?;
+            self.conn_health.mark_active();
         }
         Ok(())
     }
@@ -186,37 +246,163 @@ impl CheckableSink for MysqlSinker {
         if data.is_empty() {
             return Ok(());
         }
+        self.auto_create_tables(data).await?;
+        self.run_before_tb_hooks(data).await?;
+        self.sink_rows(data, batch).await
+    }
+}
 
-        if !batch {
-            self.serial_sink(data).await?;
-        } else {
-            match data[0].row_type {
-                RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+impl MysqlSinker {
+    async fn auto_create_tables(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        let Some(table_creator) = &self.table_creator else {
+            return Ok(());
+        };
+        let created = table_creator.lock().await.ensure_tables(data).await?;
+        for (schema, tb) in created.iter() {
+            self.meta_manager.invalidate_cache_for_table(schema, tb);
+        }
+        Ok(())
+    }
+
+    async fn run_before_tb_hooks(&self, data: &[RowData]) -> anyhow::Result<()> {
+        if let Some(sql_hook) = &self.sql_hook {
+            sql_hook.lock().await.before_rows(data).await?;
+        }
+        Ok(())
+    }
+
+    async fn sink_rows(&mut self, data: &mut [RowData], batch: bool) -> anyhow::Result<()> {
+        // rows sunk before a failure are rolled back with the open transaction, which is
+        // extracted again after the restart, so no retries here
+        if self.trx.is_some() {
+            return self.serial_sink(data).await;
+        }
+
+        let replace = self.replace;
+        let mut batch_size = self.base_sinker.batch_size_for(data, self.batch_size);
+        let mut serial_size = data.len();
+        let mut retries = 0;
+        let mut tripped = false;
+        self.check_conn().await?;
+        let result = loop {
+            let attempt_at = Instant::now();
+            match self
+                .sink_rows_once(data, batch, batch_size, serial_size)
+                .await
+            {
+                Err(error)
+                    if retries < self.lock_retry.max_retries
+                        && LockRetry::is_lock_conflict(&error) =>
+                {
+                    retries += 1;
+                    batch_size = cmp::max(batch_size / 2, 1);
+                    serial_size = cmp::max(serial_size / 2, 1);
+                    let backoff = self.lock_retry.backoff(retries);
+                    log_warn!(
+                        "mysql sinker lock conflict, retry: {} in {} ms with batch_size: {}, error: {}",
+                        retries,
+                        backoff.as_millis(),
+                        cmp::min(batch_size, serial_size),
+                        error
+                    );
+                    // rows sunk before the failure are sunk again, replace keeps inserts idempotent
+                    self.replace = true;
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(error)
+                    if self.conn_health.is_enabled() && ConnHealth::is_connection_error(&error) =>
+                {
+                    self.reconnect(error).await?;
+                    // rows may be committed before the connection was lost
+                    self.replace = true;
                 }
-                RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
+                Err(error) => {
+                    let Some(circuit_breaker) = &self.circuit_breaker else {
+                        break Err(error);
+                    };
+                    // the rows are kept and sunk again once the target recovers
+                    CircuitBreaker::wait_to_retry(circuit_breaker, error, attempt_at.into_std())
+                        .await?;
+                    tripped = true;
+                    self.replace = true;
                 }
-                _ => self.serial_sink(data).await?,
+                Ok(()) => break Ok(()),
+            }
+        };
+        self.replace = replace;
+        if result.is_ok() {
+            self.conn_health.mark_active();
+            if let Some(circuit_breaker) = self.circuit_breaker.as_ref().filter(|_| tripped) {
+                circuit_breaker.lock().await.close();
+            }
+        }
+        result
+    }
+
+    async fn check_conn(&mut self) -> anyhow::Result<()> {
+        if !self.conn_health.needs_check() {
+            return Ok(());
+        }
+        if let Err(error) = Self::ping(self.conn_pool.clone()).await {
+            self.reconnect(error).await?;
+        }
+        self.conn_health.mark_active();
+        Ok(())
+    }
+
+    async fn reconnect(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        // broken connections are dropped by the pool when acquired, a successful ping
+        // means new connections to the target can be established
+        let conn_pool = self.conn_pool.clone();
+        self.conn_health
+            .recover("mysql", error, || Self::ping(conn_pool.clone()))
+            .await
+    }
+
+    async fn ping(conn_pool: Pool<MySql>) -> anyhow::Result<()> {
+        sqlx::query("SELECT 1").execute(&conn_pool).await?;
+        Ok(())
+    }
+
+    async fn sink_rows_once(
+        &mut self,
+        data: &mut [RowData],
+        batch: bool,
+        batch_size: usize,
+        serial_size: usize,
+    ) -> anyhow::Result<()> {
+        if !batch || !matches!(data[0].row_type, RowType::Insert | RowType::Delete) {
+            // one transaction for all rows unless retrying
+            for sub_data in data.chunks(serial_size) {
+                self.serial_sink(sub_data).await?;
             }
+        } else if data[0].row_type == RowType::Insert {
+            call_batch_fn!(self, data, Self::batch_insert, batch_size);
+        } else {
+            call_batch_fn!(self, data, Self::batch_delete, batch_size);
         }
         Ok(())
     }
-}
 
-impl MysqlSinker {
     async fn serial_sink(&mut self, data: &[RowData]) -> anyhow::Result<()> {
         let task_id = self.base_sinker.source_task_id_for_rows(data, &self.router);
         self.base_sinker.ensure_monitor_for(&task_id);
         let monitor_interval = self.base_sinker.monitor_interval_secs();
         let mut last_monitor_time = Instant::now();
-        let mut tx = self.conn_pool.begin().await?;
-        if let Some(sql) = self.get_data_marker_sql().await {
-            sqlx::query(&sql)
-                .execute(&mut *tx)
-                .await
-                .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
-        }
+        let in_trx = self.trx.is_some();
+        let mut tx = match self.trx.take() {
+            Some(tx) => tx,
+            None => {
+                let mut tx = self.conn_pool.begin().await?;
+                if let Some(sql) = self.get_data_marker_sql().await {
+                    sqlx::query(&sql)
+                        .execute(&mut *tx)
+                        .await
+                        .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+                }
+                tx
+            }
+        };
 
         let mut data_len = 0;
         let mut data_size = 0;
@@ -230,7 +416,16 @@ impl MysqlSinker {
             let query = query_builder.create_mysql_query(&query_info)?;
 
             let start_time = Instant::now();
-            query.execute(&mut *tx).await.with_context(|| {
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                &row_data.schema,
+                &row_data.tb,
+                query.execute(&mut *tx),
+            )
+            .await
+            .with_context(|| {
                 format!(
                     "serial sink failed, sql: [{}], row_data: [{}]",
                     query_info.sql, row_data
@@ -251,7 +446,11 @@ impl MysqlSinker {
                 last_monitor_time = Instant::now();
             }
         }
-        tx.commit().await?;
+        if in_trx {
+            self.trx = Some(tx);
+        } else {
+            tx.commit().await?;
+        }
 
         if data_len > 0 || data_size > 0 {
             self.base_sinker
@@ -286,13 +485,30 @@ impl MysqlSinker {
 
         let start_time = Instant::now();
         let mut rts = LimitedQueue::new(1);
+        let (schema, tb) = (&data[start_index].schema, &data[start_index].tb);
         if let Some(sql) = self.get_data_marker_sql().await {
             let mut tx = self.conn_pool.begin().await?;
             sqlx::query(&sql).execute(&mut *tx).await?;
-            query.execute(&mut *tx).await?;
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&mut *tx),
+            )
+            .await?;
             tx.commit().await?;
         } else {
-            query.execute(&self.conn_pool).await?;
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&self.conn_pool),
+            )
+            .await?;
         }
         rts.push((start_time.elapsed().as_millis() as u64, 1));
 
@@ -317,31 +533,74 @@ impl MysqlSinker {
             .get_tb_meta_by_row_data(&data[0])
             .await?
             .to_owned();
-        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
 
+        if let Some(load_data) = self.load_data.as_ref().filter(|i| i.is_enabled()) {
+            let sub_data = &data[start_index..start_index + batch_size];
+            let start_time = Instant::now();
+            let data_marker_sql = self.get_data_marker_sql().await;
+            if load_data
+                .load(
+                    &self.conn_pool,
+                    &tb_meta,
+                    sub_data,
+                    self.replace,
+                    data_marker_sql,
+                )
+                .await
+            {
+                let mut rts = LimitedQueue::new(1);
+                rts.push((start_time.elapsed().as_millis() as u64, 1));
+                let data_size: usize = sub_data.iter().map(|i| i.data_size).sum();
+                self.base_sinker
+                    .update_monitor_rt_for(&task_id, &rts)
+                    .await?;
+                return self
+                    .base_sinker
+                    .update_batch_monitor_for(&task_id, batch_size as u64, data_size as u64)
+                    .await;
+            }
+        }
+
+        let query_builder = RdbQueryBuilder::new_for_mysql(&tb_meta, None);
         let (query_info, data_size) =
             query_builder.get_batch_insert_query(data, start_index, batch_size, self.replace)?;
         let query = query_builder.create_mysql_query(&query_info)?;
 
         let start_time = Instant::now();
         let mut rts = LimitedQueue::new(1);
+        let (schema, tb) = (&tb_meta.basic.schema, &tb_meta.basic.tb);
         let exec_error = if let Some(sql) = self.get_data_marker_sql().await {
             let mut tx = self.conn_pool.begin().await?;
             sqlx::query(&sql).execute(&mut *tx).await?;
-            query.execute(&mut *tx).await?;
-            match tx.commit().await {
-                Err(e) => Some(e),
-                _ => None,
-            }
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&mut *tx),
+            )
+            .await?;
+            tx.commit().await.err().map(anyhow::Error::from)
         } else {
-            match query.execute(&self.conn_pool).await {
-                Err(e) => Some(e),
-                _ => None,
-            }
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&self.conn_pool),
+            )
+            .await
+            .err()
         };
         rts.push((start_time.elapsed().as_millis() as u64, 1));
 
         if let Some(error) = exec_error {
+            // rows of a timed out batch are likely to time out one by one as well
+            if QueryTimeout::is_timeout_error(&error) {
+                return Err(error);
+            }
             log_error!(
                 "batch insert failed, will insert one by one, schema: {}, tb: {}, error: {}",
                 tb_meta.basic.schema,
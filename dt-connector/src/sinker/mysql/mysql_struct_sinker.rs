@@ -10,7 +10,8 @@ use crate::{
     Sinker,
 };
 use dt_common::{
-    config::config_enums::ConflictPolicyEnum, meta::struct_meta::struct_data::StructData,
+    config::config_enums::ConflictPolicyEnum,
+    meta::struct_meta::{pg_to_mysql_converter::PgToMysqlConverter, struct_data::StructData},
     rdb_filter::RdbFilter,
 };
 
@@ -18,17 +19,29 @@ use dt_common::{
 pub struct MysqlStructSinker {
     pub conn_pool: Pool<MySql>,
     pub conflict_policy: ConflictPolicyEnum,
+    // for views, triggers and routines
+    pub object_conflict_policy: ConflictPolicyEnum,
     pub filter: RdbFilter,
     pub router: Option<RdbRouter>,
     pub base_sinker: BaseSinker,
+    // converts structures of pg sources, mysql structures are kept as is
+    pub pg_to_mysql: PgToMysqlConverter,
 }
 
 #[async_trait]
 impl Sinker for MysqlStructSinker {
     async fn sink_struct(&mut self, data: Vec<StructData>) -> anyhow::Result<()> {
+        let data = data
+            .into_iter()
+            .map(|i| StructData {
+                schema: i.schema,
+                statement: self.pg_to_mysql.convert(i.statement),
+            })
+            .collect();
         BaseStructSinker::sink_structs(
             &DBConnPool::MySQL(self.conn_pool.clone()),
             &self.conflict_policy,
+            &self.object_conflict_policy,
             data,
             &self.filter,
             &self.base_sinker,
@@ -0,0 +1,556 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{header, Client, Method, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio::time::Instant;
+
+use dt_common::{
+    error::Error,
+    log_info, log_warn,
+    meta::{
+        col_value::ColValue, rdb_meta_manager::RdbMetaManager, rdb_tb_meta::RdbTbMeta,
+        row_data::RowData, row_type::RowType,
+    },
+    utils::limit_queue::LimitedQueue,
+};
+
+use crate::{
+    call_batch_fn,
+    sinker::{base_sinker::BaseSinker, http_sinker::HttpSinker},
+    Sinker,
+};
+
+// formats of date / datetime / timestamp values extracted from mysql / pg
+const DATE_FORMATS: &str = "yyyy-MM-dd HH:mm:ss.SSSSSSSSS||yyyy-MM-dd HH:mm:ss.SSSSSS||yyyy-MM-dd HH:mm:ss.SSS||yyyy-MM-dd HH:mm:ss||yyyy-MM-dd||strict_date_optional_time||epoch_millis";
+const ALREADY_EXISTS_ERROR: &str = "resource_already_exists_exception";
+
+/// Writes rows as documents into OpenSearch / Elasticsearch indices by the bulk api, with the
+/// primary / unique key values of rows as _id:
+/// - insert: the document is indexed, replacing the existing one
+/// - update: the document is updated by the changed columns, and created if missing
+/// - delete: the document is deleted
+///
+/// items rejected with 429 are retried with backoff, other rejected items fail the task.
+pub struct OpenSearchSinker {
+    // without username and password
+    pub url: String,
+    pub username: String,
+    pub password: Option<String>,
+    // with the configured timeout
+    pub http_client: Client,
+    pub batch_size: usize,
+    // keyed by (schema, tb), tables not listed are written to {schema}_{tb} in lower case
+    pub indices: HashMap<(String, String), String>,
+    // create missing indices with mappings generated from source table structures
+    pub create_index: bool,
+    pub max_retries: u32,
+    pub retry_interval_ms: u64,
+    // of the source, for key columns and column types
+    pub meta_manager: RdbMetaManager,
+    // indices checked or created by this sinker
+    pub ensured_indices: HashSet<String>,
+    pub base_sinker: BaseSinker,
+}
+
+#[derive(Debug)]
+struct BulkAction {
+    // action and metadata line, e.g. {"index":{"_index":"db1_tb1","_id":"1"}}
+    meta: String,
+    // document line, None for deletes
+    source: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BulkResponse {
+    errors: bool,
+    #[serde(default)]
+    items: Vec<HashMap<String, BulkItem>>,
+}
+
+#[derive(Deserialize)]
+struct BulkItem {
+    status: u16,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[async_trait]
+impl Sinker for OpenSearchSinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        call_batch_fn!(self, data, Self::send_batch);
+        Ok(())
+    }
+}
+
+impl OpenSearchSinker {
+    async fn send_batch(
+        &mut self,
+        data: &mut [RowData],
+        sinked_count: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let rows = &mut data[sinked_count..sinked_count + batch_size];
+        let task_id = self.base_sinker.task_id_for_rows(rows);
+        self.base_sinker.ensure_monitor_for(&task_id);
+
+        let mut data_size = 0;
+        let mut actions = Vec::with_capacity(batch_size);
+        for row_data in rows.iter_mut() {
+            data_size += row_data.get_data_size();
+            row_data.convert_raw_string();
+            let index = self.get_index(&row_data.schema, &row_data.tb);
+            if self.create_index && !self.ensured_indices.contains(&index) {
+                self.ensure_index(&index, &row_data.schema, &row_data.tb)
+                    .await?;
+            }
+            let tb_meta = self
+                .meta_manager
+                .get_tb_meta(&row_data.schema, &row_data.tb)
+                .await?;
+            actions.extend(Self::build_actions(&index, tb_meta, row_data)?);
+        }
+
+        let start_time = Instant::now();
+        let mut rts = LimitedQueue::new(1);
+        self.bulk(actions).await?;
+        rts.push((start_time.elapsed().as_millis() as u64, batch_size as u64));
+
+        self.base_sinker
+            .update_batch_monitor_for(&task_id, batch_size as u64, data_size)
+            .await?;
+        self.base_sinker.update_monitor_rt_for(&task_id, &rts).await
+    }
+
+    fn get_index(&self, schema: &str, tb: &str) -> String {
+        self.indices
+            .get(&(schema.to_string(), tb.to_string()))
+            .cloned()
+            .unwrap_or_else(|| format!("{}_{}", schema, tb).to_lowercase())
+    }
+
+    fn build_actions(
+        index: &str,
+        tb_meta: &RdbTbMeta,
+        row_data: &RowData,
+    ) -> anyhow::Result<Vec<BulkAction>> {
+        let action = |op: &str, id: &str, source: Option<Value>| BulkAction {
+            meta: json!({ op: { "_index": index, "_id": id } }).to_string(),
+            source: source.map(|source| source.to_string()),
+        };
+
+        let actions = match row_data.row_type {
+            RowType::Insert => {
+                let after = row_data.require_after()?;
+                let id = Self::doc_id(tb_meta, after)?;
+                vec![action("index", &id, Some(Self::doc(after)))]
+            }
+
+            RowType::Update => {
+                let after = row_data.require_after()?;
+                let id = Self::doc_id(tb_meta, after)?;
+                let mut actions = Vec::new();
+                // the key is changed, before images without keys are taken as unchanged
+                if let Some(before) = &row_data.before {
+                    if let Ok(before_id) = Self::doc_id(tb_meta, before) {
+                        if before_id != id {
+                            actions.push(action("delete", &before_id, None));
+                        }
+                    }
+                }
+                let source = json!({ "doc": Self::doc(after), "doc_as_upsert": true });
+                actions.push(action("update", &id, Some(source)));
+                actions
+            }
+
+            RowType::Delete => {
+                let before = row_data.require_before()?;
+                let id = Self::doc_id(tb_meta, before)?;
+                vec![action("delete", &id, None)]
+            }
+        };
+        Ok(actions)
+    }
+
+    // values of the primary / unique key joined by ':' in key column order
+    fn doc_id(
+        tb_meta: &RdbTbMeta,
+        col_values: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<String> {
+        if tb_meta.order_cols.is_empty() {
+            bail! {Error::SinkerError(format!(
+                "opensearch sinker requires a primary / unique key, table: {}.{}",
+                tb_meta.schema, tb_meta.tb
+            ))}
+        }
+
+        let mut values = Vec::with_capacity(tb_meta.order_cols.len());
+        for col in tb_meta.order_cols.iter() {
+            let Some(value) = col_values.get(col).and_then(|v| v.to_option_string()) else {
+                bail! {Error::SinkerError(format!(
+                    "key column: {} is missing or NULL, table: {}.{}",
+                    col, tb_meta.schema, tb_meta.tb
+                ))}
+            };
+            values.push(value);
+        }
+        Ok(values.join(":"))
+    }
+
+    fn doc(col_values: &HashMap<String, ColValue>) -> Value {
+        let mut doc = Map::with_capacity(col_values.len());
+        for (col, col_value) in col_values {
+            let value = match col_value {
+                // pg toast columns not changed by the update are left as they are
+                ColValue::UnchangedToast => continue,
+                ColValue::Blob(v) => Value::String(STANDARD.encode(v)),
+                ColValue::Json(v) => serde_json::from_slice(v)
+                    .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(v).into())),
+                ColValue::Json2(v) => {
+                    serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.clone()))
+                }
+                _ => serde_json::to_value(col_value).unwrap_or(Value::Null),
+            };
+            doc.insert(col.clone(), value);
+        }
+        Value::Object(doc)
+    }
+
+    async fn ensure_index(&mut self, index: &str, schema: &str, tb: &str) -> anyhow::Result<()> {
+        let url = format!("{}/{}", self.url, index);
+        let response = self.request(Method::HEAD, &url).send().await?;
+        match response.status() {
+            StatusCode::OK => {}
+
+            StatusCode::NOT_FOUND => {
+                let tb_meta = self.meta_manager.get_tb_meta(schema, tb).await?;
+                let body = Self::index_mappings(tb_meta);
+                let response = self
+                    .request(Method::PUT, &url)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(body.to_string())
+                    .send()
+                    .await?;
+                let status_code = response.status();
+                let response_text = response.text().await.unwrap_or_default();
+                // created by another sinker meanwhile
+                if !status_code.is_success() && !response_text.contains(ALREADY_EXISTS_ERROR) {
+                    bail! {Error::HttpError(format!(
+                        "failed to create index: {}, status_code: {}, response_text: {:?}",
+                        index, status_code, response_text
+                    ))}
+                }
+                log_info!("index: {} created for table: {}.{}", index, schema, tb);
+            }
+
+            status_code => {
+                bail! {Error::HttpError(format!(
+                    "failed to check index: {}, status_code: {}",
+                    index, status_code
+                ))}
+            }
+        }
+
+        self.ensured_indices.insert(index.to_string());
+        Ok(())
+    }
+
+    // columns of types not mapped are left to dynamic mapping
+    fn index_mappings(tb_meta: &RdbTbMeta) -> Value {
+        let mut properties = Map::new();
+        for col in tb_meta.cols.iter() {
+            let Some(origin_type) = tb_meta.col_origin_type_map.get(col) else {
+                continue;
+            };
+            if let Some(field) = Self::field_mapping(origin_type) {
+                properties.insert(col.clone(), field);
+            }
+        }
+        json!({ "mappings": { "properties": properties } })
+    }
+
+    fn field_mapping(origin_type: &str) -> Option<Value> {
+        let origin_type = origin_type.to_lowercase();
+        // pg arrays
+        if origin_type.starts_with('_') || origin_type.ends_with("[]") {
+            return None;
+        }
+
+        let base_type = origin_type.split(['(', ' ']).next().unwrap_or_default();
+        let field_type = match base_type {
+            "tinyint" if origin_type.starts_with("tinyint(1)") => "boolean",
+            "bool" | "boolean" => "boolean",
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2"
+            | "int4" | "int8" | "smallserial" | "serial" | "bigserial" | "year" | "bit" => "long",
+            "float" | "double" | "real" | "float4" | "float8" | "decimal" | "numeric" => "double",
+            // zero dates of mysql, e.g. 0000-00-00, are kept in _source without being indexed
+            "date" | "datetime" | "timestamp" => {
+                return Some(json!({
+                    "type": "date",
+                    "format": DATE_FORMATS,
+                    "ignore_malformed": true
+                }));
+            }
+            "char" | "varchar" | "bpchar" | "character" | "tinytext" | "text" | "mediumtext"
+            | "longtext" | "citext" | "name" | "uuid" => {
+                return Some(json!({
+                    "type": "text",
+                    "fields": { "keyword": { "type": "keyword", "ignore_above": 256 } }
+                }));
+            }
+            "enum" | "set" => "keyword",
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bytea" => {
+                "binary"
+            }
+            _ => return None,
+        };
+        Some(json!({ "type": field_type }))
+    }
+
+    async fn bulk(&self, mut actions: Vec<BulkAction>) -> anyhow::Result<()> {
+        let url = format!("{}/_bulk", self.url);
+        let mut retries = 0;
+        loop {
+            let result = self
+                .request(Method::POST, &url)
+                .header(header::CONTENT_TYPE, "application/x-ndjson")
+                .body(Self::bulk_body(&actions))
+                .send()
+                .await;
+
+            let err = match result {
+                Ok(response) if response.status().is_success() => {
+                    let bulk_response: BulkResponse = response.json().await?;
+                    if !bulk_response.errors {
+                        return Ok(());
+                    }
+                    let retry_count;
+                    (actions, retry_count) = Self::rejected_actions(actions, bulk_response)?;
+                    if actions.is_empty() {
+                        return Ok(());
+                    }
+                    format!("{} item(s) rejected with status_code: 429", retry_count)
+                }
+
+                Ok(response) => {
+                    let status_code = response.status();
+                    let response_text = response.text().await.unwrap_or_default();
+                    let err = format!(
+                        "status_code: {}, response_text: {:?}",
+                        status_code, response_text
+                    );
+                    if !HttpSinker::is_retryable(status_code) {
+                        bail! {Error::HttpError(format!("opensearch bulk request failed, {}", err))}
+                    }
+                    err
+                }
+
+                // connection errors and timeouts
+                Err(err) => err.to_string(),
+            };
+
+            if retries >= self.max_retries {
+                bail! {Error::HttpError(format!(
+                    "opensearch bulk request failed, retries: {}, {}",
+                    retries, err
+                ))}
+            }
+            let backoff_millis = HttpSinker::backoff_millis(self.retry_interval_ms, retries);
+            log_warn!(
+                "opensearch bulk request failed, retry in {} ms, {}",
+                backoff_millis,
+                err
+            );
+            tokio::time::sleep(Duration::from_millis(backoff_millis)).await;
+            retries += 1;
+        }
+    }
+
+    // actions to retry, i.e. rejected with 429, fails if any item is rejected otherwise
+    fn rejected_actions(
+        actions: Vec<BulkAction>,
+        bulk_response: BulkResponse,
+    ) -> anyhow::Result<(Vec<BulkAction>, usize)> {
+        let mut rejected = Vec::new();
+        for (action, item) in actions.into_iter().zip(bulk_response.items) {
+            let Some(item) = item.into_values().next() else {
+                continue;
+            };
+            let Some(error) = item.error else {
+                continue;
+            };
+            if item.status != StatusCode::TOO_MANY_REQUESTS.as_u16() {
+                bail! {Error::SinkerError(format!(
+                    "opensearch bulk item failed, action: {}, status_code: {}, error: {}",
+                    action.meta, item.status, error
+                ))}
+            }
+            rejected.push(action);
+        }
+        let count = rejected.len();
+        Ok((rejected, count))
+    }
+
+    fn bulk_body(actions: &[BulkAction]) -> String {
+        let mut body = String::new();
+        for action in actions {
+            body.push_str(&action.meta);
+            body.push('\n');
+            if let Some(source) = &action.source {
+                body.push_str(source);
+                body.push('\n');
+            }
+        }
+        body
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self.http_client.request(method, url);
+        if self.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.username, self.password.as_ref())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tb_meta() -> RdbTbMeta {
+        RdbTbMeta {
+            schema: "db1".into(),
+            tb: "tb1".into(),
+            cols: vec!["id".into(), "name".into(), "created_at".into()],
+            col_origin_type_map: HashMap::from([
+                ("id".into(), "int(11)".into()),
+                ("name".into(), "varchar(255)".into()),
+                ("created_at".into(), "datetime(3)".into()),
+            ]),
+            order_cols: vec!["id".into()],
+            ..Default::default()
+        }
+    }
+
+    fn col_values(id: i32, name: &str) -> HashMap<String, ColValue> {
+        HashMap::from([
+            ("id".to_string(), ColValue::Long(id)),
+            ("name".to_string(), ColValue::String(name.into())),
+        ])
+    }
+
+    fn meta(action: &BulkAction) -> Value {
+        serde_json::from_str(&action.meta).unwrap()
+    }
+
+    #[test]
+    fn test_build_actions() {
+        let tb_meta = tb_meta();
+        let insert = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(col_values(1, "a")),
+        );
+        let actions = OpenSearchSinker::build_actions("db1_tb1", &tb_meta, &insert).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            meta(&actions[0]),
+            json!({"index": {"_index": "db1_tb1", "_id": "1"}})
+        );
+
+        // the key is changed
+        let update = RowData::new(
+            "db1".into(),
+            "tb1".into(),
+            0,
+            RowType::Update,
+            Some(col_values(1, "a")),
+            Some(col_values(2, "a")),
+        );
+        let actions = OpenSearchSinker::build_actions("db1_tb1", &tb_meta, &update).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(
+            meta(&actions[0]),
+            json!({"delete": {"_index": "db1_tb1", "_id": "1"}})
+        );
+        assert!(actions[0].source.is_none());
+        assert_eq!(
+            meta(&actions[1]),
+            json!({"update": {"_index": "db1_tb1", "_id": "2"}})
+        );
+        let source: Value = serde_json::from_str(actions[1].source.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            source,
+            json!({"doc": {"id": 2, "name": "a"}, "doc_as_upsert": true})
+        );
+
+        let mut no_key = tb_meta.clone();
+        no_key.order_cols.clear();
+        assert!(OpenSearchSinker::build_actions("db1_tb1", &no_key, &insert).is_err());
+    }
+
+    #[test]
+    fn test_index_mappings() {
+        let mappings = OpenSearchSinker::index_mappings(&tb_meta());
+        let properties = &mappings["mappings"]["properties"];
+        assert_eq!(properties["id"], json!({"type": "long"}));
+        assert_eq!(properties["name"]["type"], "text");
+        assert_eq!(properties["created_at"]["type"], "date");
+
+        assert_eq!(
+            OpenSearchSinker::field_mapping("tinyint(1)"),
+            Some(json!({"type": "boolean"}))
+        );
+        assert_eq!(
+            OpenSearchSinker::field_mapping("bytea"),
+            Some(json!({"type": "binary"}))
+        );
+        assert_eq!(OpenSearchSinker::field_mapping("_int4"), None);
+        assert_eq!(OpenSearchSinker::field_mapping("json"), None);
+    }
+
+    #[test]
+    fn test_rejected_actions() {
+        let actions = vec![
+            BulkAction {
+                meta: "a".into(),
+                source: None,
+            },
+            BulkAction {
+                meta: "b".into(),
+                source: None,
+            },
+        ];
+        let response: BulkResponse = serde_json::from_str(
+            r#"{"errors":true,"items":[{"delete":{"status":404}},{"index":{"status":429,"error":{"type":"es_rejected_execution_exception"}}}]}"#,
+        )
+        .unwrap();
+        let (rejected, count) = OpenSearchSinker::rejected_actions(actions, response).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(rejected[0].meta, "b");
+
+        let actions = vec![BulkAction {
+            meta: "a".into(),
+            source: None,
+        }];
+        let response: BulkResponse = serde_json::from_str(
+            r#"{"errors":true,"items":[{"index":{"status":400,"error":{"type":"mapper_parsing_exception"}}}]}"#,
+        )
+        .unwrap();
+        assert!(OpenSearchSinker::rejected_actions(actions, response).is_err());
+    }
+}
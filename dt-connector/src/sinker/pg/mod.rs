@@ -1,2 +1,4 @@
+pub mod pg_large_object;
+pub mod pg_pipeline;
 pub mod pg_sinker;
 pub mod pg_struct_sinker;
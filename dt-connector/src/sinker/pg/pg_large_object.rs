@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+
+use sqlx::{postgres::types::Oid, Pool, Postgres, Row};
+
+use dt_common::{
+    log_info, log_warn,
+    meta::{col_value::ColValue, pg::pg_tb_meta::PgTbMeta, row_data::RowData, row_type::RowType},
+};
+
+// bytes read from the source and written to the target per statement
+const LO_CHUNK_SIZE: i32 = 1024 * 1024;
+// oids known to exist on the target, cleared once exceeded
+const MAX_COPIED_OIDS: usize = 100_000;
+// duplicate_object, the large object was created by another sinker meanwhile
+const DUPLICATE_OBJECT_CODE: &str = "42710";
+
+/// Copies large objects referenced by oid / lo columns of rows from the source to the target
+/// before the rows are written, keeping their oids, so references stay valid on the target.
+#[derive(Clone)]
+pub struct PgLargeObjectCopier {
+    pub src_conn_pool: Pool<Postgres>,
+    pub copied_oids: HashSet<u32>,
+}
+
+impl PgLargeObjectCopier {
+    pub fn new(src_conn_pool: Pool<Postgres>) -> Self {
+        Self {
+            src_conn_pool,
+            copied_oids: HashSet::new(),
+        }
+    }
+
+    pub async fn copy_for_rows(
+        &mut self,
+        dst_conn_pool: &Pool<Postgres>,
+        tb_meta: &PgTbMeta,
+        data: &[RowData],
+    ) -> anyhow::Result<()> {
+        let lo_cols = Self::get_lo_cols(tb_meta);
+        if lo_cols.is_empty() {
+            return Ok(());
+        }
+
+        for row_data in data.iter() {
+            // large objects are not removed with rows, clean up the target by vacuumlo if needed
+            if row_data.row_type == RowType::Delete {
+                continue;
+            }
+            let Some(after) = &row_data.after else {
+                continue;
+            };
+            for col in lo_cols.iter() {
+                if let Some(oid) = after.get(*col).and_then(Self::parse_oid) {
+                    self.copy(dst_conn_pool, oid).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn copy(&mut self, dst_conn_pool: &Pool<Postgres>, oid: u32) -> anyhow::Result<()> {
+        if self.copied_oids.contains(&oid) {
+            return Ok(());
+        }
+        if self.copied_oids.len() >= MAX_COPIED_OIDS {
+            self.copied_oids.clear();
+        }
+
+        if Self::exists(dst_conn_pool, oid).await? {
+            self.copied_oids.insert(oid);
+            return Ok(());
+        }
+        if !Self::exists(&self.src_conn_pool, oid).await? {
+            log_warn!("large object: {} not found in source, skip copying it", oid);
+            return Ok(());
+        }
+
+        // created and written in one transaction, other sinkers copying the same oid
+        // wait for it and then fail with duplicate_object
+        let mut tx = dst_conn_pool.begin().await?;
+        let created = sqlx::query("SELECT lo_create($1)")
+            .bind(Oid(oid))
+            .execute(&mut *tx)
+            .await;
+        if let Err(sqlx::Error::Database(e)) = &created {
+            if e.code().as_deref() == Some(DUPLICATE_OBJECT_CODE) {
+                self.copied_oids.insert(oid);
+                return Ok(());
+            }
+        }
+        created?;
+
+        let mut offset: i64 = 0;
+        loop {
+            let chunk: Vec<u8> = sqlx::query("SELECT lo_get($1, $2, $3)")
+                .bind(Oid(oid))
+                .bind(offset)
+                .bind(LO_CHUNK_SIZE)
+                .fetch_one(&self.src_conn_pool)
+                .await?
+                .try_get(0)?;
+            if chunk.is_empty() {
+                break;
+            }
+            sqlx::query("SELECT lo_put($1, $2, $3)")
+                .bind(Oid(oid))
+                .bind(offset)
+                .bind(&chunk)
+                .execute(&mut *tx)
+                .await?;
+            offset += chunk.len() as i64;
+            if chunk.len() < LO_CHUNK_SIZE as usize {
+                break;
+            }
+        }
+        tx.commit().await?;
+
+        log_info!("large object: {} copied, size: {}", oid, offset);
+        self.copied_oids.insert(oid);
+        Ok(())
+    }
+
+    async fn exists(conn_pool: &Pool<Postgres>, oid: u32) -> anyhow::Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM pg_largeobject_metadata WHERE oid = $1")
+            .bind(Oid(oid))
+            .fetch_optional(conn_pool)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    // columns of type oid, or lo of the lo extension
+    fn get_lo_cols(tb_meta: &PgTbMeta) -> Vec<&String> {
+        tb_meta
+            .basic
+            .cols
+            .iter()
+            .filter(|col| {
+                tb_meta
+                    .col_type_map
+                    .get(*col)
+                    .is_some_and(|col_type| matches!(col_type.alias.as_str(), "oid" | "lo"))
+            })
+            .collect()
+    }
+
+    fn parse_oid(col_value: &ColValue) -> Option<u32> {
+        col_value
+            .to_option_string()
+            .and_then(|v| v.parse::<u32>().ok())
+            // InvalidOid
+            .filter(|oid| *oid != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dt_common::meta::{
+        pg::{pg_col_type::PgColType, pg_value_type::PgValueType},
+        rdb_tb_meta::RdbTbMeta,
+    };
+
+    use super::*;
+
+    fn col_type(alias: &str) -> PgColType {
+        PgColType {
+            value_type: PgValueType::Int64,
+            name: alias.into(),
+            alias: alias.into(),
+            oid: 0,
+            parent_oid: 0,
+            element_oid: 0,
+            category: String::new(),
+            enum_values: None,
+            schema_name: String::new(),
+            typmod: 0,
+        }
+    }
+
+    #[test]
+    fn test_get_lo_cols() {
+        let tb_meta = PgTbMeta {
+            basic: RdbTbMeta {
+                cols: vec!["id".into(), "doc".into(), "image".into()],
+                ..Default::default()
+            },
+            oid: 0,
+            col_type_map: HashMap::from([
+                ("id".to_string(), col_type("int8")),
+                ("doc".to_string(), col_type("oid")),
+                ("image".to_string(), col_type("lo")),
+            ]),
+        };
+        assert_eq!(
+            PgLargeObjectCopier::get_lo_cols(&tb_meta),
+            vec!["doc", "image"]
+        );
+    }
+
+    #[test]
+    fn test_parse_oid() {
+        assert_eq!(
+            PgLargeObjectCopier::parse_oid(&ColValue::LongLong(16400)),
+            Some(16400)
+        );
+        assert_eq!(
+            PgLargeObjectCopier::parse_oid(&ColValue::String("16401".into())),
+            Some(16401)
+        );
+        assert_eq!(PgLargeObjectCopier::parse_oid(&ColValue::LongLong(0)), None);
+        assert_eq!(PgLargeObjectCopier::parse_oid(&ColValue::None), None);
+    }
+}
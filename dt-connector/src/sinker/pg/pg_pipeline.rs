@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use futures::future::try_join_all;
+use postgres_types::{ToSql, Type};
+use tokio::time::Instant;
+use tokio_postgres::{Client, NoTls, Statement};
+
+use crate::extractor::pg::pg_cdc_client::PgCdcClient;
+use dt_common::{
+    config::{connection_auth_config::ConnectionAuthConfig, ssl_config::SslMode},
+    log_info, log_warn,
+    meta::col_value::ColValue,
+};
+
+/// A statement and its params in text format, the sql casts each placeholder
+/// to the column type, e.g. $1::int4, so all params are sent as text.
+pub struct PipelineQuery {
+    pub sql: String,
+    pub params: Vec<Option<String>>,
+}
+
+impl PipelineQuery {
+    pub fn new(sql: String, binds: &[Option<&ColValue>]) -> Self {
+        let params = binds.iter().map(|v| Self::to_text_param(*v)).collect();
+        Self { sql, params }
+    }
+
+    fn to_text_param(col_value: Option<&ColValue>) -> Option<String> {
+        match col_value? {
+            ColValue::None | ColValue::UnchangedToast => None,
+            ColValue::Blob(v) => Some(format!(r#"\x{}"#, hex::encode(v))),
+            ColValue::RawString(v) | ColValue::Json(v) => {
+                Some(String::from_utf8_lossy(v).to_string())
+            }
+            v => v.to_option_string(),
+        }
+    }
+}
+
+/// Sends statements of a transaction in extended-protocol pipelines: up to `pipeline_size`
+/// statements are queued before waiting for their results, instead of one round trip per row.
+pub struct PgPipeline {
+    client: Client,
+    pipeline_size: usize,
+    // prepared statements of this connection, keyed by sql which is decided by
+    // table, row type and the set of columns
+    statements: HashMap<String, Statement>,
+    statement_cache_capacity: usize,
+    disable_foreign_key_checks: bool,
+}
+
+impl PgPipeline {
+    pub async fn connect(
+        url: &str,
+        connection_auth: &ConnectionAuthConfig,
+        pipeline_size: usize,
+        statement_cache_capacity: usize,
+        disable_foreign_key_checks: bool,
+    ) -> anyhow::Result<Self> {
+        let (config, ssl_config) = PgCdcClient::build_config(url, connection_auth)?;
+        let client = match ssl_config.ssl_mode {
+            SslMode::Disable => {
+                let (client, connection) = config.connect(NoTls).await?;
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        log_info!("postgres pipeline connection drops, error: {}", e);
+                    }
+                });
+                client
+            }
+            _ => {
+                let connector = PgCdcClient::build_tls_connector(&ssl_config)?;
+                let (client, connection) = config.connect(connector).await?;
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        log_info!("postgres pipeline connection drops, error: {}", e);
+                    }
+                });
+                client
+            }
+        };
+
+        if disable_foreign_key_checks {
+            if let Err(e) = client
+                .batch_execute("SET session_replication_role = 'replica';")
+                .await
+            {
+                log_warn!(
+                    "Failed to disable foreign key checks for pipeline connection: {}. \
+                    Foreign key constraints will remain enabled.",
+                    e
+                );
+            }
+        }
+
+        Ok(Self {
+            client,
+            pipeline_size: pipeline_size.max(1),
+            statements: HashMap::new(),
+            statement_cache_capacity: statement_cache_capacity.max(1),
+            disable_foreign_key_checks,
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.client.is_closed()
+    }
+
+    /// replace the lost connection with a new one of the same settings,
+    /// prepared statements of the old connection are dropped
+    pub async fn reconnect(
+        &mut self,
+        url: &str,
+        connection_auth: &ConnectionAuthConfig,
+    ) -> anyhow::Result<()> {
+        *self = Self::connect(
+            url,
+            connection_auth,
+            self.pipeline_size,
+            self.statement_cache_capacity,
+            self.disable_foreign_key_checks,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// execute all queries in one transaction, returns the elapsed millis of each pipeline
+    pub async fn execute(
+        &mut self,
+        data_marker_sql: Option<String>,
+        queries: &[PipelineQuery],
+    ) -> anyhow::Result<Vec<u64>> {
+        let mut statements = Vec::with_capacity(queries.len());
+        for query in queries.iter() {
+            statements.push(self.prepare(&query.sql, query.params.len()).await?);
+        }
+
+        let mut rts = Vec::new();
+        let tx = self.client.transaction().await?;
+        if let Some(sql) = data_marker_sql {
+            tx.batch_execute(&sql)
+                .await
+                .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+        }
+
+        for (chunk, chunk_statements) in queries
+            .chunks(self.pipeline_size)
+            .zip(statements.chunks(self.pipeline_size))
+        {
+            let start_time = Instant::now();
+            // futures polled together are pipelined by tokio-postgres
+            let futures = chunk
+                .iter()
+                .zip(chunk_statements)
+                .map(|(query, statement)| {
+                    let tx = &tx;
+                    async move {
+                        let params: Vec<&(dyn ToSql + Sync)> = query
+                            .params
+                            .iter()
+                            .map(|p| p as &(dyn ToSql + Sync))
+                            .collect();
+                        tx.execute(statement, &params)
+                            .await
+                            .with_context(|| format!("pipeline sink failed, sql: [{}]", query.sql))
+                    }
+                });
+            try_join_all(futures).await?;
+            rts.push(start_time.elapsed().as_millis() as u64);
+        }
+
+        tx.commit().await?;
+        Ok(rts)
+    }
+
+    async fn prepare(&mut self, sql: &str, param_count: usize) -> anyhow::Result<Statement> {
+        if let Some(statement) = self.statements.get(sql) {
+            return Ok(statement.clone());
+        }
+
+        if self.statements.len() >= self.statement_cache_capacity {
+            // statements of the same table are usually sunk together, so simply start over
+            self.statements.clear();
+        }
+
+        let statement = self
+            .client
+            .prepare_typed(sql, &vec![Type::TEXT; param_count])
+            .await
+            .with_context(|| format!("failed to prepare sql: [{}]", sql))?;
+        self.statements.insert(sql.to_string(), statement.clone());
+        Ok(statement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_query_text_params() {
+        let blob = ColValue::Blob(vec![1, 2, 255]);
+        let long = ColValue::LongLong(-3);
+        let string = ColValue::String("a'b".into());
+        let query = PipelineQuery::new(
+            "INSERT INTO t VALUES($1::bytea,$2::int8,$3::text,$4::text)".into(),
+            &[
+                Some(&blob),
+                Some(&long),
+                Some(&string),
+                Some(&ColValue::None),
+            ],
+        );
+        assert_eq!(
+            query.params,
+            vec![
+                Some(r#"\x0102ff"#.to_string()),
+                Some("-3".to_string()),
+                Some("a'b".to_string()),
+                None,
+            ]
+        );
+    }
+}
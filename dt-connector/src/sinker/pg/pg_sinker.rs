@@ -1,21 +1,40 @@
-use std::{cmp, str::FromStr, sync::Arc};
+use std::{cmp, str::FromStr, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use async_trait::async_trait;
 use sqlx::{
     postgres::{PgConnectOptions, PgPoolOptions},
-    Executor, Pool, Postgres,
+    Executor, Pool, Postgres, Transaction,
+};
+use tokio::{
+    sync::{Mutex, RwLock},
+    time::Instant,
 };
-use tokio::{sync::RwLock, time::Instant};
 
 use crate::sinker::checkable_sinker::CheckableSink;
 use crate::{
-    call_batch_fn, data_marker::DataMarker, rdb_query_builder::RdbQueryBuilder,
-    rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker,
+    call_batch_fn,
+    data_marker::DataMarker,
+    rdb_query_builder::RdbQueryBuilder,
+    rdb_router::RdbRouter,
+    sinker::{
+        base_sinker::BaseSinker,
+        circuit_breaker::{CircuitBreaker, SharedCircuitBreaker},
+        conn_health::ConnHealth,
+        ddl_guard::DdlGuard,
+        lock_retry::LockRetry,
+        pg::{
+            pg_large_object::PgLargeObjectCopier,
+            pg_pipeline::{PgPipeline, PipelineQuery},
+        },
+        sql_hook::SqlHook,
+        table_creator::TableCreator,
+    },
+    Sinker,
 };
 use dt_common::{
-    config::connection_auth_config::ConnectionAuthConfig,
-    log_error, log_info,
+    config::{config_enums::ConflictPolicyEnum, connection_auth_config::ConnectionAuthConfig},
+    log_error, log_info, log_warn,
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
         dt_data::{DtData, DtItem},
@@ -24,10 +43,12 @@ use dt_common::{
         row_data::RowData,
         row_type::RowType,
     },
-    utils::limit_queue::LimitedQueue,
+    utils::{
+        limit_queue::LimitedQueue,
+        query_timeout::{QueryTimeout, SINKER_STAGE},
+    },
 };
 
-#[derive(Clone)]
 pub struct PgSinker {
     pub url: String,
     pub connection_auth: ConnectionAuthConfig,
@@ -38,6 +59,26 @@ pub struct PgSinker {
     pub base_sinker: BaseSinker,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub replace: bool,
+    pub table_creator: Option<Arc<Mutex<TableCreator>>>,
+    // runs before_tb / after_tb hooks of snapshot tables, None if [sinker] sql_hooks has none
+    pub sql_hook: Option<Arc<Mutex<SqlHook>>>,
+    // pipelined connection for serial sink, None if [sinker] pipeline_size=1
+    pub pipeline: Option<Arc<Mutex<PgPipeline>>>,
+    pub lock_retry: LockRetry,
+    pub conn_health: ConnHealth,
+    // waits for the target to recover on tripping errors, None if [circuit_breaker] is absent
+    pub circuit_breaker: Option<SharedCircuitBreaker>,
+    // checks ddls against the target before applying, None if [sinker] ddl_guard_policy=none
+    pub ddl_guard: Option<DdlGuard>,
+    pub ddl_conflict_policy: ConflictPolicyEnum,
+    // apply consecutive transactional ddls of a batch in one transaction
+    pub ddl_transaction: bool,
+    // client side limit of each sink statement, None if [sinker] statement_timeout_secs=0
+    pub statement_timeout: Option<Duration>,
+    // copies large objects referenced by rows from the source, None if [sinker] copy_large_objects=false
+    pub large_object_copier: Option<PgLargeObjectCopier>,
+    // the target transaction opened by begin_trx, serial sinks run in it until commit_trx
+    pub trx: Option<Transaction<'static, Postgres>>,
 }
 
 #[async_trait]
@@ -46,21 +87,12 @@ impl Sinker for PgSinker {
         if data.is_empty() {
             return Ok(());
         }
-
-        if !batch {
-            self.serial_sink(&data).await?;
-        } else {
-            match data[0].row_type {
-                RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
-                }
-                RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
-                }
-                _ => self.serial_sink(&data).await?,
-            }
-        }
-        Ok(())
+        // rows of a batch share the same meta version
+        self.meta_manager.sync_meta_version(data[0].meta_version);
+        self.auto_create_tables(&data).await?;
+        self.run_before_tb_hooks(&data).await?;
+        self.copy_large_objects(&data).await?;
+        self.sink_rows(&mut data, batch).await
     }
 
     async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
@@ -68,55 +100,49 @@ impl Sinker for PgSinker {
             return Ok(());
         }
 
-        let mut rts = LimitedQueue::new(cmp::min(100, data.len()));
+        let mut ddls = Vec::new();
+        for ddl_data in data.iter() {
+            if let Some(ddl_guard) = &self.ddl_guard {
+                if !ddl_guard.check(ddl_data).await? {
+                    continue;
+                }
+            }
+            ddls.push(ddl_data);
+        }
+
+        let mut rts = LimitedQueue::new(cmp::min(100, ddls.len().max(1)));
         let monitor_interval = self.base_sinker.monitor_interval_secs();
         let mut data_size = 0;
         let mut data_len = 0;
         let mut last_monitor_time = Instant::now();
 
-        for ddl_data in data.iter() {
-            let (schema, _tb) = ddl_data.get_schema_tb();
-            data_size += ddl_data.get_data_size();
-            data_len += 1;
-
-            let final_url = ConnectionAuthConfig::merge_url_with_auth(
-                self.url.as_str(),
-                &self.connection_auth,
-            )?;
-            let mut conn_options = PgConnectOptions::from_str(final_url.as_str())?;
-            let mut pool_options = PgPoolOptions::new().max_connections(1);
-            if let Some(ssl) = self.connection_auth.ssl_config() {
-                conn_options = ssl.apply_pg(conn_options);
-            }
-
-            let sql = format!("SET search_path = '{}';", schema);
-
-            if !schema.is_empty() {
-                match ddl_data.ddl_type {
-                    DdlType::CreateSchema | DdlType::DropSchema | DdlType::AlterSchema => {}
-                    _ => {
-                        pool_options = pool_options.after_connect(move |conn, _meta| {
-                            let sql = sql.clone();
-                            Box::pin(async move {
-                                conn.execute(sql.as_str()).await?;
-                                Ok(())
-                            })
-                        });
-                    }
-                }
-            }
-
-            let sql = ddl_data.to_sql();
-            log_info!("sink ddl, schema: {}, sql: {}", schema, sql);
+        let mut i = 0;
+        while i < ddls.len() {
+            // consecutive transactional ddls are applied in one transaction
+            let group_len = if self.ddl_transaction {
+                ddls[i..]
+                    .iter()
+                    .take_while(|ddl_data| ddl_data.is_pg_transactional())
+                    .count()
+                    .max(1)
+            } else {
+                1
+            };
+            let group = &ddls[i..i + group_len];
+            i += group_len;
 
             let start_time = Instant::now();
-
-            let conn_pool = pool_options.connect_with(conn_options).await?;
-            let query = sqlx::query(&sql);
-            query.execute(&conn_pool).await?;
-
-            rts.push((start_time.elapsed().as_millis() as u64, 1));
-            conn_pool.close().await;
+            if group.len() > 1 {
+                self.sink_ddls_in_transaction(group).await?;
+            } else {
+                self.sink_single_ddl(group[0]).await?;
+            }
+            rts.push((start_time.elapsed().as_millis() as u64, group.len() as u64));
+            data_size += group
+                .iter()
+                .map(|ddl_data| ddl_data.get_data_size())
+                .sum::<u64>();
+            data_len += group.len();
 
             if last_monitor_time.elapsed().as_secs() >= monitor_interval {
                 self.base_sinker
@@ -155,10 +181,13 @@ impl Sinker for PgSinker {
             let (routed_schema, routed_tb) = if let Some(router) = &self.router {
                 router.get_tb_map(schema, tb)
             } else {
-                (schema.as_str(), tb.as_str())
+                (schema.into(), tb.into())
             };
             self.meta_manager
-                .invalidate_cache_for_table(routed_schema, routed_tb);
+                .invalidate_cache_for_table(&routed_schema, &routed_tb);
+            if let Some(sql_hook) = &self.sql_hook {
+                sql_hook.lock().await.after_tb(schema, tb).await?;
+            }
         }
         Ok(())
     }
@@ -166,6 +195,27 @@ impl Sinker for PgSinker {
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    async fn begin_trx(&mut self) -> anyhow::Result<()> {
+        self.check_conn().await?;
+        let mut tx = self.conn_pool.begin().await?;
+        if let Some(sql) = self.get_data_marker_sql().await {
+            sqlx::query(&sql)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+        }
+        self.trx = Some(tx);
+        Ok(())
+    }
+
+    async fn commit_trx(&mut self) -> anyhow::Result<()> {
+        if let Some(tx) = self.trx.take() {
+            tx.commit().await?;
+            self.conn_health.mark_active();
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -174,26 +224,290 @@ impl CheckableSink for PgSinker {
         if data.is_empty() {
             return Ok(());
         }
+        self.auto_create_tables(data).await?;
+        self.run_before_tb_hooks(data).await?;
+        self.copy_large_objects(data).await?;
+        self.sink_rows(data, batch).await
+    }
+}
 
-        if !batch {
-            self.serial_sink(data).await?;
-        } else {
-            match data[0].row_type {
-                RowType::Insert => {
-                    call_batch_fn!(self, data, Self::batch_insert);
+impl PgSinker {
+    // a failed ddl is skipped if [sinker] ddl_conflict_policy=ignore
+    async fn sink_single_ddl(&self, ddl_data: &DdlData) -> anyhow::Result<()> {
+        if let Err(err) = self.execute_ddl(ddl_data).await {
+            if self.ddl_conflict_policy != ConflictPolicyEnum::Ignore {
+                return Err(err);
+            }
+            log_error!(
+                "failed to sink ddl, ignored, sql: {}, error: {}",
+                ddl_data.to_sql(),
+                err
+            );
+        }
+        Ok(())
+    }
+
+    async fn execute_ddl(&self, ddl_data: &DdlData) -> anyhow::Result<()> {
+        let (schema, _tb) = ddl_data.get_schema_tb();
+        let final_url =
+            ConnectionAuthConfig::merge_url_with_auth(self.url.as_str(), &self.connection_auth)?;
+        let mut conn_options = PgConnectOptions::from_str(final_url.as_str())?;
+        let mut pool_options = PgPoolOptions::new().max_connections(1);
+        if let Some(ssl) = self.connection_auth.ssl_config() {
+            conn_options = ssl.apply_pg(conn_options);
+        }
+
+        if let Some(sql) = Self::get_search_path_sql(ddl_data, &schema, false) {
+            pool_options = pool_options.after_connect(move |conn, _meta| {
+                let sql = sql.clone();
+                Box::pin(async move {
+                    conn.execute(sql.as_str()).await?;
+                    Ok(())
+                })
+            });
+        }
+
+        let sql = ddl_data.to_sql();
+        log_info!("sink ddl, schema: {}, sql: {}", schema, sql);
+
+        let conn_pool = pool_options.connect_with(conn_options).await?;
+        let result = sqlx::query(&sql).execute(&conn_pool).await;
+        conn_pool.close().await;
+        result?;
+        Ok(())
+    }
+
+    // the transaction is rolled back if any ddl fails, then ddls are applied one by one
+    // if [sinker] ddl_conflict_policy=ignore
+    async fn sink_ddls_in_transaction(&self, ddls: &[&DdlData]) -> anyhow::Result<()> {
+        let mut tx = self.conn_pool.begin().await?;
+        for ddl_data in ddls.iter() {
+            let (schema, _tb) = ddl_data.get_schema_tb();
+            let sql = ddl_data.to_sql();
+            log_info!("sink ddl in transaction, schema: {}, sql: {}", schema, sql);
+
+            let mut result = Ok(());
+            if let Some(search_path_sql) = Self::get_search_path_sql(ddl_data, &schema, true) {
+                result = sqlx::raw_sql(&search_path_sql)
+                    .execute(&mut *tx)
+                    .await
+                    .map(|_| ());
+            }
+            if result.is_ok() {
+                result = sqlx::raw_sql(&sql).execute(&mut *tx).await.map(|_| ());
+            }
+
+            if let Err(err) = result {
+                tx.rollback().await?;
+                if self.ddl_conflict_policy != ConflictPolicyEnum::Ignore {
+                    return Err(err)
+                        .with_context(|| format!("ddl transaction rolled back, sql: {}", sql));
                 }
-                RowType::Delete => {
-                    call_batch_fn!(self, data, Self::batch_delete);
+                log_warn!(
+                    "ddl transaction rolled back, will apply ddls one by one, sql: {}, error: {}",
+                    sql,
+                    err
+                );
+                for ddl_data in ddls.iter() {
+                    self.sink_single_ddl(ddl_data).await?;
                 }
-                _ => self.serial_sink(data).await?,
+                return Ok(());
             }
         }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // SET LOCAL only lasts in the transaction, so the pooled connection is not affected
+    fn get_search_path_sql(ddl_data: &DdlData, schema: &str, local: bool) -> Option<String> {
+        if schema.is_empty() {
+            return None;
+        }
+        match ddl_data.ddl_type {
+            DdlType::CreateSchema | DdlType::DropSchema | DdlType::AlterSchema => None,
+            _ if local => Some(format!("SET LOCAL search_path = '{}';", schema)),
+            _ => Some(format!("SET search_path = '{}';", schema)),
+        }
+    }
+
+    async fn auto_create_tables(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        let Some(table_creator) = &self.table_creator else {
+            return Ok(());
+        };
+        let created = table_creator.lock().await.ensure_tables(data).await?;
+        for (schema, tb) in created.iter() {
+            self.meta_manager.invalidate_cache_for_table(schema, tb);
+        }
+        Ok(())
+    }
+
+    async fn run_before_tb_hooks(&self, data: &[RowData]) -> anyhow::Result<()> {
+        if let Some(sql_hook) = &self.sql_hook {
+            sql_hook.lock().await.before_rows(data).await?;
+        }
+        Ok(())
+    }
+
+    async fn copy_large_objects(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        let Some(copier) = &mut self.large_object_copier else {
+            return Ok(());
+        };
+        // rows of a batch may come from different tables in serial sink
+        let mut start = 0;
+        while start < data.len() {
+            let (schema, tb) = (&data[start].schema, &data[start].tb);
+            let end = data[start..]
+                .iter()
+                .position(|row_data| &row_data.schema != schema || &row_data.tb != tb)
+                .map_or(data.len(), |n| start + n);
+            let tb_meta = self.meta_manager.get_tb_meta(schema, tb).await?;
+            copier
+                .copy_for_rows(&self.conn_pool, tb_meta, &data[start..end])
+                .await?;
+            start = end;
+        }
+        Ok(())
+    }
+
+    async fn sink_rows(&mut self, data: &mut [RowData], batch: bool) -> anyhow::Result<()> {
+        // rows sunk before a failure are rolled back with the open transaction, which is
+        // extracted again after the restart, so no retries here
+        if self.trx.is_some() {
+            return self.serial_sink(data).await;
+        }
+
+        let replace = self.replace;
+        let mut batch_size = self.base_sinker.batch_size_for(data, self.batch_size);
+        let mut serial_size = data.len();
+        let mut retries = 0;
+        let mut tripped = false;
+        self.check_conn().await?;
+        let result = loop {
+            let attempt_at = Instant::now();
+            match self
+                .sink_rows_once(data, batch, batch_size, serial_size)
+                .await
+            {
+                Err(error)
+                    if retries < self.lock_retry.max_retries
+                        && LockRetry::is_lock_conflict(&error) =>
+                {
+                    retries += 1;
+                    batch_size = cmp::max(batch_size / 2, 1);
+                    serial_size = cmp::max(serial_size / 2, 1);
+                    let backoff = self.lock_retry.backoff(retries);
+                    log_warn!(
+                        "pg sinker lock conflict, retry: {} in {} ms with batch_size: {}, error: {}",
+                        retries,
+                        backoff.as_millis(),
+                        cmp::min(batch_size, serial_size),
+                        error
+                    );
+                    // rows sunk before the failure are sunk again, replace keeps inserts idempotent
+                    self.replace = true;
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(error)
+                    if self.conn_health.is_enabled() && ConnHealth::is_connection_error(&error) =>
+                {
+                    self.reconnect(error).await?;
+                    // rows may be committed before the connection was lost
+                    self.replace = true;
+                }
+                Err(error) => {
+                    let Some(circuit_breaker) = &self.circuit_breaker else {
+                        break Err(error);
+                    };
+                    // the rows are kept and sunk again once the target recovers
+                    CircuitBreaker::wait_to_retry(circuit_breaker, error, attempt_at.into_std())
+                        .await?;
+                    tripped = true;
+                    self.replace = true;
+                }
+                Ok(()) => break Ok(()),
+            }
+        };
+        self.replace = replace;
+        if result.is_ok() {
+            self.conn_health.mark_active();
+            if let Some(circuit_breaker) = self.circuit_breaker.as_ref().filter(|_| tripped) {
+                circuit_breaker.lock().await.close();
+            }
+        }
+        result
+    }
+
+    async fn check_conn(&mut self) -> anyhow::Result<()> {
+        if !self.conn_health.needs_check() {
+            return Ok(());
+        }
+        if let Err(error) = Self::ping(self.conn_pool.clone()).await {
+            self.reconnect(error).await?;
+        }
+        self.conn_health.mark_active();
+        Ok(())
+    }
+
+    async fn reconnect(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        // broken connections are dropped by the pool when acquired, a successful ping
+        // means new connections to the target can be established
+        let conn_pool = self.conn_pool.clone();
+        let pipeline = self.pipeline.clone();
+        let url = self.url.clone();
+        let connection_auth = self.connection_auth.clone();
+        self.conn_health
+            .recover("pg", error, || {
+                let (conn_pool, pipeline, url, connection_auth) = (
+                    conn_pool.clone(),
+                    pipeline.clone(),
+                    url.clone(),
+                    connection_auth.clone(),
+                );
+                async move {
+                    Self::ping(conn_pool).await?;
+                    if let Some(pipeline) = pipeline {
+                        let mut pipeline = pipeline.lock().await;
+                        if pipeline.is_closed() {
+                            pipeline.reconnect(&url, &connection_auth).await?;
+                        }
+                    }
+                    Ok(())
+                }
+            })
+            .await
+    }
+
+    async fn ping(conn_pool: Pool<Postgres>) -> anyhow::Result<()> {
+        sqlx::query("SELECT 1").execute(&conn_pool).await?;
+        Ok(())
+    }
+
+    async fn sink_rows_once(
+        &mut self,
+        data: &mut [RowData],
+        batch: bool,
+        batch_size: usize,
+        serial_size: usize,
+    ) -> anyhow::Result<()> {
+        if !batch || !matches!(data[0].row_type, RowType::Insert | RowType::Delete) {
+            // one transaction for all rows unless retrying
+            for sub_data in data.chunks(serial_size) {
+                self.serial_sink(sub_data).await?;
+            }
+        } else if data[0].row_type == RowType::Insert {
+            call_batch_fn!(self, data, Self::batch_insert, batch_size);
+        } else {
+            call_batch_fn!(self, data, Self::batch_delete, batch_size);
+        }
         Ok(())
     }
-}
 
-impl PgSinker {
     async fn serial_sink(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        // the pipelined connection can not join the open transaction
+        if self.pipeline.is_some() && self.trx.is_none() {
+            return self.pipeline_sink(data).await;
+        }
+
         let task_id = self.base_sinker.source_task_id_for_rows(data, &self.router);
         self.base_sinker.ensure_monitor_for(&task_id);
         let monitor_interval = self.base_sinker.monitor_interval_secs();
@@ -201,13 +515,20 @@ impl PgSinker {
         let mut data_len = 0;
         let mut last_monitor_time = Instant::now();
 
-        let mut tx = self.conn_pool.begin().await?;
-        if let Some(sql) = self.get_data_marker_sql().await {
-            sqlx::query(&sql)
-                .execute(&mut *tx)
-                .await
-                .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
-        }
+        let in_trx = self.trx.is_some();
+        let mut tx = match self.trx.take() {
+            Some(tx) => tx,
+            None => {
+                let mut tx = self.conn_pool.begin().await?;
+                if let Some(sql) = self.get_data_marker_sql().await {
+                    sqlx::query(&sql)
+                        .execute(&mut *tx)
+                        .await
+                        .with_context(|| format!("failed to execute data marker sql: [{}]", sql))?;
+                }
+                tx
+            }
+        };
         let mut rts = LimitedQueue::new(cmp::min(100, data.len()));
         for row_data in data.iter() {
             data_size += row_data.get_data_size() as usize;
@@ -220,7 +541,16 @@ impl PgSinker {
             let query = query_builder.create_pg_query(&query_info)?;
 
             let start_time = Instant::now();
-            query.execute(&mut *tx).await.with_context(|| {
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                &row_data.schema,
+                &row_data.tb,
+                query.execute(&mut *tx),
+            )
+            .await
+            .with_context(|| {
                 format!(
                     "serial sink failed, sql: [{}], row_data: [{}]",
                     query_info.sql, row_data
@@ -241,7 +571,11 @@ impl PgSinker {
                 last_monitor_time = Instant::now();
             }
         }
-        tx.commit().await?;
+        if in_trx {
+            self.trx = Some(tx);
+        } else {
+            tx.commit().await?;
+        }
 
         if data_len > 0 || data_size > 0 {
             self.base_sinker
@@ -254,6 +588,41 @@ impl PgSinker {
         Ok(())
     }
 
+    async fn pipeline_sink(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        let task_id = self.base_sinker.source_task_id_for_rows(data, &self.router);
+        self.base_sinker.ensure_monitor_for(&task_id);
+
+        let mut data_size = 0;
+        let mut queries = Vec::with_capacity(data.len());
+        for row_data in data.iter() {
+            data_size += row_data.get_data_size();
+            let tb_meta = self.meta_manager.get_tb_meta_by_row_data(row_data).await?;
+            let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None);
+            let query_info = query_builder.get_query_info(row_data, self.replace)?;
+            queries.push(PipelineQuery::new(
+                query_info.sql.clone(),
+                &query_info.binds,
+            ));
+        }
+
+        let data_marker_sql = self.get_data_marker_sql().await;
+        let pipeline = self.pipeline.as_ref().unwrap();
+        let elapsed = pipeline
+            .lock()
+            .await
+            .execute(data_marker_sql, &queries)
+            .await?;
+
+        let mut rts = LimitedQueue::new(cmp::max(1, cmp::min(100, elapsed.len())));
+        for rt in elapsed {
+            rts.push((rt, 1));
+        }
+        self.base_sinker
+            .update_serial_monitor_for(&task_id, data.len() as u64, data_size)
+            .await?;
+        self.base_sinker.update_monitor_rt_for(&task_id, &rts).await
+    }
+
     async fn batch_delete(
         &mut self,
         data: &mut [RowData],
@@ -273,13 +642,30 @@ impl PgSinker {
 
         let start_time = Instant::now();
         let mut rts = LimitedQueue::new(1);
+        let (schema, tb) = (&data[start_index].schema, &data[start_index].tb);
         if let Some(sql) = self.get_data_marker_sql().await {
             let mut tx = self.conn_pool.begin().await?;
             sqlx::query(&sql).execute(&mut *tx).await?;
-            query.execute(&mut *tx).await?;
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&mut *tx),
+            )
+            .await?;
             tx.commit().await?;
         } else {
-            query.execute(&self.conn_pool).await?;
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&self.conn_pool),
+            )
+            .await?;
         }
         rts.push((start_time.elapsed().as_millis() as u64, 1));
 
@@ -312,19 +698,38 @@ impl PgSinker {
 
         let start_time = Instant::now();
         let mut rts = LimitedQueue::new(1);
+        let (schema, tb) = (&tb_meta.basic.schema, &tb_meta.basic.tb);
         let exec_error = if let Some(sql) = self.get_data_marker_sql().await {
             let mut tx = self.conn_pool.begin().await?;
             sqlx::query(&sql).execute(&mut *tx).await?;
-            query.execute(&mut *tx).await?;
-            tx.commit().await
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&mut *tx),
+            )
+            .await?;
+            tx.commit().await.map_err(anyhow::Error::from)
         } else {
-            match query.execute(&self.conn_pool).await {
-                Err(e) => Err(e),
-                _ => Ok(()),
-            }
+            QueryTimeout::run(
+                self.statement_timeout,
+                SINKER_STAGE,
+                &query_info.sql,
+                schema,
+                tb,
+                query.execute(&self.conn_pool),
+            )
+            .await
+            .map(|_| ())
         };
 
         if let Err(error) = exec_error {
+            // rows of a timed out batch are likely to time out one by one as well
+            if QueryTimeout::is_timeout_error(&error) {
+                return Err(error);
+            }
             log_error!(
                 "batch insert failed, will insert one by one, schema: {}, tb: {}, error: {}",
                 tb_meta.basic.schema,
@@ -18,6 +18,8 @@ use dt_common::{
 pub struct PgStructSinker {
     pub conn_pool: Pool<Postgres>,
     pub conflict_policy: ConflictPolicyEnum,
+    // for views, triggers and routines
+    pub object_conflict_policy: ConflictPolicyEnum,
     pub filter: RdbFilter,
     pub router: Option<RdbRouter>,
     pub base_sinker: BaseSinker,
@@ -29,6 +31,7 @@ impl Sinker for PgStructSinker {
         BaseStructSinker::sink_structs(
             &DBConnPool::PostgreSQL(self.conn_pool.clone()),
             &self.conflict_policy,
+            &self.object_conflict_policy,
             data,
             &self.filter,
             &self.base_sinker,
@@ -1,12 +1,20 @@
 use anyhow::bail;
 use dt_common::error::Error;
+
+use crate::extractor::redis::rdb::entry_parser::min_restore_version;
 use dt_common::meta::redis::{
     redis_entry::RedisEntry,
     redis_object::{
-        HashObject, ListObject, ModuleObject, RedisCmd, SetObject, StringObject, ZsetObject,
+        FunctionObject, HashObject, ListObject, ModuleObject, RedisCmd, RedisObject, SetObject,
+        StringObject, ZsetObject,
     },
 };
 
+// rdb version of DUMP payloads for keys
+const DUMP_RDB_VERSION: u16 = 6;
+// FUNCTION RESTORE requires the rdb version of redis 7.0+
+const FUNCTION_DUMP_RDB_VERSION: u16 = 10;
+
 const CRC64_TABLE: [u64; 256] = [
     0x0000000000000000,
     0x7ad870c830358979,
@@ -269,6 +277,26 @@ const CRC64_TABLE: [u64; 256] = [
 pub struct EntryRewriter {}
 
 impl EntryRewriter {
+    pub fn rewrite_value(entry: &mut RedisEntry, version: f32) -> anyhow::Result<Vec<RedisCmd>> {
+        match entry.value {
+            RedisObject::String(ref mut obj) => Self::rewrite_string(obj),
+            RedisObject::List(ref mut obj) => Self::rewrite_list(obj),
+            RedisObject::Set(ref mut obj) => Self::rewrite_set(obj),
+            RedisObject::Hash(ref mut obj) => Self::rewrite_hash(obj),
+            RedisObject::Zset(ref mut obj) => Self::rewrite_zset(obj),
+            RedisObject::Stream(ref mut obj) => Ok(obj.cmds.drain(..).collect()),
+            RedisObject::Module(_) => {
+                // TODO: support rewrite some 8.0+ major module2 types, such as: JSON, Bloom, CountMinSketch, TDigest, TopK, TimeSeries
+                let cmd = Self::rewrite_as_restore(entry, version)?;
+                Ok(vec![cmd])
+            }
+            RedisObject::Function(ref obj) => Self::rewrite_function(obj),
+            // e.g. lua scripts from rdb aux fields
+            RedisObject::Unknown if !entry.cmd.args.is_empty() => Ok(vec![entry.cmd.clone()]),
+            _ => bail! {Error::SinkerError("rewrite not implemented".into())},
+        }
+    }
+
     pub fn rewrite_hash(obj: &mut HashObject) -> anyhow::Result<Vec<RedisCmd>> {
         let mut cmds = vec![];
         for (k, v) in &obj.value {
@@ -311,6 +339,15 @@ impl EntryRewriter {
         )}
     }
 
+    pub fn rewrite_function(obj: &FunctionObject) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg("function");
+        cmd.add_str_arg("load");
+        cmd.add_str_arg("replace");
+        cmd.add_redis_arg(&obj.code);
+        Ok(vec![cmd])
+    }
+
     pub fn rewrite_set(obj: &mut SetObject) -> anyhow::Result<Vec<RedisCmd>> {
         let mut cmds = vec![];
         for ele in &obj.elements {
@@ -345,18 +382,56 @@ impl EntryRewriter {
     }
 
     pub fn rewrite_as_restore(entry: &RedisEntry, version: f32) -> anyhow::Result<RedisCmd> {
-        let value = Self::create_value_dump(entry.value_type_byte, &entry.raw_bytes);
+        if let RedisObject::Function(_) = entry.value {
+            return Ok(Self::rewrite_function_as_restore(entry));
+        }
+
+        let value =
+            Self::create_value_dump(entry.value_type_byte, &entry.raw_bytes, DUMP_RDB_VERSION);
+        // ABSTTL keeps the exact expire time regardless of how long the entry waited before sinking
+        let abs_ttl = version >= 5.0 && entry.expire_at_ms > 0;
+        let ttl = if abs_ttl {
+            entry.expire_at_ms
+        } else {
+            entry.expire_ms
+        };
         let mut cmd = RedisCmd::new();
         cmd.add_str_arg("restore");
         cmd.add_redis_arg(&entry.key);
-        cmd.add_str_arg(&entry.expire_ms.to_string());
+        cmd.add_str_arg(&ttl.to_string());
         cmd.add_arg(value);
         if version >= 3.0 {
             cmd.add_str_arg("replace");
         }
+        if abs_ttl {
+            cmd.add_str_arg("absttl");
+        }
         Ok(cmd)
     }
 
+    /// Whether the target is able to load the DUMP payload of the entry, e.g. listpack
+    /// encodings from redis 7.x can not be restored to redis 6.x.
+    pub fn can_restore(entry: &RedisEntry, version: f32) -> bool {
+        if let RedisObject::Function(_) = entry.value {
+            return version >= 7.0;
+        }
+        version >= min_restore_version(entry.value_type_byte)
+    }
+
+    fn rewrite_function_as_restore(entry: &RedisEntry) -> RedisCmd {
+        let value = Self::create_value_dump(
+            entry.value_type_byte,
+            &entry.raw_bytes,
+            FUNCTION_DUMP_RDB_VERSION,
+        );
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg("function");
+        cmd.add_str_arg("restore");
+        cmd.add_arg(value);
+        cmd.add_str_arg("replace");
+        cmd
+    }
+
     pub fn rewrite_expire(entry: &RedisEntry) -> anyhow::Result<Option<RedisCmd>> {
         if entry.expire_ms == 0 {
             return Ok(None);
@@ -368,11 +443,24 @@ impl EntryRewriter {
         Ok(Some(cmd))
     }
 
-    fn create_value_dump(type_byte: u8, val: &[u8]) -> Vec<u8> {
+    // expire_ms of entries decoded from rdb is a ttl relative to the extracting time,
+    // convert it to an absolute timestamp so the command can be replayed later
+    pub fn rewrite_expire_at(entry: &RedisEntry, now_ms: i64) -> anyhow::Result<Option<RedisCmd>> {
+        if entry.expire_ms == 0 {
+            return Ok(None);
+        }
+        let mut cmd = RedisCmd::new();
+        cmd.add_str_arg("pexpireat");
+        cmd.add_redis_arg(&entry.key);
+        cmd.add_str_arg(&(now_ms + entry.expire_ms).to_string());
+        Ok(Some(cmd))
+    }
+
+    fn create_value_dump(type_byte: u8, val: &[u8], rdb_version: u16) -> Vec<u8> {
         let mut buf: Vec<u8> = Vec::new();
         buf.push(type_byte);
         buf.extend_from_slice(val);
-        buf.extend_from_slice(&6u16.to_le_bytes());
+        buf.extend_from_slice(&rdb_version.to_le_bytes());
         let sum64 = Self::calc_crc64(&buf);
         buf.extend_from_slice(&sum64.to_le_bytes());
         buf
@@ -387,3 +475,71 @@ impl EntryRewriter {
         crc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::redis::redis_object::RedisString;
+
+    use super::*;
+
+    #[test]
+    fn test_rewrite_function() {
+        let code = "#!lua name=mylib\nredis.register_function('f', function() return 1 end)";
+        let mut raw_bytes = vec![code.len() as u8];
+        raw_bytes.extend_from_slice(code.as_bytes());
+
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.raw_bytes = raw_bytes.clone();
+        entry.value_type_byte = 0xf5;
+        entry.value = RedisObject::Function(FunctionObject {
+            code: RedisString::from(code.to_string()),
+        });
+
+        let cmd = EntryRewriter::rewrite_as_restore(&entry, 7.0).unwrap();
+        assert_eq!(cmd.args.len(), 4);
+        assert_eq!(cmd.get_str_arg(0), "function");
+        assert_eq!(cmd.get_str_arg(1), "restore");
+        assert_eq!(cmd.get_str_arg(3), "replace");
+        let payload = &cmd.args[2];
+        assert_eq!(payload[0], 0xf5);
+        assert_eq!(&payload[1..raw_bytes.len() + 1], raw_bytes.as_slice());
+        assert_eq!(
+            &payload[raw_bytes.len() + 1..raw_bytes.len() + 3],
+            &FUNCTION_DUMP_RDB_VERSION.to_le_bytes()
+        );
+        assert_eq!(payload.len(), raw_bytes.len() + 11);
+
+        let cmds = EntryRewriter::rewrite_value(&mut entry, 7.0).unwrap();
+        assert_eq!(
+            cmds[0].args_to_string(),
+            vec!["function", "load", "replace", code]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_as_restore_ttl_and_version() {
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.key = RedisString::from("k".to_string());
+        entry.raw_bytes = vec![1, b'v'];
+        entry.value_type_byte = 0;
+        entry.expire_ms = 1000;
+        entry.expire_at_ms = 1700000000000;
+
+        let cmd = EntryRewriter::rewrite_as_restore(&entry, 7.0).unwrap();
+        assert_eq!(cmd.get_str_arg(2), "1700000000000");
+        assert_eq!(cmd.get_str_arg(4), "replace");
+        assert_eq!(cmd.get_str_arg(5), "absttl");
+
+        let cmd = EntryRewriter::rewrite_as_restore(&entry, 4.0).unwrap();
+        assert_eq!(cmd.get_str_arg(2), "1000");
+        assert_eq!(cmd.args.len(), 5);
+
+        assert!(EntryRewriter::can_restore(&entry, 2.8));
+        // set listpack, since redis 7.2
+        entry.value_type_byte = 20;
+        assert!(EntryRewriter::can_restore(&entry, 7.2));
+        assert!(!EntryRewriter::can_restore(&entry, 7.0));
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+
+use dt_common::{config::sinker_config::RedisTableKey, error::Error, meta::col_value::ColValue};
+
+/// How rows of a relational table are written into redis
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableKey {
+    // None for the default key: {schema}.{tb}.{primary key value}
+    pub template: Option<KeyTemplate>,
+    // 0 for no expiration
+    pub ttl_secs: u64,
+}
+
+impl TableKey {
+    pub fn from_config(config: &RedisTableKey) -> anyhow::Result<Self> {
+        let template = if config.key.is_empty() {
+            None
+        } else {
+            Some(KeyTemplate::parse(&config.key)?)
+        };
+        Ok(Self {
+            template,
+            ttl_secs: config.ttl_secs,
+        })
+    }
+}
+
+/// Key of rows from a relational table, e.g. user:{id}, each {col} is replaced by the column value,
+/// {{ and }} are literal braces, e.g. {{user:{id}}} makes the hash tag {user:1}.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyTemplate {
+    parts: Vec<KeyPart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum KeyPart {
+    Literal(String),
+    Col(String),
+}
+
+impl KeyTemplate {
+    pub fn parse(template: &str) -> anyhow::Result<Self> {
+        let invalid = |reason: &str| {
+            Error::ConfigError(format!(
+                "invalid redis key template: [{}], {}",
+                template, reason
+            ))
+        };
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut col = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => bail!(invalid("unclosed {")),
+                            Some(c) => col.push(c),
+                        }
+                    }
+                    if col.is_empty() {
+                        bail!(invalid("empty column name"));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(KeyPart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(KeyPart::Col(col));
+                }
+                '}' => bail!(invalid("unmatched }")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(KeyPart::Literal(literal));
+        }
+
+        if !parts.iter().any(|part| matches!(part, KeyPart::Col(_))) {
+            bail!(invalid("no column is referenced"));
+        }
+        Ok(Self { parts })
+    }
+
+    /// None if any referenced column is missing or NULL
+    pub fn render(
+        &self,
+        col_values: &HashMap<String, ColValue>,
+        to_string: impl Fn(&ColValue) -> Option<String>,
+    ) -> Option<String> {
+        let mut key = String::new();
+        for part in self.parts.iter() {
+            match part {
+                KeyPart::Literal(literal) => key.push_str(literal),
+                KeyPart::Col(col) => key.push_str(&to_string(col_values.get(col)?)?),
+            }
+        }
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_template() {
+        let col_values = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("region".to_string(), ColValue::String("eu".into())),
+            ("note".to_string(), ColValue::None),
+        ]);
+        let render = |template: &str| {
+            KeyTemplate::parse(template)
+                .unwrap()
+                .render(&col_values, |v| v.to_option_string())
+        };
+
+        assert_eq!(render("user:{id}"), Some("user:1".to_string()));
+        assert_eq!(render("{region}:{id}"), Some("eu:1".to_string()));
+        assert_eq!(render("{{user:{id}}}"), Some("{user:1}".to_string()));
+        assert_eq!(render("user:{note}"), None);
+        assert_eq!(render("user:{missing}"), None);
+
+        for template in ["user", "user:{id", "user:id}", "user:{}"] {
+            assert!(KeyTemplate::parse(template).is_err(), "{}", template);
+        }
+    }
+}
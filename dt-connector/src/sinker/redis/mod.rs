@@ -1,3 +1,5 @@
 pub mod entry_rewriter;
+pub mod key_template;
+pub mod redis_aof_sinker;
 pub mod redis_sinker;
 pub mod redis_statistic_sinker;
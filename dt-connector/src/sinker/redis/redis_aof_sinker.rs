@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncWriteExt, BufWriter},
+};
+
+use dt_common::{
+    log_info,
+    meta::{
+        dt_data::{DtData, DtItem},
+        redis::{command::cmd_encoder::CmdEncoder, redis_object::RedisCmd},
+    },
+};
+
+use super::entry_rewriter::EntryRewriter;
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+
+// RESTORE payloads of module objects are written with REPLACE
+const AOF_RESTORE_VERSION: f32 = 7.0;
+
+/// writes replicated entries into a file in AOF (RESP commands) format,
+/// the file can be loaded by redis-server directly or replayed by redis-cli --pipe
+pub struct RedisAofSinker {
+    pub file_path: String,
+    pub writer: Option<BufWriter<File>>,
+    pub now_db_id: i64,
+    pub router: Option<RdbRouter>,
+    pub base_sinker: BaseSinker,
+}
+
+#[async_trait]
+impl Sinker for RedisAofSinker {
+    async fn sink_raw(&mut self, mut data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut data_size = 0;
+        let mut cmd_count = 0;
+        for dt_item in data.iter_mut() {
+            data_size += dt_item.dt_data.get_data_size();
+            let cmds = self.rewrite_entry(&mut dt_item.dt_data)?;
+            cmd_count += cmds.len();
+            self.write_cmds(&cmds).await?;
+        }
+        self.writer_mut().await?.flush().await?;
+
+        self.base_sinker
+            .update_batch_monitor(cmd_count as u64, data_size)
+            .await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush().await?;
+            writer.get_mut().sync_all().await?;
+            log_info!("redis aof file: {} closed", self.file_path);
+        }
+        Ok(())
+    }
+}
+
+impl RedisAofSinker {
+    fn rewrite_entry(&mut self, dt_data: &mut DtData) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut cmds = Vec::new();
+        if let DtData::Redis { entry } = dt_data {
+            let dst_db_id = if let Some(router) = &self.router {
+                router.route_redis_db_id(entry.db_id)?
+            } else {
+                entry.db_id
+            };
+
+            if dst_db_id != self.now_db_id {
+                let db_id = &dst_db_id.to_string();
+                cmds.push(RedisCmd::from_str_args(&["SELECT", db_id]));
+                self.now_db_id = dst_db_id;
+            }
+
+            if entry.is_base {
+                // logical commands keep the file portable across redis versions
+                cmds.extend(EntryRewriter::rewrite_value(entry, AOF_RESTORE_VERSION)?);
+                let now_ms = chrono::Utc::now().timestamp_millis();
+                if let Some(expire_cmd) = EntryRewriter::rewrite_expire_at(entry, now_ms)? {
+                    cmds.push(expire_cmd);
+                }
+            } else if let Some(router) = &self.router {
+                cmds.push(router.route_redis_cmd(&entry.cmd)?);
+            } else {
+                cmds.push(entry.cmd.clone());
+            }
+        }
+        Ok(cmds)
+    }
+
+    async fn write_cmds(&mut self, cmds: &[RedisCmd]) -> anyhow::Result<()> {
+        let writer = self.writer_mut().await?;
+        for cmd in cmds.iter() {
+            writer.write_all(&CmdEncoder::encode(cmd)).await?;
+        }
+        Ok(())
+    }
+
+    async fn writer_mut(&mut self) -> anyhow::Result<&mut BufWriter<File>> {
+        if self.writer.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&self.file_path)
+                .await?;
+            log_info!("redis aof file: {} created", self.file_path);
+            self.writer = Some(BufWriter::new(file));
+        }
+        Ok(self.writer.as_mut().unwrap())
+    }
+}
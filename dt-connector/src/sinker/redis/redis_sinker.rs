@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::bail;
 use async_trait::async_trait;
@@ -8,6 +8,7 @@ use redis::ConnectionLike;
 use redis::Value;
 use tokio::{sync::RwLock, time::Instant};
 
+use dt_common::config::connection_auth_config::ConnectionAuthConfig;
 use dt_common::error::Error;
 use dt_common::log_debug;
 use dt_common::meta::col_value::ColValue;
@@ -17,30 +18,45 @@ use dt_common::meta::rdb_meta_manager::RdbMetaManager;
 use dt_common::meta::redis::cluster_node::ClusterNode;
 use dt_common::meta::redis::command::cmd_encoder::CmdEncoder;
 use dt_common::meta::redis::command::key_parser::KeyParser;
+use dt_common::meta::redis::redis_entry::RedisEntry;
 use dt_common::meta::redis::redis_object::RedisCmd;
-use dt_common::meta::redis::redis_object::RedisObject;
 use dt_common::meta::redis::redis_write_method::RedisWriteMethod;
 use dt_common::meta::row_data::RowData;
 use dt_common::meta::row_type::RowType;
+use dt_common::utils::redis_util::RedisUtil;
 
-use super::entry_rewriter::EntryRewriter;
+use super::{entry_rewriter::EntryRewriter, key_template::TableKey};
 use crate::{
-    call_batch_fn, data_marker::DataMarker, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker,
+    call_batch_fn,
+    data_marker::DataMarker,
+    rdb_router::RdbRouter,
+    sinker::{base_sinker::BaseSinker, conn_health::ConnHealth},
     Sinker,
 };
 
 pub struct RedisSinker {
     pub cluster_node: Option<ClusterNode>,
     pub batch_size: usize,
+    // url of the node which conn connects to, to reconnect after the connection is lost
+    pub url: String,
+    pub connection_auth: ConnectionAuthConfig,
     pub conn: Connection,
+    // db selected by entries being rewritten
     pub now_db_id: i64,
+    // db selected on conn by commands already sunk, -1 if never selected
+    pub conn_db_id: i64,
     pub version: f32,
     pub method: RedisWriteMethod,
+    // rewrite entries as commands if their dump payloads can not be restored to the target
+    pub restore_fallback: bool,
     pub meta_manager: Option<RdbMetaManager>,
     pub base_sinker: BaseSinker,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub key_parser: KeyParser,
     pub router: Option<RdbRouter>,
+    // key template and ttl of rows from relational tables, keyed by (schema, tb)
+    pub table_keys: HashMap<(String, String), TableKey>,
+    pub conn_health: ConnHealth,
 }
 
 #[async_trait]
@@ -139,38 +155,51 @@ impl RedisSinker {
 
             match self.method {
                 RedisWriteMethod::Restore => {
-                    if entry.is_raw() {
+                    if !entry.is_raw() {
+                        cmds.push(self.route_cmd(&entry.cmd)?);
+                    } else if EntryRewriter::can_restore(entry, self.version) {
                         let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
                         cmds.push(cmd);
+                    } else if self.restore_fallback {
+                        log_debug!(
+                            "rdb type: {} can not be restored to redis {}, rewrite key: {}",
+                            entry.value_type_byte,
+                            self.version,
+                            String::from(entry.key.clone())
+                        );
+                        cmds.extend(Self::rewrite_entry_value(entry, self.version)?);
                     } else {
-                        cmds.push(entry.cmd.clone());
+                        bail! {Error::SinkerError(format!(
+                            "rdb type: {} can not be restored to redis {}, key: {}, set [sinker] restore_fallback=true to rewrite it as commands",
+                            entry.value_type_byte,
+                            self.version,
+                            String::from(entry.key.clone())
+                        ))}
                     }
                 }
 
                 RedisWriteMethod::Rewrite => {
-                    let mut rewrite_cmds = match entry.value {
-                        RedisObject::String(ref mut obj) => EntryRewriter::rewrite_string(obj),
-                        RedisObject::List(ref mut obj) => EntryRewriter::rewrite_list(obj),
-                        RedisObject::Set(ref mut obj) => EntryRewriter::rewrite_set(obj),
-                        RedisObject::Hash(ref mut obj) => EntryRewriter::rewrite_hash(obj),
-                        RedisObject::Zset(ref mut obj) => EntryRewriter::rewrite_zset(obj),
-                        RedisObject::Stream(ref mut obj) => Ok(obj.cmds.drain(..).collect()),
-                        RedisObject::Module(_) => {
-                            // TODO: support rewrite some 8.0+ major module2 types, such as: JSON, Bloom, CountMinSketch, TDigest, TopK, TimeSeries
-                            let cmd = EntryRewriter::rewrite_as_restore(entry, self.version)?;
-                            Ok(vec![cmd])
-                        }
-                        _ => bail! {Error::SinkerError("rewrite not implemented".into())},
-                    }?;
-                    if let Some(expire_cmd) = EntryRewriter::rewrite_expire(entry)? {
-                        rewrite_cmds.push(expire_cmd)
-                    }
-                    cmds.extend(rewrite_cmds);
+                    cmds.extend(Self::rewrite_entry_value(entry, self.version)?);
                 }
             }
         }
         Ok(cmds)
     }
+
+    fn route_cmd(&self, cmd: &RedisCmd) -> anyhow::Result<RedisCmd> {
+        match &self.router {
+            Some(router) => router.route_redis_cmd(cmd),
+            None => Ok(cmd.clone()),
+        }
+    }
+
+    fn rewrite_entry_value(entry: &mut RedisEntry, version: f32) -> anyhow::Result<Vec<RedisCmd>> {
+        let mut cmds = EntryRewriter::rewrite_value(entry, version)?;
+        if let Some(expire_cmd) = EntryRewriter::rewrite_expire(entry)? {
+            cmds.push(expire_cmd)
+        }
+        Ok(cmds)
+    }
 }
 
 /// sink dml
@@ -190,9 +219,7 @@ impl RedisSinker {
         let mut cmds = Vec::new();
         for row_data in data.iter().skip(start_index).take(batch_size) {
             data_size += row_data.get_data_size();
-            if let Some(cmd) = self.dml_to_redis_cmd(row_data).await? {
-                cmds.push(cmd);
-            }
+            cmds.extend(self.dml_to_redis_cmds(row_data).await?);
         }
         self.batch_sink(&cmds).await?;
 
@@ -208,9 +235,8 @@ impl RedisSinker {
 
         for row_data in data.iter() {
             data_size += row_data.get_data_size();
-            if let Some(cmd) = self.dml_to_redis_cmd(row_data).await? {
-                self.batch_sink(&[cmd]).await?
-            }
+            let cmds = self.dml_to_redis_cmds(row_data).await?;
+            self.batch_sink(&cmds).await?
         }
 
         self.base_sinker
@@ -218,42 +244,30 @@ impl RedisSinker {
             .await
     }
 
-    async fn dml_to_redis_cmd(&mut self, row_data: &RowData) -> anyhow::Result<Option<RedisCmd>> {
-        if self.meta_manager.is_none() {
-            return Ok(None);
-        }
-
-        let tb_meta = self
-            .meta_manager
-            .as_mut()
-            .unwrap()
-            .get_tb_meta(&row_data.schema, &row_data.tb)
-            .await?;
-
-        // no single primary / unique key exists, do not sink to redis
-        if tb_meta.order_cols.is_empty() {
-            return Ok(None);
-        }
-
-        let key = if let Some(col) = tb_meta.order_cols.first() {
-            match row_data.row_type {
-                RowType::Insert | RowType::Update => row_data
-                    .require_after()?
-                    .get(col)
-                    .and_then(Self::redis_col_value_string),
-                RowType::Delete => row_data
-                    .require_before()?
-                    .get(col)
-                    .and_then(Self::redis_col_value_string),
-            }
-        } else {
-            None
+    async fn dml_to_redis_cmds(&mut self, row_data: &RowData) -> anyhow::Result<Vec<RedisCmd>> {
+        let col_values = match row_data.row_type {
+            RowType::Insert | RowType::Update => row_data.require_after()?,
+            RowType::Delete => row_data.require_before()?,
         };
-
-        let key = if let Some(v) = key {
-            format!("{}.{}.{}", row_data.schema, row_data.tb, v)
-        } else {
-            return Ok(None);
+        let table_key = self
+            .table_keys
+            .get(&(row_data.schema.clone(), row_data.tb.clone()))
+            .cloned();
+
+        let key = match table_key.as_ref().and_then(|t| t.template.as_ref()) {
+            Some(template) => {
+                let Some(key) = template.render(col_values, Self::redis_col_value_string) else {
+                    bail!(Error::SinkerError(format!(
+                        "failed to build redis key of {}.{} by template, referenced columns are missing or NULL",
+                        row_data.schema, row_data.tb
+                    )));
+                };
+                key
+            }
+            None => match self.default_key(row_data, col_values).await? {
+                Some(key) => key,
+                None => return Ok(Vec::new()),
+            },
         };
 
         let mut cmd = RedisCmd::new();
@@ -261,7 +275,7 @@ impl RedisSinker {
             RowType::Insert | RowType::Update => {
                 cmd.add_str_arg("hset");
                 cmd.add_str_arg(&key);
-                for (col, col_value) in row_data.require_after()? {
+                for (col, col_value) in col_values {
                     cmd.add_str_arg(col);
                     if let Some(v) = Self::redis_col_value_string(col_value) {
                         cmd.add_str_arg(&v);
@@ -273,9 +287,43 @@ impl RedisSinker {
             RowType::Delete => {
                 cmd.add_str_arg("del");
                 cmd.add_str_arg(&key);
+                return Ok(vec![cmd]);
             }
         }
-        Ok(Some(cmd))
+
+        let mut cmds = vec![cmd];
+        if let Some(ttl_secs) = table_key.map(|t| t.ttl_secs).filter(|ttl| *ttl > 0) {
+            // refreshed by every insert / update of the row
+            cmds.push(RedisCmd::from_str_args(&[
+                "expire",
+                &key,
+                &ttl_secs.to_string(),
+            ]));
+        }
+        Ok(cmds)
+    }
+
+    // {schema}.{tb}.{value of the single primary / unique key}
+    async fn default_key(
+        &mut self,
+        row_data: &RowData,
+        col_values: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(meta_manager) = self.meta_manager.as_mut() else {
+            return Ok(None);
+        };
+        let tb_meta = meta_manager
+            .get_tb_meta(&row_data.schema, &row_data.tb)
+            .await?;
+
+        // no single primary / unique key exists, do not sink to redis
+        let Some(col) = tb_meta.order_cols.first() else {
+            return Ok(None);
+        };
+        Ok(col_values
+            .get(col)
+            .and_then(Self::redis_col_value_string)
+            .map(|v| format!("{}.{}.{}", row_data.schema, row_data.tb, v)))
     }
 
     fn redis_col_value_string(col_value: &ColValue) -> Option<String> {
@@ -314,10 +362,26 @@ impl RedisSinker {
             tx_wrapper_cmds.push(exec_cmd);
         }
 
+        if self.conn_health.needs_check() {
+            if let Err(error) = redis::cmd("PING").query::<String>(&mut self.conn) {
+                self.reconnect(error.into()).await?;
+            }
+        }
+
         let count = if is_tx { cmds.len() + 3 } else { cmds.len() };
         let mut rts = LimitedQueue::new(1);
         let start_time = Instant::now();
-        let result = self.conn.req_packed_commands(&packed_cmds, 0, count);
+        let result = loop {
+            match self.conn.req_packed_commands(&packed_cmds, 0, count) {
+                Err(error)
+                    if self.conn_health.is_enabled() && ConnHealth::is_redis_conn_error(&error) =>
+                {
+                    // commands may be applied before the connection was lost and are sent again
+                    self.reconnect(error.into()).await?;
+                }
+                result => break result,
+            }
+        };
         rts.push((start_time.elapsed().as_millis() as u64, 1));
         self.base_sinker.update_monitor_rt(&rts).await?;
 
@@ -330,6 +394,8 @@ impl RedisSinker {
             }
 
             Ok(values) => {
+                self.conn_db_id = self.now_db_id;
+                self.conn_health.mark_active();
                 for (i, v) in values.iter().enumerate() {
                     if *v == Value::Okay {
                         continue;
@@ -363,6 +429,27 @@ impl RedisSinker {
         Ok(())
     }
 
+    async fn reconnect(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        let url = self.url.clone();
+        let connection_auth = self.connection_auth.clone();
+        let db_id = self.conn_db_id;
+        self.conn = self
+            .conn_health
+            .recover("redis", error, || {
+                let (url, connection_auth) = (url.clone(), connection_auth.clone());
+                async move {
+                    let mut conn = RedisUtil::create_redis_conn(&url, &connection_auth).await?;
+                    // commands to resend are based on the db selected before the failure
+                    if db_id >= 0 {
+                        redis::cmd("SELECT").arg(db_id).query::<()>(&mut conn)?;
+                    }
+                    Ok(conn)
+                }
+            })
+            .await?;
+        Ok(())
+    }
+
     async fn get_data_marker_cmd(&self, mut cmd: RedisCmd) -> anyhow::Result<Option<RedisCmd>> {
         if let Some(data_marker) = &self.data_marker {
             let data_marker = data_marker.read().await;
@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use dt_common::{
+    error::Error,
+    meta::{
+        ddl_meta::ddl_data::DdlData, dt_data::DtItem, position::Position,
+        rdb_meta_manager::RdbMetaManager, row_data::RowData, row_type::RowType,
+    },
+};
+
+use crate::{
+    rdb_router::{RdbRouter, ShardRouter},
+    Sinker,
+};
+
+/// Sinks rows to one of the targets of a sharded task.
+///
+/// Rows of a table go to the target whose tbs contains it, or to the default target. Rows of
+/// hash_tbs are spread to all targets by hash of their key values, an update changing the key
+/// values of a row becomes a delete on the old target and an insert on the new one.
+pub struct ShardSinker {
+    // targets[0] is the default target
+    pub targets: Vec<Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>>,
+    pub shard_router: ShardRouter,
+    pub router: Option<RdbRouter>,
+    // meta of the default target, tables are the same in all targets
+    pub meta_manager: RdbMetaManager,
+}
+
+#[async_trait]
+impl Sinker for ShardSinker {
+    async fn sink_dml(&mut self, data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        let mut target_data: Vec<Vec<RowData>> = vec![Vec::new(); self.targets.len()];
+        for row_data in data {
+            if let Some(index) = self
+                .shard_router
+                .get_target_by_tb(&row_data.schema, &row_data.tb)
+            {
+                target_data[index].push(row_data);
+                continue;
+            }
+
+            let before_index = match &row_data.before {
+                Some(_) => Some(self.get_target_by_hash(&row_data, false).await?),
+                None => None,
+            };
+            let after_index = match &row_data.after {
+                Some(_) => Some(self.get_target_by_hash(&row_data, true).await?),
+                None => None,
+            };
+            match (row_data.row_type.clone(), before_index, after_index) {
+                (RowType::Update, Some(before_index), Some(after_index))
+                    if before_index != after_index =>
+                {
+                    let (delete, insert) = row_data.split_update_row_data();
+                    target_data[before_index].push(delete);
+                    target_data[after_index].push(insert);
+                }
+                (_, _, Some(index)) | (_, Some(index), None) => target_data[index].push(row_data),
+                _ => bail!(Error::SinkerError(format!(
+                    "row of {}.{} has no values",
+                    row_data.schema, row_data.tb
+                ))),
+            }
+        }
+
+        for (index, data) in target_data.into_iter().enumerate() {
+            if data.is_empty() {
+                continue;
+            }
+            self.targets[index]
+                .lock()
+                .await
+                .sink_dml(data, batch)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, batch: bool) -> anyhow::Result<()> {
+        for ddl_data in data {
+            let (schema, tb) = ddl_data.get_schema_tb();
+            // ddls of databases and hash tables are needed by all targets
+            let index = if tb.is_empty() {
+                None
+            } else {
+                self.shard_router.get_target_by_tb(&schema, &tb)
+            };
+            match index {
+                Some(index) => {
+                    self.targets[index]
+                        .lock()
+                        .await
+                        .sink_ddl(vec![ddl_data], batch)
+                        .await?
+                }
+                None => {
+                    for target in self.targets.iter() {
+                        target
+                            .lock()
+                            .await
+                            .sink_ddl(vec![ddl_data.clone()], batch)
+                            .await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        for ddl_data in data.iter() {
+            self.meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+        }
+        for target in self.targets.iter() {
+            target.lock().await.refresh_meta(data.clone()).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_control_item(&mut self, item: &DtItem) -> anyhow::Result<()> {
+        for target in self.targets.iter() {
+            target.lock().await.handle_control_item(item).await?;
+        }
+        Ok(())
+    }
+
+    async fn sink_snapshot_watermark(&mut self, position: &Position) -> anyhow::Result<()> {
+        for target in self.targets.iter() {
+            target
+                .lock()
+                .await
+                .sink_snapshot_watermark(position)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        for target in self.targets.iter() {
+            target.lock().await.close().await?;
+        }
+        Ok(())
+    }
+}
+
+impl ShardSinker {
+    async fn get_target_by_hash(
+        &mut self,
+        row_data: &RowData,
+        after: bool,
+    ) -> anyhow::Result<usize> {
+        let (dst_schema, dst_tb) = match &self.router {
+            Some(router) => {
+                let (schema, tb) = router.get_tb_map(&row_data.schema, &row_data.tb);
+                (schema.to_string(), tb.to_string())
+            }
+            None => (row_data.schema.clone(), row_data.tb.clone()),
+        };
+        let tb_meta = self.meta_manager.get_tb_meta(&dst_schema, &dst_tb).await?;
+        if tb_meta.id_cols.is_empty() {
+            bail!(Error::SinkerError(format!(
+                "table {}.{} in [sinker].shard_hash_tbs has no primary / unique key",
+                row_data.schema, row_data.tb
+            )));
+        }
+
+        // columns in meta are of the target, values in rows are keyed by source columns
+        let col_map = self
+            .router
+            .as_ref()
+            .and_then(|router| router.reverse_get_col_map(&dst_schema, &dst_tb));
+        let col_values = if after {
+            row_data.after.as_ref()
+        } else {
+            row_data.before.as_ref()
+        };
+        let key_values: Vec<Option<String>> = tb_meta
+            .id_cols
+            .iter()
+            .map(|col| {
+                let src_col = col_map.and_then(|m| m.get(col)).unwrap_or(col);
+                col_values
+                    .and_then(|values| values.get(src_col))
+                    .and_then(|v| v.to_option_string())
+            })
+            .collect();
+        Ok(self.shard_router.get_target_by_hash(&key_values))
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+
+use dt_common::{
+    config::{
+        config_enums::{DbType, SqlHookErrorPolicy, SqlHookStage},
+        filter_config::FilterConfig,
+        sinker_config::SqlHookConfig,
+    },
+    log_info, log_warn,
+    meta::row_data::RowData,
+    rdb_filter::RdbFilter,
+    utils::sql_util::SqlUtil,
+};
+
+use crate::{rdb_router::RdbRouter, sinker::base_struct_sinker::DBConnPool};
+
+/// runs [sinker].sql_hooks on the target, shared by all sinkers of a task so that
+/// each table hook runs once no matter which sinker meets the table first
+pub struct SqlHook {
+    pub hooks: Vec<(SqlHookConfig, RdbFilter)>,
+    pub conn_pool: DBConnPool,
+    pub db_type: DbType,
+    pub router: Option<RdbRouter>,
+    // source tables whose before_tb / after_tb hooks have run
+    pub started_tbs: HashSet<(String, String)>,
+    pub finished_tbs: HashSet<(String, String)>,
+}
+
+impl SqlHook {
+    pub fn new(
+        configs: &[SqlHookConfig],
+        conn_pool: DBConnPool,
+        db_type: &DbType,
+        router: Option<RdbRouter>,
+    ) -> anyhow::Result<Self> {
+        let mut hooks = Vec::new();
+        for config in configs.iter() {
+            let filter_config = FilterConfig {
+                do_tbs: config.tbs.clone(),
+                ..Default::default()
+            };
+            let filter = RdbFilter::from_config(&filter_config, db_type)?;
+            hooks.push((config.clone(), filter));
+        }
+        Ok(Self {
+            hooks,
+            conn_pool,
+            db_type: db_type.clone(),
+            router,
+            started_tbs: HashSet::new(),
+            finished_tbs: HashSet::new(),
+        })
+    }
+
+    pub fn has_stage(&self, stage: &SqlHookStage) -> bool {
+        self.hooks.iter().any(|(config, _)| config.stage == *stage)
+    }
+
+    /// runs before_task / after_task hooks
+    pub async fn run_task_hooks(&self, stage: &SqlHookStage) -> anyhow::Result<()> {
+        for (config, _) in self.hooks.iter() {
+            if config.stage == *stage {
+                self.execute(config, &config.sql).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// runs before_tb hooks of tables met for the first time, rows carry routed target names
+    pub async fn before_rows(&mut self, data: &[RowData]) -> anyhow::Result<()> {
+        for row_data in data.iter() {
+            let (src_schema, src_tb) = match &self.router {
+                Some(router) => router.reverse_get_tb_map(&row_data.schema, &row_data.tb),
+                None => (row_data.schema.as_str(), row_data.tb.as_str()),
+            };
+            let key = (src_schema.to_string(), src_tb.to_string());
+            if self.started_tbs.contains(&key) {
+                continue;
+            }
+            self.run_tb_hooks(&SqlHookStage::BeforeTb, &key.0, &key.1)
+                .await?;
+            self.started_tbs.insert(key);
+        }
+        Ok(())
+    }
+
+    /// runs after_tb hooks once the snapshot of the source table is finished,
+    /// before_tb hooks run first if no rows of the table were met
+    pub async fn after_tb(&mut self, src_schema: &str, src_tb: &str) -> anyhow::Result<()> {
+        let key = (src_schema.to_string(), src_tb.to_string());
+        if self.finished_tbs.contains(&key) {
+            return Ok(());
+        }
+        if !self.started_tbs.contains(&key) {
+            self.run_tb_hooks(&SqlHookStage::BeforeTb, src_schema, src_tb)
+                .await?;
+            self.started_tbs.insert(key.clone());
+        }
+        self.run_tb_hooks(&SqlHookStage::AfterTb, src_schema, src_tb)
+            .await?;
+        self.finished_tbs.insert(key);
+        Ok(())
+    }
+
+    async fn run_tb_hooks(
+        &self,
+        stage: &SqlHookStage,
+        src_schema: &str,
+        src_tb: &str,
+    ) -> anyhow::Result<()> {
+        let (schema, tb) = match &self.router {
+            Some(router) => router.get_tb_map(src_schema, src_tb),
+            None => (src_schema.into(), src_tb.into()),
+        };
+        for (config, filter) in self.hooks.iter() {
+            if config.stage != *stage || filter.filter_tb(src_schema, src_tb) {
+                continue;
+            }
+            let sql = Self::render(&config.sql, &schema, &tb, &self.db_type);
+            self.execute(config, &sql).await?;
+        }
+        Ok(())
+    }
+
+    fn render(sql: &str, schema: &str, tb: &str, db_type: &DbType) -> String {
+        sql.replace("{schema}", &SqlUtil::quote_by_db_type(schema, db_type))
+            .replace("{tb}", &SqlUtil::quote_by_db_type(tb, db_type))
+    }
+
+    async fn execute(&self, config: &SqlHookConfig, sql: &str) -> anyhow::Result<()> {
+        log_info!("sql hook {} begin: {}", config.stage, sql);
+        let result = match &self.conn_pool {
+            DBConnPool::MySQL(pool) => sqlx::raw_sql(sql).execute(pool).await.map(|_| ()),
+            DBConnPool::PostgreSQL(pool) => sqlx::raw_sql(sql).execute(pool).await.map(|_| ()),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(error) => match config.on_error {
+                SqlHookErrorPolicy::Fail => {
+                    bail!(
+                        "sql hook {} failed, sql: {}, error: {}",
+                        config.stage,
+                        sql,
+                        error
+                    )
+                }
+                SqlHookErrorPolicy::Warn => {
+                    log_warn!(
+                        "sql hook {} failed, sql: {}, error: {}",
+                        config.stage,
+                        sql,
+                        error
+                    );
+                    Ok(())
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let sql = "ALTER TABLE {schema}.{tb} ENABLE KEYS";
+        assert_eq!(
+            SqlHook::render(sql, "db_1", "tb`1", &DbType::Mysql),
+            "ALTER TABLE `db_1`.`tb``1` ENABLE KEYS"
+        );
+        assert_eq!(
+            SqlHook::render("ANALYZE {schema}.{tb}", "public", "Tb_1", &DbType::Pg),
+            r#"ANALYZE "public"."Tb_1""#
+        );
+        assert_eq!(
+            SqlHook::render(
+                "SET GLOBAL innodb_flush_log_at_trx_commit=2",
+                "a",
+                "b",
+                &DbType::Mysql
+            ),
+            "SET GLOBAL innodb_flush_log_at_trx_commit=2"
+        );
+    }
+}
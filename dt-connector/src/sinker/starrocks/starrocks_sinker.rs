@@ -53,7 +53,8 @@ impl Sinker for StarRocksSinker {
         if !batch {
             self.serial_sink(data.as_mut_slice()).await?;
         } else {
-            call_batch_fn!(self, data, Self::batch_sink);
+            let batch_size = self.base_sinker.batch_size_for(&data, self.batch_size);
+            call_batch_fn!(self, data, Self::batch_sink, batch_size);
         }
         Ok(())
     }
@@ -1,11 +1,14 @@
-use std::cmp;
+use std::{cmp, collections::HashMap};
 
-use crate::{close_conn_pool, rdb_router::RdbRouter, Sinker};
+use crate::{
+    close_conn_pool, rdb_router::RdbRouter, sinker::base_struct_sinker::BaseStructSinker, Sinker,
+};
 
 use anyhow::bail;
+use chrono::{Datelike, Months, NaiveDate};
 use dt_common::{
-    config::config_enums::{ConflictPolicyEnum, DbType},
-    log_error, log_info,
+    config::config_enums::{ConflictPolicyEnum, DbType, TimePartitionUnit},
+    log_error, log_info, log_warn,
     meta::{
         mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
         pg::{pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
@@ -17,17 +20,21 @@ use dt_common::{
             structure::{column::Column, table::Table},
         },
     },
+    quote_mysql, quote_pg,
     rdb_filter::RdbFilter,
 };
 
 use async_trait::async_trait;
 use futures::TryStreamExt;
-use sqlx::{MySql, Pool};
+use sqlx::{MySql, Pool, Postgres, Row};
+
+use quote_mysql as quote;
 
 const SIGN_COL_NAME: &str = "_ape_dts_is_deleted";
 const SIGN_COL_TYPE: &str = "BOOLEAN";
 const TIMESTAMP_COL_NAME: &str = "_ape_dts_timestamp";
 const TIMESTAMP_COL_TYPE: &str = "BIGINT";
+const MAX_BUCKETS: u64 = 1024;
 
 #[derive(Clone)]
 pub struct StarrocksStructSinker {
@@ -38,6 +45,18 @@ pub struct StarrocksStructSinker {
     pub router: Option<RdbRouter>,
     pub extractor_meta_manager: RdbMetaManager,
     pub backend_count: i32,
+    pub bucket_size_mb: u64,
+    // key: (source schema, source tb), value: time column to create range partitions on
+    pub partition_cols: HashMap<(String, String), String>,
+    pub partition_unit: TimePartitionUnit,
+}
+
+/// Bucket count and time partitions computed from source statistics before bulk load.
+#[derive(Default)]
+struct TableLayout {
+    buckets: Option<u64>,
+    // (col, start, end), end is exclusive
+    partition: Option<(String, NaiveDate, NaiveDate)>,
 }
 
 #[async_trait]
@@ -51,13 +70,17 @@ impl Sinker for StarrocksStructSinker {
             match i.statement {
                 StructStatement::MysqlCreateDatabase(statement) => {
                     let sql = format!(
-                        "CREATE DATABASE IF NOT EXISTS `{}`",
-                        statement.database.name
+                        "CREATE DATABASE IF NOT EXISTS {}",
+                        quote!(statement.database.name)
                     );
                     self.execute_sql(&sql).await?;
                 }
 
                 StructStatement::MysqlCreateTable(statement) => {
+                    BaseStructSinker::warn_skipped_constraints(
+                        &statement.constraints,
+                        &self.db_type,
+                    );
                     let (schema, tb) = if let Some(router) = &self.router {
                         router.reverse_get_tb_map(
                             &statement.table.database_name,
@@ -73,18 +96,38 @@ impl Sinker for StarrocksStructSinker {
                         self.extractor_meta_manager.mysql_meta_manager.as_mut()
                     {
                         let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
-                        let sql =
-                            self.get_create_table_sql(&statement.table, Some(&tb_meta), None)?;
+                        let conn_pool = meta_manager.meta_fetcher.conn_pool.clone();
+                        let layout = self
+                            .get_table_layout(
+                                SourcePool::Mysql(&conn_pool),
+                                schema,
+                                tb,
+                                Self::get_key_cols(&tb_meta.basic),
+                            )
+                            .await?;
+                        let sql = self.get_create_table_sql(
+                            &statement.table,
+                            Some(&tb_meta),
+                            None,
+                            &layout,
+                        )?;
                         self.execute_sql(&sql).await?;
                     }
                 }
 
                 StructStatement::PgCreateSchema(statement) => {
-                    let sql = format!("CREATE DATABASE IF NOT EXISTS `{}`", statement.schema.name);
+                    let sql = format!(
+                        "CREATE DATABASE IF NOT EXISTS {}",
+                        quote!(statement.schema.name)
+                    );
                     self.execute_sql(&sql).await?;
                 }
 
                 StructStatement::PgCreateTable(statement) => {
+                    BaseStructSinker::warn_skipped_constraints(
+                        &statement.constraints,
+                        &self.db_type,
+                    );
                     let (schema, tb) = if let Some(router) = &self.router {
                         router.reverse_get_tb_map(
                             &statement.table.schema_name,
@@ -99,8 +142,21 @@ impl Sinker for StarrocksStructSinker {
                     if let Some(meta_manager) = self.extractor_meta_manager.pg_meta_manager.as_mut()
                     {
                         let tb_meta = meta_manager.get_tb_meta(schema, tb).await?.to_owned();
-                        let sql =
-                            self.get_create_table_sql(&statement.table, None, Some(&tb_meta))?;
+                        let conn_pool = meta_manager.conn_pool.clone();
+                        let layout = self
+                            .get_table_layout(
+                                SourcePool::Pg(&conn_pool),
+                                schema,
+                                tb,
+                                Self::get_key_cols(&tb_meta.basic),
+                            )
+                            .await?;
+                        let sql = self.get_create_table_sql(
+                            &statement.table,
+                            None,
+                            Some(&tb_meta),
+                            &layout,
+                        )?;
                         self.execute_sql(&sql).await?;
                     }
                 }
@@ -124,6 +180,7 @@ impl StarrocksStructSinker {
         table: &Table,
         mysql_tb_meta: Option<&MysqlTbMeta>,
         pg_tb_meta: Option<&PgTbMeta>,
+        layout: &TableLayout,
     ) -> anyhow::Result<String> {
         let rdb_tb_meta = if let Some(tb_meta) = pg_tb_meta {
             &tb_meta.basic
@@ -131,34 +188,44 @@ impl StarrocksStructSinker {
             &mysql_tb_meta.as_ref().unwrap().basic
         };
 
+        let key_cols = Self::get_key_cols(rdb_tb_meta);
         let mut dst_cols = vec![];
+        let mut hash_col = key_cols.first();
 
         // In StarRocks:
         // Key columns must be the first few columns of the schema and the order
         // of the key columns must be consistent with the order of the schema.
-        for col in rdb_tb_meta.id_cols.iter() {
+        for col in key_cols.iter() {
             for column in table.columns.iter() {
                 if column.column_name == *col {
-                    dst_cols.push(self.get_dst_col(
-                        column,
-                        mysql_tb_meta,
-                        pg_tb_meta,
-                        rdb_tb_meta,
-                    )?);
+                    let (dst_col, _) =
+                        self.get_dst_col(column, mysql_tb_meta, pg_tb_meta, key_cols)?;
+                    dst_cols.push(dst_col);
                 }
             }
         }
 
         for column in table.columns.iter() {
-            if !rdb_tb_meta.id_cols.contains(&column.column_name) {
-                dst_cols.push(self.get_dst_col(column, mysql_tb_meta, pg_tb_meta, rdb_tb_meta)?);
+            if !key_cols.contains(&column.column_name) {
+                let (dst_col, dst_col_type) =
+                    self.get_dst_col(column, mysql_tb_meta, pg_tb_meta, key_cols)?;
+                dst_cols.push(dst_col);
+                // tables without key are created as duplicate key tables,
+                // distributed by the first column which can be hashed
+                if hash_col.is_none() && Self::is_hashable(&dst_col_type) {
+                    hash_col = Some(&column.column_name);
+                }
             }
         }
 
         // sign and timestamp cols
         if self.db_type == DbType::StarRocks {
-            dst_cols.push(format!("`{}` {}", SIGN_COL_NAME, SIGN_COL_TYPE));
-            dst_cols.push(format!("`{}` {}", TIMESTAMP_COL_NAME, TIMESTAMP_COL_TYPE));
+            dst_cols.push(format!("{} {}", quote!(SIGN_COL_NAME), SIGN_COL_TYPE));
+            dst_cols.push(format!(
+                "{} {}",
+                quote!(TIMESTAMP_COL_NAME),
+                TIMESTAMP_COL_TYPE
+            ));
         }
 
         let schema = if mysql_tb_meta.is_some() {
@@ -167,17 +234,16 @@ impl StarrocksStructSinker {
             &table.schema_name
         };
         let mut sql = format!(
-            "CREATE TABLE IF NOT EXISTS `{}`.`{}` ({})",
-            schema,
-            table.table_name,
+            "CREATE TABLE IF NOT EXISTS {}.{} ({})",
+            quote!(schema),
+            quote!(table.table_name),
             dst_cols.join(", "),
         );
 
-        if !rdb_tb_meta.id_cols.is_empty() {
-            let primary_keys = rdb_tb_meta
-                .id_cols
+        if !key_cols.is_empty() {
+            let primary_keys = key_cols
                 .iter()
-                .map(|i| format!("`{}`", i))
+                .map(|i| quote!(i).to_string())
                 .collect::<Vec<String>>()
                 .join(",");
 
@@ -186,11 +252,19 @@ impl StarrocksStructSinker {
             } else {
                 sql = format!("{} PRIMARY KEY ({})", sql, primary_keys);
             }
+        }
 
-            if !table.table_comment.is_empty() {
-                sql = format!("{} COMMENT '{}'", sql, table.table_comment);
+        if !table.table_comment.is_empty() {
+            sql = format!("{} COMMENT '{}'", sql, Self::escape(&table.table_comment));
+        }
+        if let Some((col, start, end)) = &layout.partition {
+            sql = format!("{} {}", sql, self.get_partition_clause(col, start, end));
+        }
+        if let Some(hash_col) = hash_col {
+            sql = format!("{} DISTRIBUTED BY HASH({})", sql, quote!(hash_col));
+            if let Some(buckets) = layout.buckets {
+                sql = format!("{} BUCKETS {}", sql, buckets);
             }
-            sql = format!("{} DISTRIBUTED BY HASH(`{}`)", sql, rdb_tb_meta.id_cols[0]);
         }
 
         if self.backend_count < 3 {
@@ -199,13 +273,35 @@ impl StarrocksStructSinker {
         Ok(sql)
     }
 
+    // primary key, or the unique key picked as id cols, empty if the table has no key,
+    // in which case id cols are all cols and can not be used as the key of target tables
+    fn get_key_cols(rdb_tb_meta: &RdbTbMeta) -> &[String] {
+        if rdb_tb_meta.key_map.is_empty() {
+            &[]
+        } else {
+            &rdb_tb_meta.id_cols
+        }
+    }
+
+    fn is_hashable(dst_col_type: &str) -> bool {
+        !matches!(
+            dst_col_type,
+            "FLOAT" | "DOUBLE" | "STRING" | "JSON" | "VARBINARY" | "BOOLEAN"
+        )
+    }
+
+    fn escape(comment: &str) -> String {
+        comment.replace('\\', "\\\\").replace('\'', "\\'")
+    }
+
+    // (column definition, column type)
     fn get_dst_col(
         &self,
         column: &Column,
         mysql_tb_meta: Option<&MysqlTbMeta>,
         pg_tb_meta: Option<&PgTbMeta>,
-        rdb_tb_meta: &RdbTbMeta,
-    ) -> anyhow::Result<String> {
+        key_cols: &[String],
+    ) -> anyhow::Result<(String, String)> {
         let col = &column.column_name;
         let dst_col_type = if let Some(tb_meta) = mysql_tb_meta {
             self.get_dst_col_type_from_mysql(col, tb_meta)
@@ -224,19 +320,26 @@ impl StarrocksStructSinker {
         // At this point, attempting to delete the data using the following stream_load method will fail
         // because the value of f_1 field (null) is invalid:
         //      curl -X PUT "http://127.0.0.1:8042/api/public/tb_1/_stream_load" -H "Expect: 100-continue" -H "format: json"  -H "strip_outer_array: true" -H "timezone: UTC" -H "merge_type: delete" -u "root:" -d '[{"id": 1, value: null}]'
-        let mut is_nullable = pg_tb_meta.is_some() && !rdb_tb_meta.id_cols.contains(col);
+        let is_key = key_cols.contains(col);
+        let mut is_nullable = pg_tb_meta.is_some() && !is_key;
         is_nullable |= column.is_nullable;
+        // key columns of StarRocks / Doris can not be nullable, a unique key may have nullable columns
+        is_nullable &= !is_key;
         let mut dst_col = if is_nullable {
-            format!("`{}` {}", col, dst_col_type)
+            format!("{} {}", quote!(col), dst_col_type)
         } else {
-            format!("`{}` {} NOT NULL", col, dst_col_type)
+            format!("{} {} NOT NULL", quote!(col), dst_col_type)
         };
 
         if !column.column_comment.is_empty() {
-            dst_col = format!("{} COMMENT '{}'", dst_col, column.column_comment);
+            dst_col = format!(
+                "{} COMMENT '{}'",
+                dst_col,
+                Self::escape(&column.column_comment)
+            );
         }
 
-        Ok(dst_col)
+        Ok((dst_col, dst_col_type))
     }
 
     fn get_dst_col_type_from_mysql(
@@ -365,6 +468,91 @@ impl StarrocksStructSinker {
         Ok(dst_col.to_string())
     }
 
+    async fn get_table_layout(
+        &self,
+        source: SourcePool<'_>,
+        schema: &str,
+        tb: &str,
+        id_cols: &[String],
+    ) -> anyhow::Result<TableLayout> {
+        let mut layout = TableLayout::default();
+        // buckets / partitions are only set along with DISTRIBUTED BY HASH on key columns
+        if id_cols.is_empty() {
+            return Ok(layout);
+        }
+
+        if self.bucket_size_mb > 0 {
+            let data_size = source.get_data_size(schema, tb).await?;
+            layout.buckets = Some(Self::calc_buckets(data_size, self.bucket_size_mb));
+        }
+
+        if let Some(col) = self.partition_cols.get(&(schema.into(), tb.into())) {
+            // partition columns of primary / unique key tables must be key columns
+            if !id_cols.contains(col) {
+                log_warn!(
+                    "partition col: {} is not a key col of {}.{}, skip creating partitions",
+                    col,
+                    schema,
+                    tb
+                );
+            } else if let Some((min, max)) = source.get_date_range(schema, tb, col).await? {
+                let (start, end) = Self::calc_partition_range(min, max, &self.partition_unit);
+                layout.partition = Some((col.clone(), start, end));
+            }
+        }
+        Ok(layout)
+    }
+
+    fn calc_buckets(data_size: u64, bucket_size_mb: u64) -> u64 {
+        let bucket_size = bucket_size_mb * 1024 * 1024;
+        data_size.div_ceil(bucket_size).clamp(1, MAX_BUCKETS)
+    }
+
+    fn calc_partition_range(
+        min: NaiveDate,
+        max: NaiveDate,
+        unit: &TimePartitionUnit,
+    ) -> (NaiveDate, NaiveDate) {
+        let floor = |date: NaiveDate| match unit {
+            TimePartitionUnit::Day => date,
+            TimePartitionUnit::Month => date.with_day(1).unwrap(),
+            TimePartitionUnit::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+        };
+        let end = floor(max);
+        let end = match unit {
+            TimePartitionUnit::Day => end.succ_opt().unwrap_or(end),
+            TimePartitionUnit::Month => end + Months::new(1),
+            TimePartitionUnit::Year => end + Months::new(12),
+        };
+        (floor(min), end)
+    }
+
+    fn get_partition_clause(&self, col: &str, start: &NaiveDate, end: &NaiveDate) -> String {
+        let unit = match self.partition_unit {
+            TimePartitionUnit::Day => "DAY",
+            TimePartitionUnit::Month => "MONTH",
+            TimePartitionUnit::Year => "YEAR",
+        };
+        let (start, end) = (start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+        if self.db_type == DbType::Doris {
+            format!(
+                r#"PARTITION BY RANGE({}) (FROM ("{}") TO ("{}") INTERVAL 1 {})"#,
+                quote!(col),
+                start,
+                end,
+                unit
+            )
+        } else {
+            format!(
+                r#"PARTITION BY RANGE({}) (START ("{}") END ("{}") EVERY (INTERVAL 1 {}))"#,
+                quote!(col),
+                start,
+                end,
+                unit
+            )
+        }
+    }
+
     async fn get_backend_count(&self) -> anyhow::Result<i32> {
         let sql = "SHOW BACKENDS";
         let mut count = 0;
@@ -394,3 +582,117 @@ impl StarrocksStructSinker {
         Ok(())
     }
 }
+
+enum SourcePool<'a> {
+    Mysql(&'a Pool<MySql>),
+    Pg(&'a Pool<Postgres>),
+}
+
+impl SourcePool<'_> {
+    // bytes of table data from catalog statistics, indexes excluded
+    async fn get_data_size(&self, schema: &str, tb: &str) -> anyhow::Result<u64> {
+        let size: Option<i64> = match self {
+            Self::Mysql(conn_pool) => {
+                let sql = "SELECT CAST(COALESCE(data_length, 0) AS SIGNED) FROM information_schema.tables WHERE table_schema = ? AND table_name = ?";
+                sqlx::query(sql)
+                    .bind(schema)
+                    .bind(tb)
+                    .fetch_optional(*conn_pool)
+                    .await?
+                    .map(|row| row.try_get(0))
+                    .transpose()?
+            }
+            Self::Pg(conn_pool) => {
+                let sql = "SELECT pg_relation_size(c.oid) FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace WHERE n.nspname = $1 AND c.relname = $2";
+                sqlx::query(sql)
+                    .bind(schema)
+                    .bind(tb)
+                    .fetch_optional(*conn_pool)
+                    .await?
+                    .map(|row| row.try_get(0))
+                    .transpose()?
+            }
+        };
+        Ok(size.unwrap_or(0).max(0) as u64)
+    }
+
+    // min / max dates of the column, None if the table is empty
+    async fn get_date_range(
+        &self,
+        schema: &str,
+        tb: &str,
+        col: &str,
+    ) -> anyhow::Result<Option<(NaiveDate, NaiveDate)>> {
+        let row = match self {
+            Self::Mysql(conn_pool) => {
+                let sql = format!(
+                    "SELECT DATE_FORMAT(MIN({col}), '%Y-%m-%d'), DATE_FORMAT(MAX({col}), '%Y-%m-%d') FROM {schema}.{tb}",
+                    col = quote!(col),
+                    schema = quote!(schema),
+                    tb = quote!(tb)
+                );
+                let row = sqlx::query(&sql).fetch_one(*conn_pool).await?;
+                (
+                    row.try_get::<Option<String>, _>(0)?,
+                    row.try_get::<Option<String>, _>(1)?,
+                )
+            }
+            Self::Pg(conn_pool) => {
+                let sql = format!(
+                    "SELECT to_char(MIN({col}), 'YYYY-MM-DD'), to_char(MAX({col}), 'YYYY-MM-DD') FROM {schema}.{tb}",
+                    col = quote_pg!(col),
+                    schema = quote_pg!(schema),
+                    tb = quote_pg!(tb)
+                );
+                let row = sqlx::query(&sql).fetch_one(*conn_pool).await?;
+                (
+                    row.try_get::<Option<String>, _>(0)?,
+                    row.try_get::<Option<String>, _>(1)?,
+                )
+            }
+        };
+        match row {
+            (Some(min), Some(max)) => Ok(Some((
+                NaiveDate::parse_from_str(&min, "%Y-%m-%d")?,
+                NaiveDate::parse_from_str(&max, "%Y-%m-%d")?,
+            ))),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_buckets() {
+        assert_eq!(StarrocksStructSinker::calc_buckets(0, 1024), 1);
+        assert_eq!(
+            StarrocksStructSinker::calc_buckets(10 * 1024 * 1024 * 1024 + 1, 1024),
+            11
+        );
+        assert_eq!(
+            StarrocksStructSinker::calc_buckets(u32::MAX as u64 * 1024, 1),
+            MAX_BUCKETS
+        );
+    }
+
+    #[test]
+    fn test_calc_partition_range() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let (min, max) = (date("2023-03-15"), date("2024-12-31"));
+        assert_eq!(
+            StarrocksStructSinker::calc_partition_range(min, max, &TimePartitionUnit::Day),
+            (date("2023-03-15"), date("2025-01-01"))
+        );
+        assert_eq!(
+            StarrocksStructSinker::calc_partition_range(min, max, &TimePartitionUnit::Month),
+            (date("2023-03-01"), date("2025-01-01"))
+        );
+        assert_eq!(
+            StarrocksStructSinker::calc_partition_range(min, max, &TimePartitionUnit::Year),
+            (date("2023-01-01"), date("2025-01-01"))
+        );
+    }
+}
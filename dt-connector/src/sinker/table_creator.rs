@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use anyhow::bail;
+use sqlx::Row;
+
+use dt_common::{
+    error::Error,
+    log_info,
+    meta::{
+        row_data::RowData,
+        struct_meta::{
+            statement::struct_statement::StructStatement,
+            struct_data::StructData,
+            structure::constraint::{Constraint, ConstraintType},
+        },
+    },
+    rdb_filter::RdbFilter,
+};
+
+use crate::{
+    meta_fetcher::{
+        mysql::mysql_struct_fetcher::MysqlStructFetcher, pg::pg_struct_fetcher::PgStructFetcher,
+    },
+    rdb_router::RdbRouter,
+    sinker::base_struct_sinker::{BaseStructSinker, DBConnPool},
+};
+
+pub enum SourceStructFetcher {
+    Mysql(MysqlStructFetcher),
+    Pg(PgStructFetcher),
+}
+
+/// creates missing target tables from source structures before rows are applied,
+/// used when struct migration is not done in advance and [sinker].auto_create_table=true
+pub struct TableCreator {
+    pub fetcher: SourceStructFetcher,
+    pub target_conn_pool: DBConnPool,
+    pub router: Option<RdbRouter>,
+    pub filter: RdbFilter,
+    // target tables known to exist
+    pub existing_tbs: HashSet<(String, String)>,
+}
+
+impl TableCreator {
+    /// returns the target tables created by this call
+    pub async fn ensure_tables(
+        &mut self,
+        data: &[RowData],
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        let mut created = Vec::new();
+        for row_data in data.iter() {
+            let key = (row_data.schema.clone(), row_data.tb.clone());
+            if self.existing_tbs.contains(&key) {
+                continue;
+            }
+
+            if !self.target_tb_exists(&key.0, &key.1).await? {
+                self.create_table(&key.0, &key.1).await?;
+                created.push(key.clone());
+            }
+            self.existing_tbs.insert(key);
+        }
+        Ok(created)
+    }
+
+    /// creates the target table from its source, schema and tb are routed names
+    pub async fn create_table(&mut self, schema: &str, tb: &str) -> anyhow::Result<()> {
+        let (src_schema, src_tb) = match &self.router {
+            Some(router) => router.reverse_get_tb_map(schema, tb),
+            None => (schema, tb),
+        };
+        let (src_schema, src_tb) = (src_schema.to_string(), src_tb.to_string());
+
+        let mut statements = Vec::new();
+        match &mut self.fetcher {
+            SourceStructFetcher::Mysql(fetcher) => {
+                fetcher.dbs = HashSet::from([src_schema.clone()]);
+                for statement in fetcher.get_create_database_statements(&src_schema).await? {
+                    statements.push(StructStatement::MysqlCreateDatabase(statement));
+                }
+                for mut statement in fetcher
+                    .get_create_table_statements(&src_schema, &src_tb)
+                    .await?
+                {
+                    Self::remove_foreign_keys(&mut statement.constraints);
+                    statements.push(StructStatement::MysqlCreateTable(statement));
+                }
+            }
+
+            SourceStructFetcher::Pg(fetcher) => {
+                fetcher.schemas = HashSet::from([src_schema.clone()]);
+                for statement in fetcher.get_create_schema_statements(&src_schema).await? {
+                    statements.push(StructStatement::PgCreateSchema(statement));
+                }
+                for mut statement in fetcher
+                    .get_create_table_statements(&src_schema, &src_tb)
+                    .await?
+                {
+                    Self::remove_foreign_keys(&mut statement.constraints);
+                    statements.push(StructStatement::PgCreateTable(statement));
+                }
+            }
+        }
+
+        if !statements.iter().any(|s| {
+            matches!(
+                s,
+                StructStatement::MysqlCreateTable(_) | StructStatement::PgCreateTable(_)
+            )
+        }) {
+            bail! {Error::Unexpected(format!(
+                "auto create table failed, source table not found: {}.{}",
+                src_schema, src_tb
+            ))}
+        }
+
+        log_info!(
+            "auto create table: {}.{} from source: {}.{}",
+            schema,
+            tb,
+            src_schema,
+            src_tb
+        );
+        for statement in statements {
+            let struct_data = StructData {
+                schema: src_schema.clone(),
+                statement,
+            };
+            let mut struct_data = match &self.router {
+                Some(router) => router.route_struct(struct_data),
+                None => struct_data,
+            };
+            for (_, sql) in struct_data.statement.to_sqls(&self.filter)? {
+                log_info!("auto create table, ddl: {}", sql);
+                BaseStructSinker::execute(&self.target_conn_pool, &sql).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn target_tb_exists(&self, schema: &str, tb: &str) -> anyhow::Result<bool> {
+        let count: i64 = match &self.target_conn_pool {
+            DBConnPool::MySQL(pool) => {
+                let sql = "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = ? AND table_name = ?";
+                sqlx::query(sql)
+                    .bind(schema)
+                    .bind(tb)
+                    .fetch_one(pool)
+                    .await?
+                    .try_get(0)?
+            }
+            DBConnPool::PostgreSQL(pool) => {
+                let sql = "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = $1 AND table_name = $2";
+                sqlx::query(sql)
+                    .bind(schema)
+                    .bind(tb)
+                    .fetch_one(pool)
+                    .await?
+                    .try_get(0)?
+            }
+        };
+        Ok(count > 0)
+    }
+
+    // referenced tables may not exist in target yet
+    fn remove_foreign_keys(constraints: &mut Vec<Constraint>) {
+        constraints.retain(|c| c.constraint_type != ConstraintType::Foreign);
+    }
+}
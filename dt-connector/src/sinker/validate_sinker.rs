@@ -0,0 +1,728 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use strum::IntoStaticStr;
+use tokio::sync::Mutex;
+
+use crate::{checker::check_log::to_json_line, rdb_query_builder::RdbQueryBuilder, Sinker};
+use dt_common::{
+    log_info, log_report, log_summary, log_warn,
+    meta::{
+        col_value::ColValue,
+        mysql::{mysql_col_type::MysqlColType, mysql_tb_meta::MysqlTbMeta},
+        pg::{pg_col_type::PgColType, pg_tb_meta::PgTbMeta, pg_value_type::PgValueType},
+        rdb_meta_manager::RdbMetaManager,
+        rdb_tb_meta::RdbTbMeta,
+        row_data::RowData,
+        row_type::RowType,
+    },
+};
+
+const MAX_EXAMPLE_LEN: usize = 64;
+
+/// Runs the rows through target meta fetching, statement building and value binding
+/// like the write sinkers, but never writes. Problems a real run would hit are collected
+/// for each table / column and written to report.log when the task finishes.
+#[derive(Clone)]
+pub struct ValidateSinker {
+    pub meta_manager: RdbMetaManager,
+    // rows validated for each table, the rest are ignored
+    pub sample_rows: usize,
+    // shared by all parallel sinkers
+    pub report: Arc<Mutex<ValidateReport>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, IntoStaticStr)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProblemKind {
+    // target table does not exist or its meta can not be fetched
+    NoTargetTable,
+    // source column does not exist in target, its values are dropped
+    NotInTarget,
+    // target column is required but not provided by source
+    NotInSource,
+    NotNull,
+    OutOfRange,
+    Truncated,
+    InvalidValue,
+    // statement building or value binding failed
+    BuildFailed,
+}
+
+#[derive(Default)]
+pub struct ValidateReport {
+    sampled: HashMap<(String, String), usize>,
+    // (schema, tb, col, kind) -> problem, col is empty for table level problems
+    problems: BTreeMap<(String, String, String, ProblemKind), ProblemStat>,
+    reported: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProblemStat {
+    pub rows: u64,
+    pub example: String,
+}
+
+#[derive(Serialize)]
+struct ValidateSummary {
+    is_valid: bool,
+    tables: usize,
+    rows: usize,
+    problems: Vec<ProblemLog>,
+}
+
+#[derive(Serialize)]
+struct ProblemLog {
+    schema: String,
+    tb: String,
+    col: String,
+    kind: ProblemKind,
+    rows: u64,
+    example: String,
+}
+
+struct Problem {
+    col: String,
+    kind: ProblemKind,
+    detail: String,
+}
+
+impl Problem {
+    fn new(col: &str, kind: ProblemKind, detail: String) -> Self {
+        Self {
+            col: col.to_string(),
+            kind,
+            detail,
+        }
+    }
+}
+
+#[async_trait]
+impl Sinker for ValidateSinker {
+    async fn sink_dml(&mut self, data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+        for row_data in data.iter() {
+            if !self
+                .report
+                .lock()
+                .await
+                .take_sample(row_data, self.sample_rows)
+            {
+                continue;
+            }
+            let problems = self.validate_row(row_data).await;
+            self.report.lock().await.add_problems(row_data, problems);
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        // all sinkers have finished sinking before any of them is closed
+        self.report.lock().await.write();
+        self.meta_manager.close().await
+    }
+}
+
+impl ValidateSinker {
+    async fn validate_row(&mut self, row_data: &RowData) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        if let Some(meta_manager) = self.meta_manager.mysql_meta_manager.as_mut() {
+            match meta_manager.get_tb_meta_by_row_data(row_data).await {
+                Ok(tb_meta) => Self::validate_mysql_row(tb_meta, row_data, &mut problems),
+                Err(err) => problems.push(Self::no_target_table(err)),
+            }
+        } else if let Some(meta_manager) = self.meta_manager.pg_meta_manager.as_mut() {
+            match meta_manager.get_tb_meta_by_row_data(row_data).await {
+                Ok(tb_meta) => Self::validate_pg_row(tb_meta, row_data, &mut problems),
+                Err(err) => problems.push(Self::no_target_table(err)),
+            }
+        }
+        problems
+    }
+
+    fn no_target_table(err: anyhow::Error) -> Problem {
+        Problem::new(
+            "",
+            ProblemKind::NoTargetTable,
+            format!("failed to get target table meta: {}", err),
+        )
+    }
+
+    fn validate_mysql_row(tb_meta: &MysqlTbMeta, row_data: &RowData, problems: &mut Vec<Problem>) {
+        let Some(col_values) = Self::get_col_values(row_data) else {
+            return;
+        };
+        Self::validate_cols(&tb_meta.basic, &tb_meta.generated_cols, row_data, problems);
+        for (col, value) in col_values.iter() {
+            let Some(col_type) = tb_meta.col_type_map.get(col) else {
+                continue;
+            };
+            if tb_meta.generated_cols.contains(col) {
+                continue;
+            }
+            if let Some(problem) = Self::check_null(&tb_meta.basic, col, value) {
+                problems.push(problem);
+            } else if let Some((kind, detail)) = check_mysql_value(col_type, value) {
+                problems.push(Problem::new(col, kind, detail));
+            }
+        }
+
+        let query_builder = RdbQueryBuilder::new_for_mysql(tb_meta, None);
+        if let Err(err) = query_builder
+            .get_query_info(row_data, false)
+            .and_then(|query_info| query_builder.create_mysql_query(&query_info).map(|_| ()))
+        {
+            problems.push(Problem::new("", ProblemKind::BuildFailed, err.to_string()));
+        }
+    }
+
+    fn validate_pg_row(tb_meta: &PgTbMeta, row_data: &RowData, problems: &mut Vec<Problem>) {
+        let Some(col_values) = Self::get_col_values(row_data) else {
+            return;
+        };
+        Self::validate_cols(&tb_meta.basic, &HashSet::new(), row_data, problems);
+        for (col, value) in col_values.iter() {
+            let Some(col_type) = tb_meta.col_type_map.get(col) else {
+                continue;
+            };
+            if let Some(problem) = Self::check_null(&tb_meta.basic, col, value) {
+                problems.push(problem);
+            } else if let Some((kind, detail)) = check_pg_value(col_type, value) {
+                problems.push(Problem::new(col, kind, detail));
+            }
+        }
+
+        let query_builder = RdbQueryBuilder::new_for_pg(tb_meta, None);
+        if let Err(err) = query_builder
+            .get_query_info(row_data, false)
+            .and_then(|query_info| query_builder.create_pg_query(&query_info).map(|_| ()))
+        {
+            problems.push(Problem::new("", ProblemKind::BuildFailed, err.to_string()));
+        }
+    }
+
+    fn get_col_values(row_data: &RowData) -> Option<&HashMap<String, ColValue>> {
+        match row_data.row_type {
+            RowType::Delete => row_data.before.as_ref(),
+            _ => row_data.after.as_ref(),
+        }
+    }
+
+    fn validate_cols(
+        tb_meta: &RdbTbMeta,
+        generated_cols: &HashSet<String>,
+        row_data: &RowData,
+        problems: &mut Vec<Problem>,
+    ) {
+        let Some(col_values) = Self::get_col_values(row_data) else {
+            return;
+        };
+        for col in col_values.keys() {
+            if !tb_meta.has_col(col) {
+                problems.push(Problem::new(
+                    col,
+                    ProblemKind::NotInTarget,
+                    "source column not in target, its values are dropped".into(),
+                ));
+            }
+        }
+
+        for col in tb_meta.cols.iter() {
+            if col_values.contains_key(col) || generated_cols.contains(col) {
+                continue;
+            }
+            let detail = match row_data.row_type {
+                // inserts write NULL for every target column missing in source
+                RowType::Insert if !tb_meta.is_col_nullable(col) => {
+                    "target column is NOT NULL but not in source"
+                }
+                RowType::Update | RowType::Delete if tb_meta.id_cols.contains(col) => {
+                    "target key column not in source, rows can not be located"
+                }
+                _ => continue,
+            };
+            problems.push(Problem::new(col, ProblemKind::NotInSource, detail.into()));
+        }
+    }
+
+    fn check_null(tb_meta: &RdbTbMeta, col: &str, value: &ColValue) -> Option<Problem> {
+        if matches!(value, ColValue::None) && !tb_meta.is_col_nullable(col) {
+            return Some(Problem::new(
+                col,
+                ProblemKind::NotNull,
+                "NULL into NOT NULL target column".into(),
+            ));
+        }
+        None
+    }
+}
+
+impl ValidateReport {
+    fn take_sample(&mut self, row_data: &RowData, sample_rows: usize) -> bool {
+        let count = self
+            .sampled
+            .entry((row_data.schema.clone(), row_data.tb.clone()))
+            .or_default();
+        if *count >= sample_rows {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    fn add_problems(&mut self, row_data: &RowData, problems: Vec<Problem>) {
+        for problem in problems {
+            self.problems
+                .entry((
+                    row_data.schema.clone(),
+                    row_data.tb.clone(),
+                    problem.col,
+                    problem.kind,
+                ))
+                .or_insert_with(|| ProblemStat {
+                    rows: 0,
+                    example: problem.detail,
+                })
+                .rows += 1;
+        }
+    }
+
+    fn write(&mut self) {
+        if self.reported {
+            return;
+        }
+        self.reported = true;
+
+        let mut problems = Vec::with_capacity(self.problems.len());
+        for ((schema, tb, col, kind), stat) in self.problems.iter() {
+            let kind_name: &str = kind.into();
+            let target = if col.is_empty() {
+                format!("{}.{}", schema, tb)
+            } else {
+                format!("{}.{}.{}", schema, tb, col)
+            };
+            log_report!(
+                "[{}] {}, rows: {}, e.g. {}",
+                kind_name,
+                target,
+                stat.rows,
+                stat.example
+            );
+            problems.push(ProblemLog {
+                schema: schema.clone(),
+                tb: tb.clone(),
+                col: col.clone(),
+                kind: *kind,
+                rows: stat.rows,
+                example: stat.example.clone(),
+            });
+        }
+
+        let summary = ValidateSummary {
+            is_valid: problems.is_empty(),
+            tables: self.sampled.len(),
+            rows: self.sampled.values().sum(),
+            problems,
+        };
+        if summary.is_valid {
+            log_info!(
+                "validation passed, tables: {}, rows: {}",
+                summary.tables,
+                summary.rows
+            );
+        } else {
+            log_warn!(
+                "validation found {} problems, tables: {}, rows: {}, see report.log for details",
+                summary.problems.len(),
+                summary.tables,
+                summary.rows
+            );
+        }
+        if let Some(log) = to_json_line(&summary) {
+            log_summary!("{}", log);
+        }
+    }
+}
+
+fn example(value: &ColValue) -> String {
+    let value = value.to_utf8_or_hex_string().unwrap_or("NULL".into());
+    if value.chars().count() <= MAX_EXAMPLE_LEN {
+        return value;
+    }
+    format!(
+        "{}...",
+        value.chars().take(MAX_EXAMPLE_LEN).collect::<String>()
+    )
+}
+
+/// conversion problems of writing the value into a mysql column
+pub fn check_mysql_value(
+    col_type: &MysqlColType,
+    value: &ColValue,
+) -> Option<(ProblemKind, String)> {
+    if matches!(value, ColValue::None | ColValue::UnchangedToast) {
+        return None;
+    }
+    match col_type {
+        MysqlColType::TinyInt { unsigned } => check_integer(value, int_range(8, *unsigned)),
+        MysqlColType::SmallInt { unsigned } => check_integer(value, int_range(16, *unsigned)),
+        MysqlColType::MediumInt { unsigned } => check_integer(value, int_range(24, *unsigned)),
+        MysqlColType::Int { unsigned } => check_integer(value, int_range(32, *unsigned)),
+        MysqlColType::BigInt { unsigned } => check_integer(value, int_range(64, *unsigned)),
+        MysqlColType::Decimal { precision, scale } => check_decimal(value, *precision, *scale),
+        MysqlColType::Year => match check_integer(value, (0, 2155)) {
+            None if matches!(integer_of(value), Some(v) if v != 0 && v < 1901) => Some((
+                ProblemKind::OutOfRange,
+                format!("{} not in [1901, 2155]", example(value)),
+            )),
+            problem => problem,
+        },
+        MysqlColType::Timestamp { .. } => check_year_range(value, 1970, 2038),
+        MysqlColType::Char { length, .. } | MysqlColType::Varchar { length, .. } => {
+            check_length(value, *length, true)
+        }
+        MysqlColType::TinyText { length, .. }
+        | MysqlColType::Text { length, .. }
+        | MysqlColType::MediumText { length, .. }
+        | MysqlColType::LongText { length, .. } => check_length(value, *length, false),
+        MysqlColType::Binary { length } => check_length(value, *length as u64, false),
+        MysqlColType::VarBinary { length } => check_length(value, *length as u64, false),
+        MysqlColType::Enum { items } => match value {
+            ColValue::Enum(index) if *index as usize > items.len() => Some((
+                ProblemKind::InvalidValue,
+                format!("enum index {} exceeds {} items", index, items.len()),
+            )),
+            ColValue::Enum2(v) | ColValue::String(v)
+                if !items.iter().any(|item| item.eq_ignore_ascii_case(v)) =>
+            {
+                Some((
+                    ProblemKind::InvalidValue,
+                    format!("{} not in enum items", example(value)),
+                ))
+            }
+            _ => None,
+        },
+        MysqlColType::Set { items } => match value {
+            ColValue::Set(bits) => {
+                let mask = items.keys().fold(0, |mask, bit| mask | bit);
+                (bits & !mask != 0).then(|| {
+                    (
+                        ProblemKind::InvalidValue,
+                        format!("set bits {:#b} exceed items", bits),
+                    )
+                })
+            }
+            ColValue::Set2(v) | ColValue::String(v) => v
+                .split(',')
+                .filter(|member| !member.is_empty())
+                .find(|member| !items.values().any(|item| item.eq_ignore_ascii_case(member)))
+                .map(|member| {
+                    (
+                        ProblemKind::InvalidValue,
+                        format!("{} not in set items", member),
+                    )
+                }),
+            _ => None,
+        },
+        MysqlColType::Json => check_json(value),
+        _ => None,
+    }
+}
+
+/// conversion problems of writing the value into a postgres column
+pub fn check_pg_value(col_type: &PgColType, value: &ColValue) -> Option<(ProblemKind, String)> {
+    if matches!(value, ColValue::None | ColValue::UnchangedToast) {
+        return None;
+    }
+    if col_type.is_enum() {
+        let enum_values = col_type.enum_values.as_ref()?;
+        let v = value.to_option_string()?;
+        return (!enum_values.contains(&v)).then(|| {
+            (
+                ProblemKind::InvalidValue,
+                format!("{} not in enum values", example(value)),
+            )
+        });
+    }
+    match col_type.value_type {
+        PgValueType::Int16 => check_integer(value, int_range(16, false)),
+        PgValueType::Int32 => check_integer(value, int_range(32, false)),
+        PgValueType::Int64 => check_integer(value, int_range(64, false)),
+        // typmod of numeric(p, s) is ((p << 16) | s) + 4
+        PgValueType::Numeric if col_type.typmod >= 4 => {
+            let typmod = (col_type.typmod - 4) as u32;
+            check_decimal(value, (typmod >> 16) & 0xffff, typmod & 0xffff)
+        }
+        PgValueType::Char | PgValueType::String => {
+            let text = match value {
+                ColValue::RawString(v) | ColValue::Blob(v) => match String::from_utf8(v.clone()) {
+                    Ok(text) => text,
+                    Err(_) => {
+                        return Some((
+                            ProblemKind::InvalidValue,
+                            format!("{} is not valid UTF-8", example(value)),
+                        ))
+                    }
+                },
+                _ => value.to_option_string()?,
+            };
+            if text.contains('\0') {
+                return Some((
+                    ProblemKind::InvalidValue,
+                    "text contains 0x00, which is not supported by postgres".into(),
+                ));
+            }
+            // typmod of varchar(n) / char(n) is n + 4
+            if matches!(col_type.alias.as_str(), "varchar" | "bpchar") && col_type.typmod > 4 {
+                return check_length(&ColValue::String(text), (col_type.typmod - 4) as u64, true);
+            }
+            None
+        }
+        PgValueType::Date | PgValueType::Timestamp | PgValueType::TimestampTZ => {
+            let v = value.to_option_string()?;
+            v.starts_with("0000-00-00").then(|| {
+                (
+                    ProblemKind::InvalidValue,
+                    format!("zero date {} is not supported by postgres", v),
+                )
+            })
+        }
+        PgValueType::JSON => check_json(value),
+        _ => None,
+    }
+}
+
+fn int_range(bits: u32, unsigned: bool) -> (i128, i128) {
+    if unsigned {
+        (0, (1i128 << bits) - 1)
+    } else {
+        (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+    }
+}
+
+fn integer_of(value: &ColValue) -> Option<i128> {
+    match value {
+        ColValue::Bool(v) => Some(*v as i128),
+        ColValue::Year(v) => Some(*v as i128),
+        ColValue::Bit(v) => Some(*v as i128),
+        v if v.is_integer() => v.convert_into_integer_128().ok(),
+        _ => None,
+    }
+}
+
+fn check_integer(value: &ColValue, (min, max): (i128, i128)) -> Option<(ProblemKind, String)> {
+    let v = match integer_of(value) {
+        Some(v) => v,
+        None => {
+            let text = match value {
+                ColValue::Float(_)
+                | ColValue::Double(_)
+                | ColValue::Decimal(_)
+                | ColValue::String(_) => value.to_option_string()?,
+                _ => return None,
+            };
+            let text = text.trim();
+            match (text.parse::<i128>(), text.parse::<f64>()) {
+                (Ok(v), _) => v,
+                (_, Ok(f)) if f.fract() != 0.0 => {
+                    return Some((
+                        ProblemKind::Truncated,
+                        format!("fraction of {} is dropped", text),
+                    ))
+                }
+                (_, Ok(f)) => f as i128,
+                _ => {
+                    return Some((
+                        ProblemKind::InvalidValue,
+                        format!("{} is not an integer", example(value)),
+                    ))
+                }
+            }
+        }
+    };
+    (v < min || v > max).then(|| {
+        (
+            ProblemKind::OutOfRange,
+            format!("{} not in [{}, {}]", v, min, max),
+        )
+    })
+}
+
+fn check_decimal(value: &ColValue, precision: u32, scale: u32) -> Option<(ProblemKind, String)> {
+    if precision == 0 {
+        return None;
+    }
+    let text = match value {
+        ColValue::Decimal(_) | ColValue::String(_) | ColValue::Float(_) | ColValue::Double(_) => {
+            value.to_option_string()?
+        }
+        v if v.is_integer() => v.to_option_string()?,
+        _ => return None,
+    };
+    let digits = text.trim().trim_start_matches(['-', '+']);
+    // scientific notation, NaN, inf are left to the target
+    if !digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    let int_digits = int_part.trim_start_matches('0').len() as u32;
+    if int_digits > precision.saturating_sub(scale) {
+        return Some((
+            ProblemKind::OutOfRange,
+            format!("{} exceeds decimal({}, {})", text, precision, scale),
+        ));
+    }
+    let frac_digits = frac_part.trim_end_matches('0').len() as u32;
+    if frac_digits > scale {
+        return Some((
+            ProblemKind::Truncated,
+            format!("{} is rounded to {} decimal places", text, scale),
+        ));
+    }
+    None
+}
+
+fn check_length(value: &ColValue, max: u64, in_chars: bool) -> Option<(ProblemKind, String)> {
+    // unknown length
+    if max == 0 {
+        return None;
+    }
+    let len = match value {
+        ColValue::RawString(v) | ColValue::Blob(v) | ColValue::Json(v) => {
+            if in_chars {
+                String::from_utf8_lossy(v).chars().count()
+            } else {
+                v.len()
+            }
+        }
+        _ => {
+            let text = value.to_option_string()?;
+            if in_chars {
+                text.chars().count()
+            } else {
+                text.len()
+            }
+        }
+    } as u64;
+    let unit = if in_chars { "chars" } else { "bytes" };
+    (len > max).then(|| {
+        (
+            ProblemKind::Truncated,
+            format!("{} {} exceeds the limit of {}", len, unit, max),
+        )
+    })
+}
+
+fn check_year_range(value: &ColValue, min: i32, max: i32) -> Option<(ProblemKind, String)> {
+    let text = value.to_option_string()?;
+    let year: i32 = text.get(..4)?.parse().ok()?;
+    // zero dates are kept as they are
+    (year != 0 && (year < min || year > max)).then(|| {
+        (
+            ProblemKind::OutOfRange,
+            format!("{} not in years [{}, {}]", text, min, max),
+        )
+    })
+}
+
+fn check_json(value: &ColValue) -> Option<(ProblemKind, String)> {
+    let text = match value {
+        ColValue::String(v) | ColValue::Json2(v) => v,
+        _ => return None,
+    };
+    serde_json::from_str::<serde_json::Value>(text)
+        .err()
+        .map(|_| {
+            (
+                ProblemKind::InvalidValue,
+                format!("{} is not valid JSON", example(value)),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(col_type: &MysqlColType, value: ColValue) -> Option<ProblemKind> {
+        check_mysql_value(col_type, &value).map(|(kind, _)| kind)
+    }
+
+    #[test]
+    fn test_check_mysql_value() {
+        let tiny = MysqlColType::TinyInt { unsigned: false };
+        assert_eq!(kind_of(&tiny, ColValue::Long(127)), None);
+        assert_eq!(
+            kind_of(&tiny, ColValue::Long(128)),
+            Some(ProblemKind::OutOfRange)
+        );
+        assert_eq!(
+            kind_of(&tiny, ColValue::Double(1.5)),
+            Some(ProblemKind::Truncated)
+        );
+        assert_eq!(
+            kind_of(&tiny, ColValue::String("abc".into())),
+            Some(ProblemKind::InvalidValue)
+        );
+        let unsigned = MysqlColType::Int { unsigned: true };
+        assert_eq!(
+            kind_of(&unsigned, ColValue::LongLong(-1)),
+            Some(ProblemKind::OutOfRange)
+        );
+
+        let decimal = MysqlColType::Decimal {
+            precision: 5,
+            scale: 2,
+        };
+        assert_eq!(
+            kind_of(&decimal, ColValue::Decimal("-999.990".into())),
+            None
+        );
+        assert_eq!(
+            kind_of(&decimal, ColValue::Decimal("1000.00".into())),
+            Some(ProblemKind::OutOfRange)
+        );
+        assert_eq!(
+            kind_of(&decimal, ColValue::Decimal("1.234".into())),
+            Some(ProblemKind::Truncated)
+        );
+
+        let varchar = MysqlColType::Varchar {
+            length: 2,
+            charset: "utf8mb4".into(),
+        };
+        assert_eq!(kind_of(&varchar, ColValue::String("中文".into())), None);
+        assert_eq!(
+            kind_of(&varchar, ColValue::String("abc".into())),
+            Some(ProblemKind::Truncated)
+        );
+
+        let enum_type = MysqlColType::Enum {
+            items: vec!["a".into(), "b".into()],
+        };
+        assert_eq!(kind_of(&enum_type, ColValue::Enum2("B".into())), None);
+        assert_eq!(
+            kind_of(&enum_type, ColValue::Enum2("c".into())),
+            Some(ProblemKind::InvalidValue)
+        );
+
+        let timestamp = MysqlColType::Timestamp {
+            precision: 0,
+            timezone_offset: 0,
+            is_nullable: true,
+        };
+        assert_eq!(
+            kind_of(&timestamp, ColValue::DateTime("1969-12-31 00:00:00".into())),
+            Some(ProblemKind::OutOfRange)
+        );
+        assert_eq!(
+            kind_of(&MysqlColType::Json, ColValue::String("{".into())),
+            Some(ProblemKind::InvalidValue)
+        );
+        assert_eq!(kind_of(&tiny, ColValue::None), None);
+    }
+}
@@ -1,16 +1,20 @@
 use std::env;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-use dt_precheck::{config::task_config::PrecheckTaskConfig, do_precheck};
-use dt_task::task_runner::TaskRunner;
+use dt_precheck::{config::task_config::PrecheckTaskConfig, do_discovery, do_precheck};
+use dt_task::{task_lifecycle::TaskLifecycle, task_runner::TaskRunner};
 
 const ENV_SHUTDOWN_TIMEOUT_SECS: &str = "SHUTDOWN_TIMEOUT_SECS";
 #[cfg(feature = "tokio-console")]
 const ENV_TOKIO_CONSOLE: &str = "APE_DTS_TOKIO_CONSOLE";
 
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short = 'v', long = "version", alias = "versions")]
     version: bool,
 
@@ -22,6 +26,61 @@ struct Args {
 
     #[arg(long)]
     init: bool,
+
+    /// list source tables with statistics and risks instead of running the task
+    #[arg(long, conflicts_with = "init")]
+    discover: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// parse the task config, and run prechecks if [precheck] is configured
+    Validate(ConfigArg),
+    /// run the task
+    Run {
+        #[command(flatten)]
+        config: ConfigArg,
+        #[arg(long)]
+        init: bool,
+    },
+    /// print positions, finished tables and latest counters from logs of the task
+    Status(ConfigArg),
+    /// run the task from the stored checkpoint, positions in [runtime] log_dir are used if
+    /// [resumer] is not configured
+    Resume(ConfigArg),
+    /// revise the target by miss / diff logs of a check
+    Revise {
+        #[command(flatten)]
+        config: ConfigArg,
+        /// [checker] check_log_dir or {log_dir}/check by default
+        #[arg(long)]
+        check_log_dir: Option<String>,
+    },
+    /// snapshot tables into the running cdc task of the config, by [pipeline] table_backfill_file
+    Backfill {
+        #[command(flatten)]
+        config: ConfigArg,
+        /// tables in the same format as [filter] do_tbs
+        #[arg(long)]
+        tbs: String,
+        /// seconds to wait for the running task to hold / merge rows of the tables
+        #[arg(long, default_value_t = 600)]
+        wait_timeout_secs: u64,
+    },
+    /// remove the pg replication slot / publication and positions left by the stopped task
+    Cleanup {
+        #[command(flatten)]
+        config: ConfigArg,
+        /// only print what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, clap::Args)]
+struct ConfigArg {
+    #[arg(short, long, value_name = "CONFIG")]
+    config: String,
 }
 
 impl Args {
@@ -46,10 +105,84 @@ async fn main() {
         return;
     }
 
+    if let Some(Command::Status(arg)) = &args.command {
+        match TaskLifecycle::status(&arg.config) {
+            Ok(status) => print!("{}", status),
+            Err(e) => exit_with_error(e),
+        }
+        return;
+    }
+
+    spawn_shutdown_handler();
+    match args.command {
+        Some(Command::Validate(arg)) => validate(&arg.config).await,
+        Some(Command::Run { config, init }) => run(TaskRunner::new(&config.config), init).await,
+        Some(Command::Resume(arg)) => run(TaskLifecycle::resume_runner(&arg.config), false).await,
+        Some(Command::Revise {
+            config,
+            check_log_dir,
+        }) => {
+            let runner = TaskLifecycle::revise_runner(&config.config, check_log_dir.as_deref());
+            run(runner, false).await
+        }
+        Some(Command::Backfill {
+            config,
+            tbs,
+            wait_timeout_secs,
+        }) => {
+            if let Err(e) = TaskLifecycle::backfill(&config.config, &tbs, wait_timeout_secs).await {
+                exit_with_error(e);
+            }
+        }
+        Some(Command::Cleanup { config, dry_run }) => {
+            match TaskLifecycle::cleanup(&config.config, dry_run).await {
+                Ok(report) => print!("{}", report),
+                Err(e) => exit_with_error(e),
+            }
+        }
+        Some(Command::Status(_)) => {}
+        None => run_legacy(&args).await,
+    }
+}
+
+async fn run_legacy(args: &Args) {
     let config = args
         .config_path()
         .unwrap_or_else(|| panic!("no task_config provided in args"));
 
+    if args.discover {
+        do_discovery(config).await.unwrap();
+    } else if PrecheckTaskConfig::new(config).is_ok() {
+        do_precheck(config).await;
+    } else {
+        let runner = TaskRunner::new(config).unwrap();
+        runner.start_task(args.init).await.unwrap()
+    }
+}
+
+async fn validate(config: &str) {
+    if let Err(e) = TaskRunner::new(config) {
+        exit_with_error(e);
+    }
+    println!("task config is valid.");
+    if PrecheckTaskConfig::new(config).is_ok() {
+        do_precheck(config).await;
+    }
+}
+
+async fn run(runner: anyhow::Result<TaskRunner>, is_init: bool) {
+    match runner {
+        Ok(runner) => runner.start_task(is_init).await.unwrap(),
+        Err(e) => exit_with_error(e),
+    }
+}
+
+fn exit_with_error(e: anyhow::Error) -> ! {
+    eprintln!("{:#}", e);
+    std::process::exit(1);
+}
+
+fn spawn_shutdown_handler() {
     tokio::spawn(async {
         tokio::signal::ctrl_c().await.unwrap();
         tokio::time::sleep(std::time::Duration::from_secs(
@@ -61,13 +194,6 @@ async fn main() {
         .await;
         std::process::exit(0);
     });
-
-    if PrecheckTaskConfig::new(config).is_ok() {
-        do_precheck(config).await;
-    } else {
-        let runner = TaskRunner::new(config).unwrap();
-        runner.start_task(args.init).await.unwrap()
-    }
 }
 
 #[cfg(feature = "tokio-console")]
@@ -109,6 +235,71 @@ mod tests {
         assert_eq!(args.legacy_config.as_deref(), Some("version"));
     }
 
+    #[test]
+    fn accepts_discover_flag() {
+        let args =
+            Args::try_parse_from(["dt-main", "--discover", "--config", "task_config.ini"]).unwrap();
+        assert!(args.discover);
+        assert_eq!(args.config_path(), Some("task_config.ini"));
+    }
+
+    #[test]
+    fn accepts_subcommands() {
+        let args =
+            Args::try_parse_from(["dt-main", "run", "--config", "task_config.ini", "--init"])
+                .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Run { init: true, .. })
+        ));
+
+        let args = Args::try_parse_from([
+            "dt-main",
+            "revise",
+            "-c",
+            "task_config.ini",
+            "--check-log-dir",
+            "./logs/check",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Revise {
+                check_log_dir: Some(_),
+                ..
+            })
+        ));
+
+        let args = Args::try_parse_from([
+            "dt-main",
+            "backfill",
+            "-c",
+            "task_config.ini",
+            "--tbs",
+            "db_1.tb_1,db_1.tb_2",
+        ])
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Backfill {
+                wait_timeout_secs: 600,
+                ..
+            })
+        ));
+
+        let args =
+            Args::try_parse_from(["dt-main", "cleanup", "-c", "task_config.ini", "--dry-run"])
+                .unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Cleanup { dry_run: true, .. })
+        ));
+
+        // legacy config which is not a subcommand
+        let args = Args::try_parse_from(["dt-main", "task_config.ini"]).unwrap();
+        assert!(args.command.is_none());
+    }
+
     #[test]
     fn rejects_config_flag_and_positional_config_together() {
         let err =
@@ -0,0 +1,84 @@
+use std::{
+    cmp,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use dt_common::config::parallelizer_config::AdaptiveBatchConfig;
+
+/// Tunes the number of rows sent to the sinker in one call by measured sink latency:
+/// grows by 1/4 while batches finish within half of the target latency,
+/// halves once a batch takes longer than the target.
+pub struct AdaptiveBatcher {
+    config: AdaptiveBatchConfig,
+    batch_size: AtomicUsize,
+}
+
+impl AdaptiveBatcher {
+    pub fn new(config: AdaptiveBatchConfig) -> Self {
+        let batch_size = AtomicUsize::new(config.max_batch_size);
+        Self { config, batch_size }
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.load(Ordering::Relaxed)
+    }
+
+    pub fn record(&self, rows: usize, elapsed: Duration) {
+        let target = Duration::from_millis(self.config.target_latency_ms);
+        let current = self.batch_size();
+        let next = if elapsed > target {
+            cmp::max(current / 2, self.config.min_batch_size)
+        } else if elapsed < target / 2 && rows >= current {
+            // a partial batch finishing fast says nothing about whether a bigger one would
+            cmp::min(
+                current + cmp::max(current / 4, 1),
+                self.config.max_batch_size,
+            )
+        } else {
+            current
+        };
+        if next != current {
+            // concurrent sinkers may race here, the last measurement wins
+            self.batch_size.store(next, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_batch_size() {
+        let batcher = AdaptiveBatcher::new(AdaptiveBatchConfig {
+            min_batch_size: 10,
+            max_batch_size: 100,
+            target_latency_ms: 1000,
+        });
+        assert_eq!(batcher.batch_size(), 100);
+
+        let slow = Duration::from_millis(1500);
+        let fast = Duration::from_millis(100);
+        batcher.record(100, slow);
+        assert_eq!(batcher.batch_size(), 50);
+        batcher.record(50, slow);
+        batcher.record(25, slow);
+        batcher.record(12, slow);
+        assert_eq!(batcher.batch_size(), 10);
+
+        // in range, keep
+        batcher.record(10, Duration::from_millis(800));
+        assert_eq!(batcher.batch_size(), 10);
+        // partial batch, keep
+        batcher.record(5, fast);
+        assert_eq!(batcher.batch_size(), 10);
+
+        batcher.record(10, fast);
+        assert_eq!(batcher.batch_size(), 12);
+        for _ in 0..20 {
+            batcher.record(batcher.batch_size(), fast);
+        }
+        assert_eq!(batcher.batch_size(), 100);
+    }
+}
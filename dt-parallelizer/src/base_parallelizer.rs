@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, future::Future, sync::Arc};
+use std::{collections::VecDeque, future::Future, sync::Arc, time::Instant};
 
 use anyhow::bail;
 use concurrent_queue::PopError;
@@ -18,12 +18,15 @@ use dt_common::{
 };
 use dt_connector::Sinker;
 
+use crate::adaptive_batcher::AdaptiveBatcher;
+
 type SharedSinker = Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>;
 
 #[derive(Default)]
 pub struct BaseParallelizer {
     pub popped_data: VecDeque<DtItem>,
     pub monitor: TaskMonitorHandle,
+    pub adaptive_batcher: Option<Arc<AdaptiveBatcher>>,
 }
 
 impl BaseParallelizer {
@@ -34,13 +37,25 @@ impl BaseParallelizer {
         }
 
         let mut record_size_counter = Counter::new(0, 0);
+        let mut meta_version = data
+            .iter()
+            .find_map(|item: &DtItem| item.dt_data.get_meta_version());
         // ddls and dmls should be drained separately
         while let Some(item) = self.pop(buffer, &mut record_size_counter).await? {
+            // rows parsed by different table metas, e.g. before and after an alter table whose
+            // ddl is filtered, should also be drained separately
+            let item_meta_version = item.dt_data.get_meta_version();
+            let same_meta_version = match (meta_version, item_meta_version) {
+                (Some(version), Some(item_version)) => version == item_version,
+                _ => true,
+            };
             if data.is_empty()
                 || (data[0].get_row_sql_type() == item.get_row_sql_type()
-                    && data[0].data_origin_node == item.data_origin_node)
+                    && data[0].data_origin_node == item.data_origin_node
+                    && same_meta_version)
             {
                 // merge when sql type is the same
+                meta_version = meta_version.or(item_meta_version);
                 data.push(item);
             } else {
                 self.popped_data.push_back(item);
@@ -107,13 +122,51 @@ impl BaseParallelizer {
         parallel_size: usize,
         batch: bool,
     ) -> anyhow::Result<()> {
+        // serial writes are not batched by the sinker, nothing to tune
+        let Some(batcher) = self.adaptive_batcher.clone().filter(|_| batch) else {
+            return self
+                .sink_by_available_sinker(
+                    sub_data_items,
+                    sinkers,
+                    parallel_size,
+                    move |sinker, data| async move {
+                        sinker.lock().await.sink_dml(data, batch).await
+                    },
+                )
+                .await;
+        };
+
         self.sink_by_available_sinker(
             sub_data_items,
             sinkers,
             parallel_size,
-            move |sinker, data| async move { sinker.lock().await.sink_dml(data, batch).await },
+            move |sinker, mut data| {
+                let batcher = batcher.clone();
+                async move {
+                    // chunks of one sub batch are sunk in order by the same sinker
+                    let mut sinker = sinker.lock().await;
+                    while !data.is_empty() {
+                        let tail = data.split_off(batcher.batch_size().min(data.len()));
+                        let rows = data.len();
+                        let start = Instant::now();
+                        sinker.sink_dml(data, batch).await?;
+                        batcher.record(rows, start.elapsed());
+                        data = tail;
+                    }
+                    Ok::<_, anyhow::Error>(())
+                }
+            },
         )
-        .await
+        .await?;
+
+        if let Some(batcher) = &self.adaptive_batcher {
+            self.monitor.set_counter(
+                self.monitor.default_task_id(),
+                CounterType::AdaptiveBatchSize,
+                batcher.batch_size() as u64,
+            );
+        }
+        Ok(())
     }
 
     pub async fn sink_ddl(
@@ -232,10 +285,51 @@ impl BaseParallelizer {
 
 #[cfg(test)]
 mod tests {
-    use dt_common::{meta::dt_queue::DtQueue, monitor::counter::Counter};
+    use dt_common::{
+        meta::{
+            dt_data::{DtData, DtItem},
+            dt_queue::DtQueue,
+            position::Position,
+            row_data::RowData,
+            row_type::RowType,
+        },
+        monitor::counter::Counter,
+    };
 
     use super::BaseParallelizer;
 
+    fn row_item(meta_version: u64) -> DtItem {
+        let mut row_data = RowData::new(
+            "db".into(),
+            "tb".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(Default::default()),
+        );
+        row_data.meta_version = meta_version;
+        DtItem {
+            dt_data: DtData::Dml { row_data },
+            position: Position::None,
+            data_origin_node: String::new(),
+            trace: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn drain_splits_at_meta_version_change() {
+        let mut parallelizer = BaseParallelizer::default();
+        let queue = DtQueue::new(8, 0, None, None);
+        for meta_version in [1, 1, 2, 2] {
+            queue.push(row_item(meta_version)).await.unwrap();
+        }
+
+        assert_eq!(parallelizer.drain(&queue).await.unwrap().len(), 2);
+        let data = parallelizer.drain(&queue).await.unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].dt_data.get_meta_version(), Some(2));
+    }
+
     #[tokio::test]
     async fn pop_returns_none_when_queue_is_empty() {
         let parallelizer = BaseParallelizer::default();
@@ -1032,6 +1032,7 @@ mod tests {
                 },
                 position: Position::None,
                 data_origin_node: String::new(),
+                trace: None,
             },
             DtItem {
                 dt_data: DtData::Dml {
@@ -1039,6 +1040,7 @@ mod tests {
                 },
                 position: Position::None,
                 data_origin_node: String::new(),
+                trace: None,
             },
         ];
 
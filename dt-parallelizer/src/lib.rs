@@ -1,3 +1,4 @@
+pub mod adaptive_batcher;
 pub mod base_parallelizer;
 pub mod chunk_partitioner;
 pub mod merge_parallelizer;
@@ -1,9 +1,12 @@
-use std::{cmp, sync::Arc};
+use std::{
+    cmp::{self, Reverse},
+    sync::Arc,
+};
 
 use super::{base_parallelizer::BaseParallelizer, mongo_merger::MongoMerger};
 use crate::{DataSize, Merger, Parallelizer};
 use async_trait::async_trait;
-use dt_common::config::sinker_config::BasicSinkerConfig;
+use dt_common::config::{pipeline_config::TableOverrides, sinker_config::BasicSinkerConfig};
 use dt_common::meta::dcl_meta::dcl_data::DclData;
 use dt_common::meta::ddl_meta::ddl_data::DdlData;
 use dt_common::meta::dt_queue::DtQueue;
@@ -20,6 +23,8 @@ pub struct MergeParallelizer {
     pub meta_manager: Option<RdbMetaManager>,
     pub parallel_size: usize,
     pub sinker_basic_config: BasicSinkerConfig,
+    pub table_overrides: TableOverrides,
+    pub lag_priority: bool,
 }
 
 enum MergeType {
@@ -34,6 +39,12 @@ pub struct TbMergedData {
     pub unmerged_rows: Vec<RowData>,
 }
 
+impl TbMergedData {
+    pub fn row_count(&self) -> usize {
+        self.delete_rows.len() + self.insert_rows.len() + self.unmerged_rows.len()
+    }
+}
+
 #[async_trait]
 impl Parallelizer for MergeParallelizer {
     async fn close(&mut self) -> anyhow::Result<()> {
@@ -58,10 +69,16 @@ impl Parallelizer for MergeParallelizer {
     ) -> anyhow::Result<DataSize> {
         let mut data_size = DataSize::default();
         let mut tb_merged_data = self.merger.merge(data).await?;
+        let lag_parallel_sizes = self.prioritize_lagged_tbs(&mut tb_merged_data);
         for merge_type in [MergeType::Delete, MergeType::Insert, MergeType::Unmerged] {
             data_size.add(
-                self.sink_dml_adaptive(&mut tb_merged_data, sinkers, merge_type)
-                    .await?,
+                self.sink_dml_adaptive(
+                    &mut tb_merged_data,
+                    &lag_parallel_sizes,
+                    sinkers,
+                    merge_type,
+                )
+                .await?,
             );
         }
         Ok(data_size)
@@ -126,9 +143,47 @@ impl MergeParallelizer {
             meta_manager,
             parallel_size,
             sinker_basic_config,
+            table_overrides: TableOverrides::default(),
+            lag_priority: false,
         }
     }
 
+    pub fn with_table_overrides(mut self, table_overrides: TableOverrides) -> Self {
+        self.table_overrides = table_overrides;
+        self
+    }
+
+    pub fn with_lag_priority(mut self, lag_priority: bool) -> Self {
+        self.lag_priority = lag_priority;
+        self
+    }
+
+    /// In catch-up (the batch is more than one round of sinking for all sinkers),
+    /// sort tables by backlog so the most-lagged tables are sunk first,
+    /// and cap the sinkers of each table by its share of the backlog.
+    /// Returns the capped parallel size of each table, None if not in catch-up.
+    fn prioritize_lagged_tbs(
+        &self,
+        tb_merged_data_items: &mut [TbMergedData],
+    ) -> Vec<Option<usize>> {
+        let total_count: usize = tb_merged_data_items.iter().map(|i| i.row_count()).sum();
+        let round_count = cmp::max(self.sinker_basic_config.batch_size, 1) * self.parallel_size;
+        if !self.lag_priority || tb_merged_data_items.len() <= 1 || total_count <= round_count {
+            return vec![None; tb_merged_data_items.len()];
+        }
+
+        tb_merged_data_items.sort_by_key(|i| Reverse(i.row_count()));
+        tb_merged_data_items
+            .iter()
+            .map(|i| {
+                Some(cmp::max(
+                    (self.parallel_size * i.row_count()).div_ceil(total_count),
+                    1,
+                ))
+            })
+            .collect()
+    }
+
     pub fn for_check(
         base_parallelizer: BaseParallelizer,
         merger: Box<dyn Merger + Send + Sync>,
@@ -161,12 +216,15 @@ impl MergeParallelizer {
     async fn sink_dml_adaptive(
         &mut self,
         tb_merged_data_items: &mut [TbMergedData],
+        lag_parallel_sizes: &[Option<usize>],
         sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
         merge_type: MergeType,
     ) -> anyhow::Result<DataSize> {
         let mut futures = Vec::new();
         let mut data_size = DataSize::default();
-        for tb_merged_data in tb_merged_data_items.iter_mut() {
+        for (tb_merged_data, lag_parallel_size) in
+            tb_merged_data_items.iter_mut().zip(lag_parallel_sizes)
+        {
             let data: Vec<RowData> = match merge_type {
                 MergeType::Delete => tb_merged_data.delete_rows.drain(..).collect(),
                 MergeType::Insert => tb_merged_data.insert_rows.drain(..).collect(),
@@ -180,14 +238,24 @@ impl MergeParallelizer {
                 .add_count(data.len() as u64)
                 .add_bytes(data.iter().map(|v| v.get_data_size()).sum());
 
+            let (schema, tb) = (&data[0].schema, &data[0].tb);
+            let tb_batch_size =
+                self.table_overrides
+                    .batch_size(schema, tb, self.sinker_basic_config.batch_size);
+            let mut tb_parallel_size =
+                self.table_overrides
+                    .parallel_size(schema, tb, self.parallel_size);
+            if let Some(lag_parallel_size) = lag_parallel_size {
+                tb_parallel_size = cmp::min(tb_parallel_size, *lag_parallel_size);
+            }
             // make sure NO too much threads generated
-            let batch_size = cmp::max(
-                data.len() / self.parallel_size,
-                cmp::max(self.sinker_basic_config.batch_size, 1),
-            );
+            let batch_size = cmp::max(data.len() / tb_parallel_size, cmp::max(tb_batch_size, 1));
 
             match merge_type {
                 MergeType::Insert | MergeType::Delete => {
+                    // sub batches of the table rotate within tb_parallel_size sinkers
+                    let first_sinker = futures.len();
+                    let mut sub_count = 0;
                     let mut remaining = data;
                     while !remaining.is_empty() {
                         let tail = if remaining.len() > batch_size {
@@ -196,7 +264,10 @@ impl MergeParallelizer {
                             Vec::new()
                         };
                         let sub_data = std::mem::replace(&mut remaining, tail);
-                        let sinker = sinkers[futures.len() % self.parallel_size].clone();
+                        let sinker_index =
+                            (first_sinker + sub_count % tb_parallel_size) % self.parallel_size;
+                        sub_count += 1;
+                        let sinker = sinkers[sinker_index].clone();
                         let future = tokio::spawn(async move {
                             sinker.lock().await.sink_dml(sub_data, true).await
                         });
@@ -241,3 +312,66 @@ impl MergeParallelizer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::row_type::RowType;
+
+    use super::*;
+
+    fn build_tb_merged_data(tb: &str, row_count: usize) -> TbMergedData {
+        let row_data = RowData::new("test_db".into(), tb.into(), 0, RowType::Insert, None, None);
+        TbMergedData {
+            delete_rows: Vec::new(),
+            insert_rows: vec![row_data; row_count],
+            unmerged_rows: Vec::new(),
+        }
+    }
+
+    fn build_parallelizer(lag_priority: bool) -> MergeParallelizer {
+        let sinker_basic_config = BasicSinkerConfig {
+            batch_size: 10,
+            ..Default::default()
+        };
+        MergeParallelizer::for_mongo(BaseParallelizer::default(), 4, sinker_basic_config)
+            .with_lag_priority(lag_priority)
+    }
+
+    fn tbs(tb_merged_data_items: &[TbMergedData]) -> Vec<String> {
+        tb_merged_data_items
+            .iter()
+            .map(|i| i.insert_rows[0].tb.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_prioritize_lagged_tbs() {
+        let parallelizer = build_parallelizer(true);
+        let mut items = vec![
+            build_tb_merged_data("tb_1", 10),
+            build_tb_merged_data("tb_2", 60),
+            build_tb_merged_data("tb_3", 30),
+        ];
+        // 100 rows are more than one round of 4 sinkers * 10 rows
+        let lag_parallel_sizes = parallelizer.prioritize_lagged_tbs(&mut items);
+        assert_eq!(tbs(&items), vec!["tb_2", "tb_3", "tb_1"]);
+        assert_eq!(lag_parallel_sizes, vec![Some(3), Some(2), Some(1)]);
+
+        // not in catch-up
+        let mut items = vec![
+            build_tb_merged_data("tb_1", 10),
+            build_tb_merged_data("tb_2", 20),
+        ];
+        let lag_parallel_sizes = parallelizer.prioritize_lagged_tbs(&mut items);
+        assert_eq!(tbs(&items), vec!["tb_1", "tb_2"]);
+        assert_eq!(lag_parallel_sizes, vec![None, None]);
+
+        // disabled
+        let mut items = vec![
+            build_tb_merged_data("tb_1", 10),
+            build_tb_merged_data("tb_2", 60),
+        ];
+        build_parallelizer(false).prioritize_lagged_tbs(&mut items);
+        assert_eq!(tbs(&items), vec!["tb_1", "tb_2"]);
+    }
+}
@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    cmp::{self, Reverse},
+    collections::HashMap,
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use dt_common::meta::{
@@ -16,6 +20,9 @@ pub struct PartitionParallelizer {
     pub base_parallelizer: BaseParallelizer,
     pub partitioner: RdbPartitioner,
     pub parallel_size: usize,
+    // [sinker] batch_size, rows of one round of sinking for each sinker
+    pub batch_size: usize,
+    pub lag_priority: bool,
 }
 
 #[async_trait]
@@ -48,7 +55,7 @@ impl Parallelizer for PartitionParallelizer {
                     }
                 }
 
-                DtData::Commit { .. } => {
+                DtData::Begin { .. } | DtData::Commit { .. } => {
                     data.push(item);
                 }
 
@@ -72,7 +79,11 @@ impl Parallelizer for PartitionParallelizer {
             bytes: data.iter().map(|v| v.get_data_size()).sum(),
         };
 
-        let sub_data = self.partitioner.partition(data, self.parallel_size).await?;
+        let mut sub_data = self.partitioner.partition(data, self.parallel_size).await?;
+        if self.lag_priority {
+            let round_count = cmp::max(self.batch_size, 1) * self.parallel_size;
+            Self::prioritize_lagged_tbs(&mut sub_data, round_count);
+        }
         let _ = self
             .base_parallelizer
             .sink_dml(sub_data, sinkers, self.parallel_size, false)
@@ -81,3 +92,92 @@ impl Parallelizer for PartitionParallelizer {
         Ok(data_size)
     }
 }
+
+impl PartitionParallelizer {
+    /// In catch-up (the batch is more than one round of sinking for all sinkers),
+    /// sort rows in each partition by the backlog of their tables so the most-lagged tables
+    /// are sunk first, rows of a table keep their order.
+    fn prioritize_lagged_tbs(sub_data_items: &mut [Vec<RowData>], round_count: usize) {
+        let mut tb_counts: HashMap<(String, String), usize> = HashMap::new();
+        for row_data in sub_data_items.iter().flatten() {
+            *tb_counts
+                .entry((row_data.schema.clone(), row_data.tb.clone()))
+                .or_default() += 1;
+        }
+        let total_count: usize = tb_counts.values().sum();
+        if tb_counts.len() <= 1 || total_count <= round_count {
+            return;
+        }
+
+        for sub_data in sub_data_items.iter_mut() {
+            // stable, rows of the same table are not reordered
+            sub_data.sort_by_cached_key(|row_data| {
+                Reverse(tb_counts[&(row_data.schema.clone(), row_data.tb.clone())])
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::meta::row_type::RowType;
+
+    use super::*;
+
+    fn build_row_data(tb: &str, id: usize) -> RowData {
+        let mut row_data =
+            RowData::new("test_db".into(), tb.into(), 0, RowType::Insert, None, None);
+        row_data.data_size = id;
+        row_data
+    }
+
+    fn rows(sub_data: &[RowData]) -> Vec<(String, usize)> {
+        sub_data
+            .iter()
+            .map(|i| (i.tb.clone(), i.data_size))
+            .collect()
+    }
+
+    #[test]
+    fn test_prioritize_lagged_tbs() {
+        // tb_2 has the most rows of the batch across partitions
+        let build = || {
+            vec![
+                vec![
+                    build_row_data("tb_1", 1),
+                    build_row_data("tb_2", 2),
+                    build_row_data("tb_1", 3),
+                    build_row_data("tb_2", 4),
+                ],
+                vec![build_row_data("tb_2", 5), build_row_data("tb_1", 6)],
+                vec![build_row_data("tb_2", 7), build_row_data("tb_3", 8)],
+            ]
+        };
+
+        let mut sub_data_items = build();
+        PartitionParallelizer::prioritize_lagged_tbs(&mut sub_data_items, 4);
+        let expect = |items: &[(&str, usize)]| -> Vec<(String, usize)> {
+            items.iter().map(|(tb, id)| (tb.to_string(), *id)).collect()
+        };
+        assert_eq!(
+            rows(&sub_data_items[0]),
+            expect(&[("tb_2", 2), ("tb_2", 4), ("tb_1", 1), ("tb_1", 3)])
+        );
+        assert_eq!(
+            rows(&sub_data_items[1]),
+            expect(&[("tb_2", 5), ("tb_1", 6)])
+        );
+        assert_eq!(
+            rows(&sub_data_items[2]),
+            expect(&[("tb_2", 7), ("tb_3", 8)])
+        );
+
+        // not in catch-up
+        let mut sub_data_items = build();
+        PartitionParallelizer::prioritize_lagged_tbs(&mut sub_data_items, 8);
+        assert_eq!(
+            rows(&sub_data_items[0]),
+            expect(&[("tb_1", 1), ("tb_2", 2), ("tb_1", 3), ("tb_2", 4)])
+        );
+    }
+}
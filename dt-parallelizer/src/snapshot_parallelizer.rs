@@ -2,7 +2,9 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use dt_common::{
-    config::parallelizer_config::ChunkPartitionerRebalanceConfig,
+    config::{
+        parallelizer_config::ChunkPartitionerRebalanceConfig, pipeline_config::TableOverrides,
+    },
     meta::{dt_data::DtItem, dt_queue::DtQueue, row_data::RowData},
 };
 use dt_connector::Sinker;
@@ -14,6 +16,7 @@ pub struct SnapshotParallelizer {
     pub base_parallelizer: BaseParallelizer,
     pub parallel_size: usize,
     pub chunk_partitioner_rebalance: ChunkPartitionerRebalanceConfig,
+    pub table_overrides: TableOverrides,
 }
 
 #[async_trait]
@@ -36,7 +39,7 @@ impl Parallelizer for SnapshotParallelizer {
             bytes: data.iter().map(|v| v.get_data_size()).sum(),
         };
 
-        let effective_parallelism = self.parallel_size.min(sinkers.len());
+        let effective_parallelism = self.table_parallel_size(&data).min(sinkers.len());
         let sub_datas = ChunkPartitioner::partition_dml(
             data,
             effective_parallelism,
@@ -69,6 +72,28 @@ impl Parallelizer for SnapshotParallelizer {
 }
 
 impl SnapshotParallelizer {
+    // if rows of several tables are in one batch, the smallest hint among them wins
+    fn table_parallel_size(&self, data: &[RowData]) -> usize {
+        if self.table_overrides.is_empty() {
+            return self.parallel_size;
+        }
+        let mut parallel_size = self.parallel_size;
+        let mut last_tb: Option<(&str, &str)> = None;
+        for row_data in data {
+            let tb = (row_data.schema.as_str(), row_data.tb.as_str());
+            if last_tb == Some(tb) {
+                continue;
+            }
+            last_tb = Some(tb);
+            parallel_size = parallel_size.min(self.table_overrides.parallel_size(
+                tb.0,
+                tb.1,
+                self.parallel_size,
+            ));
+        }
+        parallel_size
+    }
+
     pub fn partition<T>(data: Vec<T>, parallele_size: usize) -> anyhow::Result<Vec<Vec<T>>> {
         let mut sub_data_items = Vec::new();
         if parallele_size <= 1 {
@@ -10,10 +10,21 @@ use tokio::{
     time::{Duration, Instant},
 };
 
-use crate::{lua_processor::LuaProcessor, Pipeline};
+use crate::{
+    idle_detector::IdleDetector,
+    lua_processor::LuaProcessor,
+    mongo_projector::MongoProjector,
+    redis_mapper::RedisMapper,
+    soft_deleter::SoftDeleter,
+    tb_backfiller::TbBackfiller,
+    tb_remover::TbRemover,
+    text_cleaner::TextCleaner,
+    trx_spiller::{TrxSpiller, TrxUnit},
+    Pipeline,
+};
 use dt_common::{
     config::sinker_config::SinkerConfig,
-    log_error, log_finished, log_info, log_position, log_warn,
+    log_error, log_finished, log_info, log_monitor, log_position, log_warn,
     meta::{
         dcl_meta::dcl_data::DclData,
         ddl_meta::ddl_data::DdlData,
@@ -21,17 +32,21 @@ use dt_common::{
         dt_queue::DtQueue,
         position::Position,
         row_data::RowData,
+        row_type::RowSqlType,
         syncer::Syncer,
     },
     monitor::{
-        counter_type::CounterType, task_metrics::TaskMetricsType, task_monitor::MonitorType,
-        task_monitor_handle::TaskMonitorHandle,
+        counter_type::CounterType, latency_tracer::LatencyTracer, task_metrics::TaskMetricsType,
+        task_monitor::MonitorType, task_monitor_handle::TaskMonitorHandle,
     },
 };
 use dt_connector::{
     checker::CheckerHandle,
     data_marker::DataMarker,
-    extractor::resumer::{recorder::Recorder, utils::ResumerUtil},
+    extractor::resumer::{
+        recorder::Recorder, snapshot_manifest::SnapshotManifest, utils::ResumerUtil,
+    },
+    sinker::circuit_breaker::{CircuitBreaker, SharedCircuitBreaker},
     Sinker,
 };
 use dt_parallelizer::{DataSize, Parallelizer};
@@ -49,8 +64,20 @@ pub struct BasePipeline {
     pub pending_snapshot_finished: HashMap<String, Position>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub lua_processor: Option<LuaProcessor>,
+    pub text_cleaner: Option<TextCleaner>,
+    pub mongo_projector: Option<MongoProjector>,
+    pub soft_deleter: Option<SoftDeleter>,
+    pub redis_mapper: Option<RedisMapper>,
     pub recorder: Option<Arc<dyn Recorder + Send + Sync>>,
     pub checker: Option<CheckerHandle>,
+    pub tb_remover: Option<TbRemover>,
+    pub tb_backfiller: Option<TbBackfiller>,
+    pub snapshot_manifest: Option<Arc<SnapshotManifest>>,
+    pub circuit_breaker: Option<SharedCircuitBreaker>,
+    pub idle_detector: Option<IdleDetector>,
+    pub latency_tracer: Option<Arc<LatencyTracer>>,
+    // applies source transactions in target transactions, None if [pipeline] transactional=false
+    pub trx_spiller: Option<TrxSpiller>,
 }
 
 enum SinkMethod {
@@ -71,6 +98,9 @@ impl Pipeline for BasePipeline {
             let syncer = self.syncer.lock().await;
             Self::checker_close_position(&syncer)
         };
+        if let Some(text_cleaner) = &self.text_cleaner {
+            text_cleaner.report();
+        }
         if let Some(checker) = &mut self.checker {
             if let Err(err) = checker.close_with_position(final_position.as_ref()).await {
                 log_warn!("checker close failed: {}", err);
@@ -91,6 +121,9 @@ impl Pipeline for BasePipeline {
         let mut last_checkpoint_time = Instant::now();
         let mut last_received_position = Position::None;
         let mut last_commit_positions = HashMap::new();
+        // positions before the oldest row held by tb_backfiller, checkpoints do not pass them
+        // so held rows are extracted again after a restart
+        let mut held_checkpoint: Option<(Position, HashMap<String, Position>)> = None;
         let mut record_time = Instant::now();
 
         loop {
@@ -107,6 +140,15 @@ impl Pipeline for BasePipeline {
                 self.buffer.wait_for_data(Duration::from_secs(1)).await;
             }
 
+            // stop pulling data while the target is unhealthy, the buffer bounds what is queued
+            if let Some(circuit_breaker) = &self.circuit_breaker {
+                let error = circuit_breaker.lock().await.check().await;
+                if let Some(err) = error {
+                    CircuitBreaker::wait_until_recovered(circuit_breaker, err).await?;
+                    circuit_breaker.lock().await.close();
+                }
+            }
+
             // to avoid too many sub counters, only add counter when buffer is not empty
             if !self.buffer.is_empty() {
                 self.monitor
@@ -118,7 +160,7 @@ impl Pipeline for BasePipeline {
                     .await;
             }
             if record_time.elapsed().as_secs() > 1 {
-                let len = self.buffer.len() as u64;
+                let len = self.buffer.data_len() as u64;
                 let size = self.buffer.get_curr_size();
                 self.monitor.set_counter(
                     self.monitor.default_task_id(),
@@ -130,18 +172,67 @@ impl Pipeline for BasePipeline {
                     CounterType::QueuedByteCurrent,
                     size,
                 );
+                self.monitor.set_counter(
+                    self.monitor.default_task_id(),
+                    CounterType::QueuedControlCurrent,
+                    self.buffer.control_len() as u64,
+                );
                 record_time = Instant::now();
             }
 
+            // stop pulling data while held rows of backfilled tables reach the memory limit
+            let backfill_full = !shutting_down
+                && self
+                    .tb_backfiller
+                    .as_ref()
+                    .is_some_and(|tb_backfiller| tb_backfiller.is_full());
+            if backfill_full {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
             // some sinkers need to accumulate data to a big batch and sink
-            let data = if last_sink_time.elapsed().as_secs() < self.batch_sink_interval_secs
-                && !self.buffer.is_full()
+            let mut data = if backfill_full
+                || (last_sink_time.elapsed().as_secs() < self.batch_sink_interval_secs
+                    && !self.buffer.is_full())
             {
                 Vec::new()
             } else {
                 last_sink_time = Instant::now();
                 self.parallelizer.drain(self.buffer.as_ref()).await?
             };
+            let traces = match &self.latency_tracer {
+                Some(_) => LatencyTracer::take_traces(&mut data),
+                None => Vec::new(),
+            };
+            // redis entries become rows before anything inspects dml
+            let data = match &mut self.redis_mapper {
+                Some(redis_mapper) => redis_mapper.process(data).await?,
+                None => data,
+            };
+
+            // rows of tables being backfilled are held until their snapshot is finished
+            let data = match &mut self.tb_backfiller {
+                Some(tb_backfiller) => {
+                    let data = tb_backfiller.process(data);
+                    if !tb_backfiller.is_holding() {
+                        held_checkpoint = None;
+                    } else if held_checkpoint.is_none() {
+                        held_checkpoint = Some((
+                            last_received_position.clone(),
+                            last_commit_positions.clone(),
+                        ));
+                    }
+                    data
+                }
+                None => data,
+            };
+
+            if let Some(tb_remover) = &mut self.tb_remover {
+                tb_remover.observe(&data);
+            }
+            if let Some(manifest) = &self.snapshot_manifest {
+                Self::mark_snapshot_in_progress(manifest, &data);
+            }
 
             if let Some(data_marker) = &mut self.data_marker {
                 if !data.is_empty() {
@@ -150,12 +241,23 @@ impl Pipeline for BasePipeline {
             }
 
             // process all row_data_items in buffer at a time
-            let (data_size, last_received, last_commits) = match self.get_sink_method(&data) {
-                SinkMethod::Ddl => self.sink_ddl(data).await?,
-                SinkMethod::Dcl => self.sink_dcl(data).await?,
-                SinkMethod::Dml => self.sink_dml(data).await?,
-                SinkMethod::Raw => self.sink_raw(data).await?,
-                SinkMethod::Struct => self.sink_struct(data).await?,
+            let (data_size, last_received, last_commits) =
+                if let Some(trx_spiller) = &mut self.trx_spiller {
+                    let units = trx_spiller.process(data)?;
+                    self.sink_trx_units(units).await?
+                } else {
+                    self.sink(data).await?
+                };
+            // popped items are sunk, their bytes no longer count in [pipeline] memory_budget_mb
+            self.buffer.release_in_flight();
+            if let Some(latency_tracer) = &self.latency_tracer {
+                latency_tracer.record(traces);
+                latency_tracer.try_report(&self.monitor);
+            }
+            // checkpoint at once when rows come after an idle period
+            let resumed = match &mut self.idle_detector {
+                Some(idle_detector) => idle_detector.observe(data_size.count).await,
+                None => false,
             };
 
             if let Some(position) = &last_received {
@@ -167,11 +269,40 @@ impl Pipeline for BasePipeline {
                     .insert(ResumerUtil::get_key_from_position(&position), position);
             }
 
+            if let Some(tb_remover) = &mut self.tb_remover {
+                tb_remover.try_report(&last_received_position).await;
+                if last_checkpoint_time.elapsed().as_secs() >= self.checkpoint_interval_secs {
+                    if let Err(err) = tb_remover.poll(self.buffer.len()).await {
+                        log_warn!(
+                            "failed to read table removal file {}: {}",
+                            tb_remover.control_file,
+                            err
+                        );
+                    }
+                }
+            }
+
+            if let Some(tb_backfiller) = &mut self.tb_backfiller {
+                if last_checkpoint_time.elapsed().as_secs() >= self.checkpoint_interval_secs {
+                    if let Err(err) = tb_backfiller.poll().await {
+                        log_warn!(
+                            "failed to read table backfill file {}: {}",
+                            tb_backfiller.control_file,
+                            err
+                        );
+                    }
+                }
+            }
+
+            let (checkpoint_position, checkpoint_commit_positions) = match &held_checkpoint {
+                Some((position, commit_positions)) => (position, commit_positions),
+                None => (&last_received_position, &last_commit_positions),
+            };
             last_checkpoint_time = self
                 .record_checkpoint(
-                    Some(last_checkpoint_time),
-                    &last_received_position,
-                    &last_commit_positions,
+                    (!resumed).then_some(last_checkpoint_time),
+                    checkpoint_position,
+                    checkpoint_commit_positions,
                 )
                 .await?;
 
@@ -194,9 +325,16 @@ impl Pipeline for BasePipeline {
             yield_now().await;
         }
 
-        self.record_checkpoint(None, &last_received_position, &last_commit_positions)
+        let (checkpoint_position, checkpoint_commit_positions) = match &held_checkpoint {
+            Some((position, commit_positions)) => (position, commit_positions),
+            None => (&last_received_position, &last_commit_positions),
+        };
+        self.record_checkpoint(None, checkpoint_position, checkpoint_commit_positions)
             .await?;
         self.try_finish_snapshot_tasks().await?;
+        if let Some(manifest) = &self.snapshot_manifest {
+            manifest.persist().await?;
+        }
         Ok(())
     }
 }
@@ -207,6 +345,112 @@ impl BasePipeline {
             .then_some(syncer.committed_position.clone())
     }
 
+    async fn sink(
+        &mut self,
+        data: Vec<DtItem>,
+    ) -> anyhow::Result<(DataSize, Option<Position>, Vec<Position>)> {
+        match self.get_sink_method(&data) {
+            SinkMethod::Ddl => self.sink_ddl(data).await,
+            SinkMethod::Dcl => self.sink_dcl(data).await,
+            SinkMethod::Dml => self.sink_dml(data).await,
+            SinkMethod::Raw => self.sink_raw(data).await,
+            SinkMethod::Struct => self.sink_struct(data).await,
+        }
+    }
+
+    // rows are applied in one target transaction until a ddl / dcl, which commits implicitly
+    async fn sink_trx_units(
+        &mut self,
+        units: Vec<TrxUnit>,
+    ) -> anyhow::Result<(DataSize, Option<Position>, Vec<Position>)> {
+        let mut result = (DataSize::default(), None, Vec::new());
+        let mut in_trx = false;
+        for unit in units {
+            match unit {
+                TrxUnit::Items(items) => {
+                    for run in Self::split_by_sql_type(items) {
+                        let is_dml = run[0].get_row_sql_type() == RowSqlType::DML;
+                        let has_rows = run
+                            .iter()
+                            .any(|item| matches!(item.dt_data, DtData::Dml { .. }));
+                        if in_trx && !is_dml {
+                            self.commit_trx().await?;
+                            in_trx = false;
+                        } else if !in_trx && has_rows {
+                            self.begin_trx().await?;
+                            in_trx = true;
+                        }
+                        let sunk = self.sink(run).await?;
+                        Self::merge_sink_result(&mut result, sunk);
+                    }
+                }
+
+                TrxUnit::Spilled(mut reader) => {
+                    log_info!(
+                        "applying {} spilled rows from {}",
+                        reader.rows,
+                        reader.path().display()
+                    );
+                    if !in_trx {
+                        self.begin_trx().await?;
+                        in_trx = true;
+                    }
+                    loop {
+                        let chunk = reader.next_chunk()?;
+                        if chunk.is_empty() {
+                            break;
+                        }
+                        let sunk = self.sink(chunk).await?;
+                        Self::merge_sink_result(&mut result, sunk);
+                    }
+                    reader.finish()?;
+                }
+            }
+        }
+        if in_trx {
+            self.commit_trx().await?;
+        }
+        Ok(result)
+    }
+
+    async fn begin_trx(&mut self) -> anyhow::Result<()> {
+        for sinker in self.sinkers.iter() {
+            sinker.lock().await.begin_trx().await?;
+        }
+        Ok(())
+    }
+
+    async fn commit_trx(&mut self) -> anyhow::Result<()> {
+        for sinker in self.sinkers.iter() {
+            sinker.lock().await.commit_trx().await?;
+        }
+        Ok(())
+    }
+
+    // splits items into runs of the same sql type, keeping their order
+    fn split_by_sql_type(items: Vec<DtItem>) -> Vec<Vec<DtItem>> {
+        let mut runs: Vec<Vec<DtItem>> = Vec::new();
+        for item in items {
+            match runs.last_mut() {
+                Some(run) if run[0].get_row_sql_type() == item.get_row_sql_type() => run.push(item),
+                _ => runs.push(vec![item]),
+            }
+        }
+        runs
+    }
+
+    fn merge_sink_result(
+        result: &mut (DataSize, Option<Position>, Vec<Position>),
+        sunk: (DataSize, Option<Position>, Vec<Position>),
+    ) {
+        let (data_size, last_received, commit_positions) = sunk;
+        result.0.add(data_size);
+        if last_received.is_some() {
+            result.1 = last_received;
+        }
+        result.2.extend(commit_positions);
+    }
+
     async fn sink_raw(
         &mut self,
         all_data: Vec<DtItem>,
@@ -255,9 +499,11 @@ impl BasePipeline {
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(DataSize, Option<Position>, Vec<Position>)> {
-        let (mut data, last_received_position, last_commit_position) =
-            Self::fetch_dml(all_data, &mut self.pending_snapshot_finished);
-        let commit_positions = last_commit_position.into_iter().collect();
+        let (mut data, last_received_position, commit_positions) = Self::fetch_dml(
+            all_data,
+            &mut self.pending_snapshot_finished,
+            self.sinker_config.needs_row_position(),
+        );
         if data.is_empty() {
             return Ok((
                 DataSize::default(),
@@ -271,6 +517,18 @@ impl BasePipeline {
             data = lua_processor.process(data)?;
         }
 
+        if let Some(text_cleaner) = &mut self.text_cleaner {
+            text_cleaner.process(&mut data);
+        }
+
+        if let Some(mongo_projector) = &self.mongo_projector {
+            mongo_projector.process(&mut data);
+        }
+
+        if let Some(soft_deleter) = &self.soft_deleter {
+            soft_deleter.process(&mut data);
+        }
+
         let data_size = self.parallelizer.sink_dml(data, &self.sinkers).await?;
         Ok((data_size, last_received_position, commit_positions))
     }
@@ -279,9 +537,8 @@ impl BasePipeline {
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(DataSize, Option<Position>, Vec<Position>)> {
-        let (data, last_received_position, last_commit_position) =
+        let (data, last_received_position, commit_positions) =
             Self::fetch_ddl(all_data, &mut self.pending_snapshot_finished);
-        let commit_positions: Vec<_> = last_commit_position.clone().into_iter().collect();
         if !data.is_empty() {
             let data_size = self
                 .parallelizer
@@ -318,9 +575,8 @@ impl BasePipeline {
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(DataSize, Option<Position>, Vec<Position>)> {
-        let (data, last_received_position, last_commit_position) =
+        let (data, last_received_position, commit_positions) =
             Self::fetch_dcl(all_data, &mut self.pending_snapshot_finished);
-        let commit_positions = last_commit_position.into_iter().collect();
         let data_size = DataSize {
             count: data.len() as u64,
             bytes: 0,
@@ -353,7 +609,7 @@ impl BasePipeline {
                     last_received_position = Some(i.position.clone());
                     continue;
                 }
-                DtData::Begin {} => {
+                DtData::Begin { .. } => {
                     continue;
                 }
 
@@ -370,18 +626,22 @@ impl BasePipeline {
             }
         }
 
-        let mut commit_positions: Vec<(String, Position)> = commit_positions.into_iter().collect();
-        commit_positions.sort_by(|left, right| left.0.cmp(&right.0));
         (
             data_count,
             last_received_position,
-            commit_positions
-                .into_iter()
-                .map(|(_, position)| position)
-                .collect(),
+            Self::sorted_commit_positions(commit_positions),
         )
     }
 
+    fn sorted_commit_positions(commit_positions: HashMap<String, Position>) -> Vec<Position> {
+        let mut commit_positions: Vec<(String, Position)> = commit_positions.into_iter().collect();
+        commit_positions.sort_by(|left, right| left.0.cmp(&right.0));
+        commit_positions
+            .into_iter()
+            .map(|(_, position)| position)
+            .collect()
+    }
+
     fn collect_commit_position(
         commit_positions: &mut HashMap<String, Position>,
         position: &Position,
@@ -399,27 +659,32 @@ impl BasePipeline {
     fn fetch_dml(
         mut data: Vec<DtItem>,
         pending_snapshot_finished: &mut HashMap<String, Position>,
-    ) -> (Vec<RowData>, Option<Position>, Option<Position>) {
+        attach_position: bool,
+    ) -> (Vec<RowData>, Option<Position>, Vec<Position>) {
         let mut dml_data = Vec::new();
         let mut last_received_position = Option::None;
-        let mut last_commit_position = Option::None;
+        // the latest commit of each source, e.g. of each mysql instance in a multi-source task
+        let mut commit_positions = HashMap::new();
         for i in data.drain(..) {
             match i.dt_data {
                 DtData::Commit { .. } => {
                     if Self::collect_snapshot_finished(&i.position, pending_snapshot_finished) {
                         continue;
                     }
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                     continue;
                 }
                 DtData::Heartbeat {} => {
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                     continue;
                 }
 
-                DtData::Dml { row_data } => {
+                DtData::Dml { mut row_data } => {
+                    if attach_position {
+                        row_data.position = Some(Arc::new(i.position.clone()));
+                    }
                     last_received_position = Some(i.position);
                     dml_data.push(row_data);
                 }
@@ -428,35 +693,39 @@ impl BasePipeline {
             }
         }
 
-        (dml_data, last_received_position, last_commit_position)
+        (
+            dml_data,
+            last_received_position,
+            Self::sorted_commit_positions(commit_positions),
+        )
     }
 
     fn fetch_ddl(
         mut data: Vec<DtItem>,
         pending_snapshot_finished: &mut HashMap<String, Position>,
-    ) -> (Vec<DdlData>, Option<Position>, Option<Position>) {
+    ) -> (Vec<DdlData>, Option<Position>, Vec<Position>) {
         let mut result = Vec::new();
         let mut last_received_position = Option::None;
-        let mut last_commit_position = Option::None;
+        let mut commit_positions = HashMap::new();
         for i in data.drain(..) {
             match i.dt_data {
                 DtData::Commit { .. } => {
                     if Self::collect_snapshot_finished(&i.position, pending_snapshot_finished) {
                         continue;
                     }
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                     continue;
                 }
                 DtData::Heartbeat {} => {
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                     continue;
                 }
 
                 DtData::Ddl { ddl_data } => {
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                     result.push(ddl_data);
                 }
 
@@ -464,33 +733,37 @@ impl BasePipeline {
             }
         }
 
-        (result, last_received_position, last_commit_position)
+        (
+            result,
+            last_received_position,
+            Self::sorted_commit_positions(commit_positions),
+        )
     }
 
     fn fetch_dcl(
         mut data: Vec<DtItem>,
         pending_snapshot_finished: &mut HashMap<String, Position>,
-    ) -> (Vec<DclData>, Option<Position>, Option<Position>) {
+    ) -> (Vec<DclData>, Option<Position>, Vec<Position>) {
         let mut result = Vec::new();
         let mut last_received_position = Option::None;
-        let mut last_commit_position = Option::None;
+        let mut commit_positions = HashMap::new();
         for i in data.drain(..) {
             match i.dt_data {
                 DtData::Commit { .. } => {
                     if Self::collect_snapshot_finished(&i.position, pending_snapshot_finished) {
                         continue;
                     }
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                 }
                 DtData::Heartbeat {} => {
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                 }
 
                 DtData::Dcl { dcl_data } => {
-                    last_commit_position = Some(i.position);
-                    last_received_position = last_commit_position.clone();
+                    Self::collect_commit_position(&mut commit_positions, &i.position);
+                    last_received_position = Some(i.position);
                     result.push(dcl_data);
                 }
 
@@ -498,7 +771,11 @@ impl BasePipeline {
             }
         }
 
-        (result, last_received_position, last_commit_position)
+        (
+            result,
+            last_received_position,
+            Self::sorted_commit_positions(commit_positions),
+        )
     }
 
     fn get_sink_method(&self, data: &Vec<DtItem>) -> SinkMethod {
@@ -509,7 +786,7 @@ impl BasePipeline {
                 DtData::Dcl { .. } => return SinkMethod::Dcl,
                 DtData::Dml { .. } => return SinkMethod::Dml,
                 DtData::Redis { .. } => return SinkMethod::Raw,
-                DtData::Begin {} | DtData::Commit { .. } | DtData::Heartbeat {} => continue,
+                DtData::Begin { .. } | DtData::Commit { .. } | DtData::Heartbeat {} => continue,
             }
         }
         SinkMethod::Raw
@@ -533,6 +810,11 @@ impl BasePipeline {
             self.monitor
                 .add_no_window_metrics(TaskMetricsType::FinishedProgressCount, 1);
             log_finished!("{}", finish_position.to_string());
+            if let (Some(manifest), Position::RdbSnapshotFinished { schema, tb, .. }) =
+                (&self.snapshot_manifest, &finish_position)
+            {
+                manifest.mark_done(schema, tb);
+            }
             if let Some(handler) = &self.recorder {
                 if let Err(err) = handler.record_position(&finish_position).await {
                     log_error!(
@@ -559,6 +841,7 @@ impl BasePipeline {
                 dt_data: DtData::Commit { xid: String::new() },
                 position: finish_position.clone(),
                 data_origin_node: String::new(),
+                trace: None,
             };
             if let Some(checker) = &self.checker {
                 if let Err(err) = checker.handle_control_item(&item).await {
@@ -569,9 +852,47 @@ impl BasePipeline {
                 sinker.lock().await.handle_control_item(&item).await?;
             }
         }
+
+        if let Position::RdbSnapshotFinished {
+            schema,
+            tb,
+            row_count,
+            cdc_position: Some(cdc_position),
+            ..
+        } = finish_position
+        {
+            log_monitor!(
+                "pipeline | snapshot watermark | schema={} | tb={} | row_count={} | cdc_position={}",
+                schema,
+                tb,
+                row_count.unwrap_or_default(),
+                cdc_position
+            );
+            // emitted once per table, not by every parallel sinker
+            if let Some(sinker) = self.sinkers.first() {
+                sinker
+                    .lock()
+                    .await
+                    .sink_snapshot_watermark(finish_position)
+                    .await?;
+            }
+        }
         Ok(())
     }
 
+    fn mark_snapshot_in_progress(manifest: &SnapshotManifest, data: &[DtItem]) {
+        let mut last_tb: Option<(&str, &str)> = None;
+        for item in data {
+            if let Position::RdbSnapshot { schema, tb, .. } = &item.position {
+                let current = Some((schema.as_str(), tb.as_str()));
+                if last_tb != current {
+                    manifest.mark_in_progress(schema, tb);
+                    last_tb = current;
+                }
+            }
+        }
+    }
+
     fn collect_snapshot_finished(
         position: &Position,
         pending_snapshot_finished: &mut HashMap<String, Position>,
@@ -593,8 +914,14 @@ impl BasePipeline {
         last_received_position: &Position,
         last_commit_positions: &HashMap<String, Position>,
     ) -> anyhow::Result<Instant> {
+        let checkpoint_interval_secs = match &self.idle_detector {
+            Some(idle_detector) => {
+                idle_detector.checkpoint_interval_secs(self.checkpoint_interval_secs)
+            }
+            None => self.checkpoint_interval_secs,
+        };
         if let Some(last) = last_checkpoint_time {
-            if last.elapsed().as_secs() < self.checkpoint_interval_secs {
+            if last.elapsed().as_secs() < checkpoint_interval_secs {
                 return Ok(last);
             }
         }
@@ -654,6 +981,12 @@ impl BasePipeline {
             last_received_position.to_timestamp(),
         );
 
+        if let Some(manifest) = &self.snapshot_manifest {
+            if let Err(err) = manifest.persist().await {
+                log_warn!("failed to persist snapshot manifest: {}", err);
+            }
+        }
+
         Ok(Instant::now())
     }
 }
@@ -690,6 +1023,7 @@ mod tests {
             },
             position,
             data_origin_node: String::new(),
+            trace: None,
         }
     }
 
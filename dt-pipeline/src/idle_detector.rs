@@ -0,0 +1,112 @@
+use std::{sync::Arc, time::Instant};
+
+use tokio::sync::Mutex;
+
+use dt_common::{log_info, log_monitor, meta::syncer::Syncer};
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// Detects periods without sunk rows. While the source is idle, checkpoints and monitor logs
+/// are written every [pipeline] idle_checkpoint_interval_secs instead of checkpoint_interval_secs,
+/// the normal cadence is back once rows come again.
+///
+/// The time of the last sunk rows is shared by the syncer, so extractors skip heartbeat writes
+/// while rows are flowing.
+pub struct IdleDetector {
+    threshold_secs: u64,
+    idle_checkpoint_interval_secs: u64,
+    last_active_time: Instant,
+    // (since, formatted since) of the current idle period
+    idle_since: Option<(Instant, String)>,
+    syncer: Arc<Mutex<Syncer>>,
+}
+
+impl IdleDetector {
+    pub fn new(
+        threshold_secs: u64,
+        idle_checkpoint_interval_secs: u64,
+        syncer: Arc<Mutex<Syncer>>,
+    ) -> Self {
+        Self {
+            threshold_secs,
+            idle_checkpoint_interval_secs,
+            last_active_time: Instant::now(),
+            idle_since: None,
+            syncer,
+        }
+    }
+
+    /// Returns true if rows come after an idle period, the caller should checkpoint at once.
+    pub async fn observe(&mut self, sunk_count: u64) -> bool {
+        if sunk_count > 0 {
+            self.last_active_time = Instant::now();
+            let mut syncer = self.syncer.lock().await;
+            syncer.last_data_time = Some(self.last_active_time);
+            let Some((since, since_str)) = self.idle_since.take() else {
+                return false;
+            };
+            syncer.is_idle = false;
+            log_monitor!(
+                "pipeline | idle end | since={} | idle_secs={}",
+                since_str,
+                since.elapsed().as_secs()
+            );
+            log_info!("source is active again after idle since {}", since_str);
+            return true;
+        }
+
+        if self.idle_since.is_none()
+            && self.last_active_time.elapsed().as_secs() >= self.threshold_secs
+        {
+            let since_str = (chrono::Local::now()
+                - chrono::Duration::seconds(self.last_active_time.elapsed().as_secs() as i64))
+            .format(TIME_FORMAT)
+            .to_string();
+            log_monitor!("pipeline | idle since | since={}", since_str);
+            log_info!(
+                "source is idle since {}, checkpoint every {} secs",
+                since_str,
+                self.idle_checkpoint_interval_secs
+            );
+            self.idle_since = Some((self.last_active_time, since_str));
+            self.syncer.lock().await.is_idle = true;
+        }
+        false
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle_since.is_some()
+    }
+
+    pub fn checkpoint_interval_secs(&self, checkpoint_interval_secs: u64) -> u64 {
+        if self.is_idle() {
+            checkpoint_interval_secs.max(self.idle_checkpoint_interval_secs)
+        } else {
+            checkpoint_interval_secs
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_observe() {
+        let syncer = Arc::new(Mutex::new(Syncer::default()));
+        let mut detector = IdleDetector::new(0, 60, syncer.clone());
+        assert_eq!(detector.checkpoint_interval_secs(10), 10);
+
+        assert!(!detector.observe(0).await);
+        assert!(detector.is_idle());
+        assert!(syncer.lock().await.is_idle);
+        assert_eq!(detector.checkpoint_interval_secs(10), 60);
+        assert_eq!(detector.checkpoint_interval_secs(100), 100);
+
+        assert!(detector.observe(1).await);
+        assert!(!detector.is_idle());
+        assert!(!syncer.lock().await.is_idle);
+        assert!(!syncer.lock().await.should_heartbeat(10));
+        assert!(!detector.observe(1).await);
+    }
+}
@@ -1,5 +1,13 @@
 pub mod base_pipeline;
+pub mod idle_detector;
 pub mod lua_processor;
+pub mod mongo_projector;
+pub mod redis_mapper;
+pub mod soft_deleter;
+pub mod tb_backfiller;
+pub mod tb_remover;
+pub mod text_cleaner;
+pub mod trx_spiller;
 
 use async_trait::async_trait;
 
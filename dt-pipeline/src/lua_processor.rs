@@ -30,6 +30,9 @@ impl LuaProcessor {
             lua.globals().set("tb", row_data.tb)?;
             lua.globals()
                 .set("row_type", row_data.row_type.to_string())?;
+            // original sql of the row if captured, read only
+            lua.globals()
+                .set("query", row_data.query.as_ref().map(|q| q.as_str()))?;
 
             // execute lua
             lua.load(&self.lua_code).exec()?;
@@ -49,7 +52,8 @@ impl LuaProcessor {
             let schema = lua.globals().get("schema")?;
             let tb = lua.globals().get("tb")?;
             let row_type = RowType::from_str(&row_type)?;
-            let new_row_data = RowData::new(schema, tb, 0, row_type, before, after);
+            let mut new_row_data = RowData::new(schema, tb, 0, row_type, before, after);
+            new_row_data.query = row_data.query;
             new_data.push(new_row_data);
         }
 
@@ -149,7 +153,11 @@ impl LuaProcessor {
             ColValue::Long(v) => mlua::Value::Integer(v as i64),
             ColValue::UnsignedLong(v) => mlua::Value::Integer(v as i64),
             ColValue::LongLong(v) => mlua::Value::Integer(v),
-            ColValue::UnsignedLongLong(v) => mlua::Value::Integer(v as i64),
+            // lua integers are i64, pass larger values as strings to keep them exact
+            ColValue::UnsignedLongLong(v) => match i64::try_from(v) {
+                Ok(v) => mlua::Value::Integer(v),
+                Err(_) => v.to_string().into_lua(lua)?,
+            },
             ColValue::Year(v) => mlua::Value::Integer(v as i64),
             ColValue::Bit(v) => mlua::Value::Integer(v as i64),
             ColValue::Set(v) => mlua::Value::Integer(v as i64),
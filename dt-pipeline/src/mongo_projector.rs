@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use dt_common::meta::{mongo::mongo_projection::MongoProjection, row_data::RowData};
+
+/// Trims and renames fields of mongo documents by [processor].mongo_projections before they
+/// are sunk, rows of collections without projections are kept as they are.
+pub struct MongoProjector {
+    // key: (db, tb)
+    projections: HashMap<(String, String), MongoProjection>,
+}
+
+impl MongoProjector {
+    pub fn new(projections: &[MongoProjection]) -> Self {
+        Self {
+            projections: projections
+                .iter()
+                .map(|i| ((i.db.clone(), i.tb.clone()), i.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn process(&self, data: &mut Vec<RowData>) {
+        // updates of removed fields only are dropped, there is nothing to update in the target
+        data.retain_mut(|row_data| {
+            match self
+                .projections
+                .get(&(row_data.schema.clone(), row_data.tb.clone()))
+            {
+                Some(projection) => projection.project_row(row_data),
+                None => true,
+            }
+        });
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context};
+use redis::{Connection, Value};
+use regex::Regex;
+
+use dt_common::{
+    config::{connection_auth_config::ConnectionAuthConfig, processor_config::RedisMappingConfig},
+    error::Error,
+    log_warn,
+    meta::{
+        col_value::ColValue,
+        dt_data::{DtData, DtItem},
+        redis::{
+            command::key_parser::KeyParser, redis_entry::RedisEntry, redis_object::RedisObject,
+        },
+        row_data::RowData,
+        row_type::RowType,
+    },
+    utils::redis_util::RedisUtil,
+};
+
+/// Maps redis entries of keys in [processor].redis_mappings to rows of relational tables:
+/// hash -> row, string -> single-column row, so mysql / pg sinkers can write them.
+///
+/// Snapshot entries carry the whole value. A cdc command may change part of a hash, so the
+/// current value of each key it touches is read back from the source, a missing key becomes
+/// a delete. Every entry is followed by a heartbeat with its position, so positions of
+/// unmapped entries still advance.
+pub struct RedisMapper {
+    mappings: Vec<KeyMapping>,
+    key_parser: KeyParser,
+    url: String,
+    connection_auth: ConnectionAuthConfig,
+    // connected on the first cdc entry, snapshot tasks never read back
+    conn: Option<Connection>,
+    conn_db_id: i64,
+}
+
+struct KeyMapping {
+    config: RedisMappingConfig,
+    regex: Regex,
+    key_cols: Vec<String>,
+}
+
+enum KeyValue {
+    String(Vec<u8>),
+    Hash(Vec<(Vec<u8>, Vec<u8>)>),
+    None,
+    // types which can not be mapped, e.g. list, set
+    Other(String),
+}
+
+impl RedisMapper {
+    pub fn new(
+        configs: &[RedisMappingConfig],
+        url: String,
+        connection_auth: ConnectionAuthConfig,
+    ) -> anyhow::Result<Self> {
+        let mut mappings = Vec::new();
+        for config in configs {
+            let (regex, key_cols) = Self::compile_key_pattern(&config.key_pattern)?;
+            mappings.push(KeyMapping {
+                config: config.clone(),
+                regex,
+                key_cols,
+            });
+        }
+        Ok(Self {
+            mappings,
+            key_parser: KeyParser::new(),
+            url,
+            connection_auth,
+            conn: None,
+            conn_db_id: 0,
+        })
+    }
+
+    pub async fn process(&mut self, data: Vec<DtItem>) -> anyhow::Result<Vec<DtItem>> {
+        if !data
+            .iter()
+            .any(|i| matches!(i.dt_data, DtData::Redis { .. }))
+        {
+            return Ok(data);
+        }
+
+        let mut mapped = Vec::with_capacity(data.len());
+        for item in data {
+            let DtData::Redis { entry } = item.dt_data else {
+                mapped.push(item);
+                continue;
+            };
+
+            let rows = if entry.is_base {
+                self.map_base_entry(&entry)
+            } else {
+                self.map_cmd_entry(entry).await?
+            };
+            for row_data in rows {
+                mapped.push(DtItem {
+                    dt_data: DtData::Dml { row_data },
+                    position: item.position.clone(),
+                    data_origin_node: item.data_origin_node.clone(),
+                    trace: None,
+                });
+            }
+            mapped.push(DtItem {
+                dt_data: DtData::Heartbeat {},
+                position: item.position,
+                data_origin_node: item.data_origin_node,
+                trace: None,
+            });
+        }
+        Ok(mapped)
+    }
+
+    fn map_base_entry(&self, entry: &RedisEntry) -> Vec<RowData> {
+        let key = String::from_utf8_lossy(entry.key.as_bytes()).to_string();
+        let Some((index, key_values)) = self.match_key(entry.db_id, &key) else {
+            return Vec::new();
+        };
+        let value = match &entry.value {
+            RedisObject::String(obj) => KeyValue::String(obj.value.bytes.clone()),
+            RedisObject::Hash(obj) => KeyValue::Hash(
+                obj.value
+                    .iter()
+                    .map(|(field, (value, _))| (field.bytes.clone(), value.bytes.clone()))
+                    .collect(),
+            ),
+            _ => KeyValue::Other(entry.get_type()),
+        };
+        Self::build_row(&self.mappings[index], &key, key_values, value)
+            .into_iter()
+            .collect()
+    }
+
+    async fn map_cmd_entry(&mut self, mut entry: RedisEntry) -> anyhow::Result<Vec<RowData>> {
+        if entry.cmd.keys.is_empty() && entry.cmd.parse_keys(&self.key_parser).is_err() {
+            return Ok(Vec::new());
+        }
+
+        let mut rows = Vec::new();
+        for key in entry.cmd.keys.iter() {
+            let Some((index, key_values)) = self.match_key(entry.db_id, key) else {
+                continue;
+            };
+            let value = self.read_back(entry.db_id, key).await?;
+            if let Some(row_data) = Self::build_row(&self.mappings[index], key, key_values, value) {
+                rows.push(row_data);
+            }
+        }
+        Ok(rows)
+    }
+
+    fn match_key(&self, db_id: i64, key: &str) -> Option<(usize, HashMap<String, ColValue>)> {
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            if mapping.config.db_id.is_some_and(|id| id != db_id) {
+                continue;
+            }
+            let Some(captures) = mapping.regex.captures(key) else {
+                continue;
+            };
+            let key_values = mapping
+                .key_cols
+                .iter()
+                .map(|col| {
+                    let value = captures.name(col).map(|m| m.as_str()).unwrap_or_default();
+                    (col.clone(), ColValue::String(value.to_string()))
+                })
+                .collect();
+            return Some((index, key_values));
+        }
+        None
+    }
+
+    fn build_row(
+        mapping: &KeyMapping,
+        key: &str,
+        key_values: HashMap<String, ColValue>,
+        value: KeyValue,
+    ) -> Option<RowData> {
+        let config = &mapping.config;
+        let mut after = key_values.clone();
+        match value {
+            KeyValue::String(value) => {
+                after.insert(config.value_col.clone(), Self::to_col_value(value));
+            }
+            KeyValue::Hash(fields) => {
+                for (field, value) in fields {
+                    let col = String::from_utf8_lossy(&field).to_string();
+                    // fields named as key columns never overwrite values from the key
+                    if !mapping.key_cols.contains(&col) {
+                        after.insert(col, Self::to_col_value(value));
+                    }
+                }
+            }
+            KeyValue::None => {
+                return Some(RowData::new(
+                    config.db.clone(),
+                    config.tb.clone(),
+                    0,
+                    RowType::Delete,
+                    Some(key_values),
+                    None,
+                ));
+            }
+            KeyValue::Other(key_type) => {
+                log_warn!(
+                    "redis key: {}, type: {} can not be mapped to {}.{}, skip",
+                    key,
+                    key_type,
+                    config.db,
+                    config.tb
+                );
+                return None;
+            }
+        }
+        Some(RowData::new(
+            config.db.clone(),
+            config.tb.clone(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        ))
+    }
+
+    async fn read_back(&mut self, db_id: i64, key: &str) -> anyhow::Result<KeyValue> {
+        if self.conn.is_none() {
+            let mut conn = RedisUtil::create_redis_conn(&self.url, &self.connection_auth).await?;
+            if RedisUtil::is_redis_cluster(&mut conn, None) {
+                bail!(Error::ConfigError(
+                    "config [processor].redis_mappings does not support cdc of redis cluster"
+                        .into()
+                ));
+            }
+            self.conn = Some(conn);
+            self.conn_db_id = 0;
+        }
+        let conn = self.conn.as_mut().unwrap();
+        if self.conn_db_id != db_id {
+            RedisUtil::send_cmd(conn, &["SELECT", &db_id.to_string()])?;
+            self.conn_db_id = db_id;
+        }
+
+        let key_type =
+            RedisUtil::parse_result_as_string(RedisUtil::send_cmd(conn, &["TYPE", key])?)?
+                .pop()
+                .unwrap_or_default();
+        let value = match key_type.as_str() {
+            "none" => KeyValue::None,
+            "string" => match RedisUtil::send_cmd(conn, &["GET", key])? {
+                Value::BulkString(value) => KeyValue::String(value),
+                // removed after TYPE
+                _ => KeyValue::None,
+            },
+            "hash" => {
+                let value = RedisUtil::send_cmd(conn, &["HGETALL", key])?;
+                let fields = Self::parse_hash(value)
+                    .with_context(|| format!("failed to read back redis hash: {}", key))?;
+                if fields.is_empty() {
+                    KeyValue::None
+                } else {
+                    KeyValue::Hash(fields)
+                }
+            }
+            _ => KeyValue::Other(key_type),
+        };
+        Ok(value)
+    }
+
+    fn parse_hash(value: Value) -> anyhow::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let to_bytes = |value: Value| match value {
+            Value::BulkString(v) => Ok(v),
+            Value::SimpleString(v) => Ok(v.into_bytes()),
+            Value::Int(v) => Ok(v.to_string().into_bytes()),
+            other => bail!(Error::RedisResultError(format!(
+                "unexpected hash value: {:?}",
+                other
+            ))),
+        };
+
+        let mut fields = Vec::new();
+        match value {
+            // resp2
+            Value::Array(values) => {
+                let mut iter = values.into_iter();
+                while let (Some(field), Some(value)) = (iter.next(), iter.next()) {
+                    fields.push((to_bytes(field)?, to_bytes(value)?));
+                }
+            }
+            // resp3
+            Value::Map(values) => {
+                for (field, value) in values {
+                    fields.push((to_bytes(field)?, to_bytes(value)?));
+                }
+            }
+            other => bail!(Error::RedisResultError(format!(
+                "unexpected HGETALL result: {:?}",
+                other
+            ))),
+        }
+        Ok(fields)
+    }
+
+    fn to_col_value(value: Vec<u8>) -> ColValue {
+        match String::from_utf8(value) {
+            Ok(v) => ColValue::String(v),
+            Err(e) => ColValue::Blob(e.into_bytes()),
+        }
+    }
+
+    /// user:{id}:* -> ^user:(?P<id>.+?):.*$
+    fn compile_key_pattern(key_pattern: &str) -> anyhow::Result<(Regex, Vec<String>)> {
+        let mut regex = String::from("^");
+        let mut key_cols = Vec::new();
+        let mut rest = key_pattern;
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix('{') {
+                let Some(end) = tail.find('}') else {
+                    bail!(Error::ConfigError(format!(
+                        "redis key_pattern: [{}] has unclosed {{",
+                        key_pattern
+                    )));
+                };
+                let col = &tail[..end];
+                if col.is_empty() || !col.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    bail!(Error::ConfigError(format!(
+                        "redis key_pattern: [{}] has invalid column name: [{}]",
+                        key_pattern, col
+                    )));
+                }
+                regex.push_str(&format!("(?P<{}>.+?)", col));
+                key_cols.push(col.to_string());
+                rest = &tail[end + 1..];
+            } else if let Some(tail) = rest.strip_prefix('*') {
+                regex.push_str(".*?");
+                rest = tail;
+            } else {
+                let end = rest.find(['{', '*']).unwrap_or(rest.len());
+                regex.push_str(&regex::escape(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+        regex.push('$');
+        Ok((Regex::new(&regex)?, key_cols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_key_pattern() {
+        let (regex, key_cols) = RedisMapper::compile_key_pattern("user:{tenant}:{id}:*").unwrap();
+        assert_eq!(key_cols, vec!["tenant", "id"]);
+        let captures = regex.captures("user:t.1:100:profile").unwrap();
+        assert_eq!(&captures["tenant"], "t.1");
+        assert_eq!(&captures["id"], "100");
+        assert!(regex.captures("order:t1:100:profile").is_none());
+
+        let (regex, _) = RedisMapper::compile_key_pattern("user:{id}").unwrap();
+        assert_eq!(&regex.captures("user:a:b").unwrap()["id"], "a:b");
+        assert!(regex.captures("user:").is_none());
+
+        assert!(RedisMapper::compile_key_pattern("user:{id").is_err());
+        assert!(RedisMapper::compile_key_pattern("user:{i-d}").is_err());
+    }
+}
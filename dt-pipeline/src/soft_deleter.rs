@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use dt_common::{
+    config::processor_config::SoftDeleteConfig,
+    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+};
+
+// same layout as datetime values of mysql / pg sources, e.g. 2024-02-29 01:25:09.554271
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+/// Converts deletes of tables in [processor].soft_deletes to updates which keep the row in the
+/// target and mark it as deleted, so it works for all targets sinking updates by keys.
+pub struct SoftDeleter {
+    // key: (db, tb)
+    configs: HashMap<(String, String), SoftDeleteConfig>,
+}
+
+impl SoftDeleter {
+    pub fn new(configs: &[SoftDeleteConfig]) -> Self {
+        Self {
+            configs: configs
+                .iter()
+                .map(|i| ((i.db.clone(), i.tb.clone()), i.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn process(&self, data: &mut [RowData]) {
+        let mut deleted_at = None;
+        for row_data in data.iter_mut() {
+            if row_data.row_type != RowType::Delete {
+                continue;
+            }
+            let Some(config) = self
+                .configs
+                .get(&(row_data.schema.clone(), row_data.tb.clone()))
+            else {
+                continue;
+            };
+            let Some(before) = &row_data.before else {
+                continue;
+            };
+
+            let mut after = before.clone();
+            if !config.flag_col.is_empty() {
+                after.insert(
+                    config.flag_col.clone(),
+                    ColValue::String(config.flag_value.clone()),
+                );
+            }
+            if !config.time_col.is_empty() {
+                // rows of the same batch share the time
+                let deleted_at = deleted_at
+                    .get_or_insert_with(|| chrono::Utc::now().format(TIME_FORMAT).to_string());
+                after.insert(
+                    config.time_col.clone(),
+                    ColValue::String(deleted_at.clone()),
+                );
+            }
+            row_data.row_type = RowType::Update;
+            row_data.after = Some(after);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soft_delete() {
+        let config = SoftDeleteConfig {
+            db: "db".into(),
+            tb: "tb".into(),
+            flag_col: "_deleted".into(),
+            flag_value: "1".into(),
+            time_col: "_deleted_at".into(),
+        };
+        let deleter = SoftDeleter::new(&[config]);
+
+        let before = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("name".to_string(), ColValue::String("a".into())),
+        ]);
+        let mut data = vec![
+            RowData::new(
+                "db".into(),
+                "tb".into(),
+                0,
+                RowType::Delete,
+                Some(before.clone()),
+                None,
+            ),
+            RowData::new(
+                "db".into(),
+                "other".into(),
+                0,
+                RowType::Delete,
+                Some(before.clone()),
+                None,
+            ),
+        ];
+        deleter.process(&mut data);
+
+        assert_eq!(data[0].row_type, RowType::Update);
+        assert_eq!(data[0].before, Some(before.clone()));
+        let after = data[0].after.as_ref().unwrap();
+        assert_eq!(after.get("id"), Some(&ColValue::Long(1)));
+        assert_eq!(after.get("_deleted"), Some(&ColValue::String("1".into())));
+        assert!(matches!(
+            after.get("_deleted_at"),
+            Some(ColValue::String(_))
+        ));
+
+        assert_eq!(data[1].row_type, RowType::Delete);
+        assert!(data[1].after.is_none());
+    }
+}
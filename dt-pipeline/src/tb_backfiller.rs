@@ -0,0 +1,378 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::{fs, io::AsyncWriteExt};
+
+use dt_common::{
+    config::config_enums::DbType,
+    log_error, log_info,
+    meta::dt_data::{DtData, DtItem},
+    rdb_filter::RdbFilter,
+};
+use dt_connector::rdb_router::RdbRouter;
+
+/// Backfills tables into a running cdc task, driven by `dt-main backfill` through files:
+/// 1. tables written to the control file are added to the filter, their rows are held instead
+///    of sunk, and `buffering` is reported to `<control file>.report`;
+/// 2. `dt-main backfill` snapshots the tables, then writes them to `<control file>.finished`;
+/// 3. the held rows are sunk before newer ones and `merged` is reported, after that the tables
+///    are synced as others.
+///
+/// While rows are held, the pipeline keeps checkpoints before the oldest of them, and stops
+/// pulling data once they reach max_held_bytes.
+pub struct TbBackfiller {
+    pub control_file: String,
+    pub filter: RdbFilter,
+    pub router: Option<RdbRouter>,
+    backfills: Vec<Backfill>,
+    // held rows of merged tables, sunk with the next dml batch
+    released: Vec<DtItem>,
+    // data size of held and released rows
+    held_bytes: u64,
+    // 0 for no limit
+    max_held_bytes: u64,
+}
+
+struct Backfill {
+    // (schema, tb) in do_tbs format
+    tb_pattern: (String, String),
+    state: BackfillState,
+    held: Vec<DtItem>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillState {
+    Buffering,
+    Merged,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackfillReport {
+    pub schema: String,
+    pub tb: String,
+    pub state: BackfillState,
+    // rows of the table held during the snapshot, sunk when merged
+    pub held_count: usize,
+}
+
+impl TbBackfiller {
+    pub fn new(control_file: String, filter: RdbFilter, router: Option<RdbRouter>) -> Self {
+        Self {
+            control_file,
+            filter,
+            router,
+            backfills: Vec::new(),
+            released: Vec::new(),
+            held_bytes: 0,
+            max_held_bytes: 0,
+        }
+    }
+
+    pub fn with_max_held_mb(mut self, max_held_mb: u64) -> Self {
+        self.max_held_bytes = max_held_mb * 1024 * 1024;
+        self
+    }
+
+    /// Whether any rows are held or released but not sunk yet.
+    pub fn is_holding(&self) -> bool {
+        !self.released.is_empty() || self.backfills.iter().any(|b| !b.held.is_empty())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.max_held_bytes > 0 && self.held_bytes >= self.max_held_bytes
+    }
+
+    pub fn finished_file(control_file: &str) -> String {
+        format!("{}.finished", control_file)
+    }
+
+    pub fn report_file(control_file: &str) -> String {
+        format!("{}.report", control_file)
+    }
+
+    /// Reads tables in the same format as [filter] do_tbs, lines starting with `#` are ignored.
+    pub async fn read_tbs(file: &str, db_type: &DbType) -> anyhow::Result<Vec<(String, String)>> {
+        let config_str = Self::read_config_str(file).await?;
+        if config_str.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(RdbFilter::parse_pair_tokens(&config_str, db_type)?
+            .into_iter()
+            .collect())
+    }
+
+    async fn read_config_str(file: &str) -> anyhow::Result<String> {
+        if !Path::new(file).exists() {
+            return Ok(String::new());
+        }
+        let content = fs::read_to_string(file).await?;
+        Ok(content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join(","))
+    }
+
+    pub async fn poll(&mut self) -> anyhow::Result<()> {
+        let config_str = Self::read_config_str(&self.control_file).await?;
+        if config_str.is_empty() {
+            return Ok(());
+        }
+        // added to the filter before any state changes, so no rows are dropped in between
+        self.filter.add_tbs(&config_str)?;
+
+        let db_type = self.filter.db_type.clone();
+        let finished = Self::read_tbs(&Self::finished_file(&self.control_file), &db_type).await?;
+        for tb_pattern in RdbFilter::parse_pair_tokens(&config_str, &db_type)? {
+            let is_finished = finished.contains(&tb_pattern);
+            let idx = match self
+                .backfills
+                .iter()
+                .position(|b| b.tb_pattern == tb_pattern)
+            {
+                Some(idx) => idx,
+                None => {
+                    // finished by a former run of the task, e.g. before a restart
+                    let state = if is_finished {
+                        BackfillState::Merged
+                    } else {
+                        BackfillState::Buffering
+                    };
+                    self.backfills.push(Backfill {
+                        tb_pattern,
+                        state,
+                        held: Vec::new(),
+                    });
+                    let idx = self.backfills.len() - 1;
+                    if state == BackfillState::Buffering {
+                        self.report(idx).await;
+                    }
+                    continue;
+                }
+            };
+
+            let backfill = &mut self.backfills[idx];
+            match (backfill.state, is_finished) {
+                (BackfillState::Buffering, true) => {
+                    backfill.state = BackfillState::Merged;
+                    self.report(idx).await;
+                    let backfill = &mut self.backfills[idx];
+                    self.released.append(&mut backfill.held);
+                }
+                // backfilled again
+                (BackfillState::Merged, false) => {
+                    backfill.state = BackfillState::Buffering;
+                    self.report(idx).await;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Holds rows of buffering tables, and puts released rows before the others.
+    pub fn process(&mut self, data: Vec<DtItem>) -> Vec<DtItem> {
+        if self.released.is_empty()
+            && self
+                .backfills
+                .iter()
+                .all(|b| b.state == BackfillState::Merged)
+        {
+            return data;
+        }
+
+        let mut result = Vec::with_capacity(data.len());
+        // released rows go with dml only, a batch is sunk by the type of its items
+        if !self.released.is_empty() && Self::is_dml_batch(&data) {
+            log_info!(
+                "sinking {} held rows of backfilled tables",
+                self.released.len()
+            );
+            let released_bytes: u64 = self.released.iter().map(|i| i.get_data_size()).sum();
+            self.held_bytes = self.held_bytes.saturating_sub(released_bytes);
+            result.append(&mut self.released);
+        }
+        for item in data {
+            if let DtData::Dml { row_data } = &item.dt_data {
+                if let Some(idx) = self.get_buffering(&row_data.schema, &row_data.tb) {
+                    self.held_bytes += item.get_data_size();
+                    self.backfills[idx].held.push(item);
+                    continue;
+                }
+            }
+            result.push(item);
+        }
+        result
+    }
+
+    fn get_buffering(&self, schema: &str, tb: &str) -> Option<usize> {
+        // rows carry routed names
+        let (src_schema, src_tb) = match &self.router {
+            Some(router) => router.reverse_get_tb_map(schema, tb),
+            None => (schema, tb),
+        };
+        self.backfills.iter().position(|b| {
+            b.state == BackfillState::Buffering
+                && self
+                    .filter
+                    .match_tb_pattern(&b.tb_pattern, src_schema, src_tb)
+        })
+    }
+
+    fn is_dml_batch(data: &[DtItem]) -> bool {
+        data.iter().all(|i| {
+            matches!(
+                i.dt_data,
+                DtData::Dml { .. }
+                    | DtData::Begin { .. }
+                    | DtData::Commit { .. }
+                    | DtData::Heartbeat {}
+            )
+        })
+    }
+
+    async fn report(&self, idx: usize) {
+        let backfill = &self.backfills[idx];
+        let report = BackfillReport {
+            schema: backfill.tb_pattern.0.clone(),
+            tb: backfill.tb_pattern.1.clone(),
+            state: backfill.state,
+            held_count: backfill.held.len(),
+        };
+        let line = match serde_json::to_string(&report) {
+            Ok(line) => line,
+            Err(err) => {
+                log_error!("failed to serialize table backfill report: {}", err);
+                return;
+            }
+        };
+        log_info!("table backfill: {}", line);
+        if let Err(err) = self.append_report(&line).await {
+            log_error!(
+                "failed to write table backfill report to {}: {}",
+                Self::report_file(&self.control_file),
+                err
+            );
+        }
+    }
+
+    async fn append_report(&self, line: &str) -> anyhow::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::report_file(&self.control_file))
+            .await?;
+        file.write_all(format!("{}\n", line).as_bytes()).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use dt_common::{
+        config::filter_config::FilterConfig,
+        meta::{col_value::ColValue, position::Position, row_data::RowData, row_type::RowType},
+    };
+
+    fn dml(schema: &str, tb: &str) -> DtItem {
+        DtItem {
+            dt_data: DtData::Dml {
+                row_data: RowData::new(
+                    schema.into(),
+                    tb.into(),
+                    0,
+                    RowType::Insert,
+                    None,
+                    Some(HashMap::from([("id".to_string(), ColValue::Long(1))])),
+                ),
+            },
+            position: Position::None,
+            data_origin_node: String::new(),
+            trace: None,
+        }
+    }
+
+    fn tbs(data: &[DtItem]) -> Vec<String> {
+        data.iter()
+            .filter_map(|i| match &i.dt_data {
+                DtData::Dml { row_data } => Some(format!("{}.{}", row_data.schema, row_data.tb)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_backfill() {
+        let dir = std::env::temp_dir().join(format!("tb_backfiller_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let control_file = dir.join("backfill_tbs").to_string_lossy().to_string();
+        let finished_file = TbBackfiller::finished_file(&control_file);
+        let _ = std::fs::remove_file(&finished_file);
+        let _ = std::fs::remove_file(TbBackfiller::report_file(&control_file));
+
+        let config = FilterConfig {
+            do_tbs: "db_1.tb_1".into(),
+            do_events: "*".into(),
+            ..Default::default()
+        };
+        let filter = RdbFilter::from_config(&config, &DbType::Mysql).unwrap();
+        let mut backfiller = TbBackfiller::new(control_file.clone(), filter.clone(), None);
+        assert!(filter.filter_tb("db_1", "tb_2"));
+
+        std::fs::write(&control_file, "# new tables\ndb_1.tb_2\n").unwrap();
+        backfiller.poll().await.unwrap();
+        assert!(!filter.filter_tb("db_1", "tb_2"));
+
+        let data = backfiller.process(vec![dml("db_1", "tb_1"), dml("db_1", "tb_2")]);
+        assert_eq!(tbs(&data), vec!["db_1.tb_1"]);
+        assert!(backfiller.is_holding());
+
+        std::fs::write(&finished_file, "db_1.tb_2\n").unwrap();
+        backfiller.poll().await.unwrap();
+        assert!(backfiller.is_holding());
+        let data = backfiller.process(vec![dml("db_1", "tb_2"), dml("db_1", "tb_1")]);
+        assert_eq!(tbs(&data), vec!["db_1.tb_2", "db_1.tb_2", "db_1.tb_1"]);
+        assert!(!backfiller.is_holding());
+
+        let reports: Vec<BackfillReport> =
+            std::fs::read_to_string(TbBackfiller::report_file(&control_file))
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].state, BackfillState::Buffering);
+        assert_eq!(reports[1].state, BackfillState::Merged);
+        assert_eq!(reports[1].held_count, 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backfill_max_held() {
+        let dir = std::env::temp_dir().join(format!("tb_backfiller_max_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let control_file = dir.join("backfill_tbs").to_string_lossy().to_string();
+        std::fs::write(&control_file, "db_1.tb_2\n").unwrap();
+
+        let filter = RdbFilter::from_config(&FilterConfig::default(), &DbType::Mysql).unwrap();
+        let mut backfiller = TbBackfiller::new(control_file, filter, None);
+        let row_size = dml("db_1", "tb_2").get_data_size();
+        assert!(row_size > 0);
+        backfiller.max_held_bytes = row_size * 2;
+        backfiller.poll().await.unwrap();
+
+        backfiller.process(vec![dml("db_1", "tb_2")]);
+        assert!(!backfiller.is_full());
+        backfiller.process(vec![dml("db_1", "tb_1"), dml("db_1", "tb_2")]);
+        assert!(backfiller.is_full());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
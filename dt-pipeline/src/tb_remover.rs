@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use serde::Serialize;
+use tokio::{fs, io::AsyncWriteExt};
+
+use dt_common::{
+    log_error, log_info,
+    meta::{
+        dt_data::{DtData, DtItem},
+        position::Position,
+    },
+    rdb_filter::RdbFilter,
+};
+
+/// Removes tables from a running task by polling a control file, tables are written in the same
+/// format as [filter] do_tbs. Once the items queued before the removal are sunk, the final
+/// applied position of each removed table is reported to `<control file>.report`.
+pub struct TbRemover {
+    pub control_file: String,
+    pub filter: RdbFilter,
+    pending: Vec<PendingRemoval>,
+}
+
+struct PendingRemoval {
+    schema: String,
+    tb: String,
+    // items in buffer when the removal was requested, they may contain rows of the table
+    in_flight: usize,
+    tb_position: Position,
+    tb_count: u64,
+}
+
+#[derive(Serialize)]
+struct RemovalReport<'a> {
+    schema: &'a str,
+    tb: &'a str,
+    // rows of the table sunk after the removal was requested
+    drained_count: u64,
+    // position of the last sunk row of the table, None if no row was drained
+    tb_position: &'a Position,
+    // position of the last sunk item of the task when the table was quiesced
+    position: &'a Position,
+}
+
+impl TbRemover {
+    pub fn new(control_file: String, filter: RdbFilter) -> Self {
+        Self {
+            control_file,
+            filter,
+            pending: Vec::new(),
+        }
+    }
+
+    pub async fn poll(&mut self, buffer_len: usize) -> anyhow::Result<()> {
+        if !Path::new(&self.control_file).exists() {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(&self.control_file).await?;
+        let config_str = content
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join(",");
+        if config_str.is_empty() {
+            return Ok(());
+        }
+
+        for (schema, tb) in self.filter.remove_tbs(&config_str)? {
+            log_info!(
+                "schema: {}, tb: {}, removed from task, draining {} in-flight items",
+                schema,
+                tb,
+                buffer_len
+            );
+            self.pending.push(PendingRemoval {
+                schema,
+                tb,
+                in_flight: buffer_len,
+                tb_position: Position::None,
+                tb_count: 0,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn observe(&mut self, data: &[DtItem]) {
+        if self.pending.is_empty() || data.is_empty() {
+            return;
+        }
+
+        for removal in self.pending.iter_mut() {
+            let removed = (removal.schema.clone(), removal.tb.clone());
+            for item in data.iter().take(removal.in_flight) {
+                if let DtData::Dml { row_data } = &item.dt_data {
+                    if self
+                        .filter
+                        .match_removed_tb(&removed, &row_data.schema, &row_data.tb)
+                    {
+                        removal.tb_position = item.position.clone();
+                        removal.tb_count += 1;
+                    }
+                }
+            }
+            removal.in_flight = removal.in_flight.saturating_sub(data.len());
+        }
+    }
+
+    /// Called after the observed items are sunk.
+    pub async fn try_report(&mut self, last_received_position: &Position) {
+        if !self.pending.iter().any(|removal| removal.in_flight == 0) {
+            return;
+        }
+
+        let (finished, pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|removal| removal.in_flight == 0);
+        self.pending = pending;
+
+        for removal in finished {
+            let report = RemovalReport {
+                schema: &removal.schema,
+                tb: &removal.tb,
+                drained_count: removal.tb_count,
+                tb_position: &removal.tb_position,
+                position: last_received_position,
+            };
+            let line = match serde_json::to_string(&report) {
+                Ok(line) => line,
+                Err(err) => {
+                    log_error!("failed to serialize table removal report: {}", err);
+                    continue;
+                }
+            };
+            log_info!("table removed: {}", line);
+            if let Err(err) = self.append_report(&line).await {
+                log_error!(
+                    "failed to write table removal report to {}.report: {}",
+                    self.control_file,
+                    err
+                );
+            }
+        }
+    }
+
+    async fn append_report(&self, line: &str) -> anyhow::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(format!("{}.report", self.control_file))
+            .await?;
+        file.write_all(format!("{}\n", line).as_bytes()).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashMap};
+
+use dt_common::{log_info, meta::col_value::ColValue, meta::row_data::RowData};
+
+const BOM: char = '\u{feff}';
+// zero width space / non-joiner / joiner, word joiner, BOM used as zero width no-break space
+const ZERO_WIDTH_CHARS: [char; 5] = ['\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', BOM];
+
+/// Strips BOM, zero-width characters and CESU-8 encoded surrogates (MySQL utf8 columns written
+/// by non-conforming clients) from text values, since they break CSV stream loads and parsers
+/// on analytic targets. Changes are counted per column and reported when the task stops.
+#[derive(Default)]
+pub struct TextCleaner {
+    // key: (schema, tb, col)
+    counters: BTreeMap<(String, String, String), TextCleanCounters>,
+}
+
+#[derive(Default, Debug, PartialEq)]
+struct TextCleanCounters {
+    bom: u64,
+    zero_width: u64,
+    surrogate: u64,
+    // RawString values which are still not valid utf8 after cleaning, kept as they are
+    invalid_utf8: u64,
+}
+
+impl TextCleaner {
+    pub fn process(&mut self, data: &mut [RowData]) {
+        for row_data in data.iter_mut() {
+            for col_values in [&mut row_data.before, &mut row_data.after]
+                .into_iter()
+                .flatten()
+            {
+                Self::clean_col_values(
+                    &mut self.counters,
+                    &row_data.schema,
+                    &row_data.tb,
+                    col_values,
+                );
+            }
+        }
+    }
+
+    pub fn report(&self) {
+        if self.counters.is_empty() {
+            log_info!("text cleaner: no value changed");
+            return;
+        }
+        for ((schema, tb, col), counters) in self.counters.iter() {
+            log_info!(
+                "text cleaner, schema: {}, tb: {}, col: {}, bom: {}, zero_width: {}, surrogate: {}, invalid_utf8: {}",
+                schema,
+                tb,
+                col,
+                counters.bom,
+                counters.zero_width,
+                counters.surrogate,
+                counters.invalid_utf8
+            );
+        }
+    }
+
+    fn clean_col_values(
+        counters: &mut BTreeMap<(String, String, String), TextCleanCounters>,
+        schema: &str,
+        tb: &str,
+        col_values: &mut HashMap<String, ColValue>,
+    ) {
+        for (col, col_value) in col_values.iter_mut() {
+            let mut delta = TextCleanCounters::default();
+            match col_value {
+                ColValue::String(v) => {
+                    if let Some(cleaned) = Self::clean_str(v, &mut delta) {
+                        *v = cleaned;
+                    }
+                }
+                ColValue::RawString(v) => {
+                    if let Some(cleaned) = Self::clean_bytes(v, &mut delta) {
+                        *v = cleaned;
+                    }
+                }
+                _ => continue,
+            }
+
+            if delta == TextCleanCounters::default() {
+                continue;
+            }
+            let counter = counters
+                .entry((schema.into(), tb.into(), col.clone()))
+                .or_default();
+            counter.bom += delta.bom;
+            counter.zero_width += delta.zero_width;
+            counter.surrogate += delta.surrogate;
+            counter.invalid_utf8 += delta.invalid_utf8;
+        }
+    }
+
+    fn clean_str(value: &str, delta: &mut TextCleanCounters) -> Option<String> {
+        if !value.contains(ZERO_WIDTH_CHARS) {
+            return None;
+        }
+
+        let mut cleaned = String::with_capacity(value.len());
+        for (i, c) in value.char_indices() {
+            if i == 0 && c == BOM {
+                delta.bom += 1;
+            } else if ZERO_WIDTH_CHARS.contains(&c) {
+                delta.zero_width += 1;
+            } else {
+                cleaned.push(c);
+            }
+        }
+        Some(cleaned)
+    }
+
+    fn clean_bytes(value: &[u8], delta: &mut TextCleanCounters) -> Option<Vec<u8>> {
+        if let Ok(s) = std::str::from_utf8(value) {
+            return Self::clean_str(s, delta).map(String::into_bytes);
+        }
+
+        // surrogates U+D800..U+DFFF are encoded as ED A0..BF 80..BF, which is invalid in utf8
+        let mut stripped = Vec::with_capacity(value.len());
+        let mut i = 0;
+        while i < value.len() {
+            if value[i] == 0xed
+                && i + 2 < value.len()
+                && (0xa0..=0xbf).contains(&value[i + 1])
+                && (0x80..=0xbf).contains(&value[i + 2])
+            {
+                delta.surrogate += 1;
+                i += 3;
+            } else {
+                stripped.push(value[i]);
+                i += 1;
+            }
+        }
+
+        match String::from_utf8(stripped) {
+            Ok(s) => {
+                let s = Self::clean_str(&s, delta).unwrap_or(s);
+                Some(s.into_bytes())
+            }
+            Err(_) => {
+                // not utf8 text, e.g. latin1 columns, keep the original bytes
+                *delta = TextCleanCounters {
+                    invalid_utf8: 1,
+                    ..Default::default()
+                };
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dt_common::meta::row_type::RowType;
+
+    #[test]
+    fn test_clean_text() {
+        let after = HashMap::from([
+            (
+                "s".to_string(),
+                ColValue::String("\u{feff}a\u{200b}b\u{feff}".into()),
+            ),
+            (
+                "raw".to_string(),
+                ColValue::RawString(vec![b'a', 0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80, b'b']),
+            ),
+            ("latin1".to_string(), ColValue::RawString(vec![b'a', 0xe9])),
+            ("clean".to_string(), ColValue::String("abc".into())),
+            ("n".to_string(), ColValue::Long(1)),
+        ]);
+        let mut data = vec![RowData::new(
+            "db".into(),
+            "tb".into(),
+            0,
+            RowType::Insert,
+            None,
+            Some(after),
+        )];
+
+        let mut cleaner = TextCleaner::default();
+        cleaner.process(&mut data);
+
+        let after = data[0].after.as_ref().unwrap();
+        assert_eq!(after["s"], ColValue::String("ab".into()));
+        assert_eq!(after["raw"], ColValue::RawString(b"ab".to_vec()));
+        assert_eq!(after["latin1"], ColValue::RawString(vec![b'a', 0xe9]));
+        assert_eq!(after["clean"], ColValue::String("abc".into()));
+
+        let key = |col: &str| ("db".to_string(), "tb".to_string(), col.to_string());
+        assert_eq!(
+            cleaner.counters[&key("s")],
+            TextCleanCounters {
+                bom: 1,
+                zero_width: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(cleaner.counters[&key("raw")].surrogate, 2);
+        assert_eq!(cleaner.counters[&key("latin1")].invalid_utf8, 1);
+        assert!(!cleaner.counters.contains_key(&key("clean")));
+    }
+}
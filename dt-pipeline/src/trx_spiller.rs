@@ -0,0 +1,472 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Lines, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use dt_common::{
+    log_info,
+    meta::{
+        col_value::ColValue,
+        dt_data::{DtData, DtItem},
+        position::Position,
+        row_data::RowData,
+        row_type::RowType,
+        tagged_col_value_map,
+    },
+};
+
+const SPILL_FILE_EXT: &str = "spill";
+
+/// Holds items of the open source transaction until its Commit, so that each source transaction
+/// is applied by one target transaction, refer to [pipeline] transactional.
+///
+/// Rows of the open transaction beyond max_held_bytes are spilled to {spill_dir}/{seq}.spill,
+/// one SpilledRow per line, and streamed back to the sinker by chunks once the transaction
+/// commits, so transactions of any size are applied with bounded memory.
+///
+/// A ddl in the open transaction releases the rows before it, since ddls commit implicitly on
+/// the target. Spill files are removed once applied, those left by a former run are removed
+/// by new(), as their transactions are extracted again after the restart.
+pub struct TrxSpiller {
+    spill_dir: PathBuf,
+    max_held_bytes: u64,
+    // items since the Begin of the open transaction, None if no transaction is open
+    held: Option<Vec<DtItem>>,
+    held_bytes: u64,
+    // rows of the open transaction spilled so far
+    spill: Option<SpillWriter>,
+    seq: u64,
+}
+
+/// Released items in the order to apply, all units released by one process() call are applied
+/// in one target transaction.
+pub enum TrxUnit {
+    Items(Vec<DtItem>),
+    Spilled(SpillReader),
+}
+
+struct SpillWriter {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    rows: u64,
+}
+
+/// Reads spilled rows of a committed transaction by chunks of up to chunk_bytes.
+pub struct SpillReader {
+    path: PathBuf,
+    lines: Lines<BufReader<File>>,
+    chunk_bytes: u64,
+    pub rows: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SpilledRow {
+    position: Position,
+    data_origin_node: String,
+    schema: String,
+    tb: String,
+    row_type: RowType,
+    before: Option<SpilledCols>,
+    after: Option<SpilledCols>,
+    data_size: usize,
+    is_not_origin: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    query: Option<Arc<String>>,
+    meta_version: u64,
+}
+
+// ColValue is serialized without its type by default, which can not be read back
+#[derive(Serialize, Deserialize)]
+struct SpilledCols(#[serde(with = "tagged_col_value_map")] BTreeMap<String, ColValue>);
+
+impl TrxSpiller {
+    pub fn new(spill_dir: &str, max_held_mb: u64) -> anyhow::Result<Self> {
+        let spill_dir = PathBuf::from(spill_dir);
+        fs::create_dir_all(&spill_dir)
+            .with_context(|| format!("failed to create dir: [{}]", spill_dir.display()))?;
+        for entry in fs::read_dir(&spill_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == SPILL_FILE_EXT) {
+                fs::remove_file(&path)
+                    .with_context(|| format!("failed to remove file: [{}]", path.display()))?;
+                log_info!(
+                    "removed spill file left by a former run: {}",
+                    path.display()
+                );
+            }
+        }
+
+        Ok(Self {
+            spill_dir,
+            max_held_bytes: max_held_mb * 1024 * 1024,
+            held: None,
+            held_bytes: 0,
+            spill: None,
+            seq: 0,
+        })
+    }
+
+    /// Whether a transaction is open, i.e. its items are held or spilled.
+    pub fn is_holding(&self) -> bool {
+        self.held.is_some()
+    }
+
+    /// Holds items of the open transaction, and releases committed transactions and items out
+    /// of transactions, e.g. heartbeats and ddls.
+    pub fn process(&mut self, data: Vec<DtItem>) -> anyhow::Result<Vec<TrxUnit>> {
+        let mut units = Vec::new();
+        let mut items = Vec::with_capacity(data.len());
+        for item in data {
+            let Some(held) = self.held.as_mut() else {
+                if item.dt_data.is_begin() {
+                    self.held = Some(vec![item]);
+                } else {
+                    items.push(item);
+                }
+                continue;
+            };
+
+            match &item.dt_data {
+                DtData::Commit { .. } => {
+                    held.push(item);
+                    self.release(&mut units, &mut items)?;
+                    self.held = None;
+                }
+                DtData::Ddl { .. } | DtData::Dcl { .. } => {
+                    self.release(&mut units, &mut items)?;
+                    items.push(item);
+                }
+                _ => {
+                    self.held_bytes += item.get_data_size();
+                    held.push(item);
+                    if self.max_held_bytes > 0 && self.held_bytes >= self.max_held_bytes {
+                        self.spill_held()?;
+                    }
+                }
+            }
+        }
+
+        if !items.is_empty() {
+            units.push(TrxUnit::Items(items));
+        }
+        Ok(units)
+    }
+
+    // releases held and spilled items of the open transaction, it stays open
+    fn release(&mut self, units: &mut Vec<TrxUnit>, items: &mut Vec<DtItem>) -> anyhow::Result<()> {
+        let held = self.held.replace(Vec::new()).unwrap_or_default();
+        self.held_bytes = 0;
+        if let Some(spill) = self.spill.take() {
+            if !items.is_empty() {
+                units.push(TrxUnit::Items(std::mem::take(items)));
+            }
+            units.push(TrxUnit::Spilled(spill.finish(self.max_held_bytes)?));
+        }
+        items.extend(held);
+        Ok(())
+    }
+
+    // begin and heartbeats of the open transaction are dropped, its commit comes later
+    fn spill_held(&mut self) -> anyhow::Result<()> {
+        let Some(held) = self.held.as_mut() else {
+            return Ok(());
+        };
+        if self.spill.is_none() {
+            let path = self
+                .spill_dir
+                .join(format!("{}.{}", self.seq, SPILL_FILE_EXT));
+            self.seq += 1;
+            log_info!(
+                "open transaction exceeds {} bytes, spilling rows to {}",
+                self.max_held_bytes,
+                path.display()
+            );
+            self.spill = Some(SpillWriter::new(path)?);
+        }
+
+        let spill = self.spill.as_mut().unwrap();
+        for item in held.drain(..) {
+            if let DtData::Dml { row_data } = item.dt_data {
+                spill.write(item.position, item.data_origin_node, row_data)?;
+            }
+        }
+        self.held_bytes = 0;
+        Ok(())
+    }
+}
+
+impl SpillWriter {
+    fn new(path: PathBuf) -> anyhow::Result<Self> {
+        let file = File::create(&path)
+            .with_context(|| format!("failed to create file: [{}]", path.display()))?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            rows: 0,
+        })
+    }
+
+    fn write(
+        &mut self,
+        position: Position,
+        data_origin_node: String,
+        row_data: RowData,
+    ) -> anyhow::Result<()> {
+        let to_cols = |cols: Option<HashMap<String, ColValue>>| {
+            cols.map(|cols| SpilledCols(cols.into_iter().collect()))
+        };
+        let row = SpilledRow {
+            position,
+            data_origin_node,
+            schema: row_data.schema,
+            tb: row_data.tb,
+            row_type: row_data.row_type,
+            before: to_cols(row_data.before),
+            after: to_cols(row_data.after),
+            data_size: row_data.data_size,
+            is_not_origin: row_data.is_not_origin,
+            query: row_data.query,
+            meta_version: row_data.meta_version,
+        };
+        serde_json::to_writer(&mut self.writer, &row)?;
+        self.writer.write_all(b"\n")?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self, chunk_bytes: u64) -> anyhow::Result<SpillReader> {
+        self.writer.flush()?;
+        drop(self.writer);
+        SpillReader::new(self.path, chunk_bytes, self.rows)
+    }
+}
+
+impl SpillReader {
+    fn new(path: PathBuf, chunk_bytes: u64, rows: u64) -> anyhow::Result<Self> {
+        let file = File::open(&path)
+            .with_context(|| format!("failed to open file: [{}]", path.display()))?;
+        Ok(Self {
+            path,
+            lines: BufReader::new(file).lines(),
+            chunk_bytes,
+            rows,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Next rows in the spilled order, empty once all are read.
+    pub fn next_chunk(&mut self) -> anyhow::Result<Vec<DtItem>> {
+        let mut chunk = Vec::new();
+        let mut chunk_bytes = 0;
+        while chunk_bytes < self.chunk_bytes {
+            let Some(line) = self.lines.next() else {
+                break;
+            };
+            let row: SpilledRow = serde_json::from_str(&line?)
+                .with_context(|| format!("invalid spill file: [{}]", self.path.display()))?;
+            chunk_bytes += row.data_size as u64;
+            chunk.push(row.into_item());
+        }
+        Ok(chunk)
+    }
+
+    /// Removes the spill file once its rows are applied.
+    pub fn finish(self) -> anyhow::Result<()> {
+        fs::remove_file(&self.path)
+            .with_context(|| format!("failed to remove file: [{}]", self.path.display()))
+    }
+}
+
+impl SpilledRow {
+    fn into_item(self) -> DtItem {
+        let from_cols = |cols: Option<SpilledCols>| cols.map(|cols| cols.0.into_iter().collect());
+        let mut row_data = RowData::new(
+            self.schema,
+            self.tb,
+            0,
+            self.row_type,
+            from_cols(self.before),
+            from_cols(self.after),
+        );
+        row_data.data_size = self.data_size;
+        row_data.is_not_origin = self.is_not_origin;
+        row_data.query = self.query;
+        row_data.meta_version = self.meta_version;
+        DtItem {
+            dt_data: DtData::Dml { row_data },
+            position: self.position,
+            data_origin_node: self.data_origin_node,
+            trace: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dt_common::meta::ddl_meta::ddl_data::DdlData;
+
+    fn dml(id: i32) -> DtItem {
+        DtItem {
+            dt_data: DtData::Dml {
+                row_data: RowData::new(
+                    "db1".into(),
+                    "tb1".into(),
+                    0,
+                    RowType::Insert,
+                    None,
+                    Some(HashMap::from([
+                        ("id".to_string(), ColValue::Long(id)),
+                        ("value".to_string(), ColValue::Decimal("1.10".into())),
+                    ])),
+                ),
+            },
+            position: Position::None,
+            data_origin_node: String::new(),
+            trace: None,
+        }
+    }
+
+    fn control(dt_data: DtData) -> DtItem {
+        DtItem {
+            dt_data,
+            position: Position::None,
+            data_origin_node: String::new(),
+            trace: None,
+        }
+    }
+
+    fn begin() -> DtItem {
+        control(DtData::Begin { xid: "1".into() })
+    }
+
+    fn commit() -> DtItem {
+        control(DtData::Commit { xid: "1".into() })
+    }
+
+    fn ids(items: &[DtItem]) -> Vec<i32> {
+        items
+            .iter()
+            .filter_map(|i| match &i.dt_data {
+                DtData::Dml { row_data } => match row_data.after.as_ref()?.get("id")? {
+                    ColValue::Long(id) => Some(*id),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn spill_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("trx_spiller_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_hold_until_commit() {
+        let dir = spill_dir("hold");
+        let mut spiller = TrxSpiller::new(&dir, 64).unwrap();
+
+        let units = spiller.process(vec![dml(1), begin(), dml(2)]).unwrap();
+        assert!(spiller.is_holding());
+        let [TrxUnit::Items(items)] = units.as_slice() else {
+            panic!("unexpected units");
+        };
+        assert_eq!(ids(items), vec![1]);
+
+        let units = spiller.process(vec![dml(3), commit(), dml(4)]).unwrap();
+        assert!(!spiller.is_holding());
+        let [TrxUnit::Items(items)] = units.as_slice() else {
+            panic!("unexpected units");
+        };
+        assert_eq!(ids(items), vec![2, 3, 4]);
+        assert!(items[3].dt_data.is_commit());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_spill() {
+        let dir = spill_dir("spill");
+        // a spill file left by a former run
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(Path::new(&dir).join("0.spill"), "").unwrap();
+
+        let mut spiller = TrxSpiller::new(&dir, 0).unwrap();
+        assert!(!Path::new(&dir).join("0.spill").exists());
+        // spill on every row
+        spiller.max_held_bytes = 1;
+
+        let units = spiller.process(vec![begin(), dml(1), dml(2)]).unwrap();
+        assert!(units.is_empty());
+        let mut units = spiller
+            .process(vec![dml(3), commit(), dml(4)])
+            .unwrap()
+            .into_iter();
+        let Some(TrxUnit::Spilled(mut reader)) = units.next() else {
+            panic!("unexpected units");
+        };
+        let Some(TrxUnit::Items(items)) = units.next() else {
+            panic!("unexpected units");
+        };
+        assert!(units.next().is_none());
+        // only the commit is left in memory
+        assert_eq!(ids(&items), vec![4]);
+        assert!(items[0].dt_data.is_commit());
+
+        assert_eq!(reader.rows, 3);
+        let mut spilled = Vec::new();
+        loop {
+            let chunk = reader.next_chunk().unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            spilled.extend(chunk);
+        }
+        assert_eq!(ids(&spilled), vec![1, 2, 3]);
+        let DtData::Dml { row_data } = &spilled[0].dt_data else {
+            panic!("unexpected item");
+        };
+        let DtData::Dml { row_data: origin } = &dml(1).dt_data else {
+            panic!("unexpected item");
+        };
+        // values are read back with their types
+        assert_eq!(row_data, origin);
+
+        let path = reader.path().to_path_buf();
+        reader.finish().unwrap();
+        assert!(!path.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_release_before_ddl() {
+        let dir = spill_dir("ddl");
+        let mut spiller = TrxSpiller::new(&dir, 64).unwrap();
+
+        let ddl = control(DtData::Ddl {
+            ddl_data: DdlData::default(),
+        });
+        spiller.process(vec![begin(), dml(1)]).unwrap();
+        let units = spiller.process(vec![ddl]).unwrap();
+        let [TrxUnit::Items(items)] = units.as_slice() else {
+            panic!("unexpected units");
+        };
+        assert_eq!(ids(items), vec![1]);
+        assert!(items.last().unwrap().is_ddl());
+        // the rest of the transaction is still held
+        assert!(spiller.is_holding());
+        assert!(spiller.process(vec![dml(2)]).unwrap().is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
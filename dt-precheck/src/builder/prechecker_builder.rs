@@ -2,7 +2,11 @@ use std::vec;
 
 use anyhow::bail;
 use dt_common::{
-    config::{config_enums::DbType, task_config::TaskConfig},
+    config::{
+        config_enums::{DbType, ReplicaIdentityPolicy},
+        extractor_config::ExtractorConfig,
+        task_config::TaskConfig,
+    },
     rdb_filter::RdbFilter,
 };
 
@@ -54,12 +58,41 @@ impl PrecheckerBuilder {
             )
         };
 
+        let (slot_name, pub_name, pub_for_filtered_tables, replica_identity_policy) =
+            match &self.task_config.extractor {
+                ExtractorConfig::PgCdc {
+                    slot_name,
+                    pub_name,
+                    pub_for_filtered_tables,
+                    replica_identity_policy,
+                    ..
+                } if is_source => (
+                    slot_name.clone(),
+                    pub_name.clone(),
+                    *pub_for_filtered_tables,
+                    replica_identity_policy.clone(),
+                ),
+                _ => (
+                    String::new(),
+                    String::new(),
+                    false,
+                    ReplicaIdentityPolicy::default(),
+                ),
+            };
+
+        let heartbeat_tb = if is_source {
+            self.task_config.extractor.heartbeat_tb(&db_type)
+        } else {
+            None
+        };
+
         let filter = RdbFilter::from_config(&self.task_config.filter, &db_type).unwrap();
         let checker: Option<Box<dyn Prechecker + Send>> = match db_type {
             DbType::Mysql => Some(Box::new(MySqlPrechecker {
                 filter_config: self.task_config.filter.clone(),
                 precheck_config: self.precheck_config.clone(),
                 is_source,
+                heartbeat_tb,
                 fetcher: MysqlFetcher {
                     pool: None,
                     url,
@@ -72,6 +105,11 @@ impl PrecheckerBuilder {
                 filter_config: self.task_config.filter.clone(),
                 precheck_config: self.precheck_config.clone(),
                 is_source,
+                slot_name: slot_name.clone(),
+                pub_name: pub_name.clone(),
+                pub_for_filtered_tables,
+                replica_identity_policy,
+                heartbeat_tb,
                 fetcher: PgFetcher {
                     pool: None,
                     url,
@@ -114,6 +152,13 @@ impl PrecheckerBuilder {
         if !self.valid_config() {
             bail! {"config is invalid."};
         }
+        // invalid filter patterns, e.g. regexes, fail the precheck before building checkers
+        for db_type in [
+            &self.task_config.extractor_basic.db_type,
+            &self.task_config.sinker_basic.db_type,
+        ] {
+            RdbFilter::from_config(&self.task_config.filter, db_type)?;
+        }
         let (source_checker_option, sink_checker_option) =
             (self.build_checker(true), self.build_checker(false));
         if source_checker_option.is_none() || sink_checker_option.is_none() {
@@ -148,6 +193,10 @@ impl PrecheckerBuilder {
         if self.precheck_config.do_cdc {
             println!("[*]begin to check the cdc setting");
             check_results.push(source_checker.check_cdc_supported().await);
+            match source_checker.check_cdc_details().await {
+                Ok(results) => check_results.extend(results.into_iter().map(Ok)),
+                Err(e) => check_results.push(Err(e)),
+            }
         }
 
         println!("[*]begin to check the if the structs is existed or not");
@@ -0,0 +1,212 @@
+use std::collections::{BTreeSet, HashSet};
+
+use dt_common::{config::config_enums::DbType, utils::sql_util::SqlUtil};
+
+#[derive(Debug, Clone, Default)]
+pub struct TableProfile {
+    pub schema: String,
+    pub tb: String,
+    // estimated by statistics of the source, may be stale if tables are not analyzed
+    pub rows: u64,
+    pub size_bytes: u64,
+    pub has_pk: bool,
+    pub has_uk: bool,
+    pub spatial_cols: Vec<String>,
+    pub huge_blob_cols: Vec<String>,
+    // (schema, tb) referenced by foreign keys
+    pub fk_refs: Vec<(String, String)>,
+}
+
+/// Result of the discovery task: a [filter] section with all discovered tables,
+/// and a risk report of tables which need attention before the real task.
+pub struct DiscoveryReport {
+    pub db_type: DbType,
+    pub tables: Vec<TableProfile>,
+}
+
+impl DiscoveryReport {
+    pub fn risks(&self, table: &TableProfile) -> Vec<String> {
+        let mut risks = Vec::new();
+        if !table.has_pk && !table.has_uk {
+            risks.push(
+                "no primary / unique key: not splittable into chunks, not checkable, duplicates on retry"
+                    .to_string(),
+            );
+        } else if !table.has_pk {
+            risks.push("no primary key: unique key used as identity".to_string());
+        }
+        if !table.spatial_cols.is_empty() {
+            risks.push(format!("spatial columns: {}", table.spatial_cols.join(",")));
+        }
+        if !table.huge_blob_cols.is_empty() {
+            risks.push(format!(
+                "huge blob columns: {}",
+                table.huge_blob_cols.join(",")
+            ));
+        }
+
+        let discovered: HashSet<(&str, &str)> = self
+            .tables
+            .iter()
+            .map(|t| (t.schema.as_str(), t.tb.as_str()))
+            .collect();
+        let missing_refs: BTreeSet<String> = table
+            .fk_refs
+            .iter()
+            .filter(|(schema, tb)| !discovered.contains(&(schema.as_str(), tb.as_str())))
+            .map(|(schema, tb)| format!("{}.{}", schema, tb))
+            .collect();
+        if !missing_refs.is_empty() {
+            risks.push(format!(
+                "references tables not included: {}",
+                missing_refs.into_iter().collect::<Vec<_>>().join(",")
+            ));
+        }
+        risks
+    }
+
+    pub fn to_filter_config(&self) -> String {
+        let risky_tbs: Vec<String> = self
+            .tables
+            .iter()
+            .filter(|t| !self.risks(t).is_empty())
+            .map(|t| self.to_filter_tb(t))
+            .collect();
+        let do_tbs: Vec<String> = self.tables.iter().map(|t| self.to_filter_tb(t)).collect();
+        let schemas: HashSet<&str> = self.tables.iter().map(|t| t.schema.as_str()).collect();
+
+        let mut lines = vec![
+            "[filter]".to_string(),
+            format!(
+                "# {} tables in {} schemas discovered, {} with risks",
+                do_tbs.len(),
+                schemas.len(),
+                risky_tbs.len()
+            ),
+            format!("do_tbs={}", do_tbs.join(",")),
+        ];
+        if !risky_tbs.is_empty() {
+            lines.push(
+                "# tables with risks, see the risk report, uncomment to skip them".to_string(),
+            );
+            lines.push(format!("# ignore_tbs={}", risky_tbs.join(",")));
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_risk_report(&self) -> String {
+        let mut lines = vec![format!(
+            "{:<64} {:>14} {:>12} {:>4} {:>4}  risks",
+            "table", "rows", "size", "pk", "fks"
+        )];
+        let (mut total_rows, mut total_size) = (0, 0);
+        for table in self.tables.iter() {
+            total_rows += table.rows;
+            total_size += table.size_bytes;
+            let risks = self.risks(table);
+            lines.push(format!(
+                "{:<64} {:>14} {:>12} {:>4} {:>4}  {}",
+                format!("{}.{}", table.schema, table.tb),
+                table.rows,
+                Self::format_size(table.size_bytes),
+                if table.has_pk { "yes" } else { "no" },
+                table.fk_refs.len(),
+                if risks.is_empty() {
+                    "-".to_string()
+                } else {
+                    risks.join("; ")
+                }
+            ));
+        }
+        lines.push(format!(
+            "total: {} tables, {} rows, {}",
+            self.tables.len(),
+            total_rows,
+            Self::format_size(total_size)
+        ));
+        lines.join("\n")
+    }
+
+    fn to_filter_tb(&self, table: &TableProfile) -> String {
+        format!(
+            "{}.{}",
+            self.to_filter_token(&table.schema),
+            self.to_filter_token(&table.tb)
+        )
+    }
+
+    // names with chars other than [a-zA-Z0-9_] are escaped, wildcards included
+    fn to_filter_token(&self, token: &str) -> String {
+        if token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            token.to_string()
+        } else {
+            SqlUtil::escape_by_db_type(token, &self.db_type)
+        }
+    }
+
+    fn format_size(bytes: u64) -> String {
+        const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+        let mut size = bytes as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{}{}", bytes, UNITS[0])
+        } else {
+            format!("{:.1}{}", size, UNITS[unit])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(schema: &str, tb: &str) -> TableProfile {
+        TableProfile {
+            schema: schema.into(),
+            tb: tb.into(),
+            has_pk: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_discovery_report() {
+        let report = DiscoveryReport {
+            db_type: DbType::Mysql,
+            tables: vec![
+                TableProfile {
+                    fk_refs: vec![("db1".into(), "tb_2".into()), ("db2".into(), "tb".into())],
+                    ..profile("db1", "tb_1")
+                },
+                TableProfile {
+                    has_pk: false,
+                    spatial_cols: vec!["location".into()],
+                    ..profile("db1", "tb_2")
+                },
+                profile("db1", "tb-3"),
+            ],
+        };
+
+        assert_eq!(
+            report.risks(&report.tables[0]),
+            vec!["references tables not included: db2.tb".to_string()]
+        );
+        assert_eq!(report.risks(&report.tables[1]).len(), 2);
+        assert!(report.risks(&report.tables[2]).is_empty());
+
+        assert_eq!(
+            report.to_filter_config(),
+            "[filter]
+# 3 tables in 1 schemas discovered, 2 with risks
+do_tbs=db1.tb_1,db1.tb_2,db1.`tb-3`
+# tables with risks, see the risk report, uncomment to skip them
+# ignore_tbs=db1.tb_1,db1.tb_2"
+        );
+        assert_eq!(DiscoveryReport::format_size(1000), "1000B");
+        assert_eq!(DiscoveryReport::format_size(3 * 1024 * 1024 / 2), "1.5MB");
+    }
+}
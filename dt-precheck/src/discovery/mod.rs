@@ -0,0 +1,4 @@
+pub mod discovery_report;
+pub mod mysql_discoverer;
+pub mod pg_discoverer;
+pub mod traits;
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use sqlx::{mysql::MySqlRow, query, MySql, Pool, Row};
+
+use dt_common::{
+    config::{config_enums::DbType, connection_auth_config::ConnectionAuthConfig},
+    error::Error,
+    rdb_filter::RdbFilter,
+    utils::sql_util::SqlUtil,
+};
+use dt_task::task_util::TaskUtil;
+
+use super::{discovery_report::TableProfile, traits::Discoverer};
+
+const SYSTEM_SCHEMAS: &str = "'information_schema','mysql','performance_schema','sys'";
+const SPATIAL_TYPES: [&str; 9] = [
+    "geometry",
+    "point",
+    "linestring",
+    "polygon",
+    "multipoint",
+    "multilinestring",
+    "multipolygon",
+    "geometrycollection",
+    "geomcollection",
+];
+// types which may hold values larger than max_allowed_packet of the target
+const HUGE_BLOB_TYPES: [&str; 3] = ["longblob", "longtext", "json"];
+
+pub struct MysqlDiscoverer {
+    pub pool: Option<Pool<MySql>>,
+    pub url: String,
+    pub connection_auth: ConnectionAuthConfig,
+    pub filter: RdbFilter,
+}
+
+#[async_trait]
+impl Discoverer for MysqlDiscoverer {
+    async fn build_connection(&mut self) -> anyhow::Result<()> {
+        self.pool = Some(
+            TaskUtil::create_mysql_conn_pool(
+                &self.url,
+                &DbType::Mysql,
+                &self.connection_auth,
+                1,
+                true,
+                None,
+            )
+            .await?,
+        );
+        Ok(())
+    }
+
+    async fn discover(&mut self) -> anyhow::Result<Vec<TableProfile>> {
+        let mut tables = BTreeMap::new();
+
+        let sql = format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME,
+                CAST(IFNULL(TABLE_ROWS, 0) AS UNSIGNED) AS TB_ROWS,
+                CAST(IFNULL(DATA_LENGTH, 0) + IFNULL(INDEX_LENGTH, 0) AS UNSIGNED) AS TB_SIZE
+            FROM information_schema.TABLES
+            WHERE TABLE_TYPE = 'BASE TABLE' AND TABLE_SCHEMA NOT IN ({})",
+            SYSTEM_SCHEMAS
+        );
+        for row in self.fetch_all(&sql).await? {
+            let (schema, tb) = Self::get_schema_tb(&row, "TABLE_SCHEMA", "TABLE_NAME")?;
+            if self.filter.filter_tb(&schema, &tb) {
+                continue;
+            }
+            let profile = TableProfile {
+                schema: schema.clone(),
+                tb: tb.clone(),
+                rows: row.try_get("TB_ROWS")?,
+                size_bytes: row.try_get("TB_SIZE")?,
+                ..Default::default()
+            };
+            tables.insert((schema, tb), profile);
+        }
+
+        let sql = format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME, CONSTRAINT_TYPE
+            FROM information_schema.TABLE_CONSTRAINTS
+            WHERE CONSTRAINT_TYPE IN ('PRIMARY KEY', 'UNIQUE') AND TABLE_SCHEMA NOT IN ({})",
+            SYSTEM_SCHEMAS
+        );
+        for row in self.fetch_all(&sql).await? {
+            let key = Self::get_schema_tb(&row, "TABLE_SCHEMA", "TABLE_NAME")?;
+            if let Some(profile) = tables.get_mut(&key) {
+                match SqlUtil::try_get_mysql_string(&row, "CONSTRAINT_TYPE")?.as_str() {
+                    "PRIMARY KEY" => profile.has_pk = true,
+                    _ => profile.has_uk = true,
+                }
+            }
+        }
+
+        let data_types = SPATIAL_TYPES
+            .iter()
+            .chain(HUGE_BLOB_TYPES.iter())
+            .map(|t| format!("'{}'", t))
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME, COLUMN_NAME, DATA_TYPE
+            FROM information_schema.COLUMNS
+            WHERE DATA_TYPE IN ({}) AND TABLE_SCHEMA NOT IN ({})
+            ORDER BY TABLE_SCHEMA, TABLE_NAME, ORDINAL_POSITION",
+            data_types, SYSTEM_SCHEMAS
+        );
+        for row in self.fetch_all(&sql).await? {
+            let key = Self::get_schema_tb(&row, "TABLE_SCHEMA", "TABLE_NAME")?;
+            if let Some(profile) = tables.get_mut(&key) {
+                let col = SqlUtil::try_get_mysql_string(&row, "COLUMN_NAME")?;
+                let data_type = SqlUtil::try_get_mysql_string(&row, "DATA_TYPE")?;
+                if SPATIAL_TYPES.contains(&data_type.to_lowercase().as_str()) {
+                    profile.spatial_cols.push(col);
+                } else {
+                    profile.huge_blob_cols.push(col);
+                }
+            }
+        }
+
+        let sql = format!(
+            "SELECT DISTINCT TABLE_SCHEMA, TABLE_NAME, REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME
+            FROM information_schema.KEY_COLUMN_USAGE
+            WHERE REFERENCED_TABLE_NAME IS NOT NULL AND TABLE_SCHEMA NOT IN ({})",
+            SYSTEM_SCHEMAS
+        );
+        for row in self.fetch_all(&sql).await? {
+            let key = Self::get_schema_tb(&row, "TABLE_SCHEMA", "TABLE_NAME")?;
+            let fk_ref =
+                Self::get_schema_tb(&row, "REFERENCED_TABLE_SCHEMA", "REFERENCED_TABLE_NAME")?;
+            if let Some(profile) = tables.get_mut(&key) {
+                profile.fk_refs.push(fk_ref);
+            }
+        }
+
+        Ok(tables.into_values().collect())
+    }
+}
+
+impl MysqlDiscoverer {
+    async fn fetch_all(&self, sql: &str) -> anyhow::Result<Vec<MySqlRow>> {
+        let Some(pool) = &self.pool else {
+            bail! {Error::from(sqlx::Error::PoolClosed)}
+        };
+        Ok(query(sql).fetch_all(pool).await?)
+    }
+
+    fn get_schema_tb(
+        row: &MySqlRow,
+        schema_col: &str,
+        tb_col: &str,
+    ) -> anyhow::Result<(String, String)> {
+        Ok((
+            SqlUtil::try_get_mysql_string(row, schema_col)?,
+            SqlUtil::try_get_mysql_string(row, tb_col)?,
+        ))
+    }
+}
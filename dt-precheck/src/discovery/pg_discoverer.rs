@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use sqlx::{postgres::PgRow, query, Pool, Postgres, Row};
+
+use dt_common::{
+    config::connection_auth_config::ConnectionAuthConfig, error::Error, rdb_filter::RdbFilter,
+};
+use dt_task::task_util::TaskUtil;
+
+use super::{discovery_report::TableProfile, traits::Discoverer};
+
+const SYSTEM_SCHEMAS_CONDITION: &str = "n.nspname NOT IN ('pg_catalog', 'information_schema')
+    AND n.nspname NOT LIKE 'pg_toast%' AND n.nspname NOT LIKE 'pg_temp%'";
+// postgis and built-in geometric types
+const SPATIAL_TYPES: [&str; 9] = [
+    "geometry",
+    "geography",
+    "point",
+    "line",
+    "lseg",
+    "box",
+    "path",
+    "polygon",
+    "circle",
+];
+// bytea up to 1GB and large objects of the lo extension
+const HUGE_BLOB_TYPES: [&str; 2] = ["bytea", "lo"];
+
+pub struct PgDiscoverer {
+    pub pool: Option<Pool<Postgres>>,
+    pub url: String,
+    pub connection_auth: ConnectionAuthConfig,
+    pub filter: RdbFilter,
+}
+
+#[async_trait]
+impl Discoverer for PgDiscoverer {
+    async fn build_connection(&mut self) -> anyhow::Result<()> {
+        self.pool = Some(
+            TaskUtil::create_pg_conn_pool(&self.url, &self.connection_auth, 1, true, false).await?,
+        );
+        Ok(())
+    }
+
+    async fn discover(&mut self) -> anyhow::Result<Vec<TableProfile>> {
+        let mut tables = BTreeMap::new();
+
+        let sql = format!(
+            "SELECT n.nspname::text AS schema_name,
+                c.relname::text AS table_name,
+                GREATEST(c.reltuples, 0)::int8 AS tb_rows,
+                pg_catalog.pg_total_relation_size(c.oid)::int8 AS tb_size,
+                EXISTS (SELECT 1 FROM pg_catalog.pg_index i WHERE i.indrelid = c.oid AND i.indisprimary) AS has_pk,
+                EXISTS (SELECT 1 FROM pg_catalog.pg_index i WHERE i.indrelid = c.oid AND i.indisunique AND NOT i.indisprimary) AS has_uk
+            FROM pg_catalog.pg_class c
+            JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+            WHERE c.relkind IN ('r', 'p') AND {}",
+            SYSTEM_SCHEMAS_CONDITION
+        );
+        for row in self.fetch_all(&sql).await? {
+            let (schema, tb): (String, String) = (row.get("schema_name"), row.get("table_name"));
+            if self.filter.filter_tb(&schema, &tb) {
+                continue;
+            }
+            let profile = TableProfile {
+                schema: schema.clone(),
+                tb: tb.clone(),
+                rows: row.get::<i64, _>("tb_rows") as u64,
+                size_bytes: row.get::<i64, _>("tb_size") as u64,
+                has_pk: row.get("has_pk"),
+                has_uk: row.get("has_uk"),
+                ..Default::default()
+            };
+            tables.insert((schema, tb), profile);
+        }
+
+        let type_names = SPATIAL_TYPES
+            .iter()
+            .chain(HUGE_BLOB_TYPES.iter())
+            .map(|t| format!("'{}'", t))
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            "SELECT n.nspname::text AS schema_name,
+                c.relname::text AS table_name,
+                a.attname::text AS column_name,
+                t.typname::text AS type_name
+            FROM pg_catalog.pg_attribute a
+            JOIN pg_catalog.pg_class c ON a.attrelid = c.oid
+            JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+            JOIN pg_catalog.pg_type t ON a.atttypid = t.oid
+            WHERE a.attnum > 0 AND NOT a.attisdropped AND t.typname IN ({}) AND {}
+            ORDER BY n.nspname, c.relname, a.attnum",
+            type_names, SYSTEM_SCHEMAS_CONDITION
+        );
+        for row in self.fetch_all(&sql).await? {
+            let key: (String, String) = (row.get("schema_name"), row.get("table_name"));
+            if let Some(profile) = tables.get_mut(&key) {
+                let col: String = row.get("column_name");
+                let type_name: String = row.get("type_name");
+                if SPATIAL_TYPES.contains(&type_name.as_str()) {
+                    profile.spatial_cols.push(col);
+                } else {
+                    profile.huge_blob_cols.push(col);
+                }
+            }
+        }
+
+        let sql = format!(
+            "SELECT DISTINCT n.nspname::text AS schema_name,
+                c.relname::text AS table_name,
+                rn.nspname::text AS ref_schema_name,
+                rc.relname::text AS ref_table_name
+            FROM pg_catalog.pg_constraint con
+            JOIN pg_catalog.pg_class c ON con.conrelid = c.oid
+            JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+            JOIN pg_catalog.pg_class rc ON con.confrelid = rc.oid
+            JOIN pg_catalog.pg_namespace rn ON rc.relnamespace = rn.oid
+            WHERE con.contype = 'f' AND {}",
+            SYSTEM_SCHEMAS_CONDITION
+        );
+        for row in self.fetch_all(&sql).await? {
+            let key: (String, String) = (row.get("schema_name"), row.get("table_name"));
+            if let Some(profile) = tables.get_mut(&key) {
+                profile
+                    .fk_refs
+                    .push((row.get("ref_schema_name"), row.get("ref_table_name")));
+            }
+        }
+
+        Ok(tables.into_values().collect())
+    }
+}
+
+impl PgDiscoverer {
+    async fn fetch_all(&self, sql: &str) -> anyhow::Result<Vec<PgRow>> {
+        let Some(pool) = &self.pool else {
+            bail! {Error::from(sqlx::Error::PoolClosed)}
+        };
+        Ok(query(sql).fetch_all(pool).await?)
+    }
+}
@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+
+use super::discovery_report::TableProfile;
+
+#[async_trait]
+pub trait Discoverer {
+    async fn build_connection(&mut self) -> anyhow::Result<()>;
+
+    /// profiles of all user tables kept by the filter, sorted by schema and table
+    async fn discover(&mut self) -> anyhow::Result<Vec<TableProfile>>;
+}
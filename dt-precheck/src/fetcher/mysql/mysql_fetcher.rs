@@ -1,9 +1,9 @@
 use futures::{Stream, TryStreamExt};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::bail;
 use async_trait::async_trait;
-use sqlx::{mysql::MySqlRow, query, MySql, Pool};
+use sqlx::{mysql::MySqlRow, query, MySql, Pool, Row};
 
 use crate::{
     fetcher::traits::Fetcher,
@@ -253,6 +253,47 @@ impl MysqlFetcher {
         }
     }
 
+    pub async fn fetch_tb_exists(&self, schema: &str, tb: &str) -> anyhow::Result<bool> {
+        let sql = format!(
+            "SELECT COUNT(*) AS tb_count FROM information_schema.TABLES
+            WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}'",
+            Self::escape_literal(schema),
+            Self::escape_literal(tb)
+        );
+        let rows = self.fetch_all(sql, "mysql query table existence").await?;
+        Ok(rows
+            .first()
+            .is_some_and(|row| row.get::<i64, _>("tb_count") > 0))
+    }
+
+    // privileges of the current user granted globally, on the schema or on the table,
+    // privileges granted through roles are not listed in information_schema
+    pub async fn fetch_tb_privileges(
+        &self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<HashSet<String>> {
+        let grantee = "CONCAT('''', SUBSTRING_INDEX(CURRENT_USER(), '@', 1), '''@''', SUBSTRING_INDEX(CURRENT_USER(), '@', -1), '''')";
+        let (schema, tb) = (Self::escape_literal(schema), Self::escape_literal(tb));
+        let sql = format!(
+            "SELECT PRIVILEGE_TYPE FROM information_schema.USER_PRIVILEGES WHERE GRANTEE = {grantee}
+            UNION SELECT PRIVILEGE_TYPE FROM information_schema.SCHEMA_PRIVILEGES
+            WHERE GRANTEE = {grantee} AND TABLE_SCHEMA = '{schema}'
+            UNION SELECT PRIVILEGE_TYPE FROM information_schema.TABLE_PRIVILEGES
+            WHERE GRANTEE = {grantee} AND TABLE_SCHEMA = '{schema}' AND TABLE_NAME = '{tb}'"
+        );
+        let rows = self.fetch_all(sql, "mysql query table privileges").await?;
+        let mut privileges = HashSet::new();
+        for row in rows {
+            privileges.insert(Self::get_str_with_null(&row, "PRIVILEGE_TYPE")?.to_uppercase());
+        }
+        Ok(privileges)
+    }
+
+    fn escape_literal(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('\'', "''")
+    }
+
     fn get_system_databases() -> Vec<String> {
         let dbs = ["mysql", "performance_schema", "sys", "information_schema"];
         dbs.iter().map(|d| d.to_string()).collect()
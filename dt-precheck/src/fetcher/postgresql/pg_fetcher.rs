@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::bail;
 use async_trait::async_trait;
@@ -11,7 +11,7 @@ use sqlx::{postgres::PgRow, query, Pool, Postgres, Row};
 
 use crate::{
     fetcher::traits::Fetcher,
-    meta::database_mode::{Constraint, Database, Schema, Table},
+    meta::database_mode::{Constraint, Database, Schema, Table, TableReplicaIdentity},
 };
 
 pub struct PgFetcher {
@@ -204,16 +204,34 @@ impl Fetcher for PgFetcher {
 }
 
 impl PgFetcher {
-    async fn fetch_all(&self, sql: String, mut sql_msg: &str) -> anyhow::Result<Vec<PgRow>> {
+    async fn fetch_all(&self, sql: String, sql_msg: &str) -> anyhow::Result<Vec<PgRow>> {
+        self.fetch_all_with_params(sql, &[], sql_msg).await
+    }
+
+    // params are bound to $1, $2 ... in order
+    async fn fetch_all_with_params(
+        &self,
+        sql: String,
+        params: &[&str],
+        mut sql_msg: &str,
+    ) -> anyhow::Result<Vec<PgRow>> {
         let pg_pool = match &self.pool {
             Some(pool) => pool,
             None => bail! {Error::from(sqlx::Error::PoolClosed)},
         };
 
         sql_msg = if sql_msg.is_empty() { "sql" } else { sql_msg };
-        println!("{}: {}", sql_msg, sql);
+        if params.is_empty() {
+            println!("{}: {}", sql_msg, sql);
+        } else {
+            println!("{}: {}, params: {:?}", sql_msg, sql, params);
+        }
 
-        let rows_result = query(&sql).fetch_all(pg_pool).await;
+        let mut sql_query = query(&sql);
+        for param in params {
+            sql_query = sql_query.bind(*param);
+        }
+        let rows_result = sql_query.fetch_all(pg_pool).await;
         match rows_result {
             Ok(rows) => Ok(rows),
             Err(e) => bail! {Error::from(e)},
@@ -252,6 +270,102 @@ impl PgFetcher {
         Ok(slots)
     }
 
+    pub async fn fetch_wal_sender_count(&self) -> anyhow::Result<i32> {
+        let sql = "SELECT count(*)::int4 AS cnt FROM pg_catalog.pg_stat_replication".to_string();
+        let rows = self.fetch_all(sql, "pg query wal senders").await?;
+        Ok(rows.first().map(|row| row.get("cnt")).unwrap_or(0))
+    }
+
+    // returns (rolsuper, rolreplication) of the current user
+    pub async fn fetch_role_attributes(&self) -> anyhow::Result<(bool, bool)> {
+        let sql =
+            "SELECT rolsuper, rolreplication FROM pg_catalog.pg_roles WHERE rolname = current_user"
+                .to_string();
+        let rows = self.fetch_all(sql, "pg query role attributes").await?;
+        match rows.first() {
+            Some(row) => Ok((row.get("rolsuper"), row.get("rolreplication"))),
+            None => Ok((false, false)),
+        }
+    }
+
+    // whether the current user can write the table, or create it if missing
+    pub async fn fetch_tb_writable(&self, schema: &str, tb: &str) -> anyhow::Result<bool> {
+        let schema_literal = schema.replace('\'', "''");
+        let tb_literal = format!(
+            r#""{}"."{}""#,
+            schema.replace('"', r#""""#),
+            tb.replace('"', r#""""#)
+        )
+        .replace('\'', "''");
+        let sql = format!(
+            "SELECT CASE
+            WHEN to_regclass('{tb_literal}') IS NOT NULL
+                THEN has_table_privilege('{tb_literal}', 'INSERT,UPDATE')
+            WHEN EXISTS (SELECT 1 FROM pg_catalog.pg_namespace WHERE nspname = '{schema_literal}')
+                THEN has_schema_privilege('{schema_literal}', 'CREATE')
+            ELSE has_database_privilege(current_database(), 'CREATE')
+            END AS writable"
+        );
+        let rows = self.fetch_all(sql, "pg query table writable").await?;
+        Ok(rows.first().is_some_and(|row| row.get("writable")))
+    }
+
+    // returns None if the publication does not exist, otherwise whether it is FOR ALL TABLES
+    pub async fn fetch_publication(&self, pub_name: &str) -> anyhow::Result<Option<bool>> {
+        let sql = "SELECT puballtables FROM pg_catalog.pg_publication WHERE pubname = $1";
+        let rows = self
+            .fetch_all_with_params(sql.to_string(), &[pub_name], "pg query publication")
+            .await?;
+        Ok(rows.first().map(|row| row.get("puballtables")))
+    }
+
+    pub async fn fetch_publication_tables(
+        &self,
+        pub_name: &str,
+    ) -> anyhow::Result<HashSet<String>> {
+        let sql = "SELECT schemaname::text AS schema_name, tablename::text AS table_name
+            FROM pg_catalog.pg_publication_tables WHERE pubname = $1";
+        let rows = self
+            .fetch_all_with_params(sql.to_string(), &[pub_name], "pg query publication tables")
+            .await?;
+        let mut tbs = HashSet::new();
+        for row in rows {
+            let (schema_name, table_name): (String, String) =
+                (row.get("schema_name"), row.get("table_name"));
+            tbs.insert(format!("{}.{}", schema_name, table_name));
+        }
+        Ok(tbs)
+    }
+
+    pub async fn fetch_replica_identities(&self) -> anyhow::Result<Vec<TableReplicaIdentity>> {
+        let sql = "SELECT n.nspname::text AS schema_name,
+            c.relname::text AS table_name,
+            c.relreplident::text AS replica_identity,
+            EXISTS (SELECT 1 FROM pg_catalog.pg_index i WHERE i.indrelid = c.oid AND i.indisprimary) AS has_pk
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+        WHERE c.relkind IN ('r', 'p')
+        AND n.nspname NOT IN ('pg_catalog', 'information_schema')"
+            .to_string();
+
+        let mut identities = vec![];
+        let rows = self.fetch_all(sql, "pg query replica identity").await?;
+        for row in rows {
+            let (schema_name, table_name): (String, String) =
+                (row.get("schema_name"), row.get("table_name"));
+            if self.filter.filter_tb(&schema_name, &table_name) {
+                continue;
+            }
+            identities.push(TableReplicaIdentity {
+                schema_name,
+                table_name,
+                replica_identity: row.get("replica_identity"),
+                has_pk: row.get("has_pk"),
+            });
+        }
+        Ok(identities)
+    }
+
     fn get_text_with_null(row: &PgRow, col_name: &str) -> anyhow::Result<String> {
         let mut str_val = String::new();
 
@@ -1,11 +1,22 @@
-use dt_common::config::task_config::TaskConfig;
+use anyhow::bail;
+use dt_common::{
+    config::{config_enums::DbType, task_config::TaskConfig},
+    error::Error,
+    rdb_filter::RdbFilter,
+};
 
 use crate::{
-    builder::prechecker_builder::PrecheckerBuilder, config::task_config::PrecheckTaskConfig,
+    builder::prechecker_builder::PrecheckerBuilder,
+    config::task_config::PrecheckTaskConfig,
+    discovery::{
+        discovery_report::DiscoveryReport, mysql_discoverer::MysqlDiscoverer,
+        pg_discoverer::PgDiscoverer, traits::Discoverer,
+    },
 };
 
 pub mod builder;
 pub mod config;
+pub mod discovery;
 pub mod fetcher;
 pub mod meta;
 pub mod prechecker;
@@ -23,3 +34,44 @@ pub async fn do_precheck(config: &str) {
 
     println!("precheck passed.");
 }
+
+/// Lists tables of [extractor] with statistics and risks, prints a [filter] section to start with.
+/// All user tables are discovered if neither [filter] do_dbs nor do_tbs is set.
+pub async fn do_discovery(config: &str) -> anyhow::Result<()> {
+    let task_config = TaskConfig::new(config)?;
+    let basic = &task_config.extractor_basic;
+
+    let mut filter_config = task_config.filter.clone();
+    if filter_config.do_schemas.is_empty() && filter_config.do_tbs.is_empty() {
+        filter_config.do_schemas = "*".to_string();
+    }
+    let filter = RdbFilter::from_config(&filter_config, &basic.db_type)?;
+
+    let mut discoverer: Box<dyn Discoverer + Send> = match basic.db_type {
+        DbType::Mysql => Box::new(MysqlDiscoverer {
+            pool: None,
+            url: basic.url.clone(),
+            connection_auth: basic.connection_auth.clone(),
+            filter,
+        }),
+        DbType::Pg => Box::new(PgDiscoverer {
+            pool: None,
+            url: basic.url.clone(),
+            connection_auth: basic.connection_auth.clone(),
+            filter,
+        }),
+        _ => bail! {Error::ConfigError(format!(
+            "discovery is not supported for [extractor] db_type={}",
+            basic.db_type
+        ))},
+    };
+    discoverer.build_connection().await?;
+
+    let report = DiscoveryReport {
+        db_type: basic.db_type.clone(),
+        tables: discoverer.discover().await?,
+    };
+    println!("{}\n", report.to_risk_report());
+    println!("{}", report.to_filter_config());
+    Ok(())
+}
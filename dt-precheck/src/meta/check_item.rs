@@ -8,4 +8,9 @@ pub enum CheckItem {
     CheckIfDatabaseSupportCdc,
     CheckIfStructExisted,
     CheckIfTableStructSupported,
+    CheckIfReplicationSlotAvailable,
+    CheckIfPublicationCoversTables,
+    CheckIfReplicationPermission,
+    CheckIfReplicaIdentitySupported,
+    CheckIfHeartbeatTbWritable,
 }
@@ -91,6 +91,35 @@ impl CheckResult {
                 }
                 advise_msg = format!("{} wait for the next release.", advise_version);
             }
+            CheckItem::CheckIfReplicationSlotAvailable => {
+                check_desc = format!(
+                    "check whether the {} database has free replication slots and wal senders",
+                    source_or_sink
+                );
+                advise_msg = "(1)increase 'max_replication_slots' or drop unused replication slots. (2)increase 'max_wal_senders'.".to_string();
+            }
+            CheckItem::CheckIfPublicationCoversTables => {
+                check_desc = format!(
+                    "check whether the publication of the {} database covers the tables to be migrated",
+                    source_or_sink
+                );
+                advise_msg = "(1)add the missing tables to the publication by 'ALTER PUBLICATION ... ADD TABLE ...'. (2)or leave 'pub_name' empty and use a superuser so the publication can be created automatically.".to_string();
+            }
+            CheckItem::CheckIfReplicationPermission => {
+                check_desc = format!(
+                    "check whether the account of the {} database has replication permission",
+                    source_or_sink
+                );
+                advise_msg = "grant replication permission by 'ALTER ROLE ... WITH REPLICATION'."
+                    .to_string();
+            }
+            CheckItem::CheckIfReplicaIdentitySupported => {
+                check_desc = format!(
+                    "check whether the replica identity of the {} tables to be migrated supports cdc",
+                    source_or_sink
+                );
+                advise_msg = "set 'REPLICA IDENTITY FULL' for tables without primary key by 'ALTER TABLE ... REPLICA IDENTITY FULL'.".to_string();
+            }
         }
         let mut warn_msg = String::new();
         if let Some(err) = warn_option {
@@ -32,3 +32,11 @@ pub struct Constraint {
     pub constraint_name: String,
     pub constraint_type: String,
 }
+
+pub struct TableReplicaIdentity {
+    pub schema_name: String,
+    pub table_name: String,
+    // d: default, n: nothing, f: full, i: index
+    pub replica_identity: String,
+    pub has_pk: bool,
+}
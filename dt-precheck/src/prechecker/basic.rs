@@ -1,5 +1,8 @@
 use dt_common::{config::config_enums::DbType, rdb_filter::RdbFilter};
 
+// matched tables listed in the check result at most
+const MAX_REPORTED_TBS: usize = 100;
+
 pub struct BasicPrechecker {}
 
 impl BasicPrechecker {
@@ -27,4 +30,56 @@ impl BasicPrechecker {
 
         false
     }
+
+    /// Patterns can not be checked for existence one by one, so the check passes if they match
+    /// any existing table, and the matched tables are reported as a warning for review.
+    pub fn check_filter_patterns(
+        mut matched_tbs: Vec<String>,
+    ) -> (Option<anyhow::Error>, Option<anyhow::Error>) {
+        if matched_tbs.is_empty() {
+            return (
+                Some(anyhow::Error::msg(
+                    "no existing tables matched the filter patterns",
+                )),
+                None,
+            );
+        }
+
+        matched_tbs.sort();
+        let count = matched_tbs.len();
+        let mut msg = format!(
+            "filter patterns matched {} tables: [{}]",
+            count,
+            matched_tbs
+                .iter()
+                .take(MAX_REPORTED_TBS)
+                .map(|tb| tb.as_str())
+                .collect::<Vec<&str>>()
+                .join(";")
+        );
+        if count > MAX_REPORTED_TBS {
+            msg.push_str(&format!(", {} more not listed", count - MAX_REPORTED_TBS));
+        }
+        (None, Some(anyhow::Error::msg(msg)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_filter_patterns() {
+        let (err, warn) = BasicPrechecker::check_filter_patterns(Vec::new());
+        assert!(err.is_some());
+        assert!(warn.is_none());
+
+        let (err, warn) =
+            BasicPrechecker::check_filter_patterns(vec!["db_2.tb_1".into(), "db_1.tb_1".into()]);
+        assert!(err.is_none());
+        assert_eq!(
+            warn.unwrap().to_string(),
+            "filter patterns matched 2 tables: [db_1.tb_1;db_2.tb_1]"
+        );
+    }
 }
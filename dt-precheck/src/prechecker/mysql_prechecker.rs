@@ -2,7 +2,9 @@ use std::collections::HashSet;
 
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::config::{config_enums::DbType, filter_config::FilterConfig};
+use dt_common::config::{
+    config_enums::DbType, extractor_config::HeartbeatTb, filter_config::FilterConfig,
+};
 use regex::Regex;
 
 use crate::{
@@ -21,6 +23,7 @@ pub struct MySqlPrechecker {
     pub filter_config: FilterConfig,
     pub precheck_config: PrecheckConfig,
     pub is_source: bool,
+    pub heartbeat_tb: Option<HeartbeatTb>,
 }
 
 #[async_trait]
@@ -144,6 +147,15 @@ impl Prechecker for MySqlPrechecker {
         ))
     }
 
+    async fn check_cdc_details(&mut self) -> anyhow::Result<Vec<CheckResult>> {
+        match self.heartbeat_tb.clone() {
+            Some(heartbeat_tb) if self.is_source => {
+                Ok(vec![self.check_heartbeat_tb(&heartbeat_tb).await?])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
     async fn check_struct_existed_or_not(&mut self) -> anyhow::Result<CheckResult> {
         let mut check_error = None;
 
@@ -161,14 +173,20 @@ impl Prechecker for MySqlPrechecker {
         let is_filter_pattern =
             BasicPrechecker::is_filter_pattern(DbType::Mysql, &self.fetcher.filter);
         if is_filter_pattern {
+            let matched_tbs = self
+                .fetcher
+                .fetch_tables()
+                .await?
+                .iter()
+                .map(|t| format!("{}.{}", t.database_name, t.table_name))
+                .collect();
+            let (check_error, warn_error) = BasicPrechecker::check_filter_patterns(matched_tbs);
             return Ok(CheckResult::build_with_err(
                 CheckItem::CheckIfStructExisted,
                 self.is_source,
                 DbType::Mysql,
                 check_error,
-                Some(anyhow::Error::msg(
-                    "CheckIfStructExisted with filter in pattern is not supported.",
-                )),
+                warn_error,
             ));
         }
 
@@ -395,3 +413,39 @@ impl Prechecker for MySqlPrechecker {
         ))
     }
 }
+
+impl MySqlPrechecker {
+    async fn check_heartbeat_tb(&self, heartbeat_tb: &HeartbeatTb) -> anyhow::Result<CheckResult> {
+        let (schema, tb) = (&heartbeat_tb.schema, &heartbeat_tb.tb);
+        // heartbeats are written by REPLACE INTO
+        let mut required = vec!["INSERT", "DELETE"];
+        if !self.fetcher.fetch_tb_exists(schema, tb).await? {
+            required.push("CREATE");
+        }
+
+        let privileges = self.fetcher.fetch_tb_privileges(schema, tb).await?;
+        let missing: Vec<&str> = required
+            .into_iter()
+            .filter(|privilege| {
+                !privileges.contains(*privilege) && !privileges.contains("ALL PRIVILEGES")
+            })
+            .collect();
+        let check_error = if missing.is_empty() {
+            None
+        } else {
+            Some(anyhow::Error::msg(format!(
+                "the current user lacks privileges [{}] on heartbeat tb: {}.{}",
+                missing.join(","),
+                schema,
+                tb
+            )))
+        };
+        Ok(CheckResult::build_with_err(
+            CheckItem::CheckIfHeartbeatTbWritable,
+            self.is_source,
+            DbType::Mysql,
+            check_error,
+            None,
+        ))
+    }
+}
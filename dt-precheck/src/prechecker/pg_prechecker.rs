@@ -2,7 +2,11 @@ use std::collections::HashSet;
 
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::config::{config_enums::DbType, filter_config::FilterConfig};
+use dt_common::config::{
+    config_enums::{DbType, ReplicaIdentityPolicy},
+    extractor_config::HeartbeatTb,
+    filter_config::FilterConfig,
+};
 
 use crate::{
     config::precheck_config::PrecheckConfig,
@@ -22,6 +26,11 @@ pub struct PostgresqlPrechecker {
     pub filter_config: FilterConfig,
     pub precheck_config: PrecheckConfig,
     pub is_source: bool,
+    pub slot_name: String,
+    pub pub_name: String,
+    pub pub_for_filtered_tables: bool,
+    pub replica_identity_policy: ReplicaIdentityPolicy,
+    pub heartbeat_tb: Option<HeartbeatTb>,
 }
 
 #[async_trait]
@@ -95,62 +104,16 @@ impl Prechecker for PostgresqlPrechecker {
         }
 
         // check the cdc settings
-        let configs: Vec<String> = ["wal_level", "max_wal_senders", "max_replication_slots"]
-            .iter()
-            .map(|c| c.to_string())
-            .collect();
-        let (mut max_replication_slots_i32, mut err_msgs): (i32, Vec<String>) = (0, vec![]);
-        let result = self.fetcher.fetch_configuration(configs).await;
-        match result {
-            Ok(rows) => {
-                for (k, v) in rows {
-                    match k.as_str() {
-                        "wal_level" => {
-                            if v.to_lowercase() != "logical" {
-                                err_msgs.push(format!(
-                                    "wal_level should not be '{}', need to be 'logical'.",
-                                    v
-                                ))
-                            }
-                        }
-                        "max_replication_slots" => {
-                            max_replication_slots_i32 = v.parse().unwrap();
-                            if max_replication_slots_i32 < 1 {
-                                err_msgs.push(format!(
-                                    "max_replication_slots needs to be greater than 0. current is '{}'",
-                                    max_replication_slots_i32
-                                ))
-                            }
-                        }
-                        "max_wal_senders" => {
-                            let sender_i32: i32 = v.parse().unwrap();
-                            if sender_i32 < 1 {
-                                err_msgs.push(format!(
-                                    "max_wel_senders needs to be greater than 0, current is '{}'",
-                                    sender_i32
-                                ))
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            Err(e) => bail! {e},
-        }
-        if !err_msgs.is_empty() {
-            check_error = Some(anyhow::Error::msg(err_msgs.join(";")));
-        }
-
-        if check_error.is_none() {
-            // check the slot count is less than max_replication_slots or not
-            let slot_result = self.fetcher.fetch_slot_names().await;
-            match slot_result {
-                Ok(slots) => {
-                    if max_replication_slots_i32 == (slots.len() as i32) {
-                        check_error = Some(anyhow::Error::msg(  format!("the current number of slots:[{}] has reached max_replication_slots, and new slots cannot be created", max_replication_slots_i32) ));
-                    }
-                }
-                Err(e) => check_error = Some(e),
+        let configs = self
+            .fetcher
+            .fetch_configuration(vec!["wal_level".to_string()])
+            .await?;
+        if let Some(wal_level) = configs.get("wal_level") {
+            if wal_level.to_lowercase() != "logical" {
+                check_error = Some(anyhow::Error::msg(format!(
+                    "wal_level should not be '{}', need to be 'logical'.",
+                    wal_level
+                )));
             }
         }
 
@@ -163,6 +126,24 @@ impl Prechecker for PostgresqlPrechecker {
         ))
     }
 
+    async fn check_cdc_details(&mut self) -> anyhow::Result<Vec<CheckResult>> {
+        if !self.is_source {
+            return Ok(vec![]);
+        }
+
+        let (is_super, has_replication) = self.fetcher.fetch_role_attributes().await?;
+        let mut results = vec![
+            self.check_replication_slot_available().await?,
+            self.check_publication_covers_tables(is_super).await?,
+            self.check_replication_permission(is_super, has_replication),
+            self.check_replica_identity().await?,
+        ];
+        if let Some(heartbeat_tb) = self.heartbeat_tb.clone() {
+            results.push(self.check_heartbeat_tb(&heartbeat_tb).await?);
+        }
+        Ok(results)
+    }
+
     async fn check_struct_existed_or_not(&mut self) -> anyhow::Result<CheckResult> {
         let mut check_error = None;
 
@@ -179,14 +160,20 @@ impl Prechecker for PostgresqlPrechecker {
         let is_filter_pattern =
             BasicPrechecker::is_filter_pattern(DbType::Pg, &self.fetcher.filter);
         if is_filter_pattern {
+            let matched_tbs = self
+                .fetcher
+                .fetch_tables()
+                .await?
+                .iter()
+                .map(|t| format!("{}.{}", t.schema_name, t.table_name))
+                .collect();
+            let (check_error, warn_error) = BasicPrechecker::check_filter_patterns(matched_tbs);
             return Ok(CheckResult::build_with_err(
                 CheckItem::CheckIfStructExisted,
                 self.is_source,
                 DbType::Pg,
                 check_error,
-                Some(anyhow::Error::msg(
-                    "CheckIfStructExisted with filter in pattern is not supported.",
-                )),
+                warn_error,
             ));
         }
 
@@ -395,3 +382,199 @@ impl Prechecker for PostgresqlPrechecker {
         ))
     }
 }
+
+impl PostgresqlPrechecker {
+    async fn check_replication_slot_available(&mut self) -> anyhow::Result<CheckResult> {
+        let configs = self
+            .fetcher
+            .fetch_configuration(vec![
+                "max_replication_slots".to_string(),
+                "max_wal_senders".to_string(),
+            ])
+            .await?;
+        let get_i32 = |key: &str| -> i32 {
+            configs
+                .get(key)
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default()
+        };
+        let (max_replication_slots, max_wal_senders) =
+            (get_i32("max_replication_slots"), get_i32("max_wal_senders"));
+
+        let mut err_msgs = vec![];
+        // an existing slot with the same name will be reused, no new slot is needed
+        let slots = self.fetcher.fetch_slot_names().await?;
+        if !slots.contains(&self.slot_name) && slots.len() as i32 >= max_replication_slots {
+            err_msgs.push(format!(
+                "the current number of slots:[{}] has reached max_replication_slots:[{}], and new slots cannot be created",
+                slots.len(),
+                max_replication_slots
+            ));
+        }
+
+        let wal_senders = self.fetcher.fetch_wal_sender_count().await?;
+        if wal_senders >= max_wal_senders {
+            err_msgs.push(format!(
+                "the current number of wal senders:[{}] has reached max_wal_senders:[{}]",
+                wal_senders, max_wal_senders
+            ));
+        }
+
+        let check_error = if err_msgs.is_empty() {
+            None
+        } else {
+            Some(anyhow::Error::msg(err_msgs.join(";")))
+        };
+        Ok(CheckResult::build_with_err(
+            CheckItem::CheckIfReplicationSlotAvailable,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+            None,
+        ))
+    }
+
+    async fn check_publication_covers_tables(
+        &mut self,
+        is_super: bool,
+    ) -> anyhow::Result<CheckResult> {
+        // keep the same default as PgCdcClient
+        let pub_name = if self.pub_name.is_empty() {
+            format!("{}_publication_for_all_tables", self.slot_name)
+        } else {
+            self.pub_name.clone()
+        };
+
+        let mut check_error = None;
+        match self.fetcher.fetch_publication(&pub_name).await? {
+            Some(true) => {}
+
+            // missing tables are added when the task starts
+            Some(false) if self.pub_for_filtered_tables => {}
+
+            Some(false) => {
+                let pub_tbs = self.fetcher.fetch_publication_tables(&pub_name).await?;
+                let mut missing_tbs: Vec<String> = self
+                    .fetcher
+                    .fetch_tables()
+                    .await?
+                    .iter()
+                    .map(|t| format!("{}.{}", t.schema_name, t.table_name))
+                    .filter(|tb| !pub_tbs.contains(tb))
+                    .collect();
+                missing_tbs.sort();
+                if !missing_tbs.is_empty() {
+                    check_error = Some(anyhow::Error::msg(format!(
+                        "publication:[{}] does not cover tables: [{}]",
+                        pub_name,
+                        missing_tbs.join(";")
+                    )));
+                }
+            }
+
+            None => {
+                // the publication will be created FOR ALL TABLES, which requires superuser
+                if !is_super && !self.pub_for_filtered_tables {
+                    check_error = Some(anyhow::Error::msg(format!(
+                        "publication:[{}] does not exist and can not be created since the current user is not superuser",
+                        pub_name
+                    )));
+                }
+            }
+        }
+
+        Ok(CheckResult::build_with_err(
+            CheckItem::CheckIfPublicationCoversTables,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+            None,
+        ))
+    }
+
+    fn check_replication_permission(&self, is_super: bool, has_replication: bool) -> CheckResult {
+        let check_error = if is_super || has_replication {
+            None
+        } else {
+            Some(anyhow::Error::msg(
+                "the current user has neither REPLICATION nor SUPERUSER attribute",
+            ))
+        };
+        CheckResult::build_with_err(
+            CheckItem::CheckIfReplicationPermission,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+            None,
+        )
+    }
+
+    async fn check_heartbeat_tb(&self, heartbeat_tb: &HeartbeatTb) -> anyhow::Result<CheckResult> {
+        let writable = self
+            .fetcher
+            .fetch_tb_writable(&heartbeat_tb.schema, &heartbeat_tb.tb)
+            .await?;
+        let check_error = if writable {
+            None
+        } else {
+            Some(anyhow::Error::msg(format!(
+                "the current user can not create or write heartbeat tb: {}.{}, INSERT and UPDATE on the table, or CREATE on the schema if the table is missing, are required",
+                heartbeat_tb.schema, heartbeat_tb.tb
+            )))
+        };
+        Ok(CheckResult::build_with_err(
+            CheckItem::CheckIfHeartbeatTbWritable,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+            None,
+        ))
+    }
+
+    async fn check_replica_identity(&mut self) -> anyhow::Result<CheckResult> {
+        let (mut nothing_tbs, mut no_pk_tbs) = (vec![], vec![]);
+        for identity in self.fetcher.fetch_replica_identities().await? {
+            let tb = format!("{}.{}", identity.schema_name, identity.table_name);
+            match identity.replica_identity.as_str() {
+                "n" => nothing_tbs.push(tb),
+                "d" if !identity.has_pk => no_pk_tbs.push(tb),
+                _ => {}
+            }
+        }
+        nothing_tbs.sort();
+        no_pk_tbs.sort();
+
+        let mut errors = vec![];
+        if !nothing_tbs.is_empty() {
+            errors.push(format!(
+                "tables with REPLICA IDENTITY NOTHING can not be replicated by update/delete: [{}]",
+                nothing_tbs.join(";")
+            ));
+        }
+        let mut warn_error = None;
+        if !no_pk_tbs.is_empty() {
+            let msg = format!(
+                "tables without primary key use REPLICA IDENTITY DEFAULT, update/delete events will have no before images: [{}]",
+                no_pk_tbs.join(";")
+            );
+            // the task fails on such tables unless their update/delete events are skipped
+            match self.replica_identity_policy {
+                ReplicaIdentityPolicy::Fail => errors.push(msg),
+                ReplicaIdentityPolicy::Skip => warn_error = Some(anyhow::Error::msg(msg)),
+            }
+        }
+        let check_error = if errors.is_empty() {
+            None
+        } else {
+            Some(anyhow::Error::msg(errors.join("; ")))
+        };
+
+        Ok(CheckResult::build_with_err(
+            CheckItem::CheckIfReplicaIdentitySupported,
+            self.is_source,
+            DbType::Pg,
+            check_error,
+            warn_error,
+        ))
+    }
+}
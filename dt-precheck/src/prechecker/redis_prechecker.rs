@@ -13,7 +13,7 @@ use crate::{
 use dt_common::{
     config::{
         config_enums::{DbType, ExtractType},
-        extractor_config::ExtractorConfig,
+        extractor_config::{ExtractorConfig, RedisModuleConfig},
         task_config::TaskConfig,
     },
     meta::{dt_queue::DtQueue, redis::cluster_node::ClusterNode, syncer::Syncer},
@@ -26,6 +26,7 @@ use dt_connector::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
         extractor_monitor::ExtractorMonitor,
+        extractor_progress::ExtractorProgress,
         redis::{redis_client::RedisClient, redis_psync_extractor::RedisPsyncExtractor},
     },
     rdb_router::RdbRouter,
@@ -158,6 +159,7 @@ impl Prechecker for RedisPrechecker {
             buffer,
             router: RdbRouter::from_config(&self.task_config.router, &DbType::Redis)?,
             shut_down: Arc::new(AtomicBool::new(false)),
+            progress: ExtractorProgress::default(),
         };
         let extract_state = ExtractState {
             monitor: ExtractorMonitor::new(monitor, String::new()).await,
@@ -182,6 +184,7 @@ impl Prechecker for RedisPrechecker {
             recovery: None,
             cluster_node: None,
             wait_task_finish: true,
+            module_config: RedisModuleConfig::default(),
         };
 
         if let Err(error) = psyncer.start_psync().await {
@@ -12,6 +12,11 @@ pub trait Prechecker {
 
     async fn check_cdc_supported(&mut self) -> anyhow::Result<CheckResult>;
 
+    // fine-grained cdc checks reported as separate results, only some databases need them
+    async fn check_cdc_details(&mut self) -> anyhow::Result<Vec<CheckResult>> {
+        Ok(vec![])
+    }
+
     async fn check_struct_existed_or_not(&mut self) -> anyhow::Result<CheckResult>;
 
     async fn check_table_structs(&mut self) -> anyhow::Result<CheckResult>;
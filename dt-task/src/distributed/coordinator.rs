@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+use dt_common::{log_info, log_warn};
+
+use super::{CoordinatorStatus, HeartbeatRequest, HeartbeatResponse, TbId, WorkerStatus};
+
+/// Table assignments of a distributed task: every table is either pending, running on
+/// exactly one worker or finished.
+pub struct Assignments {
+    pending: VecDeque<TbId>,
+    finished: BTreeSet<TbId>,
+    workers: BTreeMap<String, WorkerState>,
+    assign_batch_size: usize,
+}
+
+struct WorkerState {
+    running_tbs: BTreeSet<TbId>,
+    finished_cnt: usize,
+    last_heartbeat: Instant,
+}
+
+impl Assignments {
+    pub fn new(tbs: Vec<TbId>, assign_batch_size: usize) -> Self {
+        Self {
+            pending: tbs.into(),
+            finished: BTreeSet::new(),
+            workers: BTreeMap::new(),
+            assign_batch_size: assign_batch_size.max(1),
+        }
+    }
+
+    pub fn heartbeat(&mut self, request: HeartbeatRequest, now: Instant) -> HeartbeatResponse {
+        let worker = self
+            .workers
+            .entry(request.worker_id.clone())
+            .or_insert_with(|| {
+                log_info!("distributed worker registered: {}", request.worker_id);
+                WorkerState {
+                    running_tbs: BTreeSet::new(),
+                    finished_cnt: 0,
+                    last_heartbeat: now,
+                }
+            });
+        worker.last_heartbeat = now;
+
+        for tb in request.finished_tbs {
+            worker.running_tbs.remove(&tb);
+            self.pending.retain(|i| i != &tb);
+            if self.finished.insert(tb) {
+                worker.finished_cnt += 1;
+            }
+        }
+
+        // tables assigned before but not reported as running anymore were lost
+        let reported: BTreeSet<TbId> = request.running_tbs.into_iter().collect();
+        let lost: Vec<TbId> = worker.running_tbs.difference(&reported).cloned().collect();
+        if !lost.is_empty() {
+            log_warn!(
+                "distributed worker: {} lost tables: {:?}, reassign them",
+                request.worker_id,
+                lost
+            );
+        }
+        for tb in lost.into_iter().rev() {
+            self.pending.push_front(tb);
+        }
+        // a worker considered dead may come back with its tables still running
+        self.pending.retain(|tb| !reported.contains(tb));
+        worker.running_tbs = reported
+            .into_iter()
+            .filter(|tb| !self.finished.contains(tb))
+            .collect();
+
+        let mut assigned_tbs = Vec::new();
+        if request.want_tbs {
+            while assigned_tbs.len() < self.assign_batch_size {
+                let Some(tb) = self.pending.pop_front() else {
+                    break;
+                };
+                worker.running_tbs.insert(tb.clone());
+                assigned_tbs.push(tb);
+            }
+        }
+
+        HeartbeatResponse {
+            assigned_tbs,
+            all_finished: self.all_finished(),
+        }
+    }
+
+    /// Removes workers without heartbeat within timeout, their running tables go back to
+    /// the front of the pending queue.
+    pub fn expire_workers(&mut self, now: Instant, timeout: Duration) -> Vec<String> {
+        let dead: Vec<String> = self
+            .workers
+            .iter()
+            .filter(|(_, w)| now.duration_since(w.last_heartbeat) > timeout)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for worker_id in dead.iter() {
+            let worker = self.workers.remove(worker_id).unwrap();
+            for tb in worker.running_tbs.into_iter().rev() {
+                self.pending.push_front(tb);
+            }
+        }
+        dead
+    }
+
+    pub fn all_finished(&self) -> bool {
+        self.pending.is_empty() && self.workers.values().all(|w| w.running_tbs.is_empty())
+    }
+
+    pub fn status(&self, now: Instant) -> CoordinatorStatus {
+        let workers: Vec<WorkerStatus> = self
+            .workers
+            .iter()
+            .map(|(worker_id, w)| WorkerStatus {
+                worker_id: worker_id.clone(),
+                running_tbs: w.running_tbs.iter().cloned().collect(),
+                finished_tbs: w.finished_cnt,
+                last_heartbeat_secs: now.duration_since(w.last_heartbeat).as_secs(),
+            })
+            .collect();
+        CoordinatorStatus {
+            pending_tbs: self.pending.len(),
+            running_tbs: workers.iter().map(|w| w.running_tbs.len()).sum(),
+            finished_tbs: self.finished.len(),
+            workers,
+        }
+    }
+}
+
+pub struct Coordinator {
+    pub http_host: String,
+    pub http_port: u64,
+    pub heartbeat_timeout_secs: u64,
+}
+
+impl Coordinator {
+    /// Serves workers until all tables are finished. After that, keeps serving for one more
+    /// heartbeat timeout so that idle workers get notified and exit.
+    pub async fn run(&self, assignments: Assignments) -> anyhow::Result<()> {
+        let assignments = Arc::new(Mutex::new(assignments));
+        let data = assignments.clone();
+        let addr = format!("{}:{}", self.http_host, self.http_port);
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(data.clone()))
+                .service(web::resource("/heartbeat").route(web::post().to(heartbeat_handler)))
+                .service(web::resource("/status").route(web::get().to(status_handler)))
+        })
+        .workers(1)
+        .bind(&addr)?
+        .run();
+        let handle = server.handle();
+        let server_task = tokio::spawn(server);
+        log_info!("distributed coordinator listening on {}", addr);
+
+        let timeout = Duration::from_secs(self.heartbeat_timeout_secs);
+        let mut finished_at: Option<Instant> = None;
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        let mut last_report = Instant::now();
+        loop {
+            interval.tick().await;
+            let now = Instant::now();
+            let mut assignments = assignments.lock().unwrap();
+            for worker_id in assignments.expire_workers(now, timeout) {
+                log_warn!(
+                    "distributed worker: {} has no heartbeat for {}s, reassign its tables",
+                    worker_id,
+                    self.heartbeat_timeout_secs
+                );
+            }
+
+            if now.duration_since(last_report) >= timeout || assignments.all_finished() {
+                let status = assignments.status(now);
+                log_info!(
+                    "distributed progress, pending: {}, running: {}, finished: {}, workers: {}",
+                    status.pending_tbs,
+                    status.running_tbs,
+                    status.finished_tbs,
+                    status.workers.len()
+                );
+                last_report = now;
+            }
+
+            if assignments.all_finished() {
+                let finished_at = *finished_at.get_or_insert(now);
+                if now.duration_since(finished_at) >= timeout {
+                    break;
+                }
+            } else {
+                finished_at = None;
+            }
+        }
+
+        handle.stop(true).await;
+        server_task.await??;
+        log_info!("distributed coordinator: all tables finished");
+        Ok(())
+    }
+}
+
+async fn heartbeat_handler(
+    assignments: web::Data<Arc<Mutex<Assignments>>>,
+    request: web::Json<HeartbeatRequest>,
+) -> impl Responder {
+    let response = assignments
+        .lock()
+        .unwrap()
+        .heartbeat(request.into_inner(), Instant::now());
+    HttpResponse::Ok().json(response)
+}
+
+async fn status_handler(assignments: web::Data<Arc<Mutex<Assignments>>>) -> impl Responder {
+    let status = assignments.lock().unwrap().status(Instant::now());
+    HttpResponse::Ok().json(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tb(name: &str) -> TbId {
+        TbId {
+            schema: "db".into(),
+            tb: name.into(),
+        }
+    }
+
+    fn request(
+        worker_id: &str,
+        running: &[TbId],
+        finished: &[TbId],
+        want: bool,
+    ) -> HeartbeatRequest {
+        HeartbeatRequest {
+            worker_id: worker_id.into(),
+            running_tbs: running.to_vec(),
+            finished_tbs: finished.to_vec(),
+            want_tbs: want,
+        }
+    }
+
+    #[test]
+    fn test_assign_and_reassign_dead_worker() {
+        let now = Instant::now();
+        let mut assignments = Assignments::new(vec![tb("a"), tb("b"), tb("c")], 1);
+
+        let res = assignments.heartbeat(request("w1", &[], &[], true), now);
+        assert_eq!(res.assigned_tbs, vec![tb("a")]);
+        let res = assignments.heartbeat(request("w2", &[], &[], true), now);
+        assert_eq!(res.assigned_tbs, vec![tb("b")]);
+
+        // w1 dies, its table goes back to the front of the queue
+        let later = now + Duration::from_secs(10);
+        assignments.heartbeat(request("w2", &[tb("b")], &[], false), later);
+        let dead = assignments.expire_workers(later, Duration::from_secs(5));
+        assert_eq!(dead, vec!["w1".to_string()]);
+
+        let res = assignments.heartbeat(request("w2", &[], &[tb("b")], true), later);
+        assert_eq!(res.assigned_tbs, vec![tb("a")]);
+        assert!(!res.all_finished);
+
+        let res = assignments.heartbeat(request("w2", &[], &[tb("a")], true), later);
+        assert_eq!(res.assigned_tbs, vec![tb("c")]);
+        let res = assignments.heartbeat(request("w2", &[], &[tb("c")], true), later);
+        assert!(res.assigned_tbs.is_empty());
+        assert!(res.all_finished);
+        assert_eq!(assignments.status(later).finished_tbs, 3);
+    }
+
+    #[test]
+    fn test_restarted_worker_loses_running_tbs() {
+        let now = Instant::now();
+        let mut assignments = Assignments::new(vec![tb("a"), tb("b")], 2);
+
+        let res = assignments.heartbeat(request("w1", &[], &[], true), now);
+        assert_eq!(res.assigned_tbs, vec![tb("a"), tb("b")]);
+
+        // w1 restarts and asks for work without reporting its running tables
+        let res = assignments.heartbeat(request("w1", &[], &[], true), now);
+        assert_eq!(res.assigned_tbs, vec![tb("a"), tb("b")]);
+        assert_eq!(assignments.status(now).pending_tbs, 0);
+    }
+}
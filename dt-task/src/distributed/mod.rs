@@ -0,0 +1,43 @@
+pub mod coordinator;
+pub mod worker;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TbId {
+    pub schema: String,
+    pub tb: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HeartbeatRequest {
+    pub worker_id: String,
+    // tables the worker is migrating now, tables assigned before but missing here are
+    // considered lost (e.g. the worker restarted) and will be reassigned
+    pub running_tbs: Vec<TbId>,
+    pub finished_tbs: Vec<TbId>,
+    // set by idle workers to ask for new tables
+    pub want_tbs: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HeartbeatResponse {
+    pub assigned_tbs: Vec<TbId>,
+    pub all_finished: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CoordinatorStatus {
+    pub pending_tbs: usize,
+    pub running_tbs: usize,
+    pub finished_tbs: usize,
+    pub workers: Vec<WorkerStatus>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    pub worker_id: String,
+    pub running_tbs: Vec<TbId>,
+    pub finished_tbs: usize,
+    pub last_heartbeat_secs: u64,
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use dt_common::{error::Error, log_warn};
+
+use super::{HeartbeatRequest, HeartbeatResponse, TbId};
+
+#[derive(Clone)]
+pub struct Worker {
+    pub coordinator_url: String,
+    pub worker_id: String,
+    pub heartbeat_interval_secs: u64,
+    http_client: reqwest::Client,
+}
+
+impl Worker {
+    pub fn new(coordinator_url: &str, worker_id: &str, heartbeat_interval_secs: u64) -> Self {
+        Self {
+            coordinator_url: coordinator_url.trim_end_matches('/').to_string(),
+            worker_id: worker_id.to_string(),
+            heartbeat_interval_secs,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Asks the coordinator for tables until all tables of the task are finished, retries
+    /// if the coordinator is unreachable.
+    pub async fn next_tbs(&self, finished_tbs: &[TbId]) -> HeartbeatResponse {
+        let request = HeartbeatRequest {
+            worker_id: self.worker_id.clone(),
+            running_tbs: Vec::new(),
+            finished_tbs: finished_tbs.to_vec(),
+            want_tbs: true,
+        };
+        loop {
+            match self.heartbeat(&request).await {
+                Ok(response) => {
+                    if response.assigned_tbs.is_empty() && !response.all_finished {
+                        // other workers are still running, their tables may be reassigned
+                        self.sleep().await;
+                        continue;
+                    }
+                    return response;
+                }
+                Err(err) => {
+                    log_warn!("distributed worker heartbeat failed: {}", err);
+                    self.sleep().await;
+                }
+            }
+        }
+    }
+
+    /// Keeps reporting the running tables while they are being migrated.
+    pub async fn keep_alive(self, running_tbs: Vec<TbId>) {
+        let request = HeartbeatRequest {
+            worker_id: self.worker_id.clone(),
+            running_tbs,
+            ..Default::default()
+        };
+        loop {
+            self.sleep().await;
+            if let Err(err) = self.heartbeat(&request).await {
+                log_warn!("distributed worker heartbeat failed: {}", err);
+            }
+        }
+    }
+
+    async fn heartbeat(&self, request: &HeartbeatRequest) -> anyhow::Result<HeartbeatResponse> {
+        let url = format!("{}/heartbeat", self.coordinator_url);
+        let response = self
+            .http_client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(serde_json::to_string(request)?)
+            .send()
+            .await
+            .with_context(|| format!("failed to send heartbeat to: {}", url))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            bail! {Error::HttpError(format!(
+                "coordinator responded with status: {}, body: {}",
+                status, body
+            ))}
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    async fn sleep(&self) {
+        tokio::time::sleep(Duration::from_secs(self.heartbeat_interval_secs)).await;
+    }
+}
@@ -11,9 +11,11 @@ use tokio::sync::Mutex;
 use dt_common::{
     config::{
         config_enums::{CheckMode, DbType, ExtractType, TaskKind},
-        extractor_config::ExtractorConfig,
+        extractor_config::{ExtractorConfig, MysqlCdcSourceConfig, RedisModuleConfig},
+        sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
+    error::Error,
     meta::{
         avro::avro_converter::AvroConverter, dt_queue::DtQueue,
         mysql::mysql_meta_manager::MysqlMetaManager, pg::pg_meta_manager::PgMetaManager,
@@ -30,7 +32,12 @@ use dt_connector::{
     extractor::{
         base_extractor::{BaseExtractor, ExtractState},
         extractor_monitor::ExtractorMonitor,
-        kafka::kafka_extractor::KafkaExtractor,
+        extractor_progress::ExtractorProgress,
+        file::chunk_file_extractor::ChunkFileExtractor,
+        kafka::{
+            chunk_assembler::ChunkAssembler, dedup_window::DedupWindow,
+            kafka_extractor::KafkaExtractor,
+        },
         mongo::{
             mongo_cdc_extractor::MongoCdcExtractor, mongo_check_extractor::MongoCheckExtractor,
             mongo_snapshot_extractor::MongoSnapshotExtractor,
@@ -39,6 +46,7 @@ use dt_connector::{
         mysql::{
             mysql_cdc_extractor::MysqlCdcExtractor,
             mysql_check_extractor::MysqlCheckExtractor,
+            mysql_multi_source_extractor::MysqlMultiSourceExtractor,
             mysql_snapshot_extractor::{MysqlSnapshotExtractor, MysqlSnapshotShared},
             mysql_struct_extractor::MysqlStructExtractor,
         },
@@ -68,6 +76,8 @@ use super::task_util::TaskUtil;
 pub type PartitionCols = HashMap<(String, String), String>;
 
 const JSON_PREFIX: &str = "json:";
+// incomplete chunked payloads kept by a kafka extractor
+const KAFKA_CHUNK_ASSEMBLER_CAPACITY: usize = 64;
 
 pub struct ExtractorUtil {}
 
@@ -94,6 +104,84 @@ impl ExtractorUtil {
         }
     }
 
+    // validate tasks only read the rows to be validated
+    fn sample_rows(config: &TaskConfig) -> Option<usize> {
+        match config.sinker {
+            SinkerConfig::Validate { sample_rows, .. } => Some(sample_rows),
+            _ => None,
+        }
+    }
+
+    /// Creates the extractor of another source in a multi-source mysql cdc task, settings not
+    /// in the source config are taken from the extractor of [extractor].url.
+    async fn create_mysql_source_extractor(
+        config: &TaskConfig,
+        default: &MysqlCdcExtractor,
+        source: MysqlCdcSourceConfig,
+    ) -> anyhow::Result<MysqlCdcExtractor> {
+        let conn_pool = TaskUtil::create_mysql_conn_pool_with_timeout(
+            &source.url,
+            &DbType::Mysql,
+            &default.connection_auth,
+            config.extractor_basic.max_connections,
+            TaskUtil::check_enable_sqlx_log(&config.runtime.log_level),
+            None,
+            &config.extractor_basic.timeout,
+        )
+        .await?;
+        let meta_manager = TaskUtil::create_mysql_meta_manager(
+            &source.url,
+            &default.connection_auth,
+            &config.runtime.log_level,
+            DbType::Mysql,
+            config.meta_center.clone(),
+            Some(conn_pool.clone()),
+            &config.extractor_basic.timeout,
+        )
+        .await?;
+        let mut base_extractor = default.base_extractor.clone();
+        base_extractor.router =
+            RdbRouter::with_schema_prefix(base_extractor.router, &source.db_prefix);
+
+        Ok(MysqlCdcExtractor {
+            base_extractor,
+            extract_state: Self::derive_mysql_source_state(default).await,
+            meta_manager,
+            conn_pool,
+            filter: default.filter.clone(),
+            url: source.url,
+            connection_auth: default.connection_auth.clone(),
+            binlog_filename: source.binlog_filename,
+            binlog_position: source.binlog_position,
+            server_id: source.server_id.unwrap_or(default.server_id),
+            gtid_enabled: default.gtid_enabled,
+            gtid_set: source.gtid_set,
+            binlog_heartbeat_interval_secs: default.binlog_heartbeat_interval_secs,
+            binlog_timeout_secs: default.binlog_timeout_secs,
+            heartbeat_interval_secs: default.heartbeat_interval_secs,
+            heartbeat_tb: default.heartbeat_tb.clone(),
+            keepalive_idle_secs: default.keepalive_idle_secs,
+            keepalive_interval_secs: default.keepalive_interval_secs,
+            capture_rows_query: default.capture_rows_query,
+            enum_set_as_index: default.enum_set_as_index,
+            failover_urls: Vec::new(),
+            syncer: default.syncer.clone(),
+            recovery: default.recovery.clone(),
+            source_id: source.id,
+            wait_task_finish: false,
+        })
+    }
+
+    async fn derive_mysql_source_state(default: &MysqlCdcExtractor) -> ExtractState {
+        let state = &default.extract_state;
+        let monitor = ExtractorMonitor::new(
+            state.monitor.monitor.clone(),
+            state.monitor.default_task_id.clone(),
+        )
+        .await;
+        state.derive_for_table(monitor, state.data_marker.clone())
+    }
+
     pub async fn create_extractor(
         config: &TaskConfig,
         extractor_config: &ExtractorConfig,
@@ -106,11 +194,13 @@ impl ExtractorUtil {
         data_marker: Option<DataMarker>,
         router: Option<RdbRouter>,
         recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+        filter: RdbFilter,
     ) -> anyhow::Result<Box<dyn Extractor + Send>> {
         let base_extractor = BaseExtractor {
             buffer,
             router,
             shut_down,
+            progress: ExtractorProgress::default(),
         };
         let mut extract_state = ExtractState {
             monitor: ExtractorMonitor::new(monitor, monitor_task_id).await,
@@ -118,17 +208,18 @@ impl ExtractorUtil {
             time_filter: TimeFilter::default(),
         };
 
-        let filter = RdbFilter::from_config(&config.filter, &config.extractor_basic.db_type)?;
-
         let extractor: Box<dyn Extractor + Send> = match extractor_config.to_owned() {
             ExtractorConfig::MysqlSnapshot {
                 url,
                 connection_auth,
                 db_tbs,
                 partition_cols,
+                snapshot_watermark,
                 parallel_size,
                 parallel_type,
                 batch_size,
+                table_order,
+                enum_set_as_index,
                 ..
             } => {
                 let conn_pool = match extractor_client {
@@ -144,6 +235,7 @@ impl ExtractorUtil {
                     DbType::Mysql,
                     config.meta_center.clone(),
                     Some(conn_pool.clone()),
+                    &config.extractor_basic.timeout,
                 )
                 .await?;
                 let extractor = MysqlSnapshotExtractor {
@@ -156,11 +248,15 @@ impl ExtractorUtil {
                         batch_size,
                         parallel_type,
                         sample_rate: Self::sample_rate(config, extractor_config),
+                        sample_rows: Self::sample_rows(config),
                         recovery,
+                        snapshot_watermark,
+                        enum_set_as_index,
                     },
                     db_tbs,
                     parallel_size,
                     extract_state,
+                    table_order,
                 };
                 Box::new(extractor)
             }
@@ -170,6 +266,7 @@ impl ExtractorUtil {
                 connection_auth,
                 check_log_dir,
                 batch_size,
+                conflict_check,
             } => {
                 let conn_pool = match extractor_client {
                     ConnClient::MySQL(conn_pool) => conn_pool,
@@ -184,14 +281,49 @@ impl ExtractorUtil {
                     DbType::Mysql,
                     config.meta_center.clone(),
                     None,
+                    &config.extractor_basic.timeout,
                 )
                 .await?;
+                // conflicts only matter when revising
+                let (target_conn_pool, target_meta_manager) =
+                    if conflict_check && config.checker.is_none() {
+                        if config.sinker_basic.db_type != DbType::Mysql {
+                            bail!(Error::ConfigError(
+                                "[extractor] conflict_check requires [sinker] db_type=mysql".into()
+                            ));
+                        }
+                        let target_conn_pool = TaskUtil::create_mysql_conn_pool_with_timeout(
+                            &config.sinker_basic.url,
+                            &DbType::Mysql,
+                            &config.sinker_basic.connection_auth,
+                            1,
+                            TaskUtil::check_enable_sqlx_log(&config.runtime.log_level),
+                            None,
+                            &config.sinker_basic.timeout,
+                        )
+                        .await?;
+                        let target_meta_manager = TaskUtil::create_mysql_meta_manager(
+                            &config.sinker_basic.url,
+                            &config.sinker_basic.connection_auth,
+                            &config.runtime.log_level,
+                            DbType::Mysql,
+                            None,
+                            Some(target_conn_pool.clone()),
+                            &config.sinker_basic.timeout,
+                        )
+                        .await?;
+                        (Some(target_conn_pool), Some(target_meta_manager))
+                    } else {
+                        (None, None)
+                    };
                 let extractor = MysqlCheckExtractor {
                     conn_pool,
                     meta_manager,
                     check_log_dir,
                     batch_size,
                     replay_diff_as_update: config.checker.is_none(),
+                    target_conn_pool,
+                    target_meta_manager,
                     base_extractor,
                     extract_state,
                     filter,
@@ -215,6 +347,11 @@ impl ExtractorUtil {
                 keepalive_interval_secs,
                 start_time_utc,
                 end_time_utc,
+                capture_rows_query,
+                enum_set_as_index,
+                failover_urls,
+                sources,
+                ..
             } => {
                 let conn_pool = match extractor_client {
                     ConnClient::MySQL(conn_pool) => conn_pool,
@@ -227,10 +364,11 @@ impl ExtractorUtil {
                     DbType::Mysql,
                     config.meta_center.clone(),
                     Some(conn_pool.clone()),
+                    &config.extractor_basic.timeout,
                 )
                 .await?;
                 extract_state.time_filter = TimeFilter::new(&start_time_utc, &end_time_utc)?;
-                let extractor = MysqlCdcExtractor {
+                let mut extractor = MysqlCdcExtractor {
                     meta_manager,
                     filter,
                     conn_pool,
@@ -250,17 +388,45 @@ impl ExtractorUtil {
                     extract_state,
                     gtid_enabled,
                     gtid_set,
+                    capture_rows_query,
+                    enum_set_as_index,
+                    failover_urls,
                     recovery,
+                    source_id: String::new(),
+                    wait_task_finish: sources.is_empty(),
                 };
-                Box::new(extractor)
+                if sources.is_empty() {
+                    Box::new(extractor)
+                } else {
+                    let mut extractors = Vec::new();
+                    for source in sources {
+                        extractors.push(
+                            Self::create_mysql_source_extractor(config, &extractor, source).await?,
+                        );
+                    }
+                    // the default source gets its own state, the task level one is kept to
+                    // flush monitors when all sources finish
+                    let source_state = Self::derive_mysql_source_state(&extractor).await;
+                    let extract_state =
+                        std::mem::replace(&mut extractor.extract_state, source_state);
+                    let base_extractor = extractor.base_extractor.clone();
+                    extractors.insert(0, extractor);
+                    Box::new(MysqlMultiSourceExtractor {
+                        base_extractor,
+                        extract_state,
+                        extractors,
+                    })
+                }
             }
 
             ExtractorConfig::PgSnapshot {
                 schema_tbs,
                 partition_cols,
+                snapshot_watermark,
                 parallel_size,
                 parallel_type,
                 batch_size,
+                consistent_snapshot,
                 ..
             } => {
                 let conn_pool = match extractor_client {
@@ -280,11 +446,16 @@ impl ExtractorUtil {
                         batch_size,
                         parallel_type,
                         sample_rate: Self::sample_rate(config, extractor_config),
+                        sample_rows: Self::sample_rows(config),
                         recovery,
+                        snapshot_watermark,
+                        exported_snapshot: None,
                     },
                     parallel_size,
                     schema_tbs,
                     extract_state,
+                    table_order,
+                    consistent_snapshot,
                 };
                 Box::new(extractor)
             }
@@ -292,6 +463,7 @@ impl ExtractorUtil {
             ExtractorConfig::PgCheck {
                 check_log_dir,
                 batch_size,
+                conflict_check,
                 ..
             } => {
                 let conn_pool = match extractor_client {
@@ -301,12 +473,36 @@ impl ExtractorUtil {
                     }
                 };
                 let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
+                // conflicts only matter when revising
+                let (target_conn_pool, target_meta_manager) = if conflict_check
+                    && config.checker.is_none()
+                {
+                    if config.sinker_basic.db_type != DbType::Pg {
+                        bail!(Error::ConfigError(
+                            "[extractor] conflict_check requires [sinker] db_type=pg".into()
+                        ));
+                    }
+                    let target_conn_pool = TaskUtil::create_pg_conn_pool(
+                        &config.sinker_basic.url,
+                        &config.sinker_basic.connection_auth,
+                        1,
+                        TaskUtil::check_enable_sqlx_log(&config.runtime.log_level),
+                        false,
+                    )
+                    .await?;
+                    let target_meta_manager = PgMetaManager::new(target_conn_pool.clone()).await?;
+                    (Some(target_conn_pool), Some(target_meta_manager))
+                } else {
+                    (None, None)
+                };
                 let extractor = PgCheckExtractor {
                     conn_pool,
                     meta_manager,
                     check_log_dir,
                     batch_size,
                     replay_diff_as_update: config.checker.is_none(),
+                    target_conn_pool,
+                    target_meta_manager,
                     base_extractor,
                     extract_state,
                     filter,
@@ -319,6 +515,7 @@ impl ExtractorUtil {
                 connection_auth,
                 slot_name,
                 pub_name,
+                pub_for_filtered_tables,
                 start_lsn,
                 recreate_slot_if_exists,
                 keepalive_interval_secs,
@@ -327,6 +524,8 @@ impl ExtractorUtil {
                 ddl_meta_tb,
                 start_time_utc,
                 end_time_utc,
+                replica_identity_policy,
+                ..
             } => {
                 let conn_pool = match extractor_client {
                     ConnClient::PostgreSQL(conn_pool) => conn_pool,
@@ -342,6 +541,7 @@ impl ExtractorUtil {
                     conn_pool,
                     slot_name,
                     pub_name,
+                    pub_for_filtered_tables,
                     start_lsn,
                     recreate_slot_if_exists,
                     syncer,
@@ -349,6 +549,7 @@ impl ExtractorUtil {
                     heartbeat_interval_secs,
                     heartbeat_tb,
                     ddl_meta_tb,
+                    replica_identity_policy,
                     base_extractor,
                     extract_state,
                     recovery,
@@ -361,6 +562,8 @@ impl ExtractorUtil {
                 parallel_size,
                 parallel_type,
                 batch_size,
+                read_concern,
+                read_preference,
                 ..
             } => {
                 let mongo_client = match extractor_client {
@@ -372,6 +575,9 @@ impl ExtractorUtil {
                     parallel_type,
                     parallel_size,
                     batch_size,
+                    read_concern,
+                    read_preference,
+                    at_cluster_time: None,
                     mongo_client,
                     sample_rate: Self::sample_rate(config, extractor_config),
                     base_extractor,
@@ -504,6 +710,7 @@ impl ExtractorUtil {
                 connection_auth,
                 repl_port,
                 is_cluster,
+                module_config,
             } => {
                 let mut conn = RedisUtil::create_redis_conn(&url, &connection_auth)
                     .await
@@ -523,6 +730,7 @@ impl ExtractorUtil {
                         heartbeat_interval_secs: 0,
                         heartbeat_key: String::new(),
                         recovery,
+                        module_config,
                     };
                     return Ok(Box::new(extractor));
                 }
@@ -544,13 +752,29 @@ impl ExtractorUtil {
                     recovery,
                     cluster_node: None,
                     wait_task_finish: true,
+                    module_config,
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::ChunkFile { file_dir } => {
+                let extractor = ChunkFileExtractor {
+                    file_dir,
+                    filter,
+                    syncer,
+                    base_extractor,
+                    extract_state,
                 };
                 Box::new(extractor)
             }
 
-            ExtractorConfig::RedisSnapshotFile { file_path } => {
+            ExtractorConfig::RedisSnapshotFile {
+                file_path,
+                module_config,
+            } => {
                 let extractor = RedisSnapshotFileExtractor {
                     file_path,
+                    module_config,
                     filter,
                     base_extractor,
                     extract_state,
@@ -608,6 +832,7 @@ impl ExtractorUtil {
                         extract_state,
                         extract_type: ExtractType::Cdc,
                         recovery,
+                        module_config: RedisModuleConfig::default(),
                     };
                     return Ok(Box::new(extractor));
                 }
@@ -629,6 +854,7 @@ impl ExtractorUtil {
                     recovery,
                     cluster_node: None,
                     wait_task_finish: true,
+                    module_config: RedisModuleConfig::default(),
                 };
                 Box::new(extractor)
             }
@@ -642,6 +868,7 @@ impl ExtractorUtil {
                 heartbeat_interval_secs,
                 heartbeat_key,
                 is_cluster,
+                module_config,
             } => {
                 let mut conn = RedisUtil::create_redis_conn(&url, &connection_auth)
                     .await
@@ -661,6 +888,7 @@ impl ExtractorUtil {
                         extract_state,
                         extract_type: ExtractType::SnapshotAndCdc,
                         recovery,
+                        module_config,
                     };
                     return Ok(Box::new(extractor));
                 }
@@ -682,6 +910,7 @@ impl ExtractorUtil {
                     recovery,
                     cluster_node: None,
                     wait_task_finish: true,
+                    module_config,
                 };
                 Box::new(extractor)
             }
@@ -706,9 +935,11 @@ impl ExtractorUtil {
                 partition,
                 offset,
                 ack_interval_secs,
+                dedup_window_size,
+                replay_until,
             } => {
                 let meta_manager = TaskUtil::create_rdb_meta_manager(config).await?;
-                let avro_converter = AvroConverter::new(meta_manager, false);
+                let avro_converter = AvroConverter::new(meta_manager, false, false, false);
                 let extractor = KafkaExtractor {
                     url,
                     group,
@@ -717,10 +948,16 @@ impl ExtractorUtil {
                     offset,
                     ack_interval_secs,
                     avro_converter,
+                    filter,
                     syncer,
                     base_extractor,
                     extract_state,
                     recovery,
+                    dedup_window: (dedup_window_size > 0)
+                        .then(|| DedupWindow::new(dedup_window_size)),
+                    replay_until,
+                    chunk_assembler: ChunkAssembler::new(KAFKA_CHUNK_ASSEMBLER_CAPACITY),
+                    proxy: config.proxy.kafka_proxy.clone(),
                 };
                 Box::new(extractor)
             }
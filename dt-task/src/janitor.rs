@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    ffi::CString,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use dt_common::{
+    config::janitor_config::JanitorConfig,
+    log_info, log_warn,
+    monitor::{task_metrics::TaskMetricsType, task_monitor::TaskMonitor},
+};
+
+/// Enforces age / size based retention on local artifacts, e.g. rotated logs, check logs
+/// and statistic logs, and reports their disk usage.
+///
+/// files being written by ape-dts, e.g. default.log, position.log and miss.log, and json files
+/// keeping task state, e.g. snapshot manifests and chunk metas, are never removed.
+pub struct Janitor {
+    pub config: JanitorConfig,
+    pub task_monitor: Arc<TaskMonitor>,
+}
+
+struct Artifact {
+    size: u64,
+    modified: SystemTime,
+    removable: bool,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct CleanResult {
+    removed_count: u64,
+    removed_bytes: u64,
+    remaining_bytes: u64,
+}
+
+impl Janitor {
+    pub async fn run(self) {
+        let interval = Duration::from_secs(self.config.interval_secs.max(1));
+        loop {
+            let config = self.config.clone();
+            match tokio::task::spawn_blocking(move || Self::clean(&config, SystemTime::now())).await
+            {
+                Ok(result) => {
+                    if result.removed_count > 0 {
+                        log_info!(
+                            "janitor removed {} artifact(s), {} bytes, remaining: {} bytes",
+                            result.removed_count,
+                            result.removed_bytes,
+                            result.remaining_bytes
+                        );
+                    }
+                    self.task_monitor.set_no_window_metrics(
+                        TaskMetricsType::ArtifactBytes,
+                        result.remaining_bytes,
+                    );
+                }
+                Err(err) => log_warn!("janitor failed: {}", err),
+            }
+            self.check_disk_usage();
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    fn check_disk_usage(&self) {
+        let mut max_used_percent = 0;
+        for dir in self.config.dirs.iter() {
+            let Some(used_percent) = Self::volume_used_percent(dir) else {
+                continue;
+            };
+            max_used_percent = max_used_percent.max(used_percent);
+            if self.config.disk_usage_warn_percent > 0
+                && used_percent >= self.config.disk_usage_warn_percent
+            {
+                log_warn!(
+                    "volume of dir: {} is {}% used, over [janitor].disk_usage_warn_percent: {}",
+                    dir,
+                    used_percent,
+                    self.config.disk_usage_warn_percent
+                );
+            }
+        }
+        self.task_monitor
+            .set_no_window_metrics(TaskMetricsType::ArtifactVolumeUsedPercent, max_used_percent);
+    }
+
+    fn clean(config: &JanitorConfig, now: SystemTime) -> CleanResult {
+        // keyed by canonical path since dirs may be nested, e.g. {log_dir}/check
+        let mut artifacts = BTreeMap::new();
+        for dir in config.dirs.iter() {
+            Self::collect(Path::new(dir), &mut artifacts);
+        }
+
+        let mut result = CleanResult::default();
+
+        if config.max_age_secs > 0 {
+            let max_age = Duration::from_secs(config.max_age_secs);
+            artifacts.retain(|path, artifact| {
+                let expired = now
+                    .duration_since(artifact.modified)
+                    .is_ok_and(|age| age > max_age);
+                !(artifact.removable && expired && Self::remove(path, artifact, &mut result))
+            });
+        }
+
+        let mut total_bytes: u64 = artifacts.values().map(|artifact| artifact.size).sum();
+        if config.max_size > 0 && total_bytes > config.max_size {
+            let mut oldest: Vec<_> = artifacts
+                .iter()
+                .filter(|(_, artifact)| artifact.removable)
+                .collect();
+            oldest.sort_by_key(|(_, artifact)| artifact.modified);
+            for (path, artifact) in oldest {
+                if total_bytes <= config.max_size {
+                    break;
+                }
+                if Self::remove(path, artifact, &mut result) {
+                    total_bytes -= artifact.size;
+                }
+            }
+        }
+
+        result.remaining_bytes = total_bytes;
+        result
+    }
+
+    fn remove(path: &Path, artifact: &Artifact, result: &mut CleanResult) -> bool {
+        match fs::remove_file(path) {
+            Ok(()) => {
+                result.removed_count += 1;
+                result.removed_bytes += artifact.size;
+                true
+            }
+            Err(err) => {
+                log_warn!("janitor failed to remove: {}, {}", path.display(), err);
+                false
+            }
+        }
+    }
+
+    fn collect(dir: &Path, artifacts: &mut BTreeMap<PathBuf, Artifact>) {
+        // dirs not created yet are skipped
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                Self::collect(&path, artifacts);
+                continue;
+            }
+            let Ok(canonical_path) = path.canonicalize() else {
+                continue;
+            };
+            artifacts.insert(
+                canonical_path,
+                Artifact {
+                    size: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    removable: Self::is_removable(&path),
+                },
+            );
+        }
+    }
+
+    /// rotated logs, e.g. default1.log, and other artifacts are removable,
+    /// logs being written, e.g. default.log, and json files are not
+    fn is_removable(path: &Path) -> bool {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if name.ends_with(".json") {
+            return false;
+        }
+        match name.strip_suffix(".log") {
+            Some(stem) => stem.ends_with(|c: char| c.is_ascii_digit()),
+            None => true,
+        }
+    }
+
+    // field types of statvfs differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    fn volume_used_percent(dir: &str) -> Option<u64> {
+        let path = CString::new(dir).ok()?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        // the same as df: used / (used + available to unprivileged users)
+        let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64);
+        let total = used + stat.f_bavail as u64;
+        if total == 0 {
+            return None;
+        }
+        Some((used * 100).div_ceil(total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_janitor_clean() {
+        let dir = std::env::temp_dir().join(format!("ape_dts_janitor_{}", std::process::id()));
+        let check_dir = dir.join("check");
+        fs::create_dir_all(&check_dir).unwrap();
+        for (name, size) in [
+            ("default.log", 10),
+            ("default1.log", 20),
+            ("position3.log", 30),
+            ("snapshot_manifest.json", 40),
+            ("check/miss.log", 50),
+            ("check/miss.log.zst", 60),
+        ] {
+            fs::write(dir.join(name), vec![0u8; size]).unwrap();
+        }
+
+        let mut config = JanitorConfig {
+            // nested dirs are counted once
+            dirs: vec![
+                dir.to_string_lossy().to_string(),
+                check_dir.to_string_lossy().to_string(),
+            ],
+            ..Default::default()
+        };
+        let result = Janitor::clean(&config, SystemTime::now());
+        assert_eq!(result.removed_count, 0);
+        assert_eq!(result.remaining_bytes, 210);
+
+        config.max_size = 150;
+        let result = Janitor::clean(&config, SystemTime::now());
+        assert!(result.remaining_bytes <= 150);
+        assert!(dir.join("default.log").exists());
+        assert!(dir.join("check/miss.log").exists());
+
+        config.max_size = 0;
+        config.max_age_secs = 1;
+        let result = Janitor::clean(&config, SystemTime::now() + Duration::from_secs(3600));
+        assert_eq!(result.remaining_bytes, 100);
+        assert!(dir.join("snapshot_manifest.json").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,13 @@
 #![allow(clippy::too_many_arguments)]
 
+pub mod distributed;
 pub mod extractor_util;
+pub mod janitor;
 pub mod parallelizer_util;
+pub mod proxy_util;
 pub mod sinker_util;
+pub mod struct_syncer;
+pub mod subset_planner;
+pub mod task_lifecycle;
 pub mod task_runner;
 pub mod task_util;
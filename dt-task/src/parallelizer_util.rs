@@ -1,17 +1,21 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
 
 use super::task_util::TaskUtil;
 use anyhow::anyhow;
 use dt_common::{
-    config::{config_enums::ParallelType, task_config::TaskConfig},
+    config::{config_enums::ParallelType, sinker_config::SinkerConfig, task_config::TaskConfig},
     meta::redis::command::key_parser::KeyParser,
     monitor::task_monitor_handle::TaskMonitorHandle,
     utils::redis_util::RedisUtil,
 };
 use dt_parallelizer::{
-    base_parallelizer::BaseParallelizer, merge_parallelizer::MergeParallelizer,
-    mongo_merger::MongoMerger, partition_parallelizer::PartitionParallelizer,
-    rdb_merger::RdbMerger, rdb_partitioner::RdbPartitioner, redis_parallelizer::RedisParallelizer,
+    adaptive_batcher::AdaptiveBatcher, base_parallelizer::BaseParallelizer,
+    merge_parallelizer::MergeParallelizer, mongo_merger::MongoMerger,
+    partition_parallelizer::PartitionParallelizer, rdb_merger::RdbMerger,
+    rdb_partitioner::RdbPartitioner, redis_parallelizer::RedisParallelizer,
     serial_parallelizer::SerialParallelizer, snapshot_parallelizer::SnapshotParallelizer,
     table_parallelizer::TableParallelizer, Merger, Parallelizer,
 };
@@ -28,6 +32,10 @@ impl ParallelizerUtil {
         let base_parallelizer = BaseParallelizer {
             popped_data: VecDeque::new(),
             monitor,
+            adaptive_batcher: config
+                .parallelizer
+                .adaptive_batch()
+                .map(|c| Arc::new(AdaptiveBatcher::new(c.clone()))),
         };
 
         let parallelizer: Box<dyn Parallelizer + Send + Sync> = match parallel_type {
@@ -39,6 +47,7 @@ impl ParallelizerUtil {
                     .chunk_partitioner_rebalance()
                     .expect("snapshot parallelizer should have rebalance config")
                     .clone(),
+                table_overrides: TaskUtil::create_table_overrides(config)?,
             }),
 
             ParallelType::RdbPartition => {
@@ -47,6 +56,8 @@ impl ParallelizerUtil {
                     base_parallelizer,
                     partitioner,
                     parallel_size,
+                    batch_size: config.sinker_basic.batch_size,
+                    lag_priority: config.parallelizer.lag_priority(),
                 })
             }
 
@@ -68,14 +79,17 @@ impl ParallelizerUtil {
 
             ParallelType::Redis => {
                 let mut slot_node_map = HashMap::new();
-                let mut conn = RedisUtil::create_redis_conn(
-                    &config.sinker_basic.url,
-                    &config.sinker_basic.connection_auth,
-                )
-                .await?;
-                if RedisUtil::is_redis_cluster(&mut conn, config.sinker_basic.is_cluster) {
-                    let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
-                    slot_node_map = RedisUtil::get_slot_address_map(&nodes);
+                // file based sinkers have no target to detect cluster topology from
+                if !matches!(config.sinker, SinkerConfig::RedisAof { .. }) {
+                    let mut conn = RedisUtil::create_redis_conn(
+                        &config.sinker_basic.url,
+                        &config.sinker_basic.connection_auth,
+                    )
+                    .await?;
+                    if RedisUtil::is_redis_cluster(&mut conn, config.sinker_basic.is_cluster) {
+                        let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
+                        slot_node_map = RedisUtil::get_slot_address_map(&nodes);
+                    }
                 }
                 Box::new(RedisParallelizer {
                     base_parallelizer,
@@ -114,20 +128,28 @@ impl ParallelizerUtil {
     ) -> anyhow::Result<Box<dyn Parallelizer + Send + Sync>> {
         let merger = Self::create_rdb_merger(config).await?;
         if config.checker.is_some() {
-            Ok(Box::new(MergeParallelizer::for_check(
-                base_parallelizer,
-                merger,
-                parallel_size,
-                config.sinker_basic.clone(),
-            )))
+            Ok(Box::new(
+                MergeParallelizer::for_check(
+                    base_parallelizer,
+                    merger,
+                    parallel_size,
+                    config.sinker_basic.clone(),
+                )
+                .with_table_overrides(TaskUtil::create_table_overrides(config)?)
+                .with_lag_priority(config.parallelizer.lag_priority()),
+            ))
         } else {
-            Ok(Box::new(MergeParallelizer::for_rdb_merge(
-                base_parallelizer,
-                merger,
-                parallel_size,
-                config.sinker_basic.clone(),
-                TaskUtil::create_rdb_meta_manager(config).await?,
-            )))
+            Ok(Box::new(
+                MergeParallelizer::for_rdb_merge(
+                    base_parallelizer,
+                    merger,
+                    parallel_size,
+                    config.sinker_basic.clone(),
+                    TaskUtil::create_rdb_meta_manager(config).await?,
+                )
+                .with_table_overrides(TaskUtil::create_table_overrides(config)?)
+                .with_lag_priority(config.parallelizer.lag_priority()),
+            ))
         }
     }
 
@@ -137,18 +159,26 @@ impl ParallelizerUtil {
         parallel_size: usize,
     ) -> anyhow::Result<Box<dyn Parallelizer + Send + Sync>> {
         if config.checker.is_some() {
-            Ok(Box::new(MergeParallelizer::for_check(
-                base_parallelizer,
-                Box::new(MongoMerger {}),
-                parallel_size,
-                config.sinker_basic.clone(),
-            )))
+            Ok(Box::new(
+                MergeParallelizer::for_check(
+                    base_parallelizer,
+                    Box::new(MongoMerger {}),
+                    parallel_size,
+                    config.sinker_basic.clone(),
+                )
+                .with_table_overrides(TaskUtil::create_table_overrides(config)?)
+                .with_lag_priority(config.parallelizer.lag_priority()),
+            ))
         } else {
-            Ok(Box::new(MergeParallelizer::for_mongo(
-                base_parallelizer,
-                parallel_size,
-                config.sinker_basic.clone(),
-            )))
+            Ok(Box::new(
+                MergeParallelizer::for_mongo(
+                    base_parallelizer,
+                    parallel_size,
+                    config.sinker_basic.clone(),
+                )
+                .with_table_overrides(TaskUtil::create_table_overrides(config)?)
+                .with_lag_priority(config.parallelizer.lag_priority()),
+            ))
         }
     }
 }
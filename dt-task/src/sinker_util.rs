@@ -1,22 +1,42 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::{bail, Context};
-use kafka::producer::{Producer, RequiredAcks};
-use reqwest::{redirect::Policy, Url};
+use kafka::{client::Compression, producer::RequiredAcks};
+use rdkafka::{producer::FutureProducer, ClientConfig};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    redirect::Policy,
+    Url,
+};
 use sqlx::types::chrono::Utc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 use dt_common::{
-    config::{config_enums::DbType, sinker_config::SinkerConfig, task_config::TaskConfig},
+    config::{
+        config_enums::{
+            DbType, DdlGuardPolicy, ExtractType, FileFormat, KafkaCompression, SqlHookStage,
+        },
+        connection_auth_config::ConnectionAuthConfig,
+        sinker_config::{ShardConfig, SinkerConfig},
+        task_config::TaskConfig,
+    },
+    log_info,
     meta::{
         avro::avro_converter::AvroConverter,
         mongo::mongo_shard::{is_mongos, list_shard_collections},
         mysql::mysql_meta_manager::MysqlMetaManager,
         pg::pg_meta_manager::PgMetaManager,
+        rdb_meta_manager::RdbMetaManager,
         redis::{
             command::key_parser::KeyParser, redis_statistic_type::RedisStatisticType,
             redis_write_method::RedisWriteMethod,
         },
+        struct_meta::pg_to_mysql_converter::PgToMysqlConverter,
     },
     monitor::task_monitor_handle::TaskMonitorHandle,
     rdb_filter::RdbFilter,
@@ -24,27 +44,57 @@ use dt_common::{
 };
 
 use super::task_util::TaskUtil;
-use crate::{extractor_util::ExtractorUtil, task_util::ConnClient};
+use crate::{extractor_util::ExtractorUtil, proxy_util::ProxyUtil, task_util::ConnClient};
 use dt_connector::{
     checker::DataCheckerHandle,
     data_marker::DataMarker,
-    rdb_router::RdbRouter,
+    meta_fetcher::{
+        mysql::mysql_struct_fetcher::MysqlStructFetcher, pg::pg_struct_fetcher::PgStructFetcher,
+    },
+    rdb_router::{RdbRouter, ShardRouter},
     sinker::{
         base_sinker::BaseSinker,
+        base_struct_sinker::DBConnPool,
         checkable_sinker::{wrap_sinker_with_checker, CheckableSink},
+        circuit_breaker::SharedCircuitBreaker,
         clickhouse::{
             clickhouse_sinker::ClickhouseSinker, clickhouse_struct_sinker::ClickhouseStructSinker,
         },
+        conn_health::ConnHealth,
+        ddl_guard::DdlGuard,
         dummy_sinker::DummySinker,
-        kafka::kafka_sinker::KafkaSinker,
+        file::file_sinker::FileSinker,
+        http_sinker::HttpSinker,
+        kafka::{
+            ddl_route::KafkaDdlRoute,
+            kafka_sinker::{KafkaProducerConfig, KafkaSinker},
+            large_value::KafkaLargeValue,
+            proxy_context::KafkaProxyContext,
+            rdkafka_sinker::RdkafkaSinker,
+        },
+        lock_retry::LockRetry,
         mongo::{mongo_sinker::MongoSinker, mongo_struct_sinker::MongoStructSinker},
-        mysql::{mysql_sinker::MysqlSinker, mysql_struct_sinker::MysqlStructSinker},
-        pg::{pg_sinker::PgSinker, pg_struct_sinker::PgStructSinker},
-        redis::{redis_sinker::RedisSinker, redis_statistic_sinker::RedisStatisticSinker},
+        mysql::{
+            mysql_load_data::MysqlLoadData, mysql_sinker::MysqlSinker,
+            mysql_struct_sinker::MysqlStructSinker,
+        },
+        opensearch_sinker::OpenSearchSinker,
+        pg::{
+            pg_large_object::PgLargeObjectCopier, pg_pipeline::PgPipeline, pg_sinker::PgSinker,
+            pg_struct_sinker::PgStructSinker,
+        },
+        redis::{
+            key_template::TableKey, redis_aof_sinker::RedisAofSinker, redis_sinker::RedisSinker,
+            redis_statistic_sinker::RedisStatisticSinker,
+        },
+        shard_sinker::ShardSinker,
+        sql_hook::SqlHook,
         sql_sinker::SqlSinker,
         starrocks::{
             starrocks_sinker::StarRocksSinker, starrocks_struct_sinker::StarrocksStructSinker,
         },
+        table_creator::{SourceStructFetcher, TableCreator},
+        validate_sinker::{ValidateReport, ValidateSinker},
     },
     Sinker,
 };
@@ -61,6 +111,118 @@ macro_rules! create_filter {
 }
 
 impl SinkerUtil {
+    async fn create_shard_sinkers(
+        config: &TaskConfig,
+        shard: &ShardConfig,
+        client: ConnClient,
+        monitor: TaskMonitorHandle,
+        data_marker: Option<Arc<RwLock<DataMarker>>>,
+        circuit_breaker: Option<SharedCircuitBreaker>,
+    ) -> anyhow::Result<Sinkers> {
+        let db_type = config.sinker_basic.db_type.clone();
+        let shard_router = ShardRouter::from_config(shard, &db_type)?;
+        let router = RdbRouter::from_config(&config.router, &db_type)?;
+        let meta_manager = match &client {
+            ConnClient::MySQL(conn_pool) => {
+                RdbMetaManager::from_mysql(MysqlMetaManager::new(conn_pool.clone()).await?)
+            }
+            ConnClient::PostgreSQL(conn_pool) => {
+                RdbMetaManager::from_pg(PgMetaManager::new(conn_pool.clone()).await?)
+            }
+            _ => bail!("connection pool not found"),
+        }
+        .with_key_overrides(TaskUtil::create_table_overrides(config)?);
+
+        // each target is sunk as a task without shards, the default one reuses the client
+        let to_target_config = |target_url: &str| {
+            let mut target_config = config.clone();
+            target_config.sinker_basic.url = target_url.to_string();
+            if let SinkerConfig::Mysql { url, shard, .. } | SinkerConfig::Pg { url, shard, .. } =
+                &mut target_config.sinker
+            {
+                *url = target_url.to_string();
+                *shard = None;
+            }
+            target_config
+        };
+        let mut targets = vec![(to_target_config(&config.sinker_basic.url), client)];
+        for target in shard.targets.iter() {
+            let target_config = to_target_config(&target.url);
+            let target_client = ConnClient::sinker_from_config(&target_config).await?;
+            targets.push((target_config, target_client));
+        }
+
+        let mut target_sinkers = Vec::new();
+        for (target_config, target_client) in targets {
+            let sinkers = Box::pin(Self::create_sinkers(
+                &target_config,
+                target_client,
+                monitor.clone(),
+                data_marker.clone(),
+                None,
+                circuit_breaker.clone(),
+            ))
+            .await?;
+            target_sinkers.push(sinkers);
+        }
+
+        let mut sub_sinkers: Sinkers = Vec::new();
+        for i in 0..config.parallelizer.parallel_size() {
+            let sinker = ShardSinker {
+                targets: target_sinkers.iter().map(|s| s[i].clone()).collect(),
+                shard_router: shard_router.clone(),
+                router: router.clone(),
+                meta_manager: meta_manager.clone(),
+            };
+            Self::push_sinker(&mut sub_sinkers, sinker);
+        }
+        Ok(sub_sinkers)
+    }
+
+    /// runs [sinker].sql_hooks of the task stages on the target, None if there are no hooks
+    pub fn create_sql_hook(
+        config: &TaskConfig,
+        client: &ConnClient,
+    ) -> anyhow::Result<Option<SqlHook>> {
+        let (hooks, conn_pool) = match (&config.sinker, client) {
+            (SinkerConfig::Mysql { sql_hooks, .. }, ConnClient::MySQL(conn_pool)) => {
+                (sql_hooks, DBConnPool::MySQL(conn_pool.clone()))
+            }
+            (SinkerConfig::Pg { sql_hooks, .. }, ConnClient::PostgreSQL(conn_pool)) => {
+                (sql_hooks, DBConnPool::PostgreSQL(conn_pool.clone()))
+            }
+            _ => return Ok(None),
+        };
+        if hooks.is_empty() {
+            return Ok(None);
+        }
+        let db_type = &config.sinker_basic.db_type;
+        let router = RdbRouter::from_config(&config.router, db_type)?;
+        Ok(Some(SqlHook::new(hooks, conn_pool, db_type, router)?))
+    }
+
+    // table hooks only run in snapshot, shared by all sinkers of the target
+    fn create_tb_sql_hook(
+        config: &TaskConfig,
+        client: &ConnClient,
+    ) -> anyhow::Result<Option<Arc<Mutex<SqlHook>>>> {
+        if !matches!(
+            config.extractor_basic.extract_type,
+            ExtractType::Snapshot | ExtractType::SnapshotAndCdc
+        ) {
+            return Ok(None);
+        }
+        match Self::create_sql_hook(config, client)? {
+            Some(sql_hook)
+                if sql_hook.has_stage(&SqlHookStage::BeforeTb)
+                    || sql_hook.has_stage(&SqlHookStage::AfterTb) =>
+            {
+                Ok(Some(Arc::new(Mutex::new(sql_hook))))
+            }
+            _ => Ok(None),
+        }
+    }
+
     fn push_sinker<S: Sinker + Send + 'static>(sub_sinkers: &mut Sinkers, sinker: S) {
         sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
     }
@@ -82,11 +244,38 @@ impl SinkerUtil {
         monitor: TaskMonitorHandle,
         data_marker: Option<Arc<RwLock<DataMarker>>>,
         checker: Option<DataCheckerHandle>,
+        circuit_breaker: Option<SharedCircuitBreaker>,
     ) -> anyhow::Result<Sinkers> {
+        if let SinkerConfig::Mysql {
+            shard: Some(shard), ..
+        }
+        | SinkerConfig::Pg {
+            shard: Some(shard), ..
+        } = &config.sinker
+        {
+            if checker.is_some() {
+                bail!("data checker is not supported by sharded targets");
+            }
+            return Self::create_shard_sinkers(
+                config,
+                shard,
+                client,
+                monitor,
+                data_marker,
+                circuit_breaker,
+            )
+            .await;
+        }
+
         let log_level = &config.runtime.log_level;
         let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(log_level);
         let parallel_size = config.parallelizer.parallel_size() as u32;
         let monitor_interval = config.pipeline.checkpoint_interval_secs;
+        let table_overrides = TaskUtil::create_table_overrides(config)?;
+        let conn_health = ConnHealth::new(
+            config.sinker_basic.conn_grace_period_secs,
+            config.sinker_basic.health_check_interval_secs,
+        );
 
         let mut sub_sinkers: Sinkers = Vec::new();
         match config.sinker.clone() {
@@ -102,9 +291,16 @@ impl SinkerUtil {
                 connection_auth,
                 batch_size,
                 replace,
+                auto_create_table,
+                max_retries,
+                retry_interval_ms,
+                ddl_guard_policy,
+                ddl_conflict_policy,
+                load_data_dir,
                 ..
             } => {
                 let router = RdbRouter::from_config(&config.router, &DbType::Mysql)?;
+                let sql_hook = Self::create_tb_sql_hook(config, &client)?;
 
                 let conn_pool = match client {
                     ConnClient::MySQL(conn_pool) => conn_pool,
@@ -112,7 +308,41 @@ impl SinkerUtil {
                         bail!("connection pool not found");
                     }
                 };
-                let meta_manager = MysqlMetaManager::new(conn_pool.clone()).await?;
+                let meta_manager = MysqlMetaManager::new(conn_pool.clone())
+                    .await?
+                    .with_key_overrides(table_overrides.clone());
+                let table_creator = if auto_create_table {
+                    let src_conn_pool = TaskUtil::create_mysql_conn_pool(
+                        &config.extractor_basic.url,
+                        &DbType::Mysql,
+                        &config.extractor_basic.connection_auth,
+                        1,
+                        enable_sqlx_log,
+                        None,
+                    )
+                    .await?;
+                    let fetcher = MysqlStructFetcher {
+                        conn_pool: src_conn_pool.clone(),
+                        dbs: HashSet::new(),
+                        filter: None,
+                        meta_manager: MysqlMetaManager::new(src_conn_pool).await?,
+                    };
+                    Some(Arc::new(Mutex::new(TableCreator {
+                        fetcher: SourceStructFetcher::Mysql(fetcher),
+                        target_conn_pool: DBConnPool::MySQL(conn_pool.clone()),
+                        router: router.clone(),
+                        filter: create_filter!(config, Mysql),
+                        existing_tbs: HashSet::new(),
+                    })))
+                } else {
+                    None
+                };
+                let ddl_guard = (ddl_guard_policy != DdlGuardPolicy::None).then(|| DdlGuard {
+                    policy: ddl_guard_policy.clone(),
+                    target_conn_pool: DBConnPool::MySQL(conn_pool.clone()),
+                });
+                let load_data =
+                    (!load_data_dir.is_empty()).then(|| MysqlLoadData::new(&load_data_dir));
 
                 for _ in 0..parallel_size {
                     let sinker = MysqlSinker {
@@ -122,9 +352,23 @@ impl SinkerUtil {
                         meta_manager: meta_manager.clone(),
                         router: router.clone(),
                         batch_size,
-                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval)
+                            .with_table_overrides(table_overrides.clone()),
                         data_marker: data_marker.clone(),
                         replace,
+                        table_creator: table_creator.clone(),
+                        sql_hook: sql_hook.clone(),
+                        lock_retry: LockRetry {
+                            max_retries,
+                            retry_interval_ms,
+                        },
+                        conn_health: conn_health.clone(),
+                        circuit_breaker: circuit_breaker.clone(),
+                        ddl_guard: ddl_guard.clone(),
+                        ddl_conflict_policy: ddl_conflict_policy.clone(),
+                        statement_timeout: config.sinker_basic.timeout.statement_timeout(),
+                        load_data: load_data.clone(),
+                        trx: None,
                     };
                     Self::push_checkable_sinker(&mut sub_sinkers, sinker, &checker);
                 }
@@ -135,18 +379,88 @@ impl SinkerUtil {
                 connection_auth,
                 batch_size,
                 replace,
+                auto_create_table,
+                disable_foreign_key_checks,
+                statement_cache_capacity,
+                pipeline_size,
+                max_retries,
+                retry_interval_ms,
+                ddl_guard_policy,
+                ddl_conflict_policy,
+                ddl_transaction,
+                copy_large_objects,
                 ..
             } => {
                 let router = RdbRouter::from_config(&config.router, &DbType::Pg)?;
+                let sql_hook = Self::create_tb_sql_hook(config, &client)?;
                 let conn_pool = match client {
                     ConnClient::PostgreSQL(conn_pool) => conn_pool,
                     _ => {
                         bail!("connection pool not found");
                     }
                 };
-                let meta_manager = PgMetaManager::new(conn_pool.clone()).await?;
+                let meta_manager = PgMetaManager::new(conn_pool.clone())
+                    .await?
+                    .with_key_overrides(table_overrides.clone());
+                let table_creator = if auto_create_table {
+                    let src_conn_pool = TaskUtil::create_pg_conn_pool(
+                        &config.extractor_basic.url,
+                        &config.extractor_basic.connection_auth,
+                        1,
+                        enable_sqlx_log,
+                        false,
+                    )
+                    .await?;
+                    let fetcher = PgStructFetcher {
+                        conn_pool: src_conn_pool,
+                        schemas: HashSet::new(),
+                        filter: None,
+                    };
+                    Some(Arc::new(Mutex::new(TableCreator {
+                        fetcher: SourceStructFetcher::Pg(fetcher),
+                        target_conn_pool: DBConnPool::PostgreSQL(conn_pool.clone()),
+                        router: router.clone(),
+                        filter: create_filter!(config, Pg),
+                        existing_tbs: HashSet::new(),
+                    })))
+                } else {
+                    None
+                };
+                let ddl_guard = (ddl_guard_policy != DdlGuardPolicy::None).then(|| DdlGuard {
+                    policy: ddl_guard_policy.clone(),
+                    target_conn_pool: DBConnPool::PostgreSQL(conn_pool.clone()),
+                });
+                let large_object_copier = if copy_large_objects {
+                    if config.extractor_basic.db_type != DbType::Pg {
+                        bail!("[sinker] copy_large_objects only supports pg sources");
+                    }
+                    let src_conn_pool = TaskUtil::create_pg_conn_pool(
+                        &config.extractor_basic.url,
+                        &config.extractor_basic.connection_auth,
+                        parallel_size,
+                        enable_sqlx_log,
+                        false,
+                    )
+                    .await?;
+                    Some(PgLargeObjectCopier::new(src_conn_pool))
+                } else {
+                    None
+                };
 
                 for _ in 0..parallel_size {
+                    let pipeline = if pipeline_size > 1 {
+                        let pipeline = PgPipeline::connect(
+                            &url,
+                            &connection_auth,
+                            pipeline_size,
+                            statement_cache_capacity,
+                            disable_foreign_key_checks,
+                        )
+                        .await?;
+                        Some(Arc::new(Mutex::new(pipeline)))
+                    } else {
+                        None
+                    };
                     let sinker = PgSinker {
                         url: url.to_string(),
                         connection_auth: connection_auth.clone(),
@@ -154,9 +468,25 @@ impl SinkerUtil {
                         meta_manager: meta_manager.clone(),
                         router: router.clone(),
                         batch_size,
-                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval)
+                            .with_table_overrides(table_overrides.clone()),
                         data_marker: data_marker.clone(),
                         replace,
+                        table_creator: table_creator.clone(),
+                        sql_hook: sql_hook.clone(),
+                        pipeline,
+                        lock_retry: LockRetry {
+                            max_retries,
+                            retry_interval_ms,
+                        },
+                        conn_health: conn_health.clone(),
+                        circuit_breaker: circuit_breaker.clone(),
+                        ddl_guard: ddl_guard.clone(),
+                        ddl_conflict_policy: ddl_conflict_policy.clone(),
+                        ddl_transaction,
+                        statement_timeout: config.sinker_basic.timeout.statement_timeout(),
+                        large_object_copier: large_object_copier.clone(),
+                        trx: None,
                     };
                     Self::push_checkable_sinker(&mut sub_sinkers, sinker, &checker);
                 }
@@ -180,7 +510,8 @@ impl SinkerUtil {
                         batch_size,
                         router: router.clone(),
                         mongo_client: mongo_client.clone(),
-                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval)
+                            .with_table_overrides(table_overrides.clone()),
                         target_shard_collections: HashMap::new(),
                         require_shard_key_filter,
                         is_target_mongos,
@@ -218,7 +549,22 @@ impl SinkerUtil {
                 ack_timeout_secs,
                 required_acks,
                 with_field_defs,
+                idempotency_key,
+                compression,
+                linger_ms,
+                with_provenance,
+                with_logical_types,
+                ddl_topic,
+                ddl_topic_suffix,
+                transactional_id,
+                compress_cols,
+                compress_min_bytes,
+                max_message_bytes,
             } => {
+                let ddl_route = KafkaDdlRoute {
+                    ddl_topic,
+                    ddl_topic_suffix,
+                };
                 let router = RdbRouter::from_config_for_topic(
                     &config.router,
                     // use the db_type of extractor
@@ -226,30 +572,217 @@ impl SinkerUtil {
                 )?;
                 // kafka sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(config).await?;
-                let avro_converter = AvroConverter::new(meta_manager, with_field_defs);
+                let avro_converter = AvroConverter::new(
+                    meta_manager,
+                    with_field_defs,
+                    with_provenance,
+                    with_logical_types,
+                );
 
-                let brokers = vec![url.to_string()];
-                let acks = match required_acks.as_str() {
-                    "all" => RequiredAcks::All,
-                    "none" => RequiredAcks::None,
-                    _ => RequiredAcks::One,
-                };
+                let large_value = KafkaLargeValue::new(
+                    &compress_cols,
+                    compress_min_bytes,
+                    max_message_bytes,
+                    &config.extractor_basic.db_type,
+                )?;
 
-                for _ in 0..parallel_size {
-                    // TODO, authentication, https://github.com/kafka-rust/kafka-rust/blob/master/examples/example-ssl.rs
-                    let producer = Producer::from_hosts(brokers.clone())
-                        .with_ack_timeout(std::time::Duration::from_secs(ack_timeout_secs))
-                        .with_required_acks(acks)
-                        .create()
-                        .with_context(|| {
+                // kafka-rust producer does not support message headers, lz4 / zstd, lingering,
+                // transactions or proxies
+                let transactional = !transactional_id.is_empty();
+                let use_rdkafka = idempotency_key
+                    || linger_ms > 0
+                    || !config.proxy.kafka_proxy.is_empty()
+                    || transactional
+                    || large_value.is_enabled()
+                    || matches!(compression, KafkaCompression::Lz4 | KafkaCompression::Zstd);
+                if use_rdkafka {
+                    if idempotency_key {
+                        log_info!("kafka sinker uses the rdkafka producer to send idempotency keys in message headers");
+                    }
+                    let acks = match required_acks.as_str() {
+                        // transactions require the idempotent producer, which requires acks=all
+                        _ if transactional => "all",
+                        "all" => "all",
+                        "none" => "0",
+                        _ => "1",
+                    };
+                    let mut client_config = ClientConfig::new();
+                    client_config
+                        .set("bootstrap.servers", &url)
+                        .set("acks", acks)
+                        .set("message.timeout.ms", (ack_timeout_secs * 1000).to_string())
+                        .set("compression.codec", compression.to_string());
+                    if linger_ms > 0 {
+                        client_config.set("linger.ms", linger_ms.to_string());
+                    }
+                    if transactional {
+                        client_config.set("enable.idempotence", "true");
+                    }
+                    if max_message_bytes > 0 {
+                        client_config.set("message.max.bytes", max_message_bytes.to_string());
+                    }
+                    for i in 0..parallel_size {
+                        let mut client_config = client_config.clone();
+                        if transactional {
+                            // stable across restarts so producers of the previous run are fenced
+                            client_config
+                                .set("transactional.id", format!("{}-{}", transactional_id, i));
+                        }
+                        let producer: FutureProducer<KafkaProxyContext> = client_config
+                            .create_with_context(KafkaProxyContext::new(&config.proxy.kafka_proxy))
+                            .with_context(|| {
+                                format!("failed to create kafka producer, url: [{}]", url)
+                            })?;
+                        let sinker = RdkafkaSinker {
+                            batch_size,
+                            router: router.clone(),
+                            producer,
+                            avro_converter: avro_converter.clone(),
+                            base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                            queue_timeout_secs: ack_timeout_secs,
+                            idempotency_key,
+                            ddl_route: ddl_route.clone(),
+                            transactional,
+                            large_value: large_value.clone(),
+                        };
+                        sinker.init_transactions().await?;
+                        Self::push_sinker(&mut sub_sinkers, sinker);
+                    }
+                } else {
+                    let producer_config = KafkaProducerConfig {
+                        brokers: vec![url.to_string()],
+                        ack_timeout_secs,
+                        required_acks: match required_acks.as_str() {
+                            "all" => RequiredAcks::All,
+                            "none" => RequiredAcks::None,
+                            _ => RequiredAcks::One,
+                        },
+                        compression: match compression {
+                            KafkaCompression::Gzip => Compression::GZIP,
+                            KafkaCompression::Snappy => Compression::SNAPPY,
+                            _ => Compression::NONE,
+                        },
+                    };
+
+                    for _ in 0..parallel_size {
+                        // TODO, authentication, https://github.com/kafka-rust/kafka-rust/blob/master/examples/example-ssl.rs
+                        let producer = producer_config.create_producer().with_context(|| {
                             format!("failed to create kafka producer, url: [{}]", url)
                         })?;
-                    // the sending performance of RdkafkaSinker is much worse than KafkaSinker
-                    let sinker = KafkaSinker {
+                        // the sending performance of RdkafkaSinker is much worse than KafkaSinker
+                        let sinker = KafkaSinker {
+                            batch_size,
+                            router: router.clone(),
+                            producer,
+                            producer_config: producer_config.clone(),
+                            avro_converter: avro_converter.clone(),
+                            base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                            conn_health: conn_health.clone(),
+                            ddl_route: ddl_route.clone(),
+                        };
+                        Self::push_sinker(&mut sub_sinkers, sinker);
+                    }
+                }
+            }
+
+            SinkerConfig::Http {
+                url,
+                batch_size,
+                headers,
+                timeout_secs,
+                max_retries,
+                retry_interval_ms,
+                with_provenance,
+                idempotency_key,
+            } => {
+                // key columns of idempotency keys are taken from the source tables
+                let meta_manager = if idempotency_key {
+                    ExtractorUtil::get_extractor_meta_manager(config).await?
+                } else {
+                    None
+                };
+                let router =
+                    RdbRouter::from_config(&config.router, &config.extractor_basic.db_type)?;
+                let mut header_map = HeaderMap::new();
+                for (name, value) in headers.iter() {
+                    header_map.insert(
+                        HeaderName::from_str(name)
+                            .with_context(|| format!("invalid http header name: [{}]", name))?,
+                        HeaderValue::from_str(value)
+                            .with_context(|| format!("invalid http header value of: [{}]", name))?,
+                    );
+                }
+                let http_client = ProxyUtil::http_client_builder(&config.proxy)?
+                    .default_headers(header_map)
+                    .timeout(Duration::from_secs(timeout_secs))
+                    .build()?;
+                // each sinker sends one batch at a time, parallel_size bounds the concurrent requests
+                for _ in 0..parallel_size {
+                    let sinker = HttpSinker {
+                        url: url.clone(),
                         batch_size,
+                        http_client: http_client.clone(),
+                        max_retries,
+                        retry_interval_ms,
+                        with_provenance,
+                        idempotency_key,
+                        meta_manager: meta_manager.clone(),
                         router: router.clone(),
-                        producer,
-                        avro_converter: avro_converter.clone(),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                    };
+                    Self::push_sinker(&mut sub_sinkers, sinker);
+                }
+            }
+
+            SinkerConfig::OpenSearch {
+                url,
+                connection_auth,
+                batch_size,
+                indices,
+                create_index,
+                timeout_secs,
+                max_retries,
+                retry_interval_ms,
+            } => {
+                // documents are built with key columns and column types of the source tables
+                let Some(meta_manager) = ExtractorUtil::get_extractor_meta_manager(config).await?
+                else {
+                    bail!("opensearch sinker only supports mysql / pg sources");
+                };
+                let mut url_info = Url::parse(&url)?;
+                let (username, password) = match &connection_auth {
+                    ConnectionAuthConfig::Basic { username, password } => {
+                        (username.clone(), password.clone())
+                    }
+                    ConnectionAuthConfig::BasicSsl {
+                        username: Some(username),
+                        password,
+                        ..
+                    } => (username.clone(), password.clone()),
+                    _ => (
+                        url_info.username().to_string(),
+                        url_info.password().map(|p| p.to_string()),
+                    ),
+                };
+                let _ = url_info.set_username("");
+                let _ = url_info.set_password(None);
+                let url = url_info.as_str().trim_end_matches('/').to_string();
+                let http_client = ProxyUtil::http_client_builder(&config.proxy)?
+                    .timeout(Duration::from_secs(timeout_secs))
+                    .build()?;
+                for _ in 0..parallel_size {
+                    let sinker = OpenSearchSinker {
+                        url: url.clone(),
+                        username: username.clone(),
+                        password: password.clone(),
+                        http_client: http_client.clone(),
+                        batch_size,
+                        indices: indices.clone(),
+                        create_index,
+                        max_retries,
+                        retry_interval_ms,
+                        meta_manager: meta_manager.clone(),
+                        ensured_indices: HashSet::new(),
                         base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
                     };
                     Self::push_sinker(&mut sub_sinkers, sinker);
@@ -257,7 +790,10 @@ impl SinkerUtil {
             }
 
             SinkerConfig::MysqlStruct {
-                conflict_policy, ..
+                conflict_policy,
+                object_conflict_policy,
+                type_mappings,
+                ..
             } => {
                 let filter = create_filter!(config, Mysql);
                 let router = RdbRouter::from_config(&config.router, &DbType::Mysql)?;
@@ -271,15 +807,19 @@ impl SinkerUtil {
                 let sinker = MysqlStructSinker {
                     conn_pool,
                     conflict_policy: conflict_policy.clone(),
+                    object_conflict_policy: object_conflict_policy.clone(),
                     filter: filter.clone(),
                     router,
+                    pg_to_mysql: PgToMysqlConverter { type_mappings },
                     base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
                 };
                 Self::push_sinker(&mut sub_sinkers, sinker);
             }
 
             SinkerConfig::PgStruct {
-                conflict_policy, ..
+                conflict_policy,
+                object_conflict_policy,
+                ..
             } => {
                 let filter = create_filter!(config, Pg);
                 let router = RdbRouter::from_config(&config.router, &DbType::Pg)?;
@@ -293,6 +833,7 @@ impl SinkerUtil {
                 let sinker = PgStructSinker {
                     conn_pool,
                     conflict_policy: conflict_policy.clone(),
+                    object_conflict_policy: object_conflict_policy.clone(),
                     filter: filter.clone(),
                     router,
                     base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
@@ -305,10 +846,16 @@ impl SinkerUtil {
                 connection_auth,
                 batch_size,
                 method,
+                restore_fallback,
                 is_cluster,
+                table_keys,
             } => {
                 // redis sinker may need meta data from RDB extractor
                 let meta_manager = ExtractorUtil::get_extractor_meta_manager(config).await?;
+                let mut parsed_table_keys = HashMap::new();
+                for (tb, table_key) in table_keys.iter() {
+                    parsed_table_keys.insert(tb.clone(), TableKey::from_config(table_key)?);
+                }
                 let mut conn = RedisUtil::create_redis_conn(&url, &connection_auth)
                     .await
                     .context("failed to create Redis sinker connection")?;
@@ -331,20 +878,27 @@ impl SinkerUtil {
                             continue;
                         }
 
-                        let new_url = format!("redis://{}:{}@{}", username, password, node.address);
+                        let address = ProxyUtil::tunnel_redis_node(&config.proxy, &node.address)?;
+                        let new_url = format!("redis://{}:{}@{}", username, password, address);
                         let conn = RedisUtil::create_redis_conn(&new_url, &connection_auth).await?;
                         let sinker = RedisSinker {
                             cluster_node: Some(node.clone()),
+                            url: new_url,
+                            connection_auth: connection_auth.clone(),
                             conn,
                             batch_size,
                             now_db_id: -1,
+                            conn_db_id: -1,
                             version,
                             method: method.clone(),
+                            restore_fallback,
                             meta_manager: meta_manager.clone(),
                             base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
                             data_marker: data_marker.clone(),
                             key_parser: KeyParser::new(),
                             router: router.clone(),
+                            table_keys: parsed_table_keys.clone(),
+                            conn_health: conn_health.clone(),
                         };
                         Self::push_sinker(&mut sub_sinkers, sinker);
                     }
@@ -353,22 +907,44 @@ impl SinkerUtil {
                         let conn = RedisUtil::create_redis_conn(&url, &connection_auth).await?;
                         let sinker = RedisSinker {
                             cluster_node: None,
+                            url: url.clone(),
+                            connection_auth: connection_auth.clone(),
                             conn,
                             batch_size,
                             now_db_id: -1,
+                            conn_db_id: -1,
                             version,
                             method: method.clone(),
+                            restore_fallback,
                             meta_manager: meta_manager.clone(),
                             base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
                             data_marker: data_marker.clone(),
                             key_parser: KeyParser::new(),
                             router: router.clone(),
+                            table_keys: parsed_table_keys.clone(),
+                            conn_health: conn_health.clone(),
                         };
                         Self::push_sinker(&mut sub_sinkers, sinker);
                     }
                 }
             }
 
+            SinkerConfig::RedisAof { file_path } => {
+                let router = RdbRouter::from_config(&config.router, &DbType::Redis)?;
+                if let Some(router) = &router {
+                    router.validate_redis_db_map(false)?;
+                }
+                // all entries are written into a single file in order
+                let sinker = RedisAofSinker {
+                    file_path,
+                    writer: None,
+                    now_db_id: -1,
+                    router,
+                    base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                };
+                Self::push_sinker(&mut sub_sinkers, sinker);
+            }
+
             SinkerConfig::RedisStatistic {
                 statistic_type,
                 data_size_threshold,
@@ -407,7 +983,7 @@ impl SinkerUtil {
                     let username = url_info.username().to_string();
                     let password = url_info.password().unwrap_or("").to_string();
                     let custom = Policy::custom(|attempt| attempt.follow());
-                    let http_client = reqwest::Client::builder()
+                    let http_client = ProxyUtil::http_client_builder(&config.proxy)?
                         .http1_title_case_headers()
                         .redirect(custom)
                         .build()?;
@@ -435,7 +1011,8 @@ impl SinkerUtil {
                         password,
                         batch_size,
                         meta_manager,
-                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval)
+                            .with_table_overrides(table_overrides.clone()),
                         sync_timestamp: Utc::now().timestamp_millis(),
                         hard_delete: false,
                     };
@@ -451,11 +1028,17 @@ impl SinkerUtil {
                 url,
                 connection_auth,
                 conflict_policy,
+                bucket_size_mb,
+                partition_cols,
+                partition_unit,
             }
             | SinkerConfig::DorisStruct {
                 url,
                 connection_auth,
                 conflict_policy,
+                bucket_size_mb,
+                partition_cols,
+                partition_unit,
             } => {
                 let conn_pool = TaskUtil::create_mysql_conn_pool(
                     &url,
@@ -479,11 +1062,18 @@ impl SinkerUtil {
                     router,
                     extractor_meta_manager,
                     backend_count: 0,
+                    bucket_size_mb,
+                    partition_cols: ExtractorUtil::parse_partition_cols(&partition_cols)?,
+                    partition_unit,
                 };
                 Self::push_sinker(&mut sub_sinkers, sinker);
             }
 
-            SinkerConfig::ClickHouse { url, batch_size } => {
+            SinkerConfig::ClickHouse {
+                url,
+                batch_size,
+                insert_dedup_token,
+            } => {
                 for _ in 0..parallel_size {
                     let url_info = Url::parse(&url)?;
                     let host = url_info.host_str().unwrap().to_string();
@@ -491,7 +1081,7 @@ impl SinkerUtil {
                     let username = url_info.username().to_string();
                     let password = url_info.password().unwrap_or("").to_string();
                     let custom = Policy::custom(|attempt| attempt.follow());
-                    let http_client = reqwest::Client::builder()
+                    let http_client = ProxyUtil::http_client_builder(&config.proxy)?
                         .http1_title_case_headers()
                         .redirect(custom)
                         .build()?;
@@ -502,8 +1092,11 @@ impl SinkerUtil {
                         username,
                         password,
                         batch_size,
-                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval)
+                            .with_table_overrides(table_overrides.clone()),
                         sync_timestamp: Utc::now().timestamp_millis(),
+                        insert_dedup_token,
+                        replicated_tbs: HashMap::new(),
                     };
                     Self::push_sinker(&mut sub_sinkers, sinker);
                 }
@@ -537,6 +1130,47 @@ impl SinkerUtil {
                 Self::push_sinker(&mut sub_sinkers, sinker);
             }
 
+            SinkerConfig::File {
+                file_dir,
+                file_format,
+                max_file_size,
+                gzip,
+                orc_compression,
+                s3_config,
+                s3_key_prefix,
+            } => {
+                // rows have been routed by extractor, router is only used to find the source table
+                let router =
+                    RdbRouter::from_config(&config.router, &config.extractor_basic.db_type)?;
+                let s3_client = match &s3_config {
+                    Some(s3_config) => Some(TaskUtil::create_s3_client(s3_config)?),
+                    None => None,
+                };
+                for sinker_id in 0..parallel_size as usize {
+                    // orc schemas are built from tb_meta of the source
+                    let meta_manager = if file_format == FileFormat::Orc {
+                        ExtractorUtil::get_extractor_meta_manager(config).await?
+                    } else {
+                        None
+                    };
+                    let sinker = FileSinker {
+                        sinker_id,
+                        file_dir: file_dir.clone(),
+                        file_format: file_format.clone(),
+                        max_file_size,
+                        gzip,
+                        orc_compression: orc_compression.clone(),
+                        meta_manager,
+                        s3_client: s3_client.clone(),
+                        s3_key_prefix: s3_key_prefix.clone(),
+                        router: router.clone(),
+                        writers: HashMap::new(),
+                        base_sinker: BaseSinker::new(monitor.clone(), monitor_interval),
+                    };
+                    Self::push_sinker(&mut sub_sinkers, sinker);
+                }
+            }
+
             SinkerConfig::Sql { reverse } => {
                 let router =
                     RdbRouter::from_config(&config.router, &config.extractor_basic.db_type)?;
@@ -554,6 +1188,29 @@ impl SinkerUtil {
                     Self::push_sinker(&mut sub_sinkers, sinker);
                 }
             }
+
+            SinkerConfig::Validate { sample_rows, .. } => {
+                let meta_manager = match client {
+                    ConnClient::MySQL(conn_pool) => {
+                        RdbMetaManager::from_mysql(MysqlMetaManager::new(conn_pool).await?)
+                    }
+                    ConnClient::PostgreSQL(conn_pool) => {
+                        RdbMetaManager::from_pg(PgMetaManager::new(conn_pool).await?)
+                    }
+                    _ => {
+                        bail!("connection pool not found");
+                    }
+                };
+                let report = Arc::new(Mutex::new(ValidateReport::default()));
+                for _ in 0..parallel_size {
+                    let sinker = ValidateSinker {
+                        meta_manager: meta_manager.clone(),
+                        sample_rows,
+                        report: report.clone(),
+                    };
+                    Self::push_sinker(&mut sub_sinkers, sinker);
+                }
+            }
         };
         Ok(sub_sinkers)
     }
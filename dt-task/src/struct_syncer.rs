@@ -0,0 +1,568 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Duration,
+};
+
+use dt_common::{
+    config::{config_enums::DbType, struct_sync_config::StructSyncConfig, task_config::TaskConfig},
+    log_info, log_warn,
+    meta::{
+        mysql::mysql_meta_manager::MysqlMetaManager,
+        struct_meta::{
+            statement::{
+                mysql_create_table_statement::MysqlCreateTableStatement,
+                pg_create_table_statement::PgCreateTableStatement,
+                struct_statement::StructStatement,
+            },
+            struct_data::StructData,
+            structure::{
+                column::Column,
+                index::{Index, IndexKind},
+                structure_type::StructureType,
+            },
+        },
+    },
+    rdb_filter::RdbFilter,
+};
+use dt_connector::{
+    meta_fetcher::{
+        mysql::mysql_struct_fetcher::MysqlStructFetcher, pg::pg_struct_fetcher::PgStructFetcher,
+    },
+    rdb_router::RdbRouter,
+    sinker::{
+        base_struct_sinker::{BaseStructSinker, DBConnPool},
+        table_creator::{SourceStructFetcher, TableCreator},
+    },
+};
+
+use crate::task_util::{ConnClient, TaskUtil};
+
+/// Reconciles target structures of filtered tables with the source periodically.
+///
+/// tables, columns and indexes missing in target are created. Differences which can not be
+/// applied without losing data, e.g. columns / indexes only in target or changed column types,
+/// are logged once and left to be handled manually.
+pub struct StructSyncer {
+    pub config: StructSyncConfig,
+    // source fetcher, target pool, router and filter, also creates missing tables
+    pub creator: TableCreator,
+    // differences not applied and already logged
+    reported: HashSet<String>,
+}
+
+enum ColumnDiff {
+    Same,
+    Missing,
+    Changed(String),
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct TbDiff {
+    sqls: Vec<String>,
+    // destructive differences, only logged
+    unapplied: Vec<String>,
+}
+
+impl StructSyncer {
+    pub async fn from_config(config: &TaskConfig) -> anyhow::Result<Option<Self>> {
+        let Some(struct_sync) = config.struct_sync.clone() else {
+            return Ok(None);
+        };
+
+        let db_type = &config.extractor_basic.db_type;
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&config.runtime.log_level);
+        let (fetcher, target_conn_pool) = match db_type {
+            DbType::Mysql => {
+                let src_conn_pool = TaskUtil::create_mysql_conn_pool(
+                    &config.extractor_basic.url,
+                    db_type,
+                    &config.extractor_basic.connection_auth,
+                    1,
+                    enable_sqlx_log,
+                    None,
+                )
+                .await?;
+                let dst_conn_pool = TaskUtil::create_mysql_conn_pool(
+                    &config.sinker_basic.url,
+                    db_type,
+                    &config.sinker_basic.connection_auth,
+                    1,
+                    enable_sqlx_log,
+                    None,
+                )
+                .await?;
+                let fetcher = MysqlStructFetcher {
+                    conn_pool: src_conn_pool.clone(),
+                    dbs: HashSet::new(),
+                    filter: None,
+                    meta_manager: MysqlMetaManager::new(src_conn_pool).await?,
+                };
+                (
+                    SourceStructFetcher::Mysql(fetcher),
+                    DBConnPool::MySQL(dst_conn_pool),
+                )
+            }
+            _ => {
+                let src_conn_pool = TaskUtil::create_pg_conn_pool(
+                    &config.extractor_basic.url,
+                    &config.extractor_basic.connection_auth,
+                    1,
+                    enable_sqlx_log,
+                    false,
+                )
+                .await?;
+                let dst_conn_pool = TaskUtil::create_pg_conn_pool(
+                    &config.sinker_basic.url,
+                    &config.sinker_basic.connection_auth,
+                    1,
+                    enable_sqlx_log,
+                    false,
+                )
+                .await?;
+                let fetcher = PgStructFetcher {
+                    conn_pool: src_conn_pool,
+                    schemas: HashSet::new(),
+                    filter: None,
+                };
+                (
+                    SourceStructFetcher::Pg(fetcher),
+                    DBConnPool::PostgreSQL(dst_conn_pool),
+                )
+            }
+        };
+
+        Ok(Some(Self {
+            config: struct_sync,
+            creator: TableCreator {
+                fetcher,
+                target_conn_pool,
+                router: RdbRouter::from_config(&config.router, db_type)?,
+                filter: RdbFilter::from_config(&config.filter, db_type)?,
+                existing_tbs: HashSet::new(),
+            },
+            reported: HashSet::new(),
+        }))
+    }
+
+    pub async fn run(mut self) {
+        let interval = Duration::from_secs(self.config.interval_secs.max(1));
+        loop {
+            if let Err(err) = self.sync().await {
+                log_warn!("struct sync failed: {}", err);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn sync(&mut self) -> anyhow::Result<()> {
+        let mut src_tbs = self.fetch_src_tbs().await?;
+        let schemas: HashSet<String> = src_tbs.keys().map(|(schema, _)| schema.clone()).collect();
+        let dst_tbs = self.fetch_dst_tbs(&schemas).await?;
+
+        for ((schema, tb), src) in src_tbs.iter_mut() {
+            let Some(dst) = dst_tbs.get(&(schema.clone(), tb.clone())) else {
+                log_info!("struct sync, table missing in target: {}.{}", schema, tb);
+                if let Err(err) = self.creator.create_table(schema, tb).await {
+                    log_warn!(
+                        "struct sync, failed to create table: {}.{}, error: {}",
+                        schema,
+                        tb,
+                        err
+                    );
+                }
+                continue;
+            };
+
+            let diff = match (src, dst) {
+                (
+                    StructStatement::MysqlCreateTable(src),
+                    StructStatement::MysqlCreateTable(dst),
+                ) => Self::diff_mysql(src, dst, &self.creator.filter),
+                (StructStatement::PgCreateTable(src), StructStatement::PgCreateTable(dst)) => {
+                    Self::diff_pg(src, dst, &self.creator.filter)?
+                }
+                _ => continue,
+            };
+            self.apply(schema, tb, diff).await;
+        }
+
+        for (schema, tb) in dst_tbs.keys() {
+            if src_tbs.contains_key(&(schema.clone(), tb.clone())) {
+                continue;
+            }
+            // only target tables mapped from filtered source tables are concerned
+            let (src_schema, src_tb) = match &self.creator.router {
+                Some(router) => router.reverse_get_tb_map(schema, tb),
+                None => (schema.as_str(), tb.as_str()),
+            };
+            if !self.creator.filter.filter_tb(src_schema, src_tb) {
+                self.report(schema, tb, "table not in source".into());
+            }
+        }
+        Ok(())
+    }
+
+    async fn apply(&mut self, schema: &str, tb: &str, diff: TbDiff) {
+        for sql in diff.sqls {
+            log_info!("struct sync, ddl: {}", sql);
+            if let Err(err) = BaseStructSinker::execute(&self.creator.target_conn_pool, &sql).await
+            {
+                log_warn!("struct sync, failed to execute: {}, error: {}", sql, err);
+            }
+        }
+        for unapplied in diff.unapplied {
+            self.report(schema, tb, unapplied);
+        }
+    }
+
+    fn report(&mut self, schema: &str, tb: &str, difference: String) {
+        let key = format!("{}.{}.{}", schema, tb, difference);
+        if self.reported.insert(key) {
+            log_warn!(
+                "struct sync, difference not applied, tb: {}.{}, {}",
+                schema,
+                tb,
+                difference
+            );
+        }
+    }
+
+    /// create table statements of filtered source tables, keyed by routed names
+    async fn fetch_src_tbs(
+        &mut self,
+    ) -> anyhow::Result<BTreeMap<(String, String), StructStatement>> {
+        let (conn_client, db_type) = match &self.creator.fetcher {
+            SourceStructFetcher::Mysql(fetcher) => {
+                (ConnClient::MySQL(fetcher.conn_pool.clone()), DbType::Mysql)
+            }
+            SourceStructFetcher::Pg(fetcher) => (
+                ConnClient::PostgreSQL(fetcher.conn_pool.clone()),
+                DbType::Pg,
+            ),
+        };
+        let schemas: HashSet<String> = TaskUtil::list_schemas(&conn_client, &db_type)
+            .await?
+            .into_iter()
+            .filter(|schema| !self.creator.filter.filter_schema(schema))
+            .collect();
+
+        let mut statements = Vec::new();
+        match &mut self.creator.fetcher {
+            SourceStructFetcher::Mysql(fetcher) => {
+                fetcher.dbs = schemas;
+                for statement in fetcher.get_create_table_statements("", "").await? {
+                    let (schema, tb) =
+                        (&statement.table.database_name, &statement.table.table_name);
+                    if !self.creator.filter.filter_tb(schema, tb) {
+                        statements
+                            .push((schema.clone(), StructStatement::MysqlCreateTable(statement)));
+                    }
+                }
+            }
+            SourceStructFetcher::Pg(fetcher) => {
+                fetcher.schemas = schemas;
+                for statement in fetcher.get_create_table_statements("", "").await? {
+                    let (schema, tb) = (&statement.table.schema_name, &statement.table.table_name);
+                    if !self.creator.filter.filter_tb(schema, tb) {
+                        statements
+                            .push((schema.clone(), StructStatement::PgCreateTable(statement)));
+                    }
+                }
+            }
+        }
+
+        let mut tbs = BTreeMap::new();
+        for (schema, statement) in statements {
+            let struct_data = StructData { schema, statement };
+            let struct_data = match &self.creator.router {
+                Some(router) => router.route_struct(struct_data),
+                None => struct_data,
+            };
+            if let Some(key) = Self::get_tb_key(&struct_data.statement) {
+                tbs.insert(key, struct_data.statement);
+            }
+        }
+        Ok(tbs)
+    }
+
+    async fn fetch_dst_tbs(
+        &self,
+        schemas: &HashSet<String>,
+    ) -> anyhow::Result<HashMap<(String, String), StructStatement>> {
+        let mut tbs = HashMap::new();
+        match &self.creator.target_conn_pool {
+            DBConnPool::MySQL(conn_pool) => {
+                let mut fetcher = MysqlStructFetcher {
+                    conn_pool: conn_pool.clone(),
+                    dbs: schemas.clone(),
+                    filter: None,
+                    meta_manager: MysqlMetaManager::new(conn_pool.clone()).await?,
+                };
+                for statement in fetcher.get_create_table_statements("", "").await? {
+                    let statement = StructStatement::MysqlCreateTable(statement);
+                    if let Some(key) = Self::get_tb_key(&statement) {
+                        tbs.insert(key, statement);
+                    }
+                }
+            }
+            DBConnPool::PostgreSQL(conn_pool) => {
+                let mut fetcher = PgStructFetcher {
+                    conn_pool: conn_pool.clone(),
+                    schemas: schemas.clone(),
+                    filter: None,
+                };
+                for statement in fetcher.get_create_table_statements("", "").await? {
+                    let statement = StructStatement::PgCreateTable(statement);
+                    if let Some(key) = Self::get_tb_key(&statement) {
+                        tbs.insert(key, statement);
+                    }
+                }
+            }
+        }
+        Ok(tbs)
+    }
+
+    fn get_tb_key(statement: &StructStatement) -> Option<(String, String)> {
+        match statement {
+            StructStatement::MysqlCreateTable(s) => {
+                Some((s.table.database_name.clone(), s.table.table_name.clone()))
+            }
+            StructStatement::PgCreateTable(s) => {
+                Some((s.table.schema_name.clone(), s.table.table_name.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn diff_mysql(
+        src: &mut MysqlCreateTableStatement,
+        dst: &MysqlCreateTableStatement,
+        filter: &RdbFilter,
+    ) -> TbDiff {
+        let mut diff = TbDiff::default();
+        src.table
+            .columns
+            .sort_by(|a, b| a.ordinal_position.cmp(&b.ordinal_position));
+
+        let mut after = None;
+        for column in src.table.columns.iter() {
+            match Self::diff_column(column, &dst.table.columns) {
+                ColumnDiff::Same => {}
+                ColumnDiff::Missing => diff.sqls.push(MysqlCreateTableStatement::add_column_sql(
+                    &src.table, column, after,
+                )),
+                ColumnDiff::Changed(unapplied) => diff.unapplied.push(unapplied),
+            }
+            after = Some(column.column_name.as_str());
+        }
+        Self::diff_dropped_columns(&src.table.columns, &dst.table.columns, &mut diff);
+
+        for index in src.indexes.iter_mut() {
+            if Self::filter_index(index, filter) {
+                continue;
+            }
+            if !dst.indexes.iter().any(|i| i.index_name == index.index_name) {
+                diff.sqls
+                    .push(MysqlCreateTableStatement::add_index_sql(index));
+            }
+        }
+        Self::diff_dropped_indexes(&src.indexes, &dst.indexes, &mut diff);
+        diff
+    }
+
+    fn diff_pg(
+        src: &mut PgCreateTableStatement,
+        dst: &PgCreateTableStatement,
+        filter: &RdbFilter,
+    ) -> anyhow::Result<TbDiff> {
+        let mut diff = TbDiff::default();
+        src.table
+            .columns
+            .sort_by(|a, b| a.ordinal_position.cmp(&b.ordinal_position));
+
+        for column in src.table.columns.iter() {
+            match Self::diff_column(column, &dst.table.columns) {
+                ColumnDiff::Same => {}
+                ColumnDiff::Missing => diff
+                    .sqls
+                    .push(PgCreateTableStatement::add_column_sql(&src.table, column)),
+                ColumnDiff::Changed(unapplied) => diff.unapplied.push(unapplied),
+            }
+        }
+        Self::diff_dropped_columns(&src.table.columns, &dst.table.columns, &mut diff);
+
+        // indexes of primary key / unique constraints come along with the constraints
+        let is_constraint_index = |statement: &PgCreateTableStatement, index: &Index| {
+            statement
+                .constraints
+                .iter()
+                .any(|c| c.constraint_name == index.index_name)
+        };
+        let src_indexes: Vec<Index> = src
+            .indexes
+            .iter()
+            .filter(|i| !is_constraint_index(src, i))
+            .cloned()
+            .collect();
+        let dst_indexes: Vec<Index> = dst
+            .indexes
+            .iter()
+            .filter(|i| !is_constraint_index(dst, i))
+            .cloned()
+            .collect();
+        for index in src_indexes.iter() {
+            if Self::filter_index(index, filter) {
+                continue;
+            }
+            if !dst_indexes.iter().any(|i| i.index_name == index.index_name) {
+                diff.sqls
+                    .push(PgCreateTableStatement::add_index_sql(index)?);
+            }
+        }
+        Self::diff_dropped_indexes(&src_indexes, &dst_indexes, &mut diff);
+        Ok(diff)
+    }
+
+    fn diff_column(column: &Column, dst_columns: &[Column]) -> ColumnDiff {
+        let Some(dst_column) = dst_columns
+            .iter()
+            .find(|c| c.column_name == column.column_name)
+        else {
+            return ColumnDiff::Missing;
+        };
+
+        if !dst_column
+            .column_type
+            .eq_ignore_ascii_case(&column.column_type)
+            || dst_column.is_nullable != column.is_nullable
+        {
+            return ColumnDiff::Changed(format!(
+                "column: {} changed, source: {}{}, target: {}{}",
+                column.column_name,
+                column.column_type,
+                if column.is_nullable { "" } else { " NOT NULL" },
+                dst_column.column_type,
+                if dst_column.is_nullable {
+                    ""
+                } else {
+                    " NOT NULL"
+                },
+            ));
+        }
+        ColumnDiff::Same
+    }
+
+    fn diff_dropped_columns(src_columns: &[Column], dst_columns: &[Column], diff: &mut TbDiff) {
+        for column in dst_columns.iter() {
+            if !src_columns
+                .iter()
+                .any(|c| c.column_name == column.column_name)
+            {
+                diff.unapplied
+                    .push(format!("column: {} not in source", column.column_name));
+            }
+        }
+    }
+
+    fn diff_dropped_indexes(src_indexes: &[Index], dst_indexes: &[Index], diff: &mut TbDiff) {
+        for index in dst_indexes.iter() {
+            if !src_indexes.iter().any(|i| i.index_name == index.index_name) {
+                diff.unapplied
+                    .push(format!("index: {} not in source", index.index_name));
+            }
+        }
+    }
+
+    // unique indexes are part of the table structure, same as in struct migration
+    fn filter_index(index: &Index, filter: &RdbFilter) -> bool {
+        match index.index_kind {
+            IndexKind::Unique => filter.filter_structure(&StructureType::Table),
+            _ => filter.filter_structure(&StructureType::Index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::{
+        config::filter_config::FilterConfig,
+        meta::struct_meta::structure::{index::IndexColumn, table::Table},
+    };
+
+    use super::*;
+
+    fn column(name: &str, ordinal_position: u32, column_type: &str) -> Column {
+        Column {
+            column_name: name.into(),
+            ordinal_position,
+            column_type: column_type.into(),
+            is_nullable: true,
+            ..Default::default()
+        }
+    }
+
+    fn statement(columns: Vec<Column>, indexes: Vec<Index>) -> MysqlCreateTableStatement {
+        MysqlCreateTableStatement {
+            table: Table {
+                database_name: "db".into(),
+                table_name: "tb".into(),
+                columns,
+                ..Default::default()
+            },
+            constraints: Vec::new(),
+            indexes,
+        }
+    }
+
+    fn index(name: &str, column_name: &str) -> Index {
+        Index {
+            database_name: "db".into(),
+            table_name: "tb".into(),
+            index_name: name.into(),
+            columns: vec![IndexColumn {
+                column_name: column_name.into(),
+                seq_in_index: 1,
+                prefix_length: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_mysql() {
+        let filter = RdbFilter::from_config(&FilterConfig::default(), &DbType::Mysql).unwrap();
+        let mut src = statement(
+            vec![
+                column("id", 1, "int"),
+                column("name", 2, "varchar(64)"),
+                column("age", 3, "int"),
+            ],
+            vec![index("idx_name", "name")],
+        );
+        let dst = statement(
+            vec![
+                column("id", 1, "int"),
+                column("name", 2, "varchar(32)"),
+                column("extra", 3, "int"),
+            ],
+            vec![index("idx_extra", "extra")],
+        );
+
+        let diff = StructSyncer::diff_mysql(&mut src, &dst, &filter);
+        assert_eq!(
+            diff.sqls,
+            vec![
+                "ALTER TABLE `db`.`tb` ADD COLUMN `age` int NULL AFTER `name`".to_string(),
+                "CREATE  INDEX `idx_name` ON `db`.`tb` (`name`) ".to_string(),
+            ]
+        );
+        assert_eq!(
+            diff.unapplied,
+            vec![
+                "column: name changed, source: varchar(64), target: varchar(32)".to_string(),
+                "column: extra not in source".to_string(),
+                "index: idx_extra not in source".to_string(),
+            ]
+        );
+    }
+}
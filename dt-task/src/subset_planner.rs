@@ -0,0 +1,309 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use futures::TryStreamExt;
+use sqlx::Row;
+
+use dt_common::{
+    config::{config_enums::DbType, subset_config::SubsetConfig},
+    log_info, log_warn,
+    meta::foreign_key::ForeignKey,
+    rdb_filter::RdbFilter,
+    utils::sql_util::SqlUtil,
+};
+
+use crate::task_util::ConnClient;
+
+type TbKey = (String, String);
+
+/// Resolves the referential closure of [subset] seeds into a condition per table, which
+/// selects seed rows, rows they reference, and optionally rows referencing them.
+///
+/// Conditions are nested subqueries on the source, e.g. the parent of a seed table:
+/// `("id") IN (SELECT "customer_id" FROM "shop"."orders" WHERE <seed condition>)`,
+/// a table reached by several paths takes rows of any of them.
+pub struct SubsetPlanner {
+    pub db_type: DbType,
+    pub config: SubsetConfig,
+    pub filter: RdbFilter,
+}
+
+impl SubsetPlanner {
+    /// conditions of all tables in the closure, keyed by (schema, tb)
+    pub fn plan(&self, foreign_keys: &[Vec<ForeignKey>]) -> BTreeMap<TbKey, String> {
+        let mut conditions: BTreeMap<TbKey, Vec<String>> = BTreeMap::new();
+        // (table, condition, hops from seeds, whether reached from seeds through children only)
+        let mut queue: VecDeque<(TbKey, String, usize, bool)> = self
+            .config
+            .seeds
+            .iter()
+            .map(|seed| {
+                let key = (seed.db.clone(), seed.tb.clone());
+                (key, seed.condition.clone(), 0, true)
+            })
+            .collect();
+
+        while let Some((key, condition, depth, downward)) = queue.pop_front() {
+            let tb_conditions = conditions.entry(key.clone()).or_default();
+            if tb_conditions.contains(&condition) {
+                continue;
+            }
+            tb_conditions.push(condition.clone());
+            if depth >= self.config.max_depth {
+                continue;
+            }
+
+            for fk in foreign_keys.iter() {
+                let (child, parent) = Self::get_fk_tbs(fk);
+                // rows referenced by the current rows, needed by all tables
+                if child == key {
+                    if self.filter.filter_tb(&parent.0, &parent.1) {
+                        log_warn!(
+                            "subset: {}.{} referenced by {}.{} is filtered, the subset may be inconsistent",
+                            parent.0,
+                            parent.1,
+                            key.0,
+                            key.1
+                        );
+                        continue;
+                    }
+                    let parent_cols: Vec<&str> = fk.iter().map(|i| i.ref_col.as_str()).collect();
+                    let child_cols: Vec<&str> = fk.iter().map(|i| i.col.as_str()).collect();
+                    let parent_condition =
+                        self.build_in_condition(&parent_cols, &key, &child_cols, &condition);
+                    queue.push_back((parent, parent_condition, depth + 1, false));
+                }
+
+                // rows referencing the current rows, only followed from seeds downwards,
+                // otherwise all orders of customers of seed orders would be taken
+                if parent == key && downward && self.config.include_children {
+                    if self.filter.filter_tb(&child.0, &child.1) {
+                        continue;
+                    }
+                    let parent_cols: Vec<&str> = fk.iter().map(|i| i.ref_col.as_str()).collect();
+                    let child_cols: Vec<&str> = fk.iter().map(|i| i.col.as_str()).collect();
+                    let child_condition =
+                        self.build_in_condition(&child_cols, &key, &parent_cols, &condition);
+                    queue.push_back((child, child_condition, depth + 1, true));
+                }
+            }
+        }
+
+        let conditions: BTreeMap<TbKey, String> = conditions
+            .into_iter()
+            .map(|(key, tb_conditions)| {
+                let condition = if tb_conditions.len() == 1 {
+                    tb_conditions[0].clone()
+                } else {
+                    tb_conditions
+                        .iter()
+                        .map(|i| format!("({})", i))
+                        .collect::<Vec<_>>()
+                        .join(" OR ")
+                };
+                (key, condition)
+            })
+            .collect();
+        log_info!(
+            "subset: {} tables in the closure of {} seeds",
+            conditions.len(),
+            self.config.seeds.len()
+        );
+        conditions
+    }
+
+    // (cols) IN (SELECT src_cols FROM src_tb WHERE src_condition)
+    fn build_in_condition(
+        &self,
+        cols: &[&str],
+        src_tb: &TbKey,
+        src_cols: &[&str],
+        src_condition: &str,
+    ) -> String {
+        let escape_cols = |cols: &[&str]| {
+            cols.iter()
+                .map(|col| SqlUtil::escape_by_db_type(col, &self.db_type))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        // nulls of the referencing columns reference nothing
+        let not_nulls = src_cols
+            .iter()
+            .map(|col| {
+                format!(
+                    "{} IS NOT NULL",
+                    SqlUtil::escape_by_db_type(col, &self.db_type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        format!(
+            "({}) IN (SELECT {} FROM {}.{} WHERE ({}) AND {})",
+            escape_cols(cols),
+            escape_cols(src_cols),
+            SqlUtil::escape_by_db_type(&src_tb.0, &self.db_type),
+            SqlUtil::escape_by_db_type(&src_tb.1, &self.db_type),
+            src_condition,
+            not_nulls
+        )
+    }
+
+    fn get_fk_tbs(fk: &[ForeignKey]) -> (TbKey, TbKey) {
+        (
+            (fk[0].schema.clone(), fk[0].tb.clone()),
+            (fk[0].ref_schema.clone(), fk[0].ref_tb.clone()),
+        )
+    }
+
+    /// all foreign keys of the source, columns of a composite key are grouped in order
+    pub async fn fetch_foreign_keys(
+        conn_client: &ConnClient,
+        db_type: &DbType,
+    ) -> anyhow::Result<Vec<Vec<ForeignKey>>> {
+        // (constraint name, column of the key)
+        let mut rows: Vec<(String, ForeignKey)> = Vec::new();
+        match conn_client {
+            ConnClient::MySQL(conn_pool) if matches!(db_type, DbType::Mysql) => {
+                let sql = "SELECT TABLE_SCHEMA, TABLE_NAME, CONSTRAINT_NAME, COLUMN_NAME,
+                        REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME
+                    FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE
+                    WHERE REFERENCED_TABLE_NAME IS NOT NULL
+                    ORDER BY TABLE_SCHEMA, TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION";
+                let mut result = sqlx::query(sql).fetch(conn_pool);
+                while let Some(row) = result.try_next().await? {
+                    let get = |col: &str| SqlUtil::try_get_mysql_string(&row, col);
+                    rows.push((
+                        get("CONSTRAINT_NAME")?,
+                        ForeignKey {
+                            schema: get("TABLE_SCHEMA")?,
+                            tb: get("TABLE_NAME")?,
+                            col: get("COLUMN_NAME")?,
+                            ref_schema: get("REFERENCED_TABLE_SCHEMA")?,
+                            ref_tb: get("REFERENCED_TABLE_NAME")?,
+                            ref_col: get("REFERENCED_COLUMN_NAME")?,
+                        },
+                    ));
+                }
+            }
+
+            ConnClient::PostgreSQL(conn_pool) if matches!(db_type, DbType::Pg) => {
+                let sql = "SELECT n.nspname::text AS schema_name,
+                        c.relname::text AS table_name,
+                        con.conname::text AS constraint_name,
+                        a.attname::text AS column_name,
+                        rn.nspname::text AS ref_schema_name,
+                        rc.relname::text AS ref_table_name,
+                        ra.attname::text AS ref_column_name
+                    FROM pg_catalog.pg_constraint con
+                    JOIN pg_catalog.pg_class c ON con.conrelid = c.oid
+                    JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+                    JOIN pg_catalog.pg_class rc ON con.confrelid = rc.oid
+                    JOIN pg_catalog.pg_namespace rn ON rc.relnamespace = rn.oid
+                    CROSS JOIN LATERAL unnest(con.conkey, con.confkey)
+                        WITH ORDINALITY AS k(attnum, ref_attnum, ord)
+                    JOIN pg_catalog.pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                    JOIN pg_catalog.pg_attribute ra ON ra.attrelid = con.confrelid AND ra.attnum = k.ref_attnum
+                    WHERE con.contype = 'f'
+                    ORDER BY n.nspname, c.relname, con.conname, k.ord";
+                let mut result = sqlx::query(sql).fetch(conn_pool);
+                while let Some(row) = result.try_next().await? {
+                    rows.push((
+                        row.try_get("constraint_name")?,
+                        ForeignKey {
+                            schema: row.try_get("schema_name")?,
+                            tb: row.try_get("table_name")?,
+                            col: row.try_get("column_name")?,
+                            ref_schema: row.try_get("ref_schema_name")?,
+                            ref_tb: row.try_get("ref_table_name")?,
+                            ref_col: row.try_get("ref_column_name")?,
+                        },
+                    ));
+                }
+            }
+
+            _ => {}
+        }
+
+        let mut foreign_keys: Vec<Vec<ForeignKey>> = Vec::new();
+        let mut last_constraint: Option<(String, String, String)> = None;
+        for (constraint, fk) in rows {
+            let current = Some((fk.schema.clone(), fk.tb.clone(), constraint));
+            match foreign_keys.last_mut() {
+                Some(last) if current == last_constraint => last.push(fk),
+                _ => foreign_keys.push(vec![fk]),
+            }
+            last_constraint = current;
+        }
+        Ok(foreign_keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dt_common::config::{filter_config::FilterConfig, subset_config::SubsetSeed};
+
+    use super::*;
+
+    fn fk(tb: &str, col: &str, ref_tb: &str, ref_col: &str) -> ForeignKey {
+        ForeignKey {
+            schema: "shop".into(),
+            tb: tb.into(),
+            col: col.into(),
+            ref_schema: "shop".into(),
+            ref_tb: ref_tb.into(),
+            ref_col: ref_col.into(),
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let foreign_keys = vec![
+            vec![fk("orders", "customer_id", "customers", "id")],
+            vec![fk("order_items", "order_id", "orders", "id")],
+            vec![fk("order_items", "product_id", "products", "id")],
+            vec![fk("reviews", "customer_id", "customers", "id")],
+        ];
+        let filter = RdbFilter::from_config(
+            &FilterConfig {
+                do_tbs: "shop.*".into(),
+                ..Default::default()
+            },
+            &DbType::Mysql,
+        )
+        .unwrap();
+        let mut planner = SubsetPlanner {
+            db_type: DbType::Mysql,
+            config: SubsetConfig {
+                seeds: vec![SubsetSeed {
+                    db: "shop".into(),
+                    tb: "orders".into(),
+                    condition: "id < 10".into(),
+                }],
+                include_children: false,
+                max_depth: 10,
+            },
+            filter,
+        };
+
+        let conditions = planner.plan(&foreign_keys);
+        let key = |tb: &str| ("shop".to_string(), tb.to_string());
+        assert_eq!(conditions.len(), 2);
+        assert_eq!(conditions[&key("orders")], "id < 10");
+        assert_eq!(
+            conditions[&key("customers")],
+            "(`id`) IN (SELECT `customer_id` FROM `shop`.`orders` WHERE (id < 10) AND `customer_id` IS NOT NULL)"
+        );
+
+        // children of seeds and their parents, but not children of parents
+        planner.config.include_children = true;
+        let conditions = planner.plan(&foreign_keys);
+        assert_eq!(conditions.len(), 4);
+        assert_eq!(
+            conditions[&key("products")],
+            "(`id`) IN (SELECT `product_id` FROM `shop`.`order_items` WHERE ((`order_id`) IN (SELECT `id` FROM `shop`.`orders` WHERE (id < 10) AND `id` IS NOT NULL)) AND `product_id` IS NOT NULL)"
+        );
+        assert!(!conditions.contains_key(&key("reviews")));
+
+        planner.config.max_depth = 0;
+        assert_eq!(planner.plan(&foreign_keys).len(), 1);
+    }
+}
@@ -0,0 +1,670 @@
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+use sqlx::Row;
+
+use dt_common::{
+    config::{
+        config_enums::{DbType, ResumeType},
+        extractor_config::ExtractorConfig,
+        ini_loader::IniLoader,
+        resumer_config::ResumerConfig,
+        task_config::TaskConfig,
+    },
+    error::Error,
+    log_encoder::JsonLineEncoder,
+    log_info,
+    rdb_filter::RdbFilter,
+};
+use dt_connector::extractor::resumer::{build_recorder, utils::ResumerUtil};
+use dt_pipeline::tb_backfiller::{BackfillReport, BackfillState, TbBackfiller};
+
+use crate::{proxy_util::ProxyUtil, task_runner::TaskRunner, task_util::TaskUtil};
+
+const POSITION_LOG: &str = "position.log";
+const FINISHED_LOG: &str = "finished.log";
+const MONITOR_LOG: &str = "monitor.log";
+const CURRENT_POSITION_FLAG: &str = "| current_position |";
+const CHECKPOINT_POSITION_FLAG: &str = "| checkpoint_position |";
+// only the tail of logs is read, they are not rolled until 100mb
+const TAIL_BYTES: u64 = 1024 * 1024;
+// 2024-02-29 01:25:09.554271, lines logged in the same second are of the same round
+const LOG_TIME_SECS_LEN: usize = 19;
+
+/// Task configs changed for lifecycle commands of dt-main, based on the same config file
+/// used by run, so no other config file needs to be maintained.
+pub struct TaskLifecycle {}
+
+impl TaskLifecycle {
+    /// Resumes from positions of [resumer], or from position.log and finished.log in
+    /// [runtime] log_dir if [resumer] is not configured.
+    pub fn resume_runner(task_config_file: &str) -> anyhow::Result<TaskRunner> {
+        let mut loader = IniLoader::new(task_config_file);
+        let resume_type: ResumeType =
+            loader.get_with_default("resumer", "resume_type", ResumeType::Dummy);
+        if matches!(resume_type, ResumeType::Dummy) {
+            loader.ini.set(
+                "resumer",
+                "resume_type",
+                Some(ResumeType::FromLog.to_string()),
+            );
+        }
+        Self::build_runner(task_config_file, loader)
+    }
+
+    /// Revises the target by miss / diff logs of a check, check_log_dir is [checker]
+    /// check_log_dir or {log_dir}/check by default.
+    ///
+    /// For a check task without [sinker] url, the checked target in [checker] is revised.
+    pub fn revise_runner(
+        task_config_file: &str,
+        check_log_dir: Option<&str>,
+    ) -> anyhow::Result<TaskRunner> {
+        let mut loader = IniLoader::new(task_config_file);
+        let check_log_dir = match check_log_dir {
+            Some(dir) => dir.to_string(),
+            None => {
+                let dir: String = loader.get_optional("checker", "check_log_dir");
+                if dir.is_empty() {
+                    // log_dir of the check, with task_id if [runtime] log_dir_per_task=true
+                    let config = TaskConfig::new(task_config_file)
+                        .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
+                    format!("{}/check", config.runtime.log_dir)
+                } else {
+                    dir
+                }
+            }
+        };
+        if !Path::new(&check_log_dir).is_dir() {
+            bail!(Error::ConfigError(format!(
+                "check_log_dir: {} does not exist, run the check first",
+                check_log_dir
+            )));
+        }
+
+        let ini = &mut loader.ini;
+        if ini.get("sinker", "url").unwrap_or_default().is_empty() {
+            for key in ["db_type", "url", "username", "password"] {
+                ini.set("sinker", key, ini.get("checker", key));
+            }
+        }
+        ini.set("sinker", "sink_type", Some("write".into()));
+        ini.set("extractor", "extract_type", Some("check_log".into()));
+        ini.set("extractor", "check_log_dir", Some(check_log_dir));
+        // the revise itself is not checked
+        ini.remove_section("checker");
+        Self::build_runner(task_config_file, loader)
+    }
+
+    /// Snapshots tables into the running cdc task of the config, which holds rows of the tables
+    /// until the snapshot is finished, refer to TbBackfiller.
+    pub async fn backfill(
+        task_config_file: &str,
+        tbs: &str,
+        wait_timeout_secs: u64,
+    ) -> anyhow::Result<()> {
+        let config = TaskConfig::new(task_config_file)
+            .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
+        let control_file = config.pipeline.table_backfill_file.clone();
+        if control_file.is_empty() {
+            bail!(Error::ConfigError(
+                "config [pipeline] table_backfill_file is required by backfill".into()
+            ));
+        }
+        if !matches!(
+            config.extractor,
+            ExtractorConfig::MysqlCdc { .. } | ExtractorConfig::PgCdc { .. }
+        ) {
+            bail!(Error::ConfigError(
+                "backfill only supports mysql / pg cdc tasks".into()
+            ));
+        }
+        let tb_patterns = RdbFilter::parse_pair_tokens(tbs, &config.extractor_basic.db_type)?;
+        if tb_patterns.is_empty() {
+            bail!(Error::ConfigError("no tables to backfill".into()));
+        }
+
+        let report_file = TbBackfiller::report_file(&control_file);
+        let finished_file = TbBackfiller::finished_file(&control_file);
+        // written before the finished file is removed, so tables of a former backfill
+        // are not held again before the new control file is read
+        let report_offset = Self::file_len(&report_file);
+        fs::write(&control_file, format!("{}\n", tbs))?;
+        if Path::new(&finished_file).exists() {
+            fs::remove_file(&finished_file)?;
+        }
+        log_info!("waiting for the task to hold rows of: {}", tbs);
+        Self::wait_for_reports(
+            &report_file,
+            report_offset,
+            &tb_patterns,
+            BackfillState::Buffering,
+            wait_timeout_secs,
+        )
+        .await?;
+
+        log_info!("snapshot of: {} begins", tbs);
+        let loader = Self::backfill_loader(task_config_file, tbs, &config);
+        Self::build_runner(task_config_file, loader)?
+            .start_task(false)
+            .await?;
+
+        let report_offset = Self::file_len(&report_file);
+        fs::write(&finished_file, format!("{}\n", tbs))?;
+        Self::wait_for_reports(
+            &report_file,
+            report_offset,
+            &tb_patterns,
+            BackfillState::Merged,
+            wait_timeout_secs,
+        )
+        .await?;
+        log_info!("backfill finished: {}", tbs);
+        Ok(())
+    }
+
+    /// A snapshot task of the tables, logs are written to {log_dir}/backfill so positions of
+    /// the running task are kept.
+    fn backfill_loader(task_config_file: &str, tbs: &str, config: &TaskConfig) -> IniLoader {
+        let mut loader = IniLoader::new(task_config_file);
+        let ini = &mut loader.ini;
+        ini.set("extractor", "extract_type", Some("snapshot".into()));
+        for key in ["do_dbs", "do_ddls", "do_dcls"] {
+            ini.set("filter", key, Some(String::new()));
+        }
+        ini.set("filter", "do_tbs", Some(tbs.into()));
+        for key in ["table_backfill_file", "table_removal_file"] {
+            ini.set("pipeline", key, Some(String::new()));
+        }
+        ini.set(
+            "runtime",
+            "log_dir",
+            Some(format!("{}/backfill", config.runtime.log_dir)),
+        );
+        ini.set("runtime", "log_dir_per_task", Some("false".into()));
+        ini.remove_section("resumer");
+        ini.remove_section("checker");
+        loader
+    }
+
+    async fn wait_for_reports(
+        report_file: &str,
+        offset: u64,
+        tb_patterns: &HashSet<(String, String)>,
+        state: BackfillState,
+        wait_timeout_secs: u64,
+    ) -> anyhow::Result<()> {
+        let start_time = Instant::now();
+        loop {
+            let content = fs::read(report_file).unwrap_or_default();
+            let reported: HashSet<(String, String)> =
+                String::from_utf8_lossy(content.get(offset as usize..).unwrap_or_default())
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<BackfillReport>(line).ok())
+                    .filter(|report| report.state == state)
+                    .map(|report| (report.schema, report.tb))
+                    .collect();
+            if tb_patterns.is_subset(&reported) {
+                return Ok(());
+            }
+            if start_time.elapsed().as_secs() >= wait_timeout_secs {
+                bail!(Error::Unexpected(format!(
+                    "no {:?} reports of all tables in {} after {} secs, check the task is running",
+                    state, report_file, wait_timeout_secs
+                )));
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    fn file_len(file: &str) -> u64 {
+        fs::metadata(file).map(|m| m.len()).unwrap_or(0)
+    }
+
+    pub fn status(task_config_file: &str) -> anyhow::Result<TaskStatus> {
+        let config = TaskConfig::new(task_config_file)
+            .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
+        let log_dir = match &config.resumer {
+            ResumerConfig::FromLog { log_dir, .. } if !log_dir.is_empty() => log_dir.clone(),
+            _ => config.runtime.log_dir.clone(),
+        };
+
+        let position_lines = Self::read_tail_lines(&format!("{}/{}", log_dir, POSITION_LOG))?;
+        let last_line_with = |flag: &str| {
+            position_lines
+                .iter()
+                .rev()
+                .find(|line| line.contains(flag))
+                .cloned()
+        };
+        let finished_lines = Self::read_tail_lines(&format!("{}/{}", log_dir, FINISHED_LOG))?;
+
+        let monitor_lines = Self::read_tail_lines(&format!("{}/{}", log_dir, MONITOR_LOG))?;
+        let latest_monitor = match monitor_lines.last() {
+            Some(last) => {
+                let round = &last[..LOG_TIME_SECS_LEN.min(last.len())];
+                let start = monitor_lines
+                    .iter()
+                    .rposition(|line| !line.starts_with(round))
+                    .map_or(0, |i| i + 1);
+                monitor_lines[start..].to_vec()
+            }
+            None => Vec::new(),
+        };
+
+        Ok(TaskStatus {
+            task_id: config.global.task_id.clone(),
+            log_dir,
+            current_position: last_line_with(CURRENT_POSITION_FLAG),
+            checkpoint_position: last_line_with(CHECKPOINT_POSITION_FLAG),
+            finished_count: finished_lines.len(),
+            last_finished: finished_lines.last().cloned(),
+            latest_monitor,
+        })
+    }
+
+    /// Removes resources left by a stopped task: the pg replication slot and the publication
+    /// created for it, and positions in [resumer] / log_dir, nothing is changed if dry_run.
+    pub async fn cleanup(task_config_file: &str, dry_run: bool) -> anyhow::Result<CleanupReport> {
+        let config = TaskConfig::new(task_config_file)
+            .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
+        let mut report = CleanupReport {
+            task_id: config.global.task_id.clone(),
+            dry_run,
+            ..Default::default()
+        };
+
+        match &config.extractor {
+            ExtractorConfig::PgCdc {
+                url,
+                connection_auth,
+                slot_name,
+                pub_name,
+                ..
+            } if !slot_name.is_empty() => {
+                let conn_pool =
+                    TaskUtil::create_pg_conn_pool(url, connection_auth, 1, false, false).await?;
+                Self::cleanup_pg_slot(&conn_pool, slot_name, dry_run, &mut report).await?;
+                // same as the default of the pg cdc extractor, a configured one may be shared
+                if pub_name.is_empty() {
+                    let pub_name = format!("{}_publication_for_all_tables", slot_name);
+                    Self::cleanup_pg_publication(&conn_pool, &pub_name, dry_run, &mut report)
+                        .await?;
+                } else {
+                    report.kept.push(format!(
+                        "publication: {}, configured in [extractor]",
+                        pub_name
+                    ));
+                }
+                conn_pool.close().await;
+            }
+
+            ExtractorConfig::MysqlCdc {
+                url,
+                connection_auth,
+                ..
+            } => {
+                let conn_pool = TaskUtil::create_mysql_conn_pool(
+                    url,
+                    &DbType::Mysql,
+                    connection_auth,
+                    1,
+                    false,
+                    None,
+                )
+                .await?;
+                // binlog dumps are not bound to the task, they end once their clients disconnect
+                let sql = "SELECT ID, HOST FROM information_schema.PROCESSLIST \
+                    WHERE COMMAND LIKE 'Binlog Dump%' AND USER = SUBSTRING_INDEX(CURRENT_USER(), '@', 1)";
+                for row in sqlx::query(sql).fetch_all(&conn_pool).await? {
+                    let id: u64 = row.try_get("ID")?;
+                    let host: String = row.try_get("HOST")?;
+                    report.kept.push(format!(
+                        "binlog dump of the same user: id {}, host {}, KILL it if stale",
+                        id, host
+                    ));
+                }
+                conn_pool.close().await;
+            }
+
+            _ => {}
+        }
+
+        Self::cleanup_positions(&config, dry_run, &mut report).await?;
+        Ok(report)
+    }
+
+    async fn cleanup_pg_slot(
+        conn_pool: &sqlx::Pool<sqlx::Postgres>,
+        slot_name: &str,
+        dry_run: bool,
+        report: &mut CleanupReport,
+    ) -> anyhow::Result<()> {
+        let sql = "SELECT active, pg_wal_lsn_diff(pg_current_wal_lsn(), restart_lsn)::bigint AS retained_bytes \
+            FROM pg_catalog.pg_replication_slots WHERE slot_name = $1";
+        let Some(row) = sqlx::query(sql)
+            .bind(slot_name)
+            .fetch_optional(conn_pool)
+            .await?
+        else {
+            return Ok(());
+        };
+        let active: bool = row.try_get("active")?;
+        if active {
+            bail!(Error::Unexpected(format!(
+                "replication slot: {} is active, stop the task first",
+                slot_name
+            )));
+        }
+        let retained_bytes: Option<i64> = row.try_get("retained_bytes")?;
+        if !dry_run {
+            sqlx::query("SELECT pg_drop_replication_slot($1)")
+                .bind(slot_name)
+                .execute(conn_pool)
+                .await?;
+        }
+        report.removed.push(format!(
+            "replication slot: {}, retained wal bytes: {}",
+            slot_name,
+            retained_bytes.unwrap_or_default()
+        ));
+        Ok(())
+    }
+
+    async fn cleanup_pg_publication(
+        conn_pool: &sqlx::Pool<sqlx::Postgres>,
+        pub_name: &str,
+        dry_run: bool,
+        report: &mut CleanupReport,
+    ) -> anyhow::Result<()> {
+        let exists = sqlx::query("SELECT 1 FROM pg_catalog.pg_publication WHERE pubname = $1")
+            .bind(pub_name)
+            .fetch_optional(conn_pool)
+            .await?
+            .is_some();
+        if !exists {
+            return Ok(());
+        }
+        if !dry_run {
+            // unquoted, the same as it is created
+            sqlx::query(&format!("DROP PUBLICATION {}", pub_name))
+                .execute(conn_pool)
+                .await?;
+        }
+        report.removed.push(format!("publication: {}", pub_name));
+        Ok(())
+    }
+
+    async fn cleanup_positions(
+        config: &TaskConfig,
+        dry_run: bool,
+        report: &mut CleanupReport,
+    ) -> anyhow::Result<()> {
+        if let ResumerConfig::FromDB {
+            url,
+            connection_auth,
+            db_type,
+            table_full_name,
+            is_direct_connection,
+            ..
+        } = &config.resumer
+        {
+            if !dry_run {
+                let pool = ResumerUtil::create_pool(
+                    url,
+                    connection_auth,
+                    db_type,
+                    1,
+                    *is_direct_connection,
+                )
+                .await?;
+                // positions of the task are deleted when the recorder is initialized
+                build_recorder(&config.global.task_id, &config.resumer, Some(pool), true).await?;
+            }
+            report.removed.push(format!(
+                "positions of task_id: {} in {}",
+                config.global.task_id, table_full_name
+            ));
+        }
+
+        let mut log_dirs = vec![config.runtime.log_dir.clone()];
+        if let ResumerConfig::FromLog { log_dir, .. } = &config.resumer {
+            if !log_dir.is_empty() && !log_dirs.contains(log_dir) {
+                log_dirs.push(log_dir.clone());
+            }
+        }
+        let mut files: Vec<String> = log_dirs
+            .iter()
+            .flat_map(|dir| [POSITION_LOG, FINISHED_LOG].map(|name| format!("{}/{}", dir, name)))
+            .collect();
+        if !config.pipeline.snapshot_manifest_file.is_empty() {
+            files.push(config.pipeline.snapshot_manifest_file.clone());
+        }
+        for file in files {
+            if !Path::new(&file).is_file() {
+                continue;
+            }
+            let len = Self::file_len(&file);
+            if !dry_run {
+                fs::remove_file(&file).with_context(|| format!("failed to remove: {}", file))?;
+            }
+            report.removed.push(format!("{}, {} bytes", file, len));
+        }
+        Ok(())
+    }
+
+    fn build_runner(task_config_file: &str, loader: IniLoader) -> anyhow::Result<TaskRunner> {
+        let config = ProxyUtil::load_task_config(loader)
+            .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
+        TaskRunner::from_config(config)
+    }
+
+    fn read_tail_lines(file: &str) -> anyhow::Result<Vec<String>> {
+        if !Path::new(file).exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut f = File::open(file).with_context(|| format!("failed to open: {}", file))?;
+        let len = f.metadata()?.len();
+        let start = len.saturating_sub(TAIL_BYTES);
+        f.seek(SeekFrom::Start(start))?;
+        let mut buf = Vec::new();
+        f.read_to_end(&mut buf)?;
+
+        let content = String::from_utf8_lossy(&buf);
+        let mut lines: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        // the first line may be cut
+        if start > 0 && !lines.is_empty() {
+            lines.remove(0);
+        }
+        // lines of [runtime] log_format=json
+        Ok(lines
+            .into_iter()
+            .map(JsonLineEncoder::to_text_line)
+            .collect())
+    }
+}
+
+pub struct TaskStatus {
+    pub task_id: String,
+    pub log_dir: String,
+    pub current_position: Option<String>,
+    pub checkpoint_position: Option<String>,
+    // only counted in the tail of finished.log
+    pub finished_count: usize,
+    pub last_finished: Option<String>,
+    pub latest_monitor: Vec<String>,
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_none = |line: &Option<String>| line.clone().unwrap_or_else(|| "none".into());
+        writeln!(f, "task_id: {}", self.task_id)?;
+        writeln!(f, "log_dir: {}", self.log_dir)?;
+        writeln!(f, "current_position: {}", or_none(&self.current_position))?;
+        writeln!(
+            f,
+            "checkpoint_position: {}",
+            or_none(&self.checkpoint_position)
+        )?;
+        writeln!(f, "finished: {}", self.finished_count)?;
+        writeln!(f, "last_finished: {}", or_none(&self.last_finished))?;
+        writeln!(f, "monitor:")?;
+        for line in self.latest_monitor.iter() {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct CleanupReport {
+    pub task_id: String,
+    pub dry_run: bool,
+    pub removed: Vec<String>,
+    // found but not removed, they may be used by others
+    pub kept: Vec<String>,
+}
+
+impl fmt::Display for CleanupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "task_id: {}", self.task_id)?;
+        if self.dry_run {
+            writeln!(f, "to remove (dry run):")?;
+        } else {
+            writeln!(f, "removed:")?;
+        }
+        for line in self.removed.iter() {
+            writeln!(f, "  {}", line)?;
+        }
+        writeln!(f, "kept:")?;
+        for line in self.kept.iter() {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let dir = std::env::temp_dir().join(format!("ape_dts_status_{}", std::process::id()));
+        let log_dir = dir.join("logs");
+        fs::create_dir_all(&log_dir).unwrap();
+        let write = |name: &str, content: &str| fs::write(log_dir.join(name), content).unwrap();
+        write(
+            POSITION_LOG,
+            r#"2024-10-18 05:22:40.100000 | current_position | {"type":"PgCdc","lsn":"0/1"}
+2024-10-18 05:22:40.200000 | checkpoint_position | {"type":"PgCdc","lsn":"0/1"}
+2024-10-18 05:22:41.100000 | current_position | {"type":"PgCdc","lsn":"0/2"}
+"#,
+        );
+        write(
+            MONITOR_LOG,
+            r#"2024-02-29 01:25:08.554271 | extractor | record_count | sum=1
+2024-02-29 01:25:09.554271 | extractor | record_count | sum=13
+2024-02-29 01:25:09.554311 | extractor | data_bytes | sum=586
+"#,
+        );
+
+        let config_file = dir.join("task_config.ini");
+        fs::write(
+            &config_file,
+            format!(
+                "[extractor]\ndb_type=pg\nextract_type=cdc\nurl=postgres://127.0.0.1:5432\nslot_name=s1\n\
+                 [sinker]\ndb_type=pg\nsink_type=dummy\n\
+                 [parallelizer]\nparallel_type=serial\n\
+                 [runtime]\nlog_dir={}\n",
+                log_dir.display()
+            ),
+        )
+        .unwrap();
+
+        let status = TaskLifecycle::status(config_file.to_str().unwrap()).unwrap();
+        assert!(status.current_position.unwrap().contains("0/2"));
+        assert!(status.checkpoint_position.unwrap().contains("0/1"));
+        assert_eq!(status.finished_count, 0);
+        assert_eq!(status.latest_monitor.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_positions() {
+        let dir = std::env::temp_dir().join(format!("ape_dts_cleanup_{}", std::process::id()));
+        let log_dir = dir.join("logs");
+        fs::create_dir_all(&log_dir).unwrap();
+        fs::write(log_dir.join(POSITION_LOG), "position").unwrap();
+        fs::write(log_dir.join(FINISHED_LOG), "finished").unwrap();
+        fs::write(log_dir.join(MONITOR_LOG), "monitor").unwrap();
+
+        let config_file = dir.join("task_config.ini");
+        fs::write(
+            &config_file,
+            format!(
+                "[extractor]\ndb_type=mysql\nextract_type=snapshot\nurl=mysql://127.0.0.1:3307\n\
+                 [sinker]\ndb_type=mysql\nsink_type=dummy\n\
+                 [parallelizer]\nparallel_type=serial\n\
+                 [runtime]\nlog_dir={}\n",
+                log_dir.display()
+            ),
+        )
+        .unwrap();
+        let config_file = config_file.to_str().unwrap();
+
+        let report = TaskLifecycle::cleanup(config_file, true).await.unwrap();
+        assert_eq!(report.removed.len(), 2);
+        assert!(log_dir.join(POSITION_LOG).exists());
+
+        let report = TaskLifecycle::cleanup(config_file, false).await.unwrap();
+        assert_eq!(report.removed.len(), 2);
+        assert!(!log_dir.join(POSITION_LOG).exists());
+        assert!(!log_dir.join(FINISHED_LOG).exists());
+        assert!(log_dir.join(MONITOR_LOG).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backfill_loader() {
+        let dir = std::env::temp_dir().join(format!("ape_dts_backfill_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_file = dir.join("task_config.ini");
+        fs::write(
+            &config_file,
+            "[extractor]\ndb_type=mysql\nextract_type=cdc\nurl=mysql://127.0.0.1:3307\nserver_id=2000\n\
+             [filter]\ndo_dbs=db_1\ndo_ddls=*\n\
+             [sinker]\ndb_type=mysql\nsink_type=write\nurl=mysql://127.0.0.1:3308\n\
+             [parallelizer]\nparallel_type=rdb_merge\n\
+             [pipeline]\ntable_backfill_file=./backfill_tbs\n\
+             [runtime]\nlog_dir=./logs\n",
+        )
+        .unwrap();
+        let config_file = config_file.to_str().unwrap();
+
+        let config = TaskConfig::new(config_file).unwrap();
+        let loader = TaskLifecycle::backfill_loader(config_file, "db_2.tb_1", &config);
+        let backfill_config = TaskConfig::from_loader(loader).unwrap();
+        assert!(matches!(
+            backfill_config.extractor,
+            ExtractorConfig::MysqlSnapshot { .. }
+        ));
+        assert_eq!(backfill_config.filter.do_schemas, "");
+        assert_eq!(backfill_config.filter.do_tbs, "db_2.tb_1");
+        assert_eq!(backfill_config.filter.do_ddls, "");
+        assert!(backfill_config.pipeline.table_backfill_file.is_empty());
+        assert_eq!(backfill_config.runtime.log_dir, "./logs/backfill");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
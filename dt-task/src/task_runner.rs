@@ -6,12 +6,14 @@ use std::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use anyhow::{bail, Context};
 use chrono::Local;
 use log4rs::config::{Config, Deserializers, RawConfig};
 use opendal::Operator;
+use reqwest::{redirect::Policy, Url};
 use tokio::{
     fs::{self as tokio_fs, metadata, File},
     io::AsyncReadExt,
@@ -21,20 +23,33 @@ use tokio::{
 };
 
 use super::{
-    extractor_util::ExtractorUtil, parallelizer_util::ParallelizerUtil, sinker_util::SinkerUtil,
+    extractor_util::ExtractorUtil, janitor::Janitor, parallelizer_util::ParallelizerUtil,
+    proxy_util::ProxyUtil, sinker_util::SinkerUtil, struct_syncer::StructSyncer,
+    subset_planner::SubsetPlanner,
+};
+use crate::distributed::{
+    coordinator::{Assignments, Coordinator},
+    worker::Worker,
+    TbId,
 };
 use crate::task_util::{ConnClient, TaskUtil};
 use async_mutex::Mutex as AsyncMutex;
 use std::sync::Mutex as StdMutex;
 
 static LOG_HANDLE: StdMutex<Option<log4rs::Handle>> = StdMutex::new(None);
+use dt_common::log_encoder::JsonLineEncoderDeserializer;
 use dt_common::log_filter::{parse_size_limit, SizeLimitFilterDeserializer};
 use dt_common::{
     config::{
         checker_config::CheckerConfig,
-        config_enums::{DbType, ExtractType, PipelineType, SinkType, TaskKind, TaskType},
-        config_token_parser::{ConfigTokenParser, TokenEscapePair},
+        config_enums::{
+            DbType, ExtractType, LogFormat, PipelineType, SinkType, SqlHookStage, TaskKind,
+            TaskType,
+        },
+        connection_auth_config::ConnectionAuthConfig,
+        distributed_config::DistributedConfig,
         extractor_config::ExtractorConfig,
+        ini_loader::IniLoader,
         limiter_config::CapacityLimiterConfig,
         sinker_config::SinkerConfig,
         task_config::{TaskConfig, DEFAULT_CHECK_LOG_FILE_SIZE},
@@ -44,6 +59,7 @@ use dt_common::{
     log_error, log_finished, log_info, log_warn,
     meta::{dt_queue::DtQueue, position::Position, row_type::RowType, syncer::Syncer},
     monitor::{
+        latency_tracer::LatencyTracer,
         task_metrics::TaskMetricsType,
         task_monitor::{MonitorType, TaskMonitor},
         task_monitor_handle::TaskMonitorHandle,
@@ -54,18 +70,29 @@ use dt_common::{
 };
 use dt_connector::{
     checker::base_checker::CheckContext,
-    checker::check_log::{to_json_line, CheckSummaryLog},
+    checker::check_log::{to_json_line, CheckSummaryLog, ReviewRoundLog},
+    checker::compressed_log,
     checker::{
-        Checker, CheckerHandle, CheckerStateStore, DataCheckerHandle, MongoChecker, MysqlChecker,
-        PgChecker, StructCheckerHandle,
+        Checker, CheckerHandle, CheckerStateStore, ClickhouseChecker, DataCheckerHandle,
+        MongoChecker, MysqlChecker, PgChecker, StructCheckerHandle,
     },
     data_marker::DataMarker,
-    extractor::resumer::{recorder::Recorder, recovery::Recovery},
+    extractor::resumer::{
+        recorder::Recorder, recovery::Recovery, snapshot_manifest::SnapshotManifest,
+    },
     rdb_router::RdbRouter,
-    sinker::base_sinker::BaseSinker,
+    sinker::{
+        base_sinker::BaseSinker,
+        circuit_breaker::{CircuitBreaker, SharedCircuitBreaker, TargetProbe},
+    },
     Extractor, Sinker,
 };
-use dt_pipeline::{base_pipeline::BasePipeline, lua_processor::LuaProcessor, Pipeline};
+use dt_pipeline::{
+    base_pipeline::BasePipeline, idle_detector::IdleDetector, lua_processor::LuaProcessor,
+    mongo_projector::MongoProjector, redis_mapper::RedisMapper, soft_deleter::SoftDeleter,
+    tb_backfiller::TbBackfiller, tb_remover::TbRemover, text_cleaner::TextCleaner,
+    trx_spiller::TrxSpiller, Pipeline,
+};
 
 #[cfg(feature = "metrics")]
 use dt_common::monitor::prometheus_metrics::PrometheusMetrics;
@@ -95,6 +122,18 @@ const RUNTIME_STDOUT_APPENDER_PLACEHOLDER: &str = "RUNTIME_STDOUT_APPENDER_PLACE
 const CHECK_RESULT_STDOUT_APPENDER_PLACEHOLDER: &str = "CHECK_RESULT_STDOUT_APPENDER_PLACEHOLDER";
 const DEFAULT_CHECK_LOG_DIR_PLACEHOLDER: &str = "LOG_DIR_PLACEHOLDER/check";
 const DEFAULT_STATISTIC_LOG_DIR_PLACEHOLDER: &str = "LOG_DIR_PLACEHOLDER/statistic";
+// appenders of log_info / log_error / log_position / log_monitor etc. encoded as json lines by
+// [runtime] log_format=json, check results and statistics keep their own formats
+const JSON_LINE_APPENDERS: [&str; 7] = [
+    "stdout",
+    "default_appender",
+    "commit_appender",
+    "position_appender",
+    "monitor_appender",
+    "finished_appender",
+    "http_appender",
+];
+const MYSQL_REPEATABLE_READ_SQL: &str = "SET SESSION TRANSACTION ISOLATION LEVEL REPEATABLE READ";
 
 fn init_task_check_summary() -> CheckSummaryLog {
     CheckSummaryLog {
@@ -121,8 +160,12 @@ impl SingleTaskWorker {
 
 impl TaskRunner {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
-        let config = TaskConfig::new(task_config_file)
+        let config = ProxyUtil::load_task_config(IniLoader::new(task_config_file))
             .with_context(|| format!("invalid configs in [{}]", task_config_file))?;
+        Self::from_config(config)
+    }
+
+    pub fn from_config(config: TaskConfig) -> anyhow::Result<Self> {
         let task_type = config.task_type();
         #[cfg(not(feature = "metrics"))]
         let task_monitor = Arc::new(TaskMonitor::new(task_type));
@@ -159,6 +202,167 @@ impl TaskRunner {
             log_error!("panic: {}\nbacktrace:\n{}", panic_info, backtrace);
         }));
 
+        let janitor = self.config.janitor.clone().map(|config| {
+            tokio::spawn(
+                Janitor {
+                    config,
+                    task_monitor: self.task_monitor.clone(),
+                }
+                .run(),
+            )
+        });
+        let struct_syncer = StructSyncer::from_config(&self.config)
+            .await?
+            .map(|struct_syncer| tokio::spawn(struct_syncer.run()));
+        let result = self.run_task_by_role(is_init).await;
+        if let Some(janitor) = janitor {
+            janitor.abort();
+        }
+        if let Some(struct_syncer) = struct_syncer {
+            struct_syncer.abort();
+        }
+        result
+    }
+
+    async fn run_task_by_role(&self, is_init: bool) -> anyhow::Result<()> {
+        match &self.config.distributed {
+            Some(DistributedConfig::Coordinator {
+                http_host,
+                http_port,
+                heartbeat_timeout_secs,
+                assign_batch_size,
+            }) => {
+                let tbs = self.list_distributed_tbs().await?;
+                log_info!("distributed coordinator: {} table(s) to assign", tbs.len());
+                let coordinator = Coordinator {
+                    http_host: http_host.clone(),
+                    http_port: *http_port,
+                    heartbeat_timeout_secs: *heartbeat_timeout_secs,
+                };
+                coordinator
+                    .run(Assignments::new(tbs, *assign_batch_size))
+                    .await?;
+                log_finished!("task finished");
+                log::logger().flush();
+                Ok(())
+            }
+            Some(DistributedConfig::Worker {
+                coordinator_url,
+                worker_id,
+                heartbeat_interval_secs,
+            }) => {
+                let worker = Worker::new(coordinator_url, worker_id, *heartbeat_interval_secs);
+                let mut finished_tbs = Vec::new();
+                loop {
+                    let response = worker.next_tbs(&finished_tbs).await;
+                    if response.all_finished {
+                        break;
+                    }
+                    log_info!(
+                        "distributed worker: {} assigned tables: {:?}",
+                        worker_id,
+                        response.assigned_tbs
+                    );
+                    let keep_alive =
+                        tokio::spawn(worker.clone().keep_alive(response.assigned_tbs.clone()));
+                    let result = self
+                        .with_assigned_tbs(&response.assigned_tbs)?
+                        .run_task(is_init)
+                        .await;
+                    keep_alive.abort();
+                    result?;
+                    finished_tbs = response.assigned_tbs;
+                }
+                Ok(())
+            }
+            None if self.config.subset.is_some() => {
+                self.with_subset().await?.run_task(is_init).await
+            }
+            None => match self.config.checker.as_ref() {
+                Some(cfg) if cfg.review_rounds > 1 => self.run_review_rounds(is_init, cfg).await,
+                _ => self.run_task(is_init).await,
+            },
+        }
+    }
+
+    /// Re-checks the miss / diff logs of each round in the next one, until nothing is
+    /// inconsistent or [checker].review_rounds is reached. Logs of round n are written to
+    /// <check_log_dir>/round_<n>, and the counts of all rounds to <check_log_dir>/convergence.log.
+    async fn run_review_rounds(&self, is_init: bool, cfg: &CheckerConfig) -> anyhow::Result<()> {
+        let mut input_dir = match &self.config.extractor {
+            ExtractorConfig::MysqlCheck { check_log_dir, .. }
+            | ExtractorConfig::PgCheck { check_log_dir, .. }
+            | ExtractorConfig::MongoCheck { check_log_dir, .. } => check_log_dir.clone(),
+            _ => bail!(Error::ConfigError(
+                "config [checker].review_rounds requires [extractor] extract_type=check_log".into()
+            )),
+        };
+        let check_log_dir = self.check_log_dir(cfg);
+        let convergence_log = format!("{check_log_dir}/convergence.log");
+        tokio_fs::create_dir_all(&check_log_dir).await?;
+        Self::remove_file_if_exists(&convergence_log).await?;
+
+        let mut round_logs = String::new();
+        for round in 1..=cfg.review_rounds {
+            if round > 1 {
+                // give revise / replication time to catch up before the next round
+                tokio::time::sleep(Duration::from_secs(cfg.review_interval_secs)).await;
+            }
+
+            let round_dir = format!("{check_log_dir}/round_{round}");
+            let runner = self.with_review_round(&input_dir, &round_dir);
+            runner.clear_check_logs().await?;
+            runner.init_log4rs().await?;
+            runner.run_task(is_init).await?;
+
+            let summary_log = tokio_fs::read_to_string(format!("{round_dir}/summary.log")).await?;
+            let summary: CheckSummaryLog = serde_json::from_str(
+                summary_log
+                    .lines()
+                    .rfind(|line| !line.trim().is_empty())
+                    .context("review round wrote no summary")?,
+            )?;
+            let round_log = ReviewRoundLog::new(round, &round_dir, &summary);
+            log_info!(
+                "review round {}: checked: {}, resolved: {}, miss: {}, diff: {}",
+                round,
+                round_log.checked_count,
+                round_log.resolved_count,
+                round_log.miss_count,
+                round_log.diff_count
+            );
+            if let Some(line) = to_json_line(&round_log) {
+                round_logs.push_str(&line);
+                round_logs.push('\n');
+                tokio_fs::write(&convergence_log, &round_logs).await?;
+            }
+
+            if round_log.is_converged() {
+                break;
+            }
+            input_dir = round_dir;
+        }
+        Ok(())
+    }
+
+    fn with_review_round(&self, input_dir: &str, output_dir: &str) -> Self {
+        let mut config = self.config.clone();
+        if let ExtractorConfig::MysqlCheck { check_log_dir, .. }
+        | ExtractorConfig::PgCheck { check_log_dir, .. }
+        | ExtractorConfig::MongoCheck { check_log_dir, .. } = &mut config.extractor
+        {
+            *check_log_dir = input_dir.to_string();
+        }
+        if let Some(cfg) = config.checker.as_mut() {
+            cfg.check_log_dir = output_dir.to_string();
+        }
+        Self {
+            config,
+            ..self.clone()
+        }
+    }
+
+    async fn run_task(&self, is_init: bool) -> anyhow::Result<()> {
         log_info!(
             "start task: [taskID: {}, taskType: {:?}]",
             &self.config.global.task_id,
@@ -203,8 +407,13 @@ impl TaskRunner {
             .start_metrics()
             .await;
 
+        let snapshot_manifest = self.load_snapshot_manifest().await?;
         let task_info = self
-            .get_task_info(extractor_client.clone(), recovery.clone())
+            .get_task_info(
+                extractor_client.clone(),
+                recovery.clone(),
+                snapshot_manifest.as_ref(),
+            )
             .await?;
         let should_skip_task = self
             .task_type
@@ -212,6 +421,11 @@ impl TaskRunner {
             .is_some_and(|task_type| matches!(task_type.kind, TaskKind::Snapshot))
             && task_info.no_snapshot_data;
         if !should_skip_task {
+            // task hooks run on the default target only for sharded targets
+            let sql_hook = SinkerUtil::create_sql_hook(&self.config, &sinker_client)?;
+            if let Some(sql_hook) = &sql_hook {
+                sql_hook.run_task_hooks(&SqlHookStage::BeforeTask).await?;
+            }
             self.clone()
                 .create_task(
                     task_info.extractor_config,
@@ -222,8 +436,32 @@ impl TaskRunner {
                     recovery,
                     check_summary.clone(),
                     checker_state_store.clone(),
+                    snapshot_manifest,
                 )
                 .await?;
+            if let Some(sql_hook) = &sql_hook {
+                sql_hook.run_task_hooks(&SqlHookStage::AfterTask).await?;
+            }
+        }
+
+        if let Some(heartbeat_tb) = self.config.extractor.heartbeat_tb(db_type) {
+            if heartbeat_tb.cleanup {
+                if let Err(err) = TaskUtil::drop_tb(
+                    &extractor_client,
+                    &heartbeat_tb.schema,
+                    &heartbeat_tb.tb,
+                    db_type,
+                )
+                .await
+                {
+                    log_warn!(
+                        "failed to drop heartbeat tb: {}.{}, error: {}",
+                        heartbeat_tb.schema,
+                        heartbeat_tb.tb,
+                        err
+                    );
+                }
+            }
         }
 
         // close connections
@@ -250,6 +488,7 @@ impl TaskRunner {
 
         log::logger().flush();
         self.remove_empty_check_logs().await?;
+        self.compress_check_logs().await?;
         self.upload_check_logs_to_s3().await?;
         log_finished!("task finished");
         log::logger().flush();
@@ -269,7 +508,15 @@ impl TaskRunner {
         }
 
         tokio_fs::create_dir_all(&check_log_dir).await?;
-        for file_name in ["miss.log", "diff.log", "summary.log", "sql.log"] {
+        for file_name in [
+            "miss.log",
+            "diff.log",
+            "summary.log",
+            "sql.log",
+            "report.log",
+            "miss.log.zst",
+            "diff.log.zst",
+        ] {
             Self::remove_file_if_exists(&format!("{check_log_dir}/{file_name}")).await?;
         }
         Ok(())
@@ -319,12 +566,35 @@ impl TaskRunner {
         };
 
         let check_log_dir = self.check_log_dir(cfg);
-        for file_name in ["miss.log", "diff.log", "sql.log"] {
+        for file_name in ["miss.log", "diff.log", "sql.log", "report.log"] {
             Self::remove_file_if_empty(&format!("{check_log_dir}/{file_name}")).await?;
         }
         Ok(())
     }
 
+    async fn compress_check_logs(&self) -> anyhow::Result<()> {
+        let Some(cfg) = self.config.checker.as_ref() else {
+            return Ok(());
+        };
+        // cdc inline checks write compressed snapshots by themselves
+        if !cfg.check_log_compress || !Self::should_clear_check_logs_before_log4rs(self.task_type) {
+            return Ok(());
+        }
+
+        let check_log_dir = self.check_log_dir(cfg);
+        for file_name in ["miss.log", "diff.log"] {
+            let src = Path::new(&check_log_dir).join(file_name);
+            if !src.exists() {
+                continue;
+            }
+            let dst =
+                Path::new(&check_log_dir).join(compressed_log::compressed_log_name(file_name));
+            tokio::task::spawn_blocking(move || compressed_log::compress_file(&src, &dst))
+                .await??;
+        }
+        Ok(())
+    }
+
     async fn upload_check_logs_to_s3(&self) -> anyhow::Result<()> {
         let Some(cfg) = self.config.checker.as_ref() else {
             return Ok(());
@@ -346,7 +616,7 @@ impl TaskRunner {
         key_prefix: &str,
         check_log_dir: &str,
     ) -> anyhow::Result<()> {
-        for file_name in ["miss.log", "diff.log"] {
+        for file_name in ["miss.log", "diff.log", "miss.log.zst", "diff.log.zst"] {
             let key = format!("{key_prefix}/{file_name}");
             let path = format!("{check_log_dir}/{file_name}");
             Self::upload_optional_check_log(s3_client, &key, &path).await?;
@@ -463,12 +733,14 @@ impl TaskRunner {
         recovery: Option<Arc<dyn Recovery + Send + Sync>>,
         check_summary: Option<Arc<AsyncMutex<CheckSummaryLog>>>,
         checker_state_store: Option<Arc<CheckerStateStore>>,
+        snapshot_manifest: Option<Arc<SnapshotManifest>>,
     ) -> anyhow::Result<()> {
         // DtQueue is already bounded by buffer_size. Keep only byte capacity in
         // the enqueue limiter to avoid a duplicate records semaphore.
         let enqueue_capacity_limiter = CapacityLimiterConfig {
             buffer_size: 0,
             buffer_memory_mb: self.config.pipeline.capacity_limiter.buffer_memory_mb,
+            ..Default::default()
         };
         let enqueue_limiter = BufferLimiter::from_config(
             Some(&self.config.extractor_basic.rate_limiter),
@@ -478,19 +750,36 @@ impl TaskRunner {
         let dequeue_limiter =
             BufferLimiter::from_config(Some(&self.config.sinker_basic.rate_limiter), None)
                 .map(Arc::new);
-        let max_bytes = self.config.pipeline.capacity_limiter.buffer_memory_mb * 1024 * 1024;
-        let buffer = Arc::new(DtQueue::new(
-            self.config.pipeline.capacity_limiter.buffer_size,
-            max_bytes as u64,
-            enqueue_limiter,
-            dequeue_limiter,
-        ));
+        let capacity_limiter = &self.config.pipeline.capacity_limiter;
+        let max_bytes = capacity_limiter.buffer_memory_mb * 1024 * 1024;
+        let control_max_bytes = capacity_limiter.control_buffer_memory_mb * 1024 * 1024;
+        let buffer = Arc::new(
+            DtQueue::new(
+                capacity_limiter.buffer_size,
+                max_bytes as u64,
+                enqueue_limiter,
+                dequeue_limiter,
+            )
+            .with_control_capacity(
+                capacity_limiter.control_buffer_size,
+                control_max_bytes as u64,
+            )
+            .with_memory_budget((capacity_limiter.memory_budget_mb * 1024 * 1024) as u64)
+            .with_tracer(
+                LatencyTracer::new(
+                    self.config.pipeline.trace_sample_rate,
+                    self.config.pipeline.trace_report_interval_secs,
+                )
+                .map(Arc::new),
+            ),
+        );
 
         let shut_down = Arc::new(AtomicBool::new(false));
         let syncer = Arc::new(Mutex::new(Syncer {
             received_position: Position::None,
             committed_position: Position::None,
             committed_positions: HashMap::new(),
+            ..Default::default()
         }));
 
         let (extractor_data_marker, sinker_data_marker) = if let Some(data_marker_config) =
@@ -541,6 +830,7 @@ impl TaskRunner {
             extractor_data_marker,
             (*router).clone(),
             recovery.clone(),
+            self.filter.clone(),
         )
         .await?;
         let extractor = Arc::new(Mutex::new(extractor));
@@ -575,6 +865,16 @@ impl TaskRunner {
             monitor_count_window,
         );
         let sinker_monitor = sinker_monitor_handle.build_monitor("sinker", &task_id);
+        let pipeline_monitor_handle = TaskMonitorHandle::new(
+            self.task_monitor.clone(),
+            MonitorType::Pipeline,
+            task_id.clone(),
+            monitor_time_window_secs,
+            monitor_max_sub_count,
+            monitor_count_window,
+        );
+        let circuit_breaker =
+            self.create_circuit_breaker(&sinker_client, pipeline_monitor_handle.clone());
         let sinkers = SinkerUtil::create_sinkers(
             &self.config,
             sinker_client.clone(),
@@ -584,27 +884,22 @@ impl TaskRunner {
                 CheckerHandle::Data(handle) => Some(handle.clone()),
                 CheckerHandle::Struct(_) => None,
             }),
+            circuit_breaker.clone(),
         )
         .await?;
 
-        let pipeline_monitor_handle = TaskMonitorHandle::new(
-            self.task_monitor.clone(),
-            MonitorType::Pipeline,
-            task_id.clone(),
-            monitor_time_window_secs,
-            monitor_max_sub_count,
-            monitor_count_window,
-        );
         let pipeline = self
             .create_pipeline(
                 buffer,
                 shut_down.clone(),
-                syncer,
+                syncer.clone(),
                 sinkers,
                 pipeline_monitor_handle.clone(),
                 rw_sinker_data_marker.clone(),
                 recorder.clone(),
                 checker,
+                snapshot_manifest,
+                circuit_breaker,
             )
             .await?;
         let pipeline = Arc::new(Mutex::new(pipeline));
@@ -629,13 +924,20 @@ impl TaskRunner {
         .await?;
 
         let interval_secs = self.config.pipeline.checkpoint_interval_secs;
+        let idle_interval_secs = self.config.pipeline.idle_checkpoint_interval_secs;
         let task_flush_monitors: Vec<Arc<dyn FlushableMonitor + Send + Sync>> =
             vec![self.task_monitor.clone()];
         let monitor_shut_down = Arc::new(AtomicBool::new(false));
         let monitor_task_shutdown = monitor_shut_down.clone();
         let monitor_task = tokio::spawn(async move {
-            TaskUtil::flush_monitors(interval_secs, monitor_task_shutdown, &task_flush_monitors)
-                .await;
+            TaskUtil::flush_monitors(
+                interval_secs,
+                idle_interval_secs,
+                syncer,
+                monitor_task_shutdown,
+                &task_flush_monitors,
+            )
+            .await;
             Ok(())
         });
 
@@ -814,20 +1116,84 @@ impl TaskRunner {
         data_marker: Option<Arc<RwLock<DataMarker>>>,
         recorder: Option<Arc<dyn Recorder + Send + Sync>>,
         checker: Option<CheckerHandle>,
+        snapshot_manifest: Option<Arc<SnapshotManifest>>,
+        circuit_breaker: Option<SharedCircuitBreaker>,
     ) -> anyhow::Result<Box<dyn Pipeline + Send>> {
         match self.config.pipeline.pipeline_type {
             PipelineType::Basic => {
-                let lua_processor =
-                    self.config
-                        .processor
-                        .as_ref()
-                        .map(|processor_config| LuaProcessor {
-                            lua_code: processor_config.lua_code.clone(),
-                        });
+                let processor_config = self.config.processor.as_ref();
+                // an empty script is a no-op, skip it so row values and positions are kept as they are
+                let lua_processor = processor_config
+                    .filter(|processor_config| !processor_config.lua_code.is_empty())
+                    .map(|processor_config| LuaProcessor {
+                        lua_code: processor_config.lua_code.clone(),
+                    });
+                let text_cleaner = processor_config
+                    .filter(|processor_config| processor_config.text_cleanup)
+                    .map(|_| TextCleaner::default());
+                let mongo_projector = processor_config
+                    .filter(|processor_config| !processor_config.mongo_projections.is_empty())
+                    .map(|processor_config| {
+                        MongoProjector::new(&processor_config.mongo_projections)
+                    });
+                let soft_deleter = processor_config
+                    .filter(|processor_config| !processor_config.soft_deletes.is_empty())
+                    .map(|processor_config| SoftDeleter::new(&processor_config.soft_deletes));
+                let redis_mapper = processor_config
+                    .filter(|processor_config| !processor_config.redis_mappings.is_empty())
+                    .map(|processor_config| {
+                        RedisMapper::new(
+                            &processor_config.redis_mappings,
+                            self.config.extractor_basic.url.clone(),
+                            self.config.extractor_basic.connection_auth.clone(),
+                        )
+                    })
+                    .transpose()?;
 
                 let parallelizer =
                     ParallelizerUtil::create_parallelizer(&self.config, monitor.clone()).await?;
 
+                let table_removal_file = &self.config.pipeline.table_removal_file;
+                let tb_remover = (!table_removal_file.is_empty())
+                    .then(|| TbRemover::new(table_removal_file.clone(), self.filter.clone()));
+                let table_backfill_file = &self.config.pipeline.table_backfill_file;
+                let tb_backfiller = if table_backfill_file.is_empty() {
+                    None
+                } else {
+                    let router = RdbRouter::from_config(
+                        &self.config.router,
+                        &self.config.extractor_basic.db_type,
+                    )?;
+                    Some(
+                        TbBackfiller::new(table_backfill_file.clone(), self.filter.clone(), router)
+                            .with_max_held_mb(self.config.pipeline.table_backfill_max_held_mb),
+                    )
+                };
+
+                let pipeline_config = &self.config.pipeline;
+                let idle_detector = (pipeline_config.idle_threshold_secs > 0).then(|| {
+                    IdleDetector::new(
+                        pipeline_config.idle_threshold_secs,
+                        pipeline_config.idle_checkpoint_interval_secs,
+                        syncer.clone(),
+                    )
+                });
+
+                let trx_spiller = if pipeline_config.transactional {
+                    let spill_dir = if pipeline_config.trx_spill_dir.is_empty() {
+                        format!("{}/trx_spill", self.config.runtime.log_dir)
+                    } else {
+                        pipeline_config.trx_spill_dir.clone()
+                    };
+                    Some(TrxSpiller::new(
+                        &spill_dir,
+                        pipeline_config.trx_max_held_mb,
+                    )?)
+                } else {
+                    None
+                };
+
+                let latency_tracer = buffer.tracer();
                 let pipeline = BasePipeline {
                     buffer,
                     parallelizer,
@@ -841,14 +1207,50 @@ impl TaskRunner {
                     pending_snapshot_finished: HashMap::new(),
                     data_marker,
                     lua_processor,
+                    text_cleaner,
+                    mongo_projector,
+                    soft_deleter,
+                    redis_mapper,
                     recorder,
                     checker,
+                    tb_remover,
+                    tb_backfiller,
+                    snapshot_manifest,
+                    circuit_breaker,
+                    idle_detector,
+                    latency_tracer,
+                    trx_spiller,
                 };
                 Ok(Box::new(pipeline) as Box<dyn Pipeline + Send>)
             }
         }
     }
 
+    // shared by the pipeline and the sinkers, [circuit_breaker] only supports mysql / pg sinkers
+    fn create_circuit_breaker(
+        &self,
+        sinker_client: &ConnClient,
+        monitor: TaskMonitorHandle,
+    ) -> Option<SharedCircuitBreaker> {
+        let config = self.config.circuit_breaker.as_ref()?;
+        let sql = config.probe_sql.clone();
+        let probe = match sinker_client {
+            _ if sql.is_empty() => None,
+            ConnClient::MySQL(conn_pool) => Some(TargetProbe::Mysql {
+                conn_pool: conn_pool.clone(),
+                sql,
+            }),
+            ConnClient::PostgreSQL(conn_pool) => Some(TargetProbe::Pg {
+                conn_pool: conn_pool.clone(),
+                sql,
+            }),
+            _ => None,
+        };
+        Some(Arc::new(Mutex::new(CircuitBreaker::new(
+            config, probe, monitor,
+        ))))
+    }
+
     async fn create_checker(
         &self,
         checker_config: Option<&CheckerConfig>,
@@ -1027,6 +1429,7 @@ impl TaskRunner {
                 check_log_dir: check_log_dir_base.clone(),
                 cdc_check_log_max_file_size,
                 cdc_check_log_max_rows,
+                check_log_compress: cfg.check_log_compress,
                 s3_output: s3_output.clone(),
                 cdc_check_log_interval_secs: cfg.cdc_check_log_interval_secs,
                 state_store: state_store.clone(),
@@ -1040,7 +1443,7 @@ impl TaskRunner {
                 let extractor_meta_manager =
                     ExtractorUtil::get_extractor_meta_manager(&self.config).await?;
                 let source_checker = self
-                    .create_source_checker(is_cdc_task, enable_sqlx_log)
+                    .create_source_checker(is_cdc_task, cfg.snapshot_isolation, enable_sqlx_log)
                     .await?;
                 let conn_pool = TaskUtil::create_mysql_conn_pool(
                     &checker_url,
@@ -1048,7 +1451,8 @@ impl TaskRunner {
                     &checker_auth,
                     max_connections,
                     enable_sqlx_log,
-                    None,
+                    cfg.snapshot_isolation
+                        .then(|| vec![MYSQL_REPEATABLE_READ_SQL]),
                 )
                 .await?;
                 let meta_manager =
@@ -1057,7 +1461,7 @@ impl TaskRunner {
                     )
                     .await?;
                 let checker = DataCheckerHandle::spawn(
-                    MysqlChecker::new(conn_pool, meta_manager),
+                    MysqlChecker::new(conn_pool, meta_manager, cfg.snapshot_isolation),
                     checker_task_id.clone(),
                     build_check_context(
                         extractor_meta_manager,
@@ -1075,7 +1479,7 @@ impl TaskRunner {
                 let extractor_meta_manager =
                     ExtractorUtil::get_extractor_meta_manager(&self.config).await?;
                 let source_checker = self
-                    .create_source_checker(is_cdc_task, enable_sqlx_log)
+                    .create_source_checker(is_cdc_task, cfg.snapshot_isolation, enable_sqlx_log)
                     .await?;
                 let conn_pool = TaskUtil::create_pg_conn_pool(
                     &checker_url,
@@ -1089,7 +1493,7 @@ impl TaskRunner {
                     dt_common::meta::pg::pg_meta_manager::PgMetaManager::new(conn_pool.clone())
                         .await?;
                 let checker = DataCheckerHandle::spawn(
-                    PgChecker::new(conn_pool, meta_manager),
+                    PgChecker::new(conn_pool, meta_manager, cfg.snapshot_isolation),
                     checker_task_id.clone(),
                     build_check_context(
                         extractor_meta_manager,
@@ -1105,7 +1509,7 @@ impl TaskRunner {
             DbType::Mongo => {
                 let router = RdbRouter::from_config(&self.config.router, &DbType::Mongo)?;
                 let source_checker = self
-                    .create_source_checker(is_cdc_task, enable_sqlx_log)
+                    .create_source_checker(is_cdc_task, cfg.snapshot_isolation, enable_sqlx_log)
                     .await?;
                 let mongo_client = TaskUtil::create_mongo_client(
                     &checker_url,
@@ -1124,6 +1528,39 @@ impl TaskRunner {
                 );
                 Ok(Some(CheckerHandle::Data(checker)))
             }
+            DbType::ClickHouse => {
+                let router = RdbRouter::from_config(
+                    &self.config.router,
+                    &self.config.extractor_basic.db_type,
+                )?;
+                let url_info = Url::parse(&checker_url)?;
+                let host = url_info
+                    .host_str()
+                    .context("checker url has no host")?
+                    .to_string();
+                let port = url_info.port().context("checker url has no port")?;
+                let (username, password) = match &checker_auth {
+                    ConnectionAuthConfig::Basic { username, password } => {
+                        (username.clone(), password.clone().unwrap_or_default())
+                    }
+                    _ => (
+                        url_info.username().to_string(),
+                        url_info.password().unwrap_or("").to_string(),
+                    ),
+                };
+                let http_client = ProxyUtil::http_client_builder(&self.config.proxy)?
+                    .http1_title_case_headers()
+                    .redirect(Policy::custom(|attempt| attempt.follow()))
+                    .build()?;
+                let checker = DataCheckerHandle::spawn(
+                    ClickhouseChecker::new(http_client, host, port.to_string(), username, password),
+                    checker_task_id.clone(),
+                    build_check_context(None, router, None, false),
+                    queue_size,
+                    "ClickhouseChecker",
+                );
+                Ok(Some(CheckerHandle::Data(checker)))
+            }
             _ => bail!("checker not supported for db_type: {}", checker_db_type),
         }
     }
@@ -1131,9 +1568,11 @@ impl TaskRunner {
     async fn create_source_checker(
         &self,
         is_cdc_task: bool,
+        snapshot_isolation: bool,
         enable_sqlx_log: bool,
     ) -> anyhow::Result<Option<Arc<AsyncMutex<Box<dyn Checker>>>>> {
-        if !is_cdc_task {
+        // snapshot checks re-read mismatched rows from the source before retrying them
+        if !is_cdc_task && !snapshot_isolation {
             return Ok(None);
         }
 
@@ -1145,13 +1584,13 @@ impl TaskRunner {
                     &self.config.extractor_basic.connection_auth,
                     1,
                     enable_sqlx_log,
-                    None,
+                    snapshot_isolation.then(|| vec![MYSQL_REPEATABLE_READ_SQL]),
                 )
                 .await?;
                 let meta_manager =
                     dt_common::meta::mysql::mysql_meta_manager::MysqlMetaManager::new(pool.clone())
                         .await?;
-                Box::new(MysqlChecker::new(pool, meta_manager))
+                Box::new(MysqlChecker::new(pool, meta_manager, snapshot_isolation))
             }
             DbType::Pg => {
                 let pool = TaskUtil::create_pg_conn_pool(
@@ -1164,7 +1603,7 @@ impl TaskRunner {
                 .await?;
                 let meta_manager =
                     dt_common::meta::pg::pg_meta_manager::PgMetaManager::new(pool.clone()).await?;
-                Box::new(PgChecker::new(pool, meta_manager))
+                Box::new(PgChecker::new(pool, meta_manager, snapshot_isolation))
             }
             DbType::Mongo => {
                 let client = TaskUtil::create_mongo_client(
@@ -1245,9 +1684,16 @@ impl TaskRunner {
                 );
         }
 
-        let raw: RawConfig = serde_yaml::from_str(&config_str)?;
+        let raw: RawConfig = if matches!(self.config.runtime.log_format, LogFormat::Json) {
+            let mut value: serde_yaml::Value = serde_yaml::from_str(&config_str)?;
+            Self::use_json_line_encoder(&mut value, &self.config.global.task_id);
+            serde_yaml::from_value(value)?
+        } else {
+            serde_yaml::from_str(&config_str)?
+        };
         let mut deserializers = Deserializers::default();
         deserializers.insert("size_limit", SizeLimitFilterDeserializer);
+        deserializers.insert("json_line", JsonLineEncoderDeserializer);
         let (appenders, errors) = raw.appenders_lossy(&deserializers);
         if !errors.is_empty() {
             bail!("errors deserializing appenders: {:?}", errors);
@@ -1268,6 +1714,26 @@ impl TaskRunner {
         Ok(())
     }
 
+    fn use_json_line_encoder(config: &mut serde_yaml::Value, task_id: &str) {
+        let Some(appenders) = config
+            .get_mut("appenders")
+            .and_then(serde_yaml::Value::as_mapping_mut)
+        else {
+            return;
+        };
+        for name in JSON_LINE_APPENDERS {
+            if let Some(appender) = appenders
+                .get_mut(name)
+                .and_then(serde_yaml::Value::as_mapping_mut)
+            {
+                let mut encoder = serde_yaml::Mapping::new();
+                encoder.insert("kind".into(), "json_line".into());
+                encoder.insert("task_id".into(), task_id.into());
+                appender.insert("encoder".into(), encoder.into());
+            }
+        }
+    }
+
     async fn create_task_tables(
         &self,
         extractor_client: ConnClient,
@@ -1275,23 +1741,12 @@ impl TaskRunner {
         sinker_data_marker: Option<DataMarker>,
     ) -> anyhow::Result<()> {
         // create heartbeat table
-        let heartbeat_schema_tb = match &self.config.extractor {
-            ExtractorConfig::MysqlCdc { heartbeat_tb, .. }
-            | ExtractorConfig::PgCdc { heartbeat_tb, .. } => ConfigTokenParser::parse(
-                heartbeat_tb,
-                &['.'],
-                &TokenEscapePair::from_char_pairs(SqlUtil::get_escape_pairs(
-                    &self.config.extractor_basic.db_type,
-                )),
-            ),
-            _ => vec![],
-        };
-
-        if heartbeat_schema_tb.len() == 2 {
+        let db_type = &self.config.extractor_basic.db_type;
+        if let Some(heartbeat_tb) = self.config.extractor.heartbeat_tb(db_type) {
+            let (schema, tb) = (&heartbeat_tb.schema, &heartbeat_tb.tb);
             match &self.config.extractor {
                 ExtractorConfig::MysqlCdc { .. } => {
-                    let db_sql =
-                        format!("CREATE DATABASE IF NOT EXISTS `{}`", heartbeat_schema_tb[0]);
+                    let db_sql = format!("CREATE DATABASE IF NOT EXISTS `{}`", schema);
                     let tb_sql = format!(
                         "CREATE TABLE IF NOT EXISTS `{}`.`{}`(
                         server_id INT UNSIGNED,
@@ -1304,13 +1759,13 @@ impl TaskRunner {
                         flushed_timestamp VARCHAR(255),
                         PRIMARY KEY(server_id)
                     )",
-                        heartbeat_schema_tb[0], heartbeat_schema_tb[1]
+                        schema, tb
                     );
 
                     TaskUtil::check_and_create_tb(
-                        &extractor_client.clone(),
-                        &heartbeat_schema_tb[0],
-                        &heartbeat_schema_tb[1],
+                        &extractor_client,
+                        schema,
+                        tb,
                         &db_sql,
                         &tb_sql,
                         &DbType::Mysql,
@@ -1319,10 +1774,7 @@ impl TaskRunner {
                 }
 
                 ExtractorConfig::PgCdc { .. } => {
-                    let schema_sql = format!(
-                        r#"CREATE SCHEMA IF NOT EXISTS "{}""#,
-                        heartbeat_schema_tb[0]
-                    );
+                    let schema_sql = format!(r#"CREATE SCHEMA IF NOT EXISTS "{}""#, schema);
                     let tb_sql = format!(
                         r#"CREATE TABLE IF NOT EXISTS "{}"."{}"(
                         slot_name character varying(64) not null,
@@ -1333,13 +1785,13 @@ impl TaskRunner {
                         flushed_timestamp character varying(64),
                         primary key(slot_name)
                     )"#,
-                        heartbeat_schema_tb[0], heartbeat_schema_tb[1]
+                        schema, tb
                     );
 
                     TaskUtil::check_and_create_tb(
-                        &extractor_client.clone(),
-                        &heartbeat_schema_tb[0],
-                        &heartbeat_schema_tb[1],
+                        &extractor_client,
+                        schema,
+                        tb,
                         &schema_sql,
                         &tb_sql,
                         &DbType::Pg,
@@ -1347,6 +1799,19 @@ impl TaskRunner {
                     .await?
                 }
 
+                // collections are schemaless, only need to exist
+                ExtractorConfig::MongoCdc { .. } => {
+                    TaskUtil::check_and_create_tb(
+                        &extractor_client,
+                        schema,
+                        tb,
+                        "",
+                        "",
+                        &DbType::Mongo,
+                    )
+                    .await?
+                }
+
                 _ => {}
             }
         }
@@ -1414,13 +1879,128 @@ impl TaskRunner {
         Ok(())
     }
 
+    async fn load_snapshot_manifest(&self) -> anyhow::Result<Option<Arc<SnapshotManifest>>> {
+        let manifest_file = &self.config.pipeline.snapshot_manifest_file;
+        let is_snapshot_task = self
+            .task_type
+            .as_ref()
+            .is_some_and(|task_type| task_type.kind == TaskKind::Snapshot);
+        if manifest_file.is_empty() || !is_snapshot_task {
+            return Ok(None);
+        }
+        Ok(Some(Arc::new(SnapshotManifest::load(manifest_file).await?)))
+    }
+
+    async fn list_distributed_tbs(&self) -> anyhow::Result<Vec<TbId>> {
+        let db_type = &self.config.extractor_basic.db_type;
+        let (extractor_client, sinker_client) = ConnClient::from_config(&self.config).await?;
+        let mut tbs = Vec::new();
+        for schema in TaskUtil::list_schemas(&extractor_client, db_type).await? {
+            if self.filter.filter_schema(&schema) {
+                continue;
+            }
+            for tb in TaskUtil::list_tbs(&extractor_client, &schema, db_type).await? {
+                if !self.filter.filter_tb(&schema, &tb) {
+                    tbs.push(TbId {
+                        schema: schema.clone(),
+                        tb,
+                    });
+                }
+            }
+        }
+        extractor_client.close().await?;
+        sinker_client.close().await?;
+        Ok(tbs)
+    }
+
+    /// Runner restricted to the tables assigned by the distributed coordinator.
+    fn with_assigned_tbs(&self, tbs: &[TbId]) -> anyhow::Result<Self> {
+        let mut config = self.config.clone();
+        let db_type = &config.extractor_basic.db_type;
+        config.filter.do_schemas = String::new();
+        config.filter.do_tbs = tbs
+            .iter()
+            .map(|i| {
+                format!(
+                    "{}.{}",
+                    SqlUtil::escape_by_db_type(&i.schema, db_type),
+                    SqlUtil::escape_by_db_type(&i.tb, db_type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        config.distributed = None;
+        Ok(Self {
+            filter: RdbFilter::from_config(&config.filter, db_type)?,
+            config,
+            ..self.clone()
+        })
+    }
+
+    /// Runner restricted to the referential closure of [subset] seeds,
+    /// each table is extracted by its condition resolved from foreign keys of the source.
+    async fn with_subset(&self) -> anyhow::Result<Self> {
+        let Some(subset) = &self.config.subset else {
+            return Ok(self.clone());
+        };
+        let db_type = &self.config.extractor_basic.db_type;
+        let (extractor_client, sinker_client) = ConnClient::from_config(&self.config).await?;
+        let foreign_keys = SubsetPlanner::fetch_foreign_keys(&extractor_client, db_type).await;
+        extractor_client.close().await?;
+        sinker_client.close().await?;
+
+        let planner = SubsetPlanner {
+            db_type: db_type.clone(),
+            config: subset.clone(),
+            filter: self.filter.clone(),
+        };
+        let conditions = planner.plan(&foreign_keys?);
+
+        let mut config = self.config.clone();
+        config.filter.do_schemas = String::new();
+        config.filter.do_tbs = conditions
+            .keys()
+            .map(|(schema, tb)| {
+                format!(
+                    "{}.{}",
+                    SqlUtil::escape_by_db_type(schema, db_type),
+                    SqlUtil::escape_by_db_type(tb, db_type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let where_conditions: Vec<_> = conditions
+            .iter()
+            .map(|((schema, tb), condition)| {
+                serde_json::json!({"db": schema, "tb": tb, "condition": condition})
+            })
+            .collect();
+        config.filter.where_conditions =
+            format!("json:{}", serde_json::to_string(&where_conditions)?);
+        config.subset = None;
+        Ok(Self {
+            filter: RdbFilter::from_config(&config.filter, db_type)?,
+            config,
+            ..self.clone()
+        })
+    }
+
     async fn get_task_info(
         &self,
         extractor_client: ConnClient,
         recovery: Option<Arc<dyn Recovery + Send + Sync>>,
+        snapshot_manifest: Option<&Arc<SnapshotManifest>>,
     ) -> anyhow::Result<TaskInfo> {
         let db_type = &self.config.extractor_basic.db_type;
         let filter = &self.filter;
+        // chunk files are listed by the extractor itself, no source db to query
+        if matches!(self.config.extractor, ExtractorConfig::ChunkFile { .. }) {
+            return Ok(TaskInfo {
+                extractor_config: self.config.extractor.clone(),
+                no_snapshot_data: false,
+            });
+        }
+
         let is_snapshot_task = matches!(
             self.config.extractor,
             ExtractorConfig::MysqlSnapshot { .. }
@@ -1541,10 +2121,25 @@ impl TaskRunner {
                     }
                 }
 
+                if let Some(manifest) = snapshot_manifest {
+                    if manifest.is_done(schema, tb) {
+                        log_info!(
+                            "schema: {}, tb: {}, already finished in snapshot manifest",
+                            schema,
+                            tb
+                        );
+                        finished_tbs += 1;
+                        continue;
+                    }
+                }
+
                 if filter.filter_event(schema, tb, &RowType::Insert) {
                     log_info!("schema: {}, tb: {}, insert events filtered", schema, tb);
                     continue;
                 }
+                if let Some(manifest) = snapshot_manifest {
+                    manifest.add_pending(schema, tb);
+                }
                 tables.push(tb.to_owned());
             }
             schema_tbs.insert(schema.clone(), tables);
@@ -1552,6 +2147,9 @@ impl TaskRunner {
             self.task_monitor
                 .add_no_window_metrics(TaskMetricsType::FinishedProgressCount, finished_tbs as u64);
         }
+        if let Some(manifest) = snapshot_manifest {
+            manifest.persist().await?;
+        }
         let extractor_config = match &self.config.extractor {
             ExtractorConfig::MysqlSnapshot {
                 url,
@@ -1560,6 +2158,9 @@ impl TaskRunner {
                 parallel_size,
                 parallel_type,
                 batch_size,
+                snapshot_watermark,
+                table_order,
+                enum_set_as_index,
                 ..
             } => ExtractorConfig::MysqlSnapshot {
                 url: url.clone(),
@@ -1572,6 +2173,9 @@ impl TaskRunner {
                 parallel_type: parallel_type.clone(),
                 batch_size: *batch_size,
                 partition_cols: String::new(),
+                snapshot_watermark: *snapshot_watermark,
+                table_order: table_order.clone(),
+                enum_set_as_index: *enum_set_as_index,
             },
 
             ExtractorConfig::PgSnapshot {
@@ -1581,6 +2185,9 @@ impl TaskRunner {
                 parallel_size,
                 parallel_type,
                 batch_size,
+                snapshot_watermark,
+                table_order,
+                consistent_snapshot,
                 ..
             } => ExtractorConfig::PgSnapshot {
                 url: url.clone(),
@@ -1593,6 +2200,9 @@ impl TaskRunner {
                 parallel_type: parallel_type.clone(),
                 batch_size: *batch_size,
                 partition_cols: String::new(),
+                snapshot_watermark: *snapshot_watermark,
+                table_order: table_order.clone(),
+                consistent_snapshot: *consistent_snapshot,
             },
 
             ExtractorConfig::MongoSnapshot {
@@ -1603,6 +2213,8 @@ impl TaskRunner {
                 parallel_size,
                 parallel_type,
                 batch_size,
+                read_concern,
+                read_preference,
                 ..
             } => ExtractorConfig::MongoSnapshot {
                 url: url.clone(),
@@ -1615,6 +2227,8 @@ impl TaskRunner {
                 parallel_size: *parallel_size,
                 parallel_type: parallel_type.clone(),
                 batch_size: *batch_size,
+                read_concern: read_concern.clone(),
+                read_preference: read_preference.clone(),
             },
             _ => self.config.extractor.clone(),
         };
@@ -1656,6 +2270,7 @@ mod tests {
             connection_auth: ConnectionAuthConfig::NoAuth,
             check_log_dir: "/tmp/ape-dts/check/".to_string(),
             batch_size: 1,
+            conflict_check: false,
         };
 
         assert!(TaskRunner::check_log_replay_reads_from_dir(
@@ -9,7 +9,10 @@ use std::{
 
 use anyhow::bail;
 use futures::{future::join_all, TryStreamExt};
-use mongodb::{bson::doc, options::ClientOptions};
+use mongodb::{
+    bson::{doc, Document},
+    options::ClientOptions,
+};
 use opendal::Operator;
 use sqlx::{
     mysql::{MySqlConnectOptions, MySqlPoolOptions},
@@ -24,10 +27,12 @@ use dt_common::{
         extractor_config::ExtractorConfig,
         global_config::GlobalConfig,
         meta_center_config::MetaCenterConfig,
+        pipeline_config::TableOverrides,
         resumer_config::ResumerConfig,
         s3_config::S3Config,
         sinker_config::{BasicSinkerConfig, SinkerConfig},
-        task_config::TaskConfig,
+        task_config::{TaskConfig, DEFAULT_PG_STATEMENT_CACHE_CAPACITY},
+        timeout_config::TimeoutConfig,
     },
     error::Error,
     log_info, log_warn,
@@ -38,6 +43,7 @@ use dt_common::{
         },
         pg::pg_meta_manager::PgMetaManager,
         rdb_meta_manager::RdbMetaManager,
+        syncer::Syncer,
     },
     monitor::FlushableMonitor,
     rdb_filter::RdbFilter,
@@ -49,8 +55,9 @@ use dt_connector::{
     extractor::resumer::{
         build_recorder, build_recovery, recorder::Recorder, recovery::Recovery, utils::ResumerUtil,
     },
+    rdb_router::RdbRouter,
 };
-use tokio::select;
+use tokio::{select, sync::Mutex};
 
 pub struct TaskUtil {}
 
@@ -68,15 +75,20 @@ impl TaskUtil {
                     target.db_type.clone(),
                     None,
                     None,
+                    &target.timeout,
                 )
                 .await?;
                 Some(RdbMetaManager::from_mysql(mysql_meta_manager))
             }
 
             DbType::Pg => {
-                let pg_meta_manager =
-                    Self::create_pg_meta_manager(&target.url, &target.connection_auth, log_level)
-                        .await?;
+                let pg_meta_manager = Self::create_pg_meta_manager(
+                    &target.url,
+                    &target.connection_auth,
+                    log_level,
+                    &target.timeout,
+                )
+                .await?;
                 Some(RdbMetaManager::from_pg(pg_meta_manager))
             }
 
@@ -92,6 +104,29 @@ impl TaskUtil {
         max_connections: u32,
         enable_sqlx_log: bool,
         after_connect_settings: Option<Vec<&'static str>>,
+    ) -> anyhow::Result<Pool<MySql>> {
+        Self::create_mysql_conn_pool_with_timeout(
+            url,
+            db_type,
+            connection_auth,
+            max_connections,
+            enable_sqlx_log,
+            after_connect_settings,
+            &TimeoutConfig::default(),
+        )
+        .await
+    }
+
+    /// statement timeouts are applied by max_execution_time, which only limits SELECT statements
+    /// of mysql, other statements are limited by the callers, e.g. sinkers
+    pub async fn create_mysql_conn_pool_with_timeout(
+        url: &str,
+        db_type: &DbType,
+        connection_auth: &ConnectionAuthConfig,
+        max_connections: u32,
+        enable_sqlx_log: bool,
+        after_connect_settings: Option<Vec<&'static str>>,
+        timeout: &TimeoutConfig,
     ) -> anyhow::Result<Pool<MySql>> {
         let final_url = ConnectionAuthConfig::merge_url_with_auth(url, connection_auth)?;
 
@@ -114,26 +149,36 @@ impl TaskUtil {
                 .no_engine_substitution(false)
         }
 
+        let mut settings: Vec<String> = after_connect_settings
+            .unwrap_or_default()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        if let (DbType::Mysql, Some(statement_timeout)) = (db_type, timeout.statement_timeout()) {
+            settings.push(format!(
+                "SET SESSION max_execution_time={}",
+                statement_timeout.as_millis()
+            ));
+        }
+
         let mut conn_pool = MySqlPoolOptions::new()
             .max_connections(max_connections)
-            .acquire_timeout(Duration::from_secs(15))
+            .acquire_timeout(timeout.connect_timeout())
             .idle_timeout(Some(Duration::from_secs(5 * 60)));
-        if let Some(settings) = after_connect_settings {
-            if !settings.is_empty() {
-                conn_pool = conn_pool.after_connect(move |conn, _meta| {
-                    let additions = settings.clone();
-                    Box::pin(async move {
-                        log_info!(
-                            "execute addition settings after create new connection: {:?}",
-                            additions
-                        );
-                        for addition in additions {
-                            conn.execute(sqlx::query(addition)).await?;
-                        }
-                        Ok(())
-                    })
+        if !settings.is_empty() {
+            conn_pool = conn_pool.after_connect(move |conn, _meta| {
+                let additions = settings.clone();
+                Box::pin(async move {
+                    log_info!(
+                        "execute addition settings after create new connection: {:?}",
+                        additions
+                    );
+                    for addition in additions {
+                        conn.execute(sqlx::query(&addition)).await?;
+                    }
+                    Ok(())
                 })
-            }
+            })
         }
 
         Ok(conn_pool.connect_with(conn_options).await?)
@@ -176,11 +221,54 @@ impl TaskUtil {
         max_connections: u32,
         enable_sqlx_log: bool,
         disable_foreign_key_checks: bool,
+    ) -> anyhow::Result<Pool<Postgres>> {
+        Self::create_pg_conn_pool_with_timeout(
+            url,
+            connection_auth,
+            max_connections,
+            enable_sqlx_log,
+            disable_foreign_key_checks,
+            &TimeoutConfig::default(),
+        )
+        .await
+    }
+
+    pub async fn create_pg_conn_pool_with_timeout(
+        url: &str,
+        connection_auth: &ConnectionAuthConfig,
+        max_connections: u32,
+        enable_sqlx_log: bool,
+        disable_foreign_key_checks: bool,
+        timeout: &TimeoutConfig,
+    ) -> anyhow::Result<Pool<Postgres>> {
+        Self::create_pg_conn_pool_with_cache(
+            url,
+            connection_auth,
+            max_connections,
+            enable_sqlx_log,
+            disable_foreign_key_checks,
+            DEFAULT_PG_STATEMENT_CACHE_CAPACITY,
+            timeout,
+        )
+        .await
+    }
+
+    /// statements are prepared once per connection and cached by sql, which is decided by
+    /// table, row type and columns, statement_cache_capacity bounds the cache of each connection
+    pub async fn create_pg_conn_pool_with_cache(
+        url: &str,
+        connection_auth: &ConnectionAuthConfig,
+        max_connections: u32,
+        enable_sqlx_log: bool,
+        disable_foreign_key_checks: bool,
+        statement_cache_capacity: usize,
+        timeout: &TimeoutConfig,
     ) -> anyhow::Result<Pool<Postgres>> {
         let final_url = ConnectionAuthConfig::merge_url_with_auth(url, connection_auth)?;
 
         let mut conn_options = PgConnectOptions::from_str(&final_url)?;
         conn_options = conn_options
+            .statement_cache_capacity(statement_cache_capacity)
             .log_statements(log::LevelFilter::Debug)
             .log_slow_statements(log::LevelFilter::Debug, Duration::from_secs(1));
 
@@ -191,8 +279,17 @@ impl TaskUtil {
         if let Some(ssl) = connection_auth.ssl_config() {
             conn_options = ssl.apply_pg(conn_options);
         }
+        // cancelled statements fail with query_canceled (57014)
+        if let Some(statement_timeout) = timeout.statement_timeout() {
+            conn_options = conn_options.options([(
+                "statement_timeout",
+                statement_timeout.as_millis().to_string(),
+            )]);
+        }
 
-        let mut pool_options = PgPoolOptions::new().max_connections(max_connections);
+        let mut pool_options = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(timeout.connect_timeout());
 
         if disable_foreign_key_checks {
             pool_options = pool_options.after_connect(move |conn, _meta| {
@@ -230,6 +327,7 @@ impl TaskUtil {
                     DbType::Mysql,
                     None,
                     None,
+                    &config.sinker_basic.timeout,
                 )
                 .await?;
                 Some(RdbMetaManager::from_mysql(mysql_meta_manager))
@@ -251,6 +349,7 @@ impl TaskUtil {
                             DbType::Mysql,
                             None,
                             None,
+                            &config.extractor_basic.timeout,
                         )
                         .await?;
                         Some(RdbMetaManager::from_mysql(mysql_meta_manager))
@@ -260,8 +359,13 @@ impl TaskUtil {
                         connection_auth,
                         ..
                     } => {
-                        let pg_meta_manager =
-                            Self::create_pg_meta_manager(url, connection_auth, log_level).await?;
+                        let pg_meta_manager = Self::create_pg_meta_manager(
+                            url,
+                            connection_auth,
+                            log_level,
+                            &config.extractor_basic.timeout,
+                        )
+                        .await?;
                         Some(RdbMetaManager::from_pg(pg_meta_manager))
                     }
                     _ => None,
@@ -285,8 +389,9 @@ impl TaskUtil {
             _ => None,
         };
 
-        if meta_manager.is_some() {
-            return Ok(meta_manager);
+        if let Some(meta_manager) = meta_manager {
+            let key_overrides = Self::create_table_overrides(config)?;
+            return Ok(Some(meta_manager.with_key_overrides(key_overrides)));
         }
 
         if let Some(target) = config.checker_target() {
@@ -303,18 +408,20 @@ impl TaskUtil {
         db_type: DbType,
         meta_center_config: Option<MetaCenterConfig>,
         conn_pool_opt: Option<Pool<MySql>>,
+        timeout: &TimeoutConfig,
     ) -> anyhow::Result<MysqlMetaManager> {
         let enable_sqlx_log = Self::check_enable_sqlx_log(log_level);
         let conn_pool = match &conn_pool_opt {
             Some(conn_pool) => conn_pool.clone(),
             None => {
-                Self::create_mysql_conn_pool(
+                Self::create_mysql_conn_pool_with_timeout(
                     url,
                     &db_type,
                     connection_auth,
                     1,
                     enable_sqlx_log,
                     None,
+                    timeout,
                 )
                 .await?
             }
@@ -331,13 +438,14 @@ impl TaskUtil {
             let meta_center_conn_pool = match &conn_pool_opt {
                 Some(conn_pool) => conn_pool.clone(),
                 None => {
-                    Self::create_mysql_conn_pool(
+                    Self::create_mysql_conn_pool_with_timeout(
                         url,
                         &DbType::Mysql,
                         connection_auth,
                         1,
                         enable_sqlx_log,
                         None,
+                        timeout,
                     )
                     .await?
                 }
@@ -358,10 +466,18 @@ impl TaskUtil {
         url: &str,
         connection_auth: &ConnectionAuthConfig,
         log_level: &str,
+        timeout: &TimeoutConfig,
     ) -> anyhow::Result<PgMetaManager> {
         let enable_sqlx_log = Self::check_enable_sqlx_log(log_level);
-        let conn_pool =
-            Self::create_pg_conn_pool(url, connection_auth, 1, enable_sqlx_log, false).await?;
+        let conn_pool = Self::create_pg_conn_pool_with_timeout(
+            url,
+            connection_auth,
+            1,
+            enable_sqlx_log,
+            false,
+            timeout,
+        )
+        .await?;
         PgMetaManager::new(conn_pool.clone()).await
     }
 
@@ -387,6 +503,23 @@ impl TaskUtil {
         Ok(mongodb::Client::with_options(client_options)?)
     }
 
+    /// Table overrides are configured by source names, while parallelizers and sinkers
+    /// see the routed names.
+    pub fn create_table_overrides(config: &TaskConfig) -> anyhow::Result<TableOverrides> {
+        let overrides = &config.pipeline.table_overrides;
+        if overrides.is_empty() {
+            return Ok(overrides.clone());
+        }
+        let router = RdbRouter::from_config(&config.router, &config.extractor_basic.db_type)?;
+        Ok(match router {
+            Some(router) => overrides.map_tables(|schema, tb| {
+                let (schema, tb) = router.get_tb_map(schema, tb);
+                (schema.to_string(), tb.to_string())
+            }),
+            None => overrides.clone(),
+        })
+    }
+
     pub fn check_enable_sqlx_log(log_level: &str) -> bool {
         log_level == "debug" || log_level == "trace"
     }
@@ -578,6 +711,45 @@ WHERE
                 sqlx::query(schema_sql).execute(conn_pool).await?;
                 sqlx::query(tb_sql).execute(conn_pool).await?;
             }
+            ConnClient::MongoDB(client) => {
+                client.database(schema).create_collection(tb).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub async fn drop_tb(
+        conn_client: &ConnClient,
+        schema: &str,
+        tb: &str,
+        db_type: &DbType,
+    ) -> anyhow::Result<()> {
+        log_info!("dropping schema: {}, tb: {}", schema, tb);
+        match conn_client {
+            ConnClient::MySQL(conn_pool) => {
+                let sql = format!(
+                    "DROP TABLE IF EXISTS {}.{}",
+                    SqlUtil::escape_by_db_type(schema, db_type),
+                    SqlUtil::escape_by_db_type(tb, db_type)
+                );
+                sqlx::query(&sql).execute(conn_pool).await?;
+            }
+            ConnClient::PostgreSQL(conn_pool) => {
+                let sql = format!(
+                    "DROP TABLE IF EXISTS {}.{}",
+                    SqlUtil::escape_by_db_type(schema, db_type),
+                    SqlUtil::escape_by_db_type(tb, db_type)
+                );
+                sqlx::query(&sql).execute(conn_pool).await?;
+            }
+            ConnClient::MongoDB(client) => {
+                client
+                    .database(schema)
+                    .collection::<Document>(tb)
+                    .drop()
+                    .await?;
+            }
             _ => {}
         }
         Ok(())
@@ -783,13 +955,18 @@ WHERE
         Ok((recorder, recovery, checker_state_store))
     }
 
+    /// Flushes monitors every interval_secs, or every idle_interval_secs while the source is idle.
     pub async fn flush_monitors(
         interval_secs: u64,
+        idle_interval_secs: u64,
+        syncer: Arc<Mutex<Syncer>>,
         shut_down: Arc<AtomicBool>,
         monitors: &[Arc<dyn FlushableMonitor + Send + Sync>],
     ) {
         let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
         interval.tick().await;
+        let idle_every = idle_interval_secs.div_ceil(interval_secs).max(1);
+        let mut idle_ticks = 0;
 
         loop {
             if shut_down.load(Ordering::Acquire) {
@@ -798,7 +975,17 @@ WHERE
             }
 
             select! {
-                _ = interval.tick() => Self::flush_monitor_batch(monitors).await,
+                _ = interval.tick() => {
+                    if syncer.lock().await.is_idle {
+                        idle_ticks += 1;
+                        if idle_ticks % idle_every != 0 {
+                            continue;
+                        }
+                    } else {
+                        idle_ticks = 0;
+                    }
+                    Self::flush_monitor_batch(monitors).await
+                }
                 _ = Self::wait_for_shutdown(shut_down.clone()) => {
                     log_info!("task shutdown detected, do final flush");
                     Self::flush_monitor_batch(monitors).await;
@@ -870,13 +1057,14 @@ impl ConnClient {
                 connection_auth,
                 ..
             } => ConnClient::MySQL(
-                TaskUtil::create_mysql_conn_pool(
+                TaskUtil::create_mysql_conn_pool_with_timeout(
                     url,
                     &DbType::Mysql,
                     connection_auth,
                     extractor_max_connections,
                     enable_sqlx_log,
                     None,
+                    &task_config.extractor_basic.timeout,
                 )
                 .await?,
             ),
@@ -900,12 +1088,13 @@ impl ConnClient {
                 connection_auth,
                 ..
             } => ConnClient::PostgreSQL(
-                TaskUtil::create_pg_conn_pool(
+                TaskUtil::create_pg_conn_pool_with_timeout(
                     url,
                     connection_auth,
                     extractor_max_connections,
                     enable_sqlx_log,
                     false,
+                    &task_config.extractor_basic.timeout,
                 )
                 .await?,
             ),
@@ -948,6 +1137,15 @@ impl ConnClient {
             ),
             _ => ConnClient::None,
         };
+        let sinker_client = Self::sinker_from_config(task_config).await?;
+        Ok((extractor_client, sinker_client))
+    }
+
+    /// Creates the client of [sinker] only, e.g. for targets of a sharded task.
+    pub async fn sinker_from_config(task_config: &TaskConfig) -> anyhow::Result<Self> {
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&task_config.runtime.log_level);
+        let sinker_max_connections = task_config.sinker_basic.max_connections;
+        let timeout = &task_config.sinker_basic.timeout;
         let sinker_client = match &task_config.sinker {
             SinkerConfig::Mysql {
                 url,
@@ -961,13 +1159,14 @@ impl ConnClient {
                     transaction_isolation,
                 );
                 ConnClient::MySQL(
-                    TaskUtil::create_mysql_conn_pool(
+                    TaskUtil::create_mysql_conn_pool_with_timeout(
                         url,
                         &DbType::Mysql,
                         connection_auth,
                         sinker_max_connections,
                         enable_sqlx_log,
                         conn_settings,
+                        timeout,
                     )
                     .await?,
                 )
@@ -977,13 +1176,14 @@ impl ConnClient {
                 connection_auth,
                 ..
             } => ConnClient::MySQL(
-                TaskUtil::create_mysql_conn_pool(
+                TaskUtil::create_mysql_conn_pool_with_timeout(
                     url,
                     &DbType::Mysql,
                     connection_auth,
                     sinker_max_connections,
                     enable_sqlx_log,
                     None,
+                    timeout,
                 )
                 .await?,
             ),
@@ -991,14 +1191,17 @@ impl ConnClient {
                 url,
                 connection_auth,
                 disable_foreign_key_checks,
+                statement_cache_capacity,
                 ..
             } => ConnClient::PostgreSQL(
-                TaskUtil::create_pg_conn_pool(
+                TaskUtil::create_pg_conn_pool_with_cache(
                     url,
                     connection_auth,
                     sinker_max_connections,
                     enable_sqlx_log,
                     *disable_foreign_key_checks,
+                    *statement_cache_capacity,
+                    timeout,
                 )
                 .await?,
             ),
@@ -1007,12 +1210,13 @@ impl ConnClient {
                 connection_auth,
                 ..
             } => ConnClient::PostgreSQL(
-                TaskUtil::create_pg_conn_pool(
+                TaskUtil::create_pg_conn_pool_with_timeout(
                     url,
                     connection_auth,
                     sinker_max_connections,
                     enable_sqlx_log,
                     false,
+                    timeout,
                 )
                 .await?,
             ),
@@ -1039,9 +1243,41 @@ impl ConnClient {
                 )
                 .await?,
             ),
+            SinkerConfig::Validate {
+                db_type: DbType::Pg,
+                url,
+                connection_auth,
+                ..
+            } => ConnClient::PostgreSQL(
+                TaskUtil::create_pg_conn_pool_with_timeout(
+                    url,
+                    connection_auth,
+                    sinker_max_connections,
+                    enable_sqlx_log,
+                    false,
+                    timeout,
+                )
+                .await?,
+            ),
+            SinkerConfig::Validate {
+                url,
+                connection_auth,
+                ..
+            } => ConnClient::MySQL(
+                TaskUtil::create_mysql_conn_pool_with_timeout(
+                    url,
+                    &DbType::Mysql,
+                    connection_auth,
+                    sinker_max_connections,
+                    enable_sqlx_log,
+                    None,
+                    timeout,
+                )
+                .await?,
+            ),
             _ => ConnClient::None,
         };
-        Ok((extractor_client, sinker_client))
+        Ok(sinker_client)
     }
 
     pub async fn close(&self) -> anyhow::Result<()> {
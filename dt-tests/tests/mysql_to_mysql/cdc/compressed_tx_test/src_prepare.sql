@@ -0,0 +1,5 @@
+DROP DATABASE IF EXISTS test_db_1;
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1 (id int, f_1 varchar(255), f_2 json DEFAULT NULL, f_3 datetime(6) DEFAULT NULL, PRIMARY KEY (id)) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+CREATE TABLE test_db_1.tb_2 (id int, f_1 longtext, PRIMARY KEY (id)) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
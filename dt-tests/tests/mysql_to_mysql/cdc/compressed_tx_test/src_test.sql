@@ -0,0 +1,21 @@
+-- the session runs each transaction in one compressed Transaction_payload event, needs mysql 8.0.20+
+```
+SET SESSION binlog_transaction_compression=ON;
+BEGIN;
+INSERT INTO test_db_1.tb_1 VALUES (1, 'a', '{"k":1}', '2024-01-01 00:00:00.123456'), (2, 'b', NULL, NULL), (3, 'c', '[1,2]', '2024-01-01 00:00:01');
+INSERT INTO test_db_1.tb_2 VALUES (1, REPEAT('abcdefgh', 10000)), (2, REPEAT('x', 100));
+UPDATE test_db_1.tb_1 SET f_1 = 'aa', f_2 = '{"k":2}' WHERE id = 1;
+DELETE FROM test_db_1.tb_1 WHERE id = 2;
+COMMIT;
+```
+
+-- multiple compressed transactions and auto-commit statements
+```
+SET SESSION binlog_transaction_compression=ON;
+INSERT INTO test_db_1.tb_1 VALUES (4, 'd', NULL, NULL);
+UPDATE test_db_1.tb_2 SET f_1 = REPEAT('y', 20000) WHERE id = 2;
+BEGIN;
+DELETE FROM test_db_1.tb_2 WHERE id = 1;
+INSERT INTO test_db_1.tb_2 VALUES (3, 'z');
+COMMIT;
+```
@@ -29,6 +29,12 @@ mod test {
         TestBase::run_cdc_test("mysql_to_mysql/cdc/json_test", 3000, 2000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_compressed_tx_test() {
+        TestBase::run_cdc_test("mysql_to_mysql/cdc/compressed_tx_test", 3000, 2000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_ddl_test() {
@@ -1 +1,2 @@
 mod chunk_partitioner;
+mod row_hash;
@@ -0,0 +1 @@
+mod row_hash_bench_test;
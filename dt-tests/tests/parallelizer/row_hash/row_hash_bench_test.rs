@@ -0,0 +1,173 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    fmt::Write,
+    fs,
+    hash::{Hash, Hasher},
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use dt_common::meta::{
+    col_value::ColValue, rdb_tb_meta::RdbTbMeta, row_data::RowData, row_type::RowType,
+};
+
+const ROWS: usize = 100_000;
+const ITERATIONS: usize = 20;
+
+struct HashCase {
+    name: &'static str,
+    tb_meta: RdbTbMeta,
+    data: Vec<RowData>,
+}
+
+struct HashBenchRow {
+    implementation: &'static str,
+    elapsed_ms: f64,
+    speedup_vs_legacy: f64,
+}
+
+fn tb_meta(id_cols: &[&str]) -> RdbTbMeta {
+    let id_cols: Vec<String> = id_cols.iter().map(|col| col.to_string()).collect();
+    RdbTbMeta {
+        schema: "db".into(),
+        tb: "tb".into(),
+        cols: id_cols.clone(),
+        key_map: HashMap::from([("primary".to_string(), id_cols.clone())]),
+        id_cols,
+        ..Default::default()
+    }
+}
+
+fn row(after: HashMap<String, ColValue>) -> RowData {
+    RowData::new(
+        "db".into(),
+        "tb".into(),
+        0,
+        RowType::Insert,
+        None,
+        Some(after),
+    )
+}
+
+fn hash_cases() -> Vec<HashCase> {
+    let narrow = (0..ROWS)
+        .map(|i| row(HashMap::from([("id".into(), ColValue::LongLong(i as i64))])))
+        .collect();
+
+    let wide = (0..ROWS)
+        .map(|i| {
+            row(HashMap::from([
+                ("tenant_id".into(), ColValue::LongLong((i % 64) as i64)),
+                (
+                    "order_no".into(),
+                    ColValue::String(format!("{:08x}-7b1c-4c5e-9f7a-{:012x}", i, i * 31)),
+                ),
+                (
+                    "created_at".into(),
+                    ColValue::DateTime(format!("2024-01-01 00:00:{:02}.{:06}", i % 60, i)),
+                ),
+                ("amount".into(), ColValue::Decimal(format!("{}.25", i))),
+            ]))
+        })
+        .collect();
+
+    vec![
+        HashCase {
+            name: "narrow_bigint_key",
+            tb_meta: tb_meta(&["id"]),
+            data: narrow,
+        },
+        HashCase {
+            name: "wide_4_col_key",
+            tb_meta: tb_meta(&["tenant_id", "order_no", "created_at", "amount"]),
+            data: wide,
+        },
+    ]
+}
+
+// the previous RowData::get_hash_code: sip hash over the string of each id col value
+fn legacy_hash_code(row_data: &RowData, tb_meta: &RdbTbMeta) -> u128 {
+    let col_values = row_data.after.as_ref().unwrap();
+    let mut hash_code = 1u128;
+    for col in tb_meta.id_cols.iter() {
+        let mut hasher = DefaultHasher::new();
+        col_values
+            .get(col)
+            .unwrap()
+            .to_option_string()
+            .hash(&mut hasher);
+        hash_code = 31 * hash_code + hasher.finish() as u128;
+    }
+    hash_code
+}
+
+// hash every row and index it by hash code, as RdbMerger does for insert rows
+fn bench_merge(case: &HashCase, hash: impl Fn(&RowData, &RdbTbMeta) -> u128) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut merged: HashMap<u128, &RowData> = HashMap::with_capacity(case.data.len());
+        for row_data in case.data.iter() {
+            merged.insert(hash(row_data, &case.tb_meta), row_data);
+        }
+        black_box(merged.len());
+    }
+    start.elapsed()
+}
+
+#[test]
+#[ignore = "micro-benchmark; run with --ignored --nocapture, preferably with --release"]
+fn bench_row_key_hash() -> anyhow::Result<()> {
+    let mut report = String::new();
+    writeln!(report, "# Row Key Hash Benchmark\n")?;
+
+    for case in hash_cases() {
+        // both hashes must tell all keys apart
+        let fast_keys: HashSet<u128> = case
+            .data
+            .iter()
+            .map(|row_data| row_data.get_hash_code(&case.tb_meta).unwrap())
+            .collect();
+        assert_eq!(fast_keys.len(), case.data.len());
+
+        let legacy = bench_merge(&case, legacy_hash_code);
+        let fast = bench_merge(&case, |row_data, tb_meta| {
+            row_data.get_hash_code(tb_meta).unwrap()
+        });
+        let rows = [
+            HashBenchRow {
+                implementation: "legacy_sip_hash_on_strings",
+                elapsed_ms: legacy.as_secs_f64() * 1000.0,
+                speedup_vs_legacy: 1.0,
+            },
+            HashBenchRow {
+                implementation: "ahash_on_values",
+                elapsed_ms: fast.as_secs_f64() * 1000.0,
+                speedup_vs_legacy: legacy.as_secs_f64() / fast.as_secs_f64(),
+            },
+        ];
+
+        writeln!(report, "## {}\n", case.name)?;
+        writeln!(
+            report,
+            "| impl | rows | iterations | elapsed_ms | speedup_vs_legacy |"
+        )?;
+        writeln!(report, "| :--- | ---: | ---: | ---: | ---: |")?;
+        for row in rows {
+            writeln!(
+                report,
+                "| {} | {} | {} | {:.3} | {:.2}x |",
+                row.implementation, ROWS, ITERATIONS, row.elapsed_ms, row.speedup_vs_legacy,
+            )?;
+        }
+        writeln!(report)?;
+    }
+
+    let path = project_root::get_project_root()?
+        .join("dt-tests")
+        .join("tests")
+        .join("parallelizer")
+        .join("row_hash")
+        .join("row_hash_result.md");
+    fs::write(&path, report)?;
+    Ok(())
+}
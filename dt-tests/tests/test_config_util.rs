@@ -134,7 +134,7 @@ impl TestConfigUtil {
                 ));
             }
 
-            ExtractorConfig::RedisSnapshotFile { file_path } => {
+            ExtractorConfig::RedisSnapshotFile { file_path, .. } => {
                 let file_path = format!("{}/{}", project_root, file_path);
                 update_configs.push((EXTRACTOR.to_string(), "file_path".to_string(), file_path));
             }
@@ -248,6 +248,7 @@ impl CheckTestRunner {
             next_event_position: binlog_position,
             gtid_set: String::new(),
             timestamp: Position::format_timestamp_millis(Utc::now().timestamp_millis()),
+            source_id: String::new(),
         })
     }
 
@@ -835,9 +835,9 @@ impl MongoTestRunner {
 
         let (dst_db, dst_tb) = match &self.router {
             Some(router) => router.get_tb_map(db, tb),
-            None => (db, tb),
+            None => (db.into(), tb.into()),
         };
-        let dst_data = self.fetch_data(dst_db, dst_tb, DST).await;
+        let dst_data = self.fetch_data(&dst_db, &dst_tb, DST).await;
 
         assert_eq!(src_data.len(), dst_data.len());
         for id in src_data.keys() {
@@ -1288,7 +1288,7 @@ impl RdbTestRunner {
         for (db, tb) in src_db_tbs.iter() {
             let (dst_db, dst_tb) = match &self.router {
                 Some(router) => router.get_tb_map(db, tb),
-                None => (db.as_str(), tb.as_str()),
+                None => (db.into(), tb.into()),
             };
             dst_db_tbs.push((dst_db.into(), dst_tb.into()));
         }